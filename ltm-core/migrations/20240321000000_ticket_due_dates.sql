@@ -0,0 +1,2 @@
+-- Optional due date for tickets
+ALTER TABLE tickets ADD COLUMN due_date DATE;
@@ -0,0 +1,9 @@
+-- Persist running timers so they survive between invocations
+CREATE TABLE IF NOT EXISTS active_timers (
+    ticket_id INTEGER PRIMARY KEY,
+    started_at DATETIME NOT NULL,
+    segment_started_at DATETIME NOT NULL,
+    paused_at DATETIME,
+    elapsed_seconds INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
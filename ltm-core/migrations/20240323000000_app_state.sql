@@ -0,0 +1,5 @@
+-- Key/value store for per-database state such as the focused ticket
+CREATE TABLE IF NOT EXISTS app_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Manual backlog order within a project (1 = top)
+ALTER TABLE tickets ADD COLUMN rank INTEGER NOT NULL DEFAULT 0;
+
+-- Existing tickets keep their creation order
+UPDATE tickets SET rank = (
+    SELECT COUNT(*) FROM tickets AS earlier
+    WHERE earlier.project = tickets.project AND earlier.id <= tickets.id
+);
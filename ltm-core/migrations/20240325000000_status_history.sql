@@ -0,0 +1,19 @@
+-- Every status a ticket has been in; from_status is NULL for the initial status
+CREATE TABLE IF NOT EXISTS status_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticket_id INTEGER NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    changed_at DATETIME NOT NULL,
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_status_changes_ticket ON status_changes(ticket_id, changed_at);
+
+-- Existing tickets have no history: assume they were opened at creation and reached
+-- their current status at their last update
+INSERT INTO status_changes (ticket_id, from_status, to_status, changed_at)
+SELECT id, NULL, 'open', created_at FROM tickets;
+
+INSERT INTO status_changes (ticket_id, from_status, to_status, changed_at)
+SELECT id, 'open', status, updated_at FROM tickets WHERE status != 'open';
@@ -0,0 +1,11 @@
+-- External links (pull requests, issues, docs, dashboards) attached to tickets
+CREATE TABLE IF NOT EXISTS ticket_urls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticket_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    label TEXT,
+    created_at DATETIME NOT NULL,
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_urls_ticket ON ticket_urls(ticket_id);
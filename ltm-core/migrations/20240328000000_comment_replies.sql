@@ -0,0 +1,4 @@
+-- Replies point at the comment they answer; top-level comments have no parent
+ALTER TABLE comments ADD COLUMN parent_comment_id INTEGER REFERENCES comments(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_comments_parent ON comments(parent_comment_id);
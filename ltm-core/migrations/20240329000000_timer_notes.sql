@@ -0,0 +1,3 @@
+-- Optional note describing what a timer (and the time log it becomes) was spent on
+ALTER TABLE active_timers ADD COLUMN note TEXT;
+ALTER TABLE time_logs ADD COLUMN note TEXT;
@@ -0,0 +1,13 @@
+-- Priority (low, medium, high, critical) and estimated effort in minutes
+ALTER TABLE tickets ADD COLUMN priority TEXT;
+ALTER TABLE tickets ADD COLUMN estimate_minutes INTEGER;
+
+-- Free-form labels; a ticket carries each tag at most once
+CREATE TABLE IF NOT EXISTS ticket_tags (
+    ticket_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (ticket_id, tag),
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_tags_tag ON ticket_tags(tag);
@@ -0,0 +1,2 @@
+-- When a ticket was archived by `ltm cleanup`; archived tickets are left out of lists
+ALTER TABLE tickets ADD COLUMN archived_at DATETIME;
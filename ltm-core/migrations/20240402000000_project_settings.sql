@@ -0,0 +1,7 @@
+-- Per-project overrides such as a custom status set or time rounding, one row per setting
+CREATE TABLE IF NOT EXISTS project_settings (
+    project TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (project, key)
+);
@@ -0,0 +1,13 @@
+-- Random (version 4) UUIDs alongside the integer IDs, so exports from different machines
+-- can be matched up without ID collisions. New rows get theirs from `NEW_UUID` in db.rs.
+ALTER TABLE tickets ADD COLUMN uuid TEXT;
+ALTER TABLE comments ADD COLUMN uuid TEXT;
+ALTER TABLE time_logs ADD COLUMN uuid TEXT;
+
+UPDATE tickets SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)));
+UPDATE comments SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)));
+UPDATE time_logs SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)));
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tickets_uuid ON tickets(uuid);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_comments_uuid ON comments(uuid);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_time_logs_uuid ON time_logs(uuid);
@@ -0,0 +1,7 @@
+-- Who created and last changed each row, from `[user]` in config.toml; NULL when unset
+ALTER TABLE tickets ADD COLUMN created_by TEXT;
+ALTER TABLE tickets ADD COLUMN updated_by TEXT;
+ALTER TABLE comments ADD COLUMN created_by TEXT;
+ALTER TABLE comments ADD COLUMN updated_by TEXT;
+ALTER TABLE time_logs ADD COLUMN created_by TEXT;
+ALTER TABLE time_logs ADD COLUMN updated_by TEXT;
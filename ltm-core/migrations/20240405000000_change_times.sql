@@ -0,0 +1,8 @@
+-- When comments and time logs last changed, so exports can pick up only what is new
+ALTER TABLE comments ADD COLUMN updated_at DATETIME;
+ALTER TABLE time_logs ADD COLUMN updated_at DATETIME;
+UPDATE comments SET updated_at = created_at;
+UPDATE time_logs SET updated_at = created_at;
+CREATE INDEX IF NOT EXISTS idx_tickets_updated_at ON tickets(updated_at);
+CREATE INDEX IF NOT EXISTS idx_comments_updated_at ON comments(updated_at);
+CREATE INDEX IF NOT EXISTS idx_time_logs_updated_at ON time_logs(updated_at);
@@ -0,0 +1,19 @@
+-- Tickets mirrored in another tracker, such as Linear, for `ltm sync`. `synced_at` is the
+-- ticket's updated_at and `remote_updated_at` the issue's when the two last matched.
+CREATE TABLE IF NOT EXISTS remote_links (
+    ticket_id INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    remote_id TEXT NOT NULL,
+    remote_key TEXT,
+    synced_at DATETIME NOT NULL,
+    remote_updated_at DATETIME NOT NULL,
+    PRIMARY KEY (provider, ticket_id),
+    UNIQUE (provider, remote_id),
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
+
+-- How far each provider's last sync got, so the next one only fetches newer changes
+CREATE TABLE IF NOT EXISTS sync_state (
+    provider TEXT PRIMARY KEY,
+    last_synced_at DATETIME NOT NULL
+);
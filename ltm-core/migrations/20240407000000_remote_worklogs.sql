@@ -0,0 +1,10 @@
+-- Time logs sent to another tracker as work logs, such as Jira's. `synced_at` is the time
+-- log's updated_at when it was last sent, so edits made afterwards are sent again.
+CREATE TABLE IF NOT EXISTS remote_worklogs (
+    time_log_id INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    remote_id TEXT NOT NULL,
+    synced_at DATETIME NOT NULL,
+    PRIMARY KEY (provider, time_log_id),
+    FOREIGN KEY (time_log_id) REFERENCES time_logs(id) ON DELETE CASCADE
+);
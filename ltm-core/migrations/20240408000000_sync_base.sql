@@ -0,0 +1,3 @@
+-- The synced fields of a ticket and its issue when they last matched, as JSON
+-- `{"local": {...}, "remote": {...}}`, so a sync can tell which side changed each field
+ALTER TABLE remote_links ADD COLUMN base TEXT;
@@ -0,0 +1,5 @@
+-- Hourly rate for one ticket, overriding its project's `rate` setting
+ALTER TABLE tickets ADD COLUMN rate REAL;
+
+-- Whether a time log is billed; non-billable time still counts in hours worked
+ALTER TABLE time_logs ADD COLUMN billable INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,7 @@
+-- Months closed with `ltm billing lock`, as YYYY-MM. Time logs dated in them are only
+-- changed with --unlock, so invoiced hours stay as they were billed.
+CREATE TABLE IF NOT EXISTS billing_locks (
+    month TEXT PRIMARY KEY,
+    locked_at DATETIME NOT NULL,
+    locked_by TEXT
+);
@@ -0,0 +1,2 @@
+-- Type of a ticket, like "bug" or "feature"; one of its project's `kinds` setting
+ALTER TABLE tickets ADD COLUMN kind TEXT;
@@ -0,0 +1,2 @@
+-- Impact of a ticket from S1 (most severe) to S4, kept apart from its priority
+ALTER TABLE tickets ADD COLUMN severity TEXT;
@@ -0,0 +1,10 @@
+-- Areas of a project, like "frontend" or "api", added with `ltm component add`
+CREATE TABLE IF NOT EXISTS components (
+    project TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (project, name)
+);
+
+-- Component of a ticket, one of its project's components
+ALTER TABLE tickets ADD COLUMN component TEXT;
@@ -0,0 +1,10 @@
+-- Versions of a project, like "v1.4", created with `ltm release create`
+CREATE TABLE IF NOT EXISTS releases (
+    project TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (project, name)
+);
+
+-- Release a ticket is planned for, one of its project's releases
+ALTER TABLE tickets ADD COLUMN target_release TEXT;
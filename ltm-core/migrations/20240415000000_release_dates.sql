@@ -0,0 +1,2 @@
+-- Day a release is planned to ship, for `ltm roadmap`
+ALTER TABLE releases ADD COLUMN due_date DATE;
@@ -0,0 +1,11 @@
+-- Tickets that can't be finished before another one, added with `ltm ticket depend`
+CREATE TABLE IF NOT EXISTS ticket_dependencies (
+    ticket_id INTEGER NOT NULL,
+    blocker_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (ticket_id, blocker_id),
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE,
+    FOREIGN KEY (blocker_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_dependencies_blocker ON ticket_dependencies(blocker_id);
@@ -0,0 +1,6 @@
+-- Inputs for ranking the backlog with `ltm report score`: people or events reached per
+-- quarter, impact from 0.25 to 3, confidence in percent, and effort in person-weeks
+ALTER TABLE tickets ADD COLUMN reach INTEGER;
+ALTER TABLE tickets ADD COLUMN impact REAL;
+ALTER TABLE tickets ADD COLUMN confidence INTEGER;
+ALTER TABLE tickets ADD COLUMN effort REAL;
@@ -0,0 +1,8 @@
+-- Tickets made read-only with `ltm ticket lock`, such as postmortems kept as a record.
+-- Edits, comments, and deletes on them need --unlock.
+CREATE TABLE IF NOT EXISTS ticket_locks (
+    ticket_id INTEGER PRIMARY KEY,
+    locked_at DATETIME NOT NULL,
+    locked_by TEXT,
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Pinned tickets come first in `ltm list` and on the board, whatever the sort order
+ALTER TABLE tickets ADD COLUMN pinned_at DATETIME;
@@ -0,0 +1,9 @@
+-- Personal shortlists from `ltm ticket star`, one per `[user]` identity (empty without
+-- one), so people sharing a server each keep their own
+CREATE TABLE IF NOT EXISTS ticket_stars (
+    ticket_id INTEGER NOT NULL,
+    starred_by TEXT NOT NULL DEFAULT '',
+    starred_at DATETIME NOT NULL,
+    PRIMARY KEY (ticket_id, starred_by),
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
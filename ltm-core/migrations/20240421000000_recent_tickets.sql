@@ -0,0 +1,11 @@
+-- The tickets each `[user]` identity (empty without one) last used, for `ltm recent`:
+-- when, and the command line that used them
+CREATE TABLE IF NOT EXISTS recent_tickets (
+    ticket_id INTEGER NOT NULL,
+    used_by TEXT NOT NULL DEFAULT '',
+    used_at DATETIME NOT NULL,
+    command TEXT,
+    PRIMARY KEY (ticket_id, used_by),
+    FOREIGN KEY (ticket_id) REFERENCES tickets(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_recent_tickets_used ON recent_tickets(used_by, used_at);
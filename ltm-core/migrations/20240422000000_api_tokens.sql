@@ -0,0 +1,9 @@
+-- Tokens for `ltm serve`, made with `ltm serve token create`. Only a SHA-256 hash of each
+-- secret is kept; read-only tokens can query the API but not run commands.
+CREATE TABLE IF NOT EXISTS api_tokens (
+    name TEXT PRIMARY KEY,
+    token_hash TEXT NOT NULL UNIQUE,
+    read_only BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL,
+    created_by TEXT
+);
@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use dirs::home_dir;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::db::Database;
+
+/// Prefix shared by every backup file name
+const FILE_PREFIX: &str = "tickets-";
+
+/// Automatic backup settings, read from the `[backup]` table of the config file:
+///
+/// ```toml
+/// [backup]
+/// keep = 10       # copies to keep; older ones are deleted
+/// daily = true    # also back up on the first command of each day
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Set to `false` to turn automatic backups off
+    pub enabled: bool,
+    /// How many backups to keep
+    pub keep: usize,
+    /// Back up once a day, on the first command of the day
+    pub daily: bool,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { enabled: true, keep: 10, daily: false }
+    }
+}
+
+/// Directory holding backups, `~/.ltm/backups`
+pub fn backup_dir() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".ltm").join("backups"))
+}
+
+/// File name for a backup, e.g. `tickets-20240315-093000-migration.db`; names sort by age
+pub fn backup_file_name(at: NaiveDateTime, reason: &str) -> String {
+    format!("{}{}-{}.db", FILE_PREFIX, at.format("%Y%m%d-%H%M%S"), reason)
+}
+
+/// Backup files in `dir`, oldest first
+pub fn list_backups(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Could not read backup directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(FILE_PREFIX) && name.ends_with(".db"))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
+
+/// Whether a backup was already taken on `day`
+pub fn has_backup_on(dir: &Path, day: NaiveDate) -> Result<bool> {
+    let prefix = format!("{}{}-", FILE_PREFIX, day.format("%Y%m%d"));
+    Ok(list_backups(dir)?
+        .iter()
+        .any(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(&prefix))))
+}
+
+/// Delete the oldest backups beyond `keep`, returning the removed files
+pub fn rotate(dir: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(dir)?;
+    let excess = backups.len().saturating_sub(keep);
+    let removed: Vec<PathBuf> = backups.into_iter().take(excess).collect();
+    for path in &removed {
+        std::fs::remove_file(path).with_context(|| format!("Could not remove old backup {}", path.display()))?;
+    }
+    Ok(removed)
+}
+
+/// Snapshot the database into `dir` and rotate old copies, returning the new file
+pub async fn create_in(db: &Database, dir: &Path, reason: &str, keep: usize) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Could not create backup directory {}", dir.display()))?;
+    let path = dir.join(backup_file_name(Local::now().naive_local(), reason));
+    db.backup_to(&path).await?;
+    info!(path = %path.display(), reason, "backed up database");
+    for old in rotate(dir, keep.max(1))? {
+        info!(path = %old.display(), "removed old backup");
+    }
+    Ok(path)
+}
+
+/// Take an automatic backup before `reason` (e.g. `migration`, `delete`) if backups are on.
+///
+/// Failures are logged rather than returned so a full disk never blocks the command itself.
+pub async fn before(db: &Database, config: &BackupConfig, reason: &str) -> Option<PathBuf> {
+    if !config.enabled {
+        return None;
+    }
+    let dir = backup_dir()?;
+    match create_in(db, &dir, reason, config.keep).await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            warn!(error = %e, reason, "automatic backup failed");
+            None
+        }
+    }
+}
+
+/// Take the daily backup if it is enabled and none was taken today
+pub async fn daily(db: &Database, config: &BackupConfig) -> Option<PathBuf> {
+    if !config.enabled || !config.daily {
+        return None;
+    }
+    let dir = backup_dir()?;
+    match has_backup_on(&dir, Local::now().date_naive()) {
+        Ok(false) => before(db, config, "daily").await,
+        Ok(true) => None,
+        Err(e) => {
+            warn!(error = %e, "could not check for today's backup");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        for (hour, reason) in [(9, "daily"), (10, "migration"), (11, "delete")] {
+            let name = backup_file_name(day.and_hms_opt(hour, 0, 0).unwrap(), reason);
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        std::fs::write(dir.path().join("notes.txt"), b"").unwrap();
+
+        assert_eq!(backup_file_name(day.and_hms_opt(9, 30, 5).unwrap(), "daily"), "tickets-20240315-093005-daily.db");
+        assert!(has_backup_on(dir.path(), day).unwrap());
+        assert!(!has_backup_on(dir.path(), day.succ_opt().unwrap()).unwrap());
+
+        let removed = rotate(dir.path(), 2).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert!(removed[0].ends_with("tickets-20240315-090000-daily.db"));
+        assert_eq!(list_backups(dir.path()).unwrap().len(), 2);
+        assert!(dir.path().join("notes.txt").exists());
+    }
+}
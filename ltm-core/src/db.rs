@@ -0,0 +1,2466 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use dirs::home_dir;
+use sqlx::pool::PoolConnection;
+use sqlx::sqlite::{Sqlite, SqliteConnectOptions, SqliteConnection, SqlitePool};
+use sqlx::{Connection, Transaction};
+use sqlx::Row;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::str::FromStr;
+use std::time::Instant;
+use tracing::{debug, info};
+
+use crate::backup::{self, BackupConfig};
+use crate::models::{ActiveTimer, ApiToken, BillingLock, TicketLock, ChecklistItem, Comment, Component, DeleteImpact, Release, Event, MonthTrend, OpenCounts, ProjectSummary, RecentTicket, RemoteLink, RemoteWorklog, StatusChange, Ticket, TicketDependency, TicketHours, TicketUrl, TimeLogEntry};
+use crate::project_settings::ProjectSettings;
+use crate::validation::{FINISHED_STATUSES, SCORE_FIELDS};
+
+/// Column list matching the fields of `Ticket`
+const TICKET_COLUMNS: &str =
+    "id, project, name, description, status, created_at, updated_at, due_date, rank, priority, estimate_minutes, archived_at, uuid, created_by, updated_by, rate, kind, severity, component, target_release, reach, impact, confidence, effort, pinned_at";
+
+/// Column list matching the fields of `Comment`
+const COMMENT_COLUMNS: &str = "id, ticket_id, content, created_at, updated_at, parent_comment_id, uuid, created_by, updated_by";
+
+/// Column list matching the fields of `TimeLog`
+const TIME_LOG_COLUMNS: &str =
+    "id, ticket_id, hours, minutes, started_at, ended_at, created_at, updated_at, note, uuid, created_by, updated_by, billable";
+
+/// Rank that places a ticket at the bottom of a project's backlog
+const NEXT_RANK: &str = "(SELECT COALESCE(MAX(rank), 0) + 1 FROM tickets WHERE project = ?)";
+
+/// A random (version 4) UUID for a new ticket, comment, or time log
+const NEW_UUID: &str = "lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)))";
+
+#[derive(Clone)]
+pub struct Database {
+    pool: SqlitePool,
+    /// Command line recorded with each event, set by `with_command`
+    command: Option<Arc<str>>,
+    /// Stamped as `created_by`/`updated_by` on changed rows, set by `with_user`
+    user: Option<Arc<str>>,
+}
+
+/// Kinds of rows the event journal records
+#[derive(Debug, Clone, Copy)]
+enum Entity {
+    Ticket,
+    Comment,
+    TimeLog,
+    TicketUrl,
+    ChecklistItem,
+    /// Keyed by ticket ID
+    Timer,
+    /// A ticket's whole tag list, keyed by ticket ID
+    Tags,
+}
+
+impl Entity {
+    fn name(self) -> &'static str {
+        match self {
+            Entity::Ticket => "ticket",
+            Entity::Comment => "comment",
+            Entity::TimeLog => "time_log",
+            Entity::TicketUrl => "ticket_url",
+            Entity::ChecklistItem => "checklist_item",
+            Entity::Timer => "timer",
+            Entity::Tags => "tags",
+        }
+    }
+
+    /// Table whose rows carry `created_by` and `updated_by`
+    fn stamped_table(self) -> Option<&'static str> {
+        match self {
+            Entity::Ticket => Some("tickets"),
+            Entity::Comment => Some("comments"),
+            Entity::TimeLog => Some("time_logs"),
+            _ => None,
+        }
+    }
+}
+
+/// Limits on when tickets were created and last updated, in UTC; `*_after` bounds are
+/// inclusive and `*_before` bounds exclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DateBounds {
+    pub created_after: Option<NaiveDateTime>,
+    pub created_before: Option<NaiveDateTime>,
+    pub updated_after: Option<NaiveDateTime>,
+    pub updated_before: Option<NaiveDateTime>,
+}
+
+/// Notices writes to the database, from this process or another, by polling SQLite's
+/// `data_version` on a connection of its own
+pub struct ChangeWatcher {
+    conn: PoolConnection<Sqlite>,
+    version: i64,
+}
+
+impl ChangeWatcher {
+    /// Whether anything was committed since the last call (or since the watcher was made)
+    pub async fn changed(&mut self) -> Result<bool> {
+        let version: i64 = sqlx::query_scalar("PRAGMA data_version").fetch_one(&mut *self.conn).await?;
+        let changed = version != self.version;
+        self.version = version;
+        Ok(changed)
+    }
+}
+
+impl Database {
+    /// Open `~/.ltm/tickets.db`, backing it up before any pending migrations run
+    pub async fn new(backups: &BackupConfig) -> Result<Self> {
+        let db = Self::open().await?;
+        db.migrate(backups).await?;
+        Ok(db)
+    }
+
+    /// Open `~/.ltm/tickets.db` without applying migrations
+    pub async fn open() -> Result<Self> {
+        let db_path = home_dir()
+            .context("Could not find home directory")?
+            .join(".ltm")
+            .join("tickets.db");
+
+        std::fs::create_dir_all(db_path.parent().unwrap())?;
+
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", db_path.display()))?
+            .create_if_missing(true)
+            .foreign_keys(true);
+
+        debug!(path = %db_path.display(), "opening database");
+        let pool = SqlitePool::connect_with(options).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Open a database file that must not be changed, such as a cached copy of another
+    /// machine's; any write fails
+    pub async fn open_read_only(path: &Path) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.display()))?
+            .read_only(true)
+            .foreign_keys(true);
+        debug!(path = %path.display(), "opening read-only database");
+        let pool = SqlitePool::connect_with(options).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Versions of the migrations applied so far; empty for a new database
+    async fn applied_migrations(&self) -> Vec<i64> {
+        // The bookkeeping table does not exist yet on a fresh database
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(&self.pool)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Newest applied migration, `None` for a new database
+    pub async fn schema_version(&self) -> Option<i64> {
+        self.applied_migrations().await.into_iter().max()
+    }
+
+    /// Newest migration this build of ltm knows
+    pub fn latest_schema_version() -> Option<i64> {
+        sqlx::migrate!("./migrations").iter().map(|m| m.version).max()
+    }
+
+    /// Migrations not applied yet, as `(version, description)`, oldest first
+    pub async fn pending_migrations(&self) -> Vec<(i64, String)> {
+        let applied = self.applied_migrations().await;
+        sqlx::migrate!("./migrations")
+            .iter()
+            .filter(|m| !applied.contains(&m.version))
+            .map(|m| (m.version, m.description.to_string()))
+            .collect()
+    }
+
+    /// Apply pending migrations, backing up an existing database first. Returns the
+    /// migrations that ran.
+    pub async fn migrate(&self, backups: &BackupConfig) -> Result<Vec<(i64, String)>> {
+        let pending = self.pending_migrations().await;
+        if pending.is_empty() {
+            return Ok(pending);
+        }
+        if self.schema_version().await.is_some() {
+            backup::before(self, backups, "migration").await;
+        }
+        Self::run_migrations(&self.pool).await?;
+        Ok(pending)
+    }
+
+    /// Copy every row of an older ltm database into this one, which should be empty.
+    ///
+    /// The old file is left untouched: a copy of it is brought up to the current schema
+    /// first, so any earlier layout can be read. Returns the number of tickets copied.
+    pub async fn import_from(&self, old: &Path) -> Result<i64> {
+        self.load_from(old, false).await
+    }
+
+    /// Replace everything in this database with the contents of `snapshot`, which may have
+    /// an older schema. Returns the number of tickets restored.
+    pub async fn restore_from(&self, snapshot: &Path) -> Result<i64> {
+        self.load_from(snapshot, true).await
+    }
+
+    async fn load_from(&self, old: &Path, replace: bool) -> Result<i64> {
+        if !old.exists() {
+            anyhow::bail!("Database {} not found", old.display());
+        }
+        let copy = std::env::temp_dir().join(format!("ltm-import-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&copy);
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", old.display()))?.read_only(true);
+        let source = SqlitePool::connect_with(options).await?;
+        Self::from_pool(source.clone())
+            .backup_to(&copy)
+            .await
+            .with_context(|| format!("Could not read database {}", old.display()))?;
+        source.close().await;
+
+        let result = self.import_copy(&copy, replace).await;
+        let _ = std::fs::remove_file(&copy);
+        result
+    }
+
+    async fn import_copy(&self, copy: &Path, replace: bool) -> Result<i64> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", copy.display()))?;
+        let upgraded = SqlitePool::connect_with(options).await?;
+        let version = Self::from_pool(upgraded.clone()).schema_version().await;
+        if version > Self::latest_schema_version() {
+            upgraded.close().await;
+            anyhow::bail!(
+                "The database has schema version {}, newer than this ltm knows ({}); upgrade ltm first",
+                version.unwrap_or_default(),
+                Self::latest_schema_version().unwrap_or_default()
+            );
+        }
+        Self::run_migrations(&upgraded).await.context("Could not upgrade the old database")?;
+        upgraded.close().await;
+
+        // A URI with an explicit mode, so the copy is not opened in memory like an in-memory main
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("ATTACH DATABASE ? AS old")
+            .bind(format!("file:{}?mode=ro", copy.display()))
+            .execute(&mut *conn)
+            .await?;
+        let copied = copy_tables(&mut conn, replace).await;
+        sqlx::query("DETACH DATABASE old").execute(&mut *conn).await?;
+        copied
+    }
+
+    /// Write a consistent snapshot of the database to `path`
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("Could not back up database to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Start watching for writes; the watcher holds one pooled connection while it lives
+    pub async fn watch_changes(&self) -> Result<ChangeWatcher> {
+        let mut conn = self.pool.acquire().await?;
+        let version: i64 = sqlx::query_scalar("PRAGMA data_version").fetch_one(&mut *conn).await?;
+        Ok(ChangeWatcher { conn, version })
+    }
+
+    /// Apply pending migrations, logging each one that runs
+    async fn run_migrations(pool: &SqlitePool) -> Result<()> {
+        let migrator = sqlx::migrate!("./migrations");
+        let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations")
+            .fetch_all(pool)
+            .await
+            .unwrap_or_default();
+
+        let started = Instant::now();
+        migrator.run(pool).await.context("Failed to run migrations")?;
+
+        let mut newly_applied = 0;
+        for migration in migrator.iter().filter(|m| !applied.contains(&m.version)) {
+            info!(version = migration.version, description = %migration.description, "applied migration");
+            newly_applied += 1;
+        }
+        debug!(
+            known = migrator.iter().count(),
+            newly_applied,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "migrations up to date"
+        );
+        Ok(())
+    }
+
+    // Helper methods for testing
+    #[allow(dead_code)]
+    pub fn from_pool(pool: SqlitePool) -> Self {
+        Self { pool, command: None, user: None }
+    }
+
+    /// Record `command` as the origin of every change made through this handle
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into().into());
+        self
+    }
+
+    /// Stamp `user` on the tickets, comments, and time logs this handle creates or changes
+    pub fn with_user(mut self, user: Option<String>) -> Self {
+        self.user = user.map(Into::into);
+        self
+    }
+
+    /// Journal entries, newest first, optionally only those touching one ticket (including
+    /// its comments, time logs, links, checklist, timer, and tags)
+    pub async fn list_events(&self, ticket_id: Option<i64>, limit: i64) -> Result<Vec<Event>> {
+        let filter = if ticket_id.is_some() {
+            r#"
+            WHERE (entity IN ('ticket', 'timer', 'tags') AND entity_id = ?1)
+                OR (entity NOT IN ('ticket', 'timer', 'tags')
+                    AND (json_extract(before, '$.ticket_id') = ?1 OR json_extract(after, '$.ticket_id') = ?1))
+            "#
+        } else {
+            ""
+        };
+        let query = format!(
+            "SELECT id, command, entity, entity_id, action, before, after, created_at FROM events {} ORDER BY id DESC LIMIT ?2",
+            filter
+        );
+        let events = sqlx::query_as::<_, Event>(&query)
+            .bind(ticket_id.unwrap_or_default())
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(events)
+    }
+
+    /// Append a change to one row to the event journal, reading the row's new state inside
+    /// the same transaction. Nothing is recorded when the row neither existed before nor
+    /// exists now, or when it did not change.
+    ///
+    /// Created and changed tickets, comments, and time logs are stamped with the user first.
+    async fn record_event(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        entity: Entity,
+        id: i64,
+        before: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let mut after = snapshot(tx, entity, id).await?;
+        let action = match (&before, &after) {
+            (None, Some(_)) => "create",
+            (Some(_), None) => "delete",
+            (Some(before), Some(after)) if without_timestamp(before) != without_timestamp(after) => "update",
+            _ => return Ok(()),
+        };
+        if let (Some(table), "create" | "update") = (entity.stamped_table(), action) {
+            let columns = if action == "create" { "created_by = ?1, updated_by = ?1" } else { "updated_by = ?1" };
+            // Tickets set updated_at in each query that changes them; edited comments and time
+            // logs get it here
+            let touch = if action == "create" || matches!(entity, Entity::Ticket) { "" } else { ", updated_at = ?3" };
+            let sql = format!("UPDATE {} SET {}{} WHERE id = ?2", table, columns, touch);
+            let mut query = sqlx::query(&sql).bind(self.user.as_deref()).bind(id);
+            if !touch.is_empty() {
+                query = query.bind(Utc::now().naive_utc());
+            }
+            query.execute(&mut **tx).await?;
+            after = snapshot(tx, entity, id).await?;
+        }
+        sqlx::query(
+            "INSERT INTO events (command, entity, entity_id, action, before, after, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.command.as_deref())
+        .bind(entity.name())
+        .bind(id)
+        .bind(action)
+        .bind(before.map(|v| v.to_string()))
+        .bind(after.map(|v| v.to_string()))
+        .bind(Utc::now().naive_utc())
+        .execute(&mut **tx)
+        .await?;
+        debug!(entity = entity.name(), id, action, "recorded event");
+        Ok(())
+    }
+
+    /// Run one UPDATE or DELETE against a single row and journal it
+    async fn change_row<'q>(
+        &self,
+        entity: Entity,
+        id: i64,
+        query: sqlx::query::Query<'q, Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+    ) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let before = snapshot(&mut tx, entity, id).await?;
+        let affected = query.execute(&mut *tx).await?.rows_affected();
+        self.record_event(&mut tx, entity, id, before).await?;
+        tx.commit().await?;
+        Ok(affected)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    pub async fn init_db(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS tickets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS comments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ticket_id INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                created_at DATETIME NOT NULL,
+                FOREIGN KEY (ticket_id) REFERENCES tickets(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS time_logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ticket_id INTEGER NOT NULL,
+                hours INTEGER NOT NULL,
+                minutes INTEGER NOT NULL,
+                started_at DATETIME,
+                ended_at DATETIME,
+                created_at DATETIME NOT NULL,
+                FOREIGN KEY (ticket_id) REFERENCES tickets(id)
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn add_ticket(&self, project: &str, name: &str, description: &str) -> Result<i64> {
+        self.add_ticket_with_status(project, name, description, "open").await
+    }
+
+    /// Add a ticket that starts in `status` instead of "open"
+    pub async fn add_ticket_with_status(&self, project: &str, name: &str, description: &str, status: &str) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let id = self.insert_ticket(&mut tx, project, name, description, status).await?;
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    /// Insert a ticket at the bottom of its project's backlog, with its history and event
+    async fn insert_ticket(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        project: &str,
+        name: &str,
+        description: &str,
+        status: &str,
+    ) -> Result<i64> {
+        let now = Utc::now().naive_utc();
+        let id = sqlx::query(&format!(
+            r#"
+            INSERT INTO tickets (project, name, description, status, created_at, updated_at, rank, uuid)
+            VALUES (?, ?, ?, ?, ?, ?, {}, {})
+            "#,
+            NEXT_RANK, NEW_UUID
+        ))
+        .bind(project)
+        .bind(name)
+        .bind(description)
+        .bind(status)
+        .bind(now)
+        .bind(now)
+        .bind(project)
+        .execute(&mut **tx)
+        .await?
+        .last_insert_rowid();
+        record_status_change(tx, id, None, status, now).await?;
+        self.record_event(tx, Entity::Ticket, id, None).await?;
+        Ok(id)
+    }
+
+    pub async fn get_ticket(&self, id: i64) -> Result<Option<Ticket>> {
+        let ticket = sqlx::query_as::<_, Ticket>(
+            &format!("SELECT {} FROM tickets WHERE id = ?", TICKET_COLUMNS)
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(ticket)
+    }
+
+    pub async fn list_tickets(&self, project: Option<&str>) -> Result<Vec<Ticket>> {
+        let tickets = if let Some(project) = project {
+            sqlx::query_as::<_, Ticket>(
+                &format!("SELECT {} FROM tickets WHERE project = ? ORDER BY created_at DESC", TICKET_COLUMNS)
+            )
+            .bind(project)
+            .fetch_all(&self.pool)
+            .await?
+        } else {
+            sqlx::query_as::<_, Ticket>(
+                &format!("SELECT {} FROM tickets ORDER BY created_at DESC", TICKET_COLUMNS)
+            )
+            .fetch_all(&self.pool)
+            .await?
+        };
+
+        Ok(tickets)
+    }
+
+    pub async fn list_projects(&self) -> Result<Vec<String>> {
+        let projects = sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT project FROM tickets ORDER BY project",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(projects)
+    }
+
+    /// Tickets matching the filters; `archived` lists archived tickets instead of the others
+    pub async fn list_tickets_filtered(
+        &self,
+        project: Option<&str>,
+        status: Option<&str>,
+        sort: &str,
+        archived: bool,
+    ) -> Result<Vec<Ticket>> {
+        self.list_tickets_dated(project, status, sort, archived, &DateBounds::default()).await
+    }
+
+    /// `list_tickets_filtered`, limited to tickets created and last updated within `dates`
+    pub async fn list_tickets_dated(
+        &self,
+        project: Option<&str>,
+        status: Option<&str>,
+        sort: &str,
+        archived: bool,
+        dates: &DateBounds,
+    ) -> Result<Vec<Ticket>> {
+        let mut query = format!("SELECT {} FROM tickets", TICKET_COLUMNS);
+        let mut clauses: Vec<&str> = vec![if archived { "archived_at IS NOT NULL" } else { "archived_at IS NULL" }];
+        if project.is_some() {
+            clauses.push("project = ?");
+        }
+        if status.is_some() {
+            clauses.push("LOWER(status) = ?");
+        }
+        let bounds = [
+            (dates.created_after, "created_at >= ?"),
+            (dates.created_before, "created_at < ?"),
+            (dates.updated_after, "updated_at >= ?"),
+            (dates.updated_before, "updated_at < ?"),
+        ];
+        clauses.extend(bounds.iter().filter(|(at, _)| at.is_some()).map(|(_, clause)| *clause));
+        query.push_str(" WHERE ");
+        query.push_str(&clauses.join(" AND "));
+
+        let order_by = match sort.to_lowercase().as_str() {
+            "created" | "created_at" => "created_at DESC",
+            // Put in status order below
+            "status" => "updated_at DESC",
+            "project" => "project ASC, updated_at DESC",
+            "rank" => "project ASC, rank ASC, id ASC",
+            _ => "updated_at DESC",
+        };
+        query.push_str(" ORDER BY ");
+        query.push_str(order_by);
+
+        let mut q = sqlx::query_as::<_, Ticket>(&query);
+        if let Some(p) = project {
+            q = q.bind(p);
+        }
+        if let Some(s) = status {
+            q = q.bind(s.to_lowercase());
+        }
+        for at in bounds.iter().filter_map(|(at, _)| *at) {
+            q = q.bind(at);
+        }
+        let mut tickets = q.fetch_all(&self.pool).await?;
+        if sort.eq_ignore_ascii_case("status") {
+            let settings = self.all_project_settings().await?;
+            let default = ProjectSettings::default();
+            tickets.sort_by_cached_key(|t| settings.get(&t.project).unwrap_or(&default).status_sort_key(&t.status));
+        }
+        // Pinned tickets first, each group keeping the order asked for
+        tickets.sort_by_key(|t| t.pinned_at.is_none());
+        Ok(tickets)
+    }
+
+    pub async fn update_ticket_status(&self, id: i64, status: &str) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+        let before = snapshot(&mut tx, Entity::Ticket, id).await?;
+        let previous = before.as_ref().and_then(|t| t["status"].as_str().map(str::to_string));
+        sqlx::query(
+            r#"
+            UPDATE tickets SET status = ?, updated_at = ? WHERE id = ?
+            "#,
+        )
+        .bind(status)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        if let Some(previous) = previous.filter(|p| p != status) {
+            record_status_change(&mut tx, id, Some(&previous), status, now).await?;
+        }
+        self.record_event(&mut tx, Entity::Ticket, id, before).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Status changes for a ticket, oldest first
+    pub async fn get_status_changes(&self, ticket_id: i64) -> Result<Vec<StatusChange>> {
+        let changes = sqlx::query_as::<_, StatusChange>(
+            "SELECT id, ticket_id, from_status, to_status, changed_at FROM status_changes WHERE ticket_id = ? ORDER BY changed_at ASC, id ASC",
+        )
+        .bind(ticket_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(changes)
+    }
+
+    /// Status changes made between two days (inclusive, in UTC), optionally in one project,
+    /// oldest first
+    pub async fn status_changes_between(
+        &self,
+        since: NaiveDate,
+        until: NaiveDate,
+        project: Option<&str>,
+    ) -> Result<Vec<StatusChange>> {
+        let changes = sqlx::query_as::<_, StatusChange>(
+            r#"
+            SELECT s.id, s.ticket_id, s.from_status, s.to_status, s.changed_at
+            FROM status_changes s JOIN tickets t ON t.id = s.ticket_id
+            WHERE date(s.changed_at) BETWEEN ?1 AND ?2 AND (?3 IS NULL OR t.project = ?3)
+            ORDER BY s.changed_at ASC, s.id ASC
+            "#,
+        )
+        .bind(since.format("%Y-%m-%d").to_string())
+        .bind(until.format("%Y-%m-%d").to_string())
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(changes)
+    }
+
+    /// Status changes for every ticket in a project, grouped by ticket and oldest first
+    pub async fn get_project_status_changes(&self, project: &str) -> Result<Vec<StatusChange>> {
+        let changes = sqlx::query_as::<_, StatusChange>(
+            r#"
+            SELECT s.id, s.ticket_id, s.from_status, s.to_status, s.changed_at
+            FROM status_changes s JOIN tickets t ON t.id = s.ticket_id
+            WHERE t.project = ?
+            ORDER BY s.ticket_id ASC, s.changed_at ASC, s.id ASC
+            "#,
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(changes)
+    }
+
+    pub async fn update_ticket_name(&self, id: i64, name: &str) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET name = ?, updated_at = ? WHERE id = ?")
+            .bind(name)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    pub async fn update_ticket_description(&self, id: i64, description: &str) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET description = ?, updated_at = ? WHERE id = ?")
+            .bind(description)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    pub async fn update_ticket_due_date(&self, id: i64, due_date: Option<NaiveDate>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET due_date = ?, updated_at = ? WHERE id = ?")
+            .bind(due_date)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    pub async fn update_ticket_priority(&self, id: i64, priority: Option<&str>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET priority = ?, updated_at = ? WHERE id = ?")
+            .bind(priority)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    pub async fn update_ticket_estimate(&self, id: i64, estimate_minutes: Option<i64>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET estimate_minutes = ?, updated_at = ? WHERE id = ?")
+            .bind(estimate_minutes)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Set a ticket's hourly rate; `None` bills it at the project's rate
+    pub async fn update_ticket_rate(&self, id: i64, rate: Option<f64>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET rate = ?, updated_at = ? WHERE id = ?")
+            .bind(rate)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Set a ticket's type; `None` clears it
+    pub async fn update_ticket_kind(&self, id: i64, kind: Option<&str>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET kind = ?, updated_at = ? WHERE id = ?")
+            .bind(kind)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Set a ticket's severity; `None` clears it
+    pub async fn update_ticket_severity(&self, id: i64, severity: Option<&str>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET severity = ?, updated_at = ? WHERE id = ?")
+            .bind(severity)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Set or clear (`None`) one of a ticket's scoring inputs, named as in `SCORE_FIELDS`
+    pub async fn update_ticket_score(&self, id: i64, field: &str, value: Option<f64>) -> Result<()> {
+        if !SCORE_FIELDS.contains(&field) {
+            anyhow::bail!("Unknown scoring field '{}'", field);
+        }
+        let sql = format!("UPDATE tickets SET {} = ?, updated_at = ? WHERE id = ?", field);
+        let query = sqlx::query(&sql).bind(value).bind(Utc::now().naive_utc()).bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Put a ticket in one of its project's components; `None` takes it out
+    pub async fn update_ticket_component(&self, id: i64, component: Option<&str>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET component = ?, updated_at = ? WHERE id = ?")
+            .bind(component)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Plan a ticket for one of its project's releases; `None` unplans it
+    pub async fn update_ticket_release(&self, id: i64, release: Option<&str>) -> Result<()> {
+        let query = sqlx::query("UPDATE tickets SET target_release = ?, updated_at = ? WHERE id = ?")
+            .bind(release)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Tag a ticket; tags it already carries are left alone
+    pub async fn add_ticket_tags(&self, ticket_id: i64, tags: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let before = snapshot(&mut tx, Entity::Tags, ticket_id).await?;
+        let mut added = 0;
+        for tag in tags {
+            added += sqlx::query("INSERT OR IGNORE INTO ticket_tags (ticket_id, tag) VALUES (?, ?)")
+                .bind(ticket_id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        if added > 0 {
+            self.record_event(&mut tx, Entity::Tags, ticket_id, before).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// A ticket's tags in alphabetical order
+    pub async fn get_ticket_tags(&self, ticket_id: i64) -> Result<Vec<String>> {
+        let tags = sqlx::query_scalar("SELECT tag FROM ticket_tags WHERE ticket_id = ? ORDER BY tag ASC")
+            .bind(ticket_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tags)
+    }
+
+    /// Tickets opened and closed and hours logged in each of `months` (first days, oldest first)
+    pub async fn monthly_trends(&self, months: &[NaiveDate], project: Option<&str>) -> Result<Vec<MonthTrend>> {
+        let Some(since) = months.first() else {
+            return Ok(Vec::new());
+        };
+        let since = since.format("%Y-%m-%d").to_string();
+        let finished = FINISHED_STATUSES
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let project_filter = if project.is_some() { " AND t.project = ?" } else { "" };
+
+        let queries = [
+            format!(
+                "SELECT strftime('%Y-%m', t.created_at) AS month, CAST(COUNT(*) AS REAL) AS value FROM tickets t
+                 WHERE date(t.created_at) >= ?{} GROUP BY month",
+                project_filter
+            ),
+            // Only moves into a finished status count, so editing a closed ticket's status does not
+            format!(
+                "SELECT strftime('%Y-%m', sc.changed_at) AS month, CAST(COUNT(*) AS REAL) AS value
+                 FROM status_changes sc JOIN tickets t ON t.id = sc.ticket_id
+                 WHERE date(sc.changed_at) >= ? AND LOWER(sc.to_status) IN ({0})
+                   AND (sc.from_status IS NULL OR LOWER(sc.from_status) NOT IN ({0})){1}
+                 GROUP BY month",
+                finished, project_filter
+            ),
+            format!(
+                "SELECT strftime('%Y-%m', COALESCE(tl.started_at, tl.created_at)) AS month,
+                        SUM(tl.hours + tl.minutes / 60.0) AS value
+                 FROM time_logs tl JOIN tickets t ON t.id = tl.ticket_id
+                 WHERE date(COALESCE(tl.started_at, tl.created_at)) >= ?{} GROUP BY month",
+                project_filter
+            ),
+        ];
+
+        let mut totals: Vec<HashMap<String, f64>> = Vec::new();
+        for sql in &queries {
+            let mut query = sqlx::query(sql).bind(&since);
+            if let Some(p) = project {
+                query = query.bind(p);
+            }
+            let rows = query.fetch_all(&self.pool).await?;
+            totals.push(rows.iter().map(|row| (row.get::<String, _>("month"), row.get::<f64, _>("value"))).collect());
+        }
+
+        Ok(months
+            .iter()
+            .map(|month| {
+                let key = month.format("%Y-%m").to_string();
+                let value = |i: usize| totals[i].get(&key).copied().unwrap_or(0.0);
+                MonthTrend { month: *month, opened: value(0) as i64, closed: value(1) as i64, hours: value(2) }
+            })
+            .collect())
+    }
+
+    /// Unfinished tickets with a due date on or before `date`, soonest first
+    pub async fn list_tickets_due_by(&self, date: NaiveDate) -> Result<Vec<Ticket>> {
+        let finished = FINISHED_STATUSES
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            "SELECT {} FROM tickets WHERE due_date IS NOT NULL AND due_date <= ? AND LOWER(status) NOT IN ({}) ORDER BY due_date ASC, id ASC",
+            TICKET_COLUMNS, finished
+        );
+        let tickets = sqlx::query_as::<_, Ticket>(&query)
+            .bind(date)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tickets)
+    }
+
+    /// Finished tickets, not yet archived, last updated before `cutoff`, by project
+    pub async fn list_cleanup_candidates(&self, cutoff: NaiveDateTime, project: Option<&str>) -> Result<Vec<Ticket>> {
+        let finished = FINISHED_STATUSES
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            "SELECT {} FROM tickets WHERE archived_at IS NULL AND updated_at < ? AND LOWER(status) IN ({}) AND (? IS NULL OR project = ?) ORDER BY project ASC, id ASC",
+            TICKET_COLUMNS, finished
+        );
+        let tickets = sqlx::query_as::<_, Ticket>(&query)
+            .bind(cutoff)
+            .bind(project)
+            .bind(project)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tickets)
+    }
+
+    /// Archive several tickets in one transaction, returning how many were archived
+    pub async fn archive_tickets(&self, ids: &[i64]) -> Result<u64> {
+        let now = Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+        let mut archived = 0;
+        for &id in ids {
+            let before = snapshot(&mut tx, Entity::Ticket, id).await?;
+            archived += sqlx::query("UPDATE tickets SET archived_at = ? WHERE id = ? AND archived_at IS NULL")
+                .bind(now)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            self.record_event(&mut tx, Entity::Ticket, id, before).await?;
+        }
+        tx.commit().await?;
+        Ok(archived)
+    }
+
+    /// Bring an archived ticket back into lists; `false` if it was not archived
+    pub async fn unarchive_ticket(&self, id: i64) -> Result<bool> {
+        let query = sqlx::query("UPDATE tickets SET archived_at = NULL WHERE id = ? AND archived_at IS NOT NULL").bind(id);
+        Ok(self.change_row(Entity::Ticket, id, query).await? > 0)
+    }
+
+    /// Pin a ticket to the top of lists and the board; `false` if it was already pinned
+    pub async fn pin_ticket(&self, id: i64) -> Result<bool> {
+        let query = sqlx::query("UPDATE tickets SET pinned_at = ? WHERE id = ? AND pinned_at IS NULL").bind(Utc::now().naive_utc()).bind(id);
+        Ok(self.change_row(Entity::Ticket, id, query).await? > 0)
+    }
+
+    /// Put a pinned ticket back in its place; `false` if it was not pinned
+    pub async fn unpin_ticket(&self, id: i64) -> Result<bool> {
+        let query = sqlx::query("UPDATE tickets SET pinned_at = NULL WHERE id = ? AND pinned_at IS NOT NULL").bind(id);
+        Ok(self.change_row(Entity::Ticket, id, query).await? > 0)
+    }
+
+    /// Counts of unfinished tickets, due today, and overdue, in one query
+    pub async fn open_counts(&self, today: NaiveDate) -> Result<OpenCounts> {
+        let finished = FINISHED_STATUSES
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            r#"
+            SELECT COUNT(*) AS open,
+                COALESCE(SUM(CASE WHEN due_date = ? THEN 1 ELSE 0 END), 0) AS due_today,
+                COALESCE(SUM(CASE WHEN due_date < ? THEN 1 ELSE 0 END), 0) AS overdue
+            FROM tickets WHERE LOWER(status) NOT IN ({})
+            "#,
+            finished
+        );
+        let counts = sqlx::query_as::<_, OpenCounts>(&query)
+            .bind(today)
+            .bind(today)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(counts)
+    }
+
+    /// Unarchived tickets of `project` in `status`, for its WIP limit
+    pub async fn count_in_status(&self, project: &str, status: &str) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM tickets WHERE project = ? AND LOWER(status) = LOWER(?) AND archived_at IS NULL",
+        )
+        .bind(project)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Delete a ticket; its comments, time logs, links, checklist, and tags go with it and
+    /// are journaled only as part of the ticket's delete event
+    pub async fn delete_ticket(&self, id: i64) -> Result<()> {
+        self.delete_tickets(&[id]).await?;
+        Ok(())
+    }
+
+    /// Delete several tickets and everything attached to them in one transaction, returning
+    /// how many tickets were removed
+    pub async fn delete_tickets(&self, ids: &[i64]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for &id in ids {
+            let before = snapshot(&mut tx, Entity::Ticket, id).await?;
+            // Comments and time logs predate ON DELETE CASCADE, so they are removed by hand
+            sqlx::query("DELETE FROM comments WHERE ticket_id = ?").bind(id).execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM time_logs WHERE ticket_id = ?").bind(id).execute(&mut *tx).await?;
+            deleted += sqlx::query("DELETE FROM tickets WHERE id = ?").bind(id).execute(&mut *tx).await?.rows_affected();
+            self.record_event(&mut tx, Entity::Ticket, id, before).await?;
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// What deleting the given tickets takes with it: comments, time logs and their total
+    /// length, links, checklist items, dependencies, and links to synced issues
+    pub async fn delete_impact(&self, ids: &[i64]) -> Result<DeleteImpact> {
+        if ids.is_empty() {
+            return Ok(DeleteImpact::default());
+        }
+        let ids = ids.iter().map(i64::to_string).collect::<Vec<_>>().join(", ");
+        let query = format!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM comments WHERE ticket_id IN ({0})) AS comments,
+                (SELECT COUNT(*) FROM time_logs WHERE ticket_id IN ({0})) AS time_logs,
+                (SELECT COALESCE(SUM(hours * 60 + minutes), 0) FROM time_logs WHERE ticket_id IN ({0})) AS minutes,
+                (SELECT COUNT(*) FROM ticket_urls WHERE ticket_id IN ({0})) AS links,
+                (SELECT COUNT(*) FROM checklist_items WHERE ticket_id IN ({0})) AS checklist_items,
+                (SELECT COUNT(*) FROM ticket_dependencies WHERE ticket_id IN ({0}) OR blocker_id IN ({0})) AS dependencies,
+                (SELECT COUNT(*) FROM remote_links WHERE ticket_id IN ({0})) AS synced_issues
+            "#,
+            ids
+        );
+        let impact = sqlx::query_as::<_, DeleteImpact>(&query).fetch_one(&self.pool).await?;
+        Ok(impact)
+    }
+
+    pub async fn add_comment(&self, ticket_id: i64, content: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        self.insert_comment(&mut tx, ticket_id, content, None).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Reply to a comment on the same ticket, returning the reply's ID
+    pub async fn add_comment_reply(&self, parent: &Comment, content: &str) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let id = self.insert_comment(&mut tx, parent.ticket_id, content, Some(parent.id)).await?;
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    async fn insert_comment(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        ticket_id: i64,
+        content: &str,
+        parent_comment_id: Option<i64>,
+    ) -> Result<i64> {
+        let id = sqlx::query(&format!(
+            r#"
+            INSERT INTO comments (ticket_id, content, created_at, updated_at, parent_comment_id, uuid)
+            VALUES (?1, ?2, ?3, ?3, ?4, {})
+            "#,
+            NEW_UUID
+        ))
+        .bind(ticket_id)
+        .bind(content)
+        .bind(Utc::now().naive_utc())
+        .bind(parent_comment_id)
+        .execute(&mut **tx)
+        .await?
+        .last_insert_rowid();
+        self.record_event(tx, Entity::Comment, id, None).await?;
+        Ok(id)
+    }
+
+    /// Turn a comment into a new ticket in `project` whose description is the comment text.
+    ///
+    /// The new ticket gets a comment pointing back at the original, and the original comment
+    /// a reply pointing at the new ticket. Returns the new ticket's ID.
+    pub async fn promote_comment(&self, comment: &Comment, project: &str, name: &str, status: &str) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let id = self.insert_ticket(&mut tx, project, name, &comment.content, status).await?;
+        let origin = format!("Promoted from comment #{} on ticket #{}", comment.id, comment.ticket_id);
+        self.insert_comment(&mut tx, id, &origin, None).await?;
+        let pointer = format!("Promoted to ticket #{}", id);
+        self.insert_comment(&mut tx, comment.ticket_id, &pointer, Some(comment.id)).await?;
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    pub async fn get_comments(&self, ticket_id: i64) -> Result<Vec<Comment>> {
+        let comments = sqlx::query_as::<_, Comment>(
+            &format!("SELECT {} FROM comments WHERE ticket_id = ? ORDER BY created_at DESC", COMMENT_COLUMNS)
+        )
+        .bind(ticket_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    /// Attach an external URL to a ticket, returning the link's ID
+    pub async fn add_ticket_url(&self, ticket_id: i64, url: &str, label: Option<&str>) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let id = sqlx::query("INSERT INTO ticket_urls (ticket_id, url, label, created_at) VALUES (?, ?, ?, ?)")
+            .bind(ticket_id)
+            .bind(url)
+            .bind(label)
+            .bind(Utc::now().naive_utc())
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+        self.record_event(&mut tx, Entity::TicketUrl, id, None).await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// A ticket's links in the order they were added
+    pub async fn get_ticket_urls(&self, ticket_id: i64) -> Result<Vec<TicketUrl>> {
+        let urls = sqlx::query_as::<_, TicketUrl>(
+            "SELECT id, ticket_id, url, label, created_at FROM ticket_urls WHERE ticket_id = ? ORDER BY id ASC",
+        )
+        .bind(ticket_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(urls)
+    }
+
+    /// Append an item to a ticket's checklist, returning its position
+    pub async fn add_checklist_item(&self, ticket_id: i64, text: &str) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let position: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(position), 0) + 1 FROM checklist_items WHERE ticket_id = ?",
+        )
+        .bind(ticket_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let id = sqlx::query("INSERT INTO checklist_items (ticket_id, position, text, done, created_at) VALUES (?, ?, ?, 0, ?)")
+            .bind(ticket_id)
+            .bind(position)
+            .bind(text)
+            .bind(Utc::now().naive_utc())
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+        self.record_event(&mut tx, Entity::ChecklistItem, id, None).await?;
+        tx.commit().await?;
+        Ok(position)
+    }
+
+    /// A ticket's checklist in order
+    pub async fn get_checklist(&self, ticket_id: i64) -> Result<Vec<ChecklistItem>> {
+        let items = sqlx::query_as::<_, ChecklistItem>(
+            "SELECT id, ticket_id, position, text, done, created_at FROM checklist_items WHERE ticket_id = ? ORDER BY position ASC",
+        )
+        .bind(ticket_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(items)
+    }
+
+    /// Flip an item between done and not done, returning the new state, or `None` if the
+    /// ticket has no item at that position
+    pub async fn toggle_checklist_item(&self, ticket_id: i64, position: i64) -> Result<Option<bool>> {
+        let id: Option<i64> = sqlx::query_scalar("SELECT id FROM checklist_items WHERE ticket_id = ? AND position = ?")
+            .bind(ticket_id)
+            .bind(position)
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        let mut tx = self.pool.begin().await?;
+        let before = snapshot(&mut tx, Entity::ChecklistItem, id).await?;
+        let done: bool = sqlx::query_scalar("UPDATE checklist_items SET done = NOT done WHERE id = ? RETURNING done")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        self.record_event(&mut tx, Entity::ChecklistItem, id, before).await?;
+        tx.commit().await?;
+        Ok(Some(done))
+    }
+
+    /// `(done, total)` checklist counts for every ticket that has a checklist
+    pub async fn checklist_progress(&self) -> Result<HashMap<i64, (i64, i64)>> {
+        let rows = sqlx::query("SELECT ticket_id, SUM(done), COUNT(*) FROM checklist_items GROUP BY ticket_id")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(|row| (row.get::<i64, _>(0), (row.get::<i64, _>(1), row.get::<i64, _>(2)))).collect())
+    }
+
+    /// Every comment, optionally limited to one project, grouped by ticket and oldest first
+    pub async fn list_comments(&self, project: Option<&str>) -> Result<Vec<Comment>> {
+        let comments = match project {
+            Some(project) => {
+                sqlx::query_as::<_, Comment>(
+                    r#"
+                    SELECT c.id, c.ticket_id, c.content, c.created_at, c.updated_at, c.parent_comment_id, c.uuid, c.created_by, c.updated_by
+                    FROM comments c JOIN tickets t ON t.id = c.ticket_id
+                    WHERE t.project = ?
+                    ORDER BY c.ticket_id ASC, c.created_at ASC, c.id ASC
+                    "#,
+                )
+                .bind(project)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, Comment>(
+                    &format!("SELECT {} FROM comments ORDER BY ticket_id ASC, created_at ASC, id ASC", COMMENT_COLUMNS),
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(comments)
+    }
+
+    pub async fn get_comment(&self, comment_id: i64) -> Result<Option<Comment>> {
+        let comment = sqlx::query_as::<_, Comment>(
+            &format!("SELECT {} FROM comments WHERE id = ?", COMMENT_COLUMNS),
+        )
+        .bind(comment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(comment)
+    }
+
+    pub async fn update_comment(&self, comment_id: i64, content: &str) -> Result<()> {
+        let query = sqlx::query("UPDATE comments SET content = ? WHERE id = ?").bind(content).bind(comment_id);
+        self.change_row(Entity::Comment, comment_id, query).await?;
+        Ok(())
+    }
+
+    pub async fn delete_comment(&self, comment_id: i64) -> Result<()> {
+        let query = sqlx::query("DELETE FROM comments WHERE id = ?").bind(comment_id);
+        self.change_row(Entity::Comment, comment_id, query).await?;
+        Ok(())
+    }
+
+    pub async fn add_time_log(
+        &self,
+        ticket_id: i64,
+        hours: i32,
+        minutes: i32,
+        started_at: Option<DateTime<Utc>>,
+        ended_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        self.insert_time_log(ticket_id, hours, minutes, (started_at, ended_at), Utc::now().naive_utc(), None).await?;
+        Ok(())
+    }
+
+    /// Log time with a note on what it was spent on
+    pub async fn add_time_log_with_note(
+        &self,
+        ticket_id: i64,
+        hours: i32,
+        minutes: i32,
+        started_at: Option<DateTime<Utc>>,
+        ended_at: Option<DateTime<Utc>>,
+        note: Option<&str>,
+    ) -> Result<()> {
+        self.insert_time_log(ticket_id, hours, minutes, (started_at, ended_at), Utc::now().naive_utc(), note).await?;
+        Ok(())
+    }
+
+    /// Log time recorded as of `logged_at` (UTC) rather than now, for work tracked after the
+    /// fact; returns the new log's ID
+    pub async fn add_time_log_at(&self, ticket_id: i64, hours: i32, minutes: i32, logged_at: NaiveDateTime) -> Result<i64> {
+        self.insert_time_log(ticket_id, hours, minutes, (None, None), logged_at, None).await
+    }
+
+    /// `add_time_log_at` with a note on what the time was spent on
+    pub async fn add_time_log_at_with_note(
+        &self,
+        ticket_id: i64,
+        hours: i32,
+        minutes: i32,
+        logged_at: NaiveDateTime,
+        note: Option<&str>,
+    ) -> Result<i64> {
+        self.insert_time_log(ticket_id, hours, minutes, (None, None), logged_at, note).await
+    }
+
+    async fn insert_time_log(
+        &self,
+        ticket_id: i64,
+        hours: i32,
+        minutes: i32,
+        (started_at, ended_at): (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+        logged_at: NaiveDateTime,
+        note: Option<&str>,
+    ) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let id = sqlx::query(&format!(
+            r#"
+            INSERT INTO time_logs (ticket_id, hours, minutes, started_at, ended_at, created_at, updated_at, note, uuid)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, {})
+            "#,
+            NEW_UUID
+        ))
+        .bind(ticket_id)
+        .bind(hours)
+        .bind(minutes)
+        .bind(started_at.map(|dt| dt.naive_utc()))
+        .bind(ended_at.map(|dt| dt.naive_utc()))
+        .bind(logged_at)
+        .bind(Utc::now().naive_utc())
+        .bind(note)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        self.record_event(&mut tx, Entity::TimeLog, id, None).await?;
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    pub async fn get_project_summary(&self, project: &str) -> Result<ProjectSummary> {
+        let row = sqlx::query(
+            r#"
+            SELECT 
+                COUNT(*) as total_tickets,
+                SUM(CASE WHEN status = 'open' THEN 1 ELSE 0 END) as open_tickets,
+                SUM(CASE WHEN status = 'closed' THEN 1 ELSE 0 END) as closed_tickets,
+                COALESCE(SUM(tl.hours + tl.minutes / 60.0), 0.0) as total_time_hours
+            FROM tickets t
+            LEFT JOIN time_logs tl ON t.id = tl.ticket_id
+            WHERE t.project = ?
+            GROUP BY t.project
+            "#
+        )
+        .bind(project)
+        .fetch_optional(&self.pool)
+        .await?;
+        let kinds: Vec<(String, i64)> =
+            sqlx::query_as("SELECT kind, COUNT(*) FROM tickets WHERE project = ? AND kind IS NOT NULL GROUP BY kind")
+                .bind(project)
+                .fetch_all(&self.pool)
+                .await?;
+        let components: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT component, COUNT(*) FROM tickets WHERE project = ? AND component IS NOT NULL GROUP BY component",
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                Ok(ProjectSummary {
+                    project: project.to_string(),
+                    total_tickets: row.get::<i64, _>(0),
+                    open_tickets: row.get::<Option<i64>, _>(1).unwrap_or(0),
+                    closed_tickets: row.get::<Option<i64>, _>(2).unwrap_or(0),
+                    total_time_hours: row.get::<f64, _>(3),
+                    kinds: kinds.into_iter().collect(),
+                    components: components.into_iter().collect(),
+                })
+            }
+            None => {
+                // Return empty summary for non-existent projects
+                Ok(ProjectSummary {
+                    project: project.to_string(),
+                    total_tickets: 0,
+                    open_tickets: 0,
+                    closed_tickets: 0,
+                    total_time_hours: 0.0,
+                    kinds: Default::default(),
+                    components: Default::default(),
+                })
+            }
+        }
+    }
+
+    pub async fn get_time_logs(&self, ticket_id: i64) -> Result<Vec<crate::models::TimeLog>> {
+        let time_logs = sqlx::query_as::<_, crate::models::TimeLog>(&format!(
+            "SELECT {} FROM time_logs WHERE ticket_id = ? ORDER BY created_at DESC",
+            TIME_LOG_COLUMNS
+        ))
+        .bind(ticket_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(time_logs)
+    }
+
+    /// Tickets created or changed after `since` (all of them for `None`), oldest change first
+    pub async fn tickets_changed_since(&self, since: Option<NaiveDateTime>, project: Option<&str>) -> Result<Vec<Ticket>> {
+        let tickets = sqlx::query_as::<_, Ticket>(&format!(
+            r#"
+            SELECT {} FROM tickets
+            WHERE (?1 IS NULL OR updated_at > ?1) AND (?2 IS NULL OR project = ?2)
+            ORDER BY updated_at ASC, id ASC
+            "#,
+            TICKET_COLUMNS
+        ))
+        .bind(since)
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tickets)
+    }
+
+    /// Comments written or edited after `since`, oldest change first
+    pub async fn comments_changed_since(&self, since: Option<NaiveDateTime>, project: Option<&str>) -> Result<Vec<Comment>> {
+        let comments = sqlx::query_as::<_, Comment>(&format!(
+            r#"
+            SELECT {} FROM comments
+            WHERE (?1 IS NULL OR updated_at > ?1)
+                AND (?2 IS NULL OR ticket_id IN (SELECT id FROM tickets WHERE project = ?2))
+            ORDER BY updated_at ASC, id ASC
+            "#,
+            COMMENT_COLUMNS
+        ))
+        .bind(since)
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(comments)
+    }
+
+    /// Time logs recorded or edited after `since`, oldest change first
+    pub async fn time_logs_changed_since(
+        &self,
+        since: Option<NaiveDateTime>,
+        project: Option<&str>,
+    ) -> Result<Vec<crate::models::TimeLog>> {
+        let time_logs = sqlx::query_as::<_, crate::models::TimeLog>(&format!(
+            r#"
+            SELECT {} FROM time_logs
+            WHERE (?1 IS NULL OR updated_at > ?1)
+                AND (?2 IS NULL OR ticket_id IN (SELECT id FROM tickets WHERE project = ?2))
+            ORDER BY updated_at ASC, id ASC
+            "#,
+            TIME_LOG_COLUMNS
+        ))
+        .bind(since)
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(time_logs)
+    }
+
+    /// Time logs between two days (inclusive), dated like `hours_by_ticket`, oldest first
+    pub async fn time_logs_between(
+        &self,
+        since: NaiveDate,
+        until: NaiveDate,
+        project: Option<&str>,
+    ) -> Result<Vec<crate::models::TimeLog>> {
+        let time_logs = sqlx::query_as::<_, crate::models::TimeLog>(&format!(
+            r#"
+            SELECT {} FROM time_logs
+            WHERE date(COALESCE(started_at, created_at)) BETWEEN ?1 AND ?2
+                AND (?3 IS NULL OR ticket_id IN (SELECT id FROM tickets WHERE project = ?3))
+            ORDER BY COALESCE(started_at, created_at) ASC, id ASC
+            "#,
+            TIME_LOG_COLUMNS
+        ))
+        .bind(since.format("%Y-%m-%d").to_string())
+        .bind(until.format("%Y-%m-%d").to_string())
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(time_logs)
+    }
+
+    /// Time logged per ticket between two days (inclusive), dated the same way as `daily_hours`
+    pub async fn hours_by_ticket(
+        &self,
+        since: NaiveDate,
+        until: NaiveDate,
+        project: Option<&str>,
+    ) -> Result<Vec<TicketHours>> {
+        let mut sql = String::from(
+            r#"
+            SELECT t.id AS ticket_id, t.project, t.name, t.status,
+                   COUNT(*) AS entries,
+                   SUM(tl.hours * 60 + tl.minutes) AS minutes,
+                   ROUND(SUM(tl.hours * 60 + tl.minutes) / 60.0, 2) AS hours,
+                   SUM(CASE WHEN tl.billable THEN tl.hours * 60 + tl.minutes ELSE 0 END) AS billable_minutes,
+                   COALESCE(t.rate, CAST(ps.value AS REAL)) AS rate,
+                   ROUND(SUM(CASE WHEN tl.billable THEN tl.hours * 60 + tl.minutes ELSE 0 END)
+                         * COALESCE(t.rate, CAST(ps.value AS REAL)) / 60.0, 2) AS amount,
+                   pc.value AS currency
+            FROM time_logs tl
+            JOIN tickets t ON t.id = tl.ticket_id
+            LEFT JOIN project_settings ps ON ps.project = t.project AND ps.key = 'rate'
+            LEFT JOIN project_settings pc ON pc.project = t.project AND pc.key = 'currency'
+            WHERE date(COALESCE(tl.started_at, tl.created_at)) BETWEEN ? AND ?
+            "#,
+        );
+        if project.is_some() {
+            sql.push_str(" AND t.project = ?");
+        }
+        sql.push_str(" GROUP BY t.id ORDER BY t.project, minutes DESC, t.id");
+
+        let mut query = sqlx::query_as::<_, TicketHours>(&sql)
+            .bind(since.format("%Y-%m-%d").to_string())
+            .bind(until.format("%Y-%m-%d").to_string());
+        if let Some(p) = project {
+            query = query.bind(p);
+        }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// Each time log between two days (inclusive) with its ticket, dated the same way as
+    /// `hours_by_ticket`, oldest first
+    pub async fn time_log_entries(
+        &self,
+        since: NaiveDate,
+        until: NaiveDate,
+        project: Option<&str>,
+    ) -> Result<Vec<TimeLogEntry>> {
+        let entries = sqlx::query_as::<_, TimeLogEntry>(
+            r#"
+            SELECT tl.id, t.project, t.id AS ticket_id, t.name AS ticket_name,
+                   date(COALESCE(tl.started_at, tl.created_at)) AS date,
+                   tl.hours * 60 + tl.minutes AS minutes, tl.billable, tl.note
+            FROM time_logs tl
+            JOIN tickets t ON t.id = tl.ticket_id
+            WHERE date(COALESCE(tl.started_at, tl.created_at)) BETWEEN ?1 AND ?2
+                AND (?3 IS NULL OR t.project = ?3)
+            ORDER BY COALESCE(tl.started_at, tl.created_at), tl.id
+            "#,
+        )
+        .bind(since.format("%Y-%m-%d").to_string())
+        .bind(until.format("%Y-%m-%d").to_string())
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    /// Hours logged per day (by start time, falling back to when the log was recorded)
+    pub async fn daily_hours(
+        &self,
+        since: NaiveDate,
+        until: NaiveDate,
+        project: Option<&str>,
+    ) -> Result<Vec<(NaiveDate, f64)>> {
+        let mut sql = String::from(
+            r#"
+            SELECT date(COALESCE(tl.started_at, tl.created_at)) AS day,
+                   SUM(tl.hours + tl.minutes / 60.0) AS hours
+            FROM time_logs tl
+            JOIN tickets t ON t.id = tl.ticket_id
+            WHERE date(COALESCE(tl.started_at, tl.created_at)) BETWEEN ? AND ?
+            "#,
+        );
+        if project.is_some() {
+            sql.push_str(" AND t.project = ?");
+        }
+        sql.push_str(" GROUP BY day ORDER BY day");
+
+        let mut query = sqlx::query(&sql)
+            .bind(since.format("%Y-%m-%d").to_string())
+            .bind(until.format("%Y-%m-%d").to_string());
+        if let Some(p) = project {
+            query = query.bind(p);
+        }
+
+        let rows = query.fetch_all(&self.pool).await?;
+        let mut days = Vec::new();
+        for row in rows {
+            let day: String = row.get("day");
+            if let Ok(date) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+                days.push((date, row.get::<f64, _>("hours")));
+            }
+        }
+        Ok(days)
+    }
+
+    pub async fn get_time_log(&self, log_id: i64) -> Result<Option<crate::models::TimeLog>> {
+        let log = sqlx::query_as::<_, crate::models::TimeLog>(&format!(
+            "SELECT {} FROM time_logs WHERE id = ?",
+            TIME_LOG_COLUMNS
+        ))
+        .bind(log_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(log)
+    }
+
+    pub async fn update_time_log(&self, log_id: i64, hours: i32, minutes: i32) -> Result<()> {
+        let query = sqlx::query("UPDATE time_logs SET hours = ?, minutes = ?, updated_at = ? WHERE id = ?")
+            .bind(hours)
+            .bind(minutes)
+            .bind(Utc::now().naive_utc())
+            .bind(log_id);
+        self.change_row(Entity::TimeLog, log_id, query).await?;
+        Ok(())
+    }
+
+    /// Set when the work in a time log started and ended (UTC), along with its length
+    pub async fn update_time_log_span(
+        &self,
+        log_id: i64,
+        hours: i32,
+        minutes: i32,
+        started_at: Option<NaiveDateTime>,
+        ended_at: Option<NaiveDateTime>,
+    ) -> Result<()> {
+        let query = sqlx::query(
+            "UPDATE time_logs SET hours = ?, minutes = ?, started_at = ?, ended_at = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(hours)
+        .bind(minutes)
+        .bind(started_at)
+        .bind(ended_at)
+        .bind(Utc::now().naive_utc())
+        .bind(log_id);
+        self.change_row(Entity::TimeLog, log_id, query).await?;
+        Ok(())
+    }
+
+    /// Mark a time log as billable or not
+    pub async fn update_time_log_billable(&self, log_id: i64, billable: bool) -> Result<()> {
+        let query = sqlx::query("UPDATE time_logs SET billable = ?, updated_at = ? WHERE id = ?")
+            .bind(billable)
+            .bind(Utc::now().naive_utc())
+            .bind(log_id);
+        self.change_row(Entity::TimeLog, log_id, query).await?;
+        Ok(())
+    }
+
+    /// Add, change, and delete time logs in one transaction, so either all of the changes
+    /// are made or none are
+    pub async fn apply_time_log_changes(&self, changes: &[crate::models::TimeLogChange]) -> Result<()> {
+        use crate::models::TimeLogChange;
+
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now().naive_utc();
+        for change in changes {
+            match change {
+                TimeLogChange::Add { ticket_id, hours, minutes, logged_at, note } => {
+                    let id = sqlx::query(&format!(
+                        r#"
+                        INSERT INTO time_logs (ticket_id, hours, minutes, created_at, updated_at, note, uuid)
+                        VALUES (?, ?, ?, ?, ?, ?, {})
+                        "#,
+                        NEW_UUID
+                    ))
+                    .bind(ticket_id)
+                    .bind(hours)
+                    .bind(minutes)
+                    .bind(logged_at)
+                    .bind(now)
+                    .bind(note)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid();
+                    self.record_event(&mut tx, Entity::TimeLog, id, None).await?;
+                }
+                TimeLogChange::Update { id, ticket_id, hours, minutes, started_at, ended_at, note } => {
+                    let before = snapshot(&mut tx, Entity::TimeLog, *id).await?;
+                    sqlx::query(
+                        r#"
+                        UPDATE time_logs
+                        SET ticket_id = ?, hours = ?, minutes = ?, started_at = ?, ended_at = ?, note = ?, updated_at = ?
+                        WHERE id = ?
+                        "#,
+                    )
+                    .bind(ticket_id)
+                    .bind(hours)
+                    .bind(minutes)
+                    .bind(started_at)
+                    .bind(ended_at)
+                    .bind(note)
+                    .bind(now)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                    self.record_event(&mut tx, Entity::TimeLog, *id, before).await?;
+                }
+                TimeLogChange::Delete { id } => {
+                    let before = snapshot(&mut tx, Entity::TimeLog, *id).await?;
+                    sqlx::query("DELETE FROM time_logs WHERE id = ?").bind(id).execute(&mut *tx).await?;
+                    self.record_event(&mut tx, Entity::TimeLog, *id, before).await?;
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete_time_log(&self, log_id: i64) -> Result<()> {
+        let query = sqlx::query("DELETE FROM time_logs WHERE id = ?").bind(log_id);
+        self.change_row(Entity::TimeLog, log_id, query).await?;
+        Ok(())
+    }
+
+    /// Move a ticket to the end of another project's backlog. Its component and release are
+    /// kept only when the project has ones by the same names.
+    pub async fn move_ticket_project(&self, id: i64, project: &str) -> Result<()> {
+        let sql = format!(
+            r#"
+            UPDATE tickets SET project = ?, rank = {},
+                component = CASE WHEN EXISTS (SELECT 1 FROM components WHERE project = ? AND name = tickets.component) THEN component END,
+                target_release = CASE WHEN EXISTS (SELECT 1 FROM releases WHERE project = ? AND name = tickets.target_release) THEN target_release END,
+                updated_at = ?
+            WHERE id = ?
+            "#,
+            NEXT_RANK
+        );
+        let query = sqlx::query(&sql)
+            .bind(project)
+            .bind(project)
+            .bind(project)
+            .bind(project)
+            .bind(Utc::now().naive_utc())
+            .bind(id);
+        self.change_row(Entity::Ticket, id, query).await?;
+        Ok(())
+    }
+
+    /// Move every ticket in `old`, archived ones included, and its settings, components, and
+    /// releases to `new` in one transaction, returning how many tickets changed. `updated_at`
+    /// is left alone.
+    pub async fn rename_project(&self, old: &str, new: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let ids = sqlx::query_scalar::<_, i64>("SELECT id FROM tickets WHERE project = ? ORDER BY id")
+            .bind(old)
+            .fetch_all(&mut *tx)
+            .await?;
+        for &id in &ids {
+            let before = snapshot(&mut tx, Entity::Ticket, id).await?;
+            sqlx::query("UPDATE tickets SET project = ? WHERE id = ?").bind(new).bind(id).execute(&mut *tx).await?;
+            self.record_event(&mut tx, Entity::Ticket, id, before).await?;
+        }
+        for table in ["project_settings", "components", "releases"] {
+            sqlx::query(&format!("UPDATE OR REPLACE {} SET project = ? WHERE project = ?", table))
+                .bind(new)
+                .bind(old)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(ids.len() as u64)
+    }
+
+    pub async fn copy_ticket(&self, id: i64, target_project: Option<&str>) -> Result<i64> {
+        let ticket = self.get_ticket(id).await?.context("Source ticket not found")?;
+        let now = Utc::now().naive_utc();
+        let project = target_project.unwrap_or(&ticket.project);
+        let mut tx = self.pool.begin().await?;
+        let new_id = sqlx::query(&format!(
+            r#"
+            INSERT INTO tickets (project, name, description, status, created_at, updated_at, rank, uuid)
+            VALUES (?, ?, ?, ?, ?, ?, {}, {})
+            "#,
+            NEXT_RANK, NEW_UUID
+        ))
+        .bind(project)
+        .bind(&ticket.name)
+        .bind(&ticket.description)
+        .bind(&ticket.status)
+        .bind(now)
+        .bind(now)
+        .bind(project)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        record_status_change(&mut tx, new_id, None, &ticket.status, now).await?;
+        self.record_event(&mut tx, Entity::Ticket, new_id, None).await?;
+        tx.commit().await?;
+        Ok(new_id)
+    }
+
+    /// Move a ticket to a 1-based position in its project's backlog, renumbering the rest.
+    /// Positions past the end are clamped; returns the position the ticket ended up at.
+    pub async fn move_ticket_to_position(&self, id: i64, position: usize) -> Result<usize> {
+        let ticket = self.get_ticket(id).await?.context("Ticket not found")?;
+        let mut ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM tickets WHERE project = ? AND id != ? ORDER BY rank ASC, id ASC",
+        )
+        .bind(&ticket.project)
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let index = position.saturating_sub(1).min(ids.len());
+        ids.insert(index, id);
+
+        // Only the moved ticket is journaled; the others' ranks follow from it
+        let mut tx = self.pool.begin().await?;
+        let before = snapshot(&mut tx, Entity::Ticket, id).await?;
+        for (rank, ticket_id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE tickets SET rank = ? WHERE id = ?")
+                .bind(rank as i64 + 1)
+                .bind(ticket_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        self.record_event(&mut tx, Entity::Ticket, id, before).await?;
+        tx.commit().await?;
+
+        Ok(index + 1)
+    }
+
+    pub async fn get_state(&self, key: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT value FROM app_state WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|r| r.get("value")))
+    }
+
+    /// Store UI state such as the focused ticket; it is not journaled
+    pub async fn set_state(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO app_state (key, value) VALUES (?, ?)")
+            .bind(key)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_state(&self, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM app_state WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// A project's overrides; projects without any get the defaults
+    pub async fn get_project_settings(&self, project: &str) -> Result<ProjectSettings> {
+        let pairs = sqlx::query_as::<_, (String, String)>("SELECT key, value FROM project_settings WHERE project = ?")
+            .bind(project)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ProjectSettings::from_pairs(pairs))
+    }
+
+    /// Settings of every project that has any, keyed by project
+    pub async fn all_project_settings(&self) -> Result<HashMap<String, ProjectSettings>> {
+        let rows = sqlx::query_as::<_, (String, String, String)>("SELECT project, key, value FROM project_settings")
+            .fetch_all(&self.pool)
+            .await?;
+        let mut pairs: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for (project, key, value) in rows {
+            pairs.entry(project).or_default().push((key, value));
+        }
+        Ok(pairs.into_iter().map(|(project, pairs)| (project, ProjectSettings::from_pairs(pairs))).collect())
+    }
+
+    /// Store one project setting; the value should come from `ProjectSettings::normalize`.
+    /// Settings are configuration and are not journaled.
+    pub async fn set_project_setting(&self, project: &str, key: &str, value: &str) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO project_settings (project, key, value) VALUES (?, ?, ?)")
+            .bind(project)
+            .bind(key)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove what a deleted project keeps besides its tickets: its settings, components, and
+    /// releases.
+    /// Returns how many rows went.
+    pub async fn forget_project(&self, project: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut removed = 0;
+        for table in ["project_settings", "components", "releases"] {
+            let query = format!("DELETE FROM {} WHERE project = ?", table);
+            removed += sqlx::query(&query).bind(project).execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(removed)
+    }
+
+    /// Remove one project setting, or all of them when `key` is `None`; returns how many went
+    pub async fn unset_project_settings(&self, project: &str, key: Option<&str>) -> Result<u64> {
+        let removed = sqlx::query("DELETE FROM project_settings WHERE project = ? AND (? IS NULL OR key = ?)")
+            .bind(project)
+            .bind(key)
+            .bind(key)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        Ok(removed)
+    }
+
+    pub async fn start_timer(&self, ticket_id: i64, at: NaiveDateTime) -> Result<()> {
+        self.start_timer_with_note(ticket_id, at, None).await
+    }
+
+    /// Start (or restart) a timer with a note on what the time is for
+    pub async fn start_timer_with_note(&self, ticket_id: i64, at: NaiveDateTime, note: Option<&str>) -> Result<()> {
+        let query = sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO active_timers (ticket_id, started_at, segment_started_at, paused_at, elapsed_seconds, note)
+            VALUES (?, ?, ?, NULL, 0, ?)
+            "#,
+        )
+        .bind(ticket_id)
+        .bind(at)
+        .bind(at)
+        .bind(note);
+        self.change_row(Entity::Timer, ticket_id, query).await?;
+        Ok(())
+    }
+
+    pub async fn get_timer(&self, ticket_id: i64) -> Result<Option<ActiveTimer>> {
+        let timer = sqlx::query_as::<_, ActiveTimer>(
+            "SELECT ticket_id, started_at, segment_started_at, paused_at, elapsed_seconds, note FROM active_timers WHERE ticket_id = ?",
+        )
+        .bind(ticket_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(timer)
+    }
+
+    pub async fn list_timers(&self) -> Result<Vec<ActiveTimer>> {
+        let timers = sqlx::query_as::<_, ActiveTimer>(
+            "SELECT ticket_id, started_at, segment_started_at, paused_at, elapsed_seconds, note FROM active_timers ORDER BY started_at ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(timers)
+    }
+
+    pub async fn pause_timer(&self, ticket_id: i64, at: NaiveDateTime) -> Result<()> {
+        let query = sqlx::query("UPDATE active_timers SET paused_at = ? WHERE ticket_id = ?").bind(at).bind(ticket_id);
+        self.change_row(Entity::Timer, ticket_id, query).await?;
+        Ok(())
+    }
+
+    /// Fold the paused segment into the elapsed total and start a new segment at `at`
+    pub async fn resume_timer(&self, ticket_id: i64, at: NaiveDateTime) -> Result<()> {
+        if let Some(timer) = self.get_timer(ticket_id).await? {
+            let elapsed = timer.elapsed(at).num_seconds();
+            let query = sqlx::query(
+                r#"UPDATE active_timers SET segment_started_at = ?, paused_at = NULL, elapsed_seconds = ? WHERE ticket_id = ?"#,
+            )
+            .bind(at)
+            .bind(elapsed)
+            .bind(ticket_id);
+            self.change_row(Entity::Timer, ticket_id, query).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove a timer, returning whether one existed
+    pub async fn delete_timer(&self, ticket_id: i64) -> Result<bool> {
+        let query = sqlx::query("DELETE FROM active_timers WHERE ticket_id = ?").bind(ticket_id);
+        Ok(self.change_row(Entity::Timer, ticket_id, query).await? > 0)
+    }
+
+    /// Every ticket linked to an issue in `provider`
+    pub async fn list_remote_links(&self, provider: &str) -> Result<Vec<RemoteLink>> {
+        let links = sqlx::query_as::<_, RemoteLink>(
+            "SELECT ticket_id, provider, remote_id, remote_key, synced_at, remote_updated_at, base FROM remote_links WHERE provider = ?",
+        )
+        .bind(provider)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links)
+    }
+
+    /// Link a ticket to an issue, or record that the two match again
+    pub async fn save_remote_link(&self, link: &RemoteLink) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO remote_links (ticket_id, provider, remote_id, remote_key, synced_at, remote_updated_at, base)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(link.ticket_id)
+        .bind(&link.provider)
+        .bind(&link.remote_id)
+        .bind(&link.remote_key)
+        .bind(link.synced_at)
+        .bind(link.remote_updated_at)
+        .bind(&link.base)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Journal how a sync settled a field changed on both sides. `before` holds the two
+    /// versions and `after` the one kept; both name the ticket as `ticket_id`.
+    pub async fn record_sync_resolution(&self, ticket_id: i64, before: &serde_json::Value, after: &serde_json::Value) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO events (command, entity, entity_id, action, before, after, created_at) VALUES (?, 'sync_conflict', ?, 'resolve', ?, ?, ?)",
+        )
+        .bind(self.command.as_deref())
+        .bind(ticket_id)
+        .bind(before.to_string())
+        .bind(after.to_string())
+        .bind(Utc::now().naive_utc())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Every time log sent to `provider` as a work log
+    pub async fn list_remote_worklogs(&self, provider: &str) -> Result<Vec<RemoteWorklog>> {
+        let worklogs = sqlx::query_as::<_, RemoteWorklog>(
+            "SELECT time_log_id, provider, remote_id, synced_at FROM remote_worklogs WHERE provider = ?",
+        )
+        .bind(provider)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(worklogs)
+    }
+
+    /// Record that a time log was sent as a work log
+    pub async fn save_remote_worklog(&self, worklog: &RemoteWorklog) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO remote_worklogs (time_log_id, provider, remote_id, synced_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(worklog.time_log_id)
+        .bind(&worklog.provider)
+        .bind(&worklog.remote_id)
+        .bind(worklog.synced_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// When the last sync with `provider` started, `None` before the first one
+    pub async fn last_synced_at(&self, provider: &str) -> Result<Option<NaiveDateTime>> {
+        let at = sqlx::query_scalar("SELECT last_synced_at FROM sync_state WHERE provider = ?")
+            .bind(provider)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(at)
+    }
+
+    pub async fn set_last_synced_at(&self, provider: &str, at: NaiveDateTime) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO sync_state (provider, last_synced_at) VALUES (?, ?)")
+            .bind(provider)
+            .bind(at)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Close the month starting on `month` for billing; returns false when it already was
+    pub async fn lock_billing_month(&self, month: NaiveDate) -> Result<bool> {
+        let result = sqlx::query("INSERT OR IGNORE INTO billing_locks (month, locked_at, locked_by) VALUES (?, ?, ?)")
+            .bind(month.format("%Y-%m").to_string())
+            .bind(Utc::now().naive_utc())
+            .bind(self.user.as_deref())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Reopen a locked month; returns false when it was not locked
+    pub async fn unlock_billing_month(&self, month: NaiveDate) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM billing_locks WHERE month = ?")
+            .bind(month.format("%Y-%m").to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Locked months, oldest first
+    pub async fn billing_locks(&self) -> Result<Vec<BillingLock>> {
+        let locks = sqlx::query_as::<_, BillingLock>("SELECT month, locked_at, locked_by FROM billing_locks ORDER BY month")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(locks)
+    }
+
+    /// Save a token for `ltm serve` by the hash of its secret; returns false when the name
+    /// is taken
+    pub async fn create_api_token(&self, name: &str, token_hash: &str, read_only: bool) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO api_tokens (name, token_hash, read_only, created_at, created_by) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(name)
+        .bind(token_hash)
+        .bind(read_only)
+        .bind(Utc::now().naive_utc())
+        .bind(self.user.as_deref())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a token, so the server refuses it from then on; returns false when there is none
+    pub async fn revoke_api_token(&self, name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM api_tokens WHERE name = ?").bind(name).execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn api_tokens(&self) -> Result<Vec<ApiToken>> {
+        let tokens = sqlx::query_as::<_, ApiToken>("SELECT name, read_only, created_at, created_by FROM api_tokens ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tokens)
+    }
+
+    /// The token whose secret hashes to `token_hash`, if there is one
+    pub async fn api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>> {
+        let token = sqlx::query_as::<_, ApiToken>("SELECT name, read_only, created_at, created_by FROM api_tokens WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(token)
+    }
+
+    /// Add a component to a project; returns false when it already has it
+    pub async fn add_component(&self, project: &str, name: &str) -> Result<bool> {
+        let result = sqlx::query("INSERT OR IGNORE INTO components (project, name, created_at) VALUES (?, ?, ?)")
+            .bind(project)
+            .bind(name)
+            .bind(Utc::now().naive_utc())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a component from a project; returns false when it had none by that name.
+    /// Its tickets are left as they are, so take them out first.
+    pub async fn remove_component(&self, project: &str, name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM components WHERE project = ? AND name = ?")
+            .bind(project)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// A project's components by name
+    pub async fn components(&self, project: &str) -> Result<Vec<Component>> {
+        let components =
+            sqlx::query_as::<_, Component>("SELECT project, name, created_at FROM components WHERE project = ? ORDER BY name")
+                .bind(project)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(components)
+    }
+
+    /// Add a release to a project; returns false when it already has it
+    pub async fn create_release(&self, project: &str, name: &str, due_date: Option<NaiveDate>) -> Result<bool> {
+        let result = sqlx::query("INSERT OR IGNORE INTO releases (project, name, created_at, due_date) VALUES (?, ?, ?, ?)")
+            .bind(project)
+            .bind(name)
+            .bind(Utc::now().naive_utc())
+            .bind(due_date)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set or clear the day a release is planned to ship
+    pub async fn update_release_due_date(&self, project: &str, name: &str, due_date: Option<NaiveDate>) -> Result<()> {
+        sqlx::query("UPDATE releases SET due_date = ? WHERE project = ? AND name = ?")
+            .bind(due_date)
+            .bind(project)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Releases of a project, or of every project, by due date with undated ones last,
+    /// then oldest first
+    pub async fn releases(&self, project: Option<&str>) -> Result<Vec<Release>> {
+        let releases = sqlx::query_as::<_, Release>(
+            r#"
+            SELECT project, name, created_at, due_date FROM releases
+            WHERE ?1 IS NULL OR project = ?1
+            ORDER BY due_date IS NULL, due_date, created_at, name
+            "#,
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(releases)
+    }
+
+    /// Tickets planned for a release of a project, archived ones included, in backlog order
+    pub async fn release_tickets(&self, project: &str, release: &str) -> Result<Vec<Ticket>> {
+        let tickets = sqlx::query_as::<_, Ticket>(&format!(
+            "SELECT {} FROM tickets WHERE project = ? AND target_release = ? ORDER BY rank, id",
+            TICKET_COLUMNS
+        ))
+        .bind(project)
+        .bind(release)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tickets)
+    }
+
+    /// Record that `ticket_id` waits on `blocker_id`; returns false when it already did
+    pub async fn add_dependency(&self, ticket_id: i64, blocker_id: i64) -> Result<bool> {
+        let result = sqlx::query("INSERT OR IGNORE INTO ticket_dependencies (ticket_id, blocker_id, created_at) VALUES (?, ?, ?)")
+            .bind(ticket_id)
+            .bind(blocker_id)
+            .bind(Utc::now().naive_utc())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Drop a dependency; returns false when `ticket_id` didn't wait on `blocker_id`
+    pub async fn remove_dependency(&self, ticket_id: i64, blocker_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM ticket_dependencies WHERE ticket_id = ? AND blocker_id = ?")
+            .bind(ticket_id)
+            .bind(blocker_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every dependency between tickets, oldest first
+    pub async fn dependencies(&self) -> Result<Vec<TicketDependency>> {
+        let dependencies = sqlx::query_as::<_, TicketDependency>(
+            "SELECT ticket_id, blocker_id, created_at FROM ticket_dependencies ORDER BY created_at, ticket_id, blocker_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(dependencies)
+    }
+
+    /// Note that the current command used `ids`, for the current user's `ltm recent`. Tickets
+    /// the command deleted are skipped.
+    pub async fn record_recent(&self, ids: &[i64]) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+        for &id in ids {
+            sqlx::query(
+                r#"
+                INSERT INTO recent_tickets (ticket_id, used_by, used_at, command)
+                SELECT ?, ?, ?, ? WHERE EXISTS (SELECT 1 FROM tickets WHERE id = ?)
+                ON CONFLICT (ticket_id, used_by) DO UPDATE SET used_at = excluded.used_at, command = excluded.command
+                "#,
+            )
+            .bind(id)
+            .bind(self.user.as_deref().unwrap_or_default())
+            .bind(now)
+            .bind(self.command.as_deref())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The `limit` tickets the current user used last, newest first
+    pub async fn recent_tickets(&self, limit: i64) -> Result<Vec<RecentTicket>> {
+        let recent = sqlx::query_as::<_, RecentTicket>(
+            r#"
+            SELECT r.ticket_id, t.project, t.name, t.status, r.used_at, r.command
+            FROM recent_tickets r JOIN tickets t ON t.id = r.ticket_id
+            WHERE r.used_by = ? ORDER BY r.used_at DESC, r.ticket_id DESC LIMIT ?
+            "#,
+        )
+        .bind(self.user.as_deref().unwrap_or_default())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(recent)
+    }
+
+    /// Add a ticket to the current user's starred tickets; returns false when it already was
+    pub async fn star_ticket(&self, ticket_id: i64) -> Result<bool> {
+        let result = sqlx::query("INSERT OR IGNORE INTO ticket_stars (ticket_id, starred_by, starred_at) VALUES (?, ?, ?)")
+            .bind(ticket_id)
+            .bind(self.user.as_deref().unwrap_or_default())
+            .bind(Utc::now().naive_utc())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a ticket off the current user's starred tickets; returns false when it was not on them
+    pub async fn unstar_ticket(&self, ticket_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM ticket_stars WHERE ticket_id = ? AND starred_by = ?")
+            .bind(ticket_id)
+            .bind(self.user.as_deref().unwrap_or_default())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The current user's starred tickets from every project, in the order they were starred
+    pub async fn starred_tickets(&self) -> Result<Vec<Ticket>> {
+        let sql = format!(
+            "SELECT {} FROM tickets JOIN ticket_stars ON ticket_stars.ticket_id = tickets.id \
+             WHERE ticket_stars.starred_by = ? ORDER BY ticket_stars.starred_at, tickets.id",
+            TICKET_COLUMNS.split(", ").map(|column| format!("tickets.{}", column)).collect::<Vec<_>>().join(", ")
+        );
+        let tickets = sqlx::query_as::<_, Ticket>(&sql)
+            .bind(self.user.as_deref().unwrap_or_default())
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tickets)
+    }
+
+    /// Make a ticket read-only; returns false when it already was
+    pub async fn lock_ticket(&self, ticket_id: i64) -> Result<bool> {
+        let result = sqlx::query("INSERT OR IGNORE INTO ticket_locks (ticket_id, locked_at, locked_by) VALUES (?, ?, ?)")
+            .bind(ticket_id)
+            .bind(Utc::now().naive_utc())
+            .bind(self.user.as_deref())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Let a locked ticket change again; returns false when it was not locked
+    pub async fn unlock_ticket(&self, ticket_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM ticket_locks WHERE ticket_id = ?").bind(ticket_id).execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The lock on a ticket, if it has one
+    pub async fn ticket_lock(&self, ticket_id: i64) -> Result<Option<TicketLock>> {
+        let lock = sqlx::query_as::<_, TicketLock>("SELECT ticket_id, locked_at, locked_by FROM ticket_locks WHERE ticket_id = ?")
+            .bind(ticket_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(lock)
+    }
+
+    /// Which of `ids` are locked, in ID order
+    pub async fn locked_tickets(&self, ids: &[i64]) -> Result<Vec<i64>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let sql = format!("SELECT ticket_id FROM ticket_locks WHERE ticket_id IN ({}) ORDER BY ticket_id", placeholders);
+        let mut query = sqlx::query_scalar(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// The month, as YYYY-MM, when time logged on `day` falls in a locked one
+    pub async fn locked_month(&self, day: NaiveDate) -> Result<Option<String>> {
+        let month = sqlx::query_scalar("SELECT month FROM billing_locks WHERE month = ?")
+            .bind(day.format("%Y-%m").to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(month)
+    }
+}
+
+/// A snapshot without `updated_at`, so touching a row without changing it is not an update
+fn without_timestamp(value: &serde_json::Value) -> serde_json::Value {
+    let mut value = value.clone();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("updated_at");
+    }
+    value
+}
+
+/// Copy every row of each table in the attached `old` database into the same table here,
+/// in one transaction, first emptying the tables when `replace` is set. Returns the number
+/// of tickets copied.
+async fn copy_tables(conn: &mut SqliteConnection, replace: bool) -> Result<i64> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations' ORDER BY rowid",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut tx = conn.begin().await?;
+    sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await?;
+    // The journal refuses deletes, so its triggers are dropped and re-created around the swap
+    let triggers: Vec<(String, String)> = if replace {
+        sqlx::query_as("SELECT name, sql FROM main.sqlite_master WHERE type = 'trigger'")
+            .fetch_all(&mut *tx)
+            .await?
+    } else {
+        Vec::new()
+    };
+    for (name, _) in &triggers {
+        sqlx::query(&format!("DROP TRIGGER main.{}", name)).execute(&mut *tx).await?;
+    }
+    if replace {
+        for table in tables.iter().rev() {
+            sqlx::query(&format!("DELETE FROM main.{}", table)).execute(&mut *tx).await?;
+        }
+    }
+    for table in &tables {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(&mut *tx)
+            .await?;
+        let columns = columns.join(", ");
+        sqlx::query(&format!("INSERT INTO main.{0} ({1}) SELECT {1} FROM old.{0}", table, columns))
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Could not copy {}", table))?;
+    }
+    for (_, sql) in &triggers {
+        sqlx::query(sql).execute(&mut *tx).await?;
+    }
+    let tickets: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM old.tickets").fetch_one(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(tickets)
+}
+
+/// A row as JSON, or `None` if it does not exist
+async fn snapshot(conn: &mut SqliteConnection, entity: Entity, id: i64) -> Result<Option<serde_json::Value>> {
+    fn to_json<T: serde::Serialize>(row: Option<T>) -> Result<Option<serde_json::Value>> {
+        Ok(row.map(serde_json::to_value).transpose()?)
+    }
+    match entity {
+        Entity::Ticket => to_json(
+            sqlx::query_as::<_, Ticket>(&format!("SELECT {} FROM tickets WHERE id = ?", TICKET_COLUMNS))
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?,
+        ),
+        Entity::Comment => to_json(
+            sqlx::query_as::<_, Comment>(&format!("SELECT {} FROM comments WHERE id = ?", COMMENT_COLUMNS))
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?,
+        ),
+        Entity::TimeLog => to_json(
+            sqlx::query_as::<_, crate::models::TimeLog>(
+                &format!("SELECT {} FROM time_logs WHERE id = ?", TIME_LOG_COLUMNS),
+            )
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?,
+        ),
+        Entity::TicketUrl => to_json(
+            sqlx::query_as::<_, TicketUrl>("SELECT id, ticket_id, url, label, created_at FROM ticket_urls WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?,
+        ),
+        Entity::ChecklistItem => to_json(
+            sqlx::query_as::<_, ChecklistItem>(
+                "SELECT id, ticket_id, position, text, done, created_at FROM checklist_items WHERE id = ?",
+            )
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?,
+        ),
+        Entity::Timer => to_json(
+            sqlx::query_as::<_, ActiveTimer>(
+                "SELECT ticket_id, started_at, segment_started_at, paused_at, elapsed_seconds, note FROM active_timers WHERE ticket_id = ?",
+            )
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?,
+        ),
+        Entity::Tags => {
+            let tags: Vec<String> = sqlx::query_scalar("SELECT tag FROM ticket_tags WHERE ticket_id = ? ORDER BY tag ASC")
+                .bind(id)
+                .fetch_all(&mut *conn)
+                .await?;
+            Ok((!tags.is_empty()).then(|| serde_json::json!({ "ticket_id": id, "tags": tags })))
+        }
+    }
+}
+
+async fn record_status_change(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    ticket_id: i64,
+    from: Option<&str>,
+    to: &str,
+    at: NaiveDateTime,
+) -> Result<()> {
+    sqlx::query("INSERT INTO status_changes (ticket_id, from_status, to_status, changed_at) VALUES (?, ?, ?, ?)")
+        .bind(ticket_id)
+        .bind(from)
+        .bind(to)
+        .bind(at)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
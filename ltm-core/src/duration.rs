@@ -0,0 +1,65 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Compact duration such as `3d 4h`, `2h 05m`, `45m`, or `<1m`, used wherever ltm shows
+/// time spent: time logs, summaries, and timers
+pub fn format_duration(span: Duration) -> String {
+    if span <= Duration::zero() {
+        return "0m".to_string();
+    }
+    let minutes = span.num_minutes();
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        "<1m".to_string()
+    }
+}
+
+/// `format_duration` for a number of minutes, such as a time log's length
+pub fn format_minutes(minutes: i64) -> String {
+    format_duration(Duration::minutes(minutes))
+}
+
+/// A duration in JSON output: exact seconds for scripts, and the text ltm shows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HumanDuration {
+    pub seconds: i64,
+    pub human: String,
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(span: Duration) -> Self {
+        Self { seconds: span.num_seconds(), human: format_duration(span) }
+    }
+}
+
+impl HumanDuration {
+    pub fn from_minutes(minutes: i64) -> Self {
+        Duration::minutes(minutes).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::zero()), "0m");
+        assert_eq!(format_duration(Duration::seconds(30)), "<1m");
+        assert_eq!(format_duration(Duration::minutes(45)), "45m");
+        assert_eq!(format_duration(Duration::minutes(125)), "2h 05m");
+        assert_eq!(format_duration(Duration::hours(3)), "3h 00m");
+        assert_eq!(format_duration(Duration::hours(76)), "3d 4h");
+        assert_eq!(format_minutes(90), "1h 30m");
+        assert_eq!(
+            HumanDuration::from_minutes(125),
+            HumanDuration { seconds: 7500, human: "2h 05m".to_string() }
+        );
+    }
+}
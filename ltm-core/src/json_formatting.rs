@@ -0,0 +1,677 @@
+use crate::duration::HumanDuration;
+use crate::models::{ChecklistItem, Comment, Event, ProjectSummary, Ticket, TicketHours, TicketUrl, TimeLog};
+use crate::validation::ValidationError;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// JSON response structure for ticket list command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketListResponse {
+    pub version: String,
+    pub tickets: Vec<Ticket>,
+    pub summary: TicketListSummary,
+    pub project_filter: Option<String>,
+}
+
+/// Summary information for ticket list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketListSummary {
+    pub total_tickets: usize,
+    pub open_tickets: usize,
+    pub closed_tickets: usize,
+}
+
+/// JSON response structure for ticket details command  
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketDetailsResponse {
+    pub version: String,
+    pub ticket: Ticket,
+    pub comments: Vec<Comment>,
+    pub time_logs: Vec<TimeLog>,
+    #[serde(default)]
+    pub urls: Vec<TicketUrl>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// JSON response structure for project summary command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectSummaryResponse {
+    pub version: String,
+    pub project: String,
+    pub summary: ProjectSummary,
+    /// `summary.total_time_hours` as a duration
+    #[serde(default)]
+    pub total_time: HumanDuration,
+}
+
+/// JSON response structure for the time report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeReportResponse {
+    pub version: String,
+    pub project: Option<String>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub tickets: Vec<TicketHours>,
+    pub total_entries: i64,
+    pub total_minutes: i64,
+    pub total_hours: f64,
+    #[serde(default)]
+    pub total_duration: HumanDuration,
+    #[serde(default)]
+    pub total_billable_minutes: i64,
+    /// Sum of the tickets' amounts; `None` when no ticket has a rate or they bill in
+    /// different currencies
+    #[serde(default)]
+    pub total_amount: Option<f64>,
+    /// Sum of the amounts in each currency
+    #[serde(default)]
+    pub totals: Vec<CurrencyTotal>,
+}
+
+/// What the tickets billing in one currency add up to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyTotal {
+    pub currency: Option<String>,
+    pub amount: f64,
+}
+
+/// JSON response structure for the event journal
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventListResponse {
+    pub version: String,
+    pub ticket_id: Option<i64>,
+    pub events: Vec<Event>,
+}
+
+/// A project and everything attached to its tickets, written before the project is deleted
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectExport {
+    pub version: String,
+    pub project: String,
+    pub exported_at: NaiveDateTime,
+    pub tickets: Vec<ExportedTicket>,
+}
+
+/// One ticket in a project export
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedTicket {
+    pub ticket: Ticket,
+    pub comments: Vec<Comment>,
+    pub time_logs: Vec<TimeLog>,
+    pub urls: Vec<TicketUrl>,
+    pub checklist: Vec<ChecklistItem>,
+    pub tags: Vec<String>,
+}
+
+/// Tickets, comments, and time logs created or changed after `since`, for `ltm export`.
+/// Feeding `exported_at` back as the next `--since` picks up where this export ended.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangesExport {
+    pub version: String,
+    pub exported_at: DateTime<Utc>,
+    /// `None` for a full export
+    pub since: Option<DateTime<Utc>>,
+    pub project: Option<String>,
+    pub tickets: Vec<Ticket>,
+    pub comments: Vec<Comment>,
+    pub time_logs: Vec<TimeLog>,
+}
+
+/// JSON error response structure
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub version: String,
+    pub error: bool,
+    pub message: String,
+    pub code: String,
+    pub details: serde_json::Value,
+}
+
+/// Build the ticket list response shared by `--json` output and the HTTP API
+pub fn ticket_list_response(tickets: &[Ticket], project_filter: Option<&str>) -> TicketListResponse {
+    let total = tickets.len();
+    let closed = tickets.iter().filter(|t| {
+        matches!(t.status.to_lowercase().as_str(), "closed" | "completed" | "done")
+    }).count();
+    let open = total - closed;
+    
+    TicketListResponse {
+        version: "1.0".to_string(),
+        tickets: tickets.to_vec(),
+        summary: TicketListSummary {
+            total_tickets: total,
+            open_tickets: open,
+            closed_tickets: closed,
+        },
+        project_filter: project_filter.map(|s| s.to_string()),
+    }
+}
+
+/// Format ticket list as JSON
+pub fn format_ticket_list_json(tickets: &[Ticket], project_filter: Option<&str>) -> String {
+    let response = ticket_list_response(tickets, project_filter);
+    serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format ticket list as pretty JSON
+pub fn format_ticket_list_json_pretty(tickets: &[Ticket], project_filter: Option<&str>) -> String {
+    let minified = format_ticket_list_json(tickets, project_filter);
+    let v: serde_json::Value = serde_json::from_str(&minified).unwrap_or(serde_json::json!({}));
+    serde_json::to_string_pretty(&v).unwrap_or(minified)
+}
+
+/// Format ticket details as JSON
+pub fn ticket_details_response(ticket: &Ticket, comments: &[Comment], time_logs: &[TimeLog], urls: &[TicketUrl], tags: &[String]) -> TicketDetailsResponse {
+    TicketDetailsResponse {
+        version: "1.0".to_string(),
+        ticket: ticket.clone(),
+        comments: comments.to_vec(),
+        time_logs: time_logs.to_vec(),
+        urls: urls.to_vec(),
+        tags: tags.to_vec(),
+    }
+}
+
+pub fn format_ticket_details_json(ticket: &Ticket, comments: &[Comment], time_logs: &[TimeLog], urls: &[TicketUrl], tags: &[String]) -> String {
+    let response = ticket_details_response(ticket, comments, time_logs, urls, tags);
+    serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format ticket details as pretty JSON
+pub fn format_ticket_details_json_pretty(ticket: &Ticket, comments: &[Comment], time_logs: &[TimeLog], urls: &[TicketUrl], tags: &[String]) -> String {
+    let minified = format_ticket_details_json(ticket, comments, time_logs, urls, tags);
+    let v: serde_json::Value = serde_json::from_str(&minified).unwrap_or(serde_json::json!({}));
+    serde_json::to_string_pretty(&v).unwrap_or(minified)
+}
+
+/// Format a project export as pretty JSON
+pub fn format_project_export_json(project: &str, exported_at: NaiveDateTime, tickets: Vec<ExportedTicket>) -> String {
+    let export = ProjectExport { version: "1.0".to_string(), project: project.to_string(), exported_at, tickets };
+    serde_json::to_string_pretty(&export).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format an export of changes as pretty JSON
+pub fn format_changes_export_json(export: &ChangesExport) -> String {
+    serde_json::to_string_pretty(export).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format project summary as JSON
+pub fn project_summary_response(project: &str, summary: &ProjectSummary) -> ProjectSummaryResponse {
+    ProjectSummaryResponse {
+        version: "1.0".to_string(),
+        project: project.to_string(),
+        summary: summary.clone(),
+        total_time: HumanDuration::from_minutes((summary.total_time_hours * 60.0).round() as i64),
+    }
+}
+
+pub fn format_project_summary_json(project: &str, summary: &ProjectSummary) -> String {
+    let response = project_summary_response(project, summary);
+    serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format project summary as pretty JSON
+pub fn format_project_summary_json_pretty(project: &str, summary: &ProjectSummary) -> String {
+    let minified = format_project_summary_json(project, summary);
+    let v: serde_json::Value = serde_json::from_str(&minified).unwrap_or(serde_json::json!({}));
+    serde_json::to_string_pretty(&v).unwrap_or(minified)
+}
+
+/// Format journal entries as JSON; `before` and `after` stay JSON-encoded strings
+pub fn format_events_json(events: &[Event], ticket_id: Option<i64>) -> String {
+    let response = EventListResponse {
+        version: "1.0".to_string(),
+        ticket_id,
+        events: events.to_vec(),
+    };
+
+    serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// The time report with its totals
+pub fn time_report_response(rows: &[TicketHours], project: Option<&str>, from: NaiveDate, to: NaiveDate) -> TimeReportResponse {
+    let total_minutes: i64 = rows.iter().map(|r| r.minutes).sum();
+    TimeReportResponse {
+        version: "1.0".to_string(),
+        project: project.map(|s| s.to_string()),
+        from,
+        to,
+        tickets: rows.to_vec(),
+        total_entries: rows.iter().map(|r| r.entries).sum(),
+        total_minutes,
+        total_hours: (total_minutes as f64 / 60.0 * 100.0).round() / 100.0,
+        total_duration: HumanDuration::from_minutes(total_minutes),
+        total_billable_minutes: rows.iter().map(|r| r.billable_minutes).sum(),
+        total_amount: total_amount(rows),
+        totals: currency_totals(rows),
+    }
+}
+
+/// What the tickets of a time report bill per currency, in order of the codes
+pub fn currency_totals(rows: &[TicketHours]) -> Vec<CurrencyTotal> {
+    let mut totals: BTreeMap<Option<String>, f64> = BTreeMap::new();
+    for row in rows {
+        if let Some(amount) = row.amount {
+            *totals.entry(row.currency.clone()).or_default() += amount;
+        }
+    }
+    totals
+        .into_iter()
+        .map(|(currency, amount)| CurrencyTotal { currency, amount: (amount * 100.0).round() / 100.0 })
+        .collect()
+}
+
+/// What the tickets of a time report bill in total; `None` when none has a rate or they
+/// bill in different currencies
+pub fn total_amount(rows: &[TicketHours]) -> Option<f64> {
+    match currency_totals(rows).as_slice() {
+        [total] => Some(total.amount),
+        _ => None,
+    }
+}
+
+/// Format the time report as JSON
+pub fn format_time_report_json(rows: &[TicketHours], project: Option<&str>, from: NaiveDate, to: NaiveDate) -> String {
+    let response = time_report_response(rows, project, from, to);
+    serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format the time report as pretty JSON
+pub fn format_time_report_json_pretty(rows: &[TicketHours], project: Option<&str>, from: NaiveDate, to: NaiveDate) -> String {
+    let minified = format_time_report_json(rows, project, from, to);
+    let v: serde_json::Value = serde_json::from_str(&minified).unwrap_or(serde_json::json!({}));
+    serde_json::to_string_pretty(&v).unwrap_or(minified)
+}
+
+/// Format validation error as JSON
+pub fn error_response(error: &ValidationError) -> ErrorResponse {
+    let (code, message, details) = match error {
+        ValidationError::InvalidTicketId(id) => (
+            "INVALID_TICKET_ID".to_string(),
+            format!("Invalid ticket ID: {}", id),
+            serde_json::json!({"provided_id": id})
+        ),
+        ValidationError::TicketNotFound(id) => (
+            "TICKET_NOT_FOUND".to_string(),
+            format!("Ticket not found: {}", id),
+            serde_json::json!({"ticket_id": id})
+        ),
+        ValidationError::InvalidProjectName(name) => (
+            "INVALID_PROJECT_NAME".to_string(),
+            format!("Invalid project name: {}", name),
+            serde_json::json!({"provided_name": name})
+        ),
+        ValidationError::InvalidStatus(status) => (
+            "INVALID_STATUS".to_string(),
+            format!("Invalid status: {}", status),
+            serde_json::json!({"provided_status": status})
+        ),
+        ValidationError::InvalidContentLength { field_type, min, max } => (
+            "INVALID_CONTENT_LENGTH".to_string(),
+            format!("Invalid {} length. Must be between {} and {} characters.", field_type, min, max),
+            serde_json::json!({
+                "field_type": field_type,
+                "min_length": min,
+                "max_length": max
+            })
+        ),
+        ValidationError::InvalidTime(msg) => (
+            "INVALID_TIME".to_string(),
+            format!("Invalid time: {}", msg),
+            serde_json::json!({"message": msg})
+        ),
+        ValidationError::InvalidPriority(priority) => (
+            "INVALID_PRIORITY".to_string(),
+            format!("Invalid priority: {}", priority),
+            serde_json::json!({"provided_priority": priority})
+        ),
+        ValidationError::InvalidSeverity(severity) => (
+            "INVALID_SEVERITY".to_string(),
+            format!("Invalid severity: {}", severity),
+            serde_json::json!({"provided_severity": severity})
+        ),
+        ValidationError::InvalidScore { field, value } => (
+            "INVALID_SCORE".to_string(),
+            format!("Invalid {}: {}", field, value),
+            serde_json::json!({"field": field, "provided_value": value})
+        ),
+        ValidationError::InvalidComponent(component) => (
+            "INVALID_COMPONENT".to_string(),
+            format!("Invalid component: {}", component),
+            serde_json::json!({"provided_component": component})
+        ),
+        ValidationError::UnknownComponent { component, project, allowed } => (
+            "UNKNOWN_COMPONENT".to_string(),
+            format!("Project {} has no component {}", project, component),
+            serde_json::json!({"provided_component": component, "project": project, "allowed": allowed})
+        ),
+        ValidationError::InvalidRelease(release) => (
+            "INVALID_RELEASE".to_string(),
+            format!("Invalid release: {}", release),
+            serde_json::json!({"provided_release": release})
+        ),
+        ValidationError::UnknownRelease { release, project, allowed } => (
+            "UNKNOWN_RELEASE".to_string(),
+            format!("Project {} has no release {}", project, release),
+            serde_json::json!({"provided_release": release, "project": project, "allowed": allowed})
+        ),
+        ValidationError::InvalidTag(tag) => (
+            "INVALID_TAG".to_string(),
+            format!("Invalid tag: {}", tag),
+            serde_json::json!({"provided_tag": tag})
+        ),
+        ValidationError::InvalidDate(date) => (
+            "INVALID_DATE".to_string(),
+            format!("Invalid date: {}", date),
+            serde_json::json!({"provided_date": date})
+        ),
+        ValidationError::InvalidPeriod(period) => (
+            "INVALID_PERIOD".to_string(),
+            format!("Invalid period: {}", period),
+            serde_json::json!({"provided_period": period})
+        ),
+        ValidationError::InvalidMonth(month) => (
+            "INVALID_MONTH".to_string(),
+            format!("Invalid month: {}", month),
+            serde_json::json!({"provided_month": month})
+        ),
+        ValidationError::InvalidRate(rate) => (
+            "INVALID_RATE".to_string(),
+            format!("Invalid rate: {}", rate),
+            serde_json::json!({"provided_rate": rate})
+        ),
+        ValidationError::InvalidUrl(url) => (
+            "INVALID_URL".to_string(),
+            format!("Invalid URL: {}", url),
+            serde_json::json!({"provided_url": url})
+        ),
+        ValidationError::InvalidDateRange { from, to } => (
+            "INVALID_DATE_RANGE".to_string(),
+            format!("Invalid date range: {} is after {}", from, to),
+            serde_json::json!({"from": from, "to": to})
+        ),
+        ValidationError::InvalidTimeSpan { start, end } => (
+            "INVALID_TIME_SPAN".to_string(),
+            format!("Invalid time span: {} is not before {}", start, end),
+            serde_json::json!({"start": start, "end": end})
+        ),
+        ValidationError::InvalidRegex { pattern, reason } => (
+            "INVALID_REGEX".to_string(),
+            format!("Invalid regular expression: {}", reason),
+            serde_json::json!({"pattern": pattern, "reason": reason})
+        ),
+        ValidationError::NoFocusedTicket => (
+            "NO_FOCUSED_TICKET".to_string(),
+            "No ticket ID given and no ticket is focused".to_string(),
+            serde_json::json!({})
+        ),
+        ValidationError::NoLastTicket => (
+            "NO_LAST_TICKET".to_string(),
+            "No ticket has been used yet for @last to refer to".to_string(),
+            serde_json::json!({})
+        ),
+        ValidationError::TransitionNotAllowed { from, to, allowed } => (
+            "TRANSITION_NOT_ALLOWED".to_string(),
+            format!("Cannot change status from {} to {}", from, to),
+            serde_json::json!({"from": from, "to": to, "allowed": allowed})
+        ),
+        ValidationError::StatusNotInProject { status, project, allowed } => (
+            "STATUS_NOT_IN_PROJECT".to_string(),
+            format!("Status {} is not used in project {}", status, project),
+            serde_json::json!({"provided_status": status, "project": project, "allowed": allowed})
+        ),
+        ValidationError::UnknownSetting(key) => (
+            "UNKNOWN_SETTING".to_string(),
+            format!("Unknown project setting: {}", key),
+            serde_json::json!({"provided_key": key})
+        ),
+        ValidationError::InvalidSetting { key, value } => (
+            "INVALID_SETTING".to_string(),
+            format!("Invalid value for project setting {}: {}", key, value),
+            serde_json::json!({"key": key, "provided_value": value})
+        ),
+        ValidationError::MissingRequiredField { field, project } => (
+            "MISSING_REQUIRED_FIELD".to_string(),
+            format!("Project {} requires a {} on new tickets", project, field),
+            serde_json::json!({"field": field, "project": project})
+        ),
+        ValidationError::MissingStatusField { field, project, status } => (
+            "MISSING_STATUS_FIELD".to_string(),
+            format!("Project {} requires a {} before a ticket moves to {}", project, field, status),
+            serde_json::json!({"field": field, "project": project, "status": status})
+        ),
+        ValidationError::WipLimitReached { status, project, limit } => (
+            "WIP_LIMIT_REACHED".to_string(),
+            format!("Project {} allows at most {} tickets in {}", project, limit, status),
+            serde_json::json!({"status": status, "project": project, "limit": limit})
+        ),
+        ValidationError::InvalidKind { kind, allowed } => (
+            "INVALID_KIND".to_string(),
+            format!("Invalid ticket type: {}", kind),
+            serde_json::json!({"provided_kind": kind, "allowed": allowed})
+        ),
+    };
+    
+    ErrorResponse {
+        version: "1.0".to_string(),
+        error: true,
+        message,
+        code,
+        details,
+    }
+}
+
+pub fn format_error_json(error: &ValidationError) -> String {
+    let response = error_response(error);
+    serde_json::to_string(&response).unwrap_or_else(|_| r#"{"error": true, "message": "Serialization failed"}"#.to_string())
+}
+
+/// Pretty error JSON
+pub fn format_error_json_pretty(error: &ValidationError) -> String {
+    let minified = format_error_json(error);
+    let v: serde_json::Value = serde_json::from_str(&minified).unwrap_or(serde_json::json!({}));
+    serde_json::to_string_pretty(&v).unwrap_or(minified)
+}
+
+/// Convert NaiveDateTime to ISO 8601 string (utility function for tests)
+#[allow(dead_code)]
+fn format_timestamp_iso(dt: &NaiveDateTime) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    
+    fn create_test_ticket() -> Ticket {
+        let timestamp = DateTime::from_timestamp(1642694400, 0).unwrap().naive_utc();
+        Ticket {
+            id: 1,
+            project: "test_project".to_string(),
+            name: "Test ticket".to_string(),
+            description: "A test description".to_string(),
+            status: "open".to_string(),
+            created_at: timestamp,
+            updated_at: timestamp,
+            due_date: None,
+            rank: 1,
+            priority: None,
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        }
+    }
+    
+    fn create_test_comment() -> Comment {
+        let timestamp = DateTime::from_timestamp(1642694400, 0).unwrap().naive_utc();
+        Comment {
+            id: 1,
+            ticket_id: 1,
+            content: "Test comment".to_string(),
+            created_at: timestamp,
+            updated_at: timestamp,
+            parent_comment_id: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+        }
+    }
+    
+    fn create_test_time_log() -> TimeLog {
+        let timestamp = DateTime::from_timestamp(1642694400, 0).unwrap().naive_utc();
+        TimeLog {
+            id: 1,
+            ticket_id: 1,
+            hours: 2,
+            minutes: 30,
+            started_at: Some(timestamp),
+            ended_at: Some(timestamp),
+            created_at: timestamp,
+            updated_at: timestamp,
+            note: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            billable: true,
+        }
+    }
+    
+    #[test]
+    fn test_ticket_list_json_formatting() {
+        let tickets = vec![create_test_ticket()];
+        let output = format_ticket_list_json(&tickets, Some("test_project"));
+        
+        // Parse JSON to verify structure
+        let parsed: TicketListResponse = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed.tickets.len(), 1);
+        assert_eq!(parsed.tickets[0].id, 1);
+        assert_eq!(parsed.summary.total_tickets, 1);
+        assert_eq!(parsed.summary.open_tickets, 1);
+        assert_eq!(parsed.summary.closed_tickets, 0);
+        assert_eq!(parsed.project_filter, Some("test_project".to_string()));
+    }
+    
+    #[test]
+    fn test_ticket_list_json_empty() {
+        let tickets = vec![];
+        let output = format_ticket_list_json(&tickets, None);
+        
+        let parsed: TicketListResponse = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed.tickets.len(), 0);
+        assert_eq!(parsed.summary.total_tickets, 0);
+        assert_eq!(parsed.project_filter, None);
+    }
+    
+    #[test]
+    fn test_ticket_details_json_formatting() {
+        let ticket = create_test_ticket();
+        let comments = vec![create_test_comment()];
+        let time_logs = vec![create_test_time_log()];
+        
+        let urls = vec![TicketUrl {
+            id: 1,
+            ticket_id: 1,
+            url: "https://example.com/pr/7".to_string(),
+            label: Some("PR".to_string()),
+            created_at: ticket.created_at,
+        }];
+        
+        let output = format_ticket_details_json(&ticket, &comments, &time_logs, &urls, &["auth".to_string()]);
+        
+        let parsed: TicketDetailsResponse = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed.ticket.id, 1);
+        assert_eq!(parsed.comments.len(), 1);
+        assert_eq!(parsed.time_logs.len(), 1);
+        assert_eq!(parsed.urls[0].url, "https://example.com/pr/7");
+        assert_eq!(parsed.tags, vec!["auth".to_string()]);
+    }
+    
+    #[test]
+    fn test_project_export_json() {
+        let ticket = create_test_ticket();
+        let exported = vec![ExportedTicket {
+            ticket: ticket.clone(),
+            comments: vec![create_test_comment()],
+            time_logs: vec![create_test_time_log()],
+            urls: Vec::new(),
+            checklist: Vec::new(),
+            tags: vec!["auth".to_string()],
+        }];
+
+        let output = format_project_export_json("test-project", ticket.updated_at, exported);
+
+        let parsed: ProjectExport = serde_json::from_str(&output).unwrap();
+        assert_eq!((parsed.version.as_str(), parsed.project.as_str()), ("1.0", "test-project"));
+        assert_eq!(parsed.tickets[0].ticket.id, ticket.id);
+        assert_eq!((parsed.tickets[0].comments.len(), parsed.tickets[0].time_logs.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_project_summary_json_formatting() {
+        let summary = ProjectSummary {
+            project: "test_project".to_string(),
+            total_tickets: 10,
+            open_tickets: 3,
+            closed_tickets: 7,
+            total_time_hours: 25.5,
+            kinds: Default::default(),
+            components: Default::default(),
+        };
+        
+        let output = format_project_summary_json("test_project", &summary);
+        
+        let parsed: ProjectSummaryResponse = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed.project, "test_project");
+        assert_eq!(parsed.summary.total_tickets, 10);
+        assert_eq!(parsed.summary.open_tickets, 3);
+        assert_eq!(parsed.summary.closed_tickets, 7);
+        assert_eq!(parsed.summary.total_time_hours, 25.5);
+    }
+    
+    #[test]
+    fn test_error_json_formatting() {
+        let error = ValidationError::TicketNotFound(123);
+        let output = format_error_json(&error);
+        
+        let parsed: ErrorResponse = serde_json::from_str(&output).unwrap();
+        assert!(parsed.error);
+        assert_eq!(parsed.code, "TICKET_NOT_FOUND");
+        assert!(parsed.message.contains("123"));
+    }
+    
+    #[test]
+    fn test_closed_ticket_counting() {
+        let mut tickets = vec![
+            create_test_ticket(),
+            create_test_ticket(),
+            create_test_ticket(),
+        ];
+        
+        // Set different statuses
+        tickets[0].status = "open".to_string();
+        tickets[1].status = "closed".to_string();
+        tickets[2].status = "completed".to_string();
+        
+        let output = format_ticket_list_json(&tickets, None);
+        let parsed: TicketListResponse = serde_json::from_str(&output).unwrap();
+        
+        assert_eq!(parsed.summary.total_tickets, 3);
+        assert_eq!(parsed.summary.open_tickets, 1);  // Only "open"
+        assert_eq!(parsed.summary.closed_tickets, 2); // "closed" and "completed"
+    }
+}
\ No newline at end of file
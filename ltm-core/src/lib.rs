@@ -0,0 +1,40 @@
+//! The ticket store behind `ltm`, for Rust tools that want to read or change tickets
+//! without going through the CLI.
+//!
+//! Nothing here prints or prompts: functions return data, `anyhow` errors for database
+//! and I/O failures, and [`validation::ValidationError`] for bad input, leaving messages
+//! and formatting to the caller.
+//!
+//! - [`db`]: the SQLite database, with migrations applied on open
+//! - [`models`]: tickets, comments, time logs, and the other rows the database returns
+//! - [`validation`]: parsing and checking user input such as IDs, dates, and statuses
+//! - [`json_formatting`]: the JSON shapes `ltm --json` and exports produce
+//! - [`project_settings`]: per-project statuses, defaults, and required fields
+//! - [`backup`]: copies of the database taken before risky changes
+//! - [`duration`]: the `2h 05m` style ltm uses to show time spent
+//!
+//! ```no_run
+//! use ltm_core::backup::BackupConfig;
+//! use ltm_core::db::Database;
+//! use ltm_core::validation::validate_project_name;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! // Opens ~/.ltm/tickets.db, the database the CLI uses
+//! let db = Database::new(&BackupConfig::default()).await?;
+//! let project = validate_project_name("webapp")?;
+//! let id = db.add_ticket(&project, "Fix login", "Users are logged out on refresh").await?;
+//! db.add_comment(id, "Seen on Safari only").await?;
+//! for ticket in db.list_tickets(Some(&project)).await? {
+//!     println!("#{} {} [{}]", ticket.id, ticket.name, ticket.status);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod backup;
+pub mod db;
+pub mod duration;
+pub mod json_formatting;
+pub mod models;
+pub mod project_settings;
+pub mod validation;
@@ -0,0 +1,755 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::validation::{content_length, is_finished_status};
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, Default)]
+pub struct Ticket {
+    pub id: i64,
+    pub project: String,
+    pub name: String,
+    pub description: String,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub due_date: Option<NaiveDate>,
+    /// Position in the project's backlog (1 = top)
+    #[serde(default)]
+    pub rank: i64,
+    /// low, medium, high, or critical
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Estimated effort in minutes
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    /// When the ticket was archived; archived tickets are left out of lists
+    #[serde(default)]
+    pub archived_at: Option<NaiveDateTime>,
+    /// Stays the same across machines, unlike `id`
+    #[serde(default)]
+    pub uuid: String,
+    /// Identity from `[user]` in config.toml of whoever created the row
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Identity of whoever changed the row last
+    #[serde(default)]
+    pub updated_by: Option<String>,
+    /// Hourly rate, overriding the project's `rate` setting
+    #[serde(default)]
+    pub rate: Option<f64>,
+    /// Type of ticket, like "bug" or "feature"
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Impact from S1 (most severe) to S4, independent of priority
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Area of the project, one of its components
+    #[serde(default)]
+    pub component: Option<String>,
+    /// Release the ticket is planned for, one of its project's releases
+    #[serde(default)]
+    pub target_release: Option<String>,
+    /// People or events the ticket affects per quarter, for RICE scoring
+    #[serde(default)]
+    pub reach: Option<i64>,
+    /// From 0.25 (minimal) to 3 (massive)
+    #[serde(default)]
+    pub impact: Option<f64>,
+    /// How sure the reach and impact are, in percent
+    #[serde(default)]
+    pub confidence: Option<i64>,
+    /// Person-weeks of work
+    #[serde(default)]
+    pub effort: Option<f64>,
+    /// When the ticket was pinned; pinned tickets are listed first
+    #[serde(default)]
+    pub pinned_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Comment {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+    /// When the comment was written or last edited
+    #[serde(default)]
+    pub updated_at: NaiveDateTime,
+    /// The comment this one replies to, `None` for top-level comments
+    #[serde(default)]
+    pub parent_comment_id: Option<i64>,
+    /// Stays the same across machines, unlike `id`
+    #[serde(default)]
+    pub uuid: String,
+    /// Identity from `[user]` in config.toml of whoever created the row
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Identity of whoever changed the row last
+    #[serde(default)]
+    pub updated_by: Option<String>,
+}
+
+impl Comment {
+    /// The first non-empty line, shortened with an ellipsis to at most `max_len` characters, e.g.
+    /// to name a ticket promoted from the comment
+    pub fn title(&self, max_len: usize) -> String {
+        let line = self.content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+        if content_length(line) <= max_len {
+            return line.to_string();
+        }
+        let title: String = line.graphemes(true).take(max_len.saturating_sub(1)).collect();
+        format!("{}…", title.trim_end())
+    }
+}
+
+/// Order comments as threads, pairing each with its reply depth (0 = top level).
+///
+/// Top-level comments keep their given order; replies follow their parent, oldest first.
+/// Replies whose parent is not in `comments` are shown as top-level comments.
+pub fn thread_comments(comments: &[Comment]) -> Vec<(&Comment, usize)> {
+    fn push_replies<'a>(comments: &'a [Comment], parent: &'a Comment, depth: usize, out: &mut Vec<(&'a Comment, usize)>) {
+        out.push((parent, depth));
+        let mut replies: Vec<&Comment> = comments.iter().filter(|c| c.parent_comment_id == Some(parent.id)).collect();
+        replies.sort_by_key(|c| (c.created_at, c.id));
+        for reply in replies {
+            push_replies(comments, reply, depth + 1, out);
+        }
+    }
+
+    let mut out = Vec::with_capacity(comments.len());
+    for comment in comments {
+        let has_parent = comment.parent_comment_id.is_some_and(|p| comments.iter().any(|c| c.id == p));
+        if !has_parent {
+            push_replies(comments, comment, 0, &mut out);
+        }
+    }
+    out
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TimeLog {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub hours: i32,
+    pub minutes: i32,
+    pub started_at: Option<NaiveDateTime>,
+    pub ended_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    /// When the entry was recorded or last edited; `created_at` can be backdated
+    #[serde(default)]
+    pub updated_at: NaiveDateTime,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Stays the same across machines, unlike `id`
+    #[serde(default)]
+    pub uuid: String,
+    /// Identity from `[user]` in config.toml of whoever created the row
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Identity of whoever changed the row last
+    #[serde(default)]
+    pub updated_by: Option<String>,
+    /// Whether the time is billed; non-billable time still counts in hours worked
+    #[serde(default = "billable_default")]
+    pub billable: bool,
+}
+
+fn billable_default() -> bool {
+    true
+}
+
+impl TimeLog {
+    pub fn duration(&self) -> Duration {
+        Duration::minutes(self.hours as i64 * 60 + self.minutes as i64)
+    }
+
+    /// Start, end, and length after moving one or both ends or giving a new length.
+    ///
+    /// A moved end keeps the other end, if the log has one, and the length follows; given
+    /// a length, or with no other end, the other end follows instead. Logs without a span
+    /// that only get a new length stay without one.
+    pub fn respan(
+        &self,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        length: Option<Duration>,
+    ) -> (Option<NaiveDateTime>, Option<NaiveDateTime>, Duration) {
+        let keep = length.unwrap_or_else(|| self.duration());
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) => (start, end),
+            (Some(start), None) => match self.ended_at.filter(|_| length.is_none()) {
+                Some(end) => (start, end),
+                None => (start, start + keep),
+            },
+            (None, Some(end)) => match self.started_at.filter(|_| length.is_none()) {
+                Some(start) => (start, end),
+                None => (end - keep, end),
+            },
+            (None, None) => match self.started_at {
+                Some(start) => (start, start + keep),
+                None => return (None, self.ended_at, keep),
+            },
+        };
+        (Some(start), Some(end), end - start)
+    }
+}
+
+/// One change to the time logs, made together with others by `Database::apply_time_log_changes`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeLogChange {
+    /// A new time log, dated `logged_at`
+    Add { ticket_id: i64, hours: i32, minutes: i32, logged_at: NaiveDateTime, note: Option<String> },
+    /// New ticket, length, span, and note for an existing time log
+    Update {
+        id: i64,
+        ticket_id: i64,
+        hours: i32,
+        minutes: i32,
+        started_at: Option<NaiveDateTime>,
+        ended_at: Option<NaiveDateTime>,
+        note: Option<String>,
+    },
+    Delete { id: i64 },
+}
+
+/// An external link attached to a ticket, such as a pull request or design doc
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TicketUrl {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub url: String,
+    pub label: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Days a ticket of a release takes on its schedule: from the day it was created through
+/// its due date, or the release's when it has none. Open-ended when neither is set; the end
+/// comes before the start when the ticket was created after its due date.
+pub fn gantt_span(ticket: &Ticket, release_due: Option<NaiveDate>) -> (NaiveDate, Option<NaiveDate>) {
+    (ticket.created_at.date(), ticket.due_date.or(release_due))
+}
+
+/// How `ltm report score` ranks the backlog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoringMethod {
+    /// Reach × impact × confidence ÷ effort
+    Rice,
+    /// Impact × confidence ÷ effort, for work whose reach is unknown or the same everywhere
+    Ice,
+}
+
+impl ScoringMethod {
+    /// Ticket fields the method needs, in the order they appear in its formula
+    pub fn fields(self) -> &'static [&'static str] {
+        match self {
+            ScoringMethod::Rice => &["reach", "impact", "confidence", "effort"],
+            ScoringMethod::Ice => &["impact", "confidence", "effort"],
+        }
+    }
+}
+
+/// A ticket's score under `method`, or `None` while a field it needs is unset
+pub fn ticket_score(ticket: &Ticket, method: ScoringMethod) -> Option<f64> {
+    let score = ticket.impact? * ticket.confidence? as f64 / 100.0 / ticket.effort?;
+    match method {
+        ScoringMethod::Rice => Some(ticket.reach? as f64 * score),
+        ScoringMethod::Ice => Some(score),
+    }
+}
+
+/// Fields `method` needs that are unset on a ticket
+pub fn missing_score_fields(ticket: &Ticket, method: ScoringMethod) -> Vec<&'static str> {
+    let set = |field: &str| match field {
+        "reach" => ticket.reach.is_some(),
+        "impact" => ticket.impact.is_some(),
+        "confidence" => ticket.confidence.is_some(),
+        _ => ticket.effort.is_some(),
+    };
+    method.fields().iter().copied().filter(|field| !set(field)).collect()
+}
+
+/// A ticket that can't be finished before another one, its blocker
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct TicketDependency {
+    pub ticket_id: i64,
+    pub blocker_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+/// Every ticket that `tickets` wait on, directly or through the tickets in between
+pub fn transitive_blockers(dependencies: &[TicketDependency], tickets: &[i64]) -> BTreeSet<i64> {
+    let mut blockers = BTreeSet::new();
+    let mut pending = tickets.to_vec();
+    while let Some(id) = pending.pop() {
+        for dependency in dependencies.iter().filter(|d| d.ticket_id == id) {
+            if blockers.insert(dependency.blocker_id) {
+                pending.push(dependency.blocker_id);
+            }
+        }
+    }
+    blockers
+}
+
+/// A ticket mirrored as an issue in another tracker, such as Linear
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct RemoteLink {
+    pub ticket_id: i64,
+    /// Tracker name, e.g. `linear`
+    pub provider: String,
+    /// The tracker's ID for the issue
+    pub remote_id: String,
+    /// The identifier people use, e.g. `ENG-123`
+    pub remote_key: Option<String>,
+    /// The ticket's `updated_at` when ticket and issue last matched
+    pub synced_at: NaiveDateTime,
+    /// The issue's last update when ticket and issue last matched
+    pub remote_updated_at: NaiveDateTime,
+    /// Both sides' synced fields when they last matched, as JSON
+    #[serde(default)]
+    pub base: Option<String>,
+}
+
+/// A time log sent as a work log to another tracker, such as Jira
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct RemoteWorklog {
+    pub time_log_id: i64,
+    /// Tracker name, e.g. `jira`
+    pub provider: String,
+    /// The tracker's ID for the work log
+    pub remote_id: String,
+    /// The time log's `updated_at` when it was last sent
+    pub synced_at: NaiveDateTime,
+}
+
+/// A ticket the current user viewed or changed, for `ltm recent`
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct RecentTicket {
+    pub ticket_id: i64,
+    pub project: String,
+    pub name: String,
+    pub status: String,
+    /// When a command last used the ticket
+    pub used_at: NaiveDateTime,
+    /// That command's command line, when known
+    pub command: Option<String>,
+}
+
+/// A ticket made read-only with `ltm ticket lock`
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct TicketLock {
+    pub ticket_id: i64,
+    pub locked_at: NaiveDateTime,
+    pub locked_by: Option<String>,
+}
+
+/// A month closed for billing with `ltm billing lock`
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct BillingLock {
+    /// YYYY-MM
+    pub month: String,
+    pub locked_at: NaiveDateTime,
+    pub locked_by: Option<String>,
+}
+
+/// A token for `ltm serve`, made with `ltm serve token create`; the secret itself is not kept
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct ApiToken {
+    pub name: String,
+    /// Whether the token may only query the API, not run commands
+    pub read_only: bool,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<String>,
+}
+
+/// An area of a project, added with `ltm component add`
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct Component {
+    pub project: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// A version of a project, created with `ltm release create`
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct Release {
+    pub project: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    /// Day it is planned to ship
+    pub due_date: Option<NaiveDate>,
+}
+
+/// How a release on the roadmap is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReleaseRisk {
+    /// Every planned ticket is finished
+    Done,
+    OnTrack,
+    /// The open work does not fit before the due date, or some of it is due later
+    AtRisk,
+    /// The due date has passed with tickets still open
+    Overdue,
+}
+
+/// A release on the roadmap with its progress and the work left before its due date
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoadmapItem {
+    pub project: String,
+    pub release: String,
+    pub due_date: Option<NaiveDate>,
+    pub total: usize,
+    pub done: usize,
+    /// Summed estimates of the open tickets
+    pub remaining_minutes: i64,
+    /// Open tickets without an estimate
+    pub unestimated: usize,
+    /// Working time from today through the due date
+    pub available_minutes: Option<i64>,
+    /// Open tickets due after the release, or already overdue
+    pub late_tickets: usize,
+    pub risk: ReleaseRisk,
+}
+
+impl RoadmapItem {
+    /// Percentage of the planned tickets that are finished
+    pub fn percent(&self) -> usize {
+        (self.done * 100).checked_div(self.total).unwrap_or(0)
+    }
+}
+
+/// Where `release` stands on `today`, given the tickets planned for it and `hours_per_day`
+/// of work on each weekday until its due date
+pub fn roadmap_item(release: &Release, tickets: &[Ticket], today: NaiveDate, hours_per_day: f64) -> RoadmapItem {
+    let open: Vec<&Ticket> = tickets.iter().filter(|t| !is_finished_status(&t.status)).collect();
+    let remaining_minutes = open.iter().filter_map(|t| t.estimate_minutes).sum();
+    let unestimated = open.iter().filter(|t| t.estimate_minutes.is_none()).count();
+    let available_minutes = release.due_date.map(|due| {
+        let weekdays = today.iter_days().take_while(|day| *day <= due).filter(|day| day.weekday().number_from_monday() <= 5).count();
+        (weekdays as f64 * hours_per_day * 60.0).round() as i64
+    });
+    let late_tickets = open
+        .iter()
+        .filter(|t| t.due_date.is_some_and(|day| day < today || release.due_date.is_some_and(|due| day > due)))
+        .count();
+    let risk = if !tickets.is_empty() && open.is_empty() {
+        ReleaseRisk::Done
+    } else if release.due_date.is_some_and(|due| due < today) && !open.is_empty() {
+        ReleaseRisk::Overdue
+    } else if late_tickets > 0 || available_minutes.is_some_and(|available| remaining_minutes > available) {
+        ReleaseRisk::AtRisk
+    } else {
+        ReleaseRisk::OnTrack
+    };
+    RoadmapItem {
+        project: release.project.clone(),
+        release: release.name.clone(),
+        due_date: release.due_date,
+        total: tickets.len(),
+        done: tickets.len() - open.len(),
+        remaining_minutes,
+        unestimated,
+        available_minutes,
+        late_tickets,
+        risk,
+    }
+}
+
+/// One step of a ticket's checklist; `position` is its 1-based number within the ticket
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub position: i64,
+    pub text: String,
+    pub done: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectSummary {
+    pub project: String,
+    pub total_tickets: i64,
+    pub open_tickets: i64,
+    pub closed_tickets: i64,
+    pub total_time_hours: f64,
+    /// Number of tickets of each type; untyped tickets are left out
+    #[serde(default)]
+    pub kinds: BTreeMap<String, i64>,
+    /// Number of tickets in each component; tickets without one are left out
+    #[serde(default)]
+    pub components: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ActiveTimer {
+    pub ticket_id: i64,
+    /// When the timer was first started
+    pub started_at: NaiveDateTime,
+    /// When the current running segment began (reset on resume)
+    pub segment_started_at: NaiveDateTime,
+    pub paused_at: Option<NaiveDateTime>,
+    /// Time accumulated by segments before the current one
+    pub elapsed_seconds: i64,
+    /// What the time is being spent on, carried into the time log on stop
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl ActiveTimer {
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Total tracked time as of `now`, excluding paused periods
+    pub fn elapsed(&self, now: NaiveDateTime) -> Duration {
+        let segment_end = self.paused_at.unwrap_or(now);
+        Duration::seconds(self.elapsed_seconds) + (segment_end - self.segment_started_at)
+    }
+}
+
+/// One time log with its ticket, as `ltm time export` writes it
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct TimeLogEntry {
+    pub id: i64,
+    pub project: String,
+    pub ticket_id: i64,
+    pub ticket_name: String,
+    /// Day the work was done, dated like the time report
+    pub date: NaiveDate,
+    pub minutes: i64,
+    pub billable: bool,
+    pub note: Option<String>,
+}
+
+/// Time logged against one ticket over a report period
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TicketHours {
+    pub ticket_id: i64,
+    pub project: String,
+    pub name: String,
+    pub status: String,
+    /// Number of time log entries in the period
+    pub entries: i64,
+    pub minutes: i64,
+    /// `minutes` as hours, rounded to two decimals
+    pub hours: f64,
+    /// Minutes of billable time logs
+    pub billable_minutes: i64,
+    /// Hourly rate: the ticket's, else the project's `rate` setting
+    pub rate: Option<f64>,
+    /// `billable_minutes` at `rate`, rounded to cents
+    pub amount: Option<f64>,
+    /// Currency of `rate` and `amount`: the project's `currency` setting
+    pub currency: Option<String>,
+}
+
+impl TicketHours {
+    /// Bill at `rate` when neither the ticket nor its project has a rate
+    pub fn with_default_rate(mut self, rate: Option<f64>) -> Self {
+        if self.rate.is_none() {
+            self.rate = rate;
+            self.amount = rate.map(|rate| (rate * self.billable_minutes as f64 / 60.0 * 100.0).round() / 100.0);
+        }
+        self
+    }
+
+    /// Bill in `currency` when the project has no currency of its own
+    pub fn with_default_currency(mut self, currency: Option<&str>) -> Self {
+        if self.currency.is_none() {
+            self.currency = currency.map(str::to_string);
+        }
+        self
+    }
+}
+
+/// One entry of the event journal: a row created, updated, or deleted by a command
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Event {
+    pub id: i64,
+    /// Command line that made the change, when known
+    pub command: Option<String>,
+    /// ticket, comment, time_log, ticket_url, checklist_item, timer, tags, or sync_conflict
+    pub entity: String,
+    /// Row ID; the ticket ID for timers, tags, and sync conflicts
+    pub entity_id: i64,
+    /// create, update, or delete; resolve for sync conflicts
+    pub action: String,
+    /// Row as JSON before the change
+    pub before: Option<String>,
+    /// Row as JSON after the change
+    pub after: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Event {
+    /// Fields whose value differs between `before` and `after`, ignoring `updated_at`
+    pub fn changed_fields(&self) -> Vec<String> {
+        let parse = |json: &Option<String>| -> serde_json::Map<String, serde_json::Value> {
+            json.as_deref()
+                .and_then(|j| serde_json::from_str::<serde_json::Value>(j).ok())
+                .and_then(|v| v.as_object().cloned())
+                .unwrap_or_default()
+        };
+        let (before, after) = (parse(&self.before), parse(&self.after));
+        let mut fields: Vec<String> = before
+            .keys()
+            .chain(after.keys())
+            .filter(|key| key.as_str() != "updated_at" && before.get(*key) != after.get(*key))
+            .cloned()
+            .collect();
+        fields.sort();
+        fields.dedup();
+        fields
+    }
+}
+
+/// Everything deleted along with a set of tickets, shown before the user confirms
+#[derive(Debug, Clone, Default, PartialEq, Serialize, FromRow)]
+pub struct DeleteImpact {
+    pub comments: i64,
+    pub time_logs: i64,
+    /// Total length of those time logs
+    pub minutes: i64,
+    pub links: i64,
+    pub checklist_items: i64,
+    /// Dependencies on or from the tickets
+    pub dependencies: i64,
+    /// Links to issues in another tracker kept by `ltm sync`; the issues themselves stay
+    pub synced_issues: i64,
+}
+
+/// Unfinished tickets, and how many of them are due today or overdue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct OpenCounts {
+    pub open: i64,
+    pub due_today: i64,
+    pub overdue: i64,
+}
+
+/// Tickets opened and closed, and hours logged, in one calendar month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthTrend {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub opened: i64,
+    /// Tickets moved into a finished status
+    pub closed: i64,
+    pub hours: f64,
+}
+
+/// First day of each of the `count` months ending with the month containing `end`, oldest first
+pub fn month_starts(end: NaiveDate, count: usize) -> Vec<NaiveDate> {
+    let last = end.with_day(1).unwrap_or(end);
+    (0..count as u32)
+        .rev()
+        .filter_map(|back| last.checked_sub_months(Months::new(back)))
+        .collect()
+}
+
+/// Age buckets of `ltm report aging`, as the days a ticket has been open below which it
+/// falls in each; older tickets go in a last, open-ended bucket
+pub const AGE_BUCKETS: [i64; 3] = [7, 30, 90];
+
+/// Labels of the age buckets, the open-ended one last
+pub const AGE_BUCKET_LABELS: [&str; 4] = ["0–7d", "7–30d", "30–90d", "90d+"];
+
+/// An unfinished ticket and how many days it has been open
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgedTicket {
+    pub id: i64,
+    pub name: String,
+    pub status: String,
+    pub age_days: i64,
+}
+
+/// A project's unfinished tickets counted by age
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectAging {
+    pub project: String,
+    /// Tickets in each bucket of `AGE_BUCKET_LABELS`
+    pub buckets: [i64; 4],
+    /// The oldest tickets, oldest first
+    pub oldest: Vec<AgedTicket>,
+}
+
+impl ProjectAging {
+    pub fn total(&self) -> i64 {
+        self.buckets.iter().sum()
+    }
+}
+
+/// Count the unfinished, unarchived tickets of each project by how long ago they were
+/// created, keeping the `oldest` oldest of each; projects in name order
+pub fn ticket_aging(tickets: &[Ticket], now: NaiveDateTime, oldest: usize) -> Vec<ProjectAging> {
+    let mut projects: Vec<ProjectAging> = Vec::new();
+    let mut open: Vec<&Ticket> =
+        tickets.iter().filter(|t| t.archived_at.is_none() && !is_finished_status(&t.status)).collect();
+    open.sort_by_key(|t| (t.project.clone(), t.created_at, t.id));
+    for ticket in open {
+        if projects.last().is_none_or(|p| p.project != ticket.project) {
+            projects.push(ProjectAging { project: ticket.project.clone(), buckets: [0; 4], oldest: Vec::new() });
+        }
+        let project = projects.last_mut().unwrap();
+        let age_days = (now - ticket.created_at).num_days().max(0);
+        let bucket = AGE_BUCKETS.iter().position(|&days| age_days < days).unwrap_or(AGE_BUCKETS.len());
+        project.buckets[bucket] += 1;
+        if project.oldest.len() < oldest {
+            project.oldest.push(AgedTicket {
+                id: ticket.id,
+                name: ticket.name.clone(),
+                status: ticket.status.clone(),
+                age_days,
+            });
+        }
+    }
+    projects
+}
+
+/// A recorded status change; `from_status` is `None` for a ticket's initial status
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct StatusChange {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub changed_at: NaiveDateTime,
+}
+
+/// Time spent in one status, summed over every visit
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusTime {
+    pub status: String,
+    pub total: Duration,
+    pub visits: usize,
+}
+
+/// Sum how long tickets spent in each status, in the order statuses were first seen.
+///
+/// `changes` must be grouped by ticket and oldest first. A ticket's current status counts
+/// up to `now`, except finished statuses, which stop the clock.
+pub fn time_in_status(changes: &[StatusChange], now: NaiveDateTime) -> Vec<StatusTime> {
+    let mut totals: Vec<StatusTime> = Vec::new();
+    for (i, change) in changes.iter().enumerate() {
+        let end = match changes.get(i + 1) {
+            Some(next) if next.ticket_id == change.ticket_id => next.changed_at,
+            _ if is_finished_status(&change.to_status) => change.changed_at,
+            _ => now,
+        };
+        let status = change.to_status.to_lowercase();
+        let index = match totals.iter().position(|t| t.status == status) {
+            Some(index) => index,
+            None => {
+                totals.push(StatusTime { status, total: Duration::zero(), visits: 0 });
+                totals.len() - 1
+            }
+        };
+        totals[index].total += (end - change.changed_at).max(Duration::zero());
+        totals[index].visits += 1;
+    }
+    totals
+}
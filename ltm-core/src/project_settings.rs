@@ -0,0 +1,446 @@
+use regex::Regex;
+
+use crate::validation::{status_alias, status_sort_key, validate_priority, DEFAULT_KINDS, validate_rate, validate_status, ValidationError};
+
+/// Keys accepted by `ltm project set`
+pub const SETTING_KEYS: &[&str] = &["statuses", "default_status", "default_priority", "time_rounding", "id_prefix", "required_fields", "rate", "currency", "wip_limits", "wip_policy", "kinds", "required_for"];
+
+/// Fields a project can require on new tickets
+pub const REQUIRED_FIELDS: &[&str] = &["description", "due", "priority", "estimate", "tags"];
+
+/// Fields a project can require before a ticket moves to a status: those of new tickets,
+/// and a `comment` written since the ticket's last status change
+pub const STATUS_FIELDS: &[&str] = &["description", "due", "priority", "estimate", "tags", "comment"];
+
+/// Overrides for one project, stored as key/value rows in the `project_settings` table:
+///
+/// ```text
+/// ltm project set ideas statuses backlog,open,in-progress,closed
+/// ltm project set ideas default_status backlog
+/// ltm project set webapp default_priority medium
+/// ltm project set client-a time_rounding 15
+/// ltm project set webapp id_prefix WEB
+/// ltm project set webapp required_fields due,estimate
+/// ltm project set client-a rate 95
+/// ltm project set client-a currency EUR
+/// ltm project set webapp wip_limits in-progress:3,review:2
+/// ltm project set webapp wip_policy block
+/// ltm project set webapp kinds bug,feature,spike
+/// ltm project set webapp required_for in-progress:estimate,closed:comment
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectSettings {
+    /// Statuses tickets in the project may have, replacing the built-in set
+    pub statuses: Option<Vec<String>>,
+    /// Status new tickets start in instead of "open"
+    pub default_status: Option<String>,
+    /// Priority given to new tickets that do not name one
+    pub default_priority: Option<String>,
+    /// Logged time is rounded up to a multiple of this many minutes
+    pub time_rounding: Option<i64>,
+    /// Shown before ticket IDs, e.g. `WEB` for `WEB-12`
+    pub id_prefix: Option<String>,
+    /// Fields every new ticket must have, from `REQUIRED_FIELDS`
+    pub required_fields: Vec<String>,
+    /// Hourly rate for billable time, unless a ticket has its own
+    pub rate: Option<f64>,
+    /// Currency code of the rates, e.g. `EUR`
+    pub currency: Option<String>,
+    /// Most tickets allowed in a status at once, in the order they were set
+    pub wip_limits: Vec<(String, i64)>,
+    /// `block` refuses status changes past a WIP limit; otherwise they only warn
+    pub wip_policy: Option<String>,
+    /// Ticket types the project uses, replacing `DEFAULT_KINDS`
+    pub kinds: Option<Vec<String>>,
+    /// Fields a ticket needs before moving to a status, from `STATUS_FIELDS`, as
+    /// `(status, field)` pairs in the order they were set
+    pub required_for: Vec<(String, String)>,
+}
+
+impl ProjectSettings {
+    /// Build settings from stored rows; values were checked by `normalize` when set
+    pub fn from_pairs<I: IntoIterator<Item = (String, String)>>(pairs: I) -> Self {
+        let list = |value: &str| value.split(',').map(str::to_string).collect::<Vec<_>>();
+        let mut settings = Self::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "statuses" => settings.statuses = Some(list(&value)),
+                "default_status" => settings.default_status = Some(value),
+                "default_priority" => settings.default_priority = Some(value),
+                "time_rounding" => settings.time_rounding = value.parse().ok(),
+                "id_prefix" => settings.id_prefix = Some(value),
+                "required_fields" => settings.required_fields = list(&value),
+                "rate" => settings.rate = value.parse().ok(),
+                "currency" => settings.currency = Some(value),
+                "wip_limits" => {
+                    settings.wip_limits = value
+                        .split(',')
+                        .filter_map(|limit| limit.split_once(':'))
+                        .filter_map(|(status, limit)| Some((status.to_string(), limit.parse().ok()?)))
+                        .collect()
+                }
+                "wip_policy" => settings.wip_policy = Some(value),
+                "kinds" => settings.kinds = Some(list(&value)),
+                "required_for" => {
+                    settings.required_for = value
+                        .split(',')
+                        .filter_map(|pair| pair.split_once(':'))
+                        .map(|(status, field)| (status.to_string(), field.to_string()))
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    /// Check a value for `key` and put it in its stored form
+    pub fn normalize(key: &str, value: &str) -> Result<String, ValidationError> {
+        let invalid = || ValidationError::InvalidSetting { key: key.to_string(), value: value.to_string() };
+        let items = || -> Vec<String> {
+            value.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()).collect()
+        };
+        let pattern = Regex::new(r"^[a-z][a-z0-9_-]{0,29}$").unwrap();
+        match key {
+            "statuses" | "kinds" => {
+                let names = items();
+                if names.is_empty() || !names.iter().all(|s| pattern.is_match(s)) {
+                    return Err(invalid());
+                }
+                Ok(names.join(","))
+            }
+            "default_status" => {
+                let status = value.trim().to_lowercase();
+                if pattern.is_match(&status) {
+                    Ok(status)
+                } else {
+                    Err(invalid())
+                }
+            }
+            "default_priority" => validate_priority(value),
+            "time_rounding" => match value.trim().parse::<i64>() {
+                Ok(minutes) if (1..=240).contains(&minutes) => Ok(minutes.to_string()),
+                _ => Err(invalid()),
+            },
+            "id_prefix" => {
+                let prefix = value.trim().to_uppercase();
+                if Regex::new(r"^[A-Z][A-Z0-9]{0,9}$").unwrap().is_match(&prefix) {
+                    Ok(prefix)
+                } else {
+                    Err(invalid())
+                }
+            }
+            "required_fields" => {
+                let fields = items();
+                if fields.is_empty() || !fields.iter().all(|f| REQUIRED_FIELDS.contains(&f.as_str())) {
+                    return Err(invalid());
+                }
+                Ok(fields.join(","))
+            }
+            "rate" => Ok(validate_rate(value)?.to_string()),
+            "currency" => {
+                let code = value.trim().to_uppercase();
+                if Regex::new(r"^[A-Z]{3}$").unwrap().is_match(&code) {
+                    Ok(code)
+                } else {
+                    Err(invalid())
+                }
+            }
+            "wip_limits" => {
+                // status:limit pairs, also accepted as status=limit
+                let mut limits: Vec<String> = Vec::new();
+                for item in items() {
+                    let (status, limit) = item.split_once([':', '=']).ok_or_else(invalid)?;
+                    let (status, limit) = (status.trim(), limit.trim().parse::<i64>().map_err(|_| invalid())?);
+                    let seen = limits.iter().any(|l| l.split(':').next() == Some(status));
+                    if !pattern.is_match(status) || !(1..=999).contains(&limit) || seen {
+                        return Err(invalid());
+                    }
+                    limits.push(format!("{}:{}", status, limit));
+                }
+                if limits.is_empty() {
+                    return Err(invalid());
+                }
+                Ok(limits.join(","))
+            }
+            "wip_policy" => match value.trim().to_lowercase().as_str() {
+                policy @ ("warn" | "block") => Ok(policy.to_string()),
+                _ => Err(invalid()),
+            },
+            "required_for" => {
+                // status:field pairs, a status repeated for each field it needs
+                let mut pairs: Vec<String> = Vec::new();
+                for item in items() {
+                    let (status, field) = item.split_once([':', '=']).ok_or_else(invalid)?;
+                    let pair = format!("{}:{}", status.trim(), field.trim());
+                    if !pattern.is_match(status.trim()) || !STATUS_FIELDS.contains(&field.trim()) {
+                        return Err(invalid());
+                    }
+                    if !pairs.contains(&pair) {
+                        pairs.push(pair);
+                    }
+                }
+                if pairs.is_empty() {
+                    return Err(invalid());
+                }
+                Ok(pairs.join(","))
+            }
+            _ => Err(ValidationError::UnknownSetting(key.to_string())),
+        }
+    }
+
+    /// Validate a status against the project's statuses, or the built-in ones
+    pub fn validate_status(&self, project: &str, status: &str) -> Result<String, ValidationError> {
+        let Some(statuses) = &self.statuses else {
+            return validate_status(status);
+        };
+        let lower = status.trim().to_lowercase();
+        if statuses.contains(&lower) {
+            Ok(lower)
+        } else if let Some(target) = status_alias(&lower).filter(|target| statuses.contains(target)) {
+            Ok(target)
+        } else {
+            Err(ValidationError::StatusNotInProject {
+                status: status.to_string(),
+                project: project.to_string(),
+                allowed: statuses.clone(),
+            })
+        }
+    }
+
+    /// Validate a ticket type against the project's types, or `DEFAULT_KINDS`
+    pub fn validate_kind(&self, kind: &str) -> Result<String, ValidationError> {
+        let lower = kind.trim().to_lowercase();
+        let allowed = self.kinds.clone().unwrap_or_else(|| DEFAULT_KINDS.iter().map(|k| k.to_string()).collect());
+        if allowed.contains(&lower) {
+            Ok(lower)
+        } else {
+            Err(ValidationError::InvalidKind { kind: kind.to_string(), allowed })
+        }
+    }
+
+    /// Status for a new ticket: `requested`, else the project's default status, else "open",
+    /// or the first project status when the project has no "open"
+    pub fn initial_status(&self, project: &str, requested: Option<&str>) -> Result<String, ValidationError> {
+        let status = match (requested, &self.default_status, &self.statuses) {
+            (Some(status), _, _) => status,
+            (None, Some(status), _) => status,
+            (None, None, Some(statuses)) if !statuses.iter().any(|s| s == "open") => &statuses[0],
+            (None, None, _) => "open",
+        };
+        self.validate_status(project, status)
+    }
+
+    /// Sort key for a status: its place in the project's statuses when it has them, else
+    /// the built-in workflow order
+    pub fn status_sort_key(&self, status: &str) -> (usize, String) {
+        let lower = status.to_lowercase();
+        match self.statuses.as_ref().and_then(|statuses| statuses.iter().position(|s| *s == lower)) {
+            Some(index) => (index, lower),
+            None => {
+                let (index, lower) = status_sort_key(&lower);
+                (self.statuses.as_ref().map_or(0, Vec::len) + index, lower)
+            }
+        }
+    }
+
+    /// Round logged minutes up to the project's rounding step
+    pub fn round_minutes(&self, minutes: i64) -> i64 {
+        match self.time_rounding {
+            Some(step) if step > 1 && minutes > 0 => (minutes + step - 1) / step * step,
+            _ => minutes,
+        }
+    }
+
+    /// Fail with the first required field missing from `present`; a default priority counts
+    /// as a priority
+    pub fn check_required(&self, project: &str, present: &[&str]) -> Result<(), ValidationError> {
+        let missing = |field: &&String| {
+            let defaulted = field.as_str() == "priority" && self.default_priority.is_some();
+            !defaulted && !present.contains(&field.as_str())
+        };
+        match self.required_fields.iter().find(missing) {
+            Some(field) => Err(ValidationError::MissingRequiredField {
+                field: field.clone(),
+                project: project.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Fail with the first field the project requires before a ticket moves to `status`
+    /// that is missing from `present`
+    pub fn check_required_for(&self, project: &str, status: &str, present: &[&str]) -> Result<(), ValidationError> {
+        let missing = self
+            .required_for
+            .iter()
+            .find(|(s, field)| s.eq_ignore_ascii_case(status) && !present.contains(&field.as_str()));
+        match missing {
+            Some((status, field)) => Err(ValidationError::MissingStatusField {
+                field: field.clone(),
+                project: project.to_string(),
+                status: status.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// The fields the project requires before a ticket moves to `status`
+    pub fn fields_required_for(&self, status: &str) -> Vec<&str> {
+        self.required_for.iter().filter(|(s, _)| s.eq_ignore_ascii_case(status)).map(|(_, field)| field.as_str()).collect()
+    }
+
+    /// The most tickets `status` may hold at once, if limited
+    pub fn wip_limit(&self, status: &str) -> Option<i64> {
+        self.wip_limits.iter().find(|(s, _)| s.eq_ignore_ascii_case(status)).map(|(_, limit)| *limit)
+    }
+
+    /// Fail when moving a ticket into `status`, which holds `count` other tickets, passes
+    /// its WIP limit and the project blocks that; returns the limit passed when it only warns
+    pub fn check_wip(&self, project: &str, status: &str, count: i64) -> Result<Option<i64>, ValidationError> {
+        match self.wip_limit(status) {
+            Some(limit) if count >= limit && self.wip_policy.as_deref() == Some("block") => {
+                Err(ValidationError::WipLimitReached { status: status.to_string(), project: project.to_string(), limit })
+            }
+            Some(limit) if count >= limit => Ok(Some(limit)),
+            _ => Ok(None),
+        }
+    }
+
+    /// A ticket ID as shown to the user, e.g. `WEB-12` or `12`
+    pub fn display_id(&self, id: i64) -> String {
+        match &self.id_prefix {
+            Some(prefix) => format!("{}-{}", prefix, id),
+            None => id.to_string(),
+        }
+    }
+
+    /// Settings as `(key, stored value)` pairs, for display
+    pub fn to_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(statuses) = &self.statuses {
+            pairs.push(("statuses", statuses.join(",")));
+        }
+        if let Some(status) = &self.default_status {
+            pairs.push(("default_status", status.clone()));
+        }
+        if let Some(priority) = &self.default_priority {
+            pairs.push(("default_priority", priority.clone()));
+        }
+        if let Some(minutes) = self.time_rounding {
+            pairs.push(("time_rounding", minutes.to_string()));
+        }
+        if let Some(prefix) = &self.id_prefix {
+            pairs.push(("id_prefix", prefix.clone()));
+        }
+        if !self.required_fields.is_empty() {
+            pairs.push(("required_fields", self.required_fields.join(",")));
+        }
+        if let Some(rate) = self.rate {
+            pairs.push(("rate", rate.to_string()));
+        }
+        if let Some(currency) = &self.currency {
+            pairs.push(("currency", currency.clone()));
+        }
+        if !self.wip_limits.is_empty() {
+            let limits: Vec<String> = self.wip_limits.iter().map(|(status, limit)| format!("{}:{}", status, limit)).collect();
+            pairs.push(("wip_limits", limits.join(",")));
+        }
+        if let Some(policy) = &self.wip_policy {
+            pairs.push(("wip_policy", policy.clone()));
+        }
+        if let Some(kinds) = &self.kinds {
+            pairs.push(("kinds", kinds.join(",")));
+        }
+        if !self.required_for.is_empty() {
+            let required: Vec<String> = self.required_for.iter().map(|(status, field)| format!("{}:{}", status, field)).collect();
+            pairs.push(("required_for", required.join(",")));
+        }
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_settings() {
+        assert_eq!(ProjectSettings::normalize("statuses", "Backlog, open,closed").unwrap(), "backlog,open,closed");
+        assert_eq!(ProjectSettings::normalize("id_prefix", "web").unwrap(), "WEB");
+        assert!(ProjectSettings::normalize("time_rounding", "0").is_err());
+        assert!(ProjectSettings::normalize("required_fields", "due,colour").is_err());
+        assert!(matches!(ProjectSettings::normalize("colour", "red"), Err(ValidationError::UnknownSetting(_))));
+
+        let settings = ProjectSettings::from_pairs([
+            ("statuses".to_string(), "backlog,open,closed".to_string()),
+            ("time_rounding".to_string(), "15".to_string()),
+            ("id_prefix".to_string(), "WEB".to_string()),
+            ("required_fields".to_string(), "due".to_string()),
+            ("rate".to_string(), ProjectSettings::normalize("rate", "87.50").unwrap()),
+            ("currency".to_string(), ProjectSettings::normalize("currency", " chf").unwrap()),
+            ("wip_limits".to_string(), ProjectSettings::normalize("wip_limits", "In-Progress=3, review:1").unwrap()),
+            ("required_for".to_string(), ProjectSettings::normalize("required_for", "in-progress:estimate, Closed=Comment,closed:comment").unwrap()),
+        ]);
+        assert_eq!(settings.validate_status("ideas", "Backlog").unwrap(), "backlog");
+        assert!(matches!(
+            settings.validate_status("ideas", "testing"),
+            Err(ValidationError::StatusNotInProject { .. })
+        ));
+        assert!(ProjectSettings::default().validate_status("ideas", "testing").is_ok());
+        crate::validation::set_status_alias("later", "backlog");
+        assert_eq!(settings.validate_status("ideas", "Later").unwrap(), "backlog");
+        assert!(ProjectSettings::default().validate_status("ideas", "later").is_err());
+        assert_eq!(settings.initial_status("ideas", None).unwrap(), "open");
+        assert_eq!(settings.initial_status("ideas", Some("Backlog")).unwrap(), "backlog");
+        assert!(settings.initial_status("ideas", Some("testing")).is_err());
+        let without_open = ProjectSettings { statuses: Some(vec!["idea".to_string(), "done".to_string()]), ..Default::default() };
+        assert_eq!(without_open.initial_status("ideas", None).unwrap(), "idea");
+        assert_eq!(ProjectSettings::default().initial_status("ideas", None).unwrap(), "open");
+        let with_default = ProjectSettings { default_status: Some("blocked".to_string()), ..Default::default() };
+        assert_eq!(with_default.initial_status("ideas", None).unwrap(), "blocked");
+
+        assert_eq!((settings.round_minutes(1), settings.round_minutes(30), settings.round_minutes(0)), (15, 30, 0));
+        assert_eq!(ProjectSettings::default().round_minutes(7), 7);
+        let mut statuses = vec!["testing", "closed", "backlog", "open"];
+        statuses.sort_by_key(|s| settings.status_sort_key(s));
+        assert_eq!(statuses, ["backlog", "open", "closed", "testing"]);
+        statuses.sort_by_key(|s| ProjectSettings::default().status_sort_key(s));
+        assert_eq!(statuses, ["open", "testing", "closed", "backlog"]);
+        assert_eq!(settings.display_id(12), "WEB-12");
+        assert_eq!((settings.rate, settings.currency.as_deref()), (Some(87.5), Some("CHF")));
+        assert!(ProjectSettings::normalize("currency", "€").is_err());
+        assert_eq!(settings.to_pairs().iter().find(|(k, _)| *k == "wip_limits").unwrap().1, "in-progress:3,review:1");
+        assert!(ProjectSettings::normalize("wip_limits", "review:0").is_err());
+        assert!(ProjectSettings::normalize("wip_limits", "review:1,review:2").is_err());
+        assert_eq!((settings.wip_limit("in-progress"), settings.wip_limit("open")), (Some(3), None));
+        assert_eq!(settings.check_wip("webapp", "in-progress", 2).unwrap(), None);
+        assert_eq!(settings.check_wip("webapp", "review", 1).unwrap(), Some(1));
+        let blocking = ProjectSettings { wip_policy: Some("block".to_string()), ..settings.clone() };
+        assert!(matches!(blocking.check_wip("webapp", "review", 1), Err(ValidationError::WipLimitReached { limit: 1, .. })));
+        let spikes = ProjectSettings::from_pairs([("kinds".to_string(), ProjectSettings::normalize("kinds", "Bug, spike").unwrap())]);
+        assert_eq!(spikes.validate_kind("Spike").unwrap(), "spike");
+        assert!(matches!(spikes.validate_kind("feature"), Err(ValidationError::InvalidKind { allowed, .. }) if allowed == ["bug", "spike"]));
+        assert_eq!(settings.validate_kind("Feature").unwrap(), "feature");
+        assert!(settings.check_required("webapp", &["due"]).is_ok());
+        let priority_required = ProjectSettings { required_fields: vec!["priority".to_string()], ..Default::default() };
+        assert!(priority_required.check_required("webapp", &[]).is_err());
+        let with_default = ProjectSettings { default_priority: Some("medium".to_string()), ..priority_required };
+        assert!(with_default.check_required("webapp", &[]).is_ok());
+        assert!(matches!(
+            settings.check_required("webapp", &["priority"]),
+            Err(ValidationError::MissingRequiredField { field, .. }) if field == "due"
+        ));
+        assert_eq!(settings.to_pairs().iter().find(|(k, _)| *k == "required_for").unwrap().1, "in-progress:estimate,closed:comment");
+        assert!(ProjectSettings::normalize("required_for", "closed:colour").is_err());
+        assert!(ProjectSettings::normalize("required_for", "closed").is_err());
+        assert_eq!(settings.fields_required_for("Closed"), ["comment"]);
+        assert!(settings.check_required_for("webapp", "in-progress", &["estimate", "due"]).is_ok());
+        assert!(settings.check_required_for("webapp", "review", &[]).is_ok());
+        assert!(matches!(
+            settings.check_required_for("webapp", "closed", &["estimate"]),
+            Err(ValidationError::MissingStatusField { field, status, .. }) if field == "comment" && status == "closed"
+        ));
+        assert_eq!(ProjectSettings::from_pairs(settings.to_pairs().into_iter().map(|(k, v)| (k.to_string(), v))), settings);
+    }
+}
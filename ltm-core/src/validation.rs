@@ -0,0 +1,897 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use strsim::levenshtein;
+use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    #[error("Invalid ticket ID '{0}'. Must be a positive number.")]
+    InvalidTicketId(String),
+
+    #[error("Ticket #{0} not found.")]
+    TicketNotFound(i64),
+
+    #[error("Invalid project name '{0}'. Only letters, numbers, hyphens, underscores allowed.")]
+    InvalidProjectName(String),
+
+    #[error("Invalid time value. Hours must be 0-24, minutes must be 0-59.")]
+    InvalidTime(String),
+
+    #[error("Invalid status '{0}'. Must be one of: open, in-progress, testing, blocked, closed, cancelled.")]
+    InvalidStatus(String),
+
+    #[error("Invalid {field_type} length. {field_type} must be between {min} and {max} characters.")]
+    InvalidContentLength {
+        field_type: String,
+        min: usize,
+        max: usize,
+    },
+
+    #[error("Invalid priority '{0}'. Must be one of: low, medium, high, critical.")]
+    InvalidPriority(String),
+
+    #[error("Invalid severity '{0}'. Must be one of: S1, S2, S3, S4.")]
+    InvalidSeverity(String),
+
+    #[error("Invalid {field} '{value}'. Use {}.", score_range(field))]
+    InvalidScore {
+        field: String,
+        value: String,
+    },
+
+    #[error("Invalid component '{0}'. Use a letter followed by letters, numbers, hyphens, or underscores.")]
+    InvalidComponent(String),
+
+    #[error("Project '{project}' has no component '{component}'.")]
+    UnknownComponent {
+        component: String,
+        project: String,
+        allowed: Vec<String>,
+    },
+
+    #[error("Invalid release '{0}'. Use up to 30 letters, numbers, dots, hyphens, or underscores, like v1.4.")]
+    InvalidRelease(String),
+
+    #[error("Project '{project}' has no release '{release}'.")]
+    UnknownRelease {
+        release: String,
+        project: String,
+        allowed: Vec<String>,
+    },
+
+    #[error("Invalid tag '{0}'. Only letters, numbers, hyphens, underscores allowed.")]
+    InvalidTag(String),
+
+    #[error("Invalid date '{0}'. Use YYYY-MM-DD, today, tomorrow, a weekday, or an offset like +3d.")]
+    InvalidDate(String),
+
+    #[error("Invalid period '{0}'. Use a number followed by m, h, d, or w (e.g. 3d).")]
+    InvalidPeriod(String),
+
+    #[error("Invalid month '{0}'. Use YYYY-MM (e.g. 2024-05).")]
+    InvalidMonth(String),
+
+    #[error("Invalid rate '{0}'. Use a positive amount per hour (e.g. 95 or 87.50).")]
+    InvalidRate(String),
+
+    #[error("Invalid URL '{0}'. Use a full address such as https://example.com/pr/12.")]
+    InvalidUrl(String),
+
+    #[error("Invalid date range: {from} is after {to}.")]
+    InvalidDateRange {
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+
+    #[error("Invalid time span: {start} is not before {end}.")]
+    InvalidTimeSpan {
+        start: String,
+        end: String,
+    },
+
+    #[error("No ticket ID given and no ticket is focused.")]
+    NoFocusedTicket,
+
+    #[error("No ticket has been used yet for @last to refer to.")]
+    NoLastTicket,
+
+    #[error("Invalid regular expression '{pattern}': {reason}")]
+    InvalidRegex {
+        pattern: String,
+        reason: String,
+    },
+
+    #[error("Cannot change status from '{from}' to '{to}'.")]
+    TransitionNotAllowed {
+        from: String,
+        to: String,
+        allowed: Vec<String>,
+    },
+
+    #[error("Status '{status}' is not used in project '{project}'.")]
+    StatusNotInProject {
+        status: String,
+        project: String,
+        allowed: Vec<String>,
+    },
+
+    #[error("Unknown project setting '{0}'.")]
+    UnknownSetting(String),
+
+    #[error("Invalid value '{value}' for project setting '{key}'.")]
+    InvalidSetting {
+        key: String,
+        value: String,
+    },
+
+    #[error("Project '{project}' requires a {field} on new tickets.")]
+    MissingRequiredField {
+        field: String,
+        project: String,
+    },
+
+    #[error("Project '{project}' requires a {field} before a ticket moves to '{status}'.")]
+    MissingStatusField {
+        field: String,
+        project: String,
+        status: String,
+    },
+
+    #[error("Project '{project}' allows at most {limit} tickets in '{status}'.")]
+    WipLimitReached {
+        status: String,
+        project: String,
+        limit: i64,
+    },
+
+    #[error("Invalid ticket type '{kind}'. Must be one of: {}.", allowed.join(", "))]
+    InvalidKind {
+        kind: String,
+        allowed: Vec<String>,
+    },
+}
+
+/// Statuses accepted by `validate_status`
+pub const VALID_STATUSES: &[&str] = &[
+    "open",
+    "in-progress",
+    "testing",
+    "blocked",
+    "closed",
+    "cancelled",
+    "wontfix",
+];
+
+/// Workflow order of the built-in statuses, used by `--sort status` and board columns
+pub const STATUS_ORDER: &[&str] = &[
+    "open",
+    "in-progress",
+    "testing",
+    "blocked",
+    "closed",
+    "completed",
+    "done",
+    "cancelled",
+    "wontfix",
+];
+
+/// Statuses set with `set_valid_statuses`, replacing `VALID_STATUSES` when present
+static CUSTOM_STATUSES: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+/// Accept `statuses` instead of `VALID_STATUSES` for the rest of the process, in that
+/// workflow order, as configured under `[validation]` in config.toml
+pub fn set_valid_statuses(statuses: &[String]) {
+    let statuses = statuses.iter().map(|s| s.trim().to_lowercase()).collect();
+    *CUSTOM_STATUSES.write().unwrap_or_else(|e| e.into_inner()) = Some(statuses);
+}
+
+/// Statuses `validate_status` accepts: the configured ones, or `VALID_STATUSES`
+pub fn valid_statuses() -> Vec<String> {
+    match &*CUSTOM_STATUSES.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(statuses) => statuses.clone(),
+        None => VALID_STATUSES.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+/// Sort key for a status: its place in the configured statuses or `STATUS_ORDER`, with
+/// other statuses after those in alphabetical order
+pub fn status_sort_key(status: &str) -> (usize, String) {
+    let status = status.to_lowercase();
+    let index = match &*CUSTOM_STATUSES.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(statuses) => statuses.iter().position(|s| *s == status).unwrap_or(statuses.len()),
+        None => STATUS_ORDER.iter().position(|s| *s == status).unwrap_or(STATUS_ORDER.len()),
+    };
+    (index, status)
+}
+
+/// Priorities accepted by `validate_priority`, lowest first
+pub const PRIORITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+/// Severities accepted by `validate_severity`, most severe first
+pub const SEVERITIES: &[&str] = &["S1", "S2", "S3", "S4"];
+
+/// Ticket types for projects without a `kinds` setting
+pub const DEFAULT_KINDS: &[&str] = &["bug", "feature", "chore", "task"];
+
+/// Statuses that mean no further work is expected on a ticket
+pub const FINISHED_STATUSES: &[&str] = &["closed", "completed", "done", "cancelled", "wontfix"];
+
+/// Whether a status means no further work is expected
+pub fn is_finished_status(status: &str) -> bool {
+    FINISHED_STATUSES.contains(&status.to_lowercase().as_str())
+}
+
+/// Other names for statuses set with `set_status_alias`, keyed by the lowercase alias
+static STATUS_ALIASES: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Accept `alias`, in any case, wherever a status is parsed and no status has that name, as
+/// configured under `[status_aliases]` in config.toml
+pub fn set_status_alias(alias: &str, status: &str) {
+    STATUS_ALIASES
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(alias.trim().to_lowercase(), status.trim().to_lowercase());
+}
+
+/// The status an alias such as `wip` stands for
+pub fn status_alias(alias: &str) -> Option<String> {
+    STATUS_ALIASES.read().unwrap_or_else(|e| e.into_inner()).get(&alias.trim().to_lowercase()).cloned()
+}
+
+/// A status filter with its alias resolved, lowercased; filters are not validated, so an
+/// unknown status simply matches nothing
+pub fn resolve_status_alias(status: &str) -> String {
+    status_alias(status).unwrap_or_else(|| status.to_lowercase())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentType {
+    TicketName,
+    Description,
+    Comment,
+    ChecklistItem,
+    TimerNote,
+}
+
+/// Maximum lengths set with `set_max_length`, keyed by `ContentType::key`
+static MAX_LENGTHS: RwLock<BTreeMap<&'static str, usize>> = RwLock::new(BTreeMap::new());
+
+impl ContentType {
+    pub const ALL: [ContentType; 5] =
+        [ContentType::TicketName, ContentType::Description, ContentType::Comment, ContentType::ChecklistItem, ContentType::TimerNote];
+
+    /// Minimum and maximum length in characters, counting an emoji or an accented letter
+    /// as one; the maximum can be changed with `set_max_length`
+    pub fn limits(&self) -> (usize, usize) {
+        let default = match self {
+            ContentType::TicketName => 100,
+            ContentType::Description => 2000,
+            ContentType::Comment => 1000,
+            ContentType::ChecklistItem => 200,
+            ContentType::TimerNote => 200,
+        };
+        let max = MAX_LENGTHS.read().unwrap_or_else(|e| e.into_inner()).get(self.key()).copied();
+        (1, max.unwrap_or(default))
+    }
+
+    /// Name of the field in the `[limits]` table of config.toml
+    pub fn key(&self) -> &'static str {
+        match self {
+            ContentType::TicketName => "ticket_name",
+            ContentType::Description => "description",
+            ContentType::Comment => "comment",
+            ContentType::ChecklistItem => "checklist_item",
+            ContentType::TimerNote => "timer_note",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<ContentType> {
+        Self::ALL.into_iter().find(|field| field.key() == key)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContentType::TicketName => "ticket name",
+            ContentType::Description => "description",
+            ContentType::Comment => "comment",
+            ContentType::ChecklistItem => "checklist item",
+            ContentType::TimerNote => "timer note",
+        }
+    }
+}
+
+/// Stands for the ticket the last command used, in place of a ticket ID
+pub const LAST_TICKET: &str = "@last";
+
+/// Ticket set with `set_last_ticket`
+static LAST_TICKET_ID: RwLock<Option<i64>> = RwLock::new(None);
+
+/// Let `@last` stand for `ticket_id` for the rest of the process
+pub fn set_last_ticket(ticket_id: Option<i64>) {
+    *LAST_TICKET_ID.write().unwrap_or_else(|e| e.into_inner()) = ticket_id;
+}
+
+/// The ticket set with `set_last_ticket`
+pub fn last_ticket() -> Option<i64> {
+    *LAST_TICKET_ID.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Validate ticket IDs: positive integers that exist in database
+/// Accepts a project ID prefix such as `WEB-12`, or `@last` for the ticket the last command used
+pub fn validate_ticket_id(id: &str) -> Result<i64, ValidationError> {
+    if id.eq_ignore_ascii_case(LAST_TICKET) {
+        return last_ticket().ok_or(ValidationError::NoLastTicket);
+    }
+    let prefix = Regex::new(r"^[A-Za-z][A-Za-z0-9]{0,9}-").unwrap();
+    let number = prefix.find(id).map_or(id, |m| &id[m.end()..]);
+    let parsed_id = number
+        .parse::<i64>()
+        .map_err(|_| ValidationError::InvalidTicketId(id.to_string()))?;
+
+    if parsed_id <= 0 {
+        return Err(ValidationError::InvalidTicketId(id.to_string()));
+    }
+
+    Ok(parsed_id)
+}
+
+/// Pattern set with `set_project_name_pattern`, replacing the built-in one when present
+static PROJECT_NAME_PATTERN: RwLock<Option<Regex>> = RwLock::new(None);
+
+/// Accept project names matching `pattern` in full, instead of letters, numbers, hyphens,
+/// and underscores, for the rest of the process, as configured under `[validation]` in
+/// config.toml
+pub fn set_project_name_pattern(pattern: &str) -> Result<(), ValidationError> {
+    validate_regex(pattern)?;
+    let regex = validate_regex(&format!("^(?:{})$", pattern))?;
+    *PROJECT_NAME_PATTERN.write().unwrap_or_else(|e| e.into_inner()) = Some(regex);
+    Ok(())
+}
+
+/// The pattern set with `set_project_name_pattern`, as given
+pub fn project_name_pattern() -> Option<String> {
+    let pattern = PROJECT_NAME_PATTERN.read().unwrap_or_else(|e| e.into_inner());
+    pattern.as_ref().map(|regex| regex.as_str().trim_start_matches("^(?:").trim_end_matches(")$").to_string())
+}
+
+/// Validate project names: 1-50 chars of letters, numbers, hyphens, and underscores, or
+/// matching the pattern set with `set_project_name_pattern`
+pub fn validate_project_name(name: &str) -> Result<String, ValidationError> {
+    if name.is_empty() || name.len() > 50 {
+        return Err(ValidationError::InvalidProjectName(name.to_string()));
+    }
+
+    let matches = match &*PROJECT_NAME_PATTERN.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(regex) => regex.is_match(name),
+        None => Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap().is_match(name),
+    };
+    if !matches {
+        return Err(ValidationError::InvalidProjectName(name.to_string()));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Validate time values: non-negative, reasonable limits
+pub fn validate_time(hours: i32, minutes: i32) -> Result<(i32, i32), ValidationError> {
+    if !(0..=24).contains(&hours) || !(0..=59).contains(&minutes) {
+        return Err(ValidationError::InvalidTime(format!(
+            "{}h {}m",
+            hours, minutes
+        )));
+    }
+
+    Ok((hours, minutes))
+}
+
+/// Validate a duration in minutes as hours and minutes, like `validate_time`; amounts too
+/// large to split are refused the same way
+pub fn validate_minutes(total: i64) -> Result<(i32, i32), ValidationError> {
+    match (i32::try_from(total / 60), i32::try_from(total % 60)) {
+        (Ok(hours), Ok(minutes)) => validate_time(hours, minutes),
+        _ => Err(ValidationError::InvalidTime(format!("{}h {}m", total / 60, total % 60))),
+    }
+}
+
+/// Validate a priority, ignoring case
+pub fn validate_priority(priority: &str) -> Result<String, ValidationError> {
+    let lower = priority.trim().to_lowercase();
+    if PRIORITIES.contains(&lower.as_str()) {
+        Ok(lower)
+    } else {
+        Err(ValidationError::InvalidPriority(priority.to_string()))
+    }
+}
+
+/// Validate a severity such as `S2`, ignoring case; a bare `2` means `S2`
+pub fn validate_severity(severity: &str) -> Result<String, ValidationError> {
+    let upper = severity.trim().to_uppercase();
+    let upper = if upper.starts_with('S') { upper } else { format!("S{}", upper) };
+    if SEVERITIES.contains(&upper.as_str()) {
+        Ok(upper)
+    } else {
+        Err(ValidationError::InvalidSeverity(severity.to_string()))
+    }
+}
+
+/// Ticket fields `ltm report score` ranks the backlog by
+pub const SCORE_FIELDS: &[&str] = &["reach", "impact", "confidence", "effort"];
+
+/// What a scoring field accepts, for error messages
+pub fn score_range(field: &str) -> &'static str {
+    match field {
+        "reach" => "a whole number of people or events per quarter, like 500",
+        "impact" => "a number from 0.25 (minimal) to 3 (massive)",
+        "confidence" => "a percentage from 0 to 100, like 80%",
+        _ => "a positive number of person-weeks, like 2.5",
+    }
+}
+
+/// Validate a value for one of the `SCORE_FIELDS`; confidence may end in "%"
+pub fn validate_score(field: &str, input: &str) -> Result<f64, ValidationError> {
+    let number = input.trim().trim_end_matches('%').trim().parse::<f64>().ok().filter(|n| n.is_finite());
+    let valid = match (field, number) {
+        ("reach", Some(n)) => n >= 0.0 && n.fract() == 0.0,
+        ("impact", Some(n)) => (0.25..=3.0).contains(&n),
+        ("confidence", Some(n)) => (0.0..=100.0).contains(&n),
+        ("effort", Some(n)) => n > 0.0,
+        _ => false,
+    };
+    match number {
+        Some(n) if valid => Ok(n),
+        _ => Err(ValidationError::InvalidScore { field: field.to_string(), value: input.to_string() }),
+    }
+}
+
+/// Validate a tag: alphanumeric, hyphens, underscores, 1-30 chars; stored lowercase
+pub fn validate_tag(tag: &str) -> Result<String, ValidationError> {
+    let regex = Regex::new(r"^[a-zA-Z0-9_-]{1,30}$").unwrap();
+    if !regex.is_match(tag) {
+        return Err(ValidationError::InvalidTag(tag.to_string()));
+    }
+    Ok(tag.to_lowercase())
+}
+
+/// Validate a component name: a letter, then up to 29 letters, digits, hyphens, or
+/// underscores; stored lowercase
+pub fn validate_component(name: &str) -> Result<String, ValidationError> {
+    let lower = name.trim().to_lowercase();
+    if Regex::new(r"^[a-z][a-z0-9_-]{0,29}$").unwrap().is_match(&lower) {
+        Ok(lower)
+    } else {
+        Err(ValidationError::InvalidComponent(name.to_string()))
+    }
+}
+
+/// Validate a release name such as `v1.4` or `2024.06`: a letter or digit, then up to 29
+/// letters, digits, dots, hyphens, or underscores; case is kept
+pub fn validate_release(name: &str) -> Result<String, ValidationError> {
+    let name = name.trim();
+    if Regex::new(r"^[A-Za-z0-9][A-Za-z0-9._-]{0,29}$").unwrap().is_match(name) {
+        Ok(name.to_string())
+    } else {
+        Err(ValidationError::InvalidRelease(name.to_string()))
+    }
+}
+
+/// Validate a time of day given as HH:MM on a 24-hour clock
+pub fn validate_time_of_day(input: &str) -> Result<NaiveTime, ValidationError> {
+    NaiveTime::parse_from_str(input.trim(), "%H:%M").map_err(|_| ValidationError::InvalidTime(input.to_string()))
+}
+
+/// Validate status: must be one of the predefined values
+pub fn validate_status(status: &str) -> Result<String, ValidationError> {
+    let lowercase_status = status.to_lowercase();
+    let statuses = valid_statuses();
+    if statuses.contains(&lowercase_status) {
+        return Ok(lowercase_status);
+    }
+    if let Some(target) = status_alias(&lowercase_status).filter(|target| statuses.contains(target)) {
+        return Ok(target);
+    }
+
+    // Try to provide helpful suggestions using fuzzy matching
+    let mut suggestions = statuses
+        .iter()
+        .map(|s| (s, levenshtein(status, s)))
+        .collect::<Vec<_>>();
+    suggestions.sort_by_key(|&(_, dist)| dist);
+
+    Err(ValidationError::InvalidStatus(status.to_string()))
+}
+
+/// Validate a date given as YYYY-MM-DD or a relative form, resolved against the local date
+pub fn validate_date(input: &str) -> Result<NaiveDate, ValidationError> {
+    parse_date_relative_to(input, Local::now().date_naive())
+}
+
+/// Parse a date relative to `today`: YYYY-MM-DD, today/tomorrow/yesterday,
+/// a weekday name (next occurrence, including today), or an offset like +3d/-1w
+pub fn parse_date_relative_to(input: &str, today: NaiveDate) -> Result<NaiveDate, ValidationError> {
+    let trimmed = input.trim().to_lowercase();
+    let invalid = || ValidationError::InvalidDate(input.to_string());
+
+    match trimmed.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Ok(weekday) = trimmed.parse::<Weekday>() {
+        let days_ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return Ok(today + Duration::days(days_ahead as i64));
+    }
+
+    if let Some(offset) = trimmed.strip_prefix('+') {
+        let period = validate_period(offset).map_err(|_| invalid())?;
+        return Ok(today + Duration::days(period.num_days()));
+    }
+    if let Some(offset) = trimmed.strip_prefix('-') {
+        let period = validate_period(offset).map_err(|_| invalid())?;
+        return Ok(today - Duration::days(period.num_days()));
+    }
+
+    NaiveDate::parse_from_str(&trimmed, "%Y-%m-%d").map_err(|_| invalid())
+}
+
+/// When time logged for `day` is recorded, in UTC: `time` in local time, or midday, which
+/// keeps the UTC timestamp on the same calendar day in reports
+pub fn logged_at_local(day: NaiveDate, time: Option<NaiveTime>) -> Result<NaiveDateTime, ValidationError> {
+    let time = time.unwrap_or_else(|| NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+    Local
+        .from_local_datetime(&day.and_time(time))
+        .earliest()
+        .map(|at| at.naive_utc())
+        .ok_or_else(|| ValidationError::InvalidTime(time.format("%H:%M").to_string()))
+}
+
+/// Validate a point in time and return it in UTC: an RFC 3339 timestamp, a local
+/// `YYYY-MM-DD HH:MM[:SS]`, or any date `validate_date` accepts (local midnight)
+pub fn validate_timestamp(input: &str) -> Result<NaiveDateTime, ValidationError> {
+    let trimmed = input.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(at.naive_utc());
+    }
+    let local = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(trimmed, format).ok())
+        .map_or_else(|| validate_date(trimmed).map(|date| date.and_time(NaiveTime::MIN)), Ok)?;
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|at| at.naive_utc())
+        .ok_or_else(|| ValidationError::InvalidDate(input.to_string()))
+}
+
+/// Validate a month given as YYYY-MM, returning its first day
+pub fn validate_month(input: &str) -> Result<NaiveDate, ValidationError> {
+    NaiveDate::parse_from_str(&format!("{}-01", input.trim()), "%Y-%m-%d")
+        .map_err(|_| ValidationError::InvalidMonth(input.to_string()))
+}
+
+/// Validate an hourly rate such as "95" or "87.50"
+pub fn validate_rate(input: &str) -> Result<f64, ValidationError> {
+    match input.trim().parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok((rate * 100.0).round() / 100.0),
+        _ => Err(ValidationError::InvalidRate(input.to_string())),
+    }
+}
+
+/// Check that a date range runs forwards
+pub fn validate_date_range(from: NaiveDate, to: NaiveDate) -> Result<(NaiveDate, NaiveDate), ValidationError> {
+    if from > to {
+        return Err(ValidationError::InvalidDateRange { from, to });
+    }
+    Ok((from, to))
+}
+
+/// Validate a period such as "30m", "12h", "3d", or "2w"
+pub fn validate_period(input: &str) -> Result<Duration, ValidationError> {
+    let trimmed = input.trim();
+    let invalid = || ValidationError::InvalidPeriod(input.to_string());
+
+    let unit_start = trimmed.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (amount, unit) = trimmed.split_at(unit_start);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+    match unit {
+        "m" | "min" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Validate a link: a scheme such as `https://` followed by an address, without spaces
+pub fn validate_url(url: &str) -> Result<String, ValidationError> {
+    let trimmed = url.trim();
+    let regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*://\S+$").unwrap();
+    if !regex.is_match(trimmed) {
+        return Err(ValidationError::InvalidUrl(url.to_string()));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Compile a regular expression given on the command line
+pub fn validate_regex(pattern: &str) -> Result<Regex, ValidationError> {
+    Regex::new(pattern).map_err(|e| ValidationError::InvalidRegex {
+        pattern: pattern.to_string(),
+        // regex reports the pattern and a caret line first; keep only the final "error: ..." line
+        reason: e.to_string().lines().last().unwrap_or_default().trim().trim_start_matches("error: ").to_string(),
+    })
+}
+
+/// Override the maximum length of a field for the rest of the process, as configured under
+/// `[limits]` in config.toml
+pub fn set_max_length(field_type: ContentType, max: usize) {
+    MAX_LENGTHS.write().unwrap_or_else(|e| e.into_inner()).insert(field_type.key(), max);
+}
+
+/// Length of text as a reader counts it: `日本語` is 3 and `👩‍💻` is 1, where `str::len`
+/// counts bytes
+pub fn content_length(content: &str) -> usize {
+    content.graphemes(true).count()
+}
+
+/// Validate content length for different field types
+pub fn validate_content_length(
+    content: &str,
+    field_type: ContentType,
+) -> Result<String, ValidationError> {
+    let (min, max) = field_type.limits();
+    let len = content_length(content);
+
+    if len < min || len > max {
+        return Err(ValidationError::InvalidContentLength {
+            field_type: field_type.name().to_string(),
+            min,
+            max,
+        });
+    }
+
+    Ok(content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticket_id_validation() {
+        assert!(validate_ticket_id("1").is_ok());
+        assert!(validate_ticket_id("123").is_ok());
+        assert_eq!(validate_ticket_id("1").unwrap(), 1);
+        
+        assert!(validate_ticket_id("abc").is_err());
+        assert!(validate_ticket_id("0").is_err());
+        assert!(validate_ticket_id("-1").is_err());
+        assert_eq!(validate_ticket_id("WEB-12").unwrap(), 12);
+        assert!(validate_ticket_id("WEB-").is_err());
+        assert!(validate_ticket_id("").is_err());
+    }
+
+    #[test]
+    fn test_project_name_validation() {
+        // Valid names
+        assert!(validate_project_name("valid-project").is_ok());
+        assert!(validate_project_name("my_project").is_ok());
+        assert!(validate_project_name("project123").is_ok());
+        assert!(validate_project_name("a").is_ok());
+        
+        // Invalid names
+        assert!(validate_project_name("invalid project!").is_err());
+        assert!(validate_project_name("project with spaces").is_err());
+        assert!(validate_project_name("project@home").is_err());
+        assert!(validate_project_name("").is_err());
+        assert!(validate_project_name(&"a".repeat(51)).is_err());
+    }
+
+    #[test]
+    fn test_time_validation() {
+        // Valid times
+        assert!(validate_time(0, 0).is_ok());
+        assert!(validate_time(24, 59).is_ok());
+        assert!(validate_time(8, 30).is_ok());
+        
+        // Invalid times
+        assert!(validate_time(-1, 0).is_err());
+        assert!(validate_time(25, 0).is_err());
+        assert!(validate_time(0, -1).is_err());
+        assert!(validate_time(0, 60).is_err());
+
+        assert_eq!(validate_minutes(150).unwrap(), (2, 30));
+        // Not cut down to 5h by the conversion
+        assert!(validate_minutes(4294967301 * 60).is_err());
+        assert!(validate_minutes(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_status_validation() {
+        // Valid statuses
+        assert!(validate_status("open").is_ok());
+        assert!(validate_status("in-progress").is_ok());
+        assert!(validate_status("testing").is_ok());
+        assert!(validate_status("blocked").is_ok());
+        assert!(validate_status("closed").is_ok());
+        assert!(validate_status("cancelled").is_ok());
+        assert!(validate_status("wontfix").is_ok());
+        
+        // Case insensitive
+        assert!(validate_status("OPEN").is_ok());
+        assert!(validate_status("In-Progress").is_ok());
+        
+        // Invalid statuses
+        assert!(validate_status("invalid").is_err());
+        assert!(validate_status("").is_err());
+
+        // Aliases map to the status they stand for, and only to existing statuses
+        set_status_alias("WIP", "in-progress");
+        set_status_alias("icebox", "someday");
+        assert_eq!(validate_status("wip").unwrap(), "in-progress");
+        assert_eq!(resolve_status_alias("Wip"), "in-progress");
+        assert_eq!(resolve_status_alias("Closed"), "closed");
+        assert!(matches!(validate_status("icebox"), Err(ValidationError::InvalidStatus(s)) if s == "icebox"));
+    }
+
+    #[test]
+    fn test_content_length_validation() {
+        // Valid content
+        assert!(validate_content_length("Valid name", ContentType::TicketName).is_ok());
+        assert!(validate_content_length("Valid description", ContentType::Description).is_ok());
+        assert!(validate_content_length("Valid comment", ContentType::Comment).is_ok());
+        
+        // Invalid content - too short
+        assert!(validate_content_length("", ContentType::TicketName).is_err());
+        assert!(validate_content_length("", ContentType::Description).is_err());
+        assert!(validate_content_length("", ContentType::Comment).is_err());
+        
+        // Invalid content - too long
+        assert!(validate_content_length(&"a".repeat(101), ContentType::TicketName).is_err());
+        assert!(validate_content_length(&"a".repeat(2001), ContentType::Description).is_err());
+        assert!(validate_content_length(&"a".repeat(1001), ContentType::Comment).is_err());
+
+        // Characters count, not bytes: 100 CJK characters or emoji fit a 100-character name
+        assert!(validate_content_length(&"漢".repeat(100), ContentType::TicketName).is_ok());
+        assert!(validate_content_length(&"👩‍💻".repeat(100), ContentType::TicketName).is_ok());
+        assert!(validate_content_length(&"漢".repeat(101), ContentType::TicketName).is_err());
+        assert_eq!(content_length("Café ☕"), 6);
+
+        // Only timer notes are overridden, so tests running alongside keep the defaults
+        set_max_length(ContentType::TimerNote, 500);
+        assert_eq!(ContentType::TimerNote.limits(), (1, 500));
+        assert!(validate_content_length(&"a".repeat(500), ContentType::TimerNote).is_ok());
+        assert_eq!(ContentType::from_key("timer_note"), Some(ContentType::TimerNote));
+        assert_eq!(ContentType::from_key("title"), None);
+    }
+
+    #[test]
+    fn test_date_parsing() {
+        // 2024-05-08 is a Wednesday
+        let today = NaiveDate::from_ymd_opt(2024, 5, 8).unwrap();
+        let parse = |s: &str| parse_date_relative_to(s, today).unwrap();
+
+        assert_eq!(parse("2024-06-01"), NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert_eq!(parse("today"), today);
+        assert_eq!(parse("Tomorrow"), NaiveDate::from_ymd_opt(2024, 5, 9).unwrap());
+        assert_eq!(parse("friday"), NaiveDate::from_ymd_opt(2024, 5, 10).unwrap());
+        assert_eq!(parse("wed"), today);
+        assert_eq!(parse("+2w"), NaiveDate::from_ymd_opt(2024, 5, 22).unwrap());
+        assert_eq!(parse("-1d"), NaiveDate::from_ymd_opt(2024, 5, 7).unwrap());
+
+        assert!(parse_date_relative_to("someday", today).is_err());
+        assert!(parse_date_relative_to("2024-13-01", today).is_err());
+
+        let noon = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(validate_timestamp("2024-05-01T12:00:00Z").unwrap(), noon);
+        assert_eq!(validate_timestamp("2024-05-01T14:00:00+02:00").unwrap(), noon);
+        let local = |at: NaiveDateTime| Local.from_local_datetime(&at).unwrap().naive_utc();
+        assert_eq!(validate_timestamp("2024-05-01 12:00").unwrap(), local(noon));
+        assert_eq!(validate_timestamp("2024-05-01").unwrap(), local(noon.date().and_time(NaiveTime::MIN)));
+        assert!(validate_timestamp("2024-05-01 25:00").is_err());
+
+        assert_eq!(validate_month("2024-05").unwrap(), NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+        assert!(matches!(validate_month("2024-13"), Err(ValidationError::InvalidMonth(_))));
+        assert!(validate_month("2024-05-01").is_err());
+        assert_eq!(validate_rate(" 87.5 ").unwrap(), 87.5);
+        assert!(matches!(validate_rate("-5"), Err(ValidationError::InvalidRate(_))));
+        assert!(validate_rate("free").is_err());
+    }
+
+    #[test]
+    fn test_score_validation() {
+        assert_eq!(validate_score("reach", "500").unwrap(), 500.0);
+        assert!(validate_score("reach", "2.5").is_err());
+        assert_eq!(validate_score("impact", "0.25").unwrap(), 0.25);
+        assert!(validate_score("impact", "5").is_err());
+        assert_eq!(validate_score("confidence", "80%").unwrap(), 80.0);
+        assert!(validate_score("confidence", "120").is_err());
+        assert_eq!(validate_score("effort", " 1.5 ").unwrap(), 1.5);
+        let error = validate_score("effort", "0").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid effort '0'. Use a positive number of person-weeks, like 2.5.");
+    }
+
+    #[test]
+    fn test_period_validation() {
+        assert_eq!(validate_period("3d").unwrap(), Duration::days(3));
+        assert_eq!(validate_period("12h").unwrap(), Duration::hours(12));
+        assert_eq!(validate_period("2w").unwrap(), Duration::weeks(2));
+
+        assert!(validate_period("d").is_err());
+        assert!(validate_period("3").is_err());
+        assert!(validate_period("3y").is_err());
+    }
+
+    #[test]
+    fn test_time_of_day_validation() {
+        assert_eq!(validate_time_of_day("09:30").unwrap(), NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert_eq!(validate_time_of_day(" 17:05 ").unwrap(), NaiveTime::from_hms_opt(17, 5, 0).unwrap());
+        assert!(validate_time_of_day("25:00").is_err());
+        assert!(validate_time_of_day("noon").is_err());
+    }
+
+    #[test]
+    fn test_priority_and_tag_validation() {
+        assert_eq!(validate_priority("High").unwrap(), "high");
+        assert!(validate_priority("urgent").is_err());
+        assert_eq!(validate_severity("s1").unwrap(), "S1");
+        assert_eq!(validate_severity(" 3").unwrap(), "S3");
+        assert!(validate_severity("S5").is_err());
+        assert!(validate_severity("high").is_err());
+        assert_eq!(validate_component(" Frontend").unwrap(), "frontend");
+        assert!(validate_component("2fa").is_err());
+        assert!(validate_component("front end").is_err());
+        assert_eq!(validate_release(" v1.4.0-rc1").unwrap(), "v1.4.0-rc1");
+        assert!(validate_release(".v1").is_err());
+        assert!(validate_release("v 1").is_err());
+        assert_eq!(validate_tag("Auth").unwrap(), "auth");
+        assert!(validate_tag("front end").is_err());
+        assert!(validate_tag("").is_err());
+    }
+
+    #[test]
+    fn test_url_validation() {
+        assert_eq!(validate_url(" https://example.com/pr/7 ").unwrap(), "https://example.com/pr/7");
+        assert!(validate_url("file:///home/me/notes.md").is_ok());
+        assert!(validate_url("example.com").is_err());
+        assert!(validate_url("https://").is_err());
+        assert!(validate_url("--help").is_err());
+        assert!(validate_url("https://example.com/a b").is_err());
+    }
+
+    #[test]
+    fn test_last_ticket() {
+        set_last_ticket(None);
+        assert!(matches!(validate_ticket_id("@last"), Err(ValidationError::NoLastTicket)));
+        set_last_ticket(Some(42));
+        assert_eq!(validate_ticket_id("@last").unwrap(), 42);
+        assert_eq!(validate_ticket_id("@LAST").unwrap(), 42);
+        assert!(validate_ticket_id("@first").is_err());
+    }
+
+    #[test]
+    fn test_project_name_pattern() {
+        // Widens the built-in rule without admitting any name test_project_name_validation rejects
+        set_project_name_pattern(r"[a-z0-9_./-]+").unwrap();
+        assert_eq!(validate_project_name("acme.web/api").unwrap(), "acme.web/api");
+        assert!(validate_project_name("project@home").is_err());
+        assert!(validate_project_name(&"a".repeat(51)).is_err());
+        assert!(matches!(set_project_name_pattern("[a-z"), Err(ValidationError::InvalidRegex { .. })));
+    }
+
+    #[test]
+    fn test_regex_validation() {
+        assert!(validate_regex("error (500|502)").unwrap().is_match("got error 502 again"));
+        match validate_regex("error (500") {
+            Err(ValidationError::InvalidRegex { pattern, reason }) => {
+                assert_eq!(pattern, "error (500");
+                assert!(reason.contains("unclosed group"), "{}", reason);
+            }
+            other => panic!("expected an invalid regex, got {:?}", other),
+        }
+    }
+}
\ No newline at end of file
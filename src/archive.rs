@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::Config;
+use crate::db::Database;
+
+/// Layout version of the archive itself, raised when its contents change shape
+pub const FORMAT: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = "tickets.db";
+const CONFIG: &str = "config.toml";
+
+/// What an archive holds, written as `manifest.json` next to the database and config:
+///
+/// ```text
+/// manifest.json   format, ltm version, and schema version
+/// tickets.db      a consistent snapshot of the database
+/// config.toml     the config file, when there is one
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    /// Version of ltm that wrote the archive
+    pub ltm_version: String,
+    /// Newest migration applied to the archived database
+    pub schema_version: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Manifest {
+    /// Fail unless this build of ltm can restore the archive; older archives are upgraded
+    pub fn check(&self) -> Result<()> {
+        let newer = self.format > FORMAT || self.schema_version > Database::latest_schema_version();
+        if newer {
+            anyhow::bail!(
+                "The archive was written by ltm {}, which is newer than this one ({}); upgrade ltm to restore it",
+                self.ltm_version,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// An archive unpacked into a temporary directory, removed again on drop
+pub struct Unpacked {
+    dir: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl Unpacked {
+    pub fn database(&self) -> PathBuf {
+        self.dir.join(DATABASE)
+    }
+
+    /// The archived config file, if the archive has one
+    pub fn config(&self) -> Option<PathBuf> {
+        Some(self.dir.join(CONFIG)).filter(|path| path.exists())
+    }
+}
+
+impl Drop for Unpacked {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Write the database and config file to a zstd-compressed tar archive at `path`
+pub async fn create(db: &Database, path: &Path) -> Result<Manifest> {
+    let dir = staging_dir()?;
+    let result = create_in(db, &dir, path).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+async fn create_in(db: &Database, dir: &Path, path: &Path) -> Result<Manifest> {
+    db.backup_to(&dir.join(DATABASE)).await?;
+    let mut files = vec![MANIFEST, DATABASE];
+    if let Some(config) = Config::path().filter(|p| p.exists()) {
+        std::fs::copy(&config, dir.join(CONFIG))
+            .with_context(|| format!("Could not copy config file {}", config.display()))?;
+        files.push(CONFIG);
+    }
+
+    let manifest = Manifest {
+        format: FORMAT,
+        ltm_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: db.schema_version().await,
+        created_at: Utc::now(),
+    };
+    std::fs::write(dir.join(MANIFEST), serde_json::to_string_pretty(&manifest)?)?;
+
+    let archive = std::path::absolute(path)?;
+    let mut args = vec![OsStr::new("--zstd"), OsStr::new("-cf"), archive.as_os_str(), OsStr::new("-C"), dir.as_os_str()];
+    args.extend(files.into_iter().map(OsStr::new));
+    tar(&args)?;
+    Ok(manifest)
+}
+
+/// Unpack an archive and read its manifest, failing if it is not an ltm archive or is too new
+pub fn unpack(path: &Path) -> Result<Unpacked> {
+    if !path.exists() {
+        anyhow::bail!("Archive {} not found", path.display());
+    }
+    let dir = staging_dir()?;
+    match unpack_in(&dir, path) {
+        Ok(manifest) => Ok(Unpacked { dir, manifest }),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            Err(e)
+        }
+    }
+}
+
+fn unpack_in(dir: &Path, path: &Path) -> Result<Manifest> {
+    let archive = std::path::absolute(path)?;
+    tar(&[OsStr::new("--zstd"), OsStr::new("-xf"), archive.as_os_str(), OsStr::new("-C"), dir.as_os_str()])
+        .with_context(|| format!("Could not unpack {}", path.display()))?;
+
+    let text = std::fs::read_to_string(dir.join(MANIFEST))
+        .with_context(|| format!("{} is not an ltm archive (no {})", path.display(), MANIFEST))?;
+    let manifest: Manifest =
+        serde_json::from_str(&text).with_context(|| format!("Invalid {} in {}", MANIFEST, path.display()))?;
+    manifest.check()?;
+    if !dir.join(DATABASE).exists() {
+        anyhow::bail!("{} has no {}", path.display(), DATABASE);
+    }
+    Ok(manifest)
+}
+
+fn staging_dir() -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("ltm-archive-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Could not create {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// Run the system `tar`, which needs zstd support (GNU tar 1.31+ or bsdtar)
+fn tar(args: &[&OsStr]) -> Result<()> {
+    let output = Command::new("tar").args(args).output().context("Could not run tar; is it installed?")?;
+    if !output.status.success() {
+        anyhow::bail!("tar failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_check() {
+        let manifest = Manifest {
+            format: FORMAT,
+            ltm_version: "0.1.0".to_string(),
+            schema_version: Some(20240320000000),
+            created_at: Utc::now(),
+        };
+        assert!(manifest.check().is_ok());
+        assert!(Manifest { schema_version: None, ..manifest.clone() }.check().is_ok());
+        assert!(Manifest { format: FORMAT + 1, ..manifest.clone() }.check().is_err());
+        assert!(Manifest { schema_version: Some(99990101000000), ..manifest }.check().is_err());
+    }
+}
@@ -0,0 +1,86 @@
+/// A ticket to create from one line of a Markdown task list
+#[derive(Debug, Default, PartialEq)]
+pub struct BulkTicket {
+    pub name: String,
+    /// Written as `- [x]`
+    pub done: bool,
+    /// Indented items below it, with whether each is checked, when nesting
+    pub subtasks: Vec<(String, bool)>,
+}
+
+/// Read tickets from a Markdown task list such as a `todo.md`.
+///
+/// Each `- [ ]` or `- [x]` item becomes a ticket, as does a plain `-`, `*`, `+`, or `1.`
+/// item or a line of text. Blank lines, headings, and code fences are skipped. With `nest`,
+/// items indented below another become its subtasks, however deep they go.
+pub fn parse_task_list(text: &str, nest: bool) -> Vec<BulkTicket> {
+    let mut tickets: Vec<BulkTicket> = Vec::new();
+    let mut parent_indent = 0;
+
+    for line in text.lines() {
+        let content = line.trim_start();
+        let heading = content.trim_start_matches('#');
+        if content.is_empty() || content.starts_with("```") || (heading.len() < content.len() && (heading.is_empty() || heading.starts_with(' '))) {
+            continue;
+        }
+        let indent: usize = line[..line.len() - content.len()].chars().map(|c| if c == '\t' { 4 } else { 1 }).sum();
+        let (name, done) = parse_item(content);
+        if name.is_empty() {
+            continue;
+        }
+        match tickets.last_mut() {
+            Some(parent) if nest && indent > parent_indent => parent.subtasks.push((name, done)),
+            _ => {
+                parent_indent = indent;
+                tickets.push(BulkTicket { name, done, subtasks: Vec::new() });
+            }
+        }
+    }
+    tickets
+}
+
+/// The text of a list item without its marker and checkbox, and whether it is checked
+fn parse_item(content: &str) -> (String, bool) {
+    let ordered = content.find(['.', ')']).filter(|&end| end > 0 && content[..end].chars().all(|c| c.is_ascii_digit()));
+    let rest = match ordered {
+        Some(end) => &content[end + 1..],
+        None => content.strip_prefix(['-', '*', '+']).filter(|rest| rest.is_empty() || rest.starts_with(' ')).unwrap_or(content),
+    }
+    .trim_start();
+    let (rest, done) = match rest.get(..3) {
+        Some("[ ]") => (&rest[3..], false),
+        Some("[x]" | "[X]") => (&rest[3..], true),
+        _ => (rest, false),
+    };
+    (rest.trim().to_string(), done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODO: &str = "# Launch\n\n- [ ] Write docs\n  - [x] Outline\n  - [ ] Screenshots\n    * Dark mode ones\n- [x] Pick a name\n\nFix the 404 page\n1. Ship it\n";
+
+    #[test]
+    fn test_parse_task_list() {
+        let names = |tickets: &[BulkTicket]| tickets.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+
+        let flat = parse_task_list(TODO, false);
+        assert_eq!(
+            names(&flat),
+            ["Write docs", "Outline", "Screenshots", "Dark mode ones", "Pick a name", "Fix the 404 page", "Ship it"]
+        );
+        assert!(flat.iter().all(|t| t.subtasks.is_empty()));
+        assert!(flat[1].done && flat[4].done && !flat[0].done);
+
+        let nested = parse_task_list(TODO, true);
+        assert_eq!(names(&nested), ["Write docs", "Pick a name", "Fix the 404 page", "Ship it"]);
+        assert_eq!(
+            nested[0].subtasks,
+            [("Outline".to_string(), true), ("Screenshots".to_string(), false), ("Dark mode ones".to_string(), false)]
+        );
+
+        // Markers without text are dropped, and "-5 degrees" is not a list item nor "#12" a heading
+        assert_eq!(names(&parse_task_list("- [ ]\n-\n-5 degrees\n#12 follow-up\n", false)), ["-5 degrees", "#12 follow-up"]);
+    }
+}
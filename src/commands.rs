@@ -1,28 +1,297 @@
-use anyhow::Result;
-use chrono::{DateTime, Duration, Utc};
-use clap::{CommandFactory, Parser, Subcommand};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use edit::edit;
-use std::collections::HashMap;
 
-use crate::db::Database;
-use crate::formatting::{format_ticket_list, format_ticket_details};
+use crate::archive;
+use crate::backup;
+use crate::config::{Config, CONFIG_KEYS};
+use crate::db::{Database, DateBounds};
+use crate::duration::{format_duration, format_minutes, HumanDuration};
+use crate::project_settings::{ProjectSettings, SETTING_KEYS};
+use crate::models::{gantt_span, missing_score_fields, month_starts, ticket_aging, roadmap_item, ticket_score, time_in_status, transitive_blockers, Release, ScoringMethod, Ticket, TimeLogChange, AGE_BUCKET_LABELS};
+use crate::formatting::{colorize_status, format_aging, format_board, format_cleanup_report, format_dependency_graph, format_due_date, format_estimate, format_gantt, format_gantt_mermaid, format_heatmap, format_related_tickets, format_release, format_roadmap, format_scores, format_checklist, format_delete_impact, format_status_comment, format_status_time, format_ticket_list, format_trends, CleanupOutcome};
 // JSON formatting imports are used via fully qualified paths in the code
 use crate::validation::{
     format_validation_error, validate_content_length, validate_project_name,
-    validate_status, validate_ticket_id, ContentType, ValidationError,
+    is_finished_status, resolve_status_alias, validate_component, validate_date, validate_date_range, validate_month, validate_timestamp, validate_period, validate_priority, validate_rate, validate_regex, validate_release, validate_score, validate_severity, validate_status, validate_tag, validate_minutes, validate_time_of_day, validate_url, validate_ticket_id,
+    logged_at_local,
+    ContentType,
+    last_ticket, set_last_ticket, valid_statuses, ValidationError,
 };
 use crate::interactive;
 use crate::feedback;
+use crate::git_hooks;
+use crate::invoice::{self, Invoice};
+use crate::jira;
+use crate::linear;
+use crate::sync;
+use crate::server;
+use crate::daemon::{self, PromptState};
+use crate::completion::{self, CompletionKind};
 use crate::suggestions;
+use crate::i18n::tf;
+use crate::search::Matcher;
+use crate::bulk::{parse_task_list, BulkTicket};
+use crate::shortcut::{self, Shortcut};
+use crate::quick::parse_quick_add;
+use crate::log_line::{duration_word, parse_log_line};
+use crate::timesheet::{format_timesheet, parse_timesheet, plan_changes, TimesheetRow};
+use crate::time_import::{read_time_csv, ColumnMap};
+use crate::email::format_report_email;
+use crate::webhook;
+use crate::next;
+use crate::snippet;
+use crate::status_line;
+use crate::remote::Route;
+use crate::render::{OutputFormat, Renderer};
+use crate::results::{
+    Agenda, CommentList, CurrentTimer, EventList, Messages, MonthCalendar, NextTickets, ProjectList, ProjectSummaryResult, RecentList,
+    Report, TicketDetails, TicketList, TimeExport, TimeReport, TimerList, WeekPlan,
+};
+use crate::output::{self, icon, with_icon};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::{debug, trace};
 use std::fs;
+use std::io::{IsTerminal, Write};
+use colored::Colorize;
 use clap_complete::{generate, generate_to};
 use clap_complete::shells::{Bash, Zsh, Fish, PowerShell, Elvish};
 
+/// Exit status of `ltm time current` when no timer is running; 1 and 2 are taken by
+/// errors and usage mistakes
+pub const NO_TIMER_EXIT: i32 = 3;
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Log progress to stderr (-v for steps and migrations, -vv for SQL statements with timings)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Append logs to this file instead of stderr
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<std::path::PathBuf>,
+
+    /// Screen-reader friendly output: labelled lines without emoji, boxes, or spinners
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Write results as text, JSON, pretty JSON, CSV, or YAML
+    #[arg(short, long, global = true, value_enum, default_value_t, value_name = "FORMAT", alias = "output-format")]
+    pub output: OutputFormat,
+
+    /// Change a locked ticket, or time logged in a month locked for billing
+    #[arg(long, global = true)]
+    pub unlock: bool,
+}
+
+/// Swap clap's suggestions for an unknown subcommand for `suggestions::suggest_subcommands`,
+/// which ranks them by similarity and fixes a mistyped action after the command too
+fn with_command_suggestions(mut error: clap::Error, command: &clap::Command, args: &[std::ffi::OsString]) -> clap::Error {
+    use clap::error::{ContextKind, ContextValue, ErrorKind};
+    if error.kind() != ErrorKind::InvalidSubcommand {
+        return error;
+    }
+    let Some(ContextValue::String(invalid)) = error.get(ContextKind::InvalidSubcommand).cloned() else {
+        return error;
+    };
+    let args: Vec<String> = args.iter().skip(1).map(|arg| arg.to_string_lossy().into_owned()).collect();
+    let suggestions = suggestions::suggest_subcommands(command, &args, &invalid);
+    if !suggestions.is_empty() {
+        error.insert(ContextKind::SuggestedSubcommand, ContextValue::Strings(suggestions));
+    }
+    error
+}
+
+/// Print the status line for `state`, from `format`, else `status_line` in the config file
+fn print_status_line(format: Option<&str>, config: &Config, state: &PromptState) {
+    let template = format.or(config.status_line.as_deref()).unwrap_or(status_line::DEFAULT_TEMPLATE);
+    let timer = state.timer.as_ref().map(|(timer, name)| (timer, name.as_str()));
+    println!("{}", status_line::render(template, timer, &state.counts, Utc::now().naive_utc()));
+}
+
+/// Print the timer in `state` for `ltm time current`, exiting with `NO_TIMER_EXIT` without one
+fn print_current_timer(renderer: &Renderer, state: &PromptState) {
+    renderer.print(&CurrentTimer { timer: state.timer.clone(), now: Utc::now().naive_utc() });
+    if state.timer.is_none() {
+        std::process::exit(NO_TIMER_EXIT);
+    }
+}
+
+impl Cli {
+    /// Parse the command line with the `[shortcuts]` from config.toml as extra subcommands,
+    /// exiting on usage errors and `--help`
+    pub fn parse_with_shortcuts(shortcuts: &BTreeMap<String, Shortcut>) -> Self {
+        Self::try_parse_with_shortcuts(std::env::args_os(), shortcuts).unwrap_or_else(|e| e.exit())
+    }
+
+    /// `parse_with_shortcuts` for the given arguments, starting with the binary name
+    pub fn try_parse_with_shortcuts<I, T>(args: I, shortcuts: &BTreeMap<String, Shortcut>) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let args: Vec<std::ffi::OsString> = args.into_iter().map(Into::into).collect();
+        let command = Cli::command().subcommands(shortcuts.iter().map(|(name, shortcut)| shortcut.command(name)));
+        let matches = command.clone().try_get_matches_from(&args).map_err(|e| with_command_suggestions(e, &command, &args))?;
+        let Some((name, sub)) = matches.subcommand().filter(|(name, _)| shortcuts.contains_key(*name)) else {
+            return Cli::from_arg_matches(&matches);
+        };
+        Ok(Cli {
+            command: Commands::Shortcut {
+                name: name.to_string(),
+                ticket_id: sub.get_one::<String>("ticket_id").cloned(),
+                message: sub.get_one::<String>("message").cloned(),
+            },
+            verbose: matches.get_count("verbose"),
+            log_file: matches.get_one::<std::path::PathBuf>("log_file").cloned(),
+            plain: matches.get_flag("plain"),
+            output: matches.get_one::<OutputFormat>("output").copied().unwrap_or_default(),
+            unlock: matches.get_flag("unlock"),
+        })
+    }
+
+    /// Refuse shortcuts that can't be typed, that would hide a built-in command, or that
+    /// set no status
+    pub fn check_shortcuts(shortcuts: &BTreeMap<String, Shortcut>) -> Result<()> {
+        let command = Cli::command();
+        for (name, shortcut) in shortcuts {
+            if !shortcut::is_valid_name(name) {
+                anyhow::bail!("Shortcut '{}' in config.toml: use lowercase letters, digits, and hyphens", name);
+            }
+            if command.find_subcommand(name).is_some() {
+                anyhow::bail!("Shortcut '{}' in config.toml clashes with the built-in 'ltm {}' command", name, name);
+            }
+            if shortcut.status.trim().is_empty() {
+                anyhow::bail!("Shortcut '{}' in config.toml needs a status", name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the command is polled by status bars and scripts and must skip extra work
+    /// such as the daily backup. A running `ltm daemon` answers these.
+    pub fn is_polled(&self) -> bool {
+        matches!(self.command, Commands::StatusLine { .. } | Commands::Time { action: TimeAction::Current { .. } })
+    }
+
+    /// Run a polled command from the prompt state a daemon sent, without opening the database
+    pub fn run_polled(self, config: &Config, state: &PromptState) -> Result<()> {
+        let renderer = Renderer::new(self.legacy_output().unwrap_or(self.output));
+        match self.command {
+            Commands::StatusLine { format } => print_status_line(format.as_deref(), config, state),
+            Commands::Time { action: TimeAction::Current { .. } } => print_current_timer(&renderer, state),
+            _ => anyhow::bail!("Only the status line and 'ltm time current' come from the daemon"),
+        }
+        Ok(())
+    }
+
+    /// Where the command runs when `[remote]` names a shared server: commands about this
+    /// machine (its config, files, repositories, and the server itself) stay local,
+    /// completions read the cached copy of the server's data, and exports to a file and
+    /// links opened in the browser read a freshly cached copy. The rest run on the server.
+    pub fn route(&self) -> Route {
+        match &self.command {
+            Commands::Config { .. }
+            | Commands::Serve { .. }
+            | Commands::Daemon { .. }
+            | Commands::Migrate { .. }
+            | Commands::Completions { .. }
+            | Commands::Archive { .. }
+            | Commands::Sync { .. }
+            | Commands::Git { .. } => Route::Local,
+            Commands::DynamicComplete { .. } => Route::Cache { refresh: false },
+            Commands::Export { file: Some(_), .. }
+            | Commands::Export { format: Some(_), .. }
+            | Commands::Time { action: TimeAction::Invoice { .. } }
+            | Commands::OpenUrl { .. } => Route::Cache { refresh: true },
+            _ => Route::Server,
+        }
+    }
+
+    /// File, or "-" for stdin, the command reads on the machine it runs on; in shared-server
+    /// mode the client reads it and sends the text as the command's stdin
+    pub fn client_file(&self) -> Option<&std::path::Path> {
+        match &self.command {
+            Commands::Ticket { action: TicketAction::CreateBulk { from_file, .. } } => Some(from_file.as_path()),
+            Commands::Time { action: TimeAction::Import { file, .. } } => Some(file.as_path()),
+            _ => None,
+        }
+    }
+
+    /// Why the command cannot run on a shared server, which would open `$EDITOR` or write a
+    /// file on its own machine instead of the client's; `None` for most commands
+    pub fn needs_client(&self) -> Option<&'static str> {
+        match &self.command {
+            Commands::Time { action: TimeAction::Edit { .. } } => Some("A shared server cannot open an editor for 'ltm time edit'"),
+            Commands::Add { description: None, .. }
+            | Commands::Ticket { action: TicketAction::Create { description: None, .. } }
+            | Commands::Update { target: UpdateTarget::Description { description: None, .. } } => {
+                Some("A shared server cannot open an editor for the description; give it on the command line")
+            }
+            Commands::Project { action: ProjectAction::Delete { export_first: Some(_), .. } } => {
+                Some("A shared server cannot write the --export-first file; save the project with 'ltm export --file' first")
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the command keeps redrawing with `--watch`
+    pub fn watches(&self) -> bool {
+        matches!(
+            self.command,
+            Commands::Board { watch: Some(_), .. }
+                | Commands::List { watch: Some(_), .. }
+                | Commands::Ticket { action: TicketAction::List { watch: Some(_), .. } }
+        )
+    }
+
+    /// Whether the command manages schema migrations itself, so they must not run on startup
+    pub fn is_migrate(&self) -> bool {
+        matches!(self.command, Commands::Migrate { .. })
+    }
+
+    /// Whether to warn about long-running timers first; not for commands that stop or cancel
+    /// timers, or whose output other programs read
+    fn checks_timers(&self) -> bool {
+        !self.is_polled()
+            && !matches!(
+                self.command,
+                Commands::Completions { .. }
+                | Commands::DynamicComplete { .. }
+                | Commands::Migrate { .. }
+                | Commands::Time { action: TimeAction::Stop { .. } | TimeAction::Cancel { .. } }
+            )
+    }
+
+    /// The output format picked with a command's own `--json`, `--json-pretty`, or `--csv`,
+    /// kept from before `-o` so existing scripts work, or with `time export --format`
+    fn legacy_output(&self) -> Option<OutputFormat> {
+        if let Commands::Time { action: TimeAction::Export { format: Some(format), .. } } = &self.command {
+            return Some(*format);
+        }
+        let (json, json_pretty, csv) = match &self.command {
+            Commands::List { json, json_pretty, .. }
+            | Commands::Show { json, json_pretty, .. }
+            | Commands::Proj { json, json_pretty, .. } => (*json, *json_pretty, false),
+            Commands::Events { json, .. } | Commands::Time { action: TimeAction::Current { json } } => (*json, false, false),
+            Commands::Time { action: TimeAction::Report { json, json_pretty, csv, .. } } => (*json, *json_pretty, *csv),
+            _ => (false, false, false),
+        };
+        if csv {
+            Some(OutputFormat::Csv)
+        } else if json_pretty {
+            Some(OutputFormat::JsonPretty)
+        } else if json {
+            Some(OutputFormat::Json)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -55,6 +324,59 @@ enum Commands {
         action: TimeAction,
     },
 
+    /// Areas within a project that tickets can be put in
+    Component {
+        #[command(subcommand)]
+        action: ComponentAction,
+    },
+
+    /// Versions of a project that tickets are planned for
+    Release {
+        #[command(subcommand)]
+        action: ReleaseAction,
+    },
+
+    /// Releases on a timeline by due date, with their progress and the ones at risk of
+    /// slipping given the open work's estimates
+    Roadmap {
+        /// Project name; every project's releases when left out
+        project: Option<String>,
+        /// Hours of work a day, on weekdays, counted as available before a due date
+        #[arg(long, default_value_t = 6.0)]
+        hours_per_day: f64,
+    },
+
+    /// Export the graph of ticket dependencies for Graphviz, with nodes colored by status,
+    /// e.g. `ltm graph webapp --release v1.4 | dot -Tsvg > blockers.svg`
+    Graph {
+        /// Project name; every project's dependencies when left out
+        project: Option<String>,
+        /// Only this release's tickets and everything they wait on
+        #[arg(long)]
+        release: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+    },
+
+    /// Close invoiced months so their time logs stay as billed
+    Billing {
+        #[command(subcommand)]
+        action: BillingAction,
+    },
+
+    /// Checklist steps inside a ticket
+    Check {
+        #[command(subcommand)]
+        action: CheckAction,
+    },
+
+    /// Reports on logged time
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
     /// Update ticket properties
     #[command(alias = "set")]
     Update {
@@ -71,8 +393,8 @@ enum Commands {
 
     /// Mark ticket as completed
     Complete {
-        /// Ticket ID
-        ticket_id: String,
+        /// Ticket ID (defaults to the focused ticket)
+        ticket_id: Option<String>,
     },
 
     /// Mark ticket as blocked
@@ -83,19 +405,132 @@ enum Commands {
         reason: Option<String>,
     },
 
+    /// Create a ticket from one line: "Fix login @webapp !high #auth due:friday est:2h"
+    #[command(alias = "q")]
+    Quick {
+        /// Ticket name with inline @project, !priority, #tags, due:date, and est:duration
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+        /// Create the ticket even if a similar one exists
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Start working on a ticket (sets in-progress + starts timer)
     Start {
-        /// Ticket ID
-        ticket_id: String,
+        /// Ticket ID (defaults to the focused ticket)
+        ticket_id: Option<String>,
+    },
+
+    /// Focus on a ticket so commands can omit its ID
+    Focus {
+        /// Ticket ID (shows the focused ticket if omitted)
+        ticket_id: Option<String>,
+        /// Stop focusing on any ticket
+        #[arg(long, conflicts_with = "ticket_id")]
+        clear: bool,
+    },
+
+    /// Show the focused ticket and its timer
+    Current,
+
+    /// Recommend what to work on next from priority, due date, age, and status
+    Next {
+        /// Only consider this project
+        project: Option<String>,
+        /// Start the recommended ticket (sets in-progress + starts timer)
+        #[arg(long)]
+        start: bool,
+        /// Number of alternatives to show after the recommendation
+        #[arg(short = 'n', long, default_value = "3")]
+        alternatives: usize,
+    },
+
+    /// Show what is due today, what is in progress, running timers, and time logged today
+    Today {
+        /// Only this project
+        project: Option<String>,
+    },
+
+    /// Lay out this week's due dates and estimated work from Monday to Sunday, overdue first
+    Week {
+        /// Only this project
+        project: Option<String>,
+        /// Plan next week instead
+        #[arg(long)]
+        next: bool,
+    },
+
+    /// Show a month calendar marking days with tickets due and days with time logged
+    Calendar {
+        /// Month as YYYY-MM (defaults to the current month)
+        month: Option<String>,
+        /// Only this project
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Show the month before
+        #[arg(long, conflicts_with = "next")]
+        prev: bool,
+        /// Show the month after
+        #[arg(long)]
+        next: bool,
+    },
+
+    /// Open one of a ticket's links in the browser
+    OpenUrl {
+        /// Ticket ID (defaults to the focused ticket)
+        ticket_id: Option<String>,
+        /// Which link to open, as numbered in `show`
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        link: u32,
+    },
+
+    /// Show tickets as a kanban board with one column per status
+    Board {
+        /// Project filter
+        project: Option<String>,
+        /// Redraw whenever the database changes, checking every SECONDS (default 2)
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+        watch: Option<u64>,
     },
 
     /// List all projects
     Projects,
 
+    /// List your starred tickets from every project
+    Starred,
+
+    /// List the tickets you viewed or changed last, newest first
+    Recent {
+        /// How many tickets to list
+        #[arg(short = 'n', long, default_value = "10", value_parser = clap::value_parser!(i64).range(1..))]
+        limit: i64,
+    },
+
     /// Show active timers
     #[command(alias = "timer")]
     Active,
 
+    /// Show the journal of recent changes, newest first
+    Events {
+        /// Only changes to this ticket and its comments, time logs, links, checklist, timer, and tags
+        #[arg(long)]
+        ticket: Option<String>,
+        /// How many entries to show
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+        limit: u32,
+        /// Same as `-o json`
+        #[arg(long, hide = true)]
+        json: bool,
+    },
+
+    /// Print a one-line summary for tmux, polybar, or a shell prompt
+    StatusLine {
+        /// Template with {timer}, {ticket}, {name}, {elapsed}, {open}, {due}, {overdue}
+        #[arg(long)]
+        format: Option<String>,
+    },
+
     /// Generate shell completion scripts
     Completions {
         /// Shell: bash|zsh|fish|powershell|elvish
@@ -103,6 +538,132 @@ enum Commands {
         /// Output directory (optional). If omitted, prints to stdout.
         out_dir: Option<String>,
     },
+
+    /// A shortcut from `[shortcuts]` in config.toml, added by `Cli::parse_with_shortcuts`
+    #[command(skip)]
+    Shortcut {
+        name: String,
+        ticket_id: Option<String>,
+        message: Option<String>,
+    },
+
+    /// Print dynamic completion candidates (used by the completion scripts)
+    #[command(name = "__complete", hide = true)]
+    DynamicComplete {
+        /// Words typed so far after the binary name
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+
+    /// Show tickets that are overdue or due soon, and timers left running
+    Remind {
+        /// How far ahead to look (e.g., "12h", "2d", "1w")
+        #[arg(long, default_value = "2d")]
+        within: String,
+        /// Also send a desktop notification
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// Archive finished tickets that have not changed in a while
+    Cleanup {
+        /// Archive tickets last updated longer ago than this (e.g., "90d", "12w")
+        #[arg(long, default_value = "90d")]
+        older_than: String,
+        /// Only clean up this project
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Delete the tickets instead of archiving them
+        #[arg(long)]
+        delete: bool,
+        /// Show what would be cleaned up without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation prompt when deleting
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show or change a setting in config.toml, e.g. `ltm config user.name "Ada Lovelace"`
+    Config {
+        /// Setting: user.name, user.email, locale, or status_line
+        key: String,
+        /// New value (shows the current value if omitted)
+        value: Option<String>,
+        /// Remove the setting
+        #[arg(long, conflicts_with = "value")]
+        unset: bool,
+    },
+
+    /// Show the schema version and apply pending database migrations
+    Migrate {
+        /// Only report the schema version and pending migrations
+        #[arg(long)]
+        status: bool,
+        /// Copy all data from an older ltm database file into the (empty) current one
+        #[arg(long, value_name = "OLD_DB", conflicts_with = "status")]
+        from: Option<std::path::PathBuf>,
+    },
+
+    /// Export tickets, comments, and time logs as JSON, optionally only those changed since a
+    /// time, or as a static website with 'ltm export site'
+    #[command(args_conflicts_with_subcommands = true)]
+    Export {
+        #[command(subcommand)]
+        format: Option<ExportFormat>,
+        /// Only what was created or changed after this time (e.g. 2024-05-01, "2024-05-01 18:00",
+        /// or the previous export's exported_at)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only this project
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Write to this file instead of standard output
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<std::path::PathBuf>,
+    },
+
+    /// Bundle the database and config into one file to move to another machine
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+
+    /// Sync tickets with another tracker in both directions
+    Sync {
+        #[command(subcommand)]
+        provider: SyncProvider,
+    },
+
+    /// Serve the database over HTTP as a read-only JSON API, or manage who may use it with
+    /// 'ltm serve token'
+    #[command(args_conflicts_with_subcommands = true)]
+    Serve {
+        #[command(subcommand)]
+        action: Option<ServeAction>,
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port to listen on
+        #[arg(long, default_value_t = 7878)]
+        port: u16,
+        /// Also serve the bundled web UI at /
+        #[arg(long)]
+        web: bool,
+    },
+
+    /// Keep the database open in the background so status lines and 'ltm time current' answer
+    /// without waiting for it; other commands do not use the daemon
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+
+    /// Git integration
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
     
     // Legacy commands with backward compatibility
     /// [LEGACY] Add a new ticket (use 'ticket create' instead)
@@ -121,7 +682,10 @@ enum Commands {
         ticket_id: String,
         /// Status to set (defaults to 'closed')
         status: Option<String>,
-        /// Skip confirmation prompt
+        /// Comment to add, such as how the ticket was resolved
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Skip confirmation prompt and workflow rules
         #[arg(long)]
         force: bool,
     },
@@ -133,16 +697,23 @@ enum Commands {
         ticket_id: String,
         /// New status
         status: String,
-        /// Skip confirmation prompt
+        /// Skip confirmation prompt and workflow rules
         #[arg(long)]
         force: bool,
     },
 
-    /// Delete a ticket
+    /// Delete a ticket, or every ticket matching --project and --status
     #[command(alias = "rm")]
     Delete {
         /// Ticket ID
-        ticket_id: String,
+        #[arg(required_unless_present_any = ["project", "status"])]
+        ticket_id: Option<String>,
+        /// Delete every ticket in this project instead of a single ticket
+        #[arg(short, long, conflicts_with = "ticket_id")]
+        project: Option<String>,
+        /// Delete every ticket with this status instead of a single ticket
+        #[arg(short, long, conflicts_with = "ticket_id")]
+        status: Option<String>,
         /// Skip confirmation prompt
         #[arg(long)]
         force: bool,
@@ -153,18 +724,51 @@ enum Commands {
     List {
         /// Project name (optional)
         project: Option<String>,
-        /// Output in JSON format
-        #[arg(long)]
+        /// Same as `-o json`
+        #[arg(long, hide = true)]
         json: bool,
-        /// Pretty JSON output
-        #[arg(long)]
+        /// Same as `-o json-pretty`
+        #[arg(long, hide = true)]
         json_pretty: bool,
         /// Status filter
         #[arg(long)]
         status: Option<String>,
-        /// Sort by field
+        /// Only tickets of this type
+        #[arg(long = "type", value_name = "TYPE")]
+        kind: Option<String>,
+        /// Only tickets of this severity (S1 to S4)
+        #[arg(long)]
+        severity: Option<String>,
+        /// Only tickets in this component
+        #[arg(long)]
+        component: Option<String>,
+        /// Sort by field: updated, created, status (workflow order), project, or rank
         #[arg(long, default_value = "updated")]
         sort: String,
+        /// Only tickets with a comment containing this text (case-insensitive)
+        #[arg(long)]
+        comment_contains: Option<String>,
+        /// Only tickets whose name, description, or a comment matches this regular expression
+        #[arg(long, value_name = "PATTERN")]
+        regex: Option<String>,
+        /// List archived tickets instead
+        #[arg(long)]
+        archived: bool,
+        /// Only tickets created within this period, such as 7d or 2w
+        #[arg(long, value_name = "PERIOD")]
+        created_within: Option<String>,
+        /// Only tickets created before this date (YYYY-MM-DD, yesterday, or an offset like -30d)
+        #[arg(long, value_name = "DATE")]
+        created_before: Option<String>,
+        /// Only tickets updated within this period, such as 7d or 2w
+        #[arg(long, value_name = "PERIOD")]
+        updated_within: Option<String>,
+        /// Only tickets last updated before this date (YYYY-MM-DD, yesterday, or an offset like -30d)
+        #[arg(long, value_name = "DATE")]
+        updated_before: Option<String>,
+        /// Redraw whenever the database changes, checking every SECONDS (default 2)
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = ["json", "json_pretty"])]
+        watch: Option<u64>,
     },
 
     /// Show ticket details
@@ -172,11 +776,11 @@ enum Commands {
     Show {
         /// Ticket ID
         ticket_id: String,
-        /// Output in JSON format
-        #[arg(long)]
+        /// Same as `-o json`
+        #[arg(long, hide = true)]
         json: bool,
-        /// Pretty JSON output
-        #[arg(long)]
+        /// Same as `-o json-pretty`
+        #[arg(long, hide = true)]
         json_pretty: bool,
         /// Include full details
         #[arg(long)]
@@ -185,9 +789,9 @@ enum Commands {
 
 
 
-    /// [LEGACY] Log time spent on a ticket (use 'time log' instead)
+    /// Log time spent on a ticket, or describe it: ltm log "2h on 14 yesterday debugging auth"
     Log {
-        /// Ticket ID
+        /// Ticket ID, or a sentence with a duration, ticket, day, and note
         ticket_id: String,
         /// Hours spent (optional)
         hours: Option<i32>,
@@ -206,11 +810,11 @@ enum Commands {
     Proj {
         /// Project name
         project: String,
-        /// Output in JSON format
-        #[arg(long)]
+        /// Same as `-o json`
+        #[arg(long, hide = true)]
         json: bool,
-        /// Pretty JSON output
-        #[arg(long)]
+        /// Same as `-o json-pretty`
+        #[arg(long, hide = true)]
         json_pretty: bool,
     },
 }
@@ -226,6 +830,38 @@ enum TicketAction {
         name: String,
         /// Ticket description (optional)
         description: Option<String>,
+        /// Due date (e.g., "2024-06-01", "friday", "+3d")
+        #[arg(long)]
+        due: Option<String>,
+        /// Initial status (defaults to the project's default_status, or "open")
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Ticket type: bug, feature, chore, or task, or one of the project's kinds
+        #[arg(long = "type", value_name = "TYPE")]
+        kind: Option<String>,
+        /// Severity from S1 (most severe) to S4
+        #[arg(long)]
+        severity: Option<String>,
+        /// Component of the project (see `ltm component add`)
+        #[arg(long)]
+        component: Option<String>,
+        /// Create even if a similar open ticket exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Create a ticket from each item of a Markdown task list, such as `- [ ] Write docs`;
+    /// checked items are skipped
+    CreateBulk {
+        /// Project name
+        #[arg(long)]
+        project: String,
+        /// Markdown file to read, or "-" for stdin
+        #[arg(long = "from-file", value_name = "FILE")]
+        from_file: std::path::PathBuf,
+        /// Make indented items checklist items of the ticket above them
+        #[arg(long)]
+        nest: bool,
     },
 
     /// List tickets with filtering options
@@ -237,9 +873,42 @@ enum TicketAction {
         /// Status filter
         #[arg(long)]
         status: Option<String>,
-        /// Sort by field
+        /// Only tickets of this type
+        #[arg(long = "type", value_name = "TYPE")]
+        kind: Option<String>,
+        /// Only tickets of this severity (S1 to S4)
+        #[arg(long)]
+        severity: Option<String>,
+        /// Only tickets in this component
+        #[arg(long)]
+        component: Option<String>,
+        /// Sort by field: updated, created, status (workflow order), project, or rank
         #[arg(long, default_value = "updated")]
         sort: String,
+        /// Only tickets with a comment containing this text (case-insensitive)
+        #[arg(long)]
+        comment_contains: Option<String>,
+        /// Only tickets whose name, description, or a comment matches this regular expression
+        #[arg(long, value_name = "PATTERN")]
+        regex: Option<String>,
+        /// List archived tickets instead
+        #[arg(long)]
+        archived: bool,
+        /// Only tickets created within this period, such as 7d or 2w
+        #[arg(long, value_name = "PERIOD")]
+        created_within: Option<String>,
+        /// Only tickets created before this date (YYYY-MM-DD, yesterday, or an offset like -30d)
+        #[arg(long, value_name = "DATE")]
+        created_before: Option<String>,
+        /// Only tickets updated within this period, such as 7d or 2w
+        #[arg(long, value_name = "PERIOD")]
+        updated_within: Option<String>,
+        /// Only tickets last updated before this date (YYYY-MM-DD, yesterday, or an offset like -30d)
+        #[arg(long, value_name = "DATE")]
+        updated_before: Option<String>,
+        /// Redraw whenever the database changes, checking every SECONDS (default 2)
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+        watch: Option<u64>,
     },
 
     /// Show ticket details
@@ -263,77 +932,267 @@ enum TicketAction {
         value: String,
     },
 
-    /// Delete a ticket
+    /// Delete a ticket, or every ticket matching --project and --status
     #[command(alias = "rm", alias = "remove")]
     Delete {
         /// Ticket ID
-        ticket_id: String,
+        #[arg(required_unless_present_any = ["project", "status"])]
+        ticket_id: Option<String>,
+        /// Delete every ticket in this project instead of a single ticket
+        #[arg(short, long, conflicts_with = "ticket_id")]
+        project: Option<String>,
+        /// Delete every ticket with this status instead of a single ticket
+        #[arg(short, long, conflicts_with = "ticket_id")]
+        status: Option<String>,
         /// Skip confirmation prompt
         #[arg(long)]
         force: bool,
     },
 
-    /// Move ticket to different project
-    #[command(alias = "mv")]
-    Move {
+    /// Bring an archived ticket back into lists
+    Unarchive {
         /// Ticket ID
         ticket_id: String,
-        /// New project name
-        project: String,
     },
 
-    /// Copy ticket
-    #[command(alias = "cp")]
-    Copy {
+    /// Add a ticket to your starred tickets, listed by `ltm starred`
+    Star {
         /// Ticket ID
         ticket_id: String,
-        /// Target project (optional)
-        project: Option<String>,
     },
-}
 
-#[derive(Subcommand)]
-enum ProjectAction {
-    /// Show project details
-    #[command(alias = "view", alias = "info")]
-    Show {
-        /// Project name
-        project: String,
+    /// Take a ticket off your starred tickets
+    Unstar {
+        /// Ticket ID
+        ticket_id: String,
     },
 
-    /// List all projects
-    #[command(alias = "ls")]
-    List,
+    /// Keep a ticket at the top of `list` and the board, whatever the sort order
+    Pin {
+        /// Ticket ID
+        ticket_id: String,
+    },
 
-    /// Show detailed project summary
-    Summary {
-        /// Project name
-        project: String,
+    /// Stop keeping a ticket at the top
+    Unpin {
+        /// Ticket ID
+        ticket_id: String,
     },
 
-    /// Show project statistics
-    Stats {
-        /// Project name (optional, shows all if not specified)
-        project: Option<String>,
+    /// Make a ticket read-only, e.g. a postmortem kept as a record; edits, comments, and
+    /// deletes then need --unlock
+    Lock {
+        /// Ticket ID
+        ticket_id: String,
     },
-}
 
-#[derive(Subcommand)]
-enum CommentAction {
-    /// Add a comment to a ticket
-    #[command(alias = "create", alias = "note")]
-    Add {
+    /// Let a locked ticket change again
+    Unlock {
         /// Ticket ID
         ticket_id: String,
-        /// Comment content
-        content: String,
     },
 
-    /// List comments for a ticket
-    #[command(alias = "ls")]
-    List {
+    /// Plan a ticket for a release of its project ("none" to unplan it)
+    Target {
         /// Ticket ID
         ticket_id: String,
+        /// Release name, like v1.4
+        release: String,
+    },
+
+    /// Move ticket to different project
+    #[command(alias = "mv")]
+    Move {
+        /// Ticket ID
+        ticket_id: String,
+        /// New project name; without one, pick from the existing projects
+        project: Option<String>,
+    },
+
+    /// Move ticket one place up in its project's backlog
+    MoveUp {
+        /// Ticket ID
+        ticket_id: String,
+    },
+
+    /// Move ticket one place down in its project's backlog
+    MoveDown {
+        /// Ticket ID
+        ticket_id: String,
+    },
+
+    /// Move ticket to a position in its project's backlog (1 = top)
+    MoveTo {
+        /// Ticket ID
+        ticket_id: String,
+        /// New position
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        position: u32,
+    },
+
+    /// Copy ticket
+    #[command(alias = "cp")]
+    Copy {
+        /// Ticket ID
+        ticket_id: String,
+        /// Target project (optional)
+        project: Option<String>,
+    },
+
+    /// Attach an external URL (pull request, issue, doc, dashboard) to a ticket
+    LinkUrl {
+        /// Ticket ID
+        ticket_id: String,
+        /// Full URL, e.g. https://github.com/acme/web/pull/7
+        url: String,
+        /// Short label shown before the URL (optional)
+        label: Option<String>,
+    },
+
+    /// Record that a ticket can't be finished before another one, shown by `ltm graph`
+    Depend {
+        /// Ticket ID
+        ticket_id: String,
+        /// ID of the ticket it waits on
+        blocker_id: String,
+    },
+
+    /// Drop a dependency added with `ltm ticket depend`
+    Undepend {
+        /// Ticket ID
+        ticket_id: String,
+        /// ID of the ticket it no longer waits on
+        blocker_id: String,
+    },
+
+    /// List other tickets with a similar name or description
+    #[command(alias = "related")]
+    Similar {
+        /// Ticket ID
+        ticket_id: String,
+        /// Maximum number of tickets to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProjectAction {
+    /// Show project details
+    #[command(alias = "view", alias = "info")]
+    Show {
+        /// Project name
+        project: String,
+    },
+
+    /// List all projects
+    #[command(alias = "ls")]
+    List,
+
+    /// Show detailed project summary
+    Summary {
+        /// Project name
+        project: String,
+    },
+
+    /// Show project statistics
+    Stats {
+        /// Project name (optional, shows all if not specified)
+        project: Option<String>,
+    },
+
+    /// Rename a project, moving all of its tickets
+    #[command(alias = "mv")]
+    Rename {
+        /// Current project name
+        old: String,
+        /// New project name
+        new: String,
+    },
+
+    /// Delete a project with all of its tickets, comments, and time logs
+    #[command(alias = "rm")]
+    Delete {
+        /// Project name
+        project: String,
+        /// Archive the tickets instead of deleting them
+        #[arg(long)]
+        archive: bool,
+        /// Write the project and everything attached to it to this JSON file first
+        #[arg(long, value_name = "PATH")]
+        export_first: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Set a project setting: statuses, default_priority, time_rounding, id_prefix, required_fields, rate, currency, or kinds
+    Set {
+        /// Project name
+        project: String,
+        /// Setting name
+        key: String,
+        /// New value; lists are comma-separated
+        value: String,
+    },
+
+    /// Remove a project setting, or all of them
+    Unset {
+        /// Project name
+        project: String,
+        /// Setting name (all settings if omitted)
+        key: Option<String>,
+    },
+
+    /// Show a project's settings
+    Settings {
+        /// Project name
+        project: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CommentAction {
+    /// Add a comment to a ticket
+    #[command(alias = "create", alias = "note")]
+    Add {
+        /// Ticket ID (may be omitted when a ticket is focused)
+        ticket_id: String,
+        /// Comment content (added below the snippet when --snippet is given)
+        content: Option<String>,
+        /// Start from a snippet in the [snippets] table of config.toml
+        #[arg(short, long)]
+        snippet: Option<String>,
+    },
+
+    /// List the comment snippets from config.toml
+    Snippets,
+
+    /// Reply to a comment, starting or continuing a thread
+    Reply {
+        /// ID of the comment to reply to
+        comment_id: String,
+        /// Reply content
+        content: String,
+    },
+
+    /// List comments for a ticket
+    #[command(alias = "ls")]
+    List {
+        /// Ticket ID
+        ticket_id: String,
+    },
+
+    /// Turn a comment into a new ticket, linked back to the ticket it came from
+    Promote {
+        /// Comment ID
+        comment_id: String,
+        /// Project for the new ticket (defaults to the comment's project)
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Name for the new ticket (defaults to the comment's first line)
+        #[arg(short, long)]
+        name: Option<String>,
     },
 
     /// Show specific comment
@@ -359,20 +1218,134 @@ enum CommentAction {
     },
 }
 
+#[derive(Subcommand)]
+enum CheckAction {
+    /// Add an item to the end of a ticket's checklist
+    Add {
+        /// Ticket ID
+        ticket_id: String,
+        /// Item text
+        text: String,
+    },
+
+    /// Mark an item done, or not done again
+    #[command(alias = "done")]
+    Toggle {
+        /// Ticket ID
+        ticket_id: String,
+        /// Item number, as shown by `check list`
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        item: u32,
+    },
+
+    /// Show a ticket's checklist and progress
+    #[command(alias = "ls")]
+    List {
+        /// Ticket ID
+        ticket_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportAction {
+    /// Calendar heatmap of hours logged per day
+    Heatmap {
+        /// Project filter
+        #[arg(long)]
+        project: Option<String>,
+        /// Number of weeks to show
+        #[arg(long, default_value_t = 26, value_parser = clap::value_parser!(u16).range(1..=104))]
+        weeks: u16,
+    },
+    /// Tickets opened and closed and hours logged per month
+    Trends {
+        /// Project filter
+        #[arg(long)]
+        project: Option<String>,
+        /// Number of months to show, ending with the current one
+        #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u16).range(1..=60))]
+        months: u16,
+    },
+    /// Time tickets spend in each status
+    StatusTime {
+        /// Ticket ID or project name
+        target: String,
+    },
+    /// Open tickets per project by age (0–7d, 7–30d, 30–90d, 90d+), with the oldest ones
+    Aging {
+        /// Project filter
+        #[arg(long)]
+        project: Option<String>,
+        /// How many of each project's oldest tickets to list
+        #[arg(long, default_value_t = 3)]
+        oldest: usize,
+    },
+    /// Rank the backlog by RICE or ICE score, from the reach, impact, confidence, and effort
+    /// set with `ltm ticket update`
+    Score {
+        /// Project filter
+        #[arg(long)]
+        project: Option<String>,
+        /// Scoring formula
+        #[arg(long, value_enum, default_value_t = ScoreMethod::Rice)]
+        method: ScoreMethod,
+        /// How many of the top tickets to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Gantt chart of a release's tickets, from the day each was created to its due date
+    Gantt {
+        /// Release name, like v1.4
+        #[arg(long)]
+        milestone: String,
+        /// Project of the release, needed when several projects have one by that name
+        #[arg(long)]
+        project: Option<String>,
+        /// Write Mermaid syntax for embedding in docs instead of drawing the chart
+        #[arg(long)]
+        mermaid: bool,
+    },
+    /// Email a summary of the time logged and tickets finished, e.g. weekly from cron; the
+    /// SMTP server is set under [email] in config.toml
+    Send {
+        /// Cover the last seven days, up to today (the default)
+        #[arg(long, conflicts_with = "daily")]
+        weekly: bool,
+        /// Cover today only
+        #[arg(long)]
+        daily: bool,
+        /// Recipient; repeat for several (defaults to `to` under [email])
+        #[arg(long, value_name = "ADDRESS")]
+        to: Vec<String>,
+        /// Project filter
+        #[arg(long)]
+        project: Option<String>,
+        /// Print the email instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum TimeAction {
     /// Start time tracking
     #[command(alias = "begin")]
     Start {
-        /// Ticket ID
-        ticket_id: String,
+        /// Ticket ID (defaults to the focused ticket, then the last one used)
+        ticket_id: Option<String>,
+        /// Note on what the time is for, kept on the time log when the timer stops
+        #[arg(short = 'm', long = "message")]
+        note: Option<String>,
     },
 
     /// Stop time tracking
     #[command(alias = "end")]
     Stop {
-        /// Ticket ID (optional, stops all if not specified)
+        /// Ticket ID (optional, defaults to the focused ticket or else all timers)
         ticket_id: Option<String>,
+        /// Stop all active timers even when a ticket is focused
+        #[arg(long, conflicts_with = "ticket_id")]
+        all: bool,
     },
 
     /// Cancel time tracking without logging time
@@ -384,23 +1357,32 @@ enum TimeAction {
 
     /// Pause time tracking
     Pause {
-        /// Ticket ID
-        ticket_id: String,
+        /// Ticket ID (defaults to the focused ticket)
+        ticket_id: Option<String>,
     },
 
     /// Resume time tracking
     Resume {
-        /// Ticket ID
-        ticket_id: String,
+        /// Ticket ID (defaults to the focused ticket)
+        ticket_id: Option<String>,
     },
 
     /// Log time manually
     #[command(alias = "add")]
     Log {
-        /// Ticket ID
+        /// Ticket ID (may be omitted when a ticket is focused)
         ticket_id: String,
         /// Duration (e.g., "2h30m", "1.5h", "90m")
-        duration: String,
+        duration: Option<String>,
+        /// Day the work was done (YYYY-MM-DD, yesterday, or an offset like -3d)
+        #[arg(long)]
+        date: Option<String>,
+        /// Time of day the work was done (HH:MM), defaulting to midday
+        #[arg(long)]
+        at: Option<String>,
+        /// Count the time as worked but leave it out of billable hours and invoices
+        #[arg(long)]
+        non_billable: bool,
     },
 
     /// List time logs for a ticket
@@ -414,19 +1396,135 @@ enum TimeAction {
     #[command(alias = "status")]
     Active,
 
+    /// Show the timer being tracked, for scripts and editor plugins to poll. Exits with
+    /// status 3 when no timer is running.
+    Current {
+        /// Same as `-o json`, which writes `{}` when no timer is running
+        #[arg(long, hide = true)]
+        json: bool,
+    },
+
     /// Show time summary
     Summary {
         /// Ticket ID
         ticket_id: String,
     },
 
-    /// Update a time entry
-    #[command(alias = "edit")]
+    /// Hours per ticket over a date range, with totals
+    Report {
+        /// Project filter (all projects when omitted)
+        #[arg(long)]
+        project: Option<String>,
+        /// First day (YYYY-MM-DD, yesterday, or an offset like -30d); defaults to the start of this month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day, inclusive; defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Same as `-o json`
+        #[arg(long, hide = true, conflicts_with = "csv")]
+        json: bool,
+        /// Same as `-o json-pretty`
+        #[arg(long, hide = true, conflicts_with = "csv")]
+        json_pretty: bool,
+        /// Same as `-o csv`
+        #[arg(long, hide = true)]
+        csv: bool,
+    },
+
+    /// Each time log over a date range with its project, ticket, day, decimal hours, billable
+    /// flag, and note, for payroll or accounting
+    Export {
+        /// Project filter (all projects when omitted)
+        #[arg(long)]
+        project: Option<String>,
+        /// First day (YYYY-MM-DD, yesterday, or an offset like -30d); defaults to the start of this month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day, inclusive; defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Same as `-o`, e.g. `--format csv`
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        format: Option<OutputFormat>,
+    },
+
+    /// Write a PDF invoice of the time logged over a date range, with line items grouped
+    /// by ticket; letterhead, rate, and clients come from [invoice] in config.toml
+    Invoice {
+        /// Project filter (all projects when omitted)
+        #[arg(long)]
+        project: Option<String>,
+        /// First day (YYYY-MM-DD, yesterday, or an offset like -30d); defaults to the start of this month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day, inclusive; defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Client to bill: a name under [invoice.clients], or the name to print; defaults to
+        /// the client named like the project
+        #[arg(long)]
+        client: Option<String>,
+        /// Invoice number; defaults to the year and month of the last day
+        #[arg(long)]
+        number: Option<String>,
+        /// Hourly rate for every ticket, instead of the tickets' and projects' rates
+        #[arg(long)]
+        rate: Option<f64>,
+        /// Where to write the PDF; defaults to invoice-<number>.pdf
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<std::path::PathBuf>,
+    },
+
+    /// Update a time entry's duration, or when the work started and ended
     Update {
         /// Time log ID
         log_id: String,
         /// New duration
+        #[arg(required_unless_present_any = ["start", "end", "billable", "non_billable"])]
+        duration: Option<String>,
+        /// When the work started: HH:MM (the nearest such time to the current start) or a date and time
+        #[arg(long, value_name = "TIME")]
+        start: Option<String>,
+        /// When the work ended, like --start; the duration is worked out from the new span
+        #[arg(long, value_name = "TIME")]
+        end: Option<String>,
+        /// Bill the time again
+        #[arg(long, conflicts_with = "non_billable")]
+        billable: bool,
+        /// Leave the time out of billable hours and invoices
+        #[arg(long)]
+        non_billable: bool,
+    },
+
+    /// Edit a day's time logs in $EDITOR as a table, one log per line; changed, added, and
+    /// removed lines are saved together
+    Edit {
+        /// Day to edit (YYYY-MM-DD, yesterday, or an offset like -3d); defaults to today
+        #[arg(long)]
+        day: Option<String>,
+    },
+
+    /// Import time logs from a CSV file, such as another tracker's export. Every row is
+    /// checked first, and nothing is imported unless all of them are valid.
+    Import {
+        /// CSV file with a header row, or "-" for stdin
+        file: std::path::PathBuf,
+        /// Column with the ticket ID: a header name or a 1-based position
+        #[arg(long, value_name = "COLUMN", default_value = "ticket")]
+        ticket: String,
+        /// Column with the day (YYYY-MM-DD) or date and time the work was done
+        #[arg(long, value_name = "COLUMN", default_value = "date")]
+        date: String,
+        /// Column with the duration: 2h30m, 1:30, or decimal hours like 1.5
+        #[arg(long, value_name = "COLUMN", default_value = "duration")]
         duration: String,
+        /// Column with the note; a "note" column is used when there is one
+        #[arg(long, value_name = "COLUMN")]
+        note: Option<String>,
+        /// Check every row and report what would be imported, without importing
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Delete a time entry
@@ -438,74 +1536,344 @@ enum TimeAction {
 }
 
 #[derive(Subcommand)]
-enum UpdateTarget {
-    /// Update ticket status
-    Status {
-        /// Ticket ID
-        ticket_id: String,
-        /// New status
-        status: String,
-        /// Skip confirmation prompt
-        #[arg(long)]
-        force: bool,
+enum ComponentAction {
+    /// Add a component to a project, e.g. `ltm component add webapp frontend`
+    Add {
+        /// Project name
+        project: String,
+        /// Component name
+        name: String,
     },
 
-    /// Update ticket name
-    Name {
-        /// Ticket ID
-        ticket_id: String,
-        /// New name
+    /// Remove a component from a project, taking its tickets out of it
+    #[command(alias = "rm")]
+    Remove {
+        /// Project name
+        project: String,
+        /// Component name
         name: String,
     },
 
-    /// Update ticket description
-    Description {
-        /// Ticket ID
-        ticket_id: String,
-        /// New description (opens editor if not provided)
-        description: Option<String>,
+    /// List a project's components with their ticket counts
+    #[command(alias = "ls")]
+    List {
+        /// Project name
+        project: String,
     },
+}
 
-    /// Update ticket project
-    Project {
-        /// Ticket ID
-        ticket_id: String,
+#[derive(Subcommand)]
+enum ReleaseAction {
+    /// Add a release to a project, e.g. `ltm release create webapp v1.4`
+    Create {
+        /// Project name
+        project: String,
+        /// Release name, like v1.4
+        name: String,
+        /// Day it is planned to ship (YYYY-MM-DD), shown on `ltm roadmap`
+        #[arg(long)]
+        due: Option<String>,
+    },
+
+    /// Set or clear ("none") the day a release is planned to ship
+    Due {
+        /// Project name
+        project: String,
+        /// Release name
+        name: String,
+        /// Date as YYYY-MM-DD, or "none"
+        date: String,
+    },
+
+    /// List a project's releases with how many of their tickets are done
+    #[command(alias = "ls")]
+    List {
+        /// Project name
+        project: String,
+    },
+
+    /// Show a release's progress and the open tickets still planned for it
+    Show {
+        /// Project name
+        project: String,
+        /// Release name
+        name: String,
+    },
+}
+
+/// Formulas for `ltm report score`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ScoreMethod {
+    /// Reach × impact × confidence ÷ effort
+    Rice,
+    /// Impact × confidence ÷ effort, leaving reach out
+    Ice,
+}
+
+/// Formats `ltm graph` can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum GraphFormat {
+    /// Graphviz DOT, for `dot -Tsvg` and friends
+    Dot,
+}
+
+#[derive(Subcommand)]
+enum BillingAction {
+    /// Lock a month's time logs (e.g. 2024-05) so they can't change after invoicing;
+    /// time log, update, and delete then refuse them without --unlock
+    Lock {
+        /// Month as YYYY-MM
+        month: String,
+    },
+
+    /// Allow changes to a locked month's time logs again
+    Unlock {
+        /// Month as YYYY-MM
+        month: String,
+    },
+
+    /// List the locked months
+    #[command(alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Start the daemon in the background
+    Start,
+
+    /// Run the daemon in the foreground, e.g. under systemd or launchd
+    Run,
+
+    /// Stop the running daemon
+    Stop,
+
+    /// Show whether a daemon is running
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ServeAction {
+    /// Create, revoke, or list the tokens clients need once the server has any
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokenAction {
+    /// Create a token and print its secret, which is shown only this once
+    Create {
+        /// Name to tell the token apart by, e.g. the client or person it is for
+        name: String,
+        /// Only let the token query the API, not run commands
+        #[arg(long)]
+        read_only: bool,
+    },
+
+    /// Revoke a token; the server refuses it from the next request on
+    Revoke {
+        /// Name given to 'ltm serve token create'
+        name: String,
+    },
+
+    /// List the tokens, without their secrets
+    List,
+}
+
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// Write a browsable HTML site: an index of projects, a page per project, and a page per
+    /// ticket with its comments and time logs
+    Site {
+        /// Directory to write the site to; created when missing
+        dir: std::path::PathBuf,
+        /// Only this project
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ArchiveAction {
+    /// Write the database and config file to a .tar.zst archive
+    Create {
+        /// Archive to write, e.g. ltm-backup.tar.zst
+        file: std::path::PathBuf,
+    },
+
+    /// Replace the database (and a missing config file) with an archive's contents
+    Restore {
+        /// Archive written by 'ltm archive create'
+        file: std::path::PathBuf,
+        /// Replace a database that already has tickets, and an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SyncProvider {
+    /// Import and update issues of the teams under [linear.teams], and create or update
+    /// issues for their projects' tickets
+    Linear {
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Settle fields changed on both sides without asking
+        #[arg(long, value_enum)]
+        prefer: Option<sync::Prefer>,
+    },
+    /// Import and update issues of the projects under [jira.projects], create or update
+    /// issues for their tickets, and send time logs as work logs
+    Jira {
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Settle fields changed on both sides without asking
+        #[arg(long, value_enum)]
+        prefer: Option<sync::Prefer>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GitAction {
+    /// Install a post-commit hook that records ticket references from commit messages
+    InstallHooks {
+        /// Repository path (defaults to the current directory)
+        #[arg(long)]
+        repo: Option<String>,
+        /// Replace an existing hook that was not installed by ltm
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Process the latest commit message (invoked by the installed hook)
+    #[command(hide = true)]
+    PostCommit,
+}
+
+#[derive(Subcommand)]
+enum UpdateTarget {
+    /// Update ticket status
+    Status {
+        /// Ticket ID
+        ticket_id: String,
+        /// New status; without one, pick from the statuses the ticket can move to
+        status: Option<String>,
+        /// Comment to add, such as why the status changed
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Skip confirmation prompt and workflow rules
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Update ticket name
+    Name {
+        /// Ticket ID
+        ticket_id: String,
+        /// New name
+        name: String,
+    },
+
+    /// Update ticket description
+    Description {
+        /// Ticket ID
+        ticket_id: String,
+        /// New description (opens editor if not provided)
+        description: Option<String>,
+    },
+
+    /// Update ticket project
+    Project {
+        /// Ticket ID
+        ticket_id: String,
         /// New project
         project: String,
     },
+
+    /// Update ticket due date
+    Due {
+        /// Ticket ID
+        ticket_id: String,
+        /// Due date (e.g., "2024-06-01", "tomorrow", "friday", "+3d"), or "none" to clear
+        due: String,
+    },
 }
 
-pub struct TimeTrackingState {
-    start_time: DateTime<Utc>,
-    paused_at: Option<DateTime<Utc>>,
-    elapsed_time: Option<Duration>,
+/// `app_state` key holding the focused ticket ID
+const FOCUS_KEY: &str = "focus_ticket";
+
+/// Filters shared by `ltm list` and `ltm ticket list`
+#[derive(Clone)]
+struct ListFilters {
+    project: Option<String>,
+    status: Option<String>,
+    kind: Option<String>,
+    severity: Option<String>,
+    component: Option<String>,
+    sort: String,
+    comment_contains: Option<String>,
+    regex: Option<String>,
+    archived: bool,
+    created_within: Option<String>,
+    created_before: Option<String>,
+    updated_within: Option<String>,
+    updated_before: Option<String>,
 }
 
-impl TimeTrackingState {
-    fn new(start_time: DateTime<Utc>) -> Self {
-        Self {
-            start_time,
-            paused_at: None,
-            elapsed_time: None,
-        }
+impl ListFilters {
+    /// The `--created-*` and `--updated-*` flags as bounds, with periods counted back from now
+    fn date_bounds(&self) -> Result<DateBounds> {
+        let now = Utc::now().naive_utc();
+        let within = |period: &Option<String>| period.as_deref().map(validate_period).transpose().map(|p| p.map(|age| now - age));
+        let before = |date: &Option<String>| date.as_deref().map(validate_timestamp).transpose();
+        Ok(DateBounds {
+            created_after: within(&self.created_within)?,
+            created_before: before(&self.created_before)?,
+            updated_after: within(&self.updated_within)?,
+            updated_before: before(&self.updated_before)?,
+        })
     }
+}
 
-    fn is_paused(&self) -> bool {
-        self.paused_at.is_some()
-    }
+/// Optional fields of a new ticket, as given to `ltm ticket create`
+#[derive(Default)]
+struct NewTicketFields {
+    due: Option<String>,
+    status: Option<String>,
+    kind: Option<String>,
+    severity: Option<String>,
+    component: Option<String>,
+}
+
+/// What `--watch` keeps redrawing
+enum WatchView {
+    List(Box<ListFilters>),
+    Board(Option<String>),
 }
 
 pub struct CommandHandler {
     db: Database,
-    time_tracking: HashMap<i64, TimeTrackingState>,
+    config: Config,
+    renderer: Renderer,
+    /// `--unlock`: changes to locked tickets and billing months are allowed
+    unlock: bool,
+    /// Tickets the current command used, saved for `ltm recent` once it succeeds
+    used: std::sync::Mutex<Vec<i64>>,
 }
 
 impl CommandHandler {
     pub fn new(db: Database) -> Self {
-        Self {
-            db,
-            time_tracking: HashMap::new(),
-        }
+        Self { db, config: Config::default(), renderer: Renderer::default(), unlock: false, used: Default::default() }
+    }
+
+    /// Use settings from the config file, such as per-project workflows
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
     }
 
     /// Helper method to validate ticket exists
@@ -513,20 +1881,64 @@ impl CommandHandler {
         if self.db.get_ticket(ticket_id).await.map_err(|_| ValidationError::TicketNotFound(ticket_id))?.is_none() {
             return Err(ValidationError::TicketNotFound(ticket_id));
         }
+        self.use_ticket(ticket_id);
         Ok(())
     }
 
+    /// Count a ticket as used by the current command, for `ltm recent`
+    fn use_ticket(&self, ticket_id: i64) {
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        if !used.contains(&ticket_id) {
+            used.push(ticket_id);
+        }
+    }
+
     pub async fn handle_command(&mut self, cli: Cli) -> Result<()> {
-        let result = self.handle_command_with_validation(cli).await;
+        self.renderer = Renderer::new(cli.legacy_output().unwrap_or(cli.output));
+        self.unlock = cli.unlock;
+        if cli.checks_timers() {
+            self.check_long_timers().await?;
+            if !self.config.webhooks.is_empty() {
+                self.notify_long_timers().await?;
+            }
+        }
+        if self.renderer.is_data() {
+            feedback::capture_messages();
+        }
+        let last = self.db.recent_tickets(1).await.ok().and_then(|recent| recent.first().map(|r| r.ticket_id));
+        set_last_ticket(last);
+        // Boxed: with every command's state inlined, the future is too big for a thread's stack
+        let result = Box::pin(self.handle_command_with_validation(cli)).await;
+        let used = std::mem::take(&mut *self.used.lock().unwrap_or_else(|e| e.into_inner()));
+        if result.is_ok() && !used.is_empty() {
+            // Only a convenience, so it never fails the command
+            if let Err(e) = self.db.record_recent(&used).await {
+                debug!(error = %e, "could not record recent tickets");
+            }
+        }
+        let messages = feedback::captured_messages();
 
         // Convert ValidationError to user-friendly error message
         if let Err(e) = &result {
             if let Some(validation_error) = e.downcast_ref::<ValidationError>() {
+                if self.renderer.is_data() {
+                    let response = serde_json::json!(crate::json_formatting::error_response(validation_error));
+                    self.renderer.print_data(&response);
+                    return Ok(());
+                }
                 eprintln!("{}", format_validation_error(validation_error));
                 return Ok(()); // Don't propagate the error, just print the message
             }
         }
 
+        // Commands without a result of their own report what they did
+        if self.renderer.is_data() && result.is_ok() && !Renderer::printed() {
+            self.renderer.print(&Messages(messages));
+        } else {
+            for (_, message) in messages {
+                eprintln!("{}", message);
+            }
+        }
         result
     }
 
@@ -556,6 +1968,26 @@ impl CommandHandler {
                 self.handle_time_action(action).await?;
             }
 
+            Commands::Component { action } => {
+                self.handle_component_action(action).await?;
+            }
+
+            Commands::Release { action } => {
+                self.handle_release_action(action).await?;
+            }
+
+            Commands::Roadmap { project, hours_per_day } => {
+                self.handle_roadmap(project.as_deref(), hours_per_day).await?;
+            }
+
+            Commands::Graph { project, release, format } => {
+                self.handle_graph(project.as_deref(), release.as_deref(), format).await?;
+            }
+
+            Commands::Billing { action } => {
+                self.handle_billing_action(action).await?;
+            }
+
             Commands::Update { target } => {
                 self.handle_update_target(target).await?;
             }
@@ -564,118 +1996,282 @@ impl CommandHandler {
             Commands::Open { ticket_id } => {
                 let validated_ticket_id = validate_ticket_id(&ticket_id)?;
                 self.validate_ticket_exists(validated_ticket_id).await?;
-                self.update_ticket_status_internal(validated_ticket_id, "open", true).await?;
+                self.update_ticket_status_internal(validated_ticket_id, "open", true, true).await?;
             }
 
             Commands::Complete { ticket_id } => {
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                let validated_ticket_id = self.resolve_ticket_id(ticket_id.as_deref()).await?;
                 self.validate_ticket_exists(validated_ticket_id).await?;
-                self.update_ticket_status_internal(validated_ticket_id, "completed", true).await?;
+                self.update_ticket_status_internal(validated_ticket_id, "completed", true, true).await?;
             }
 
             Commands::Block { ticket_id, reason } => {
                 let validated_ticket_id = validate_ticket_id(&ticket_id)?;
                 self.validate_ticket_exists(validated_ticket_id).await?;
-                self.update_ticket_status_internal(validated_ticket_id, "blocked", true).await?;
+                let reason = reason.map(|reason| validate_content_length(&reason, ContentType::Comment)).transpose()?;
+                let comment = reason.as_ref().map(|reason| format!("Blocked: {}", reason));
+                self.change_ticket_status(validated_ticket_id, "blocked", true, true, reason.as_deref(), comment.as_deref()).await?;
+            }
+
+            Commands::Shortcut { name, ticket_id, message } => {
+                self.run_shortcut(&name, ticket_id.as_deref(), message.as_deref()).await?;
+            }
+
+            Commands::Start { ticket_id } => {
+                let validated_ticket_id = self.resolve_ticket_id(ticket_id.as_deref()).await?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+
+                self.start_working_internal(validated_ticket_id).await?;
+            }
 
-                if let Some(reason_text) = reason {
-                    let validated_content = validate_content_length(&reason_text, ContentType::Comment)?;
-                    self.db.add_comment(validated_ticket_id, &format!("Blocked: {}", validated_content)).await?;
-                    feedback::show_info("Added blocking reason as comment");
+            Commands::Focus { ticket_id, clear } => {
+                if clear {
+                    self.db.delete_state(FOCUS_KEY).await?;
+                    feedback::show_success("Cleared focused ticket");
+                } else if let Some(ticket_id) = ticket_id {
+                    let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                    self.validate_ticket_exists(validated_ticket_id).await?;
+                    self.db.set_state(FOCUS_KEY, &validated_ticket_id.to_string()).await?;
+                    if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
+                        feedback::show_success(&format!("Focused on ticket {} ('{}')", ticket.id, ticket.name));
+                    }
+                } else {
+                    self.show_current_ticket().await?;
                 }
             }
 
-            Commands::Start { ticket_id } => {
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+            Commands::Current => {
+                self.show_current_ticket().await?;
+            }
+
+            Commands::Next { project, start, alternatives } => {
+                self.next_ticket_internal(project.as_deref(), start, alternatives).await?;
+            }
+
+            Commands::Today { project } => {
+                self.today_internal(project.as_deref()).await?;
+            }
+
+            Commands::Week { project, next } => {
+                self.week_internal(project.as_deref(), next).await?;
+            }
+
+            Commands::Calendar { month, project, prev, next } => {
+                self.calendar_internal(month.as_deref(), project.as_deref(), prev, next).await?;
+            }
+
+            Commands::Quick { text, force } => {
+                self.quick_add(&text.join(" "), force).await?;
+            }
+
+            Commands::OpenUrl { ticket_id, link } => {
+                let validated_ticket_id = self.resolve_ticket_id(ticket_id.as_deref()).await?;
                 self.validate_ticket_exists(validated_ticket_id).await?;
+                let links = self.db.get_ticket_urls(validated_ticket_id).await?;
+                match links.get(link as usize - 1) {
+                    Some(url) => {
+                        feedback::open_in_browser(&url.url)?;
+                        feedback::show_success(&format!("Opened {}", url.url));
+                    }
+                    None if links.is_empty() => {
+                        feedback::show_info(&format!("Ticket {} has no links", validated_ticket_id));
+                    }
+                    None => {
+                        feedback::show_error(&format!("Ticket {} has only {} link(s)", validated_ticket_id, links.len()));
+                    }
+                }
+            }
+
+            Commands::Report { action } => {
+                self.handle_report_action(action).await?;
+            }
+
+            Commands::Check { action } => {
+                self.handle_check_action(action).await?;
+            }
 
-                // Set status to in-progress and start timer
-                self.update_ticket_status_internal(validated_ticket_id, "in-progress", true).await?;
-                self.time_tracking.insert(validated_ticket_id, TimeTrackingState::new(Utc::now()));
-                feedback::show_success(&format!("Started working on ticket {} (status: in-progress, timer: started)", validated_ticket_id));
+            Commands::Board { project, watch } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                match watch {
+                    Some(interval) => self.watch_view(WatchView::Board(validated_project), interval).await?,
+                    None => self.show_board(validated_project.as_deref()).await?,
+                }
             }
 
             Commands::Projects => {
                 self.handle_list_projects().await?;
             }
 
+            Commands::Recent { limit } => {
+                let tickets = self.db.recent_tickets(limit).await?;
+                self.renderer.print(&RecentList { tickets });
+            }
+
+            Commands::Starred => {
+                let tickets = self.db.starred_tickets().await?;
+                let checklists = self.db.checklist_progress().await?;
+                self.renderer.print(&TicketList { tickets, project: None, checklists, comment_matches: Vec::new() });
+            }
+
             Commands::Active => {
                 self.handle_show_active_timers().await?;
             }
+            Commands::Events { ticket, limit, json: _ } => {
+                let ticket_id = ticket.as_deref().map(validate_ticket_id).transpose()?;
+                let events = self.db.list_events(ticket_id, limit as i64).await?;
+                self.renderer.print(&EventList { events, ticket_id });
+            }
+            Commands::StatusLine { format } => {
+                self.print_status_line(format.as_deref()).await?;
+            }
             Commands::Completions { shell, out_dir } => {
                 self.handle_generate_completions(&shell, out_dir.as_deref())?;
             }
+            Commands::DynamicComplete { words } => {
+                self.handle_dynamic_completion(&words).await?;
+            }
+            Commands::Remind { within, notify } => {
+                self.handle_remind(&within, notify).await?;
+            }
+            Commands::Cleanup { older_than, project, delete, dry_run, force } => {
+                self.handle_cleanup(&older_than, project, delete, dry_run, force).await?;
+            }
+            Commands::Migrate { status, from } => {
+                self.migrate_internal(status, from.as_deref()).await?;
+            }
+            Commands::Export { format: Some(ExportFormat::Site { dir, project }), .. } => {
+                self.export_site_internal(&dir, project.as_deref()).await?;
+            }
+            Commands::Export { since, project, file, .. } => {
+                self.export_internal(since.as_deref(), project.as_deref(), file.as_deref()).await?;
+            }
+            Commands::Archive { action } => match action {
+                ArchiveAction::Create { file } => {
+                    let manifest = archive::create(&self.db, &file).await?;
+                    feedback::show_success(&format!(
+                        "Archived the database (schema version {}) to {}",
+                        manifest.schema_version.unwrap_or_default(),
+                        file.display()
+                    ));
+                }
+                ArchiveAction::Restore { file, force } => {
+                    self.restore_archive_internal(&file, force).await?;
+                }
+            },
+            Commands::Sync { provider } => {
+                self.sync_internal(provider).await?;
+            }
+            Commands::Config { key, value, unset } => {
+                self.config_internal(&key, value.as_deref(), unset)?;
+            }
+            Commands::Daemon { action } => {
+                self.handle_daemon_action(action).await?;
+            }
+            Commands::Serve { action: Some(ServeAction::Token { action }), .. } => {
+                self.handle_token_action(action).await?;
+            }
+            Commands::Serve { host, port, web, .. } => {
+                let addr: std::net::SocketAddr = format!("{}:{}", host, port)
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid listen address '{}:{}'", host, port))?;
+                if web {
+                    feedback::show_info(&format!("Serving web UI at http://{}/ (Ctrl-C to stop)", addr));
+                } else {
+                    feedback::show_info(&format!("Serving JSON API at http://{}/api (Ctrl-C to stop)", addr));
+                }
+                let token = self.config.server.token();
+                let tokens = self.db.api_tokens().await?;
+                if token.is_some() || !tokens.is_empty() {
+                    feedback::show_info("Clients need a token: the [server] token in config.toml or one from 'ltm serve token create'");
+                } else if !addr.ip().is_loopback() {
+                    feedback::show_warning(&format!(
+                        "Anyone who can reach {} can read the tickets; create a token with 'ltm serve token create <name>'",
+                        addr
+                    ));
+                }
+                server::serve(self.db.clone(), addr, web, token).await?;
+            }
+            Commands::Git { action } => {
+                self.handle_git_action(action).await?;
+            }
             // Legacy commands with deprecation warnings
             Commands::Add { project, name, description } => {
                 feedback::show_warning("'ltm add' is deprecated. Use 'ltm ticket create' instead.");
                 feedback::show_info("Example: ltm ticket create project \"ticket name\" \"description\"");
 
-                self.create_ticket_internal(project, name, description).await?;
+                self.create_ticket_internal(project, name, description, NewTicketFields::default(), false).await?;
             }
-            Commands::Close { ticket_id, status, force } => {
+            Commands::Close { ticket_id, status, message, force } => {
                 let validated_ticket_id = validate_ticket_id(&ticket_id)?;
                 let final_status = status.as_deref().unwrap_or("closed");
-                let validated_status = validate_status(final_status)?;
 
                 self.validate_ticket_exists(validated_ticket_id).await?;
-                self.update_ticket_status_internal(validated_ticket_id, &validated_status, force).await?;
+                let validated_status = self.validate_ticket_status(validated_ticket_id, final_status).await?;
+                let message = message.map(|message| validate_content_length(&message, ContentType::Comment)).transpose()?;
+                self.change_ticket_status(validated_ticket_id, &validated_status, force, !force, message.as_deref(), message.as_deref()).await?;
             }
             Commands::Status { ticket_id, status, force } => {
                 feedback::show_warning("'ltm status' is deprecated. Use 'ltm update status' or 'ltm set status' instead.");
                 feedback::show_info("Example: ltm set status 1 closed");
 
                 let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                let validated_status = validate_status(&status)?;
                 self.validate_ticket_exists(validated_ticket_id).await?;
-                self.update_ticket_status_internal(validated_ticket_id, &validated_status, force).await?;
+                let validated_status = self.validate_ticket_status(validated_ticket_id, &status).await?;
+                self.update_ticket_status_internal(validated_ticket_id, &validated_status, force, !force).await?;
             }
-            Commands::Delete { ticket_id, force } => {
-                // Validate inputs
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                self.validate_ticket_exists(validated_ticket_id).await?;
-
-                // Check if ticket exists for interactive confirmation
-                if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
-                    let target = format!("ticket {} ('{}')", validated_ticket_id, ticket.name);
-
-                    if !force && !interactive::confirm_destructive_action("delete", &target)? {
-                        feedback::show_info("Operation cancelled");
-                        return Ok(());
-                    }
-
-                    let pb = feedback::create_progress_bar("Deleting ticket");
-                    self.db.delete_ticket(validated_ticket_id).await?;
-                    pb.finish_with_message("Ticket deleted");
-                    feedback::show_success(&format!("Ticket {} deleted", validated_ticket_id));
-                } else {
-                    return Err(ValidationError::TicketNotFound(validated_ticket_id).into());
-                }
+            Commands::Delete { ticket_id: None, project, status, force } => {
+                self.bulk_delete_internal(project, status, force).await?;
             }
-            Commands::List { project, json, json_pretty, status, sort } => {
-                let want_json = json || json_pretty;
-                if let Err(e) = self.list_tickets_internal(project, status, Some(sort), want_json).await {
-                    if json {
-                        if let Some(validation_error) = e.downcast_ref::<ValidationError>() {
-                            println!("{}", crate::json_formatting::format_error_json(validation_error));
-                            return Ok(());
-                        }
-                    }
-                    return Err(e);
-                }
+            Commands::Delete { ticket_id: Some(ticket_id), force, .. } => {
+                self.delete_ticket_internal(&ticket_id, force).await?;
             }
-            Commands::Show { ticket_id, json, json_pretty, full } => {
-                let want_json = json || json_pretty;
-                if let Err(e) = self.show_ticket_internal(&ticket_id, full, want_json).await {
-                    if json {
-                        if let Some(validation_error) = e.downcast_ref::<ValidationError>() {
-                            println!("{}", crate::json_formatting::format_error_json(validation_error));
-                            return Ok(());
-                        }
-                    }
-                    return Err(e);
+            Commands::List {
+                project,
+                json: _,
+                json_pretty: _,
+                status,
+                kind,
+                severity,
+                component,
+                sort,
+                comment_contains,
+                regex,
+                archived,
+                created_within,
+                created_before,
+                updated_within,
+                updated_before,
+                watch,
+            } => {
+                let filters = ListFilters {
+                    project,
+                    status,
+                    kind,
+                    severity,
+                    component,
+                    sort,
+                    comment_contains,
+                    regex,
+                    archived,
+                    created_within,
+                    created_before,
+                    updated_within,
+                    updated_before,
+                };
+                if let Some(interval) = watch {
+                    return self.watch_view(WatchView::List(Box::new(filters)), interval).await;
                 }
+                self.list_tickets_internal(filters).await?;
+            }
+            Commands::Show { ticket_id, full, .. } => {
+                self.show_ticket_internal(&ticket_id, full).await?;
             }
 
+            Commands::Log { ticket_id: sentence, hours: None, minutes: None, start: false, end: false }
+                if validate_ticket_id(&sentence).is_err() =>
+            {
+                self.log_sentence_internal(&sentence).await?;
+            }
             Commands::Log {
                 ticket_id,
                 hours,
@@ -690,42 +2286,12 @@ impl CommandHandler {
                 // Check if ticket exists for interactive feedback
                 if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
                     if start {
-                        self.time_tracking.insert(validated_ticket_id, TimeTrackingState::new(Utc::now()));
-                        feedback::show_time_tracking_progress("Starting", validated_ticket_id).await;
+                        self.db.start_timer(validated_ticket_id, Utc::now().naive_utc()).await?;
+                        feedback::show_time_tracking_progress("feedback.start_tracking", validated_ticket_id);
                     } else if end {
-                        if let Some(state) = self.time_tracking.remove(&validated_ticket_id) {
-                            let end_time = Utc::now();
-                            let mut total_duration = if let Some(elapsed) = state.elapsed_time {
-                                elapsed
-                            } else {
-                                Duration::zero()
-                            };
-
-                            // If the timer is paused, use the paused_at time as the end time
-                            // Otherwise, calculate duration from start_time to now
-                            if let Some(paused_at) = state.paused_at {
-                                total_duration = total_duration + (paused_at - state.start_time);
-                            } else {
-                                total_duration = total_duration + (end_time - state.start_time);
-                            }
-
-                            let hours = total_duration.num_hours() as i32;
-                            let minutes = (total_duration.num_minutes() % 60) as i32;
-
-                            let pb = feedback::create_progress_bar("Logging time");
-                            self.db
-                                .add_time_log(validated_ticket_id, hours, minutes, Some(state.start_time), Some(end_time))
-                                .await?;
-                            pb.finish_with_message("Time logged");
-
-                            feedback::show_celebration(&format!(
-                                "Logged {} hours and {} minutes for ticket {} ('{}')",
-                                hours, minutes, validated_ticket_id, ticket.name
-                            ));
-                        } else {
-                            feedback::show_warning(&format!("No active time tracking for ticket {}", validated_ticket_id));
-                        }
+                        self.stop_time_tracking_internal(validated_ticket_id).await?;
                     } else if let (Some(hours), Some(minutes)) = (hours, minutes) {
+                        let (hours, minutes) = self.round_logged_time(validated_ticket_id, hours, minutes).await?;
                         let pb = feedback::create_progress_bar("Logging time");
                         self.db
                             .add_time_log(validated_ticket_id, hours, minutes, None, None)
@@ -743,19 +2309,10 @@ impl CommandHandler {
                     return Err(ValidationError::TicketNotFound(validated_ticket_id).into());
                 }
             }
-            Commands::Proj { project, json, json_pretty } => {
+            Commands::Proj { project, .. } => {
                 feedback::show_warning("'ltm proj' is deprecated. Use 'ltm project show' instead.");
                 feedback::show_info("Example: ltm project show myproject");
-                let want_json = json || json_pretty;
-                if let Err(e) = self.show_project_summary_internal(&project, want_json).await {
-                    if json {
-                        if let Some(validation_error) = e.downcast_ref::<ValidationError>() {
-                            println!("{}", crate::json_formatting::format_error_json(validation_error));
-                            return Ok(());
-                        }
-                    }
-                    return Err(e);
-                }
+                self.show_project_summary_internal(&project).await?;
             }
         }
         Ok(())
@@ -764,73 +2321,338 @@ impl CommandHandler {
     // Internal helper methods for new command structure
     async fn handle_ticket_action(&mut self, action: TicketAction) -> Result<()> {
         match action {
-            TicketAction::Create { project, name, description } => {
-                self.create_ticket_internal(project, name, description).await?;
+            TicketAction::Create { project, name, description, due, status, kind, severity, component, force } => {
+                let fields = NewTicketFields { due, status, kind, severity, component };
+                self.create_ticket_internal(project, name, description, fields, force).await?;
+            }
+            TicketAction::List {
+                project,
+                status,
+                kind,
+                severity,
+                component,
+                sort,
+                comment_contains,
+                regex,
+                archived,
+                created_within,
+                created_before,
+                updated_within,
+                updated_before,
+                watch,
+            } => {
+                let filters = ListFilters {
+                    project,
+                    status,
+                    kind,
+                    severity,
+                    component,
+                    sort,
+                    comment_contains,
+                    regex,
+                    archived,
+                    created_within,
+                    created_before,
+                    updated_within,
+                    updated_before,
+                };
+                match watch {
+                    Some(interval) => self.watch_view(WatchView::List(Box::new(filters)), interval).await?,
+                    None => self.list_tickets_internal(filters).await?,
+                }
             }
-            TicketAction::List { project, status, sort } => {
-                self.list_tickets_internal(project, status, Some(sort), false).await?;
+            TicketAction::CreateBulk { project, from_file, nest } => {
+                self.create_bulk_internal(&project, &from_file, nest).await?;
             }
             TicketAction::Show { ticket_id, full } => {
-                self.show_ticket_internal(&ticket_id, full, false).await?;
+                self.show_ticket_internal(&ticket_id, full).await?;
             }
             TicketAction::Update { ticket_id, field, value } => {
                 self.update_ticket_field_internal(&ticket_id, &field, &value).await?;
             }
-            TicketAction::Delete { ticket_id, force } => {
+            TicketAction::Delete { ticket_id: Some(ticket_id), force, .. } => {
                 self.delete_ticket_internal(&ticket_id, force).await?;
             }
-            TicketAction::Move { ticket_id, project } => {
-                self.move_ticket_internal(&ticket_id, &project).await?;
-            }
-            TicketAction::Copy { ticket_id, project } => {
-                self.copy_ticket_internal(&ticket_id, project).await?;
+            TicketAction::Delete { ticket_id: None, project, status, force } => {
+                self.bulk_delete_internal(project, status, force).await?;
             }
-        }
-        Ok(())
+            TicketAction::Unarchive { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.unarchive_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Ticket {} unarchived", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is not archived", validated_ticket_id));
+                }
+            }
+            TicketAction::Star { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.star_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Starred ticket {}; see your starred tickets with 'ltm starred'", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is already starred", validated_ticket_id));
+                }
+            }
+            TicketAction::Unstar { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.unstar_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Unstarred ticket {}", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is not starred", validated_ticket_id));
+                }
+            }
+            TicketAction::Pin { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.pin_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Pinned ticket {} to the top of lists and the board", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is already pinned", validated_ticket_id));
+                }
+            }
+            TicketAction::Unpin { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.unpin_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Unpinned ticket {}", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is not pinned", validated_ticket_id));
+                }
+            }
+            TicketAction::Lock { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.lock_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Locked ticket {}; edits, comments, and deletes now need --unlock", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is already locked", validated_ticket_id));
+                }
+            }
+            TicketAction::Unlock { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                if self.db.unlock_ticket(validated_ticket_id).await? {
+                    feedback::show_success(&format!("Unlocked ticket {}; it can change again", validated_ticket_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} is not locked", validated_ticket_id));
+                }
+            }
+            TicketAction::Target { ticket_id, release } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? else {
+                    return Err(ValidationError::TicketNotFound(validated_ticket_id).into());
+                };
+                self.check_ticket_lock(validated_ticket_id).await?;
+                let release = match release.trim() {
+                    "" | "none" => None,
+                    release => Some(self.check_release(&ticket.project, release).await?),
+                };
+                self.db.update_ticket_release(validated_ticket_id, release.as_deref()).await?;
+                match release {
+                    Some(release) => feedback::show_success(&format!("Ticket {} planned for {}", validated_ticket_id, release)),
+                    None => feedback::show_success(&format!("Ticket {} no longer planned for a release", validated_ticket_id)),
+                }
+            }
+            TicketAction::Move { ticket_id, project } => {
+                let project = match project {
+                    Some(project) => project,
+                    None => match self.pick_project(&ticket_id).await? {
+                        Some(project) => project,
+                        None => {
+                            feedback::show_info("Operation cancelled");
+                            return Ok(());
+                        }
+                    },
+                };
+                self.move_ticket_internal(&ticket_id, &project).await?;
+            }
+            TicketAction::MoveUp { ticket_id } => {
+                self.rank_ticket_internal(&ticket_id, |position| position.saturating_sub(1).max(1)).await?;
+            }
+            TicketAction::MoveDown { ticket_id } => {
+                self.rank_ticket_internal(&ticket_id, |position| position + 1).await?;
+            }
+            TicketAction::MoveTo { ticket_id, position } => {
+                self.rank_ticket_internal(&ticket_id, |_| position as usize).await?;
+            }
+            TicketAction::Copy { ticket_id, project } => {
+                self.copy_ticket_internal(&ticket_id, project).await?;
+            }
+            TicketAction::LinkUrl { ticket_id, url, label } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                let validated_url = validate_url(&url)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                self.check_ticket_lock(validated_ticket_id).await?;
+                let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+                self.db.add_ticket_url(validated_ticket_id, &validated_url, label.as_deref()).await?;
+                let count = self.db.get_ticket_urls(validated_ticket_id).await?.len();
+                feedback::show_success(&format!("Linked {} to ticket {} (link {})", validated_url, validated_ticket_id, count));
+            }
+            TicketAction::Depend { ticket_id, blocker_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                let validated_blocker_id = validate_ticket_id(&blocker_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                self.validate_ticket_exists(validated_blocker_id).await?;
+                if validated_ticket_id == validated_blocker_id {
+                    anyhow::bail!("A ticket can't wait on itself");
+                }
+                self.check_ticket_lock(validated_ticket_id).await?;
+                let dependencies = self.db.dependencies().await?;
+                if transitive_blockers(&dependencies, &[validated_blocker_id]).contains(&validated_ticket_id) {
+                    anyhow::bail!(
+                        "Ticket {} already waits on ticket {}, so this would make a cycle",
+                        validated_blocker_id,
+                        validated_ticket_id
+                    );
+                }
+                if self.db.add_dependency(validated_ticket_id, validated_blocker_id).await? {
+                    feedback::show_success(&format!("Ticket {} now waits on ticket {}", validated_ticket_id, validated_blocker_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} already waits on ticket {}", validated_ticket_id, validated_blocker_id));
+                }
+            }
+            TicketAction::Undepend { ticket_id, blocker_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                let validated_blocker_id = validate_ticket_id(&blocker_id)?;
+                self.check_ticket_lock(validated_ticket_id).await?;
+                if self.db.remove_dependency(validated_ticket_id, validated_blocker_id).await? {
+                    feedback::show_success(&format!("Ticket {} no longer waits on ticket {}", validated_ticket_id, validated_blocker_id));
+                } else {
+                    feedback::show_info(&format!("Ticket {} doesn't wait on ticket {}", validated_ticket_id, validated_blocker_id));
+                }
+            }
+            TicketAction::Similar { ticket_id, limit } => {
+                self.similar_tickets_internal(&ticket_id, limit).await?;
+            }
+        }
+        Ok(())
     }
 
     async fn handle_project_action(&mut self, action: ProjectAction) -> Result<()> {
         match action {
             ProjectAction::Show { project } => {
-                self.show_project_summary_internal(&project, false).await?;
+                self.show_project_summary_internal(&project).await?;
             }
             ProjectAction::List => {
                 self.handle_list_projects().await?;
             }
             ProjectAction::Summary { project } => {
-                self.show_project_summary_internal(&project, false).await?;
+                self.show_project_summary_internal(&project).await?;
             }
             ProjectAction::Stats { project } => {
                 if let Some(proj) = project {
-                    self.show_project_summary_internal(&proj, false).await?;
+                    self.show_project_summary_internal(&proj).await?;
                 } else {
                     self.handle_list_projects().await?;
                 }
             }
+            ProjectAction::Rename { old, new } => {
+                self.rename_project_internal(&old, &new).await?;
+            }
+            ProjectAction::Delete { project, archive, export_first, force } => {
+                self.delete_project_internal(&project, archive, export_first.as_deref(), force).await?;
+            }
+            ProjectAction::Set { project, key, value } => {
+                let project = validate_project_name(&project)?;
+                let value = ProjectSettings::normalize(&key, &value)?;
+                if key == "default_status" {
+                    self.db.get_project_settings(&project).await?.validate_status(&project, &value)?;
+                }
+                self.db.set_project_setting(&project, &key, &value).await?;
+                feedback::show_success(&format!("Set {} for project '{}' to {}", key, project, value));
+            }
+            ProjectAction::Unset { project, key } => {
+                let project = validate_project_name(&project)?;
+                if let Some(key) = key.as_deref().filter(|k| !SETTING_KEYS.contains(k)) {
+                    return Err(ValidationError::UnknownSetting(key.to_string()).into());
+                }
+                let removed = self.db.unset_project_settings(&project, key.as_deref()).await?;
+                if removed == 0 {
+                    feedback::show_info(&format!("No matching settings for project '{}'", project));
+                } else {
+                    feedback::show_success(&format!("Removed {} settings from project '{}'", removed, project));
+                }
+            }
+            ProjectAction::Settings { project } => {
+                let project = validate_project_name(&project)?;
+                let pairs = self.db.get_project_settings(&project).await?.to_pairs();
+                if pairs.is_empty() {
+                    feedback::show_info(&format!("No settings for project '{}'; defaults apply", project));
+                } else {
+                    let text = pairs.iter().map(|(key, value)| format!("{} = {}", key, value)).collect::<Vec<_>>();
+                    let settings: serde_json::Map<String, serde_json::Value> =
+                        pairs.into_iter().map(|(key, value)| (key.to_string(), value.into())).collect();
+                    self.renderer.print(&Report::new(text.join("\n"), serde_json::json!({ "project": project, "settings": settings })));
+                }
+            }
         }
         Ok(())
     }
 
     async fn handle_comment_action(&mut self, action: CommentAction) -> Result<()> {
         match action {
-            CommentAction::Add { ticket_id, content } => {
+            CommentAction::Add { ticket_id, content, snippet: Some(snippet) } => {
+                self.add_snippet_comment_internal(&ticket_id, &snippet, content.as_deref()).await?;
+            }
+            CommentAction::Add { ticket_id, content, snippet: None } => {
+                // A single argument is the comment for the focused ticket
+                let (ticket_id, content) = match content {
+                    Some(content) => (ticket_id, content),
+                    None => (self.resolve_ticket_id(None).await?.to_string(), ticket_id),
+                };
                 self.add_comment_internal(&ticket_id, &content).await?;
             }
+            CommentAction::Snippets => {
+                if self.config.snippets.is_empty() {
+                    feedback::show_info("No snippets; add them under [snippets] in config.toml");
+                    return Ok(());
+                }
+                let mut lines = Vec::new();
+                for (name, template) in &self.config.snippets {
+                    lines.push(format!("{}{}", icon("📝 "), name));
+                    lines.extend(template.lines().map(|line| format!("   {}", line)));
+                }
+                self.renderer.print(&Report::new(lines.join("\n"), serde_json::json!({ "snippets": self.config.snippets })));
+            }
+            CommentAction::Reply { comment_id, content } => {
+                let validated_comment_id = validate_ticket_id(&comment_id)?; // reuse positive integer validation
+                let validated_content = validate_content_length(&content, ContentType::Comment)?;
+                match self.db.get_comment(validated_comment_id).await? {
+                    Some(parent) => {
+                        self.check_ticket_lock(parent.ticket_id).await?;
+                        let reply_id = self.db.add_comment_reply(&parent, &validated_content).await?;
+                        feedback::show_success(&format!(
+                            "Reply #{} added to comment #{} on ticket {}",
+                            reply_id, parent.id, parent.ticket_id
+                        ));
+                    }
+                    None => feedback::show_error(&format!("Comment #{} not found", validated_comment_id)),
+                }
+            }
             CommentAction::List { ticket_id } => {
                 self.list_comments_internal(&ticket_id).await?;
             }
+            CommentAction::Promote { comment_id, project, name } => {
+                self.promote_comment_internal(&comment_id, project, name).await?;
+            }
             CommentAction::Show { comment_id } => {
                 let validated_comment_id = validate_ticket_id(&comment_id)?; // reuse positive integer validation
                 let pb = feedback::create_progress_bar("Loading comment");
                 if let Some(comment) = self.db.get_comment(validated_comment_id).await? {
                     pb.finish_and_clear();
-                    println!(
-                        "💬 Comment #{} (ticket {} at {}):\n{}",
+                    let reply_to = comment.parent_comment_id.map(|p| format!(", reply to #{}", p)).unwrap_or_default();
+                    let text = format!(
+                        "{}Comment #{} (ticket {}{} at {}):\n{}",
+                        icon("💬 "),
                         comment.id,
                         comment.ticket_id,
+                        reply_to,
                         comment.created_at.format("%Y-%m-%d %H:%M"),
                         comment.content
                     );
+                    self.renderer.print(&Report::new(text, serde_json::json!({ "comment": comment })));
                 } else {
                     pb.finish_and_clear();
                     feedback::show_info(&format!("Comment #{} not found", validated_comment_id));
@@ -839,6 +2661,7 @@ impl CommandHandler {
             CommentAction::Update { comment_id, content } => {
                 let validated_comment_id = validate_ticket_id(&comment_id)?;
                 let validated_content = validate_content_length(&content, ContentType::Comment)?;
+                self.check_comment_lock(validated_comment_id).await?;
                 let pb = feedback::create_progress_bar("Updating comment");
                 self.db.update_comment(validated_comment_id, &validated_content).await?;
                 pb.finish_with_message("Comment updated");
@@ -846,6 +2669,7 @@ impl CommandHandler {
             }
             CommentAction::Delete { comment_id } => {
                 let validated_comment_id = validate_ticket_id(&comment_id)?;
+                self.check_comment_lock(validated_comment_id).await?;
                 if !interactive::confirm_destructive_action("delete", &format!("comment #{}", validated_comment_id))? {
                     feedback::show_info("Operation cancelled");
                     return Ok(());
@@ -859,122 +2683,1373 @@ impl CommandHandler {
         Ok(())
     }
 
-    async fn handle_time_action(&mut self, action: TimeAction) -> Result<()> {
+    async fn handle_time_action(&mut self, action: TimeAction) -> Result<()> {
+        let unlock = self.unlock;
+        match action {
+            TimeAction::Start { ticket_id, note } => {
+                let validated_ticket_id = self.resolve_ticket_id(ticket_id.as_deref()).await?;
+                let validated_note = note.map(|n| validate_content_length(n.trim(), ContentType::TimerNote)).transpose()?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                self.db
+                    .start_timer_with_note(validated_ticket_id, Utc::now().naive_utc(), validated_note.as_deref())
+                    .await?;
+                feedback::show_time_tracking_progress("feedback.start_tracking", validated_ticket_id);
+            }
+            TimeAction::Stop { ticket_id, all } => {
+                if let Some(ticket_id_str) = ticket_id {
+                    let validated_ticket_id = validate_ticket_id(&ticket_id_str)?;
+                    self.stop_time_tracking_internal(validated_ticket_id).await?;
+                } else if let (false, Some(focused)) = (all, self.focused_ticket_id().await?) {
+                    debug!(ticket_id = focused, "stopping the focused ticket's timer");
+                    self.stop_time_tracking_internal(focused).await?;
+                } else {
+                    debug!(all, "stopping all active timers");
+                    self.stop_all_active_timers().await?;
+                }
+            }
+            TimeAction::Cancel { ticket_id } => {
+                if let Some(ticket_id_str) = ticket_id {
+                    let validated_ticket_id = validate_ticket_id(&ticket_id_str)?;
+                    self.cancel_time_tracking_internal(validated_ticket_id).await?;
+                } else {
+                    self.cancel_all_active_timers().await?;
+                }
+            }
+            TimeAction::Pause { ticket_id } => {
+                let validated_ticket_id = self.resolve_ticket_id(ticket_id.as_deref()).await?;
+                self.pause_time_tracking_internal(validated_ticket_id).await?;
+            }
+            TimeAction::Resume { ticket_id } => {
+                let validated_ticket_id = self.resolve_ticket_id(ticket_id.as_deref()).await?;
+                self.resume_time_tracking_internal(validated_ticket_id).await?;
+            }
+            TimeAction::Log { ticket_id, duration, date, at, non_billable } => {
+                // A single argument is the duration for the focused ticket
+                let (ticket_id, duration) = match duration {
+                    Some(duration) => (ticket_id, duration),
+                    None => (self.resolve_ticket_id(None).await?.to_string(), ticket_id),
+                };
+                let logged_at = match (date, at) {
+                    (None, None) => None,
+                    (date, at) => {
+                        let day = date.map(|d| validate_date(&d)).transpose()?.unwrap_or_else(|| Local::now().date_naive());
+                        Some(logged_at_local(day, at.as_deref().map(validate_time_of_day).transpose()?)?)
+                    }
+                };
+                self.check_billing_lock(logged_at.unwrap_or_else(|| Utc::now().naive_utc()), unlock).await?;
+                self.log_time_duration_internal(&ticket_id, &duration, logged_at, !non_billable).await?;
+            }
+            TimeAction::List { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                let pb = feedback::create_progress_bar("Loading time logs");
+                let logs = self.db.get_time_logs(validated_ticket_id).await?;
+                pb.finish_and_clear();
+                if logs.is_empty() {
+                    feedback::show_info(&format!("No time logs for ticket {}", validated_ticket_id));
+                } else {
+                    let mut lines = vec![format!("{}Time logs for ticket {}:", icon("⏱️  "), validated_ticket_id)];
+                    for log in &logs {
+                        let span = match (log.started_at, log.ended_at) {
+                            (Some(s), Some(e)) => format!(" ({} → {})", s.format("%Y-%m-%d %H:%M"), e.format("%Y-%m-%d %H:%M")),
+                            _ => String::new(),
+                        };
+                        let note = log.note.as_deref().map(|n| format!(" - {}", n)).unwrap_or_default();
+                        let logged = format_minutes(log.hours as i64 * 60 + log.minutes as i64);
+                        let billable = if log.billable { "" } else { " (non-billable)" };
+                        lines.push(format!("  {}#{}: {}{}{}{}", icon("• "), log.id, logged, billable, span, note));
+                    }
+                    lines.push(feedback::success_message(&format!("Found {} time log(s)", logs.len())));
+                    let data = serde_json::json!({ "ticket_id": validated_ticket_id, "time_logs": logs });
+                    self.renderer.print(&Report::new(lines.join("\n"), data));
+                }
+            }
+            TimeAction::Active => {
+                self.handle_show_active_timers().await?;
+            }
+            TimeAction::Current { .. } => {
+                self.show_current_timer().await?;
+            }
+            TimeAction::Report { project, from, to, .. } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let (from, to) = self.report_period(from.as_deref(), to.as_deref())?;
+                let rows = self.db.hours_by_ticket(from, to, validated_project.as_deref()).await?;
+                let config = &self.config.invoice;
+                let rows = rows
+                    .into_iter()
+                    .map(|row| row.with_default_rate(config.rate).with_default_currency(config.currency.as_deref()))
+                    .collect();
+                let currencies = self.config.currencies.clone();
+                self.renderer.print(&TimeReport { rows, project: validated_project, from, to, currencies });
+            }
+            TimeAction::Export { project, from, to, .. } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let (from, to) = self.report_period(from.as_deref(), to.as_deref())?;
+                let entries = self.db.time_log_entries(from, to, validated_project.as_deref()).await?;
+                self.renderer.print(&TimeExport { entries, project: validated_project, from, to });
+            }
+            TimeAction::Invoice { project, from, to, client, number, rate, file } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let (from, to) = self.report_period(from.as_deref(), to.as_deref())?;
+                if rate.is_some_and(|rate| !rate.is_finite() || rate < 0.0) {
+                    anyhow::bail!("The hourly rate must be a positive number");
+                }
+                let config = &self.config.invoice;
+                let rows = self.db.hours_by_ticket(from, to, validated_project.as_deref()).await?;
+                let rows: Vec<_> = rows
+                    .into_iter()
+                    .map(|row| row.with_default_rate(config.rate).with_default_currency(config.currency.as_deref()))
+                    .collect();
+                let logs = self.db.time_logs_between(from, to, validated_project.as_deref()).await?;
+                let mut tickets = invoice::group_by_ticket(&rows, &logs);
+                if tickets.is_empty() {
+                    anyhow::bail!("No billable time logged from {} to {}; there is nothing to invoice", from, to);
+                }
+                if rate.is_some() {
+                    tickets.iter_mut().for_each(|ticket| ticket.rate = rate);
+                }
+
+                let number = number.unwrap_or_else(|| to.format("%Y-%m").to_string());
+                let mut invoice = Invoice::new(config, number, Local::now().date_naive(), from, to);
+                invoice.client = config.client_address(client.as_deref(), validated_project.as_deref());
+                invoice.tickets = tickets;
+                invoice.currencies = self.config.currencies.clone();
+
+                let file = file.unwrap_or_else(|| format!("invoice-{}.pdf", invoice.number.replace(['/', '\\'], "-")).into());
+                fs::write(&file, invoice.to_pdf())
+                    .map_err(|e| anyhow::anyhow!("Could not write the invoice to {}: {}", file.display(), e))?;
+                let minutes = invoice.minutes();
+                let totals = invoice.totals();
+                let amount = invoice.total_text().map(|amount| format!(", {}", amount)).unwrap_or_default();
+                let text = feedback::success_message(&format!(
+                    "Wrote invoice {} for {} tickets ({}{}) to {}",
+                    invoice.number,
+                    invoice.tickets.len(),
+                    format_minutes(minutes),
+                    amount,
+                    file.display()
+                ));
+                let data = serde_json::json!({
+                    "file": file,
+                    "total_minutes": minutes,
+                    "total_amount": match totals.as_slice() {
+                        [(_, _, amount)] => Some(*amount),
+                        _ => None,
+                    },
+                    "totals": totals
+                        .iter()
+                        .map(|(currency, _, amount)| serde_json::json!({ "currency": currency, "amount": amount }))
+                        .collect::<Vec<_>>(),
+                    "invoice": invoice,
+                });
+                self.renderer.print(&Report::new(text, data));
+            }
+            TimeAction::Summary { ticket_id } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                let logs = self.db.get_time_logs(validated_ticket_id).await?;
+                let total_minutes: i64 = logs.iter().map(|l| l.hours as i64 * 60 + l.minutes as i64).sum();
+                let text = format!(
+                    "{}Time summary for ticket {}: {} ({} logs)\n{}",
+                    icon("⏱️  "),
+                    validated_ticket_id,
+                    format_minutes(total_minutes),
+                    logs.len(),
+                    feedback::success_message("Summary complete")
+                );
+                let data = serde_json::json!({
+                    "ticket_id": validated_ticket_id,
+                    "logs": logs.len(),
+                    "total": HumanDuration::from_minutes(total_minutes),
+                });
+                self.renderer.print(&Report::new(text, data));
+            }
+            TimeAction::Edit { day } => {
+                self.edit_timesheet_internal(day.as_deref(), unlock).await?;
+            }
+            TimeAction::Import { file, ticket, date, duration, note, dry_run } => {
+                let columns = ColumnMap { ticket, date, duration, note };
+                self.import_time_internal(&file, &columns, dry_run, unlock).await?;
+            }
+            TimeAction::Update { log_id, duration, start, end, billable, non_billable } => {
+                if duration.is_some() || start.is_some() || end.is_some() {
+                    self.update_time_log_internal(&log_id, duration.as_deref(), start.as_deref(), end.as_deref(), unlock)
+                        .await?;
+                }
+                if billable || non_billable {
+                    let validated_log_id = validate_ticket_id(&log_id)?;
+                    let Some(log) = self.db.get_time_log(validated_log_id).await? else {
+                        anyhow::bail!("Time log #{} not found", validated_log_id);
+                    };
+                    self.check_billing_lock(log.started_at.unwrap_or(log.created_at), unlock).await?;
+                    self.db.update_time_log_billable(validated_log_id, billable).await?;
+                    let state = if billable { "billable" } else { "non-billable" };
+                    feedback::show_success(&format!("Time log #{} is now {}", validated_log_id, state));
+                }
+            }
+            TimeAction::Delete { log_id } => {
+                let validated_log_id = validate_ticket_id(&log_id)?;
+                if let Some(log) = self.db.get_time_log(validated_log_id).await? {
+                    self.check_billing_lock(log.started_at.unwrap_or(log.created_at), unlock).await?;
+                }
+                if !interactive::confirm_destructive_action("delete", &format!("time log #{}", validated_log_id))? {
+                    feedback::show_info("Operation cancelled");
+                    return Ok(());
+                }
+                let pb = feedback::create_progress_bar("Deleting time log");
+                self.db.delete_time_log(validated_log_id).await?;
+                pb.finish_with_message("Time log deleted");
+                feedback::show_success(&format!("Deleted time log #{}", validated_log_id));
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_component_action(&mut self, action: ComponentAction) -> Result<()> {
+        match action {
+            ComponentAction::Add { project, name } => {
+                let project = validate_project_name(&project)?;
+                let name = validate_component(&name)?;
+                if self.db.add_component(&project, &name).await? {
+                    feedback::show_success(&format!("Added component '{}' to project '{}'", name, project));
+                } else {
+                    feedback::show_info(&format!("Project '{}' already has component '{}'", project, name));
+                }
+            }
+            ComponentAction::Remove { project, name } => {
+                let project = validate_project_name(&project)?;
+                let name = self.check_component(&project, &name).await?;
+                let tickets = self.db.list_tickets(Some(&project)).await?;
+                let mut moved = 0;
+                for ticket in tickets.iter().filter(|t| t.component.as_deref() == Some(name.as_str())) {
+                    self.db.update_ticket_component(ticket.id, None).await?;
+                    moved += 1;
+                }
+                self.db.remove_component(&project, &name).await?;
+                feedback::show_success(&format!("Removed component '{}' from project '{}'", name, project));
+                if moved > 0 {
+                    feedback::show_info(&format!("Took {} ticket(s) out of it", moved));
+                }
+            }
+            ComponentAction::List { project } => {
+                let project = validate_project_name(&project)?;
+                let components = self.db.components(&project).await?;
+                let counts = self.db.get_project_summary(&project).await?.components;
+                let text = if components.is_empty() {
+                    feedback::info_message(&format!("Project '{}' has no components", project))
+                } else {
+                    let mut lines = vec![format!("{}Components of {}:", icon("🧱 "), project)];
+                    for component in &components {
+                        let count = counts.get(&component.name).copied().unwrap_or(0);
+                        lines.push(format!("  {}{} ({} ticket(s))", icon("• "), component.name, count));
+                    }
+                    lines.join("\n")
+                };
+                let data: Vec<_> = components
+                    .iter()
+                    .map(|c| serde_json::json!({ "name": c.name, "tickets": counts.get(&c.name).copied().unwrap_or(0) }))
+                    .collect();
+                self.renderer.print(&Report::new(text, serde_json::json!({ "project": project, "components": data })));
+            }
+        }
+        Ok(())
+    }
+
+    /// A component name, checked against the project's components
+    async fn check_component(&self, project: &str, name: &str) -> Result<String> {
+        let name = validate_component(name)?;
+        let components: Vec<String> = self.db.components(project).await?.into_iter().map(|c| c.name).collect();
+        if !components.contains(&name) {
+            return Err(ValidationError::UnknownComponent { component: name, project: project.to_string(), allowed: components }.into());
+        }
+        Ok(name)
+    }
+
+    async fn handle_release_action(&mut self, action: ReleaseAction) -> Result<()> {
+        match action {
+            ReleaseAction::Create { project, name, due } => {
+                let project = validate_project_name(&project)?;
+                let name = validate_release(&name)?;
+                let due_date = due.as_deref().map(validate_date).transpose()?;
+                if self.db.create_release(&project, &name, due_date).await? {
+                    match due_date {
+                        Some(date) => feedback::show_success(&format!("Created release {} of project '{}', due {}", name, project, date.format("%Y-%m-%d"))),
+                        None => feedback::show_success(&format!("Created release {} of project '{}'", name, project)),
+                    }
+                    feedback::show_info(&format!("Plan tickets for it with: ltm ticket target <id> {}", name));
+                } else {
+                    feedback::show_info(&format!("Project '{}' already has release {}", project, name));
+                }
+            }
+            ReleaseAction::Due { project, name, date } => {
+                let project = validate_project_name(&project)?;
+                let name = self.check_release(&project, &name).await?;
+                let due_date = match date.to_lowercase().as_str() {
+                    "none" | "clear" => None,
+                    _ => Some(validate_date(&date)?),
+                };
+                self.db.update_release_due_date(&project, &name, due_date).await?;
+                match due_date {
+                    Some(date) => feedback::show_success(&format!("Release {} of project '{}' is due {}", name, project, date.format("%Y-%m-%d"))),
+                    None => feedback::show_success(&format!("Cleared the due date of release {} of project '{}'", name, project)),
+                }
+            }
+            ReleaseAction::List { project } => {
+                let project = validate_project_name(&project)?;
+                let releases = self.db.releases(Some(&project)).await?;
+                let tickets = self.db.list_tickets(Some(&project)).await?;
+                let progress = |name: &str| {
+                    let planned: Vec<&Ticket> = tickets.iter().filter(|t| t.target_release.as_deref() == Some(name)).collect();
+                    (planned.iter().filter(|t| is_finished_status(&t.status)).count(), planned.len())
+                };
+                let text = if releases.is_empty() {
+                    feedback::info_message(&format!("Project '{}' has no releases", project))
+                } else {
+                    let mut lines = vec![format!("{}Releases of {}:", icon("🚀 "), project)];
+                    for release in &releases {
+                        let (done, total) = progress(&release.name);
+                        let due = release.due_date.map(|date| format!(", due {}", date.format("%Y-%m-%d"))).unwrap_or_default();
+                        lines.push(format!("  {}{} ({} of {} done{})", icon("• "), release.name, done, total, due));
+                    }
+                    lines.join("\n")
+                };
+                let data: Vec<_> = releases
+                    .iter()
+                    .map(|release| {
+                        let (done, total) = progress(&release.name);
+                        serde_json::json!({ "name": release.name, "created_at": release.created_at, "due_date": release.due_date, "done": done, "total": total })
+                    })
+                    .collect();
+                self.renderer.print(&Report::new(text, serde_json::json!({ "project": project, "releases": data })));
+            }
+            ReleaseAction::Show { project, name } => {
+                let project = validate_project_name(&project)?;
+                let name = self.check_release(&project, &name).await?;
+                let tickets = self.db.release_tickets(&project, &name).await?;
+                let done = tickets.iter().filter(|t| is_finished_status(&t.status)).count();
+                let remaining: Vec<&Ticket> = tickets.iter().filter(|t| !is_finished_status(&t.status)).collect();
+                let data = serde_json::json!({
+                    "project": project,
+                    "release": name,
+                    "total": tickets.len(),
+                    "done": done,
+                    "remaining": remaining,
+                });
+                self.renderer.print(&Report::new(format_release(&project, &name, &tickets), data));
+            }
+        }
+        Ok(())
+    }
+
+    /// A release name, checked against the project's releases
+    async fn check_release(&self, project: &str, name: &str) -> Result<String> {
+        let name = validate_release(name)?;
+        let releases: Vec<String> = self.db.releases(Some(project)).await?.into_iter().map(|r| r.name).collect();
+        if !releases.contains(&name) {
+            return Err(ValidationError::UnknownRelease { release: name, project: project.to_string(), allowed: releases }.into());
+        }
+        Ok(name)
+    }
+
+    /// Tickets taking part in a dependency, or a release's tickets, along with everything
+    /// they wait on
+    async fn handle_graph(&mut self, project: Option<&str>, release: Option<&str>, format: GraphFormat) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let release = match (project.as_deref(), release) {
+            (Some(project), Some(release)) => Some(self.check_release(project, release).await?),
+            (None, Some(_)) => anyhow::bail!("--release needs a project, e.g. ltm graph webapp --release v1.4"),
+            (_, None) => None,
+        };
+        let tickets = self.db.list_tickets(None).await?;
+        let dependencies = self.db.dependencies().await?;
+        let linked = |id: i64| dependencies.iter().any(|d| d.ticket_id == id || d.blocker_id == id);
+        let roots: Vec<i64> = tickets
+            .iter()
+            .filter(|t| project.as_deref().is_none_or(|p| t.project == p))
+            .filter(|t| match &release {
+                Some(release) => t.target_release.as_ref() == Some(release),
+                None => linked(t.id),
+            })
+            .map(|t| t.id)
+            .collect();
+        let mut ids = transitive_blockers(&dependencies, &roots);
+        ids.extend(roots);
+        let mut tickets: Vec<Ticket> = tickets.into_iter().filter(|t| ids.contains(&t.id)).collect();
+        tickets.sort_by_key(|t| t.id);
+        let dependencies: Vec<_> = dependencies.into_iter().filter(|d| ids.contains(&d.ticket_id) && ids.contains(&d.blocker_id)).collect();
+
+        let text = match format {
+            GraphFormat::Dot => format_dependency_graph(&tickets, &dependencies),
+        };
+        let nodes: Vec<_> = tickets
+            .iter()
+            .map(|t| serde_json::json!({ "id": t.id, "name": t.name, "project": t.project, "status": t.status }))
+            .collect();
+        let edges: Vec<_> = dependencies.iter().map(|d| serde_json::json!({ "ticket_id": d.ticket_id, "blocker_id": d.blocker_id })).collect();
+        let data = serde_json::json!({ "project": project, "release": release, "tickets": nodes, "dependencies": edges });
+        self.renderer.print(&Report::new(text, data));
+        Ok(())
+    }
+
+    async fn handle_roadmap(&mut self, project: Option<&str>, hours_per_day: f64) -> Result<()> {
+        if !(0.0..=24.0).contains(&hours_per_day) {
+            anyhow::bail!("--hours-per-day must be between 0 and 24");
+        }
+        let project = project.map(validate_project_name).transpose()?;
+        let today = Local::now().date_naive();
+        let mut items = Vec::new();
+        for release in self.db.releases(project.as_deref()).await? {
+            let tickets = self.db.release_tickets(&release.project, &release.name).await?;
+            items.push(roadmap_item(&release, &tickets, today, hours_per_day));
+        }
+        let data = serde_json::json!({ "project": project, "today": today, "hours_per_day": hours_per_day, "releases": items });
+        self.renderer.print(&Report::new(format_roadmap(project.as_deref(), &items), data));
+        Ok(())
+    }
+
+    /// A release by name, in `project` when given or else the only project that has one by
+    /// that name
+    async fn find_release(&self, name: &str, project: Option<&str>) -> Result<Release> {
+        let project = project.map(validate_project_name).transpose()?;
+        let name = match &project {
+            Some(project) => self.check_release(project, name).await?,
+            None => validate_release(name)?,
+        };
+        let mut releases: Vec<Release> = self.db.releases(project.as_deref()).await?.into_iter().filter(|r| r.name == name).collect();
+        match releases.len() {
+            0 => anyhow::bail!("No project has a release named {}", name),
+            1 => Ok(releases.remove(0)),
+            _ => {
+                let projects: Vec<String> = releases.into_iter().map(|r| r.project).collect();
+                anyhow::bail!("Several projects have a release named {} ({}); pick one with --project", name, projects.join(", "))
+            }
+        }
+    }
+
+    async fn handle_daemon_action(&mut self, action: DaemonAction) -> Result<()> {
+        match action {
+            DaemonAction::Start => {
+                if let Some(pid) = daemon::ping().await {
+                    feedback::show_info(&format!("The daemon is already running (process {})", pid));
+                    return Ok(());
+                }
+                let mut command = std::process::Command::new(std::env::current_exe()?);
+                command
+                    .args(["daemon", "run"])
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null());
+                // Out of the terminal's process group, so Ctrl-C in the shell leaves it running
+                #[cfg(unix)]
+                std::os::unix::process::CommandExt::process_group(&mut command, 0);
+                command.spawn().context("Could not start the daemon")?;
+                for _ in 0..50 {
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    if let Some(pid) = daemon::ping().await {
+                        feedback::show_success(&format!(
+                            "Started the daemon (process {}); status lines and 'ltm time current' now come from it",
+                            pid
+                        ));
+                        return Ok(());
+                    }
+                }
+                anyhow::bail!("The daemon did not start; run 'ltm daemon run' to see why");
+            }
+            DaemonAction::Run => {
+                feedback::show_info(&format!("Answering status lines on {} (Ctrl-C to stop)", daemon::socket_path()?.display()));
+                daemon::run(self.db.clone()).await?;
+            }
+            DaemonAction::Stop => {
+                if daemon::stop().await? {
+                    feedback::show_success("Stopped the daemon");
+                } else {
+                    feedback::show_info("No daemon is running");
+                }
+            }
+            DaemonAction::Status => match daemon::ping().await {
+                Some(pid) => feedback::show_info(&format!("The daemon is running (process {}) on {}", pid, daemon::socket_path()?.display())),
+                None => feedback::show_info("No daemon is running; start one with 'ltm daemon start'"),
+            },
+        }
+        Ok(())
+    }
+
+    async fn handle_token_action(&mut self, action: TokenAction) -> Result<()> {
+        match action {
+            TokenAction::Create { name, read_only } => {
+                let name = name.trim();
+                if name.is_empty() || name.chars().count() > 50 {
+                    anyhow::bail!("A token name needs 1 to 50 characters");
+                }
+                let token = server::new_token()?;
+                if !self.db.create_api_token(name, &server::hash_token(&token), read_only).await? {
+                    anyhow::bail!("There is already a token named '{}'; revoke it first or pick another name", name);
+                }
+                let scope = if read_only { "read-only token" } else { "token" };
+                feedback::show_success(&format!("Created {} '{}'. Copy it now; it is not shown again:", scope, name));
+                println!("{}", token);
+                feedback::show_info("Clients send it as 'Authorization: Bearer <token>', or set it under [remote] in config.toml");
+            }
+            TokenAction::Revoke { name } => {
+                if !self.db.revoke_api_token(name.trim()).await? {
+                    anyhow::bail!("There is no token named '{}'; 'ltm serve token list' shows them", name.trim());
+                }
+                feedback::show_success(&format!("Revoked token '{}'; the server refuses it from now on", name.trim()));
+            }
+            TokenAction::List => {
+                let tokens = self.db.api_tokens().await?;
+                let text = if tokens.is_empty() {
+                    feedback::info_message("No tokens; the server's API is open to anyone who can reach it")
+                } else {
+                    let mut lines = vec![format!("{}Tokens:", icon("🔑 "))];
+                    for token in &tokens {
+                        let scope = if token.read_only { ", read-only" } else { "" };
+                        let by = token.created_by.as_deref().map(|user| format!(" by {}", user)).unwrap_or_default();
+                        let at = token.created_at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M");
+                        lines.push(format!("  {}{} (created {}{}{})", icon("• "), token.name, at, by, scope));
+                    }
+                    lines.join("\n")
+                };
+                self.renderer.print(&Report::new(text, serde_json::json!({ "tokens": tokens })));
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_billing_action(&mut self, action: BillingAction) -> Result<()> {
+        match action {
+            BillingAction::Lock { month } => {
+                let month = validate_month(&month)?;
+                let label = month.format("%Y-%m");
+                if self.db.lock_billing_month(month).await? {
+                    feedback::show_success(&format!("Locked {} for billing; its time logs now need --unlock to change", label));
+                } else {
+                    feedback::show_info(&format!("{} is already locked for billing", label));
+                }
+            }
+            BillingAction::Unlock { month } => {
+                let month = validate_month(&month)?;
+                let label = month.format("%Y-%m");
+                if self.db.unlock_billing_month(month).await? {
+                    feedback::show_success(&format!("Unlocked {}; its time logs can change again", label));
+                } else {
+                    feedback::show_info(&format!("{} is not locked for billing", label));
+                }
+            }
+            BillingAction::List => {
+                let locks = self.db.billing_locks().await?;
+                let text = if locks.is_empty() {
+                    feedback::info_message("No months are locked for billing")
+                } else {
+                    let mut lines = vec![format!("{}Locked for billing:", icon("🔒 "))];
+                    for lock in &locks {
+                        let by = lock.locked_by.as_deref().map(|user| format!(" by {}", user)).unwrap_or_default();
+                        let at = lock.locked_at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M");
+                        lines.push(format!("  {}{} (locked {}{})", icon("• "), lock.month, at, by));
+                    }
+                    lines.join("\n")
+                };
+                self.renderer.print(&Report::new(text, serde_json::json!({ "locks": locks })));
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse to add or change time dated `at` (UTC) in a month locked for billing,
+    /// unless `--unlock` was given
+    async fn check_billing_lock(&self, at: NaiveDateTime, unlock: bool) -> Result<()> {
+        if unlock {
+            return Ok(());
+        }
+        if let Some(month) = self.db.locked_month(at.date()).await? {
+            anyhow::bail!(
+                "Time logged in {} is locked for billing; add --unlock to change it anyway, or reopen the month with 'ltm billing unlock {}'",
+                month,
+                month
+            );
+        }
+        Ok(())
+    }
+
+    /// Refuse to change a ticket locked with `ltm ticket lock`, unless `--unlock` was given
+    async fn check_ticket_lock(&self, ticket_id: i64) -> Result<()> {
+        self.use_ticket(ticket_id);
+        if self.unlock {
+            return Ok(());
+        }
+        if let Some(lock) = self.db.ticket_lock(ticket_id).await? {
+            let by = lock.locked_by.map(|user| format!(" by {}", user)).unwrap_or_default();
+            anyhow::bail!(
+                "Ticket {} was locked{} on {}; add --unlock to change it anyway, or unlock it with 'ltm ticket unlock {}'",
+                ticket_id,
+                by,
+                Local.from_utc_datetime(&lock.locked_at).format("%Y-%m-%d"),
+                ticket_id
+            );
+        }
+        Ok(())
+    }
+
+    /// `check_ticket_lock` for the ticket a comment is on
+    async fn check_comment_lock(&self, comment_id: i64) -> Result<()> {
+        match self.db.get_comment(comment_id).await? {
+            Some(comment) => self.check_ticket_lock(comment.ticket_id).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn handle_update_target(&mut self, target: UpdateTarget) -> Result<()> {
+        match target {
+            UpdateTarget::Status { ticket_id, status, message, force } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                let status = match status {
+                    Some(status) => status,
+                    None => match self.pick_status(validated_ticket_id, force).await? {
+                        Some(status) => status,
+                        None => {
+                            feedback::show_info("Operation cancelled");
+                            return Ok(());
+                        }
+                    },
+                };
+                let validated_status = self.validate_ticket_status(validated_ticket_id, &status).await?;
+                let message = message.map(|message| validate_content_length(&message, ContentType::Comment)).transpose()?;
+                self.change_ticket_status(validated_ticket_id, &validated_status, force, !force, message.as_deref(), message.as_deref()).await?;
+            }
+            UpdateTarget::Name { ticket_id, name } => {
+                self.update_ticket_field_internal(&ticket_id, "name", &name).await?;
+            }
+            UpdateTarget::Description { ticket_id, description } => {
+                let desc = if let Some(d) = description {
+                    d
+                } else {
+                    feedback::show_info("Opening editor for description...");
+                    edit("")?.trim().to_string()
+                };
+                self.update_ticket_field_internal(&ticket_id, "description", &desc).await?;
+            }
+            UpdateTarget::Project { ticket_id, project } => {
+                self.move_ticket_internal(&ticket_id, &project).await?;
+            }
+            UpdateTarget::Due { ticket_id, due } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                self.check_ticket_lock(validated_ticket_id).await?;
+
+                let due_date = match due.to_lowercase().as_str() {
+                    "none" | "clear" => None,
+                    _ => Some(validate_date(&due)?),
+                };
+                self.db.update_ticket_due_date(validated_ticket_id, due_date).await?;
+                match due_date {
+                    Some(date) => feedback::show_success(&format!(
+                        "Ticket {} is due {}",
+                        validated_ticket_id,
+                        format_due_date(date, Local::now().date_naive())
+                    )),
+                    None => feedback::show_success(&format!("Cleared due date for ticket {}", validated_ticket_id)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The focused ticket, if one is set and still exists
+    async fn focused_ticket_id(&self) -> Result<Option<i64>> {
+        let Some(value) = self.db.get_state(FOCUS_KEY).await? else {
+            return Ok(None);
+        };
+        match value.parse::<i64>() {
+            Ok(id) if self.db.get_ticket(id).await?.is_some() => Ok(Some(id)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Validate an explicit ticket ID, or fall back to the focused ticket
+    async fn resolve_ticket_id(&self, ticket_id: Option<&str>) -> Result<i64> {
+        match ticket_id {
+            Some(id) => Ok(validate_ticket_id(id)?),
+            None => {
+                let focused = self.focused_ticket_id().await?;
+                debug!(?focused, "no ticket ID given, falling back to the focused ticket");
+                if let Some(focused) = focused {
+                    return Ok(focused);
+                }
+                let last = last_ticket().ok_or(ValidationError::NoFocusedTicket)?;
+                feedback::show_info(&format!("No ticket focused; using ticket {}, the last one used", last));
+                Ok(last)
+            }
+        }
+    }
+
+    /// Set the status of a `[shortcuts]` entry and add its comment, like `ltm block`
+    async fn run_shortcut(&mut self, name: &str, ticket_id: Option<&str>, message: Option<&str>) -> Result<()> {
+        let shortcut = self.config.shortcuts.get(name).cloned().with_context(|| format!("No shortcut '{}' in config.toml", name))?;
+        let validated_ticket_id = self.resolve_ticket_id(ticket_id).await?;
+        self.validate_ticket_exists(validated_ticket_id).await?;
+        let status = self.validate_ticket_status(validated_ticket_id, &shortcut.status).await?;
+        let comment = shortcut.comment_text(message).map(|c| validate_content_length(&c, ContentType::Comment)).transpose()?;
+
+        self.change_ticket_status(validated_ticket_id, &status, true, true, comment.as_deref(), comment.as_deref()).await
+    }
+
+    /// Report the schema version, apply pending migrations, and optionally copy in the data
+    /// of an older database
+    async fn migrate_internal(&mut self, status_only: bool, from: Option<&std::path::Path>) -> Result<()> {
+        let version = self.db.schema_version().await;
+        let pending = self.db.pending_migrations().await;
+        match version {
+            Some(version) => feedback::show_info(&format!("Schema version: {}", version)),
+            None => feedback::show_info("New database: no schema yet"),
+        }
+        if status_only {
+            if pending.is_empty() {
+                feedback::show_success("Database is up to date");
+            } else {
+                feedback::show_warning(&format!("{} pending migrations:", pending.len()));
+                let lines: Vec<String> = pending.iter().map(|(version, description)| format!("  {} {}", version, description)).collect();
+                let pending: Vec<serde_json::Value> = pending
+                    .iter()
+                    .map(|(version, description)| serde_json::json!({ "version": version, "description": description }))
+                    .collect();
+                self.renderer.print(&Report::new(lines.join("\n"), serde_json::json!({ "schema_version": version, "pending": pending })));
+            }
+            return Ok(());
+        }
+
+        let applied = self.db.migrate(&self.config.backup).await?;
+        for (version, description) in &applied {
+            feedback::show_success(&format!("Applied {} {}", version, description));
+        }
+        if applied.is_empty() {
+            feedback::show_success("Database is up to date");
+        }
+
+        if let Some(old) = from {
+            let existing = self.db.list_tickets(None).await?.len();
+            if existing > 0 {
+                return Err(anyhow::anyhow!(
+                    "The current database already has {} tickets; --from only copies into an empty database",
+                    existing
+                ));
+            }
+            let copied = self.db.import_from(old).await?;
+            feedback::show_success(&format!("Copied {} tickets and everything attached to them from {}", copied, old.display()));
+        }
+        Ok(())
+    }
+
+    /// Write everything changed after `since` (or everything) as JSON to `output` or stdout
+    async fn export_internal(
+        &mut self,
+        since: Option<&str>,
+        project: Option<&str>,
+        file: Option<&std::path::Path>,
+    ) -> Result<()> {
+        let since = since.map(validate_timestamp).transpose()?;
+        let project = project.map(validate_project_name).transpose()?;
+        // Taken before reading, so a change made during the export is picked up next time
+        let exported_at = Utc::now();
+        let export = crate::json_formatting::ChangesExport {
+            version: "1.0".to_string(),
+            exported_at,
+            since: since.map(|at| Utc.from_utc_datetime(&at)),
+            project: project.clone(),
+            tickets: self.db.tickets_changed_since(since, project.as_deref()).await?,
+            comments: self.db.comments_changed_since(since, project.as_deref()).await?,
+            time_logs: self.db.time_logs_changed_since(since, project.as_deref()).await?,
+        };
+        match file {
+            Some(path) => {
+                let json = crate::json_formatting::format_changes_export_json(&export);
+                fs::write(path, json).map_err(|e| anyhow::anyhow!("Could not write export to {}: {}", path.display(), e))?;
+                feedback::show_success(&format!(
+                    "Exported {} tickets, {} comments, and {} time logs to {}",
+                    export.tickets.len(),
+                    export.comments.len(),
+                    export.time_logs.len(),
+                    path.display()
+                ));
+            }
+            None => self.renderer.print_data(&serde_json::to_value(&export)?),
+        }
+        Ok(())
+    }
+
+    /// Write every ticket, with its comments and time logs, as a static site in `dir`.
+    /// An earlier export is replaced; a directory holding anything else is refused.
+    async fn export_site_internal(&mut self, dir: &std::path::Path, project: Option<&str>) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let tickets = self.db.tickets_changed_since(None, project.as_deref()).await?;
+        if tickets.is_empty() {
+            if let Some(project) = &project {
+                anyhow::bail!("Project '{}' has no tickets to export", project);
+            }
+        }
+        let comments = self.db.comments_changed_since(None, project.as_deref()).await?;
+        let time_logs = self.db.time_logs_changed_since(None, project.as_deref()).await?;
+
+        let files = crate::site::build_site(&tickets, &comments, &time_logs, Utc::now());
+        crate::site::write_site(dir, &files)?;
+        feedback::show_success(&format!(
+            "Exported {} tickets, {} comments, and {} time logs to {}",
+            tickets.len(),
+            comments.len(),
+            time_logs.len(),
+            dir.join("index.html").display()
+        ));
+        Ok(())
+    }
+
+    /// Sync with another tracker. Fields changed on both sides are settled by `--prefer`,
+    /// else by asking at a terminal, else in favour of the side that changed last.
+    async fn sync_internal(&mut self, provider: SyncProvider) -> Result<()> {
+        let (tracker, dry_run, prefer) = match &provider {
+            SyncProvider::Linear { dry_run, prefer } => ("Linear", *dry_run, *prefer),
+            SyncProvider::Jira { dry_run, prefer } => ("Jira", *dry_run, *prefer),
+        };
+        let ask = prefer.is_none() && !self.renderer.is_data() && interactive::can_prompt() && std::io::stderr().is_terminal();
+        let pb = feedback::create_progress_bar(&format!("Syncing with {}", tracker));
+        let resolve = |ticket: &Ticket, key: &str, conflict: &sync::Conflict| match prefer {
+            Some(prefer) => Ok(prefer.resolve(conflict)),
+            None if ask => pb.suspend(|| interactive::resolve_conflict(ticket, key, conflict)),
+            None => Ok(sync::Prefer::Newer.resolve(conflict)),
+        };
+        let changes = match provider {
+            SyncProvider::Linear { .. } => linear::sync(&self.db, &self.config.linear, dry_run, &resolve).await,
+            SyncProvider::Jira { .. } => jira::sync(&self.db, &self.config.jira, dry_run, &resolve).await,
+        };
+        pb.finish_and_clear();
+        self.print_sync_report(tracker, dry_run, &changes?);
+        Ok(())
+    }
+
+    /// Print what a sync with `tracker` did, or would do with `dry_run`
+    fn print_sync_report(&self, tracker: &str, dry_run: bool, changes: &[sync::Change]) {
+        if changes.is_empty() {
+            feedback::show_success(&format!("Tickets and {} issues are in sync", tracker));
+            return;
+        }
+
+        let heading = if dry_run { "Would sync with" } else { "Synced with" };
+        let mut lines = vec![format!("{}{} {}:", icon("🔄 "), heading, tracker)];
+        lines.extend(changes.iter().map(|change| format!("  {}{}", icon("• "), change.description)));
+        let data: Vec<serde_json::Value> = changes
+            .iter()
+            .map(|change| serde_json::json!({ "action": change.kind, "change": change.description }))
+            .collect();
+        self.renderer.print(&Report::new(lines.join("\n"), serde_json::json!({ "dry_run": dry_run, "actions": data })));
+    }
+
+    /// Replace the database with an archive's, backing up a non-empty one first, and restore
+    /// the archived config file unless one exists (or `force`)
+    async fn restore_archive_internal(&mut self, file: &std::path::Path, force: bool) -> Result<()> {
+        let unpacked = archive::unpack(file)?;
+        feedback::show_info(&format!(
+            "Archive written by ltm {} on {}",
+            unpacked.manifest.ltm_version,
+            unpacked.manifest.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ));
+
+        let existing = self.db.list_tickets(None).await?.len();
+        if existing > 0 {
+            if !force {
+                return Err(anyhow::anyhow!(
+                    "The current database already has {} tickets; pass --force to replace it (a backup is taken first)",
+                    existing
+                ));
+            }
+            backup::before(&self.db, &self.config.backup, "restore").await;
+        }
+        let restored = self.db.restore_from(&unpacked.database()).await?;
+        feedback::show_success(&format!("Restored {} tickets from {}", restored, file.display()));
+
+        if let (Some(archived), Some(target)) = (unpacked.config(), Config::path()) {
+            if target.exists() && !force {
+                feedback::show_warning(&format!(
+                    "Kept the existing {}; pass --force to replace it with the archived one",
+                    target.display()
+                ));
+            } else {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(&archived, &target)?;
+                feedback::show_success(&format!("Restored config file {}", target.display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Print a config value, or store a new one in config.toml
+    fn config_internal(&mut self, key: &str, value: Option<&str>, unset: bool) -> Result<()> {
+        if !CONFIG_KEYS.contains(&key) {
+            return Err(anyhow::anyhow!("Unknown config key '{}'. Keys: {}", key, CONFIG_KEYS.join(", ")));
+        }
+        if value.is_none() && !unset {
+            match self.config.get(key) {
+                Some(value) => self.renderer.print(&Report::new(value, serde_json::json!({ "key": key, "value": value }))),
+                None => feedback::show_info(&format!("{} is not set", key)),
+            }
+            return Ok(());
+        }
+
+        let path = Config::store(key, value)?;
+        match value {
+            Some(value) => feedback::show_success(&format!("Set {} to '{}' in {}", key, value, path.display())),
+            None => feedback::show_success(&format!("Removed {} from {}", key, path.display())),
+        }
+        Ok(())
+    }
+
+    /// Set a ticket in progress and start its timer
+    async fn start_working_internal(&mut self, ticket_id: i64) -> Result<()> {
+        self.update_ticket_status_internal(ticket_id, "in-progress", true, true).await?;
+        self.db.start_timer(ticket_id, Utc::now().naive_utc()).await?;
+        feedback::show_success(&format!("Started working on ticket {} (status: in-progress, timer: started)", ticket_id));
+        Ok(())
+    }
+
+    /// Show the best ticket to work on next with a few alternatives, optionally starting it
+    async fn next_ticket_internal(&mut self, project: Option<&str>, start: bool, alternatives: usize) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let tickets = self.db.list_tickets_filtered(project.as_deref(), None, "rank", false).await?;
+        let recommendations: Vec<_> = next::recommend(&tickets, Local::now().date_naive())
+            .into_iter()
+            .take(alternatives + 1)
+            .map(|r| (r.ticket.clone(), r.score, r.reasons))
+            .collect();
+        let best = recommendations.first().map(|(ticket, _, _)| ticket.id);
+        self.renderer.print(&NextTickets { recommendations });
+
+        if let (true, Some(id)) = (start, best) {
+            self.start_working_internal(id).await?;
+        }
+        Ok(())
+    }
+
+    async fn today_internal(&mut self, project: Option<&str>) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let in_project = |ticket: &Ticket| project.as_deref().is_none_or(|p| ticket.project == p);
+        let today = Local::now().date_naive();
+
+        let due: Vec<Ticket> = self
+            .db
+            .list_tickets_due_by(today)
+            .await?
+            .into_iter()
+            .filter(|t| t.archived_at.is_none() && in_project(t))
+            .collect();
+        // A ticket that is due is listed once, with its due date
+        let in_progress: Vec<Ticket> = self
+            .db
+            .list_tickets_filtered(project.as_deref(), Some("in-progress"), "rank", false)
+            .await?
+            .into_iter()
+            .filter(|t| !due.iter().any(|d| d.id == t.id))
+            .collect();
+        let mut timers = Vec::new();
+        for timer in self.db.list_timers().await? {
+            if let Some(ticket) = self.db.get_ticket(timer.ticket_id).await?.filter(|t| in_project(t)) {
+                timers.push((timer, ticket.name));
+            }
+        }
+        let logged_hours: f64 = self.db.daily_hours(today, today, project.as_deref()).await?.iter().map(|(_, h)| h).sum();
+
+        self.renderer.print(&Agenda {
+            date: today,
+            due,
+            in_progress,
+            timers,
+            logged_minutes: (logged_hours * 60.0).round() as i64,
+            now: Utc::now().naive_utc(),
+        });
+        Ok(())
+    }
+
+    async fn week_internal(&mut self, project: Option<&str>, next: bool) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let today = Local::now().date_naive();
+        let monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64) + chrono::Duration::weeks(next as i64);
+        let tickets: Vec<Ticket> = self
+            .db
+            .list_tickets_due_by(monday + chrono::Duration::days(6))
+            .await?
+            .into_iter()
+            .filter(|t| t.archived_at.is_none() && project.as_deref().is_none_or(|p| t.project == p))
+            .collect();
+        self.renderer.print(&WeekPlan { monday, today, tickets });
+        Ok(())
+    }
+
+    async fn calendar_internal(&mut self, month: Option<&str>, project: Option<&str>, prev: bool, next: bool) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let today = Local::now().date_naive();
+        let mut month = match month {
+            Some(month) => validate_month(month)?,
+            None => today.with_day(1).unwrap_or(today),
+        };
+        if prev {
+            month = month - chrono::Months::new(1);
+        } else if next {
+            month = month + chrono::Months::new(1);
+        }
+        let last = month + chrono::Months::new(1) - chrono::Duration::days(1);
+
+        let due: Vec<Ticket> = self
+            .db
+            .list_tickets_due_by(last)
+            .await?
+            .into_iter()
+            .filter(|t| t.due_date.is_some_and(|d| d >= month))
+            .filter(|t| t.archived_at.is_none() && project.as_deref().is_none_or(|p| t.project == p))
+            .collect();
+        let logged: HashMap<_, _> = self.db.daily_hours(month, last, project.as_deref()).await?.into_iter().collect();
+        self.renderer.print(&MonthCalendar { month, today, due, logged });
+        Ok(())
+    }
+
+    async fn show_current_ticket(&mut self) -> Result<()> {
+        let Some(ticket_id) = self.focused_ticket_id().await? else {
+            feedback::show_info("No ticket is focused. Use 'ltm focus <id>' to pick one.");
+            return Ok(());
+        };
+        let Some(ticket) = self.db.get_ticket(ticket_id).await? else {
+            return Ok(());
+        };
+
+        let mut lines = vec![
+            format!("{}Ticket #{}: {} [{}]", icon("🎯 "), ticket.id, ticket.name, ticket.project),
+            format!("   Status: {}", colorize_status(&ticket.status)),
+        ];
+        if let Some(due) = ticket.due_date {
+            lines.push(format!("   Due: {}", format_due_date(due, Local::now().date_naive())));
+        }
+        let timer = self.db.get_timer(ticket_id).await?;
+        let now = Utc::now().naive_utc();
+        match &timer {
+            Some(timer) => {
+                let state = if timer.is_paused() { "paused" } else { "running" };
+                lines.push(format!("   {}Timer: {} ({})", icon("⏱️  "), format_duration(timer.elapsed(now)), state));
+            }
+            None => lines.push(format!("   {}Timer: not running", icon("⏱️  "))),
+        }
+        let timer = timer.map(|timer| {
+            serde_json::json!({
+                "started_at": timer.started_at,
+                "paused": timer.is_paused(),
+                "elapsed": HumanDuration::from(timer.elapsed(now)),
+            })
+        });
+        self.renderer.print(&Report::new(lines.join("\n"), serde_json::json!({ "ticket": ticket, "timer": timer })));
+        Ok(())
+    }
+
+    async fn handle_check_action(&mut self, action: CheckAction) -> Result<()> {
         match action {
-            TimeAction::Start { ticket_id } => {
+            CheckAction::Add { ticket_id, text } => {
                 let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                let validated_text = validate_content_length(text.trim(), ContentType::ChecklistItem)?;
                 self.validate_ticket_exists(validated_ticket_id).await?;
-                self.time_tracking.insert(validated_ticket_id, TimeTrackingState::new(Utc::now()));
-                feedback::show_time_tracking_progress("Starting", validated_ticket_id).await;
-            }
-            TimeAction::Stop { ticket_id } => {
-                if let Some(ticket_id_str) = ticket_id {
-                    let validated_ticket_id = validate_ticket_id(&ticket_id_str)?;
-                    self.stop_time_tracking_internal(validated_ticket_id).await?;
-                } else {
-                    self.stop_all_active_timers().await?;
-                }
+                self.check_ticket_lock(validated_ticket_id).await?;
+                let position = self.db.add_checklist_item(validated_ticket_id, &validated_text).await?;
+                feedback::show_success(&format!("Added item {} to ticket {}", position, validated_ticket_id));
             }
-            TimeAction::Cancel { ticket_id } => {
-                if let Some(ticket_id_str) = ticket_id {
-                    let validated_ticket_id = validate_ticket_id(&ticket_id_str)?;
-                    self.cancel_time_tracking_internal(validated_ticket_id).await?;
-                } else {
-                    self.cancel_all_active_timers().await?;
+            CheckAction::Toggle { ticket_id, item } => {
+                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                self.check_ticket_lock(validated_ticket_id).await?;
+                match self.db.toggle_checklist_item(validated_ticket_id, item as i64).await? {
+                    Some(done) => {
+                        let state = if done { "done" } else { "not done" };
+                        feedback::show_success(&format!("Item {} of ticket {} marked {}", item, validated_ticket_id, state));
+                        self.print_checklist(validated_ticket_id).await?;
+                    }
+                    None => feedback::show_error(&format!("Ticket {} has no checklist item {}", validated_ticket_id, item)),
                 }
             }
-            TimeAction::Pause { ticket_id } => {
+            CheckAction::List { ticket_id } => {
                 let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                self.pause_time_tracking_internal(validated_ticket_id).await?;
+                self.validate_ticket_exists(validated_ticket_id).await?;
+                self.print_checklist(validated_ticket_id).await?;
             }
-            TimeAction::Resume { ticket_id } => {
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                self.resume_time_tracking_internal(validated_ticket_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn print_checklist(&self, ticket_id: i64) -> Result<()> {
+        let items = self.db.get_checklist(ticket_id).await?;
+        let text = format_checklist(&items);
+        self.renderer.print(&Report::new(text, serde_json::json!({ "ticket_id": ticket_id, "checklist": items })));
+        Ok(())
+    }
+
+    async fn handle_report_action(&mut self, action: ReportAction) -> Result<()> {
+        match action {
+            ReportAction::Send { weekly: _, daily, to, project, dry_run } => {
+                self.send_report_internal(daily, to, project.as_deref(), dry_run).await?;
             }
-            TimeAction::Log { ticket_id, duration } => {
-                self.log_time_duration_internal(&ticket_id, &duration).await?;
+            ReportAction::Heatmap { project, weeks } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let today = Local::now().date_naive();
+                let since = today - chrono::Duration::weeks(weeks as i64);
+
+                let daily: HashMap<_, _> = self
+                    .db
+                    .daily_hours(since, today, validated_project.as_deref())
+                    .await?
+                    .into_iter()
+                    .collect();
+
+                let heading = match &validated_project {
+                    Some(p) => format!("{}Hours logged per day for {} (last {} weeks)\n", icon("⏱️  "), p, weeks),
+                    None => format!("{}Hours logged per day (last {} weeks)\n", icon("⏱️  "), weeks),
+                };
+                let text = format!("{}\n{}", heading, format_heatmap(&daily, today, weeks as usize).trim_end());
+                let mut days: Vec<_> = daily.into_iter().collect();
+                days.sort_by_key(|(date, _)| *date);
+                let days: Vec<serde_json::Value> =
+                    days.into_iter().map(|(date, hours)| serde_json::json!({ "date": date, "hours": hours })).collect();
+                let data = serde_json::json!({ "project": validated_project, "from": since, "to": today, "days": days });
+                self.renderer.print(&Report::new(text, data));
             }
-            TimeAction::List { ticket_id } => {
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                self.validate_ticket_exists(validated_ticket_id).await?;
-                let pb = feedback::create_progress_bar("Loading time logs");
-                let logs = self.db.get_time_logs(validated_ticket_id).await?;
-                pb.finish_and_clear();
-                if logs.is_empty() {
-                    feedback::show_info(&format!("No time logs for ticket {}", validated_ticket_id));
+            ReportAction::Trends { project, months } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let starts = month_starts(Local::now().date_naive(), months as usize);
+                let trends = self.db.monthly_trends(&starts, validated_project.as_deref()).await?;
+                let heading = match &validated_project {
+                    Some(p) => format!("{}Monthly trends for {} (last {} months)\n", icon("📈 "), p, months),
+                    None => format!("{}Monthly trends (last {} months)\n", icon("📈 "), months),
+                };
+                let text = format!("{}\n{}", heading, format_trends(&trends).trim_end());
+                self.renderer.print(&Report::new(text, serde_json::json!({ "project": validated_project, "months": trends })));
+            }
+            ReportAction::StatusTime { target } => {
+                let (heading, target, changes) = if !target.is_empty() && target.chars().all(|c| c.is_ascii_digit()) {
+                    let ticket_id = validate_ticket_id(&target)?;
+                    self.validate_ticket_exists(ticket_id).await?;
+                    let heading = format!("{}Time in each status for ticket #{}\n", icon("⏱️  "), ticket_id);
+                    (heading, serde_json::json!({ "ticket_id": ticket_id }), self.db.get_status_changes(ticket_id).await?)
                 } else {
-                    println!("⏱️  Time logs for ticket {}:", validated_ticket_id);
-                    for log in &logs {
-                        let span = match (log.started_at, log.ended_at) {
-                            (Some(s), Some(e)) => format!(" ({} → {})", s.format("%Y-%m-%d %H:%M"), e.format("%Y-%m-%d %H:%M")),
-                            _ => String::new(),
-                        };
-                        println!("  • #{}: {}h {}m{}", log.id, log.hours, log.minutes, span);
-                    }
-                    feedback::show_success(&format!("Found {} time log(s)", logs.len()));
-                }
+                    let project = validate_project_name(&target)?;
+                    let heading = format!("{}Time in each status for {}\n", icon("⏱️  "), project);
+                    let changes = self.db.get_project_status_changes(&project).await?;
+                    (heading, serde_json::json!({ "project": project }), changes)
+                };
+                let rows = time_in_status(&changes, Utc::now().naive_utc());
+                let statuses: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|row| serde_json::json!({ "status": row.status, "visits": row.visits, "total": HumanDuration::from(row.total) }))
+                    .collect();
+                let mut data = target;
+                data["statuses"] = statuses.into();
+                self.renderer.print(&Report::new(format!("{}\n{}", heading, format_status_time(&rows)), data));
             }
-            TimeAction::Active => {
-                self.handle_show_active_timers().await?;
+            ReportAction::Score { project, method, limit } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let method = match method {
+                    ScoreMethod::Rice => ScoringMethod::Rice,
+                    ScoreMethod::Ice => ScoringMethod::Ice,
+                };
+                let tickets = self.db.list_tickets_filtered(validated_project.as_deref(), None, "rank", false).await?;
+                let backlog: Vec<&Ticket> = tickets.iter().filter(|t| !is_finished_status(&t.status)).collect();
+                let mut scored: Vec<(&Ticket, f64)> = backlog.iter().filter_map(|t| ticket_score(t, method).map(|score| (*t, score))).collect();
+                scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+                scored.truncate(limit);
+                let unscored: Vec<(&Ticket, Vec<&str>)> =
+                    backlog.iter().map(|t| (*t, missing_score_fields(t, method))).filter(|(_, missing)| !missing.is_empty()).collect();
+                let ranked: Vec<_> = scored
+                    .iter()
+                    .map(|(ticket, score)| {
+                        serde_json::json!({
+                            "id": ticket.id,
+                            "name": ticket.name,
+                            "project": ticket.project,
+                            "reach": ticket.reach,
+                            "impact": ticket.impact,
+                            "confidence": ticket.confidence,
+                            "effort": ticket.effort,
+                            "score": score,
+                        })
+                    })
+                    .collect();
+                let missing: Vec<_> = unscored.iter().map(|(ticket, missing)| serde_json::json!({ "id": ticket.id, "missing": missing })).collect();
+                let data = serde_json::json!({ "project": validated_project, "method": method, "tickets": ranked, "unscored": missing });
+                self.renderer.print(&Report::new(format_scores(method, &scored, &unscored), data));
             }
-            TimeAction::Summary { ticket_id } => {
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                self.validate_ticket_exists(validated_ticket_id).await?;
-                let logs = self.db.get_time_logs(validated_ticket_id).await?;
-                let mut total_minutes = 0i64;
-                for l in &logs {
-                    total_minutes += (l.hours as i64) * 60 + (l.minutes as i64);
-                }
-                let hours = total_minutes / 60;
-                let minutes = total_minutes % 60;
-                println!("⏱️  Time summary for ticket {}: {}h {}m ({} logs)", validated_ticket_id, hours, minutes, logs.len());
-                feedback::show_success("Summary complete");
+            ReportAction::Gantt { milestone, project, mermaid } => {
+                let release = self.find_release(&milestone, project.as_deref()).await?;
+                let tickets = self.db.release_tickets(&release.project, &release.name).await?;
+                let today = Local::now().date_naive();
+                let text = if mermaid { format_gantt_mermaid(&release, &tickets, today) } else { format_gantt(&release, &tickets, today) };
+                let rows: Vec<_> = tickets
+                    .iter()
+                    .map(|ticket| {
+                        let (start, end) = gantt_span(ticket, release.due_date);
+                        serde_json::json!({ "id": ticket.id, "name": ticket.name, "status": ticket.status, "start": start, "end": end })
+                    })
+                    .collect();
+                let data = serde_json::json!({ "project": release.project, "release": release.name, "due_date": release.due_date, "tickets": rows });
+                self.renderer.print(&Report::new(text, data));
             }
-            TimeAction::Update { log_id, duration } => {
-                let validated_log_id = validate_ticket_id(&log_id)?;
-                let (hours, minutes) = self.parse_duration(&duration)?;
-                let pb = feedback::create_progress_bar("Updating time log");
-                self.db.update_time_log(validated_log_id, hours, minutes).await?;
-                pb.finish_with_message("Time log updated");
-                feedback::show_success(&format!("Time log #{} updated to {}h {}m", validated_log_id, hours, minutes));
+            ReportAction::Aging { project, oldest } => {
+                let validated_project = project.as_deref().map(validate_project_name).transpose()?;
+                let tickets = self.db.list_tickets_filtered(validated_project.as_deref(), None, "created", false).await?;
+                let projects = ticket_aging(&tickets, Utc::now().naive_utc(), oldest);
+                let heading = match &validated_project {
+                    Some(p) => format!("{}Open tickets in {} by age\n", icon("🕰️  "), p),
+                    None => format!("{}Open tickets by age\n", icon("🕰️  ")),
+                };
+                let text = format!("{}\n{}", heading, format_aging(&projects));
+                let data = serde_json::json!({ "project": validated_project, "buckets": AGE_BUCKET_LABELS, "projects": projects });
+                self.renderer.print(&Report::new(text, data));
             }
-            TimeAction::Delete { log_id } => {
-                let validated_log_id = validate_ticket_id(&log_id)?;
-                if !interactive::confirm_destructive_action("delete", &format!("time log #{}", validated_log_id))? {
-                    feedback::show_info("Operation cancelled");
-                    return Ok(());
-                }
-                let pb = feedback::create_progress_bar("Deleting time log");
-                self.db.delete_time_log(validated_log_id).await?;
-                pb.finish_with_message("Time log deleted");
-                feedback::show_success(&format!("Deleted time log #{}", validated_log_id));
+        }
+        Ok(())
+    }
+
+    async fn handle_remind(&mut self, within: &str, notify: bool) -> Result<()> {
+        let window = validate_period(within)?;
+        let today = Local::now().date_naive();
+        let horizon = (Local::now().naive_local() + window).date();
+
+        debug!(%horizon, "looking for tickets due on or before the horizon");
+        let due_tickets = self.db.list_tickets_due_by(horizon).await?;
+        let timers = self.db.list_timers().await?;
+
+        let mut lines = Vec::new();
+        for ticket in &due_tickets {
+            if let Some(due) = ticket.due_date {
+                lines.push(format!("#{} {} [{}] due {}", ticket.id, ticket.name, ticket.project, format_due_date(due, today)));
+            }
+        }
+        let now = Utc::now().naive_utc();
+        for timer in timers.iter().filter(|t| !t.is_paused()) {
+            let elapsed = timer.elapsed(now);
+            let name = match self.db.get_ticket(timer.ticket_id).await? {
+                Some(ticket) => ticket.name,
+                None => String::new(),
+            };
+            lines.push(format!("#{} {} timer running for {}", timer.ticket_id, name, format_duration(elapsed)));
+        }
+
+        if lines.is_empty() {
+            feedback::show_success(&format!("Nothing due within {} and no timers running", within));
+            return Ok(());
+        }
+
+        let mut text = vec![format!("{}Reminders:", icon("⏰ "))];
+        text.extend(lines.iter().map(|line| format!("  {}{}", icon("• "), line)));
+        self.renderer.print(&Report::new(text.join("\n"), serde_json::json!({ "reminders": lines })));
+
+        if notify {
+            let title = format!("ltm: {} reminder(s)", lines.len());
+            if let Err(e) = feedback::send_desktop_notification(&title, &lines.join("\n")) {
+                feedback::show_warning(&e.to_string());
             }
         }
         Ok(())
     }
 
-    async fn handle_update_target(&mut self, target: UpdateTarget) -> Result<()> {
-        match target {
-            UpdateTarget::Status { ticket_id, status, force } => {
-                let validated_ticket_id = validate_ticket_id(&ticket_id)?;
-                let validated_status = validate_status(&status)?;
-                self.validate_ticket_exists(validated_ticket_id).await?;
-                self.update_ticket_status_internal(validated_ticket_id, &validated_status, force).await?;
+    async fn handle_dynamic_completion(&self, words: &[String]) -> Result<()> {
+        let kind = completion::completion_kind(&Cli::command(), words);
+        trace!(?words, ?kind, "dynamic completion");
+        let candidates = match kind {
+            Some(CompletionKind::Projects) => self.db.list_projects().await?,
+            Some(CompletionKind::Statuses) => valid_statuses(),
+            Some(CompletionKind::Tickets) => self
+                .db
+                .list_tickets(None)
+                .await?
+                .into_iter()
+                .filter(|t| !is_finished_status(&t.status))
+                .map(|t| t.id.to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+        for candidate in candidates {
+            println!("{}", candidate);
+        }
+        Ok(())
+    }
+
+    async fn handle_git_action(&mut self, action: GitAction) -> Result<()> {
+        match action {
+            GitAction::InstallHooks { repo, force } => {
+                let dir = git_hooks::hooks_dir(repo.as_deref())?;
+                let binary = std::env::current_exe()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|_| "ltm".to_string());
+                let hook_path = git_hooks::install_post_commit_hook(&dir, &binary, force)?;
+                feedback::show_success(&format!("Installed post-commit hook at {}", hook_path.display()));
+                feedback::show_info("Reference tickets in commit messages, e.g. \"Fix login redirect (ltm #12, 1h30m)\" or \"closes ltm #12\"");
             }
-            UpdateTarget::Name { ticket_id, name } => {
-                self.update_ticket_field_internal(&ticket_id, "name", &name).await?;
+            GitAction::PostCommit => {
+                let (hash, message) = git_hooks::read_head_commit()?;
+                self.process_commit_message_internal(&hash, &message).await?;
             }
-            UpdateTarget::Description { ticket_id, description } => {
-                let desc = if let Some(d) = description {
-                    d
-                } else {
-                    feedback::show_info("Opening editor for description...");
-                    edit("")?.trim().to_string()
-                };
-                self.update_ticket_field_internal(&ticket_id, "description", &desc).await?;
+        }
+        Ok(())
+    }
+
+    async fn process_commit_message_internal(&mut self, hash: &str, message: &str) -> Result<()> {
+        let subject = message.lines().next().unwrap_or_default();
+        let references = git_hooks::parse_commit_message(message);
+        debug!(commit = hash, ?references, "parsed commit message");
+        for reference in references {
+            let Some(ticket) = self.db.get_ticket(reference.ticket_id).await? else {
+                feedback::show_warning(&format!("Commit {} references unknown ticket {}", hash, reference.ticket_id));
+                continue;
+            };
+            if self.check_ticket_lock(ticket.id).await.is_err() {
+                feedback::show_warning(&format!("Commit {} references locked ticket {}; leaving it unchanged", hash, ticket.id));
+                continue;
             }
-            UpdateTarget::Project { ticket_id, project } => {
-                self.move_ticket_internal(&ticket_id, &project).await?;
+
+            let comment = format!("Commit {}: {}", hash, subject);
+            let validated_comment = validate_content_length(&comment, ContentType::Comment)?;
+            self.db.add_comment(ticket.id, &validated_comment).await?;
+
+            if let Some(duration) = &reference.duration {
+                match self.parse_duration(duration) {
+                    Ok((hours, minutes)) => {
+                        let (hours, minutes) = self.round_logged_time(ticket.id, hours, minutes).await?;
+                        self.db.add_time_log(ticket.id, hours, minutes, None, None).await?;
+                        let logged = format_minutes(hours as i64 * 60 + minutes as i64);
+                        feedback::show_success(&format!("Logged {} for ticket {} from commit {}", logged, ticket.id, hash));
+                    }
+                    Err(_) => {
+                        feedback::show_warning(&format!("Ignoring unparseable duration '{}' for ticket {}", duration, ticket.id));
+                    }
+                }
+            }
+
+            let workflow_check = self.config.workflow(&ticket.project).map(|w| w.check(&ticket.status, "closed"));
+            let required_check = if reference.closes {
+                let settings = self.db.get_project_settings(&ticket.project).await?;
+                let present = self.ticket_fields_present(&ticket).await?;
+                settings.check_required_for(&ticket.project, "closed", &present)
+            } else {
+                Ok(())
+            };
+            if reference.closes && matches!(workflow_check, Some(Err(_))) {
+                feedback::show_warning(&format!(
+                    "Not closing ticket {}: the {} workflow does not allow {} → closed",
+                    ticket.id, ticket.project, ticket.status
+                ));
+            } else if let Err(ValidationError::MissingStatusField { field, .. }) = &required_check {
+                feedback::show_warning(&format!(
+                    "Not closing ticket {}: {} requires a {} before a ticket moves to closed",
+                    ticket.id, ticket.project, field
+                ));
+            } else if reference.closes {
+                self.db.update_ticket_status(ticket.id, "closed").await?;
+                self.add_status_comment(&ticket, "closed", Some(&format!("commit {}", hash))).await?;
+                self.notify_status_change(&ticket, "closed", None).await;
+                feedback::show_success(&format!("Ticket {} ('{}') closed by commit {}", ticket.id, ticket.name, hash));
+            } else {
+                feedback::show_success(&format!("Linked commit {} to ticket {} ('{}')", hash, ticket.id, ticket.name));
             }
         }
         Ok(())
@@ -988,25 +4063,31 @@ impl CommandHandler {
             "bash" => {
                 if let Some(dir) = out_dir {
                     fs::create_dir_all(dir)?;
-                    generate_to(Bash, &mut cmd, bin_name, dir)?;
+                    let path = generate_to(Bash, &mut cmd, bin_name, dir)?;
+                    fs::OpenOptions::new().append(true).open(path)?.write_all(completion::BASH_DYNAMIC.as_bytes())?;
                 } else {
                     generate(Bash, &mut cmd, bin_name, &mut std::io::stdout());
+                    print!("{}", completion::BASH_DYNAMIC);
                 }
             }
             "zsh" => {
                 if let Some(dir) = out_dir {
                     fs::create_dir_all(dir)?;
-                    generate_to(Zsh, &mut cmd, bin_name, dir)?;
+                    let path = generate_to(Zsh, &mut cmd, bin_name, dir)?;
+                    fs::OpenOptions::new().append(true).open(path)?.write_all(completion::ZSH_DYNAMIC.as_bytes())?;
                 } else {
                     generate(Zsh, &mut cmd, bin_name, &mut std::io::stdout());
+                    print!("{}", completion::ZSH_DYNAMIC);
                 }
             }
             "fish" => {
                 if let Some(dir) = out_dir {
                     fs::create_dir_all(dir)?;
-                    generate_to(Fish, &mut cmd, bin_name, dir)?;
+                    let path = generate_to(Fish, &mut cmd, bin_name, dir)?;
+                    fs::OpenOptions::new().append(true).open(path)?.write_all(completion::FISH_DYNAMIC.as_bytes())?;
                 } else {
                     generate(Fish, &mut cmd, bin_name, &mut std::io::stdout());
+                    print!("{}", completion::FISH_DYNAMIC);
                 }
             }
             "powershell" | "pwsh" => {
@@ -1034,9 +4115,21 @@ impl CommandHandler {
         }
         Ok(())
     }
-    async fn create_ticket_internal(&mut self, project: String, name: String, description: Option<String>) -> Result<()> {
+    /// Create a ticket, first checking the project for open tickets with a very similar
+    /// name. A match asks for confirmation, or only warns when there is no terminal.
+    async fn create_ticket_internal(
+        &mut self,
+        project: String,
+        name: String,
+        description: Option<String>,
+        fields: NewTicketFields,
+        force: bool,
+    ) -> Result<()> {
+        let NewTicketFields { due, status, kind, severity, component } = fields;
         let validated_project = validate_project_name(&project)?;
         let validated_name = validate_content_length(&name, ContentType::TicketName)?;
+        let due_date = due.as_deref().map(validate_date).transpose()?;
+        let severity = severity.as_deref().map(validate_severity).transpose()?;
 
         let description = if let Some(desc) = description {
             desc
@@ -1046,6 +4139,30 @@ impl CommandHandler {
         };
 
         let validated_description = validate_content_length(&description, ContentType::Description)?;
+        let settings = self.db.get_project_settings(&validated_project).await?;
+        let status = settings.initial_status(&validated_project, status.as_deref())?;
+        let kind = kind.as_deref().map(|kind| settings.validate_kind(kind)).transpose()?;
+        let component = match component {
+            Some(component) => Some(self.check_component(&validated_project, &component).await?),
+            None => None,
+        };
+        let mut present = vec!["description"];
+        present.extend(due_date.map(|_| "due"));
+        settings.check_required(&validated_project, &present)?;
+        // A ticket created in a later status needs what moving it there would
+        present.extend(settings.default_priority.as_ref().map(|_| "priority"));
+        match settings.check_required_for(&validated_project, &status, &present) {
+            Err(ValidationError::MissingStatusField { field, .. }) if force => feedback::show_warning(&format!(
+                "{} requires a {} before a ticket moves to {}; creating it anyway",
+                validated_project, field, status
+            )),
+            result => result?,
+        }
+
+        if !force && !self.confirm_not_duplicate(&validated_project, &validated_name).await? {
+            feedback::show_info("Operation cancelled");
+            return Ok(());
+        }
 
         let project_suggestions = suggestions::suggest_project_names(&self.db, &validated_project).await?;
         if !project_suggestions.contains(&validated_project) && !project_suggestions.is_empty() {
@@ -1055,64 +4172,291 @@ impl CommandHandler {
         }
 
         let pb = feedback::create_progress_bar("Creating ticket");
-        let id = self.db.add_ticket(&validated_project, &validated_name, &validated_description).await?;
+        let id = self.db.add_ticket_with_status(&validated_project, &validated_name, &validated_description, &status).await?;
+        self.use_ticket(id);
+        if due_date.is_some() {
+            self.db.update_ticket_due_date(id, due_date).await?;
+        }
+        if settings.default_priority.is_some() {
+            self.db.update_ticket_priority(id, settings.default_priority.as_deref()).await?;
+        }
+        if kind.is_some() {
+            self.db.update_ticket_kind(id, kind.as_deref()).await?;
+        }
+        if severity.is_some() {
+            self.db.update_ticket_severity(id, severity.as_deref()).await?;
+        }
+        if component.is_some() {
+            self.db.update_ticket_component(id, component.as_deref()).await?;
+        }
         pb.finish_with_message("Ticket created");
-        feedback::show_celebration(&format!("Ticket created with ID: {}", id));
+        feedback::show_celebration(&format!("Ticket created with ID: {}", settings.display_id(id)));
+        Ok(())
+    }
+
+    /// Create a ticket from each unchecked item of a Markdown task list, with the indented
+    /// items below it as its checklist when `nest` is set. Everything is validated before the
+    /// first ticket is created.
+    async fn create_bulk_internal(&mut self, project: &str, path: &std::path::Path, nest: bool) -> Result<()> {
+        let project = validate_project_name(project)?;
+        let text = if path.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            std::fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?
+        };
+        let (done, todo): (Vec<BulkTicket>, Vec<BulkTicket>) = parse_task_list(&text, nest).into_iter().partition(|t| t.done);
+        if todo.is_empty() {
+            feedback::show_info(&format!("No open items in {}", path.display()));
+            return Ok(());
+        }
+        for ticket in &todo {
+            validate_content_length(&ticket.name, ContentType::TicketName)?;
+            for (subtask, _) in &ticket.subtasks {
+                validate_content_length(subtask, ContentType::ChecklistItem)?;
+            }
+        }
+        let settings = self.db.get_project_settings(&project).await?;
+        let status = settings.initial_status(&project, None)?;
+        settings.check_required(&project, &[])?;
+
+        let pb = feedback::create_progress_bar("Creating tickets");
+        let mut created = Vec::new();
+        for ticket in &todo {
+            let id = self.db.add_ticket_with_status(&project, &ticket.name, "", &status).await?;
+            if settings.default_priority.is_some() {
+                self.db.update_ticket_priority(id, settings.default_priority.as_deref()).await?;
+            }
+            for (subtask, checked) in &ticket.subtasks {
+                let position = self.db.add_checklist_item(id, subtask).await?;
+                if *checked {
+                    self.db.toggle_checklist_item(id, position).await?;
+                }
+            }
+            created.push((id, ticket));
+        }
+        pb.finish_and_clear();
+
+        let ids: Vec<String> = created.iter().map(|(id, _)| settings.display_id(*id)).collect();
+        let noun = |count: usize, one: &str, many: &str| if count == 1 { format!("1 {}", one) } else { format!("{} {}", count, many) };
+        let mut lines = vec![feedback::success_message(&format!("Created {} in {}: {}", noun(created.len(), "ticket", "tickets"), project, ids.join(", ")))];
+        for (id, ticket) in &created {
+            let subtasks = match ticket.subtasks.len() {
+                0 => String::new(),
+                n => format!(" ({})", noun(n, "subtask", "subtasks")),
+            };
+            lines.push(format!("  #{} {}{}", settings.display_id(*id), ticket.name, subtasks));
+        }
+        if !done.is_empty() {
+            lines.push(feedback::info_message(&format!("Skipped {}", noun(done.len(), "checked item", "checked items"))));
+        }
+        let data = serde_json::json!({
+            "project": project,
+            "created": created
+                .iter()
+                .map(|(id, ticket)| serde_json::json!({ "id": id, "name": ticket.name, "subtasks": ticket.subtasks.len() }))
+                .collect::<Vec<_>>(),
+            "skipped": done.iter().map(|t| &t.name).collect::<Vec<_>>(),
+        });
+        self.renderer.print(&Report::new(lines.join("\n"), data));
+        Ok(())
+    }
+
+    /// Warn about a similar ticket in the project, asking whether to go ahead when a
+    /// terminal is attached. Returns `false` when the user declines.
+    async fn confirm_not_duplicate(&self, project: &str, name: &str) -> Result<bool> {
+        let existing = self.db.list_tickets(Some(project)).await?;
+        let Some(similar) = suggestions::find_similar_tickets(&existing, name).into_iter().next() else {
+            return Ok(true);
+        };
+        if !interactive::can_prompt() {
+            feedback::show_warning(&tf("warning.similar_ticket", &[&similar.id, &similar.name]));
+            return Ok(true);
+        }
+        interactive::confirm_action(&tf("confirm.duplicate", &[&similar.id, &similar.name]))
+    }
+
+    /// Create a ticket from a quick-add line. Without an `@project` the focused ticket's
+    /// project is used; the description is left empty.
+    async fn quick_add(&mut self, text: &str, force: bool) -> Result<()> {
+        let quick = parse_quick_add(text);
+        let project = match quick.project {
+            Some(project) => validate_project_name(&project)?,
+            None => match self.focused_ticket_id().await? {
+                Some(id) => self.db.get_ticket(id).await?.map(|t| t.project).ok_or(ValidationError::TicketNotFound(id))?,
+                None => return Err(anyhow::anyhow!("No project given. Add @project, e.g. ltm quick \"Fix login @webapp\"")),
+            },
+        };
+        let name = validate_content_length(&quick.name, ContentType::TicketName)?;
+        let priority = quick.priority.as_deref().map(validate_priority).transpose()?;
+        let tags = quick.tags.iter().map(|tag| validate_tag(tag)).collect::<Result<Vec<_>, _>>()?;
+        let due_date = quick.due.as_deref().map(validate_date).transpose()?;
+        let estimate = quick
+            .estimate
+            .as_deref()
+            .map(|est| self.parse_duration(est).map(|(h, m)| h as i64 * 60 + m as i64))
+            .transpose()?;
+        let settings = self.db.get_project_settings(&project).await?;
+        let mut present = Vec::new();
+        present.extend(priority.as_ref().map(|_| "priority"));
+        present.extend(due_date.map(|_| "due"));
+        present.extend(estimate.map(|_| "estimate"));
+        if !tags.is_empty() {
+            present.push("tags");
+        }
+        settings.check_required(&project, &present)?;
+        let priority = priority.or_else(|| settings.default_priority.clone());
+
+        if !force && !self.confirm_not_duplicate(&project, &name).await? {
+            feedback::show_info("Operation cancelled");
+            return Ok(());
+        }
+
+        let status = settings.initial_status(&project, None)?;
+        let id = self.db.add_ticket_with_status(&project, &name, "", &status).await?;
+        self.use_ticket(id);
+        if due_date.is_some() {
+            self.db.update_ticket_due_date(id, due_date).await?;
+        }
+        if priority.is_some() {
+            self.db.update_ticket_priority(id, priority.as_deref()).await?;
+        }
+        if estimate.is_some() {
+            self.db.update_ticket_estimate(id, estimate).await?;
+        }
+        self.db.add_ticket_tags(id, &tags).await?;
+        debug!(id, %project, ?priority, ?tags, ?due_date, ?estimate, "quick-added ticket");
+
+        let mut details = vec![format!("project {}", project)];
+        details.extend(priority.map(|p| format!("priority {}", p)));
+        details.extend(tags.iter().map(|tag| format!("#{}", tag)));
+        details.extend(due_date.map(|due| format!("due {}", format_due_date(due, Local::now().date_naive()))));
+        details.extend(estimate.map(|minutes| format!("estimate {}", format_estimate(minutes))));
+        feedback::show_celebration(&format!("Ticket created with ID: {} ({})", settings.display_id(id), details.join(", ")));
         Ok(())
     }
 
-    async fn list_tickets_internal(&mut self, project: Option<String>, status: Option<String>, sort: Option<String>, json: bool) -> Result<()> {
-        let validated_project = if let Some(ref proj) = project {
+    /// List tickets. Text filters keep only tickets with a matching comment (or, for
+    /// `--regex`, a matching name or description) and print the matching comments after
+    /// the table.
+    async fn list_tickets_internal(&mut self, filters: ListFilters) -> Result<()> {
+        let validated_project = if let Some(ref proj) = filters.project {
             Some(validate_project_name(proj)?)
         } else {
             None
         };
+        let dates = filters.date_bounds()?;
+        let comment_text = filters.comment_contains.map(Matcher::Text);
+        let pattern = filters.regex.as_deref().map(validate_regex).transpose()?.map(Matcher::Regex);
+        let status = filters.status.as_deref().map(resolve_status_alias);
 
-        let sort_field = sort.unwrap_or_else(|| "updated".to_string());
         let pb = feedback::create_progress_bar("Loading tickets");
-        let tickets = self
+        let mut tickets = self
             .db
-            .list_tickets_filtered(
+            .list_tickets_dated(
                 validated_project.as_deref(),
                 status.as_deref(),
-                &sort_field,
+                &filters.sort,
+                filters.archived,
+                &dates,
             )
             .await?;
-        pb.finish_and_clear();
 
-        if json {
-            let output = crate::json_formatting::format_ticket_list_json(&tickets, validated_project.as_deref());
-            println!("{}", output);
+        let comments = if comment_text.is_some() || pattern.is_some() {
+            self.db.list_comments(validated_project.as_deref()).await?
         } else {
-            let formatted_output = format_ticket_list(&tickets);
-            println!("{}", formatted_output);
+            Vec::new()
+        };
+        let comment_hit = |ticket_id: i64, matcher: &Matcher| {
+            comments.iter().any(|c| c.ticket_id == ticket_id && matcher.is_match(&c.content))
+        };
+        let kind = filters.kind.map(|kind| kind.trim().to_lowercase());
+        let severity = filters.severity.as_deref().map(validate_severity).transpose()?;
+        let component = filters.component.as_deref().map(validate_component).transpose()?;
+        tickets.retain(|t| {
+            component.as_ref().is_none_or(|component| t.component.as_ref() == Some(component))
+                && kind.as_ref().is_none_or(|kind| t.kind.as_ref() == Some(kind))
+                && severity.as_ref().is_none_or(|severity| t.severity.as_ref() == Some(severity))
+                && comment_text.as_ref().is_none_or(|m| comment_hit(t.id, m))
+                && pattern.as_ref().is_none_or(|m| {
+                    m.is_match(&t.name) || m.is_match(&t.description) || comment_hit(t.id, m)
+                })
+        });
+        let comment_matches: Vec<_> = comments
+            .iter()
+            .filter(|c| tickets.iter().any(|t| t.id == c.ticket_id))
+            .filter_map(|c| {
+                comment_text.iter().chain(&pattern).find_map(|m| m.find(&c.content)).map(|range| (c.clone(), range))
+            })
+            .collect();
+        let checklists = self.db.checklist_progress().await?;
+        pb.finish_and_clear();
+
+        self.renderer.print(&TicketList { tickets, project: validated_project, checklists, comment_matches });
+        Ok(())
+    }
+
+    async fn show_board(&self, project: Option<&str>) -> Result<()> {
+        let tickets = self.db.list_tickets_filtered(project, None, "rank", false).await?;
+        let settings = match project {
+            Some(project) => self.db.get_project_settings(project).await?,
+            None => ProjectSettings::default(),
+        };
+        let text = format_board(&tickets, project.is_none(), &settings);
+        self.renderer.print(&Report::new(text, serde_json::json!({ "project": project, "tickets": tickets })));
+        Ok(())
+    }
 
-            if !tickets.is_empty() {
-                feedback::show_success(&format!("Found {} ticket(s)", tickets.len()));
+    /// Redraw a list or board whenever the database changes, polling every `interval`
+    /// seconds, until Ctrl-C. Plain output appends each redraw instead of clearing the screen.
+    async fn watch_view(&mut self, view: WatchView, interval: u64) -> Result<()> {
+        if self.renderer.is_data() {
+            anyhow::bail!("--watch redraws text for the terminal and cannot be combined with -o");
+        }
+        let mut watcher = self.db.watch_changes().await?;
+        let interval = std::time::Duration::from_secs(interval);
+        loop {
+            if output::is_plain() {
+                println!();
             } else {
-                feedback::show_info("No tickets found");
+                print!("\x1b[2J\x1b[H");
+            }
+            match &view {
+                WatchView::List(filters) => self.list_tickets_internal(*filters.clone()).await?,
+                WatchView::Board(project) => self.show_board(project.as_deref()).await?,
+            }
+            println!(
+                "{}",
+                with_icon("👀", format!("Updated {}. Watching for changes, Ctrl-C to stop.", Local::now().format("%H:%M:%S")))
+            );
+            std::io::stdout().flush()?;
+
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => return Ok(()),
+                    _ = tokio::time::sleep(interval) => {}
+                }
+                if watcher.changed().await? {
+                    debug!("database changed, redrawing");
+                    break;
+                }
             }
         }
-        Ok(())
     }
 
-    async fn show_ticket_internal(&mut self, ticket_id: &str, _full: bool, json: bool) -> Result<()> {
+    async fn show_ticket_internal(&mut self, ticket_id: &str, _full: bool) -> Result<()> {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
 
         let pb = feedback::create_progress_bar("Loading ticket details");
         if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
+            self.use_ticket(validated_ticket_id);
             let comments = self.db.get_comments(validated_ticket_id).await?;
             let time_logs = self.db.get_time_logs(validated_ticket_id).await?;
+            let links = self.db.get_ticket_urls(validated_ticket_id).await?;
+            let checklist = self.db.get_checklist(validated_ticket_id).await?;
+            let tags = self.db.get_ticket_tags(validated_ticket_id).await?;
+            let lock = self.db.ticket_lock(validated_ticket_id).await?;
             pb.finish_and_clear();
-
-            if json {
-                let output = crate::json_formatting::format_ticket_details_json(&ticket, &comments, &time_logs);
-                println!("{}", output);
-            } else {
-                let formatted_output = format_ticket_details(&ticket, &comments, &time_logs);
-                println!("{}", formatted_output);
-                feedback::show_success(&format!("Details for ticket {} ('{}')", validated_ticket_id, ticket.name));
-            }
+            self.renderer.print(&TicketDetails { ticket, comments, time_logs, links, checklist, tags, lock });
         } else {
             pb.finish_and_clear();
             return Err(ValidationError::TicketNotFound(validated_ticket_id).into());
@@ -1120,17 +4464,89 @@ impl CommandHandler {
         Ok(())
     }
 
-    async fn update_ticket_status_internal(&mut self, ticket_id: i64, status: &str, force: bool) -> Result<()> {
+    /// Change a ticket's status. `force` skips the confirmation prompt; `enforce_workflow`
+    /// refuses transitions the project's workflow does not allow.
+    /// Change a ticket's status, checking it against the project's statuses and workflow
+    /// Validate a status typed for a ticket against its project's statuses
+    async fn validate_ticket_status(&self, ticket_id: i64, status: &str) -> Result<String> {
+        let project = match self.db.get_ticket(ticket_id).await? {
+            Some(ticket) => ticket.project,
+            None => return Ok(validate_status(status)?),
+        };
+        let settings = self.db.get_project_settings(&project).await?;
+        Ok(settings.validate_status(&project, status)?)
+    }
+
+    async fn update_ticket_status_internal(&mut self, ticket_id: i64, status: &str, force: bool, enforce_workflow: bool) -> Result<()> {
+        self.change_ticket_status(ticket_id, status, force, enforce_workflow, None, None).await
+    }
+
+    /// Move a ticket to `status` after checking the project's statuses, workflow, required
+    /// fields, and WIP limits, then tell the webhooks. `reason` says why, e.g. what a ticket
+    /// is blocked on; `comment` is added to the ticket unless the reason goes into a status
+    /// comment instead.
+    async fn change_ticket_status(
+        &mut self,
+        ticket_id: i64,
+        status: &str,
+        force: bool,
+        enforce_workflow: bool,
+        reason: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<()> {
         if let Some(ticket) = self.db.get_ticket(ticket_id).await? {
+            self.check_ticket_lock(ticket_id).await?;
+            // Shortcuts such as `complete` pass fixed statuses, which a project status list still restricts
+            let settings = self.db.get_project_settings(&ticket.project).await?;
+            if settings.statuses.is_some() {
+                settings.validate_status(&ticket.project, status)?;
+            }
+            if let Some(workflow) = self.config.workflow(&ticket.project) {
+                match workflow.check(&ticket.status, status) {
+                    Err(e) if enforce_workflow => return Err(e.into()),
+                    Err(_) => feedback::show_warning(&format!(
+                        "Overriding the {} workflow: {} → {}",
+                        ticket.project, ticket.status, status
+                    )),
+                    Ok(()) => {}
+                }
+            }
+            if !ticket.status.eq_ignore_ascii_case(status) && !settings.fields_required_for(status).is_empty() {
+                let mut present = self.ticket_fields_present(&ticket).await?;
+                present.extend(comment.map(|_| "comment"));
+                match settings.check_required_for(&ticket.project, status, &present) {
+                    Err(e) if enforce_workflow => return Err(e.into()),
+                    Err(ValidationError::MissingStatusField { field, .. }) => feedback::show_warning(&format!(
+                        "{} requires a {} before a ticket moves to {}; moving it anyway",
+                        ticket.project, field, status
+                    )),
+                    _ => {}
+                }
+            }
+            if !ticket.status.eq_ignore_ascii_case(status) {
+                let count = self.db.count_in_status(&ticket.project, status).await?;
+                match settings.check_wip(&ticket.project, status, count) {
+                    Err(e) if enforce_workflow => return Err(e.into()),
+                    Err(ValidationError::WipLimitReached { limit, .. }) | Ok(Some(limit)) => feedback::show_warning(&format!(
+                        "{} now has {} tickets in {}, over its limit of {}",
+                        ticket.project,
+                        count + 1,
+                        status,
+                        limit
+                    )),
+                    _ => {}
+                }
+            }
+
             let target = format!("ticket {} ('{}')", ticket_id, ticket.name);
 
-            if !force && !interactive::confirm_destructive_action("update status of", &target)? {
+            if !force && !interactive::confirm_destructive_action("update-status", &target)? {
                 feedback::show_info("Operation cancelled");
                 return Ok(());
             }
 
             let suggestions = suggestions::suggest_status_names(status);
-            if !suggestions.contains(&status.to_string()) && !suggestions.is_empty() {
+            if settings.statuses.is_none() && !suggestions.contains(&status.to_string()) && !suggestions.is_empty() {
                 if let Some(suggestion_msg) = suggestions::format_suggestions(status, &suggestions, "status") {
                     feedback::show_thinking(&suggestion_msg);
                 }
@@ -1140,6 +4556,115 @@ impl CommandHandler {
             self.db.update_ticket_status(ticket_id, status).await?;
             pb.finish_with_message("Status updated");
             feedback::show_success(&format!("Ticket {} status updated to: {}", ticket_id, status));
+            // With status comments on, the reason is part of the status change's comment
+            let commented = self.add_status_comment(&ticket, status, reason).await?;
+            if let Some(comment) = comment.filter(|_| !commented) {
+                self.db.add_comment(ticket_id, comment).await?;
+                feedback::show_info("Added comment");
+            }
+            self.notify_status_change(&ticket, status, reason).await;
+        }
+        Ok(())
+    }
+
+    /// The `STATUS_FIELDS` a ticket has; a comment counts when it was written since the
+    /// ticket's last status change
+    async fn ticket_fields_present(&self, ticket: &Ticket) -> Result<Vec<&'static str>> {
+        let mut present = Vec::new();
+        present.extend(Some("description").filter(|_| !ticket.description.trim().is_empty()));
+        present.extend(ticket.due_date.map(|_| "due"));
+        present.extend(ticket.priority.as_ref().map(|_| "priority"));
+        present.extend(ticket.estimate_minutes.map(|_| "estimate"));
+        if !self.db.get_ticket_tags(ticket.id).await?.is_empty() {
+            present.push("tags");
+        }
+        let since = self.db.get_status_changes(ticket.id).await?.last().map(|change| change.changed_at);
+        let comments = self.db.get_comments(ticket.id).await?;
+        if comments.iter().any(|comment| since.is_none_or(|since| comment.created_at >= since)) {
+            present.push("comment");
+        }
+        Ok(present)
+    }
+
+    /// Comment on the change from the ticket's current status to `status` when
+    /// `status_comments` is on, returning whether a comment was added
+    async fn add_status_comment(&self, ticket: &Ticket, status: &str, reason: Option<&str>) -> Result<bool> {
+        if !self.config.status_comments || ticket.status.eq_ignore_ascii_case(status) {
+            return Ok(false);
+        }
+        let user = self.config.user.name.as_deref().or(self.config.user.email.as_deref());
+        let comment = format_status_comment(&ticket.status, status, user, Local::now().naive_local(), reason);
+        let validated_comment = validate_content_length(&comment, ContentType::Comment)?;
+        self.db.add_comment(ticket.id, &validated_comment).await?;
+        Ok(true)
+    }
+
+    /// Post a `closed` or `blocked` event to the webhooks that want it when a ticket moves
+    /// from its current status to `status`
+    async fn notify_status_change(&self, ticket: &Ticket, status: &str, reason: Option<&str>) {
+        let event = match status {
+            _ if ticket.status.eq_ignore_ascii_case(status) => return,
+            _ if status.eq_ignore_ascii_case("blocked") => "blocked",
+            _ if is_finished_status(status) => "closed",
+            _ => return,
+        };
+        if self.config.webhooks.is_empty() {
+            return;
+        }
+        let id = ticket.id.to_string();
+        let fields = [
+            ("ticket", id.as_str()),
+            ("name", &ticket.name),
+            ("project", &ticket.project),
+            ("status", status),
+            ("previous", &ticket.status),
+            ("user", self.user_name()),
+            ("reason", reason.unwrap_or("no reason given")),
+        ];
+        for failure in webhook::notify(&self.config.webhooks, event, &fields).await {
+            feedback::show_warning(&failure);
+        }
+    }
+
+    /// Who to name in webhook messages: `[user]` name or email
+    fn user_name(&self) -> &str {
+        self.config.user.name.as_deref().or(self.config.user.email.as_deref()).unwrap_or("someone")
+    }
+
+    /// Post a `timer` event, once per timer, for timers running longer than a webhook's
+    /// `timer_after`, or `timer_warning` for webhooks without one
+    async fn notify_long_timers(&mut self) -> Result<()> {
+        let default_after = self.config.timer_warning()?;
+        let now = Utc::now().naive_utc();
+        let timers = self.db.list_timers().await?;
+        for (name, webhook) in self.config.webhooks.iter().filter(|(_, webhook)| webhook.wants("timer")) {
+            let Some(after) = webhook.timer_after()?.or(default_after) else {
+                continue;
+            };
+            for timer in timers.iter().filter(|t| !t.is_paused() && t.elapsed(now) >= after) {
+                // Remembered per webhook and timer so each long run is announced once
+                let key = format!("webhook.timer.{}.{}", name, timer.ticket_id);
+                let started = timer.started_at.to_string();
+                if self.db.get_state(&key).await?.as_deref() == Some(started.as_str()) {
+                    continue;
+                }
+                let Some(ticket) = self.db.get_ticket(timer.ticket_id).await? else {
+                    continue;
+                };
+                let (id, elapsed) = (ticket.id.to_string(), format_duration(timer.elapsed(now)));
+                let fields = [
+                    ("ticket", id.as_str()),
+                    ("name", &ticket.name),
+                    ("project", &ticket.project),
+                    ("status", &ticket.status),
+                    ("user", self.user_name()),
+                    ("elapsed", &elapsed),
+                ];
+                match webhook.post("timer", &fields).await {
+                    Ok(()) => self.db.set_state(&key, &started).await?,
+                    Err(e) => feedback::show_warning(&format!("Webhook '{}' failed: {}", name, e)),
+                }
+            }
         }
         Ok(())
     }
@@ -1147,6 +4672,7 @@ impl CommandHandler {
     async fn update_ticket_field_internal(&mut self, ticket_id: &str, field: &str, value: &str) -> Result<()> {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
         self.validate_ticket_exists(validated_ticket_id).await?;
+        self.check_ticket_lock(validated_ticket_id).await?;
 
         match field {
             "name" => {
@@ -1163,12 +4689,111 @@ impl CommandHandler {
                 pb.finish_with_message("Ticket updated");
                 feedback::show_success(&format!("Ticket {} description updated", validated_ticket_id));
             }
-            "status" => {
-                let validated_status = validate_status(value)?;
-                self.update_ticket_status_internal(validated_ticket_id, &validated_status, false).await?;
+            "status" => {
+                let validated_status = self.validate_ticket_status(validated_ticket_id, value).await?;
+                self.update_ticket_status_internal(validated_ticket_id, &validated_status, false, true).await?;
+            }
+            "rate" => {
+                // "none" goes back to the project's rate
+                let rate = match value.trim() {
+                    "" | "none" => None,
+                    rate => Some(validate_rate(rate)?),
+                };
+                self.db.update_ticket_rate(validated_ticket_id, rate).await?;
+                match rate {
+                    Some(rate) => feedback::show_success(&format!("Ticket {} billed at {:.2} per hour", validated_ticket_id, rate)),
+                    None => feedback::show_success(&format!("Ticket {} billed at its project's rate", validated_ticket_id)),
+                }
+            }
+            "type" => {
+                // "none" clears the type
+                let kind = match value.trim() {
+                    "" | "none" => None,
+                    kind => {
+                        let project = self.db.get_ticket(validated_ticket_id).await?.map(|t| t.project).unwrap_or_default();
+                        Some(self.db.get_project_settings(&project).await?.validate_kind(kind)?)
+                    }
+                };
+                self.db.update_ticket_kind(validated_ticket_id, kind.as_deref()).await?;
+                match kind {
+                    Some(kind) => feedback::show_success(&format!("Ticket {} is now a {}", validated_ticket_id, kind)),
+                    None => feedback::show_success(&format!("Ticket {} type cleared", validated_ticket_id)),
+                }
+            }
+            "severity" => {
+                // "none" clears the severity
+                let severity = match value.trim() {
+                    "" | "none" => None,
+                    severity => Some(validate_severity(severity)?),
+                };
+                self.db.update_ticket_severity(validated_ticket_id, severity.as_deref()).await?;
+                match severity {
+                    Some(severity) => feedback::show_success(&format!("Ticket {} severity set to {}", validated_ticket_id, severity)),
+                    None => feedback::show_success(&format!("Ticket {} severity cleared", validated_ticket_id)),
+                }
+            }
+            "priority" => {
+                // "none" clears the priority
+                let priority = match value.trim() {
+                    "" | "none" => None,
+                    priority => Some(validate_priority(priority)?),
+                };
+                self.db.update_ticket_priority(validated_ticket_id, priority.as_deref()).await?;
+                match priority {
+                    Some(priority) => feedback::show_success(&format!("Ticket {} priority set to {}", validated_ticket_id, priority)),
+                    None => feedback::show_success(&format!("Ticket {} priority cleared", validated_ticket_id)),
+                }
+            }
+            "estimate" => {
+                // "none" clears the estimate
+                let estimate = match value.trim() {
+                    "" | "none" => None,
+                    estimate => Some(
+                        duration_word(estimate)
+                            .ok_or_else(|| anyhow::anyhow!("'{}' is not a duration like 2h, 1h30m, or 45m", estimate))?,
+                    ),
+                };
+                self.db.update_ticket_estimate(validated_ticket_id, estimate).await?;
+                match estimate {
+                    Some(minutes) => feedback::show_success(&format!("Ticket {} estimated at {}", validated_ticket_id, format_estimate(minutes))),
+                    None => feedback::show_success(&format!("Ticket {} estimate cleared", validated_ticket_id)),
+                }
+            }
+            "tags" => {
+                // Comma-separated tags, added to the ticket's existing ones
+                let tags = value.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(validate_tag).collect::<Result<Vec<_>, _>>()?;
+                self.db.add_ticket_tags(validated_ticket_id, &tags).await?;
+                feedback::show_success(&format!("Ticket {} tagged {}", validated_ticket_id, tags.join(", ")));
+            }
+            "reach" | "impact" | "confidence" | "effort" => {
+                // "none" clears the value
+                let score = match value.trim() {
+                    "" | "none" => None,
+                    score => Some(validate_score(field, score)?),
+                };
+                self.db.update_ticket_score(validated_ticket_id, field, score).await?;
+                match score {
+                    Some(_) => feedback::show_success(&format!("Ticket {} {} set to {}", validated_ticket_id, field, value.trim())),
+                    None => feedback::show_success(&format!("Ticket {} {} cleared", validated_ticket_id, field)),
+                }
+            }
+            "component" => {
+                // "none" takes the ticket out of its component
+                let component = match value.trim() {
+                    "" | "none" => None,
+                    component => {
+                        let project = self.db.get_ticket(validated_ticket_id).await?.map(|t| t.project).unwrap_or_default();
+                        Some(self.check_component(&project, component).await?)
+                    }
+                };
+                self.db.update_ticket_component(validated_ticket_id, component.as_deref()).await?;
+                match component {
+                    Some(component) => feedback::show_success(&format!("Ticket {} moved to component {}", validated_ticket_id, component)),
+                    None => feedback::show_success(&format!("Ticket {} taken out of its component", validated_ticket_id)),
+                }
             }
             _ => {
-                feedback::show_error(&format!("Unknown field '{}'. Supported fields: name, description, status", field));
+                feedback::show_error(&format!("Unknown field '{}'. Supported fields: name, description, status, rate, type, severity, component, priority, estimate, tags, reach, impact, confidence, effort", field));
             }
         }
         Ok(())
@@ -1177,9 +4802,13 @@ impl CommandHandler {
     async fn delete_ticket_internal(&mut self, ticket_id: &str, force: bool) -> Result<()> {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
         self.validate_ticket_exists(validated_ticket_id).await?;
+        self.check_ticket_lock(validated_ticket_id).await?;
 
         if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
-            let target = format!("ticket {} ('{}')", validated_ticket_id, ticket.name);
+            let mut target = format!("ticket {} ('{}')", validated_ticket_id, ticket.name);
+            if let Some(impact) = format_delete_impact(&self.db.delete_impact(&[validated_ticket_id]).await?) {
+                target = tf("delete.with", &[&target, &impact]);
+            }
 
             if !force && !interactive::confirm_destructive_action("delete", &target)? {
                 feedback::show_info("Operation cancelled");
@@ -1194,14 +4823,255 @@ impl CommandHandler {
         Ok(())
     }
 
+    async fn promote_comment_internal(&mut self, comment_id: &str, project: Option<String>, name: Option<String>) -> Result<()> {
+        let validated_comment_id = validate_ticket_id(comment_id)?; // reuse positive integer validation
+        let Some(comment) = self.db.get_comment(validated_comment_id).await? else {
+            feedback::show_error(&format!("Comment #{} not found", validated_comment_id));
+            return Ok(());
+        };
+        let project = match project {
+            Some(project) => validate_project_name(&project)?,
+            None => self.db.get_ticket(comment.ticket_id).await?.map(|t| t.project).unwrap_or_default(),
+        };
+        let name = validate_content_length(
+            &name.unwrap_or_else(|| comment.title(ContentType::TicketName.limits().1)),
+            ContentType::TicketName,
+        )?;
+        validate_content_length(&comment.content, ContentType::Description)?;
+        let settings = self.db.get_project_settings(&project).await?;
+        settings.check_required(&project, &["description"])?;
+
+        let status = settings.initial_status(&project, None)?;
+        let ticket_id = self.db.promote_comment(&comment, &project, &name, &status).await?;
+        if settings.default_priority.is_some() {
+            self.db.update_ticket_priority(ticket_id, settings.default_priority.as_deref()).await?;
+        }
+        feedback::show_success(&format!(
+            "Comment #{} promoted to ticket {} ('{}') in '{}'",
+            comment.id, settings.display_id(ticket_id), name, project
+        ));
+        Ok(())
+    }
+
+    /// Delete every ticket matching the filters, after listing them and asking the user to
+    /// type the number of tickets
+    async fn bulk_delete_internal(&mut self, project: Option<String>, status: Option<String>, force: bool) -> Result<()> {
+        let project = project.as_deref().map(validate_project_name).transpose()?;
+        let status = match (&status, &project) {
+            (Some(status), Some(project)) => {
+                Some(self.db.get_project_settings(project).await?.validate_status(project, status)?)
+            }
+            (Some(status), None) => Some(validate_status(status)?),
+            (None, _) => None,
+        };
+        let tickets = self.db.list_tickets_filtered(project.as_deref(), status.as_deref(), "project", false).await?;
+        if tickets.is_empty() {
+            feedback::show_info("No tickets match; nothing to delete");
+            return Ok(());
+        }
+
+        let expected = tickets.len().to_string();
+        if let Some(deleted) = self.delete_tickets_confirmed(&tickets, &expected, force).await? {
+            feedback::show_success(&format!("Deleted {} tickets", deleted));
+        }
+        Ok(())
+    }
+
+    /// List the tickets and what goes with them, ask for `expected` to be typed, back up, and
+    /// delete; `None` if the user declined
+    async fn delete_tickets_confirmed(&mut self, tickets: &[Ticket], expected: &str, force: bool) -> Result<Option<u64>> {
+        let ids: Vec<i64> = tickets.iter().map(|t| t.id).collect();
+        let locked = self.db.locked_tickets(&ids).await?;
+        if !locked.is_empty() && !self.unlock {
+            let locked: Vec<String> = locked.iter().map(i64::to_string).collect();
+            anyhow::bail!("Locked tickets would be deleted ({}); add --unlock to delete them anyway", locked.join(", "));
+        }
+        let impact = self.db.delete_impact(&ids).await?;
+        if !self.renderer.is_data() {
+            println!("{}", format_ticket_list(tickets));
+        }
+        feedback::show_warning(&match format_delete_impact(&impact) {
+            Some(impact) => tf("delete.bulk_summary", &[&tickets.len(), &impact]),
+            None => tf("delete.bulk_summary_alone", &[&tickets.len()]),
+        });
+
+        if !force {
+            if !interactive::can_prompt() {
+                return Err(anyhow::anyhow!("Bulk delete needs a terminal to confirm; pass --force to skip confirmation"));
+            }
+            if !interactive::confirm_typed(&tf("confirm.typed_delete", &[&expected]), expected)? {
+                feedback::show_info("Operation cancelled");
+                return Ok(None);
+            }
+        }
+
+        backup::before(&self.db, &self.config.backup, "delete").await;
+        let pb = feedback::create_progress_bar("Deleting tickets");
+        let deleted = self.db.delete_tickets(&ids).await?;
+        pb.finish_with_message("Tickets deleted");
+        Ok(Some(deleted))
+    }
+
+    /// Archive, or delete, finished tickets last updated before the cutoff, reporting per project
+    async fn handle_cleanup(
+        &mut self,
+        older_than: &str,
+        project: Option<String>,
+        delete: bool,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<()> {
+        let age = validate_period(older_than)?;
+        let project = project.as_deref().map(validate_project_name).transpose()?;
+        let tickets = self.db.list_cleanup_candidates(Utc::now().naive_utc() - age, project.as_deref()).await?;
+        if tickets.is_empty() {
+            feedback::show_success(&format!("Nothing to clean up: no finished tickets older than {}", older_than));
+            return Ok(());
+        }
+
+        let outcome = match (dry_run, delete) {
+            (true, false) => CleanupOutcome::WouldArchive,
+            (true, true) => CleanupOutcome::WouldDelete,
+            (false, false) => {
+                let ids: Vec<i64> = tickets.iter().map(|t| t.id).collect();
+                self.db.archive_tickets(&ids).await?;
+                CleanupOutcome::Archived
+            }
+            (false, true) => {
+                if self.delete_tickets_confirmed(&tickets, &tickets.len().to_string(), force).await?.is_none() {
+                    return Ok(());
+                }
+                CleanupOutcome::Deleted
+            }
+        };
+        let report = format_cleanup_report(&tickets, outcome);
+        let text = if dry_run { format!("{}\n{}", format_ticket_list(&tickets), report) } else { report };
+        let action = match outcome {
+            CleanupOutcome::Archived => "archived",
+            CleanupOutcome::Deleted => "deleted",
+            CleanupOutcome::WouldArchive => "would_archive",
+            CleanupOutcome::WouldDelete => "would_delete",
+        };
+        self.renderer.print(&Report::new(text, serde_json::json!({ "outcome": action, "tickets": tickets })));
+        if outcome == CleanupOutcome::Archived {
+            feedback::show_info("Archived tickets are hidden from lists; see them with 'ltm list --archived'");
+        }
+        Ok(())
+    }
+
+    /// Ask which status to move a ticket to, from those its project and workflow allow next.
+    /// Scripts, with no terminal, and `--force` get an error instead; `None` if the pick was
+    /// cancelled.
+    async fn pick_status(&self, ticket_id: i64, force: bool) -> Result<Option<String>> {
+        if force || !interactive::can_prompt() {
+            anyhow::bail!("No status given; name one, e.g. 'ltm update status {} closed'", ticket_id);
+        }
+        let Some(ticket) = self.db.get_ticket(ticket_id).await? else {
+            return Err(ValidationError::TicketNotFound(ticket_id).into());
+        };
+        let settings = self.db.get_project_settings(&ticket.project).await?;
+        let mut statuses = match self.config.workflow(&ticket.project).and_then(|w| w.allowed_next(&ticket.status)) {
+            Some(next) => next.to_vec(),
+            None => settings.statuses.clone().unwrap_or_else(valid_statuses),
+        };
+        statuses.retain(|status| !status.eq_ignore_ascii_case(&ticket.status));
+        if statuses.is_empty() {
+            anyhow::bail!("Ticket {} can't move on from {}", ticket_id, ticket.status);
+        }
+        let prompt = format!("Move ticket {} ('{}') from {} to", ticket_id, ticket.name, ticket.status);
+        Ok(interactive::select(&prompt, &statuses)?.map(|index| statuses.swap_remove(index)))
+    }
+
+    /// Ask which existing project to move a ticket to. Scripts, with no terminal, get an
+    /// error instead; `None` if the pick was cancelled.
+    async fn pick_project(&self, ticket_id: &str) -> Result<Option<String>> {
+        let validated_ticket_id = validate_ticket_id(ticket_id)?;
+        if !interactive::can_prompt() {
+            anyhow::bail!("No project given; name one, e.g. 'ltm ticket move {} webapp'", validated_ticket_id);
+        }
+        let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? else {
+            return Err(ValidationError::TicketNotFound(validated_ticket_id).into());
+        };
+        let mut projects = self.db.list_projects().await?;
+        projects.retain(|project| *project != ticket.project);
+        if projects.is_empty() {
+            anyhow::bail!("There is no other project to move ticket {} to; name a new one", validated_ticket_id);
+        }
+        let prompt = format!("Move ticket {} ('{}') from {} to", validated_ticket_id, ticket.name, ticket.project);
+        Ok(interactive::select(&prompt, &projects)?.map(|index| projects.swap_remove(index)))
+    }
+
     async fn move_ticket_internal(&mut self, ticket_id: &str, project: &str) -> Result<()> {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
         let validated_project = validate_project_name(project)?;
         self.validate_ticket_exists(validated_ticket_id).await?;
+        self.check_ticket_lock(validated_ticket_id).await?;
+        let before = self.db.get_ticket(validated_ticket_id).await?;
         let pb = feedback::create_progress_bar("Moving ticket");
         self.db.move_ticket_project(validated_ticket_id, &validated_project).await?;
         pb.finish_with_message("Ticket moved");
         feedback::show_success(&format!("Ticket {} moved to project '{}'", validated_ticket_id, validated_project));
+        if let (Some(before), Some(after)) = (before, self.db.get_ticket(validated_ticket_id).await?) {
+            if let Some(component) = before.component.filter(|_| after.component.is_none()) {
+                feedback::show_info(&format!("Project '{}' has no component '{}', so the ticket has none now", validated_project, component));
+            }
+            if let Some(release) = before.target_release.filter(|_| after.target_release.is_none()) {
+                feedback::show_info(&format!("Project '{}' has no release '{}', so the ticket is no longer planned for it", validated_project, release));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reorder a ticket within its project; `target` maps its current 1-based position to the new one
+    async fn rank_ticket_internal(&mut self, ticket_id: &str, target: impl Fn(usize) -> usize) -> Result<()> {
+        let validated_ticket_id = validate_ticket_id(ticket_id)?;
+        self.validate_ticket_exists(validated_ticket_id).await?;
+        let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? else {
+            return Ok(());
+        };
+
+        let backlog = self.db.list_tickets_filtered(Some(&ticket.project), None, "rank", false).await?;
+        let current = backlog.iter().position(|t| t.id == validated_ticket_id).unwrap_or(0) + 1;
+        let position = self.db.move_ticket_to_position(validated_ticket_id, target(current)).await?;
+
+        if position == current {
+            feedback::show_info(&format!(
+                "Ticket {} is already at position {} of {} in '{}'",
+                validated_ticket_id, position, backlog.len(), ticket.project
+            ));
+        } else {
+            feedback::show_success(&format!(
+                "Moved ticket {} to position {} of {} in '{}'",
+                validated_ticket_id, position, backlog.len(), ticket.project
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rank every other ticket, in any project or status, by text similarity
+    async fn similar_tickets_internal(&mut self, ticket_id: &str, limit: usize) -> Result<()> {
+        let validated_ticket_id = validate_ticket_id(ticket_id)?;
+        self.validate_ticket_exists(validated_ticket_id).await?;
+        let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? else {
+            return Ok(());
+        };
+
+        let tickets = self.db.list_tickets(None).await?;
+        let mut related = suggestions::rank_related(&ticket, &tickets);
+        related.truncate(limit);
+
+        let text = format!(
+            "{}Tickets similar to #{} {}\n\n{}",
+            icon("🔍 "),
+            ticket.id,
+            ticket.name,
+            format_related_tickets(&related)
+        );
+        let related: Vec<serde_json::Value> = related
+            .iter()
+            .map(|(t, score)| serde_json::json!({ "id": t.id, "project": t.project, "name": t.name, "status": t.status, "score": score }))
+            .collect();
+        self.renderer.print(&Report::new(text, serde_json::json!({ "ticket_id": ticket.id, "related": related })));
         Ok(())
     }
 
@@ -1226,6 +5096,7 @@ impl CommandHandler {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
         let validated_content = validate_content_length(content, ContentType::Comment)?;
         self.validate_ticket_exists(validated_ticket_id).await?;
+        self.check_ticket_lock(validated_ticket_id).await?;
 
         if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
             let pb = feedback::create_progress_bar("Adding comment");
@@ -1236,6 +5107,22 @@ impl CommandHandler {
         Ok(())
     }
 
+    async fn add_snippet_comment_internal(&mut self, ticket_id: &str, snippet: &str, extra: Option<&str>) -> Result<()> {
+        let validated_ticket_id = validate_ticket_id(ticket_id)?;
+        self.validate_ticket_exists(validated_ticket_id).await?;
+        self.check_ticket_lock(validated_ticket_id).await?;
+        let template = self.config.snippet(snippet)?;
+        let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? else {
+            return Ok(());
+        };
+        let identity = self.config.user.identity();
+        let mut content = snippet::render(template, &ticket, identity.as_deref(), Local::now().naive_local());
+        if let Some(extra) = extra {
+            content = format!("{}\n{}", content.trim_end(), extra);
+        }
+        self.add_comment_internal(ticket_id, &content).await
+    }
+
     async fn list_comments_internal(&mut self, ticket_id: &str) -> Result<()> {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
         self.validate_ticket_exists(validated_ticket_id).await?;
@@ -1244,45 +5131,111 @@ impl CommandHandler {
         let comments = self.db.get_comments(validated_ticket_id).await?;
         pb.finish_and_clear();
 
-        if comments.is_empty() {
-            feedback::show_info(&format!("No comments found for ticket {}", validated_ticket_id));
-        } else {
-            println!("💬 Comments for ticket {}:", validated_ticket_id);
-            for (i, comment) in comments.iter().enumerate() {
-                println!("  {}. {} - {}", i + 1, comment.created_at.format("%Y-%m-%d %H:%M"), comment.content);
+        self.renderer.print(&CommentList { ticket_id: validated_ticket_id, comments });
+        Ok(())
+    }
+
+    async fn rename_project_internal(&mut self, old: &str, new: &str) -> Result<()> {
+        let old = validate_project_name(old)?;
+        let new = validate_project_name(new)?;
+        let projects = self.db.list_projects().await?;
+        if !projects.contains(&old) {
+            feedback::show_info(&format!("No tickets found for project '{}'", old));
+            let suggestions = suggestions::suggest_project_names(&self.db, &old).await?;
+            if let Some(suggestion_msg) = suggestions::format_suggestions(&old, &suggestions, "project") {
+                feedback::show_thinking(&suggestion_msg);
+            }
+            return Ok(());
+        }
+        if old == new {
+            feedback::show_info(&format!("Project '{}' already has that name", old));
+            return Ok(());
+        }
+        if projects.contains(&new) {
+            return Err(anyhow::anyhow!(
+                "Project '{}' already exists; move tickets into it with 'ltm ticket move' instead",
+                new
+            ));
+        }
+
+        let renamed = self.db.rename_project(&old, &new).await?;
+        feedback::show_success(&format!("Renamed project '{}' to '{}': {} tickets updated", old, new, renamed));
+        if self.config.workflow(&old).is_some() {
+            feedback::show_warning(&format!(
+                "config.toml still has a workflow for '{}'; rename [workflows.{}] to [workflows.{}] to keep it",
+                old, old, new
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delete or archive every ticket in a project, optionally exporting it first; deleting
+    /// asks for the project name to be typed
+    async fn delete_project_internal(
+        &mut self,
+        project: &str,
+        archive: bool,
+        export_first: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        let project = validate_project_name(project)?;
+        let tickets = self.db.list_tickets(Some(&project)).await?;
+        if tickets.is_empty() {
+            if !archive && self.db.forget_project(&project).await? > 0 {
+                feedback::show_success(&format!("Removed the settings, components, and releases of project '{}', which has no tickets", project));
+            } else {
+                feedback::show_info(&format!("No tickets found for project '{}'", project));
+            }
+            return Ok(());
+        }
+
+        if let Some(path) = export_first {
+            let mut exported = Vec::with_capacity(tickets.len());
+            for ticket in &tickets {
+                exported.push(crate::json_formatting::ExportedTicket {
+                    ticket: ticket.clone(),
+                    comments: self.db.get_comments(ticket.id).await?,
+                    time_logs: self.db.get_time_logs(ticket.id).await?,
+                    urls: self.db.get_ticket_urls(ticket.id).await?,
+                    checklist: self.db.get_checklist(ticket.id).await?,
+                    tags: self.db.get_ticket_tags(ticket.id).await?,
+                });
+            }
+            let json = crate::json_formatting::format_project_export_json(&project, Utc::now().naive_utc(), exported);
+            fs::write(path, json).map_err(|e| anyhow::anyhow!("Could not write export to {}: {}", path, e))?;
+            feedback::show_success(&format!("Exported {} tickets from '{}' to {}", tickets.len(), project, path));
+        }
+
+        if archive {
+            let target = format!("all {} tickets in project '{}'", tickets.len(), project);
+            if !force && !interactive::confirm_destructive_action("archive", &target)? {
+                feedback::show_info("Operation cancelled");
+                return Ok(());
             }
-            feedback::show_success(&format!("Found {} comment(s)", comments.len()));
+            let ids: Vec<i64> = tickets.iter().map(|t| t.id).collect();
+            let archived = self.db.archive_tickets(&ids).await?;
+            feedback::show_success(&format!("Archived {} tickets in project '{}'", archived, project));
+        } else if let Some(deleted) = self.delete_tickets_confirmed(&tickets, &project, force).await? {
+            self.db.forget_project(&project).await?;
+            feedback::show_success(&format!("Deleted project '{}' with {} tickets", project, deleted));
         }
         Ok(())
     }
 
-    async fn show_project_summary_internal(&mut self, project: &str, json: bool) -> Result<()> {
+    async fn show_project_summary_internal(&mut self, project: &str) -> Result<()> {
         let validated_project = validate_project_name(project)?;
 
         let pb = feedback::create_progress_bar("Loading project summary");
         let summary = self.db.get_project_summary(&validated_project).await?;
         pb.finish_and_clear();
-        
-        if json {
-            let output = crate::json_formatting::format_project_summary_json(&validated_project, &summary);
-            println!("{}", output);
-            return Ok(());
-        }
 
-        if summary.total_tickets == 0 {
-            feedback::show_info(&format!("No tickets found for project '{}'", validated_project));
+        let suggestion = if summary.total_tickets == 0 {
             let suggestions = suggestions::suggest_project_names(&self.db, &validated_project).await?;
-            if let Some(suggestion_msg) = suggestions::format_suggestions(&validated_project, &suggestions, "project") {
-                feedback::show_thinking(&suggestion_msg);
-            }
-            return Ok(());
-        }
-
-        feedback::show_success(&format!("📊 Project Summary for '{}':", validated_project));
-        println!("   📋 Total Tickets: {}", summary.total_tickets);
-        println!("   🟢 Open Tickets: {}", summary.open_tickets);
-        println!("   🔴 Closed Tickets: {}", summary.closed_tickets);
-        println!("   ⏱️  Total Time: {:.2} hours", summary.total_time_hours);
+            suggestions::format_suggestions(&validated_project, &suggestions, "project")
+        } else {
+            None
+        };
+        self.renderer.print(&ProjectSummaryResult { project: validated_project, summary, suggestion });
         Ok(())
     }
 
@@ -1299,74 +5252,56 @@ impl CommandHandler {
         let mut project_list: Vec<String> = projects.drain().collect();
         project_list.sort();
 
-        if project_list.is_empty() {
-            feedback::show_info("No projects found");
-        } else {
-            println!("📁 Projects:");
-            for project in &project_list {
-                println!("  • {}", project);
-            }
-            feedback::show_success(&format!("Found {} project(s)", project_list.len()));
-        }
+        self.renderer.print(&ProjectList { projects: project_list });
         Ok(())
     }
 
     async fn handle_show_active_timers(&mut self) -> Result<()> {
-        if self.time_tracking.is_empty() {
-            feedback::show_info("No active timers");
-        } else {
-            println!("⏱️  Active Timers:");
-            for (ticket_id, state) in &self.time_tracking {
-                let mut total_duration = if let Some(elapsed) = state.elapsed_time {
-                    elapsed
-                } else {
-                    Duration::zero()
-                };
-
-                // Calculate current duration based on whether the timer is paused
-                if let Some(paused_at) = state.paused_at {
-                    total_duration = total_duration + (paused_at - state.start_time);
-                } else {
-                    total_duration = total_duration + (Utc::now() - state.start_time);
-                }
+        let mut timers = Vec::new();
+        for timer in self.db.list_timers().await? {
+            let name = self.db.get_ticket(timer.ticket_id).await?.map(|t| t.name);
+            timers.push((timer, name));
+        }
+        self.renderer.print(&TimerList { timers, now: Utc::now().naive_utc() });
+        Ok(())
+    }
 
-                let hours = total_duration.num_hours();
-                let minutes = total_duration.num_minutes() % 60;
-                let status = if state.is_paused() { "⏸️  PAUSED" } else { "▶️  RUNNING" };
+    /// Print the timer the status line would show. Polled like the status line, so it only
+    /// reads what the status line does.
+    async fn show_current_timer(&self) -> Result<()> {
+        let state = PromptState::read(&self.db, Local::now().date_naive()).await?;
+        print_current_timer(&self.renderer, &state);
+        Ok(())
+    }
 
-                if let Some(ticket) = self.db.get_ticket(*ticket_id).await? {
-                    println!("  • Ticket {} ('{}'): {}h {}m - {}", ticket_id, ticket.name, hours, minutes, status);
-                } else {
-                    println!("  • Ticket {}: {}h {}m - {}", ticket_id, hours, minutes, status);
-                }
-            }
-            feedback::show_success(&format!("{} active timer(s)", self.time_tracking.len()));
-        }
+    /// Print the status line. Runs in refresh loops, so it stays quiet: no spinner, no
+    /// success message, and a few small queries.
+    async fn print_status_line(&self, format: Option<&str>) -> Result<()> {
+        let state = PromptState::read(&self.db, Local::now().date_naive()).await?;
+        print_status_line(format, &self.config, &state);
         Ok(())
     }
 
     async fn stop_time_tracking_internal(&mut self, ticket_id: i64) -> Result<()> {
-        if let Some(state) = self.time_tracking.remove(&ticket_id) {
-            let end_time = Utc::now();
-            let mut total_duration = if let Some(elapsed) = state.elapsed_time {
-                elapsed
-            } else {
-                Duration::zero()
-            };
+        self.stop_time_tracking_at(ticket_id, Utc::now()).await
+    }
 
-            // If the timer is paused, use the paused_at time as the end time
-            // Otherwise, calculate duration from start_time to now
-            if let Some(paused_at) = state.paused_at {
-                total_duration = total_duration + (paused_at - state.start_time);
-            } else {
-                total_duration = total_duration + (end_time - state.start_time);
-            }
+    /// Stop a timer as if it had been stopped at `end_time`, logging the time up to then
+    async fn stop_time_tracking_at(&mut self, ticket_id: i64, end_time: DateTime<Utc>) -> Result<()> {
+        if let Some(timer) = self.db.get_timer(ticket_id).await? {
 
+            // If the timer is paused, the pause point is the effective end
+            let total_duration = timer.elapsed(end_time.naive_utc());
             let hours = total_duration.num_hours() as i32;
             let minutes = (total_duration.num_minutes() % 60) as i32;
+            let (hours, minutes) = self.round_logged_time(ticket_id, hours, minutes).await?;
 
             let pb = feedback::create_progress_bar("Logging time");
-            self.db.add_time_log(ticket_id, hours, minutes, Some(state.start_time), Some(end_time)).await?;
+            let started_at = Some(timer.started_at.and_utc());
+            self.db
+                .add_time_log_with_note(ticket_id, hours, minutes, started_at, Some(end_time), timer.note.as_deref())
+                .await?;
+            self.db.delete_timer(ticket_id).await?;
             pb.finish_with_message("Time logged");
 
             if let Some(ticket) = self.db.get_ticket(ticket_id).await? {
@@ -1386,8 +5321,59 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// Warn on stderr about running timers older than `timer_warning`. At a terminal, offer
+    /// to stop each one, trim it to when work actually stopped, or cancel it.
+    async fn check_long_timers(&mut self) -> Result<()> {
+        let Some(limit) = self.config.timer_warning()? else {
+            return Ok(());
+        };
+        let now = Utc::now().naive_utc();
+        let timers = self.db.list_timers().await?;
+        let prompt = !self.renderer.is_data() && interactive::can_prompt() && std::io::stderr().is_terminal();
+        for timer in timers.iter().filter(|t| !t.is_paused() && t.elapsed(now) >= limit) {
+            let name = self.db.get_ticket(timer.ticket_id).await?.map(|t| t.name).unwrap_or_default();
+            let warning = format!(
+                "Timer for ticket {} ('{}') has been running for {}",
+                timer.ticket_id,
+                name,
+                format_duration(timer.elapsed(now))
+            );
+            eprintln!("{}", with_icon("⏰", warning.yellow().bold()));
+            if !prompt {
+                eprintln!(
+                    "   Stop it with 'ltm time stop {0}', or drop it with 'ltm time cancel {0}'",
+                    timer.ticket_id
+                );
+                continue;
+            }
+            let choice = interactive::choose_key("   [s]top, [t]rim, [c]ancel, or any other key to keep it running?", &['s', 't', 'c'])?;
+            match choice {
+                Some('s') => self.stop_time_tracking_internal(timer.ticket_id).await?,
+                Some('t') => {
+                    let Some(answer) = interactive::ask("Stopped working at (HH:MM, or a date and time)")? else {
+                        continue;
+                    };
+                    // HH:MM is read as the nearest such time to the middle of the timer's run
+                    let middle = timer.segment_started_at + (now - timer.segment_started_at) / 2;
+                    let end = self.resolve_log_time(&answer, middle)?;
+                    let local = |at: NaiveDateTime| at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string();
+                    if end > now {
+                        anyhow::bail!("{} is in the future; the timer is still running", local(end));
+                    }
+                    if end <= timer.segment_started_at {
+                        return Err(ValidationError::InvalidTimeSpan { start: local(timer.segment_started_at), end: local(end) }.into());
+                    }
+                    self.stop_time_tracking_at(timer.ticket_id, end.and_utc()).await?;
+                }
+                Some('c') => self.cancel_time_tracking_internal(timer.ticket_id).await?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     async fn stop_all_active_timers(&mut self) -> Result<()> {
-        let active_tickets: Vec<i64> = self.time_tracking.keys().cloned().collect();
+        let active_tickets: Vec<i64> = self.db.list_timers().await?.iter().map(|t| t.ticket_id).collect();
 
         if active_tickets.is_empty() {
             feedback::show_info("No active timers to stop");
@@ -1403,7 +5389,7 @@ impl CommandHandler {
     }
 
     async fn cancel_time_tracking_internal(&mut self, ticket_id: i64) -> Result<()> {
-        if let Some(_) = self.time_tracking.remove(&ticket_id) {
+        if self.db.delete_timer(ticket_id).await? {
             if let Some(ticket) = self.db.get_ticket(ticket_id).await? {
                 feedback::show_success(&format!(
                     "Cancelled time tracking for ticket {} ('{}')",
@@ -1422,7 +5408,7 @@ impl CommandHandler {
     }
 
     async fn cancel_all_active_timers(&mut self) -> Result<()> {
-        let active_tickets: Vec<i64> = self.time_tracking.keys().cloned().collect();
+        let active_tickets: Vec<i64> = self.db.list_timers().await?.iter().map(|t| t.ticket_id).collect();
 
         if active_tickets.is_empty() {
             feedback::show_info("No active timers to cancel");
@@ -1438,14 +5424,14 @@ impl CommandHandler {
     }
 
     async fn pause_time_tracking_internal(&mut self, ticket_id: i64) -> Result<()> {
-        if let Some(state) = self.time_tracking.get_mut(&ticket_id) {
-            if state.is_paused() {
+        if let Some(timer) = self.db.get_timer(ticket_id).await? {
+            if timer.is_paused() {
                 feedback::show_warning(&format!("Timer for ticket {} is already paused", ticket_id));
                 return Ok(());
             }
 
             // Set the paused_at time to now
-            state.paused_at = Some(Utc::now());
+            self.db.pause_timer(ticket_id, Utc::now().naive_utc()).await?;
 
             if let Some(ticket) = self.db.get_ticket(ticket_id).await? {
                 feedback::show_success(&format!(
@@ -1465,26 +5451,14 @@ impl CommandHandler {
     }
 
     async fn resume_time_tracking_internal(&mut self, ticket_id: i64) -> Result<()> {
-        if let Some(state) = self.time_tracking.get_mut(&ticket_id) {
-            if !state.is_paused() {
+        if let Some(timer) = self.db.get_timer(ticket_id).await? {
+            if !timer.is_paused() {
                 feedback::show_warning(&format!("Timer for ticket {} is not paused", ticket_id));
                 return Ok(());
             }
 
-            // Calculate elapsed time up to the pause point
-            let paused_at = state.paused_at.unwrap(); // Safe because we checked is_paused()
-            let current_elapsed = paused_at - state.start_time;
-
-            // Update elapsed time (add current segment to any previous elapsed time)
-            state.elapsed_time = Some(if let Some(previous_elapsed) = state.elapsed_time {
-                previous_elapsed + current_elapsed
-            } else {
-                current_elapsed
-            });
-
-            // Reset start time to now and clear paused_at
-            state.start_time = Utc::now();
-            state.paused_at = None;
+            // Fold the paused segment into the elapsed total and start a new segment now
+            self.db.resume_timer(ticket_id, Utc::now().naive_utc()).await?;
 
             if let Some(ticket) = self.db.get_ticket(ticket_id).await? {
                 feedback::show_success(&format!(
@@ -1503,27 +5477,341 @@ impl CommandHandler {
         Ok(())
     }
 
-    async fn log_time_duration_internal(&mut self, ticket_id: &str, duration: &str) -> Result<()> {
+    /// Round logged time up to the step set for the ticket's project, if any
+    async fn round_logged_time(&self, ticket_id: i64, hours: i32, minutes: i32) -> Result<(i32, i32)> {
+        let Some(ticket) = self.db.get_ticket(ticket_id).await? else {
+            return Ok((hours, minutes));
+        };
+        let settings = self.db.get_project_settings(&ticket.project).await?;
+        let total = settings.round_minutes(hours as i64 * 60 + minutes as i64);
+        Ok(((total / 60) as i32, (total % 60) as i32))
+    }
+
+    async fn log_time_duration_internal(
+        &mut self,
+        ticket_id: &str,
+        duration: &str,
+        logged_at: Option<NaiveDateTime>,
+        billable: bool,
+    ) -> Result<()> {
         let validated_ticket_id = validate_ticket_id(ticket_id)?;
         self.validate_ticket_exists(validated_ticket_id).await?;
 
         // Parse duration string (e.g., "2h30m", "1.5h", "90m")
         let (hours, minutes) = self.parse_duration(duration)?;
+        self.log_time_internal(validated_ticket_id, hours, minutes, logged_at, billable, None).await
+    }
+
+    /// Log time described in a sentence such as "2h on 14 yesterday debugging auth", on the
+    /// focused ticket when the sentence names none
+    async fn log_sentence_internal(&mut self, sentence: &str) -> Result<()> {
+        let today = Local::now().date_naive();
+        let line = parse_log_line(sentence, today);
+        let Some(total) = line.minutes else {
+            anyhow::bail!(
+                "No duration in '{}'. Start with one, e.g. ltm log \"2h on 14 yesterday debugging auth\"",
+                sentence
+            );
+        };
+        let ticket_id = self.resolve_ticket_id(line.ticket.as_deref()).await?;
+        self.validate_ticket_exists(ticket_id).await?;
+        let (hours, minutes) = validate_minutes(total)?;
+        if line.date.is_some_and(|day| day > today) {
+            anyhow::bail!("Can't log time on {}, which is in the future", line.date.unwrap_or(today));
+        }
+        let note = match line.note.as_str() {
+            "" => None,
+            note => Some(validate_content_length(note, ContentType::TimerNote)?),
+        };
+
+        let logged_at = line.date.map(|day| logged_at_local(day, None)).transpose()?;
+        self.check_billing_lock(logged_at.unwrap_or_else(|| Utc::now().naive_utc()), self.unlock).await?;
+        self.log_time_internal(ticket_id, hours, minutes, logged_at, true, note.as_deref()).await
+    }
+
+    /// Log time on a ticket after rounding it to the project's `time_rounding`
+    async fn log_time_internal(
+        &mut self,
+        ticket_id: i64,
+        hours: i32,
+        minutes: i32,
+        logged_at: Option<NaiveDateTime>,
+        billable: bool,
+        note: Option<&str>,
+    ) -> Result<()> {
+        let (hours, minutes) = self.round_logged_time(ticket_id, hours, minutes).await?;
 
         let pb = feedback::create_progress_bar("Logging time");
-        self.db.add_time_log(validated_ticket_id, hours, minutes, None, None).await?;
+        let on_day = logged_at
+            .map(|at| format!(" on {}", at.and_utc().with_timezone(&Local).format("%Y-%m-%d")))
+            .unwrap_or_default();
+        let logged_at = logged_at.unwrap_or_else(|| Utc::now().naive_utc());
+        let log_id = self.db.add_time_log_at_with_note(ticket_id, hours, minutes, logged_at, note).await?;
+        if !billable {
+            self.db.update_time_log_billable(log_id, false).await?;
+        }
         pb.finish_with_message("Time logged");
 
-        if let Some(ticket) = self.db.get_ticket(validated_ticket_id).await? {
+        if let Some(ticket) = self.db.get_ticket(ticket_id).await? {
             feedback::show_celebration(&format!(
-                "Logged {} hours and {} minutes for ticket {} ('{}')",
-                hours, minutes, validated_ticket_id, ticket.name
+                "Logged {} hours and {} minutes for ticket {} ('{}'){}{}{}",
+                hours,
+                minutes,
+                ticket_id,
+                ticket.name,
+                on_day,
+                if billable { "" } else { ", not billable" },
+                note.map(|note| format!(": {}", note)).unwrap_or_default()
             ));
         }
         Ok(())
     }
 
-    fn parse_duration(&self, duration: &str) -> Result<(i32, i32)> {
+/// Change a time log's duration or span. A new start or end keeps the other end and
+    /// recomputes the duration; see `TimeLog::respan` for logs that only have a duration.
+    async fn update_time_log_internal(
+        &mut self,
+        log_id: &str,
+        duration: Option<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        unlock: bool,
+    ) -> Result<()> {
+        let validated_log_id = validate_ticket_id(log_id)?;
+        let Some(log) = self.db.get_time_log(validated_log_id).await? else {
+            anyhow::bail!("Time log #{} not found", validated_log_id);
+        };
+        self.check_billing_lock(log.started_at.unwrap_or(log.created_at), unlock).await?;
+        if duration.is_some() && start.is_some() && end.is_some() {
+            anyhow::bail!("Give a duration or both --start and --end, not all three");
+        }
+
+        let length = duration
+            .map(|d| self.parse_duration(d))
+            .transpose()?
+            .map(|(hours, minutes)| chrono::Duration::minutes(hours as i64 * 60 + minutes as i64));
+        let near = |at: Option<NaiveDateTime>| at.or(log.started_at).or(log.ended_at).unwrap_or(log.created_at);
+        let start = start.map(|s| self.resolve_log_time(s, near(log.started_at))).transpose()?;
+        let end = end.map(|e| self.resolve_log_time(e, near(log.ended_at))).transpose()?;
+
+        let (started_at, ended_at, length) = log.respan(start, end, length);
+        // Nor may it move into a locked month
+        if let Some(started_at) = started_at {
+            self.check_billing_lock(started_at, unlock).await?;
+        }
+        if let (Some(started_at), Some(ended_at)) = (started_at, ended_at) {
+            if ended_at <= started_at {
+                let local = |at: NaiveDateTime| at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string();
+                return Err(ValidationError::InvalidTimeSpan { start: local(started_at), end: local(ended_at) }.into());
+            }
+        }
+        let minutes = length.num_minutes();
+        let (hours, minutes) = crate::validation::validate_time((minutes / 60) as i32, (minutes % 60) as i32)?;
+
+        let pb = feedback::create_progress_bar("Updating time log");
+        self.db.update_time_log_span(validated_log_id, hours, minutes, started_at, ended_at).await?;
+        pb.finish_with_message("Time log updated");
+        let span = match (started_at, ended_at) {
+            (Some(s), Some(e)) => format!(
+                " ({} → {})",
+                s.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                e.and_utc().with_timezone(&Local).format("%H:%M")
+            ),
+            _ => String::new(),
+        };
+        let logged = format_minutes(hours as i64 * 60 + minutes as i64);
+        feedback::show_success(&format!("Time log #{} updated to {}{}", validated_log_id, logged, span));
+        Ok(())
+    }
+
+    /// Open a day's time logs in the editor and save the edits in one go, like
+    /// `crontab -e`; a sheet that doesn't parse can be opened again to fix it
+    async fn edit_timesheet_internal(&mut self, day: Option<&str>, unlock: bool) -> Result<()> {
+        let day = match day {
+            Some(day) => validate_date(day)?,
+            None => Local::now().date_naive(),
+        };
+        let logs = self.db.time_logs_between(day, day, None).await?;
+        let mut ticket_names = BTreeMap::new();
+        for log in &logs {
+            if let Some(ticket) = self.db.get_ticket(log.ticket_id).await? {
+                ticket_names.insert(ticket.id, ticket.name);
+            }
+        }
+
+        let original = format_timesheet(day, &logs, &ticket_names);
+        let mut text = original.clone();
+        let changes = loop {
+            text = edit(&text)?;
+            if text == original {
+                feedback::show_info("No changes made");
+                return Ok(());
+            }
+            let planned = match parse_timesheet(&text) {
+                Ok(rows) => self.check_timesheet_tickets(&rows).await.and_then(|()| {
+                    plan_changes(&logs, &rows, logged_at_local(day, None)?)
+                }),
+                Err(e) => Err(e),
+            };
+            match planned {
+                Ok(changes) => break changes,
+                Err(e) if interactive::can_prompt() => {
+                    feedback::show_error(&e.to_string());
+                    if !interactive::confirm_action_default_yes("Edit the timesheet again?")? {
+                        anyhow::bail!("Timesheet left unchanged");
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        if changes.is_empty() {
+            feedback::show_info("No changes made");
+            return Ok(());
+        }
+        for log in &logs {
+            self.check_billing_lock(log.started_at.unwrap_or(log.created_at), unlock).await?;
+        }
+        self.check_billing_lock(logged_at_local(day, None)?, unlock).await?;
+
+        self.db.apply_time_log_changes(&changes).await?;
+        let count = |f: fn(&TimeLogChange) -> bool| changes.iter().filter(|c| f(c)).count();
+        feedback::show_success(&format!(
+            "Timesheet for {} saved: {} added, {} changed, {} deleted",
+            day,
+            count(|c| matches!(c, TimeLogChange::Add { .. })),
+            count(|c| matches!(c, TimeLogChange::Update { .. })),
+            count(|c| matches!(c, TimeLogChange::Delete { .. }))
+        ));
+        Ok(())
+    }
+
+    /// Email the time logged and the tickets finished over the last week, or today with
+    /// `daily`
+    async fn send_report_internal(&mut self, daily: bool, to: Vec<String>, project: Option<&str>, dry_run: bool) -> Result<()> {
+        let project = project.map(validate_project_name).transpose()?;
+        let recipients = if to.is_empty() { self.config.email.to.clone() } else { to };
+        if recipients.is_empty() {
+            anyhow::bail!("No recipients; pass --to or set to under [email] in config.toml");
+        }
+        let today = Local::now().date_naive();
+        let (title, from) = if daily { ("Daily summary", today) } else { ("Weekly summary", today - chrono::Duration::days(6)) };
+
+        let entries = self.db.time_log_entries(from, today, project.as_deref()).await?;
+        let mut finished = Vec::new();
+        for change in self.db.status_changes_between(from, today, project.as_deref()).await? {
+            if !is_finished_status(&change.to_status) || finished.iter().any(|t: &Ticket| t.id == change.ticket_id) {
+                continue;
+            }
+            // Only tickets still finished, not ones reopened since
+            if let Some(ticket) = self.db.get_ticket(change.ticket_id).await?.filter(|t| is_finished_status(&t.status)) {
+                finished.push(ticket);
+            }
+        }
+        let (subject, body) = format_report_email(title, (from, today), project.as_deref(), &entries, &finished);
+
+        let message = self.config.email.message(self.config.user.identity().as_deref(), &recipients, &subject, &body)?;
+        if dry_run {
+            println!("To: {}\nSubject: {}\n\n{}", recipients.join(", "), subject, body.trim_end());
+            return Ok(());
+        }
+        let pb = feedback::create_progress_bar("Sending report");
+        self.config.email.send(message).await?;
+        pb.finish_with_message("Report sent");
+        feedback::show_success(&format!("Sent \"{}\" to {}", subject, recipients.join(", ")));
+        Ok(())
+    }
+
+    /// Import time logs from CSV in one transaction after checking every row: that it
+    /// parses, that its ticket exists, and that its month isn't locked for billing
+    async fn import_time_internal(&mut self, file: &std::path::Path, columns: &ColumnMap, dry_run: bool, unlock: bool) -> Result<()> {
+        let text = if file.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?
+        };
+        let (logs, mut errors) = read_time_csv(&text, columns)?;
+        let rows = logs.len() + errors.len();
+        if rows == 0 {
+            anyhow::bail!("No time logs in {}", file.display());
+        }
+
+        let mut tickets = HashMap::new();
+        for log in &logs {
+            let exists = match tickets.get(&log.ticket_id) {
+                Some(&exists) => exists,
+                None => *tickets.entry(log.ticket_id).or_insert(self.db.get_ticket(log.ticket_id).await?.is_some()),
+            };
+            if !exists {
+                errors.push((log.line, format!("Ticket #{} not found", log.ticket_id)));
+            } else if let Some(month) = self.db.locked_month(log.logged_at.date()).await?.filter(|_| !unlock) {
+                errors.push((log.line, format!("{} is locked for billing; add --unlock to import into it anyway", month)));
+            }
+        }
+        if !errors.is_empty() {
+            errors.sort();
+            for (line, error) in &errors {
+                feedback::show_error(&format!("Line {}: {}", line, error));
+            }
+            anyhow::bail!("{} of {} rows have problems; nothing was imported", errors.len(), rows);
+        }
+
+        let total = format_minutes(logs.iter().map(|log| log.minutes).sum());
+        let summary = format!("{} time log(s) totaling {} on {} ticket(s)", logs.len(), total, tickets.len());
+        if dry_run {
+            feedback::show_success(&format!("All {} rows are valid; would import {}", rows, summary));
+            return Ok(());
+        }
+        let changes = logs
+            .into_iter()
+            .map(|log| {
+                let (hours, minutes) = validate_minutes(log.minutes)?;
+                Ok(TimeLogChange::Add { ticket_id: log.ticket_id, hours, minutes, logged_at: log.logged_at, note: log.note })
+            })
+            .collect::<Result<Vec<_>, ValidationError>>()?;
+        self.db.apply_time_log_changes(&changes).await?;
+        feedback::show_success(&format!("Imported {}", summary));
+        Ok(())
+    }
+
+    /// Fail on the first ticket in an edited timesheet that doesn't exist
+    async fn check_timesheet_tickets(&self, rows: &[TimesheetRow]) -> Result<()> {
+        for ticket_id in rows.iter().map(|row| row.ticket_id).collect::<BTreeSet<_>>() {
+            if self.db.get_ticket(ticket_id).await?.is_none() {
+                anyhow::bail!("Ticket #{} not found", ticket_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// The days `--from` and `--to` of `time report` and `time invoice` cover, inclusive:
+    /// the current month up to today unless given
+    fn report_period(&self, from: Option<&str>, to: Option<&str>) -> Result<(NaiveDate, NaiveDate)> {
+        let today = Local::now().date_naive();
+        let from = match from {
+            Some(from) => validate_date(from)?,
+            None => today.with_day(1).unwrap_or(today),
+        };
+        let to = to.map(validate_date).transpose()?.unwrap_or(today);
+        Ok(validate_date_range(from, to)?)
+    }
+
+    /// A time given to `time update --start/--end`, in UTC: HH:MM is the local time of day
+    /// nearest to `near`, so fixing a timer that ran past midnight keeps the right day
+    fn resolve_log_time(&self, input: &str, near: NaiveDateTime) -> Result<NaiveDateTime> {
+        let Ok(time) = validate_time_of_day(input) else {
+            return Ok(validate_timestamp(input)?);
+        };
+        let day = near.and_utc().with_timezone(&Local).date_naive();
+        [day.pred_opt(), Some(day), day.succ_opt()]
+            .into_iter()
+            .flatten()
+            .filter_map(|day| Local.from_local_datetime(&day.and_time(time)).earliest())
+            .map(|at| at.naive_utc())
+            .min_by_key(|at| (*at - near).num_seconds().abs())
+            .ok_or_else(|| ValidationError::InvalidTime(input.to_string()).into())
+    }
+
+        fn parse_duration(&self, duration: &str) -> Result<(i32, i32)> {
         // Simple duration parsing - can be enhanced later
         if duration.contains('h') || duration.contains('m') {
             let mut hours = 0;
@@ -0,0 +1,142 @@
+use clap::Command;
+
+/// Kinds of values that can be completed from the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Projects,
+    Statuses,
+    Tickets,
+}
+
+/// Work out which dynamic value (if any) comes next on a partial command line.
+///
+/// `previous` holds the complete words after the binary name, excluding the word being typed;
+/// the shell filters the candidates against that word itself.
+pub fn completion_kind(root: &Command, previous: &[String]) -> Option<CompletionKind> {
+    match previous.last().map(String::as_str) {
+        Some("--project") => return Some(CompletionKind::Projects),
+        Some("--status") => return Some(CompletionKind::Statuses),
+        _ => {}
+    }
+
+    let mut cmd = root;
+    let mut positional_index = 0;
+    let mut skip_value = false;
+    for word in previous {
+        if skip_value {
+            skip_value = false;
+            continue;
+        }
+        if let Some(flag) = word.strip_prefix("--") {
+            skip_value = cmd
+                .get_arguments()
+                .find(|a| a.get_long() == Some(flag))
+                .map(|a| a.get_action().takes_values())
+                .unwrap_or(false);
+            continue;
+        }
+        if word.starts_with('-') {
+            continue;
+        }
+        if positional_index == 0 {
+            if let Some(sub) = cmd.find_subcommand(word) {
+                cmd = sub;
+                continue;
+            }
+        }
+        positional_index += 1;
+    }
+
+    let arg = cmd.get_positionals().nth(positional_index)?;
+    match arg.get_id().as_str() {
+        "ticket_id" => Some(CompletionKind::Tickets),
+        "project" => Some(CompletionKind::Projects),
+        "status" => Some(CompletionKind::Statuses),
+        _ => None,
+    }
+}
+
+/// Shell code appended to the generated bash script to query `ltm __complete`
+pub const BASH_DYNAMIC: &str = r#"
+_ltm_dynamic() {
+    local candidates
+    candidates=$(ltm __complete -- "${COMP_WORDS[@]:1:COMP_CWORD-1}" 2>/dev/null)
+    if [ -n "$candidates" ]; then
+        COMPREPLY=( $(compgen -W "$candidates" -- "${COMP_WORDS[COMP_CWORD]}") )
+    else
+        _ltm "$@"
+    fi
+}
+
+complete -F _ltm_dynamic -o nosort -o bashdefault -o default ltm
+"#;
+
+/// Shell code appended to the generated zsh script to query `ltm __complete`
+pub const ZSH_DYNAMIC: &str = r#"
+_ltm_dynamic() {
+    local -a candidates
+    candidates=(${(f)"$(ltm __complete -- "${(@)words[2,CURRENT-1]}" 2>/dev/null)"})
+    if (( ${#candidates} )); then
+        compadd -a candidates
+    else
+        _ltm "$@"
+    fi
+}
+
+compdef _ltm_dynamic ltm
+"#;
+
+/// Shell code appended to the generated fish script to query `ltm __complete`
+pub const FISH_DYNAMIC: &str = r#"
+complete -c ltm -f -a '(ltm __complete -- (commandline -opc)[2..-1] 2>/dev/null)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    fn test_command() -> Command {
+        Command::new("ltm")
+            .subcommand(Command::new("show").arg(Arg::new("ticket_id")))
+            .subcommand(
+                Command::new("update").subcommand(
+                    Command::new("status")
+                        .arg(Arg::new("ticket_id"))
+                        .arg(Arg::new("status")),
+                ),
+            )
+            .subcommand(
+                Command::new("list")
+                    .visible_alias("ls")
+                    .arg(Arg::new("project"))
+                    .arg(Arg::new("sort").long("sort")),
+            )
+    }
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_positional_completion() {
+        let cmd = test_command();
+        assert_eq!(completion_kind(&cmd, &words("show")), Some(CompletionKind::Tickets));
+        assert_eq!(completion_kind(&cmd, &words("update status 3")), Some(CompletionKind::Statuses));
+        assert_eq!(completion_kind(&cmd, &words("ls --sort created")), Some(CompletionKind::Projects));
+    }
+
+    #[test]
+    fn test_flag_value_completion() {
+        let cmd = test_command();
+        assert_eq!(completion_kind(&cmd, &words("list --status")), Some(CompletionKind::Statuses));
+        assert_eq!(completion_kind(&cmd, &words("list --project")), Some(CompletionKind::Projects));
+    }
+
+    #[test]
+    fn test_no_dynamic_completion() {
+        let cmd = test_command();
+        assert_eq!(completion_kind(&cmd, &words("")), None);
+        assert_eq!(completion_kind(&cmd, &words("show 1")), None);
+    }
+}
@@ -0,0 +1,400 @@
+use anyhow::{Context, Result};
+use chrono::Duration;
+use dirs::home_dir;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::backup::BackupConfig;
+use crate::currency::Currencies;
+use crate::email::EmailConfig;
+use crate::invoice::InvoiceConfig;
+use crate::jira::JiraConfig;
+use crate::linear::LinearConfig;
+use crate::remote::{RemoteConfig, ServerConfig};
+use crate::shortcut::Shortcut;
+use crate::webhook::Webhook;
+use crate::validation::{
+    set_max_length, set_project_name_pattern, set_status_alias, set_valid_statuses, valid_statuses, validate_period, ContentType,
+};
+use crate::workflow::Workflow;
+
+/// Environment variable pointing at a config file other than `~/.ltm/config.toml`
+pub const CONFIG_ENV: &str = "LTM_CONFIG";
+
+/// User settings read from `~/.ltm/config.toml`
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Language for messages, e.g. `de` or `de_DE.UTF-8`
+    pub locale: Option<String>,
+    /// Screen-reader friendly output, same as `--plain`
+    pub plain: bool,
+    /// Set to `false` to replace emoji with ASCII markers such as `[OK]`
+    pub emoji: Option<bool>,
+    /// Status workflows keyed by project name
+    pub workflows: BTreeMap<String, Workflow>,
+    /// Automatic backups of the database
+    pub backup: BackupConfig,
+    /// Template for `ltm status-line`, e.g. `"{timer} | {due} due"`
+    pub status_line: Option<String>,
+    /// Who is making changes, stamped on tickets, comments, and time logs
+    pub user: UserConfig,
+    /// Set to `false` to leave schema upgrades to `ltm migrate` instead of applying them
+    /// on the next command
+    pub auto_migrate: Option<bool>,
+    /// Comment templates for `ltm comment add --snippet NAME`, keyed by name
+    pub snippets: BTreeMap<String, String>,
+    /// How long a timer may run before every command warns about it, e.g. `"8h"`;
+    /// `"off"` turns the warning off
+    pub timer_warning: Option<String>,
+    /// Teams and states for `ltm sync linear`
+    pub linear: LinearConfig,
+    /// Site, projects, and field mapping for `ltm sync jira`
+    pub jira: JiraConfig,
+    /// A shared `ltm serve` to use instead of the local database
+    pub remote: RemoteConfig,
+    /// Settings for `ltm serve`
+    pub server: ServerConfig,
+    /// Letterhead, rate, and clients for `ltm time invoice`
+    pub invoice: InvoiceConfig,
+    /// SMTP server and recipients for `ltm report send`
+    pub email: EmailConfig,
+    /// How amounts are written per currency code, e.g. `[currencies.EUR]`
+    pub currencies: Currencies,
+    /// Maximum lengths in characters keyed by field, e.g. `description = 10000`
+    pub limits: BTreeMap<String, usize>,
+    /// Other names for statuses, e.g. `wip = "in-progress"`
+    pub status_aliases: BTreeMap<String, String>,
+    /// Status shortcuts such as `ltm review`, keyed by command name
+    pub shortcuts: BTreeMap<String, Shortcut>,
+    /// Slack or Discord webhooks to notify, keyed by a name of your choosing
+    pub webhooks: BTreeMap<String, Webhook>,
+    /// Comment on a ticket whenever its status changes, with who changed it, when, and why
+    pub status_comments: bool,
+    /// Project name and status rules replacing the built-in ones
+    pub validation: ValidationRules,
+}
+
+/// How long a timer runs before ltm warns about it, unless `timer_warning` says otherwise
+const DEFAULT_TIMER_WARNING: &str = "8h";
+
+/// Keys `ltm config` can read and write
+pub const CONFIG_KEYS: &[&str] = &["user.name", "user.email", "locale", "status_line", "timer_warning"];
+
+/// The `[user]` table
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct UserConfig {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// The `[validation]` table. Length limits live under `[limits]`.
+///
+/// ```toml
+/// [validation]
+/// project_name = '[a-z0-9][a-z0-9./-]*'      # the whole name must match
+/// statuses = ["open", "in-progress", "review", "closed"]
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationRules {
+    /// Regular expression project names must match, instead of letters, numbers, hyphens,
+    /// and underscores
+    pub project_name: Option<String>,
+    /// Statuses accepted everywhere, in workflow order, instead of the built-in ones
+    pub statuses: Option<Vec<String>>,
+}
+
+impl UserConfig {
+    /// `Name <email>`, or whichever of the two is set
+    pub fn identity(&self) -> Option<String> {
+        match (&self.name, &self.email) {
+            (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+            (Some(name), None) => Some(name.clone()),
+            (None, Some(email)) => Some(email.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Config {
+    /// Location of the config file, honouring `LTM_CONFIG`
+    pub fn path() -> Option<PathBuf> {
+        match std::env::var(CONFIG_ENV) {
+            Ok(path) if !path.trim().is_empty() => Some(PathBuf::from(path)),
+            _ => home_dir().map(|home| home.join(".ltm").join("config.toml")),
+        }
+    }
+
+    /// Load the config file; a missing file gives the defaults
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Could not read config file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// The workflow configured for a project, if any
+    pub fn workflow(&self, project: &str) -> Option<&Workflow> {
+        self.workflows.get(project)
+    }
+
+    /// The snippet called `name`, or an error listing the snippets there are
+    pub fn snippet(&self, name: &str) -> Result<&str> {
+        match self.snippets.get(name) {
+            Some(template) => Ok(template),
+            None if self.snippets.is_empty() => {
+                anyhow::bail!("No snippet '{}'; add snippets under [snippets] in config.toml", name)
+            }
+            None => anyhow::bail!(
+                "No snippet '{}'. Snippets: {}",
+                name,
+                self.snippets.keys().map(String::as_str).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// How long a timer may run before ltm warns about it, or `None` when the warning is off
+    pub fn timer_warning(&self) -> Result<Option<Duration>> {
+        match self.timer_warning.as_deref().unwrap_or(DEFAULT_TIMER_WARNING) {
+            "off" => Ok(None),
+            period => Ok(Some(validate_period(period).context("Invalid timer_warning in config.toml")?)),
+        }
+    }
+
+    /// Use the maximum lengths in `[limits]` for the rest of the process
+    pub fn apply_limits(&self) -> Result<()> {
+        for (key, &max) in &self.limits {
+            let Some(field) = ContentType::from_key(key) else {
+                let keys: Vec<_> = ContentType::ALL.iter().map(ContentType::key).collect();
+                anyhow::bail!("Unknown field '{}' in [limits] in config.toml. Fields: {}", key, keys.join(", "));
+            };
+            if max == 0 {
+                anyhow::bail!("limits.{} in config.toml must be at least 1", key);
+            }
+            set_max_length(field, max);
+        }
+        Ok(())
+    }
+
+    /// Use the rules in `[validation]` for the rest of the process, failing on a bad pattern
+    /// or status before applying any of them
+    pub fn apply_validation_rules(&self) -> Result<()> {
+        let rules = &self.validation;
+        if let Some(statuses) = &rules.statuses {
+            let pattern = regex::Regex::new(r"^[a-z][a-z0-9_-]{0,29}$").unwrap();
+            if statuses.is_empty() {
+                anyhow::bail!("validation.statuses in config.toml lists no statuses");
+            }
+            if let Some(status) = statuses.iter().find(|status| !pattern.is_match(&status.trim().to_lowercase())) {
+                anyhow::bail!(
+                    "Invalid status '{}' in validation.statuses in config.toml. Use up to 30 letters, numbers, hyphens, and underscores, starting with a letter",
+                    status
+                );
+            }
+        }
+        if let Some(pattern) = &rules.project_name {
+            set_project_name_pattern(pattern).context("Invalid validation.project_name in config.toml")?;
+        }
+        if let Some(statuses) = &rules.statuses {
+            set_valid_statuses(statuses);
+        }
+        Ok(())
+    }
+
+    /// Accept the names in `[status_aliases]` as statuses for the rest of the process
+    pub fn apply_status_aliases(&self) -> Result<()> {
+        for (alias, status) in &self.status_aliases {
+            if valid_statuses().contains(&alias.trim().to_lowercase()) {
+                anyhow::bail!("'{}' in [status_aliases] in config.toml is already a status", alias);
+            }
+            if status.trim().is_empty() {
+                anyhow::bail!("status_aliases.{} in config.toml names no status", alias);
+            }
+            set_status_alias(alias, status);
+        }
+        Ok(())
+    }
+
+    /// Fail on a webhook without a URL or with an unknown event
+    pub fn check_webhooks(&self) -> Result<()> {
+        for (name, webhook) in &self.webhooks {
+            webhook.check(name)?;
+        }
+        Ok(())
+    }
+
+    /// Parse config from TOML text
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml_edit::de::from_str(text)?)
+    }
+
+    /// The value of one of `CONFIG_KEYS`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "user.name" => self.user.name.as_deref(),
+            "user.email" => self.user.email.as_deref(),
+            "locale" => self.locale.as_deref(),
+            "status_line" => self.status_line.as_deref(),
+            "timer_warning" => self.timer_warning.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Set one of `CONFIG_KEYS` in the config file, or remove it when `value` is `None`,
+    /// keeping the rest of the file as it is. Returns the file's path.
+    pub fn store(key: &str, value: Option<&str>) -> Result<PathBuf> {
+        let path = Self::path().context("Could not find the home directory for config.toml")?;
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("Could not read config file {}", path.display())),
+        };
+        let updated = set_key(&text, key, value).with_context(|| format!("Invalid config file {}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, updated).with_context(|| format!("Could not write config file {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Set or remove a dotted key such as `user.name` in TOML text
+fn set_key(text: &str, key: &str, value: Option<&str>) -> Result<String> {
+    if !CONFIG_KEYS.contains(&key) {
+        anyhow::bail!("Unknown config key '{}'. Keys: {}", key, CONFIG_KEYS.join(", "));
+    }
+    let mut doc: toml_edit::DocumentMut = text.parse()?;
+    let Some((table_name, name)) = key.split_once('.') else {
+        match value {
+            Some(value) => doc.insert(key, toml_edit::value(value)),
+            None => doc.remove(key),
+        };
+        return Ok(doc.to_string());
+    };
+
+    let item = doc.entry(table_name).or_insert(toml_edit::table());
+    let table = item.as_table_like_mut().with_context(|| format!("'{}' is not a table", table_name))?;
+    match value {
+        Some(value) => table.insert(name, toml_edit::value(value)),
+        None => table.remove(name),
+    };
+    // Don't leave an empty `[user]` behind
+    if table.is_empty() {
+        doc.remove(table_name);
+    }
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(Config::parse("locale = \"de\"\n").unwrap().locale.as_deref(), Some("de"));
+        assert!(Config::parse("").unwrap().locale.is_none());
+        assert!(Config::parse("plain = true").unwrap().plain);
+        assert_eq!(Config::parse("emoji = false").unwrap().emoji, Some(false));
+        assert!(Config::parse("locale = 3").is_err());
+
+        let config = Config::parse("[workflows.webapp]\nopen = [\"in-progress\"]\n").unwrap();
+        assert!(config.workflow("webapp").unwrap().check("open", "closed").is_err());
+        assert!(config.workflow("other").is_none());
+
+        assert!(config.backup.enabled && !config.backup.daily);
+        let config = Config::parse("[backup]\nkeep = 3\ndaily = true\n").unwrap();
+        assert_eq!((config.backup.keep, config.backup.daily), (3, true));
+        assert_eq!(Config::parse("status_line = \"{open} open\"").unwrap().status_line.as_deref(), Some("{open} open"));
+        let config = Config::parse("[snippets]\ntriage = \"Triaged {date}\"\n").unwrap();
+        assert_eq!(config.snippet("triage").unwrap(), "Triaged {date}");
+        assert!(config.snippet("other").unwrap_err().to_string().contains("Snippets: triage"));
+
+        let config = Config::parse("[user]\nname = \"Ada\"\nemail = \"ada@example.com\"\n").unwrap();
+        assert_eq!(config.user.identity().as_deref(), Some("Ada <ada@example.com>"));
+        assert_eq!(config.get("user.email"), Some("ada@example.com"));
+        assert!(Config::default().user.identity().is_none());
+        assert_eq!(Config::parse("auto_migrate = false").unwrap().auto_migrate, Some(false));
+        assert!(Config::parse("status_comments = true").unwrap().status_comments && !Config::default().status_comments);
+
+        assert_eq!(Config::default().timer_warning().unwrap(), Some(Duration::hours(8)));
+        assert_eq!(Config::parse("timer_warning = \"2d\"").unwrap().timer_warning().unwrap(), Some(Duration::days(2)));
+        assert_eq!(Config::parse("timer_warning = \"off\"").unwrap().timer_warning().unwrap(), None);
+        assert!(Config::parse("timer_warning = \"soon\"").unwrap().timer_warning().is_err());
+
+        let config = Config::parse("[linear]\napi_key = \"lin_api_x\"\n[linear.teams]\nENG = \"webapp\"\n").unwrap();
+        assert_eq!(config.linear.api_key().unwrap(), "lin_api_x");
+        assert_eq!(config.linear.teams.get("ENG").map(String::as_str), Some("webapp"));
+
+        let config = Config::parse("[jira]\nurl = \"https://x.atlassian.net/\"\nemail = \"ada@example.com\"\napi_token = \"t\"\n[jira.fields]\ndue_date = \"\"\n").unwrap();
+        assert_eq!(config.jira.credentials().unwrap().0, "https://x.atlassian.net");
+        assert_eq!((config.jira.field("name"), config.jira.field("due_date")), (Some("summary"), None));
+
+        let config = Config::parse("[remote]\nurl = \"http://tickets.lan:7878/\"\n[server]\ntoken = \"s3cret\"\n").unwrap();
+        assert_eq!(config.remote.url().as_deref(), Some("http://tickets.lan:7878"));
+        assert_eq!(config.server.token().as_deref(), Some("s3cret"));
+        assert!(Config::default().remote.url().is_none());
+
+        let config = Config::parse("[invoice]\nrate = 95\nletterhead = [\"Ada\", \"London\"]\n[invoice.clients]\nwebapp = [\"ACME\"]\n").unwrap();
+        assert_eq!((config.invoice.rate, config.invoice.letterhead.len()), (Some(95.0), 2));
+        assert_eq!(config.invoice.client_address(None, Some("webapp")), vec!["ACME"]);
+
+        let config = Config::parse("[email]\nsmtp_host = \"smtp.example.com\"\nsmtp_port = 465\nto = [\"client@example.com\"]\n").unwrap();
+        assert_eq!((config.email.smtp_host.as_deref(), config.email.smtp_port), (Some("smtp.example.com"), Some(465)));
+        assert_eq!(config.email.to, ["client@example.com"]);
+
+        let config = Config::parse("[webhooks.team]\nurl = \"https://hooks.slack.com/x\"\nevents = [\"closed\"]\n").unwrap();
+        assert!(config.check_webhooks().is_ok() && config.webhooks["team"].wants("closed"));
+        let config = Config::parse("[webhooks.team]\nurl = \"https://hooks.slack.com/x\"\nevents = [\"opened\"]\n").unwrap();
+        assert!(config.check_webhooks().is_err());
+        assert!(Config::parse("[webhooks.team]\nurl = \"x\"\nchannel = \"#dev\"\n").is_err());
+
+        let config = Config::parse("[currencies.EUR]\nformat = \"{amount} €\"\ndecimal = \",\"\n").unwrap();
+        assert_eq!(config.currencies["EUR"].decimal.as_deref(), Some(","));
+
+        let config = Config::parse("[limits]\ndescription = 10000\n").unwrap();
+        assert_eq!(config.limits.get("description"), Some(&10000));
+        assert!(Config::parse("[limits]\ntitle = 300\n").unwrap().apply_limits().unwrap_err().to_string().contains("Fields: ticket_name, description"));
+        assert!(Config::parse("[limits]\ncomment = 0\n").unwrap().apply_limits().is_err());
+        assert!(Config::parse("[limits]\ncomment = -5\n").is_err());
+
+        let config = Config::parse("[status_aliases]\nwip = \"in-progress\"\n").unwrap();
+        assert_eq!(config.status_aliases.get("wip").map(String::as_str), Some("in-progress"));
+        assert!(Config::parse("[status_aliases]\nClosed = \"done\"\n").unwrap().apply_status_aliases().unwrap_err().to_string().contains("already a status"));
+        assert!(Config::parse("[status_aliases]\nhold = \" \"\n").unwrap().apply_status_aliases().is_err());
+
+        // Rules are checked before any is applied, so these leave the built-in rules in place
+        let config = Config::parse("[validation]\nproject_name = '[a-z.]+'\nstatuses = [\"open\", \"In Review\"]\n").unwrap();
+        assert_eq!(config.validation.project_name.as_deref(), Some("[a-z.]+"));
+        assert!(config.apply_validation_rules().unwrap_err().to_string().contains("Invalid status 'In Review'"));
+        assert!(Config::parse("[validation]\nstatuses = []\n").unwrap().apply_validation_rules().is_err());
+        let bad_pattern = Config::parse("[validation]\nproject_name = '[a-z'\n").unwrap().apply_validation_rules().unwrap_err();
+        assert!(format!("{:#}", bad_pattern).contains("validation.project_name"));
+        assert!(Config::parse("[validation]\nproject_names = 'x'\n").is_err());
+
+        let config = Config::parse("[shortcuts.review]\nstatus = \"testing\"\ncomment = \"Ready for review\"\n").unwrap();
+        assert_eq!(config.shortcuts["review"].status, "testing");
+        assert!(Config::parse("[shortcuts.review]\nstatus = \"testing\"\ncolour = \"red\"\n").is_err());
+    }
+
+    #[test]
+    fn test_set_key() {
+        let text = "# my settings\nlocale = \"de\"\n";
+        let text = set_key(text, "user.name", Some("Ada")).unwrap();
+        assert!(text.starts_with("# my settings\nlocale = \"de\"\n"));
+        assert_eq!(Config::parse(&text).unwrap().user.name.as_deref(), Some("Ada"));
+
+        let text = set_key(&text, "locale", None).unwrap();
+        let config = Config::parse(&text).unwrap();
+        assert!(config.locale.is_none() && config.user.name.is_some());
+        assert!(!set_key(&text, "user.name", None).unwrap().contains("[user]"));
+        assert!(set_key(&text, "user.phone", Some("1")).is_err());
+    }
+}
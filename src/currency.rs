@@ -0,0 +1,75 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// How amounts in one currency are written, from a `[currencies.<CODE>]` table of
+/// `~/.ltm/config.toml`:
+///
+/// ```toml
+/// [currencies.EUR]
+/// format = "{amount} €"      # defaults to "EUR {amount}"
+/// decimal = ","
+/// thousands = "."
+///
+/// [currencies.GBP]
+/// format = "£{amount}"
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct CurrencyFormat {
+    /// Template with `{amount}` and optionally `{code}`
+    pub format: Option<String>,
+    /// Decimal mark, "." unless set
+    pub decimal: Option<String>,
+    /// Thousands separator, "," unless set; "" for none
+    pub thousands: Option<String>,
+}
+
+/// Display formats by currency code
+pub type Currencies = BTreeMap<String, CurrencyFormat>;
+
+/// `amount` in `currency` as configured, like "EUR 1,234.50" or "1.234,50 €"; just the
+/// number without a currency
+pub fn format_amount(currencies: &Currencies, currency: Option<&str>, amount: f64) -> String {
+    let format = currency.and_then(|code| currencies.get(code)).cloned().unwrap_or_default();
+    let cents = format!("{:.2}", amount.abs());
+    let (whole, cents) = cents.split_once('.').unwrap_or((&cents, "00"));
+    let thousands = format.thousands.as_deref().unwrap_or(",");
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i).is_multiple_of(3) {
+            grouped.push_str(thousands);
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0.0 && (amount * 100.0).round() != 0.0 { "-" } else { "" };
+    let number = format!("{}{}{}{}", sign, grouped, format.decimal.as_deref().unwrap_or("."), cents);
+    match currency {
+        Some(code) => format
+            .format
+            .as_deref()
+            .unwrap_or("{code} {amount}")
+            .replace("{code}", code)
+            .replace("{amount}", &number),
+        None => number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        let mut currencies = Currencies::new();
+        currencies.insert(
+            "EUR".to_string(),
+            CurrencyFormat { format: Some("{amount} €".to_string()), decimal: Some(",".to_string()), thousands: Some(".".to_string()) },
+        );
+        currencies.insert("GBP".to_string(), CurrencyFormat { format: Some("£{amount}".to_string()), ..Default::default() });
+
+        assert_eq!(format_amount(&currencies, Some("EUR"), 1234567.891), "1.234.567,89 €");
+        assert_eq!(format_amount(&currencies, Some("GBP"), 950.0), "£950.00");
+        assert_eq!(format_amount(&currencies, Some("USD"), 1000.5), "USD 1,000.50");
+        assert_eq!(format_amount(&currencies, None, -47.5), "-47.50");
+    }
+}
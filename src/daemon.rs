@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::db::Database;
+use crate::models::{ActiveTimer, OpenCounts};
+use crate::status_line::pick_timer;
+
+/// Set to read the database directly even while a daemon is running
+pub const NO_DAEMON_ENV: &str = "LTM_NO_DAEMON";
+
+/// How long a polled command waits for the daemon before reading the database itself
+#[cfg(unix)]
+const QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// What the status line and `ltm time current` show, and all a daemon keeps for them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptState {
+    /// The timer `pick_timer` chooses, with its ticket's name
+    pub timer: Option<(ActiveTimer, String)>,
+    pub counts: OpenCounts,
+}
+
+impl PromptState {
+    /// Read the state from the database, counting tickets due by `today`
+    pub async fn read(db: &Database, today: NaiveDate) -> Result<Self> {
+        let timers = db.list_timers().await?;
+        let timer = match pick_timer(&timers) {
+            Some(timer) => {
+                let name = db.get_ticket(timer.ticket_id).await?.map(|t| t.name).unwrap_or_default();
+                Some((timer.clone(), name))
+            }
+            None => None,
+        };
+        Ok(Self { timer, counts: db.open_counts(today).await? })
+    }
+}
+
+/// `~/.ltm/daemon.sock`, where a running daemon listens next to the database it serves
+pub fn socket_path() -> Result<PathBuf> {
+    Ok(dirs::home_dir().context("Could not find home directory")?.join(".ltm").join("daemon.sock"))
+}
+
+/// The prompt state from a running daemon; `None` when there is none, it is turned off with
+/// `LTM_NO_DAEMON`, or it doesn't answer in time, so the caller reads the database instead
+#[cfg(unix)]
+pub async fn query() -> Option<PromptState> {
+    if std::env::var_os(NO_DAEMON_ENV).is_some() {
+        return None;
+    }
+    let reply = tokio::time::timeout(QUERY_TIMEOUT, send("state")).await.ok()?.ok()?;
+    match serde_json::from_str(&reply) {
+        Ok(state) => Some(state),
+        Err(_) => {
+            tracing::debug!(%reply, "daemon could not answer");
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn query() -> Option<PromptState> {
+    None
+}
+
+/// The process ID of the running daemon, if there is one
+#[cfg(unix)]
+pub async fn ping() -> Option<u32> {
+    let reply = tokio::time::timeout(QUERY_TIMEOUT, send("ping")).await.ok()?.ok()?;
+    reply.parse().ok()
+}
+
+#[cfg(not(unix))]
+pub async fn ping() -> Option<u32> {
+    None
+}
+
+/// Ask the running daemon to stop; returns false when none was running
+#[cfg(unix)]
+pub async fn stop() -> Result<bool> {
+    if ping().await.is_none() {
+        return Ok(false);
+    }
+    send("stop").await?;
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+pub async fn stop() -> Result<bool> {
+    Ok(false)
+}
+
+/// Send one request line and read the one-line reply
+#[cfg(unix)]
+async fn send(request: &str) -> Result<String> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let mut stream = tokio::net::UnixStream::connect(socket_path()?).await?;
+    stream.write_all(format!("{}\n", request).as_bytes()).await?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).await?;
+    Ok(reply.trim_end().to_string())
+}
+
+/// Answer polled commands over `~/.ltm/daemon.sock` until stopped, keeping the database open
+/// and the prompt state in memory. The state is read again only once the database has
+/// changed, by any process, or the day has.
+///
+/// Requests are single lines: `state` (the prompt state as JSON), `ping` (the process ID),
+/// and `stop`.
+#[cfg(unix)]
+pub async fn run(db: Database) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let path = socket_path()?;
+    if let Some(pid) = ping().await {
+        anyhow::bail!("A daemon is already running (process {}); stop it with 'ltm daemon stop'", pid);
+    }
+    // Left behind by a daemon that was killed
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path).with_context(|| format!("Could not listen on {}", path.display()))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut watcher = db.watch_changes().await?;
+    let mut cached: Option<(NaiveDate, PromptState)> = None;
+    loop {
+        // Errors end only the one connection, so the socket is always removed on the way out
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    tracing::debug!(%error, "could not accept a daemon connection");
+                    continue;
+                }
+            },
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+        };
+        let (reader, mut writer) = stream.into_split();
+        let mut request = String::new();
+        // A client that connects and says nothing must not hold up the others
+        let read = tokio::time::timeout(QUERY_TIMEOUT, BufReader::new(reader).read_line(&mut request)).await;
+        if !matches!(read, Ok(Ok(_))) {
+            continue;
+        }
+        let reply = match request.trim() {
+            "state" => {
+                let today = chrono::Local::now().date_naive();
+                let stale = watcher.changed().await.unwrap_or(true) || cached.as_ref().map(|(day, _)| *day) != Some(today);
+                if stale {
+                    cached = PromptState::read(&db, today).await.map(|state| (today, state)).ok();
+                }
+                match &cached {
+                    Some((_, state)) => serde_json::to_string(state).unwrap_or_else(|error| format!("error: {}", error)),
+                    None => "error: could not read the database".to_string(),
+                }
+            }
+            "ping" => std::process::id().to_string(),
+            "stop" => {
+                let _ = writer.write_all(b"stopping\n").await;
+                break;
+            }
+            other => format!("error: unknown request '{}'", other),
+        };
+        let _ = writer.write_all(format!("{}\n", reply).as_bytes()).await;
+    }
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn run(_db: Database) -> Result<()> {
+    anyhow::bail!("The daemon needs Unix sockets, which this system does not have")
+}
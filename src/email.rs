@@ -0,0 +1,171 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::models::{Ticket, TimeLogEntry};
+
+/// Environment variable with the SMTP password, used when the config file has none
+pub const PASSWORD_ENV: &str = "LTM_SMTP_PASSWORD";
+
+/// The `[email]` table of `~/.ltm/config.toml`, for `ltm report send`:
+///
+/// ```toml
+/// [email]
+/// smtp_host = "smtp.example.com"
+/// smtp_port = 587            # defaults to 587 with starttls, 465 with tls, 25 with none
+/// security = "starttls"      # or "tls", or "none" for a relay on the same machine
+/// username = "ada@example.com"
+/// password = "..."           # or set LTM_SMTP_PASSWORD
+/// from = "Ada Lovelace <ada@example.com>"   # defaults to [user]
+/// to = ["client@example.com"]               # when --to is not given
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub security: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Option<String>,
+    pub to: Vec<String>,
+}
+
+impl EmailConfig {
+    /// A plain-text email from `from` (or `fallback`, the `[user]` identity) to each of `to`
+    pub fn message(&self, fallback: Option<&str>, to: &[String], subject: &str, body: &str) -> Result<Message> {
+        let Some(from) = self.from.as_deref().or(fallback) else {
+            anyhow::bail!("No sender; set from under [email] or email under [user] in config.toml");
+        };
+        let mailbox = |address: &str| address.parse::<Mailbox>().with_context(|| format!("Invalid email address '{}'", address));
+        let mut builder = Message::builder().from(mailbox(from)?).subject(subject).header(ContentType::TEXT_PLAIN);
+        for address in to {
+            builder = builder.to(mailbox(address)?);
+        }
+        Ok(builder.body(body.to_string())?)
+    }
+
+    /// Send `message` through the configured SMTP server
+    pub async fn send(&self, message: Message) -> Result<()> {
+        let Some(host) = self.smtp_host.as_deref().map(str::trim).filter(|host| !host.is_empty()) else {
+            anyhow::bail!("No SMTP server; set smtp_host under [email] in config.toml");
+        };
+        let mut builder = match self.security.as_deref().unwrap_or("starttls") {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            other => anyhow::bail!("Unknown security '{}' under [email]; use starttls, tls, or none", other),
+        };
+        if let Some(port) = self.smtp_port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = self.username.as_deref().filter(|username| !username.is_empty()) {
+            let password = self.password.clone().or_else(|| std::env::var(PASSWORD_ENV).ok());
+            let Some(password) = password.filter(|password| !password.is_empty()) else {
+                anyhow::bail!("No SMTP password; set password under [email] in config.toml or {}", PASSWORD_ENV);
+            };
+            builder = builder.credentials(Credentials::new(username.to_string(), password));
+        }
+        builder.build().send(message).await.with_context(|| format!("Failed to send email through {}", host))?;
+        Ok(())
+    }
+}
+
+/// The subject and plain-text body of a report email: hours per ticket, each day's time
+/// logs, and the tickets finished from `from` to `to`
+pub fn format_report_email(
+    title: &str,
+    (from, to): (NaiveDate, NaiveDate),
+    project: Option<&str>,
+    entries: &[TimeLogEntry],
+    finished: &[Ticket],
+) -> (String, String) {
+    let period = if from == to { from.to_string() } else { format!("{} to {}", from, to) };
+    let subject = match project {
+        Some(project) => format!("{} for {}, {}", title, project, period),
+        None => format!("{}, {}", title, period),
+    };
+    let hours = |minutes: i64| format!("{:.2} h", minutes as f64 / 60.0);
+    let total: i64 = entries.iter().map(|e| e.minutes).sum();
+    let billable: i64 = entries.iter().filter(|e| e.billable).map(|e| e.minutes).sum();
+
+    let mut lines = vec![subject.clone(), String::new()];
+    if entries.is_empty() {
+        lines.push("No time logged.".to_string());
+    } else {
+        let billed = if billable == total { String::new() } else { format!(" ({} billable)", hours(billable)) };
+        lines.push(format!("Time logged: {}{}", hours(total), billed));
+
+        let mut tickets: BTreeMap<i64, (&TimeLogEntry, i64)> = BTreeMap::new();
+        for entry in entries {
+            tickets.entry(entry.ticket_id).or_insert((entry, 0)).1 += entry.minutes;
+        }
+        let mut tickets: Vec<_> = tickets.into_values().collect();
+        tickets.sort_by_key(|&(entry, minutes)| (-minutes, entry.ticket_id));
+        lines.extend(["".to_string(), "By ticket".to_string()]);
+        for (entry, minutes) in tickets {
+            lines.push(format!("  #{} {} ({}): {}", entry.ticket_id, entry.ticket_name, entry.project, hours(minutes)));
+        }
+
+        let mut days: BTreeMap<NaiveDate, Vec<&TimeLogEntry>> = BTreeMap::new();
+        for entry in entries {
+            days.entry(entry.date).or_default().push(entry);
+        }
+        lines.extend(["".to_string(), "By day".to_string()]);
+        for (day, entries) in days {
+            lines.push(format!("  {}: {}", day.format("%a %Y-%m-%d"), hours(entries.iter().map(|e| e.minutes).sum())));
+            for entry in entries {
+                let note = entry.note.as_deref().map(|note| format!(" - {}", note)).unwrap_or_default();
+                lines.push(format!("    #{} {}: {}{}", entry.ticket_id, entry.ticket_name, hours(entry.minutes), note));
+            }
+        }
+    }
+    if !finished.is_empty() {
+        lines.extend(["".to_string(), "Finished".to_string()]);
+        lines.extend(finished.iter().map(|t| format!("  #{} {} ({})", t.id, t.name, t.project)));
+    }
+    (subject, lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_email() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        let entry = |ticket_id: i64, name: &str, date: NaiveDate, minutes: i64, billable: bool| TimeLogEntry {
+            id: 0,
+            project: "web".to_string(),
+            ticket_id,
+            ticket_name: name.to_string(),
+            date,
+            minutes,
+            billable,
+            note: Some("notes".to_string()).filter(|_| ticket_id == 14),
+        };
+        let entries = [entry(7, "Docs", day(6), 30, false), entry(14, "Auth", day(6), 150, true), entry(14, "Auth", day(8), 60, true)];
+        let (subject, body) = format_report_email("Weekly summary", (day(6), day(12)), Some("web"), &entries, &[]);
+        assert_eq!(subject, "Weekly summary for web, 2024-05-06 to 2024-05-12");
+        assert!(body.contains("Time logged: 4.00 h (3.50 h billable)\n"));
+        assert!(body.contains("By ticket\n  #14 Auth (web): 3.50 h\n  #7 Docs (web): 0.50 h\n"));
+        assert!(body.contains("  Mon 2024-05-06: 3.00 h\n    #7 Docs: 0.50 h\n    #14 Auth: 2.50 h - notes\n  Wed 2024-05-08"));
+        assert!(!body.contains("Finished"));
+
+        let (subject, body) = format_report_email("Daily summary", (day(8), day(8)), None, &[], &[]);
+        assert_eq!(subject, "Daily summary, 2024-05-08");
+        assert!(body.contains("No time logged."));
+
+        let config = EmailConfig { from: Some("Ada <ada@example.com>".to_string()), ..Default::default() };
+        let message = config.message(None, &["client@example.com".to_string()], &subject, &body).unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("From: Ada <ada@example.com>"));
+        assert!(raw.contains("To: client@example.com"));
+        assert!(EmailConfig::default().message(None, &[], "s", "b").is_err());
+        assert!(config.message(None, &["not an address".to_string()], "s", "b").unwrap_err().to_string().contains("Invalid email"));
+    }
+}
@@ -1,67 +1,183 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::sync::Mutex;
 use std::time::Duration;
-use tokio::time::sleep;
 
-/// Creates a progress bar for database operations
+use crate::i18n::tf;
+use crate::output::{icon, is_plain};
+
+/// Messages held back while a command writes data, see `capture_messages`
+static CAPTURED: Mutex<Option<Vec<(&'static str, String)>>> = Mutex::new(None);
+
+/// Progress indicators stay hidden until an operation has taken this long, so quick
+/// commands, and scripts running many of them, never draw or wait for one
+pub const PROGRESS_DELAY: Duration = Duration::from_millis(300);
+
+/// Creates a progress bar for database operations. It appears only once the operation
+/// has run for `PROGRESS_DELAY`, and never in plain mode.
 pub fn create_progress_bar(message: &str) -> ProgressBar {
-    let pb = ProgressBar::new_spinner();
+    if is_plain() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::with_draw_target(None, ProgressDrawTarget::hidden());
     pb.set_style(
         ProgressStyle::default_spinner()
             .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
             .template("{spinner:.blue} {msg}")
             .unwrap()
     );
-    pb.set_message(format!("🔄 {}", message));
-    pb.enable_steady_tick(Duration::from_millis(100));
+    pb.set_message(format!("{}{}", icon("🔄 "), message));
+    reveal_if_slow(&pb, true);
     pb
 }
 
-/// Shows progress for time tracking operations
-pub async fn show_time_tracking_progress(operation: &str, ticket_id: i64) {
-    let pb = create_progress_bar(&format!("{} time tracking for ticket {}", operation, ticket_id));
-    sleep(Duration::from_millis(500)).await;
-    pb.finish_with_message(format!("✅ {} time tracking for ticket {}", operation, ticket_id));
+/// Draw `pb` on stderr once `PROGRESS_DELAY` has passed, unless it finished by then
+fn reveal_if_slow(pb: &ProgressBar, spinner: bool) {
+    let pb = pb.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(PROGRESS_DELAY);
+        if !pb.is_finished() {
+            pb.set_draw_target(ProgressDrawTarget::stderr());
+            if spinner {
+                pb.enable_steady_tick(Duration::from_millis(100));
+            }
+        }
+    });
+}
+
+/// Confirms a time tracking operation; `message_id` is a catalog message taking the ticket ID
+pub fn show_time_tracking_progress(message_id: &'static str, ticket_id: i64) {
+    show_success(&tf(message_id, &[&ticket_id]));
 }
 
 /// Shows success message with emoji
 pub fn show_success(message: &str) {
-    println!("✅ {}", message);
+    emit("success", message, success_message(message));
+}
+
+/// A success message as `show_success` prints it
+pub fn success_message(message: &str) -> String {
+    if is_plain() {
+        message.to_string()
+    } else {
+        format!("{}{}", icon("✅ "), message)
+    }
 }
 
 /// Shows error message with emoji
 pub fn show_error(message: &str) {
-    println!("❌ {}", message);
+    let line = if is_plain() { tf("error.prefix", &[&message]) } else { format!("{}{}", icon("❌ "), message) };
+    emit("error", message, line);
 }
 
 /// Shows info message with emoji
 pub fn show_info(message: &str) {
-    println!("ℹ️  {}", message);
+    emit("info", message, info_message(message));
+}
+
+/// An info message as `show_info` prints it
+pub fn info_message(message: &str) -> String {
+    if is_plain() {
+        message.to_string()
+    } else {
+        format!("{}{}", icon("ℹ️  "), message)
+    }
 }
 
 /// Shows warning message with emoji
 pub fn show_warning(message: &str) {
-    println!("⚠️  {}", message);
+    let line = if is_plain() { tf("warning.prefix", &[&message]) } else { format!("{}{}", icon("⚠️  "), message) };
+    emit("warning", message, line);
 }
 
 /// Shows thinking message with emoji
 pub fn show_thinking(message: &str) {
-    println!("🤔 {}", message);
+    emit("info", message, thinking_message(message));
+}
+
+/// A thinking message as `show_thinking` prints it
+pub fn thinking_message(message: &str) -> String {
+    if is_plain() {
+        message.to_string()
+    } else {
+        format!("{}{}", icon("🤔 "), message)
+    }
 }
 
 /// Shows celebration message
 pub fn show_celebration(message: &str) {
-    println!("🎉 {}", message);
+    let line = if is_plain() { message.to_string() } else { format!("{}{}", icon("🎉 "), message) };
+    emit("success", message, line);
+}
+
+/// Print a message, or hold it back while `capture_messages` is on
+fn emit(level: &'static str, message: &str, line: String) {
+    match CAPTURED.lock().unwrap().as_mut() {
+        Some(captured) => captured.push((level, message.to_string())),
+        None => println!("{}", line),
+    }
+}
+
+/// Hold back the `show_*` messages instead of printing them, so JSON, YAML, or CSV on
+/// stdout is not mixed with text. `captured_messages` hands them over.
+pub fn capture_messages() {
+    *CAPTURED.lock().unwrap() = Some(Vec::new());
 }
 
-/// Creates a simple progress bar for known work
+/// The messages held back since `capture_messages`, as `(level, message)` pairs, where
+/// the level is `success`, `info`, `warning`, or `error`
+pub fn captured_messages() -> Vec<(&'static str, String)> {
+    CAPTURED.lock().unwrap().take().unwrap_or_default()
+}
+
+/// Creates a simple progress bar for known work, shown once it has run for `PROGRESS_DELAY`
 pub fn create_determinate_progress_bar(total: u64, message: &str) -> ProgressBar {
-    let pb = ProgressBar::new(total);
+    if is_plain() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::with_draw_target(Some(total), ProgressDrawTarget::hidden());
     pb.set_style(
         ProgressStyle::default_bar()
             .template("{msg} [{wide_bar:.cyan/blue}] {pos}/{len} ({eta})")
             .unwrap()
             .progress_chars("#>-")
     );
-    pb.set_message(format!("🔄 {}", message));
+    pb.set_message(format!("{}{}", icon("🔄 "), message));
+    reveal_if_slow(&pb, false);
     pb
-}
\ No newline at end of file
+}
+
+/// Opens a URL in the browser named by `$BROWSER`, or the system default (xdg-open on
+/// Linux, open on macOS, start on Windows)
+pub fn open_in_browser(url: &str) -> anyhow::Result<()> {
+    let status = match std::env::var("BROWSER") {
+        Ok(browser) if !browser.trim().is_empty() => std::process::Command::new(browser.trim()).arg(url).status(),
+        _ if cfg!(target_os = "macos") => std::process::Command::new("open").arg(url).status(),
+        _ if cfg!(target_os = "windows") => std::process::Command::new("cmd").args(["/C", "start", "", url]).status(),
+        _ => std::process::Command::new("xdg-open").arg(url).status(),
+    };
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => anyhow::bail!("Browser command exited with {}", status),
+        Err(e) => anyhow::bail!("Could not open a browser: {}", e),
+    }
+}
+
+/// Sends a desktop notification (notify-send on Linux, osascript on macOS)
+pub fn send_desktop_notification(title: &str, body: &str) -> anyhow::Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {:?} with title {:?}",
+            body, title
+        );
+        std::process::Command::new("osascript").args(["-e", &script]).status()
+    } else {
+        std::process::Command::new("notify-send").args([title, body]).status()
+    };
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => anyhow::bail!("Notification command exited with {}", status),
+        Err(e) => anyhow::bail!("Could not send desktop notification: {}", e),
+    }
+}
@@ -1,24 +1,20 @@
-use crate::models::{Comment, ProjectSummary, Ticket, TimeLog};
-use chrono::NaiveDateTime;
+use crate::models::{gantt_span, thread_comments, ActiveTimer, ChecklistItem, Comment, DeleteImpact, Event, MonthTrend, ProjectAging, ProjectSummary, Release, RecentTicket, ReleaseRisk, ScoringMethod, RoadmapItem, StatusTime, Ticket, TicketDependency, TicketHours, TicketUrl, TimeLog, TimeLogEntry, AGE_BUCKET_LABELS};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, Weekday};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use colored::*;
 use std::env;
-use tabled::{settings::Style, Table, Tabled};
+use crate::duration::{format_duration, format_minutes};
+use crate::currency::{format_amount, Currencies};
+use crate::json_formatting::{currency_totals, total_amount};
+use crate::project_settings::ProjectSettings;
+use crate::validation::{is_finished_status, status_sort_key};
+use crate::i18n::{t, tf};
+use crate::output::{icon, is_plain, with_icon};
+use crate::search::snippet;
+use std::ops::Range;
+use tabled::{builder::Builder, settings::Style};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-#[derive(Tabled)]
-struct TicketRow {
-    #[tabled(rename = "ID")]
-    id: String,
-    #[tabled(rename = "Project")]
-    project: String,
-    #[tabled(rename = "Name")]
-    name: String,
-    #[tabled(rename = "Status")]
-    status: String,
-    #[tabled(rename = "Updated")]
-    updated: String,
-}
-
 /// Symbols for different statuses
 const STATUS_SYMBOLS: &[(&str, &str)] = &[
     ("open", "●"),
@@ -33,20 +29,59 @@ const STATUS_SYMBOLS: &[(&str, &str)] = &[
     ("wontfix", "⊘"),
 ];
 
+/// Graphviz fill colors for statuses in `ltm graph`; finished statuses are green and the
+/// ones not listed gray
+const STATUS_NODE_COLORS: &[(&str, &str)] = &[
+    ("open", "lightblue"),
+    ("in-progress", "gold"),
+    ("in_progress", "gold"),
+    ("testing", "plum"),
+    ("blocked", "salmon"),
+];
+
+/// Icons for ticket types; types a project adds get the last one
+const KIND_ICONS: &[(&str, &str)] = &[
+    ("bug", "🐛"),
+    ("feature", "✨"),
+    ("chore", "🧹"),
+    ("task", "📌"),
+    ("", "🔹"),
+];
+
+/// Colored markers for severities, most severe first
+const SEVERITY_MARKERS: &[(&str, &str)] = &[
+    ("S1", "🔴"),
+    ("S2", "🟠"),
+    ("S3", "🟡"),
+    ("S4", "⚪"),
+];
+
 /// Icons for different sections
 const ICONS: &[(&str, &str)] = &[
     ("title", "📋"),
     ("project", "🏷️"),
     ("status", "📊"),
     ("created", "📅"),
+    ("due", "⏰"),
+    ("priority", "🔥"),
+    ("estimate", "⏳"),
+    ("type", "🧩"),
+    ("severity", "🚨"),
+    ("component", "🧱"),
+    ("release", "🚀"),
+    ("score", "🎯"),
+    ("tags", "🔖"),
+    ("archived", "🗄️"),
     ("comments", "💬"),
+    ("links", "🔗"),
+    ("checklist", "☑️"),
     ("time", "⏱️"),
     ("summary", "📊"),
 ];
 
 /// Check if color output should be disabled
 fn use_colors() -> bool {
-    env::var("NO_COLOR").is_err()
+    env::var("NO_COLOR").is_err() && !is_plain()
 }
 
 /// Get status symbol for a given status
@@ -69,14 +104,39 @@ fn get_icon(section: &str) -> &str {
 
 /// Get status with symbol but without colors (for table display)
 fn get_status_display(status: &str) -> String {
-    let symbol = get_status_symbol(status);
-    format!("{} {}", symbol, status)
+    if is_plain() {
+        status.to_string()
+    } else {
+        format!("{} {}", get_status_symbol(status), status)
+    }
+}
+
+/// Ticket type with its icon, for table display
+fn get_kind_display(kind: &str) -> String {
+    let icon = KIND_ICONS.iter().find(|(k, _)| k.eq_ignore_ascii_case(kind)).unwrap_or(&KIND_ICONS[KIND_ICONS.len() - 1]).1;
+    with_icon(icon, kind)
+}
+
+/// Ticket name, after a pin for pinned tickets
+fn pinned_name(ticket: &Ticket) -> String {
+    if ticket.pinned_at.is_some() {
+        with_icon("📍", &ticket.name)
+    } else {
+        ticket.name.clone()
+    }
+}
+
+/// Severity with its colored marker; escape codes would throw off table widths
+fn get_severity_display(severity: &str) -> String {
+    match SEVERITY_MARKERS.iter().find(|(s, _)| s.eq_ignore_ascii_case(severity)) {
+        Some((_, marker)) => with_icon(marker, severity),
+        None => severity.to_string(),
+    }
 }
 
 /// Colorize status based on status type
 pub fn colorize_status(status: &str) -> ColoredString {
-    let symbol = get_status_symbol(status);
-    let text = format!("{} {}", symbol, status);
+    let text = get_status_display(status);
     
     if !use_colors() {
         return text.normal();
@@ -99,6 +159,71 @@ fn format_timestamp(dt: &NaiveDateTime) -> String {
     dt.format("%Y-%m-%d").to_string()
 }
 
+/// Format a due date along with how far away it is
+pub fn format_due_date(due: NaiveDate, today: NaiveDate) -> String {
+    let days = (due - today).num_days();
+    let relative = match days {
+        0 => t("due.today").to_string(),
+        1 => t("due.tomorrow").to_string(),
+        -1 => tf("due.overdue_one", &[&1]),
+        d if d < 0 => tf("due.overdue_other", &[&-d]),
+        d => tf("due.in_days", &[&d]),
+    };
+    format!("{} ({})", due.format("%Y-%m-%d"), relative)
+}
+
+/// Estimated effort such as `2h`, `1h 30m`, or `45m`
+pub fn format_estimate(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Priority, estimate, type, severity, component, release, scoring, and tag lines for ticket details, as `(icon, text)` pairs
+fn metadata_lines(ticket: &Ticket, tags: &[String]) -> Vec<(&'static str, String)> {
+    let mut lines = Vec::new();
+    if let Some(priority) = &ticket.priority {
+        lines.push(("priority", tf("details.priority", &[priority])));
+    }
+    if let Some(minutes) = ticket.estimate_minutes {
+        lines.push(("estimate", tf("details.estimate", &[&format_estimate(minutes)])));
+    }
+    if let Some(kind) = &ticket.kind {
+        lines.push(("type", tf("details.type", &[kind])));
+    }
+    if let Some(severity) = &ticket.severity {
+        lines.push(("severity", tf("details.severity", &[&get_severity_display(severity)])));
+    }
+    if let Some(component) = &ticket.component {
+        lines.push(("component", tf("details.component", &[component])));
+    }
+    if let Some(release) = &ticket.target_release {
+        lines.push(("release", tf("details.release", &[release])));
+    }
+    let scoring: Vec<String> = [
+        ticket.reach.map(|reach| format!("reach {}", reach)),
+        ticket.impact.map(|impact| format!("impact {}", format_score_input(impact))),
+        ticket.confidence.map(|confidence| format!("confidence {}%", confidence)),
+        ticket.effort.map(|effort| format!("effort {}", format_score_input(effort))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !scoring.is_empty() {
+        lines.push(("score", tf("details.score", &[&scoring.join(", ")])));
+    }
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+        lines.push(("tags", tf("details.tags", &[&tags.join(" ")])));
+    }
+    if let Some(archived_at) = &ticket.archived_at {
+        lines.push(("archived", tf("details.archived", &[&format_timestamp(archived_at)])));
+    }
+    lines
+}
+
 /// Truncate text to fit within specified width
 fn truncate_text(text: &str, max_width: usize) -> String {
     if text.width() <= max_width {
@@ -119,68 +244,303 @@ fn truncate_text(text: &str, max_width: usize) -> String {
     }
 }
 
+/// Count tickets as (total, open, closed)
+fn ticket_counts(tickets: &[Ticket]) -> (usize, usize, usize) {
+    let total = tickets.len();
+    let closed = tickets.iter().filter(|t| {
+        matches!(t.status.to_lowercase().as_str(), "closed" | "completed" | "done")
+    }).count();
+    (total, total - closed, closed)
+}
+
+/// The comment recorded for a status change when `status_comments` is on, such as
+/// `Status changed open → blocked by Ada at 2024-05-10 14:03, reason: waiting on legal`
+pub fn format_status_comment(from: &str, to: &str, user: Option<&str>, at: NaiveDateTime, reason: Option<&str>) -> String {
+    let mut comment = format!("Status changed {} → {}", from, to);
+    if let Some(user) = user {
+        comment.push_str(&format!(" by {}", user));
+    }
+    comment.push_str(&format!(" at {}", at.format("%Y-%m-%d %H:%M")));
+    if let Some(reason) = reason {
+        comment.push_str(&format!(", reason: {}", reason));
+    }
+    comment
+}
+
+/// What goes with deleted tickets, such as `3 comments, 2 time logs totaling 4h 30m, 1 link`,
+/// or `None` when nothing is attached to them
+pub fn format_delete_impact(impact: &DeleteImpact) -> Option<String> {
+    let parts: Vec<String> = [
+        (impact.comments, "delete.comment", "delete.comments"),
+        (impact.time_logs, "delete.time_log", "delete.time_logs"),
+        (impact.links, "delete.link", "delete.links"),
+        (impact.checklist_items, "delete.checklist_item", "delete.checklist_items"),
+        (impact.dependencies, "delete.dependency", "delete.dependencies"),
+        (impact.synced_issues, "delete.synced_issue", "delete.synced_issues"),
+    ]
+    .into_iter()
+    .filter(|&(count, _, _)| count > 0)
+    .map(|(count, one, many)| tf(if count == 1 { one } else { many }, &[&count, &format_minutes(impact.minutes)]))
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
 /// Format ticket list as a table
 pub fn format_ticket_list(tickets: &[Ticket]) -> String {
+    format_ticket_list_with_checklists(tickets, &HashMap::new())
+}
+
+/// Format ticket list as a table, with component, type, severity, and checklist columns when
+/// any ticket has one.
+///
+/// `checklists` maps ticket IDs to `(done, total)` counts.
+pub fn format_ticket_list_with_checklists(tickets: &[Ticket], checklists: &HashMap<i64, (i64, i64)>) -> String {
     if tickets.is_empty() {
-        return format!("{} No tickets found", get_icon("summary"));
+        return with_icon(get_icon("summary"), t("list.empty"));
     }
+    if is_plain() {
+        return format_ticket_list_plain(tickets, checklists);
+    }
+    let show_checklists = tickets.iter().any(|t| checklists.contains_key(&t.id));
+    let show_kinds = tickets.iter().any(|t| t.kind.is_some());
+    let show_severities = tickets.iter().any(|t| t.severity.is_some());
+    let show_components = tickets.iter().any(|t| t.component.is_some());
     
-    let rows: Vec<TicketRow> = tickets
-        .iter()
-        .map(|ticket| TicketRow {
-            id: ticket.id.to_string(),
-            project: truncate_text(&ticket.project, 15),
-            name: truncate_text(&ticket.name, 25),
-            status: get_status_display(&ticket.status),
-            updated: format_timestamp(&ticket.updated_at),
-        })
-        .collect();
+    let mut builder = Builder::default();
+    let mut header = vec![
+        t("list.header.id"),
+        t("list.header.project"),
+        t("list.header.name"),
+        t("list.header.status"),
+        t("list.header.updated"),
+    ];
+    if show_kinds {
+        header.insert(3, t("list.header.type"));
+    }
+    if show_components {
+        header.insert(2, t("list.header.component"));
+    }
+    if show_severities {
+        header.insert(header.len() - 1, t("list.header.severity"));
+    }
+    if show_checklists {
+        header.push(t("list.header.checklist"));
+    }
+    builder.set_header(header);
+    for ticket in tickets {
+        let mut record = vec![
+            ticket.id.to_string(),
+            truncate_text(&ticket.project, 15),
+            truncate_text(&pinned_name(ticket), 25),
+            get_status_display(&ticket.status),
+            format_timestamp(&ticket.updated_at),
+        ];
+        if show_kinds {
+            record.insert(3, ticket.kind.as_deref().map(get_kind_display).unwrap_or_default());
+        }
+        if show_components {
+            record.insert(2, truncate_text(ticket.component.as_deref().unwrap_or_default(), 15));
+        }
+        if show_severities {
+            record.insert(record.len() - 1, ticket.severity.as_deref().map(get_severity_display).unwrap_or_default());
+        }
+        if show_checklists {
+            record.push(checklists.get(&ticket.id).map(|(done, total)| format!("{}/{}", done, total)).unwrap_or_default());
+        }
+        builder.push_record(record);
+    }
     
-    let mut table = Table::new(rows);
+    let mut table = builder.build();
     table.with(Style::rounded());
     
     let table_str = table.to_string();
     
     // Add summary
-    let total = tickets.len();
-    let closed = tickets.iter().filter(|t| {
-        matches!(t.status.to_lowercase().as_str(), "closed" | "completed" | "done")
-    }).count();
-    let open = total - closed;
+    let (total, open, closed) = ticket_counts(tickets);
     
-    let summary = if use_colors() {
-        format!(
-            "{} Summary: {} tickets ({} open, {} closed)",
-            get_icon("summary"),
-            total.to_string().bold(),
-            open.to_string().red(),
-            closed.to_string().green()
-        )
+    let counts = if use_colors() {
+        tf("list.summary", &[&total.to_string().bold(), &open.to_string().red(), &closed.to_string().green()])
     } else {
-        format!(
-            "{} Summary: {} tickets ({} open, {} closed)",
-            get_icon("summary"),
-            total,
-            open,
-            closed
-        )
+        tf("list.summary", &[&total, &open, &closed])
     };
+    let summary = with_icon(get_icon("summary"), counts);
     
     format!("{}\n{}", table_str, summary)
 }
 
+/// Plain ticket list: one labelled line per ticket
+fn format_ticket_list_plain(tickets: &[Ticket], checklists: &HashMap<i64, (i64, i64)>) -> String {
+    let mut output = String::new();
+    for ticket in tickets {
+        output.push_str(&format!(
+            "{}: {}. {}. {}. {}",
+            tf("details.ticket", &[&ticket.id]),
+            ticket.name,
+            tf("details.project", &[&ticket.project]),
+            tf("details.status", &[&ticket.status]),
+            tf("list.updated", &[&format_timestamp(&ticket.updated_at)])
+        ));
+        if let Some(component) = &ticket.component {
+            output.push_str(&format!(". {}", tf("details.component", &[component])));
+        }
+        if let Some(kind) = &ticket.kind {
+            output.push_str(&format!(". {}", tf("details.type", &[kind])));
+        }
+        if let Some(severity) = &ticket.severity {
+            output.push_str(&format!(". {}", tf("details.severity", &[severity])));
+        }
+        if let Some((done, total)) = checklists.get(&ticket.id) {
+            output.push_str(&format!(". {}", tf("list.checklist", &[&tf("checklist.progress", &[done, total])])));
+        }
+        if ticket.pinned_at.is_some() {
+            output.push_str(&format!(". {}", t("list.pinned")));
+        }
+        output.push('\n');
+    }
+    let (total, open, closed) = ticket_counts(tickets);
+    output.push_str(&tf("list.summary", &[&total, &open, &closed]));
+    output
+}
+
+/// Format the comments that matched a search, with an excerpt around each match
+pub fn format_comment_matches(matches: &[(&Comment, Range<usize>)]) -> String {
+    let mut lines = vec![with_icon(get_icon("comments"), tf("list.comment_matches", &[&matches.len()]))];
+    for (comment, range) in matches {
+        lines.push(format!(
+            "  #{} {}: {}",
+            comment.ticket_id,
+            format_timestamp(&comment.created_at),
+            snippet(&comment.content, range.clone(), 30)
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Format tickets ranked by similarity, with their score as a percentage
+pub fn format_related_tickets(related: &[(&Ticket, f64)]) -> String {
+    if related.is_empty() {
+        return with_icon(get_icon("summary"), t("related.empty"));
+    }
+    if is_plain() {
+        return format_related_tickets_plain(related);
+    }
+
+    let mut builder = Builder::default();
+    builder.set_header([
+        t("list.header.id"),
+        t("list.header.project"),
+        t("list.header.name"),
+        t("list.header.status"),
+        t("list.header.match"),
+    ]);
+    for (ticket, score) in related {
+        builder.push_record([
+            ticket.id.to_string(),
+            truncate_text(&ticket.project, 15),
+            truncate_text(&ticket.name, 25),
+            get_status_display(&ticket.status),
+            format!("{:.0}%", score * 100.0),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    table.to_string()
+}
+
+/// Plain related tickets: one labelled line per ticket
+fn format_related_tickets_plain(related: &[(&Ticket, f64)]) -> String {
+    related
+        .iter()
+        .map(|(ticket, score)| {
+            format!(
+                "{}: {}. {}. {}. {}",
+                tf("details.ticket", &[&ticket.id]),
+                ticket.name,
+                tf("details.project", &[&ticket.project]),
+                tf("details.status", &[&ticket.status]),
+                tf("list.match", &[&format!("{:.0}", score * 100.0)])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Checklist progress such as "3/7 done"
+fn checklist_progress(items: &[ChecklistItem]) -> String {
+    let done = items.iter().filter(|item| item.done).count();
+    tf("checklist.progress", &[&done, &items.len()])
+}
+
+/// Format a ticket's checklist: a progress heading, then `[x]`/`[ ]` items by number
+pub fn format_checklist(items: &[ChecklistItem]) -> String {
+    if items.is_empty() {
+        return with_icon(get_icon("checklist"), t("checklist.empty"));
+    }
+    if is_plain() {
+        return format_checklist_plain(items);
+    }
+    let mut lines = vec![with_icon(get_icon("checklist"), tf("checklist.title", &[&checklist_progress(items)]))];
+    for item in items {
+        let mark = if item.done { "[x]" } else { "[ ]" };
+        let line = format!("  {} {}. {}", mark, item.position, item.text);
+        if item.done && use_colors() {
+            lines.push(line.dimmed().to_string());
+        } else {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Plain checklist: each item says whether it is done
+fn format_checklist_plain(items: &[ChecklistItem]) -> String {
+    let mut lines = vec![tf("checklist.title", &[&checklist_progress(items)])];
+    for item in items {
+        let id = if item.done { "checklist.item_done" } else { "checklist.item_todo" };
+        lines.push(tf(id, &[&item.position, &item.text]));
+    }
+    lines.join("\n")
+}
+
+/// A link as shown in ticket details: `label: url`, or just the URL
+fn link_text(link: &TicketUrl) -> String {
+    match link.label.as_deref().filter(|l| !l.trim().is_empty()) {
+        Some(label) => format!("{}: {}", label, link.url),
+        None => link.url.clone(),
+    }
+}
+
 /// Format ticket details in a structured box
-pub fn format_ticket_details(ticket: &Ticket, comments: &[Comment], _time_logs: &[TimeLog]) -> String {
+pub fn format_ticket_details(
+    ticket: &Ticket,
+    comments: &[Comment],
+    _time_logs: &[TimeLog],
+    links: &[TicketUrl],
+    checklist: &[ChecklistItem],
+    tags: &[String],
+) -> String {
+    if is_plain() {
+        return format_ticket_details_plain(ticket, comments, links, checklist, tags);
+    }
     let mut output = String::new();
     
     // Main ticket box
-    let title_line = format!("{} {}", get_icon("title"), ticket.name);
-    let project_line = format!("{} Project: {}", get_icon("project"), ticket.project);
-    let status_line = format!("{} Status: {}", get_icon("status"), colorize_status(&ticket.status));
-    let created_line = format!("{} Created: {}", get_icon("created"), format_timestamp(&ticket.created_at));
+    let title_line = with_icon(get_icon("title"), &ticket.name);
+    let project_line = with_icon(get_icon("project"), tf("details.project", &[&ticket.project]));
+    let status_line = with_icon(get_icon("status"), tf("details.status", &[&colorize_status(&ticket.status)]));
+    let created_line = with_icon(get_icon("created"), tf("details.created", &[&format_timestamp(&ticket.created_at)]));
+    let due_line = ticket.due_date.map(|due| {
+        let due = format_due_date(due, Local::now().date_naive());
+        with_icon(get_icon("due"), tf("details.due", &[&due]))
+    });
+    let extra_lines: Vec<String> = due_line
+        .into_iter()
+        .chain(metadata_lines(ticket, tags).into_iter().map(|(icon, text)| with_icon(get_icon(icon), text)))
+        .collect();
     
     // Calculate box width based on content
-    let content_lines = vec![&title_line, &project_line, &created_line];
+    let mut content_lines = vec![&title_line, &project_line, &created_line];
+    content_lines.extend(&extra_lines);
     let max_width = content_lines
         .iter()
         .map(|line| line.width())
@@ -189,34 +549,62 @@ pub fn format_ticket_details(ticket: &Ticket, comments: &[Comment], _time_logs:
         .max(50);
     
     // Top border
-    output.push_str(&format!("╭─ Ticket #{} {}\n", ticket.id, "─".repeat(max_width.saturating_sub(15))));
+    output.push_str(&format!("╭─ {} {}\n", tf("details.ticket", &[&ticket.id]), "─".repeat(max_width.saturating_sub(15))));
     output.push_str(&format!("│ {} {}\n", title_line, " ".repeat(max_width.saturating_sub(title_line.width() + 2))));
     output.push_str(&format!("│ {} {}\n", project_line, " ".repeat(max_width.saturating_sub(project_line.width() + 2))));
     output.push_str(&format!("│ {} {}\n", status_line, " ".repeat(max_width.saturating_sub(status_line.width() + colorize_status(&ticket.status).to_string().len() - status_line.len() + 2))));
     output.push_str(&format!("│ {} {}\n", created_line, " ".repeat(max_width.saturating_sub(created_line.width() + 2))));
+    for line in &extra_lines {
+        output.push_str(&format!("│ {} {}\n", line, " ".repeat(max_width.saturating_sub(line.width() + 2))));
+    }
     output.push_str(&format!("╰{}\n", "─".repeat(max_width + 1)));
     
     // Description
     if !ticket.description.trim().is_empty() {
         output.push('\n');
-        output.push_str("Description:\n");
+        output.push_str(t("details.description"));
+        output.push('\n');
         output.push_str(&ticket.description);
         output.push('\n');
     }
+
+    // Links, numbered for `ltm open-url <id> <n>`
+    if !links.is_empty() {
+        output.push('\n');
+        output.push_str(&with_icon(get_icon("links"), tf("details.links", &[&links.len()])));
+        output.push('\n');
+        for (i, link) in links.iter().enumerate() {
+            let text = link_text(link);
+            if use_colors() {
+                output.push_str(&format!("  {}. {}\n", i + 1, text.underline()));
+            } else {
+                output.push_str(&format!("  {}. {}\n", i + 1, text));
+            }
+        }
+    }
+
+    if !checklist.is_empty() {
+        output.push('\n');
+        output.push_str(&format_checklist(checklist));
+        output.push('\n');
+    }
     
     // Comments
     if !comments.is_empty() {
         output.push('\n');
-        output.push_str(&format!("{} Comments ({}):\n", get_icon("comments"), comments.len()));
+        output.push_str(&with_icon(get_icon("comments"), tf("details.comments", &[&comments.len()])));
+        output.push('\n');
         
-        for comment in comments {
+        // Replies sit under the comment they answer, indented one step per level
+        for (comment, depth) in thread_comments(comments) {
+            let indent = "    ".repeat(depth);
             let timestamp = format_timestamp(&comment.created_at);
-            output.push_str(&format!("┌─ {} {}\n", timestamp, "─".repeat(40usize.saturating_sub(timestamp.len()))));
+            output.push_str(&format!("{}┌─ {} {}\n", indent, timestamp, "─".repeat(40usize.saturating_sub(timestamp.len()))));
             
             // Word wrap comment content
             for line in comment.content.lines() {
                 if line.trim().is_empty() {
-                    output.push_str("│\n");
+                    output.push_str(&format!("{}│\n", indent));
                 } else {
                     // Simple word wrapping
                     let words: Vec<&str> = line.split_whitespace().collect();
@@ -225,44 +613,97 @@ pub fn format_ticket_details(ticket: &Ticket, comments: &[Comment], _time_logs:
                     for word in words {
                         if current_line.is_empty() {
                             current_line = word.to_string();
-                        } else if current_line.len() + word.len() + 1 <= 60 {
+                        } else if current_line.len() + word.len() < 60 {
                             current_line.push(' ');
                             current_line.push_str(word);
                         } else {
-                            output.push_str(&format!("│ {}\n", current_line));
+                            output.push_str(&format!("{}│ {}\n", indent, current_line));
                             current_line = word.to_string();
                         }
                     }
                     
                     if !current_line.is_empty() {
-                        output.push_str(&format!("│ {}\n", current_line));
+                        output.push_str(&format!("{}│ {}\n", indent, current_line));
                     }
                 }
             }
             
-            output.push_str("└─────────────────────────────────────────\n");
+            output.push_str(&format!("{}└─────────────────────────────────────────\n", indent));
         }
     }
     
     output
 }
 
+/// Plain ticket details: labelled lines without boxes or icons
+fn format_ticket_details_plain(ticket: &Ticket, comments: &[Comment], links: &[TicketUrl], checklist: &[ChecklistItem], tags: &[String]) -> String {
+    let mut lines = vec![
+        format!("{}: {}", tf("details.ticket", &[&ticket.id]), ticket.name),
+        tf("details.project", &[&ticket.project]),
+        tf("details.status", &[&ticket.status]),
+        tf("details.created", &[&format_timestamp(&ticket.created_at)]),
+    ];
+    if let Some(due) = ticket.due_date {
+        lines.push(tf("details.due", &[&format_due_date(due, Local::now().date_naive())]));
+    }
+    lines.extend(metadata_lines(ticket, tags).into_iter().map(|(_, text)| text));
+    if !ticket.description.trim().is_empty() {
+        lines.push(String::new());
+        lines.push(t("details.description").to_string());
+        lines.push(ticket.description.clone());
+    }
+    if !links.is_empty() {
+        lines.push(String::new());
+        lines.push(tf("details.links", &[&links.len()]));
+        for (i, link) in links.iter().enumerate() {
+            lines.push(tf("details.link", &[&(i + 1), &link_text(link)]));
+        }
+    }
+    if !checklist.is_empty() {
+        lines.push(String::new());
+        lines.push(format_checklist_plain(checklist));
+    }
+    if !comments.is_empty() {
+        lines.push(String::new());
+        lines.push(tf("details.comments", &[&comments.len()]));
+        // Replies name the comment they answer instead of relying on indentation
+        let threaded = thread_comments(comments);
+        for (i, (comment, depth)) in threaded.iter().enumerate() {
+            let timestamp = format_timestamp(&comment.created_at);
+            let parent = comment
+                .parent_comment_id
+                .filter(|_| *depth > 0)
+                .and_then(|parent| threaded.iter().position(|(c, _)| c.id == parent));
+            match parent {
+                Some(parent) => lines.push(tf("details.reply", &[&(i + 1), &comments.len(), &(parent + 1), &timestamp])),
+                None => lines.push(tf("details.comment", &[&(i + 1), &comments.len(), &timestamp])),
+            }
+            lines.push(comment.content.clone());
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
 /// Format project summary with visual indicators
 pub fn format_project_summary(project: &str, summary: &ProjectSummary) -> String {
+    if is_plain() {
+        return format_project_summary_plain(project, summary);
+    }
     let mut output = String::new();
     
     // Title with icon
     if use_colors() {
-        output.push_str(&format!("📊 Project Summary for {}\n\n", project.bold()));
+        output.push_str(&with_icon("📊", tf("summary.title", &[&project.bold()])));
     } else {
-        output.push_str(&format!("📊 Project Summary for {}\n\n", project));
+        output.push_str(&with_icon("📊", tf("summary.title", &[&project])));
     }
+    output.push_str("\n\n");
     
     // Stats with icons and colors
-    let total_line = format!("📋 Total Tickets: {}", summary.total_tickets);
-    let open_line = format!("● Open Tickets: {}", summary.open_tickets);
-    let closed_line = format!("✓ Closed Tickets: {}", summary.closed_tickets);
-    let time_line = format!("⏱️  Total Time: {:.2} hours", summary.total_time_hours);
+    let total_line = with_icon("📋", tf("summary.total", &[&summary.total_tickets]));
+    let open_line = format!("● {}", tf("summary.open", &[&summary.open_tickets]));
+    let closed_line = format!("✓ {}", tf("summary.closed", &[&summary.closed_tickets]));
+    let time_line = format!("{}{}", icon("⏱️  "), tf("summary.time", &[&format!("{:.2}", summary.total_time_hours)]));
     
     if use_colors() {
         output.push_str(&format!("{}\n", total_line.bold()));
@@ -275,119 +716,1974 @@ pub fn format_project_summary(project: &str, summary: &ProjectSummary) -> String
         output.push_str(&format!("{}\n", closed_line));
         output.push_str(&format!("{}\n", time_line));
     }
+    if !summary.kinds.is_empty() {
+        output.push_str(&format!("{}\n", with_icon(get_icon("type"), format_kind_counts(&summary.kinds))));
+    }
+    if !summary.components.is_empty() {
+        output.push_str(&format!("{}\n", with_icon(get_icon("component"), format_component_counts(&summary.components))));
+    }
     
     // Progress indicator
     if summary.total_tickets > 0 {
         let progress = (summary.closed_tickets as f64 / summary.total_tickets as f64 * 100.0) as u8;
-        let filled = (progress as usize * 20 / 100).min(20);
-        let empty = 20 - filled;
-        
-        let progress_bar = format!("{}{}",
-            "█".repeat(filled),
-            "░".repeat(empty)
-        );
+        let progress_bar = progress_bar(progress);
         
         output.push('\n');
         if use_colors() {
-            output.push_str(&format!("Progress: [{}] {}%\n", 
-                progress_bar.green(), 
-                progress.to_string().bold()
-            ));
+            output.push_str(&tf("summary.progress", &[&progress_bar.green(), &progress.to_string().bold()]));
         } else {
-            output.push_str(&format!("Progress: [{}] {}%\n", progress_bar, progress));
+            output.push_str(&tf("summary.progress", &[&progress_bar, &progress]));
         }
+        output.push('\n');
     }
     
     output
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::DateTime;
-    
-    fn create_test_ticket() -> Ticket {
-        let timestamp = DateTime::from_timestamp(1642694400, 0).unwrap().naive_utc();
-        Ticket {
-            id: 1,
-            project: "test_project".to_string(),
-            name: "Test ticket".to_string(),
-            description: "A test description".to_string(),
-            status: "open".to_string(),
-            created_at: timestamp,
-            updated_at: timestamp,
-        }
+/// A 20-character bar filled to `percent`
+fn progress_bar(percent: u8) -> String {
+    let filled = (percent as usize * 20 / 100).min(20);
+    format!("{}{}", "█".repeat(filled), "░".repeat(20 - filled))
+}
+
+/// Format a release: its progress, then the open tickets still planned for it
+pub fn format_release(project: &str, release: &str, tickets: &[Ticket]) -> String {
+    let open: Vec<&Ticket> = tickets.iter().filter(|t| !is_finished_status(&t.status)).collect();
+    let done = tickets.len() - open.len();
+    let percent = if tickets.is_empty() { 0 } else { (done * 100 / tickets.len()) as u8 };
+    let title = if use_colors() { tf("release.title", &[&release.bold(), &project]) } else { tf("release.title", &[&release, &project]) };
+    let mut lines = vec![with_icon(get_icon("release"), title)];
+    if tickets.is_empty() {
+        lines.push(t("release.empty").to_string());
+        return lines.join("\n");
     }
-    
-    #[test]
-    fn test_status_symbols() {
-        assert_eq!(get_status_symbol("open"), "●");
-        assert_eq!(get_status_symbol("closed"), "✓");
-        assert_eq!(get_status_symbol("in-progress"), "⚠");
-        assert_eq!(get_status_symbol("wontfix"), "⊘");
-        assert_eq!(get_status_symbol("unknown"), "○");
+    lines.push(if is_plain() {
+        tf("release.progress_percent", &[&percent, &done, &tickets.len()])
+    } else if use_colors() {
+        tf("release.progress", &[&progress_bar(percent).green(), &percent, &done, &tickets.len()])
+    } else {
+        tf("release.progress", &[&progress_bar(percent), &percent, &done, &tickets.len()])
+    });
+    if open.is_empty() {
+        lines.push(t("release.complete").to_string());
+        return lines.join("\n");
     }
-    
-    #[test]
-    fn test_colorize_status() {
-        let open_status = colorize_status("open");
-        assert!(open_status.to_string().contains("● open"));
-        
-        let closed_status = colorize_status("closed");
-        assert!(closed_status.to_string().contains("✓ closed"));
+    lines.push(tf("release.remaining", &[&open.len()]));
+    for ticket in open {
+        if is_plain() {
+            lines.push(format!("{}: {}. {}", tf("details.ticket", &[&ticket.id]), ticket.name, tf("details.status", &[&ticket.status])));
+        } else {
+            lines.push(format!("  #{} {} {}", ticket.id, truncate_text(&ticket.name, 40), colorize_status(&ticket.status)));
+        }
     }
-    
-    #[test]
-    fn test_table_formatting() {
-        let tickets = vec![create_test_ticket()];
-        let output = format_ticket_list(&tickets);
-        assert!(output.contains("╭────┬"));
-        assert!(output.contains("📊 Summary:"));
-        assert!(output.contains("tickets"));
-        assert!(output.contains("open"));
-        assert!(output.contains("closed"));
+    lines.join("\n")
+}
+
+/// Symbol, color, and label key for how a release on the roadmap is doing
+fn risk_display(risk: ReleaseRisk) -> (&'static str, Color, &'static str) {
+    match risk {
+        ReleaseRisk::Done => ("✓", Color::Green, "roadmap.done"),
+        ReleaseRisk::OnTrack => ("●", Color::Cyan, "roadmap.on_track"),
+        ReleaseRisk::AtRisk => ("⚠", Color::Yellow, "roadmap.at_risk"),
+        ReleaseRisk::Overdue => ("✗", Color::Red, "roadmap.overdue"),
     }
-    
-    #[test]
-    fn test_ticket_details_formatting() {
-        let ticket = create_test_ticket();
-        let comments = vec![];
-        let time_logs = vec![];
-        let output = format_ticket_details(&ticket, &comments, &time_logs);
-        
-        assert!(output.contains("╭─ Ticket #1"));
-        assert!(output.contains("📋 Test ticket"));
-        assert!(output.contains("🏷️ Project: test_project"));
-        assert!(output.contains("📊 Status:"));
+}
+
+/// Format the roadmap: one line per release in due date order, with its progress, the
+/// work left against the time until its due date, and a marker for releases at risk
+pub fn format_roadmap(project: Option<&str>, items: &[RoadmapItem]) -> String {
+    let title = match project {
+        Some(project) => tf("roadmap.title_project", &[&project]),
+        None => t("roadmap.title").to_string(),
+    };
+    let mut lines = vec![with_icon(get_icon("release"), title)];
+    if items.is_empty() {
+        lines.push(t("roadmap.empty").to_string());
+        return lines.join("\n");
     }
-    
-    #[test]
-    fn test_project_summary_formatting() {
-        let summary = ProjectSummary {
-            project: "test_project".to_string(),
-            total_tickets: 10,
-            open_tickets: 3,
-            closed_tickets: 7,
-            total_time_hours: 25.5,
-        };
-        
-        let output = format_project_summary("test_project", &summary);
-        assert!(output.contains("📊 Project Summary"));
-        assert!(output.contains("📋 Total Tickets: 10"));
-        assert!(output.contains("● Open Tickets: 3"));
-        assert!(output.contains("✓ Closed Tickets: 7"));
-        assert!(output.contains("Progress:"));
+    let label = |item: &RoadmapItem| format!("{}/{}", item.project, item.release);
+    let width = items.iter().map(|item| label(item).width()).max().unwrap_or(0);
+    for item in items {
+        let due = item.due_date.map(|day| day.format("%Y-%m-%d").to_string());
+        let (symbol, color, risk) = risk_display(item.risk);
+        let mut details = vec![t(risk).to_string()];
+        // Estimates count working hours, so show them as hours rather than days
+        if item.remaining_minutes > 0 || (item.done < item.total && item.unestimated == 0) {
+            let remaining = format_estimate(item.remaining_minutes);
+            details.push(match item.available_minutes.filter(|_| item.risk != ReleaseRisk::Overdue) {
+                Some(available) => tf("roadmap.work_available", &[&remaining, &format_estimate(available)]),
+                None => tf("roadmap.work", &[&remaining]),
+            });
+        }
+        if item.unestimated > 0 {
+            details.push(tf("roadmap.unestimated", &[&item.unestimated]));
+        }
+        if item.late_tickets > 0 {
+            details.push(tf("roadmap.late", &[&item.late_tickets]));
+        }
+
+        if is_plain() {
+            let due = due.map(|day| tf("roadmap.due", &[&day])).unwrap_or_else(|| t("roadmap.no_date").to_string());
+            lines.push(tf("roadmap.plain", &[&label(item), &due, &item.percent(), &item.done, &item.total, &details.join(", ")]));
+            continue;
+        }
+        let due = format!("{:<10}", due.unwrap_or_else(|| t("roadmap.no_date").to_string()));
+        let bar = progress_bar(item.percent() as u8);
+        let progress = format!("{:>3}% ({}/{})", item.percent(), item.done, item.total);
+        let name = format!("{:<width$}", label(item), width = width);
+        let details = details.join(", ");
+        lines.push(if use_colors() {
+            format!("  {}  {} {}  [{}] {}  {}", due.dimmed(), symbol.color(color), name.bold(), bar.green(), progress, details.color(color))
+        } else {
+            format!("  {}  {} {}  [{}] {}  {}", due, symbol, name, bar, progress, details)
+        });
     }
-    
-    #[test]
-    fn test_no_color_mode() {
-        env::set_var("NO_COLOR", "1");
-        let output = colorize_status("open");
-        // Should not contain ANSI escape codes
-        assert!(!output.to_string().contains("\x1b["));
-        env::remove_var("NO_COLOR");
+    lines.join("\n")
+}
+
+/// A number for the score report without trailing zeros, like "2" or "0.25"
+fn format_score_input(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// A computed score, with two decimals while it is small enough for ICE scores to need them
+fn format_score(score: f64) -> String {
+    if score < 10.0 { format!("{:.2}", score) } else { format!("{:.1}", score) }
+}
+
+/// Rank backlog tickets by score, highest first, then name the tickets still missing inputs
+pub fn format_scores(method: ScoringMethod, scored: &[(&Ticket, f64)], unscored: &[(&Ticket, Vec<&str>)]) -> String {
+    let title = match method {
+        ScoringMethod::Rice => t("score.title_rice"),
+        ScoringMethod::Ice => t("score.title_ice"),
+    };
+    let mut output = with_icon(get_icon("score"), title);
+    let inputs = |ticket: &Ticket| {
+        let mut inputs = Vec::new();
+        if method == ScoringMethod::Rice {
+            inputs.push(ticket.reach.map(|r| r.to_string()).unwrap_or_default());
+        }
+        inputs.push(ticket.impact.map(format_score_input).unwrap_or_default());
+        inputs.push(ticket.confidence.map(|c| format!("{}%", c)).unwrap_or_default());
+        inputs.push(ticket.effort.map(format_score_input).unwrap_or_default());
+        inputs
+    };
+    if scored.is_empty() {
+        output.push('\n');
+        output.push_str(t("score.empty"));
+    } else if is_plain() {
+        for (rank, (ticket, score)) in scored.iter().enumerate() {
+            let values: Vec<String> = method.fields().iter().zip(inputs(ticket)).map(|(field, value)| format!("{} {}", field, value)).collect();
+            output.push_str(&format!("\n{}", tf("score.plain", &[&(rank + 1), &ticket.id, &ticket.name, &ticket.project, &format_score(*score), &values.join(", ")])));
+        }
+    } else {
+        let mut builder = Builder::default();
+        let mut header = vec![t("score.header.rank"), t("list.header.id"), t("list.header.project"), t("list.header.name")];
+        if method == ScoringMethod::Rice {
+            header.push(t("score.header.reach"));
+        }
+        header.extend([t("score.header.impact"), t("score.header.confidence"), t("score.header.effort"), t("score.header.score")]);
+        builder.set_header(header);
+        for (rank, (ticket, score)) in scored.iter().enumerate() {
+            let mut record = vec![(rank + 1).to_string(), ticket.id.to_string(), ticket.project.clone(), truncate_text(&ticket.name, 40)];
+            record.extend(inputs(ticket));
+            record.push(format_score(*score));
+            builder.push_record(record);
+        }
+        let mut table = builder.build();
+        table.with(Style::rounded());
+        output.push('\n');
+        output.push_str(&table.to_string());
     }
-    
+    if !unscored.is_empty() {
+        output.push_str(&format!("\n\n{}", tf("score.unscored", &[&unscored.len()])));
+        for (ticket, missing) in unscored {
+            output.push_str(&format!("\n  #{} {} ({})", ticket.id, truncate_text(&ticket.name, 40), tf("score.missing", &[&missing.join(", ")])));
+        }
+    }
+    output
+}
+
+/// Columns of the timeline in the Gantt chart
+const GANTT_WIDTH: usize = 48;
+
+/// A Gantt chart of a release's tickets, each a bar from the day it was created to its due
+/// date, with today and the release's due date marked above the bars
+pub fn format_gantt(release: &Release, tickets: &[Ticket], today: NaiveDate) -> String {
+    let title = if use_colors() { tf("gantt.title", &[&release.name.bold(), &release.project]) } else { tf("gantt.title", &[&release.name, &release.project]) };
+    let mut lines = vec![with_icon(get_icon("release"), title)];
+    if tickets.is_empty() {
+        lines.push(t("release.empty").to_string());
+        return lines.join("\n");
+    }
+    let spans: Vec<(NaiveDate, Option<NaiveDate>)> = tickets.iter().map(|t| gantt_span(t, release.due_date)).collect();
+    if is_plain() {
+        for (ticket, (start, end)) in tickets.iter().zip(&spans) {
+            let end = end.map_or_else(|| t("gantt.no_due").to_string(), |day| day.format("%Y-%m-%d").to_string());
+            lines.push(tf("gantt.plain", &[&ticket.id, &ticket.name, &start.format("%Y-%m-%d"), &end, &ticket.status]));
+        }
+        return lines.join("\n");
+    }
+
+    let first = spans.iter().flat_map(|(start, end)| [Some(*start), *end]).flatten().chain([today]).min().unwrap_or(today);
+    let last = spans.iter().filter_map(|(_, end)| *end).chain(release.due_date).chain([today]).max().unwrap_or(today);
+    let days = (last - first).num_days().max(1);
+    let column = |day: NaiveDate| ((day - first).num_days() * (GANTT_WIDTH as i64 - 1) / days) as usize;
+    let labels: Vec<String> = tickets.iter().map(|t| truncate_text(&format!("#{} {}", t.id, t.name), 30)).collect();
+    let label_width = labels.iter().map(|l| l.width()).max().unwrap_or(0);
+
+    let first_label = first.format("%Y-%m-%d").to_string();
+    lines.push(format!("{:label_width$}  {}{:>width$}", "", first_label, last.format("%Y-%m-%d"), width = GANTT_WIDTH - first_label.len()));
+    let mut markers = [' '; GANTT_WIDTH];
+    if let Some(due) = release.due_date {
+        markers[column(due)] = '◆';
+    }
+    markers[column(today)] = '▼';
+    lines.push(format!("{:label_width$}  {}", "", markers.iter().collect::<String>().trim_end()));
+
+    for ((ticket, (start, end)), label) in tickets.iter().zip(&spans).zip(&labels) {
+        let finished = is_finished_status(&ticket.status);
+        // Open-ended bars run up to today; ones due before they were created still span the gap
+        let until = end.unwrap_or(today);
+        let (from, to) = (column(until.min(*start)), column(until.max(*start)));
+        let (bar, color) = match end {
+            _ if finished => ("█", Color::Green),
+            Some(end) if *end < today => ("▓", Color::Red),
+            Some(_) => ("▓", Color::Cyan),
+            None => ("░", Color::Yellow),
+        };
+        let bar = bar.repeat(to - from + 1);
+        // The today line runs through the space around each bar
+        let gap = |range: std::ops::Range<usize>| range.map(|c| if c == column(today) { '│' } else { ' ' }).collect::<String>();
+        let bar = if use_colors() { bar.color(color).to_string() } else { bar };
+        let row = format!("{}{}{}", gap(0..from), bar, gap(to + 1..GANTT_WIDTH).trim_end());
+        let padding = " ".repeat(label_width - label.width());
+        lines.push(format!("{}{}  {}", label, padding, row));
+    }
+    lines.push(String::new());
+    lines.push(t("gantt.legend").to_string());
+    lines.join("\n")
+}
+
+/// A release's tickets as a Mermaid Gantt chart for embedding in docs; the release's due
+/// date becomes a milestone
+pub fn format_gantt_mermaid(release: &Release, tickets: &[Ticket], today: NaiveDate) -> String {
+    // Mermaid reads ":" and ";" in a task name as syntax and "#" as the start of a comment
+    let clean = |text: &str| text.replace([':', ';', '#'], " ");
+    let mut lines = vec![
+        "gantt".to_string(),
+        format!("    title {} {}", clean(&release.project), clean(&release.name)),
+        "    dateFormat YYYY-MM-DD".to_string(),
+        format!("    section {}", clean(&release.name)),
+    ];
+    for ticket in tickets {
+        let (start, end) = gantt_span(ticket, release.due_date);
+        let tag = if is_finished_status(&ticket.status) {
+            "done, "
+        } else if end.is_some_and(|end| end < today) {
+            "crit, "
+        } else {
+            ""
+        };
+        // Mermaid ends a task on the day before its end date, so add one to cover the due date
+        let end = end.map_or_else(|| "1d".to_string(), |end| (end.max(start) + Duration::days(1)).format("%Y-%m-%d").to_string());
+        lines.push(format!("    {} {} :{}t{}, {}, {}", ticket.id, clean(&ticket.name), tag, ticket.id, start.format("%Y-%m-%d"), end));
+    }
+    if let Some(due) = release.due_date {
+        lines.push(format!("    {} :milestone, release, {}, 0d", clean(&release.name), due.format("%Y-%m-%d")));
+    }
+    lines.join("\n")
+}
+
+/// A string made safe to put inside a double-quoted DOT label
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The dependency graph in Graphviz DOT: a node per ticket filled by its status, and an
+/// edge from each blocker to the ticket waiting on it, dashed once the blocker is finished.
+/// Dependencies with an end outside `tickets` are left out.
+pub fn format_dependency_graph(tickets: &[Ticket], dependencies: &[TicketDependency]) -> String {
+    let several_projects = tickets.iter().any(|t| t.project != tickets[0].project);
+    let mut lines = vec![
+        "digraph tickets {".to_string(),
+        "  rankdir=LR;".to_string(),
+        "  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];".to_string(),
+    ];
+    for ticket in tickets {
+        let mut label = vec![dot_escape(&format!("#{} {}", ticket.id, truncate_text(&ticket.name, 40)))];
+        if several_projects {
+            label.push(dot_escape(&format!("[{}]", ticket.project)));
+        }
+        label.push(dot_escape(&format!("({})", ticket.status)));
+        let color = if is_finished_status(&ticket.status) {
+            "palegreen"
+        } else {
+            STATUS_NODE_COLORS.iter().find(|(s, _)| s.eq_ignore_ascii_case(&ticket.status)).map_or("lightgray", |(_, color)| *color)
+        };
+        lines.push(format!("  t{} [label=\"{}\", fillcolor=\"{}\"];", ticket.id, label.join("\\n"), color));
+    }
+    let status = |id: i64| tickets.iter().find(|t| t.id == id).map(|t| t.status.as_str());
+    for dependency in dependencies {
+        let (Some(blocker_status), Some(_)) = (status(dependency.blocker_id), status(dependency.ticket_id)) else {
+            continue;
+        };
+        let style = if is_finished_status(blocker_status) { " [style=dashed]" } else { "" };
+        lines.push(format!("  t{} -> t{}{};", dependency.blocker_id, dependency.ticket_id, style));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// Ticket counts by type, like "By type: 🐛 bug 4, ✨ feature 2"
+pub fn format_kind_counts(kinds: &BTreeMap<String, i64>) -> String {
+    let kinds: Vec<String> = kinds.iter().map(|(kind, count)| format!("{} {}", get_kind_display(kind), count)).collect();
+    tf("summary.kinds", &[&kinds.join(", ")])
+}
+
+/// Ticket counts by component, like "By component: api 3, frontend 5"
+pub fn format_component_counts(components: &BTreeMap<String, i64>) -> String {
+    let components: Vec<String> = components.iter().map(|(name, count)| format!("{} {}", name, count)).collect();
+    tf("summary.components", &[&components.join(", ")])
+}
+
+/// Plain project summary: labelled lines with the progress as a percentage
+fn format_project_summary_plain(project: &str, summary: &ProjectSummary) -> String {
+    let mut lines = vec![
+        tf("summary.title", &[&project]),
+        tf("summary.total", &[&summary.total_tickets]),
+        tf("summary.open", &[&summary.open_tickets]),
+        tf("summary.closed", &[&summary.closed_tickets]),
+        tf("summary.time", &[&format!("{:.2}", summary.total_time_hours)]),
+    ];
+    if !summary.kinds.is_empty() {
+        lines.push(format_kind_counts(&summary.kinds));
+    }
+    if !summary.components.is_empty() {
+        lines.push(format_component_counts(&summary.components));
+    }
+    if summary.total_tickets > 0 {
+        let progress = (summary.closed_tickets as f64 / summary.total_tickets as f64 * 100.0) as u8;
+        lines.push(tf("summary.progress_percent", &[&progress]));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Format tickets as a kanban board with one column per status.
+///
+/// Columns follow the project's status order from `settings` (the built-in workflow order
+/// by default); tickets keep the order they are given in (backlog rank).
+pub fn format_board(tickets: &[Ticket], show_project: bool, settings: &ProjectSettings) -> String {
+    if tickets.is_empty() {
+        return with_icon(get_icon("summary"), t("list.empty"));
+    }
+
+    let statuses = board_statuses(tickets, settings);
+    if is_plain() {
+        return format_board_plain(tickets, &statuses, show_project, settings);
+    }
+
+    let columns: Vec<Vec<String>> = statuses
+        .iter()
+        .map(|status| {
+            tickets
+                .iter()
+                .filter(|t| t.status.eq_ignore_ascii_case(status))
+                .map(|t| {
+                    let label = if show_project {
+                        format!("#{} {} [{}]", t.id, pinned_name(t), t.project)
+                    } else {
+                        format!("#{} {}", t.id, pinned_name(t))
+                    };
+                    truncate_text(&label, 30)
+                })
+                .collect()
+        })
+        .collect();
+
+    // Columns with a WIP limit show it, and turn red past it
+    let mut over_limit = Vec::new();
+    let mut builder = Builder::default();
+    builder.set_header(statuses.iter().zip(&columns).map(|(status, column)| {
+        match settings.wip_limit(status) {
+            Some(limit) if column.len() as i64 > limit => {
+                let header = format!("{} ({}/{}, {})", get_status_display(status), column.len(), limit, t("board.over_limit"));
+                over_limit.push(header.clone());
+                header
+            }
+            Some(limit) => format!("{} ({}/{})", get_status_display(status), column.len(), limit),
+            None => format!("{} ({})", get_status_display(status), column.len()),
+        }
+    }));
+    let depth = columns.iter().map(Vec::len).max().unwrap_or(0);
+    for row in 0..depth {
+        builder.push_record(columns.iter().map(|column| column.get(row).cloned().unwrap_or_default()));
+    }
+
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    let table = table.to_string();
+    if !use_colors() || over_limit.is_empty() {
+        return table;
+    }
+    // Color the header row after layout, since tabled counts escape codes as width
+    let mut lines: Vec<String> = table.lines().map(str::to_string).collect();
+    for header in &over_limit {
+        lines[1] = lines[1].replacen(header.as_str(), &header.red().bold().to_string(), 1);
+    }
+    lines.join("\n")
+}
+
+/// Color the cells of `column` in the body rows marked in `rows` of a rendered table. Done
+/// after layout because tabled counts escape codes as width.
+fn paint_cells(table: &str, column: usize, rows: &[bool], paint: impl Fn(&str) -> String) -> String {
+    if !use_colors() {
+        return table.to_string();
+    }
+    let mut row = 0;
+    let lines: Vec<String> = table
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            // Body rows start after the top border, header, and header separator
+            if index < 3 || !line.starts_with('│') {
+                return line.to_string();
+            }
+            row += 1;
+            if !rows.get(row - 1).copied().unwrap_or(false) {
+                return line.to_string();
+            }
+            let mut cells: Vec<String> = line.split('│').map(str::to_string).collect();
+            if let Some(cell) = cells.get_mut(column + 1) {
+                let text = cell.trim().to_string();
+                *cell = cell.replacen(&text, &paint(&text), 1);
+            }
+            cells.join("│")
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Board columns in status order
+fn board_statuses(tickets: &[Ticket], settings: &ProjectSettings) -> Vec<String> {
+    let mut statuses: Vec<String> = Vec::new();
+    for ticket in tickets {
+        let status = ticket.status.to_lowercase();
+        if !statuses.contains(&status) {
+            statuses.push(status);
+        }
+    }
+    statuses.sort_by_key(|s| settings.status_sort_key(s));
+    statuses
+}
+
+/// Plain board: one heading per status followed by its tickets
+fn format_board_plain(tickets: &[Ticket], statuses: &[String], show_project: bool, settings: &ProjectSettings) -> String {
+    let mut lines = Vec::new();
+    for status in statuses {
+        let column: Vec<&Ticket> = tickets.iter().filter(|t| t.status.eq_ignore_ascii_case(status)).collect();
+        lines.push(match settings.wip_limit(status) {
+            Some(limit) if column.len() as i64 > limit => {
+                format!("{} ({})", tf("board.column_limit", &[status, &column.len(), &limit]), t("board.over_limit"))
+            }
+            Some(limit) => tf("board.column_limit", &[status, &column.len(), &limit]),
+            None => tf("board.column", &[status, &column.len()]),
+        });
+        for ticket in column {
+            let mut line = format!("  {}: {}", tf("details.ticket", &[&ticket.id]), ticket.name);
+            if show_project {
+                line.push_str(&format!(". {}", tf("details.project", &[&ticket.project])));
+            }
+            if ticket.pinned_at.is_some() {
+                line.push_str(&format!(". {}", t("list.pinned")));
+            }
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Shade characters for the heatmap, from no time logged to a full day
+const HEATMAP_SHADES: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+
+/// Pick the shade level for a day's hours (0 = nothing, 4 = six hours or more)
+fn heatmap_level(hours: f64) -> usize {
+    match hours {
+        h if h <= 0.0 => 0,
+        h if h < 2.0 => 1,
+        h if h < 4.0 => 2,
+        h if h < 6.0 => 3,
+        _ => 4,
+    }
+}
+
+fn colorize_shade(level: usize) -> String {
+    let shade = HEATMAP_SHADES[level];
+    if !use_colors() {
+        return shade.to_string();
+    }
+    match level {
+        0 => shade.bright_black().to_string(),
+        1 | 2 => shade.green().to_string(),
+        _ => shade.bright_green().to_string(),
+    }
+}
+
+/// Format a GitHub-style calendar heatmap of hours logged per day.
+///
+/// Columns are weeks (Monday first) ending with the week containing `end`.
+pub fn format_heatmap(daily: &HashMap<NaiveDate, f64>, end: NaiveDate, weeks: usize) -> String {
+    let weeks = weeks.max(1);
+    let last_monday = end - Duration::days(end.weekday().num_days_from_monday() as i64);
+    let first_monday = last_monday - Duration::weeks(weeks as i64 - 1);
+    let (weekly, summary) = heatmap_totals(daily, first_monday, end);
+    if is_plain() {
+        return format_heatmap_plain(&weekly, &summary, first_monday, weeks);
+    }
+    let mut output = String::new();
+
+    let months: Vec<&str> = t("heatmap.months").split_whitespace().collect();
+    let weekdays: Vec<&str> = t("heatmap.weekdays").split_whitespace().collect();
+
+    // Month labels above the first week of each month
+    let mut header = String::from("    ");
+    let mut previous_month = None;
+    let mut column = 0;
+    while column < weeks {
+        let week_start = first_monday + Duration::weeks(column as i64);
+        if previous_month != Some(week_start.month()) && column + 2 <= weeks {
+            previous_month = Some(week_start.month());
+            let month = months.get(week_start.month0() as usize).copied().unwrap_or_default();
+            header.push_str(&format!("{:<4}", month));
+            column += 2;
+        } else {
+            header.push_str("  ");
+            column += 1;
+        }
+    }
+    output.push_str(header.trim_end());
+    output.push('\n');
+
+    for weekday in 0..7 {
+        let label = match weekday {
+            0 | 2 | 4 => weekdays.get(weekday as usize).copied().unwrap_or_default(),
+            _ => "",
+        };
+        output.push_str(&format!("{:<4}", label));
+        for week in 0..weeks {
+            let day = first_monday + Duration::days(week as i64 * 7 + weekday);
+            if day > end {
+                break;
+            }
+            let level = heatmap_level(daily.get(&day).copied().unwrap_or(0.0));
+            output.push_str(&colorize_shade(level));
+            output.push(' ');
+        }
+        output.push('\n');
+    }
+
+    let legend: Vec<String> = (0..HEATMAP_SHADES.len()).map(colorize_shade).collect();
+    output.push_str(&format!("\n    {}   (░ <2h, ▒ <4h, ▓ <6h, █ 6h+)\n", tf("heatmap.legend", &[&legend.join(" ")])));
+
+    output.push_str(&format!("\n{}\n", with_icon(get_icon("time"), summary)));
+
+    output
+}
+
+/// Width of the longest bar in the trends chart
+const TREND_BAR_WIDTH: usize = 12;
+
+/// A bar scaled against `max`, at least one block for any non-zero value
+fn trend_bar(value: f64, max: f64) -> String {
+    let blocks = if value <= 0.0 || max <= 0.0 { 0 } else { ((value / max * TREND_BAR_WIDTH as f64).round() as usize).max(1) };
+    "█".repeat(blocks)
+}
+
+/// Month label such as "Mar 2024", using the localised month abbreviations
+fn month_label(month: NaiveDate) -> String {
+    let months: Vec<&str> = t("heatmap.months").split_whitespace().collect();
+    format!("{} {}", months.get(month.month0() as usize).copied().unwrap_or_default(), month.year())
+}
+
+/// Whether the backlog grew or shrank over the whole period
+fn trends_summary(trends: &[MonthTrend]) -> String {
+    let net: i64 = trends.iter().map(|m| m.opened - m.closed).sum();
+    let months = trends.len();
+    match net {
+        n if n > 0 => tf("trends.grew", &[&n, &months]),
+        n if n < 0 => tf("trends.shrank", &[&-n, &months]),
+        _ => tf("trends.steady", &[&months]),
+    }
+}
+
+/// Chart tickets opened and closed and hours logged per month, oldest month first
+pub fn format_trends(trends: &[MonthTrend]) -> String {
+    if is_plain() {
+        return format_trends_plain(trends);
+    }
+    let max_tickets = trends.iter().map(|m| m.opened.max(m.closed)).max().unwrap_or(0) as f64;
+    let max_hours = trends.iter().map(|m| m.hours).fold(0.0, f64::max);
+    let labels: Vec<String> = trends.iter().map(|m| month_label(m.month)).collect();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let column = TREND_BAR_WIDTH + 7;
+
+    let mut output = format!(
+        "{:label_width$}  {:column$}{:column$}{}\n",
+        "",
+        t("trends.opened"),
+        t("trends.closed"),
+        t("trends.hours"),
+    );
+    for (month, label) in trends.iter().zip(&labels) {
+        let cell = |bar: String, value: String, paint: fn(&str) -> ColoredString| {
+            let text = if bar.is_empty() { value } else { format!("{} {}", bar, value) };
+            let padding = " ".repeat(column.saturating_sub(text.chars().count()));
+            let text = if use_colors() { paint(&text).to_string() } else { text };
+            format!("{}{}", text, padding)
+        };
+        output.push_str(&format!(
+            "{:label_width$}  {}{}{}\n",
+            label,
+            cell(trend_bar(month.opened as f64, max_tickets), month.opened.to_string(), |s| s.yellow()),
+            cell(trend_bar(month.closed as f64, max_tickets), month.closed.to_string(), |s| s.green()),
+            cell(trend_bar(month.hours, max_hours), format!("{:.1}", month.hours), |s| s.cyan()).trim_end(),
+        ));
+    }
+    output.push_str(&format!("\n{}\n", with_icon("📈", trends_summary(trends))));
+    output
+}
+
+/// Plain trends: one sentence per month, then the overall change
+fn format_trends_plain(trends: &[MonthTrend]) -> String {
+    let mut lines: Vec<String> = trends
+        .iter()
+        .map(|m| tf("trends.plain", &[&month_label(m.month), &m.opened, &m.closed, &format!("{:.1}", m.hours)]))
+        .collect();
+    lines.push(trends_summary(trends));
+    lines.join("\n") + "\n"
+}
+
+/// Hours per week (keyed by Monday) and the summary line for the visible range
+fn heatmap_totals(daily: &HashMap<NaiveDate, f64>, first_monday: NaiveDate, end: NaiveDate) -> (BTreeMap<NaiveDate, f64>, String) {
+    let in_range: Vec<(&NaiveDate, &f64)> = daily
+        .iter()
+        .filter(|(day, _)| **day >= first_monday && **day <= end)
+        .collect();
+    let total = in_range.iter().fold(0.0, |sum, (_, h)| sum + **h);
+    let active_days = in_range.iter().filter(|(_, h)| **h > 0.0).count();
+    let total_id = if active_days == 1 { "heatmap.total_one" } else { "heatmap.total_other" };
+    let mut summary = tf(total_id, &[&format!("{:.1}", total), &active_days]);
+
+    let mut weekly: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for (day, hours) in &in_range {
+        let monday = **day - Duration::days(day.weekday().num_days_from_monday() as i64);
+        *weekly.entry(monday).or_insert(0.0) += **hours;
+    }
+    if let Some((week, hours)) = weekly.iter().max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0))) {
+        let busiest = tf("heatmap.busiest", &[&week.format("%Y-%m-%d"), &format!("{:.1}", hours)]);
+        summary.push_str(&format!(", {}", busiest));
+    }
+    (weekly, summary)
+}
+
+/// Plain heatmap: hours for each week instead of the calendar grid
+fn format_heatmap_plain(weekly: &BTreeMap<NaiveDate, f64>, summary: &str, first_monday: NaiveDate, weeks: usize) -> String {
+    let mut output = String::new();
+    for week in 0..weeks {
+        let monday = first_monday + Duration::weeks(week as i64);
+        let hours = weekly.get(&monday).copied().unwrap_or(0.0);
+        output.push_str(&tf("heatmap.week", &[&monday.format("%Y-%m-%d"), &format!("{:.1}", hours)]));
+        output.push('\n');
+    }
+    output.push_str(summary);
+    output.push('\n');
+    output
+}
+
+/// Format time spent per status, in workflow order.
+///
+/// Finished statuses that were only ever the final state are left out, since their
+/// clock never runs.
+pub fn format_status_time(rows: &[StatusTime]) -> String {
+    let mut rows: Vec<&StatusTime> = rows
+        .iter()
+        .filter(|row| !(is_finished_status(&row.status) && row.total.is_zero()))
+        .collect();
+    if rows.is_empty() {
+        return with_icon(get_icon("time"), t("status_time.empty"));
+    }
+    rows.sort_by_key(|row| status_sort_key(&row.status));
+    if is_plain() {
+        return format_status_time_plain(&rows);
+    }
+
+    let mut builder = Builder::default();
+    builder.set_header([t("status_time.status"), t("status_time.time"), t("status_time.visits"), t("status_time.average")]);
+    for row in rows {
+        builder.push_record([
+            get_status_display(&row.status),
+            format_duration(row.total),
+            row.visits.to_string(),
+            format_duration(row.total / row.visits.max(1) as i32),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    table.to_string()
+}
+
+/// Plain status time report: one sentence per status
+fn format_status_time_plain(rows: &[&StatusTime]) -> String {
+    rows.iter()
+        .map(|row| {
+            let average = format_duration(row.total / row.visits.max(1) as i32);
+            tf("status_time.plain", &[&row.status, &format_duration(row.total), &row.visits, &average])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format the aging report: open tickets per project by age, then each project's oldest
+pub fn format_aging(projects: &[ProjectAging]) -> String {
+    if projects.is_empty() {
+        return with_icon(get_icon("summary"), t("aging.empty"));
+    }
+    if is_plain() {
+        return format_aging_plain(projects);
+    }
+
+    let mut builder = Builder::default();
+    let mut header = vec![t("aging.project").to_string()];
+    header.extend(AGE_BUCKET_LABELS.iter().map(|label| label.to_string()));
+    header.push(t("aging.total").to_string());
+    builder.set_header(header);
+    let mut totals = [0; 4];
+    for project in projects {
+        let mut record = vec![project.project.clone()];
+        for (i, count) in project.buckets.iter().enumerate() {
+            totals[i] += count;
+            record.push(count.to_string());
+        }
+        record.push(project.total().to_string());
+        builder.push_record(record);
+    }
+    if projects.len() > 1 {
+        let mut record = vec![t("aging.total").to_string()];
+        record.extend(totals.iter().map(i64::to_string));
+        record.push(totals.iter().sum::<i64>().to_string());
+        builder.push_record(record);
+    }
+    let mut table = builder.build();
+    table.with(Style::rounded());
+
+    // Tickets in the oldest bucket are the ones to look at
+    let stale: Vec<bool> = projects.iter().map(|project| project.buckets[3] > 0).collect();
+    let mut output = paint_cells(&table.to_string(), 4, &stale, |cell| cell.red().bold().to_string());
+    output.push_str(&format!("\n\n{}", t("aging.oldest")));
+    for project in projects {
+        for ticket in &project.oldest {
+            output.push_str(&format!(
+                "\n  #{} {} [{}] {}: {}",
+                ticket.id,
+                truncate_text(&ticket.name, 40),
+                project.project,
+                colorize_status(&ticket.status),
+                tf("aging.days", &[&ticket.age_days])
+            ));
+        }
+    }
+    output
+}
+
+/// Plain aging report: one sentence per project, naming its oldest tickets
+fn format_aging_plain(projects: &[ProjectAging]) -> String {
+    projects
+        .iter()
+        .map(|project| {
+            let buckets: Vec<String> = AGE_BUCKET_LABELS
+                .iter()
+                .zip(project.buckets)
+                .map(|(label, count)| format!("{}: {}", label, count))
+                .collect();
+            let oldest: Vec<String> = project
+                .oldest
+                .iter()
+                .map(|ticket| format!("#{} {} ({})", ticket.id, ticket.name, tf("aging.days", &[&ticket.age_days])))
+                .collect();
+            tf("aging.plain", &[&project.project, &project.total(), &buckets.join(", "), &oldest.join(", ")])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What an event did, e.g. `update ticket #3`
+fn event_change(event: &Event) -> String {
+    format!("{} {} #{}", event.action, event.entity, event.entity_id)
+}
+
+/// Fields an update touched, or the field a sync conflict was about and which side was
+/// kept; creates and deletes list none
+fn event_fields(event: &Event) -> String {
+    match event.action.as_str() {
+        "update" => event.changed_fields().join(", "),
+        "resolve" => {
+            let after: serde_json::Value = event.after.as_deref().and_then(|a| serde_json::from_str(a).ok()).unwrap_or_default();
+            format!("{}: {}", after["field"].as_str().unwrap_or_default(), after["resolution"].as_str().unwrap_or_default())
+        }
+        _ => String::new(),
+    }
+}
+
+/// Format journal entries, newest first, with times in local time
+pub fn format_events(events: &[Event]) -> String {
+    if events.is_empty() {
+        return with_icon("📜", t("events.empty"));
+    }
+    let when = |event: &Event| event.created_at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string();
+    if is_plain() {
+        return events
+            .iter()
+            .map(|event| {
+                let command = event.command.as_deref().unwrap_or("-");
+                let fields = event_fields(event);
+                let change = if fields.is_empty() {
+                    event_change(event)
+                } else {
+                    tf("events.change_fields", &[&event_change(event), &fields])
+                };
+                tf("events.plain", &[&event.id, &when(event), &change, &command])
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let mut builder = Builder::default();
+    builder.set_header([t("events.id"), t("events.when"), t("events.change"), t("events.fields"), t("events.command")]);
+    for event in events {
+        builder.push_record([
+            event.id.to_string(),
+            when(event),
+            event_change(event),
+            truncate_text(&event_fields(event), 40),
+            truncate_text(event.command.as_deref().unwrap_or(""), 40),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    table.to_string()
+}
+
+/// Tickets from `ltm recent`: when each was used, as time before `now` within a day, and
+/// by which command
+pub fn format_recent(tickets: &[RecentTicket], now: NaiveDateTime) -> String {
+    if tickets.is_empty() {
+        return with_icon(get_icon("summary"), t("recent.empty"));
+    }
+    let when = |ticket: &RecentTicket| {
+        let ago = now - ticket.used_at;
+        if ago < Duration::days(1) {
+            tf("recent.ago", &[&format_duration(ago.max(Duration::minutes(1)))])
+        } else {
+            ticket.used_at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+        }
+    };
+    if is_plain() {
+        return tickets
+            .iter()
+            .map(|ticket| {
+                let command = ticket.command.as_deref().unwrap_or("-");
+                tf("recent.plain", &[&ticket.ticket_id, &ticket.name, &ticket.project, &ticket.status, &when(ticket), &command])
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let mut builder = Builder::default();
+    builder.set_header([
+        t("list.header.id"),
+        t("list.header.project"),
+        t("list.header.name"),
+        t("list.header.status"),
+        t("events.when"),
+        t("events.command"),
+    ]);
+    for ticket in tickets {
+        builder.push_record([
+            ticket.ticket_id.to_string(),
+            truncate_text(&ticket.project, 15),
+            truncate_text(&ticket.name, 25),
+            get_status_display(&ticket.status),
+            when(ticket),
+            truncate_text(ticket.command.as_deref().unwrap_or(""), 30),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    table.to_string()
+}
+
+/// What `ltm cleanup` did, or would do, with the tickets it found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupOutcome {
+    Archived,
+    Deleted,
+    WouldArchive,
+    WouldDelete,
+}
+
+/// The `ltm today` view: tickets due today or overdue, tickets in progress, timers with
+/// their ticket names, and the time logged today
+pub fn format_agenda(
+    today: NaiveDate,
+    due: &[Ticket],
+    in_progress: &[Ticket],
+    timers: &[(ActiveTimer, String)],
+    logged_minutes: i64,
+    now: NaiveDateTime,
+) -> String {
+    let mut lines = vec![with_icon("📅", tf("agenda.title", &[&today.format("%Y-%m-%d")]))];
+    let ticket_line = |ticket: &Ticket| format!("   #{} {} [{}]", ticket.id, ticket.name, ticket.project);
+    if !due.is_empty() {
+        lines.push(with_icon("⏰", tf("agenda.due", &[&due.len()])));
+        for ticket in due {
+            let due_date = ticket.due_date.map(|d| format_due_date(d, today)).unwrap_or_default();
+            lines.push(format!("{} {}", ticket_line(ticket), due_date));
+        }
+    }
+    if !in_progress.is_empty() {
+        lines.push(with_icon("🚧", tf("agenda.in_progress", &[&in_progress.len()])));
+        lines.extend(in_progress.iter().map(ticket_line));
+    }
+    if !timers.is_empty() {
+        lines.push(with_icon(get_icon("time"), tf("agenda.timers", &[&timers.len()])));
+        for (timer, name) in timers {
+            let elapsed = format_duration(timer.elapsed(now));
+            let key = if timer.is_paused() { "agenda.timer_paused" } else { "agenda.timer_running" };
+            lines.push(format!("   #{} {} {}", timer.ticket_id, name, tf(key, &[&elapsed])));
+        }
+    }
+    if due.is_empty() && in_progress.is_empty() && timers.is_empty() {
+        lines.push(with_icon("✅", t("agenda.empty")));
+    }
+    lines.push(with_icon(get_icon("summary"), tf("agenda.logged", &[&format_minutes(logged_minutes)])));
+    lines.join("\n")
+}
+
+/// The `ltm week` plan: overdue tickets first, then Monday to Sunday with each day's tickets,
+/// their estimated load, and a bar comparing the days. `tickets` are the unfinished tickets
+/// due by the end of the week.
+pub fn format_week(monday: NaiveDate, today: NaiveDate, tickets: &[Ticket]) -> String {
+    let ticket_line = |ticket: &Ticket| {
+        let mut line = format!("   #{} {} [{}]", ticket.id, ticket.name, ticket.project);
+        if let Some(minutes) = ticket.estimate_minutes {
+            line.push_str(&format!(" ~{}", format_estimate(minutes)));
+        }
+        line
+    };
+    let due_on = |day: NaiveDate| tickets.iter().filter(move |t| t.due_date == Some(day) && day >= today);
+    let load = |day: NaiveDate| due_on(day).filter_map(|t| t.estimate_minutes).sum::<i64>();
+    let max_load = (0..7).map(|i| load(monday + Duration::days(i))).max().unwrap_or(0);
+
+    let mut lines = vec![with_icon("📆", tf("week.title", &[&monday.format("%Y-%m-%d")]))];
+    let overdue: Vec<&Ticket> = tickets.iter().filter(|t| t.due_date.is_some_and(|d| d < today)).collect();
+    if !overdue.is_empty() {
+        lines.push(with_icon("⚠️", tf("week.overdue", &[&overdue.len()])));
+        for ticket in &overdue {
+            let due = ticket.due_date.map(|d| format_due_date(d, today)).unwrap_or_default();
+            lines.push(format!("{} {}", ticket_line(ticket), due));
+        }
+    }
+
+    let weekdays: Vec<&str> = t("heatmap.weekdays").split_whitespace().collect();
+    let mut planned = 0;
+    for (index, weekday) in weekdays.iter().enumerate().take(7) {
+        let day = monday + Duration::days(index as i64);
+        let mut label = format!("{} {}", weekday, day.format("%m-%d"));
+        if day == today {
+            label = format!("{} ({})", label, t("due.today"));
+        }
+        let day_tickets: Vec<&Ticket> = due_on(day).collect();
+        planned += day_tickets.len();
+        let summary = if day_tickets.is_empty() {
+            t("week.nothing").to_string()
+        } else {
+            let mut parts = vec![format_estimate(load(day))];
+            let unestimated = day_tickets.iter().filter(|t| t.estimate_minutes.is_none()).count();
+            if unestimated > 0 {
+                parts.push(tf("week.unestimated", &[&unestimated]));
+            }
+            if !is_plain() {
+                parts.push(trend_bar(load(day) as f64, max_load as f64));
+            }
+            parts.join("  ")
+        };
+        lines.push(format!("{:<17}  {}", label, summary).trim_end().to_string());
+        lines.extend(day_tickets.into_iter().map(ticket_line));
+    }
+
+    let total: i64 = (0..7).map(|i| load(monday + Duration::days(i))).sum();
+    lines.push(with_icon(get_icon("summary"), tf("week.total", &[&format_estimate(total), &planned])));
+    lines.join("\n")
+}
+
+/// A month grid for `ltm calendar`, marking days with tickets due (`!`) and days with time
+/// logged (`+`), followed by the tickets due that month. `month` is the first of the month.
+pub fn format_calendar(month: NaiveDate, today: NaiveDate, due: &[Ticket], logged: &HashMap<NaiveDate, f64>) -> String {
+    let next_month = month + Months::new(1);
+    let mut lines = vec![format!("{:^35}", month_label(month)).trim_end().to_string()];
+    lines.push(t("heatmap.weekdays").split_whitespace().map(|d| format!("{:>3}  ", d)).collect::<String>().trim_end().to_string());
+
+    let mut week = "     ".repeat(month.weekday().num_days_from_monday() as usize);
+    let mut day = month;
+    while day < next_month {
+        let mut markers = String::new();
+        if due.iter().any(|t| t.due_date == Some(day)) {
+            markers.push('!');
+        }
+        if logged.get(&day).is_some_and(|hours| *hours > 0.0) {
+            markers.push('+');
+        }
+        let number = format!("{:>3}", day.day());
+        let number = if day == today && use_colors() { number.reversed().to_string() } else { number };
+        week.push_str(&format!("{}{:<2}", number, markers));
+        if day.weekday() == Weekday::Sun {
+            lines.push(week.trim_end().to_string());
+            week.clear();
+        }
+        day += Duration::days(1);
+    }
+    if !week.is_empty() {
+        lines.push(week.trim_end().to_string());
+    }
+
+    lines.push(String::new());
+    lines.push(t("calendar.legend").to_string());
+    if !due.is_empty() {
+        lines.push(String::new());
+        lines.push(with_icon("⏰", tf("calendar.due", &[&month_label(month), &due.len()])));
+        for ticket in due {
+            let date = ticket.due_date.map(|d| d.format("%m-%d").to_string()).unwrap_or_default();
+            lines.push(format!("   {} #{} {} [{}]", date, ticket.id, ticket.name, ticket.project));
+        }
+    }
+    lines.join("\n")
+}
+
+/// One line per project saying how many tickets cleanup archived or deleted
+pub fn format_cleanup_report(tickets: &[Ticket], outcome: CleanupOutcome) -> String {
+    let key = match outcome {
+        CleanupOutcome::Archived => "cleanup.archived",
+        CleanupOutcome::Deleted => "cleanup.deleted",
+        CleanupOutcome::WouldArchive => "cleanup.would_archive",
+        CleanupOutcome::WouldDelete => "cleanup.would_delete",
+    };
+    let mut per_project: BTreeMap<&str, usize> = BTreeMap::new();
+    for ticket in tickets {
+        *per_project.entry(&ticket.project).or_default() += 1;
+    }
+    per_project
+        .into_iter()
+        .map(|(project, count)| with_icon("🗄️", tf(key, &[&count, &project])))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn report_hours(minutes: i64) -> String {
+    format!("{:.2}", minutes as f64 / 60.0)
+}
+
+/// Format the time report: hours per ticket with a total row, and one more per currency
+/// when the tickets bill in several
+pub fn format_time_report(rows: &[TicketHours], currencies: &Currencies) -> String {
+    if rows.is_empty() {
+        return with_icon(get_icon("time"), t("time_report.empty"));
+    }
+    let total_minutes: i64 = rows.iter().map(|r| r.minutes).sum();
+    let total_entries: i64 = rows.iter().map(|r| r.entries).sum();
+    let total_billable: i64 = rows.iter().map(|r| r.billable_minutes).sum();
+    let totals = currency_totals(rows);
+    if is_plain() {
+        return format_time_report_plain(rows, total_minutes, currencies);
+    }
+
+    // Amounts only when some ticket has a rate
+    let priced = !totals.is_empty();
+    let amount = |amount: Option<f64>, currency: Option<&str>| {
+        amount.map(|a| format_amount(currencies, currency, a)).unwrap_or_default()
+    };
+    let mut builder = Builder::default();
+    let mut header = vec![
+        t("time_report.ticket"),
+        t("time_report.project"),
+        t("time_report.name"),
+        t("time_report.entries"),
+        t("time_report.hours"),
+        t("time_report.billable"),
+    ];
+    header.extend(priced.then(|| t("time_report.amount")));
+    builder.set_header(header);
+    for row in rows {
+        let mut record = vec![
+            row.ticket_id.to_string(),
+            row.project.clone(),
+            truncate_text(&row.name, 40),
+            row.entries.to_string(),
+            report_hours(row.minutes),
+            report_hours(row.billable_minutes),
+        ];
+        record.extend(priced.then(|| amount(row.amount, row.currency.as_deref())));
+        builder.push_record(record);
+    }
+    let bold = |text: String| if use_colors() { text.bold().to_string() } else { text };
+    let total = bold(t("time_report.total").to_string());
+    let mut record =
+        vec![String::new(), String::new(), total, total_entries.to_string(), report_hours(total_minutes), report_hours(total_billable)];
+    match totals.as_slice() {
+        [] => {}
+        [total] => record.push(amount(Some(total.amount), total.currency.as_deref())),
+        _ => record.push(String::new()),
+    }
+    builder.push_record(record);
+    if totals.len() > 1 {
+        for total in &totals {
+            let label = format!("{} {}", t("time_report.total"), total.currency.as_deref().unwrap_or_default());
+            let mut record = vec![String::new(); 6];
+            record[2] = bold(label.trim_end().to_string());
+            record.push(amount(Some(total.amount), total.currency.as_deref()));
+            builder.push_record(record);
+        }
+    }
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    table.to_string()
+}
+
+/// Format exported time logs as a table, one row per log, with the total hours
+pub fn format_time_export(entries: &[TimeLogEntry]) -> String {
+    if entries.is_empty() {
+        return with_icon(get_icon("time"), t("time_report.empty"));
+    }
+    let total: i64 = entries.iter().map(|e| e.minutes).sum();
+    if is_plain() {
+        let mut lines: Vec<String> = entries
+            .iter()
+            .map(|e| tf("time_export.plain", &[&e.date, &e.ticket_id, &e.ticket_name, &e.project, &report_hours(e.minutes)]))
+            .collect();
+        lines.push(tf("time_report.plain_total", &[&report_hours(total), &entries.iter().map(|e| e.ticket_id).collect::<HashSet<_>>().len()]));
+        return lines.join("\n");
+    }
+
+    let mut builder = Builder::default();
+    builder.set_header([
+        t("time_export.date"),
+        t("time_report.project"),
+        t("time_report.ticket"),
+        t("time_report.name"),
+        t("time_report.hours"),
+        t("time_report.billable"),
+        t("time_export.note"),
+    ]);
+    for entry in entries {
+        builder.push_record([
+            entry.date.to_string(),
+            entry.project.clone(),
+            entry.ticket_id.to_string(),
+            truncate_text(&entry.ticket_name, 30),
+            report_hours(entry.minutes),
+            if entry.billable { "✓" } else { "" }.to_string(),
+            truncate_text(entry.note.as_deref().unwrap_or(""), 30),
+        ]);
+    }
+    let total_label = if use_colors() { t("time_report.total").bold().to_string() } else { t("time_report.total").to_string() };
+    builder.push_record([String::new(), String::new(), String::new(), total_label, report_hours(total), String::new(), String::new()]);
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    table.to_string()
+}
+
+/// Exported time logs as CSV for payroll or accounting: one row per log, with hours as
+/// decimals and the billable flag as `yes` or `no`
+pub fn format_time_export_csv(entries: &[TimeLogEntry]) -> String {
+    let mut lines = vec!["project,ticket_id,ticket,date,hours,billable,note".to_string()];
+    for entry in entries {
+        lines.push(format!(
+            "{},{},{},{},{},{},{}",
+            csv_field(&entry.project),
+            entry.ticket_id,
+            csv_field(&entry.ticket_name),
+            entry.date,
+            report_hours(entry.minutes),
+            if entry.billable { "yes" } else { "no" },
+            csv_field(entry.note.as_deref().unwrap_or(""))
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Billable hours, when some time was not billable, and the amount, when there is one
+fn plain_billing(minutes: i64, billable_minutes: i64, amount: Option<String>) -> String {
+    let mut parts = Vec::new();
+    if billable_minutes != minutes {
+        parts.push(tf("time_report.plain_billable", &[&report_hours(billable_minutes)]));
+    }
+    parts.extend(amount.map(|amount| tf("time_report.plain_amount", &[&amount])));
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("; {}", parts.join(", "))
+    }
+}
+
+/// Plain time report: one sentence per ticket, then the total
+fn format_time_report_plain(rows: &[TicketHours], total_minutes: i64, currencies: &Currencies) -> String {
+    let mut lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let hours = tf("time_report.plain", &[&row.ticket_id, &row.name, &row.project, &report_hours(row.minutes), &row.entries]);
+            let amount = row.amount.map(|amount| format_amount(currencies, row.currency.as_deref(), amount));
+            format!("{}{}", hours, plain_billing(row.minutes, row.billable_minutes, amount))
+        })
+        .collect();
+    let total_billable: i64 = rows.iter().map(|r| r.billable_minutes).sum();
+    let totals: Vec<String> = currency_totals(rows)
+        .iter()
+        .map(|total| format_amount(currencies, total.currency.as_deref(), total.amount))
+        .collect();
+    lines.push(format!(
+        "{}{}",
+        tf("time_report.plain_total", &[&report_hours(total_minutes), &rows.len()]),
+        plain_billing(total_minutes, total_billable, (!totals.is_empty()).then(|| totals.join(", ")))
+    ));
+    lines.join("\n")
+}
+
+/// Quote a CSV field when it contains a separator, quote, or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format the time report as CSV, ending with a total row; amounts in several currencies
+/// get a total row each after it
+pub fn format_time_report_csv(rows: &[TicketHours]) -> String {
+    let money = |amount: Option<f64>| amount.map(|a| format!("{:.2}", a)).unwrap_or_default();
+    let mut lines =
+        vec!["ticket_id,project,name,status,entries,minutes,hours,billable_minutes,billable_hours,rate,amount,currency".to_string()];
+    for row in rows {
+        lines.push(format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            row.ticket_id,
+            csv_field(&row.project),
+            csv_field(&row.name),
+            csv_field(&row.status),
+            row.entries,
+            row.minutes,
+            report_hours(row.minutes),
+            row.billable_minutes,
+            report_hours(row.billable_minutes),
+            money(row.rate),
+            money(row.amount),
+            row.currency.as_deref().map(csv_field).unwrap_or_default()
+        ));
+    }
+    let total_minutes: i64 = rows.iter().map(|r| r.minutes).sum();
+    let total_entries: i64 = rows.iter().map(|r| r.entries).sum();
+    let total_billable: i64 = rows.iter().map(|r| r.billable_minutes).sum();
+    let totals = currency_totals(rows);
+    let currency = match totals.as_slice() {
+        [total] => total.currency.as_deref().map(csv_field).unwrap_or_default(),
+        _ => String::new(),
+    };
+    lines.push(format!(
+        ",,Total,,{},{},{},{},{},,{},{}",
+        total_entries,
+        total_minutes,
+        report_hours(total_minutes),
+        total_billable,
+        report_hours(total_billable),
+        money(total_amount(rows)),
+        currency
+    ));
+    if totals.len() > 1 {
+        for total in &totals {
+            let currency = total.currency.as_deref().map(csv_field).unwrap_or_default();
+            lines.push(format!(",,Total {},,,,,,,,{:.2},{}", currency, total.amount, currency));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    
+    fn create_test_ticket() -> Ticket {
+        let timestamp = DateTime::from_timestamp(1642694400, 0).unwrap().naive_utc();
+        Ticket {
+            id: 1,
+            project: "test_project".to_string(),
+            name: "Test ticket".to_string(),
+            description: "A test description".to_string(),
+            status: "open".to_string(),
+            created_at: timestamp,
+            updated_at: timestamp,
+            due_date: None,
+            rank: 1,
+            priority: None,
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        }
+    }
+    
+    #[test]
+    fn test_status_symbols() {
+        assert_eq!(get_status_symbol("open"), "●");
+        assert_eq!(get_status_symbol("closed"), "✓");
+        assert_eq!(get_status_symbol("in-progress"), "⚠");
+        assert_eq!(get_status_symbol("wontfix"), "⊘");
+        assert_eq!(get_status_symbol("unknown"), "○");
+    }
+    
+    #[test]
+    fn test_colorize_status() {
+        let open_status = colorize_status("open");
+        assert!(open_status.to_string().contains("● open"));
+        
+        let closed_status = colorize_status("closed");
+        assert!(closed_status.to_string().contains("✓ closed"));
+    }
+    
+    #[test]
+    fn test_table_formatting() {
+        let tickets = vec![create_test_ticket()];
+        let output = format_ticket_list(&tickets);
+        assert!(output.contains("╭────┬"));
+        assert!(output.contains("📊 Summary:"));
+        assert!(output.contains("tickets"));
+        assert!(output.contains("open"));
+        assert!(output.contains("closed"));
+    }
+    
+    #[test]
+    fn test_ticket_details_formatting() {
+        let ticket = create_test_ticket();
+        let comments = vec![];
+        let time_logs = vec![];
+        let output = format_ticket_details(&ticket, &comments, &time_logs, &[], &[], &[]);
+        
+        assert!(output.contains("╭─ Ticket #1"));
+        assert!(output.contains("📋 Test ticket"));
+        assert!(output.contains("🏷️ Project: test_project"));
+        assert!(output.contains("📊 Status:"));
+        assert!(!output.contains("Priority:"));
+
+        let ticket = Ticket {
+            priority: Some("high".to_string()),
+            estimate_minutes: Some(90),
+            severity: Some("S1".to_string()),
+            ..create_test_ticket()
+        };
+        let output = format_ticket_details(&ticket, &comments, &time_logs, &[], &[], &["auth".to_string(), "ui".to_string()]);
+        assert!(output.contains("🔥 Priority: high"));
+        assert!(output.contains("🚨 Severity: 🔴 S1"));
+        assert!(output.contains("⏳ Estimate: 1h 30m"));
+        assert!(output.contains("🔖 Tags: #auth #ui"));
+        assert_eq!(format_estimate(120), "2h");
+        assert_eq!(format_estimate(45), "45m");
+    }
+    
+    #[test]
+    fn test_project_summary_formatting() {
+        let summary = ProjectSummary {
+            project: "test_project".to_string(),
+            total_tickets: 10,
+            open_tickets: 3,
+            closed_tickets: 7,
+            total_time_hours: 25.5,
+            kinds: [("bug".to_string(), 4), ("spike".to_string(), 1)].into(),
+            components: [("api".to_string(), 2), ("frontend".to_string(), 5)].into(),
+        };
+        
+        let output = format_project_summary("test_project", &summary);
+        assert!(output.contains("📊 Project Summary"));
+        assert!(output.contains("📋 Total Tickets: 10"));
+        assert!(output.contains("● Open Tickets: 3"));
+        assert!(output.contains("✓ Closed Tickets: 7"));
+        assert!(output.contains("🧩 By type: 🐛 bug 4, 🔹 spike 1"));
+        assert!(output.contains("🧱 By component: api 2, frontend 5"));
+        assert!(output.contains("Progress:"));
+    }
+
+    #[test]
+    fn test_release_formatting() {
+        let ticket = |id: i64, status: &str| Ticket { id, name: format!("Ticket {}", id), status: status.to_string(), ..create_test_ticket() };
+        let tickets = vec![ticket(1, "closed"), ticket(2, "in-progress"), ticket(3, "done"), ticket(4, "open")];
+
+        let output = format_release("webapp", "v1.4", &tickets);
+        assert!(output.contains("🚀 Release v1.4 of webapp"));
+        assert!(output.contains("] 50% (2 of 4 done)"));
+        assert!(output.contains("Remaining open tickets (2):"));
+        assert!(output.contains("#2 Ticket 2") && output.contains("#4 Ticket 4"));
+        assert!(!output.contains("#1 Ticket 1"));
+        assert!(format_release("webapp", "v1.4", &tickets[..1]).contains("Every ticket in this release is done."));
+        assert!(format_release("webapp", "v2.0", &[]).contains("No tickets are planned for this release yet."));
+    }
+
+    #[test]
+    fn test_roadmap_formatting() {
+        let item = |release: &str, due: Option<NaiveDate>, done: usize, risk: ReleaseRisk| RoadmapItem {
+            project: "webapp".to_string(),
+            release: release.to_string(),
+            due_date: due,
+            total: 4,
+            done,
+            remaining_minutes: 600,
+            unestimated: 1,
+            available_minutes: due.map(|_| 360),
+            late_tickets: 0,
+            risk,
+        };
+        let items = vec![
+            item("v1.4", NaiveDate::from_ymd_opt(2024, 5, 10), 2, ReleaseRisk::AtRisk),
+            item("v2.0", None, 4, ReleaseRisk::Done),
+        ];
+
+        let output = format_roadmap(Some("webapp"), &items);
+        assert!(output.contains("🚀 Roadmap of webapp"));
+        assert!(output.contains("2024-05-10  ⚠ webapp/v1.4"));
+        assert!(output.contains("]  50% (2/4)  at risk, 10h of work left, 6h available, 1 open tickets unestimated"));
+        assert!(output.contains("no date     ✓ webapp/v2.0"));
+        assert!(output.contains("] 100% (4/4)  done"));
+        assert!(format_roadmap(None, &[]).contains("No releases yet."));
+    }
+
+    #[test]
+    fn test_status_comment() {
+        let at = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap().and_hms_opt(14, 3, 59).unwrap();
+        assert_eq!(
+            format_status_comment("open", "blocked", Some("Ada"), at, Some("waiting on legal")),
+            "Status changed open → blocked by Ada at 2024-05-10 14:03, reason: waiting on legal"
+        );
+        assert_eq!(format_status_comment("blocked", "open", None, at, None), "Status changed blocked → open at 2024-05-10 14:03");
+    }
+
+    #[test]
+    fn test_delete_impact_formatting() {
+        let impact = DeleteImpact { comments: 3, time_logs: 2, minutes: 270, links: 1, ..Default::default() };
+        assert_eq!(format_delete_impact(&impact).unwrap(), "3 comments, 2 time logs totaling 4h 30m, 1 link");
+        assert_eq!(format_delete_impact(&DeleteImpact::default()), None);
+    }
+
+    #[test]
+    fn test_score_formatting() {
+        let ticket = |id: i64, name: &str| Ticket { id, name: name.to_string(), reach: Some(500), impact: Some(0.5), confidence: Some(80), effort: Some(2.0), ..create_test_ticket() };
+        let (first, second) = (ticket(1, "Onboarding"), ticket(2, "Export"));
+        let unscored = Ticket { effort: None, ..ticket(3, "Dark mode") };
+
+        let output = format_scores(ScoringMethod::Rice, &[(&first, 100.0), (&second, 12.5)], &[(&unscored, vec!["effort"])]);
+        assert!(output.starts_with("🎯 Backlog by RICE score"));
+        assert!(output.contains("Reach") && output.contains("Confidence"));
+        let row = output.lines().find(|line| line.contains("Onboarding")).unwrap();
+        assert!(row.contains("500") && row.contains("0.5") && row.contains("80%") && row.contains("100.0"));
+        assert!(output.find("Onboarding").unwrap() < output.find("Export").unwrap());
+        assert!(output.contains("Not scored yet (1):\n  #3 Dark mode (missing effort)"));
+
+        let ice = format_scores(ScoringMethod::Ice, &[(&first, 0.2)], &[]);
+        assert!(ice.contains("ICE score") && ice.contains("0.20") && !ice.contains("Reach"));
+        assert!(format_scores(ScoringMethod::Ice, &[], &[]).contains("No backlog ticket has every input yet."));
+    }
+
+    #[test]
+    fn test_gantt_formatting() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        let release = Release { project: "webapp".to_string(), name: "v1.4".to_string(), created_at: create_test_ticket().created_at, due_date: Some(day(31)) };
+        let ticket = |id: i64, name: &str, status: &str, due: Option<NaiveDate>| Ticket {
+            id,
+            name: name.to_string(),
+            status: status.to_string(),
+            created_at: day(1).and_hms_opt(9, 0, 0).unwrap(),
+            due_date: due,
+            ..create_test_ticket()
+        };
+        let tickets = vec![ticket(1, "Schema", "closed", Some(day(10))), ticket(2, "Launch: web", "open", None)];
+
+        let chart = format_gantt(&release, &tickets, day(16));
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines[0], "🚀 Schedule of v1.4 of webapp");
+        assert_eq!(lines[1], format!("                {}{:>38}", "2024-05-01", "2024-05-31"));
+        // Day 16 of a 30-day span over 48 columns lands in column 23, the due date in the last
+        assert_eq!(lines[2], format!("{}▼{}◆", " ".repeat(16 + 23), " ".repeat(23)));
+        assert_eq!(lines[3], format!("#1 Schema       {}{}│", "█".repeat(15), " ".repeat(8)));
+        assert_eq!(lines[4], format!("#2 Launch: web  {}", "▓".repeat(48)));
+
+        let mermaid = format_gantt_mermaid(&release, &tickets, day(16));
+        assert!(mermaid.starts_with("gantt\n    title webapp v1.4\n    dateFormat YYYY-MM-DD"));
+        assert!(mermaid.contains("    1 Schema :done, t1, 2024-05-01, 2024-05-11"));
+        assert!(mermaid.contains("    2 Launch  web :t2, 2024-05-01, 2024-06-01"));
+        assert!(mermaid.contains("    v1.4 :milestone, release, 2024-05-31, 0d"));
+    }
+
+    #[test]
+    fn test_dependency_graph_formatting() {
+        let ticket = |id: i64, name: &str, status: &str| Ticket { id, name: name.to_string(), status: status.to_string(), ..create_test_ticket() };
+        let tickets = vec![ticket(1, "Schema \"v2\"", "closed"), ticket(2, "Migrate data", "blocked"), ticket(3, "Release", "open")];
+        let dependency = |ticket_id: i64, blocker_id: i64| TicketDependency { ticket_id, blocker_id, created_at: create_test_ticket().created_at };
+        let dependencies = vec![dependency(2, 1), dependency(3, 2), dependency(3, 9)];
+
+        let dot = format_dependency_graph(&tickets, &dependencies);
+        assert!(dot.starts_with("digraph tickets {") && dot.ends_with('}'));
+        assert!(dot.contains(r##"  t1 [label="#1 Schema \"v2\"\n(closed)", fillcolor="palegreen"];"##));
+        assert!(dot.contains(r##"  t2 [label="#2 Migrate data\n(blocked)", fillcolor="salmon"];"##));
+        assert!(dot.contains("  t1 -> t2 [style=dashed];"));
+        assert!(dot.contains("  t2 -> t3;"));
+        assert!(!dot.contains("t9"));
+
+        let mut other = ticket(4, "Docs", "testing");
+        other.project = "docs".to_string();
+        assert!(format_dependency_graph(&[tickets[2].clone(), other], &[]).contains(r##"label="#4 Docs\n[docs]\n(testing)", fillcolor="plum""##));
+    }
+    
+    #[test]
+    fn test_no_color_mode() {
+        env::set_var("NO_COLOR", "1");
+        let output = colorize_status("open");
+        // Should not contain ANSI escape codes
+        assert!(!output.to_string().contains("\x1b["));
+        env::remove_var("NO_COLOR");
+    }
+    
+    #[test]
+    fn test_due_date_formatting() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        assert_eq!(format_due_date(today, today), "2024-03-20 (today)");
+        assert_eq!(
+            format_due_date(NaiveDate::from_ymd_opt(2024, 3, 25).unwrap(), today),
+            "2024-03-25 (in 5 days)"
+        );
+        assert_eq!(
+            format_due_date(NaiveDate::from_ymd_opt(2024, 3, 19).unwrap(), today),
+            "2024-03-19 (overdue by 1 day)"
+        );
+    }
+
+    #[test]
+    fn test_board_formatting() {
+        let mut tickets = vec![create_test_ticket(), create_test_ticket(), create_test_ticket()];
+        tickets[0].status = "closed".to_string();
+        tickets[1].id = 2;
+        tickets[1].name = "Second".to_string();
+        tickets[2].id = 3;
+        tickets[2].name = "Third".to_string();
+
+        let output = format_board(&tickets, false, &ProjectSettings::default());
+        let header = output.lines().nth(1).unwrap();
+        assert!(header.find("open (2)").unwrap() < header.find("closed (1)").unwrap());
+        // Tickets keep their given order within a column
+        assert!(output.find("#2 Second").unwrap() < output.find("#3 Third").unwrap());
+        assert!(!output.contains("[test_project]"));
+        assert!(format_board(&tickets, true, &ProjectSettings::default()).contains("[test_project]"));
+
+        tickets[1].status = "testing".to_string();
+        let header = format_board(&tickets, false, &ProjectSettings::default()).lines().nth(1).unwrap().to_string();
+        assert!(header.find("testing (1)").unwrap() < header.find("closed (1)").unwrap());
+        let reversed = ProjectSettings::from_pairs([("statuses".to_string(), "closed,testing,open".to_string())]);
+        let header = format_board(&tickets, false, &reversed).lines().nth(1).unwrap().to_string();
+        assert!(header.find("closed (1)").unwrap() < header.find("testing (1)").unwrap());
+
+        // WIP limits show in the column headings, and a column past its limit is flagged
+        colored::control::set_override(false);
+        let limited = ProjectSettings::from_pairs([("wip_limits".to_string(), "open:1,closed:1".to_string())]);
+        let header = format_board(&tickets, false, &limited).lines().nth(1).unwrap().to_string();
+        assert!(header.contains("open (1/1)") && header.contains("closed (1/1)"));
+        tickets[1].status = "open".to_string();
+        let header = format_board(&tickets, false, &limited).lines().nth(1).unwrap().to_string();
+        assert!(header.contains("open (2/1, over limit)"));
+        let plain = format_board_plain(&tickets, &board_statuses(&tickets, &limited), false, &limited);
+        assert!(plain.contains("open: 2 of at most 1 tickets (over limit)"));
+    }
+
+    #[test]
+    fn test_heatmap_formatting() {
+        colored::control::set_override(false);
+        // Wednesday
+        let end = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let mut daily = HashMap::new();
+        daily.insert(NaiveDate::from_ymd_opt(2024, 3, 18).unwrap(), 7.0);
+        daily.insert(NaiveDate::from_ymd_opt(2024, 3, 12).unwrap(), 1.5);
+
+        let output = format_heatmap(&daily, end, 4);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[1].trim_end(), "Mon · · · █");
+        assert_eq!(lines[2].trim_end(), "    · · ░ ·");
+        assert_eq!(lines[3].trim_end(), "Wed · · · ·");
+        // Days after `end` are left blank
+        assert_eq!(lines[4].trim_end(), "    · · ·");
+        assert!(output.contains("8.5 hours over 2 active days"));
+        assert!(output.contains("busiest week of 2024-03-18 (7.0 hours)"));
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_plain_formatting() {
+        let mut ticket = create_test_ticket();
+        ticket.due_date = NaiveDate::from_ymd_opt(2024, 3, 20);
+        let list = format_ticket_list_plain(std::slice::from_ref(&ticket), &HashMap::from([(1, (3, 7))]));
+        assert!(list.starts_with("Ticket #1: Test ticket. Project: test_project. Status: open. Updated: 2022-01-20. Checklist: 3/7 done\n"));
+        assert!(list.contains("Summary: 1 tickets (1 open, 0 closed)"));
+
+        let link = TicketUrl {
+            id: 1,
+            ticket_id: 1,
+            url: "https://example.com/pr/7".to_string(),
+            label: Some("PR".to_string()),
+            created_at: ticket.created_at,
+        };
+        let item = |position: i64, text: &str, done: bool| ChecklistItem {
+            id: position,
+            ticket_id: 1,
+            position,
+            text: text.to_string(),
+            done,
+            created_at: ticket.created_at,
+        };
+        let checklist = [item(1, "Write migration", true), item(2, "Add command", false)];
+        let details = format_ticket_details_plain(&ticket, &[], &[link], &checklist, &["auth".to_string()]);
+        assert!(details.contains("Checklist (1/2 done):\nItem 1, done: Write migration\nItem 2, not done: Add command"));
+        assert!(details.contains("\nStatus: open\nCreated: 2022-01-20\nDue: 2024-03-20"));
+        assert!(details.contains("\nTags: #auth\n"));
+        assert!(details.contains("Links (1):\nLink 1: PR: https://example.com/pr/7"));
+        assert!(details.contains("Description:\nA test description"));
+
+        let related = format_related_tickets_plain(&[(&ticket, 0.456)]);
+        assert_eq!(related, "Ticket #1: Test ticket. Project: test_project. Status: open. Match: 46%");
+
+        let board = format_board_plain(
+            std::slice::from_ref(&ticket),
+            &board_statuses(&[ticket.clone()], &ProjectSettings::default()),
+            true,
+            &ProjectSettings::default(),
+        );
+        assert_eq!(board, "open: 1 tickets\n  Ticket #1: Test ticket. Project: test_project");
+
+        let end = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let mut daily = HashMap::new();
+        daily.insert(NaiveDate::from_ymd_opt(2024, 3, 18).unwrap(), 7.0);
+        let (weekly, summary) = heatmap_totals(&daily, NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(), end);
+        let heatmap = format_heatmap_plain(&weekly, &summary, NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(), 2);
+        assert!(heatmap.starts_with("Week of 2024-03-11: 0.0 hours\nWeek of 2024-03-18: 7.0 hours\n7.0 hours over 1 active day"));
+
+        for output in [list, details, related, board, heatmap] {
+            assert!(output.is_ascii(), "non-ASCII in plain output: {}", output);
+        }
+    }
+
+    #[test]
+    fn test_status_time_formatting() {
+        let rows = [
+            StatusTime { status: "testing".to_string(), total: Duration::hours(3), visits: 2 },
+            StatusTime { status: "open".to_string(), total: Duration::hours(2), visits: 1 },
+            StatusTime { status: "closed".to_string(), total: Duration::zero(), visits: 1 },
+        ];
+        let refs: Vec<&StatusTime> = rows.iter().collect();
+        assert_eq!(
+            format_status_time_plain(&refs[..2]),
+            "testing: 3h 00m in total, entered 2 times, 1h 30m on average\nopen: 2h 00m in total, entered 1 times, 2h 00m on average"
+        );
+
+        let table = format_status_time(&rows);
+        assert!(table.find("open").unwrap() < table.find("testing").unwrap());
+        assert!(!table.contains("closed"));
+    }
+
+    #[test]
+    fn test_checklist_formatting() {
+        let ticket = create_test_ticket();
+        let item = |position: i64, done: bool| ChecklistItem {
+            id: position,
+            ticket_id: 1,
+            position,
+            text: format!("Step {}", position),
+            done,
+            created_at: ticket.created_at,
+        };
+        let checklist = format_checklist(&[item(1, true), item(2, false)]);
+        assert!(checklist.contains("Checklist (1/2 done):"));
+        assert!(checklist.contains("[x] 1. Step 1"));
+        assert!(checklist.contains("[ ] 2. Step 2"));
+
+        let tickets = vec![ticket.clone()];
+        assert!(!format_ticket_list(&tickets).contains("Checklist"));
+        let list = format_ticket_list_with_checklists(&tickets, &HashMap::from([(1, (1, 2))]));
+        assert!(list.contains("Checklist") && list.contains("1/2"));
+
+        let severe = Ticket { id: 2, severity: Some("S2".to_string()), ..ticket.clone() };
+        let list = format_ticket_list(&[ticket.clone(), severe]);
+        let header = list.lines().nth(1).unwrap();
+        assert!(header.find("Severity").unwrap() > header.find("Status").unwrap());
+        assert!(list.contains("🟠 S2"));
+    }
+
+    #[test]
+    fn test_comment_threads() {
+        let ticket = create_test_ticket();
+        let comment = |id: i64, minutes: i64, parent: Option<i64>| Comment {
+            id,
+            ticket_id: 1,
+            content: format!("Comment body {}", id),
+            created_at: ticket.created_at + Duration::minutes(minutes),
+            updated_at: ticket.created_at + Duration::minutes(minutes),
+            parent_comment_id: parent,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+        };
+        // Newest first, as returned by the database
+        let comments = vec![comment(4, 30, Some(1)), comment(3, 20, None), comment(2, 10, Some(1)), comment(1, 0, None)];
+
+        let order: Vec<(i64, usize)> = thread_comments(&comments).iter().map(|(c, depth)| (c.id, *depth)).collect();
+        assert_eq!(order, vec![(3, 0), (1, 0), (2, 1), (4, 1)]);
+
+        let details = format_ticket_details(&ticket, &comments, &[], &[], &[], &[]);
+        assert!(details.contains("\n    │ Comment body 2"));
+        assert!(details.contains("\n│ Comment body 3"));
+
+        let plain = format_ticket_details_plain(&ticket, &comments, &[], &[], &[]);
+        assert!(plain.contains("Comment 3 of 4, reply to comment 2,"));
+        assert!(plain.contains("Comment 2 of 4, 2022-01-20"));
+    }
+
+    #[test]
+    fn test_event_formatting() {
+        let event = |id: i64, action: &str, before: Option<&str>, after: Option<&str>| Event {
+            id,
+            command: Some("ltm ticket rename 7 Renamed".to_string()),
+            entity: "ticket".to_string(),
+            entity_id: 7,
+            action: action.to_string(),
+            before: before.map(str::to_string),
+            after: after.map(str::to_string),
+            created_at: create_test_ticket().created_at,
+        };
+        let update = event(
+            2,
+            "update",
+            Some(r#"{"name":"Old","status":"open","updated_at":"a"}"#),
+            Some(r#"{"name":"Renamed","status":"open","updated_at":"b","rank":1}"#),
+        );
+        assert_eq!(update.changed_fields(), vec!["name".to_string(), "rank".to_string()]);
+        assert_eq!(event_fields(&event(1, "create", None, Some(r#"{"name":"Old"}"#))), "");
+
+        let table = format_events(&[update]);
+        assert!(table.contains("update ticket #7") && table.contains("name, rank"));
+        assert!(format_events(&[]).contains(t("events.empty")));
+    }
+
+    #[test]
+    fn test_recent_formatting() {
+        let now = create_test_ticket().created_at;
+        let recent = |ticket_id: i64, used_at: NaiveDateTime| RecentTicket {
+            ticket_id,
+            project: "web".to_string(),
+            name: "Fix login".to_string(),
+            status: "open".to_string(),
+            used_at,
+            command: Some("ltm show 12".to_string()),
+        };
+        let table = format_recent(&[recent(12, now - Duration::minutes(65)), recent(7, now - Duration::days(3))], now);
+        assert!(table.contains("1h 05m ago") && table.contains("ltm show 12"));
+        let older = (now - Duration::days(3)).and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string();
+        assert!(table.contains(&older));
+        assert!(format_recent(&[recent(12, now)], now).contains("1m ago"));
+        assert!(format_recent(&[], now).contains(t("recent.empty")));
+    }
+
+    #[test]
+    fn test_time_report_formatting() {
+        let row = |ticket_id: i64, name: &str, minutes: i64| TicketHours {
+            ticket_id,
+            project: "web".to_string(),
+            name: name.to_string(),
+            status: "open".to_string(),
+            entries: 2,
+            minutes,
+            hours: minutes as f64 / 60.0,
+            billable_minutes: minutes,
+            rate: None,
+            amount: None,
+            currency: None,
+        };
+        let mut signup = row(2, "Signup", 45);
+        signup.billable_minutes = 30;
+        let rows = vec![row(1, "Login, \"SSO\"", 210), signup.with_default_rate(Some(95.0))];
+
+        let csv = format_time_report_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "ticket_id,project,name,status,entries,minutes,hours,billable_minutes,billable_hours,rate,amount,currency");
+        assert_eq!(lines[1], "1,web,\"Login, \"\"SSO\"\"\",open,2,210,3.50,210,3.50,,,");
+        assert_eq!(lines[2], "2,web,Signup,open,2,45,0.75,30,0.50,95.00,47.50,");
+        assert_eq!(lines[3], ",,Total,,4,255,4.25,240,4.00,,47.50,");
+
+        let currencies = Currencies::new();
+        let plain = format_time_report_plain(&rows, 255, &currencies);
+        assert!(plain.contains("Ticket 1, Login, \"SSO\" (web): 3.50 hours in 2 entries\n"));
+        assert!(plain.contains("Ticket 2, Signup (web): 0.75 hours in 2 entries; 0.50 billable, amount 47.50"));
+        assert!(plain.ends_with("Total: 4.25 hours across 2 tickets; 4.00 billable, amount 47.50"));
+
+        // Amounts in two currencies are totalled apart
+        let mut sso = row(1, "SSO", 60).with_default_rate(Some(100.0));
+        sso.currency = Some("GBP".to_string());
+        let rows = vec![sso, row(2, "Signup", 30).with_default_rate(Some(90.0)).with_default_currency(Some("EUR"))];
+        let csv = format_time_report_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[3], ",,Total,,4,90,1.50,90,1.50,,,");
+        assert_eq!(lines[4..], [",,Total EUR,,,,,,,,45.00,EUR", ",,Total GBP,,,,,,,,100.00,GBP"]);
+        let plain = format_time_report_plain(&rows, 90, &currencies);
+        assert!(plain.contains("(web): 1.00 hours in 2 entries; amount GBP 100.00"));
+        assert!(plain.ends_with("; amount EUR 45.00, GBP 100.00"));
+    }
+
+    #[test]
+    fn test_time_export_csv() {
+        let entry = |id: i64, minutes: i64, billable: bool, note: Option<&str>| TimeLogEntry {
+            id,
+            project: "web".to_string(),
+            ticket_id: 14,
+            ticket_name: "Login, \"SSO\"".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 5, 6).unwrap(),
+            minutes,
+            billable,
+            note: note.map(str::to_string),
+        };
+        let csv = format_time_export_csv(&[entry(1, 150, true, Some("auth, again")), entry(2, 20, false, None)]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "project,ticket_id,ticket,date,hours,billable,note");
+        assert_eq!(lines[1], "web,14,\"Login, \"\"SSO\"\"\",2024-05-06,2.50,yes,\"auth, again\"");
+        assert_eq!(lines[2], "web,14,\"Login, \"\"SSO\"\"\",2024-05-06,0.33,no,");
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_agenda_formatting() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 8).unwrap();
+        let now = today.and_hms_opt(10, 5, 0).unwrap();
+        let mut overdue = create_test_ticket();
+        overdue.due_date = Some(today - Duration::days(2));
+        let mut working = create_test_ticket();
+        working.id = 2;
+        working.name = "Working on it".to_string();
+        let timer = ActiveTimer {
+            ticket_id: 2,
+            started_at: today.and_hms_opt(9, 0, 0).unwrap(),
+            segment_started_at: today.and_hms_opt(9, 0, 0).unwrap(),
+            paused_at: None,
+            elapsed_seconds: 0,
+            note: None,
+        };
+
+        let agenda = format_agenda(today, &[overdue], &[working], &[(timer, "Working on it".to_string())], 135, now);
+        assert!(agenda.contains("Today, 2024-05-08"));
+        assert!(agenda.contains("#1 Test ticket [test_project] 2024-05-06 (overdue by 2 days)"));
+        assert!(agenda.contains("In progress (1)"));
+        assert!(agenda.contains("#2 Working on it 1h 05m running"));
+        assert!(agenda.ends_with("Logged today: 2h 15m"));
+
+        let quiet = format_agenda(today, &[], &[], &[], 0, now);
+        assert!(quiet.contains("Nothing due and nothing in progress"));
+        assert!(quiet.ends_with("Logged today: 0m"));
+    }
+
+    #[test]
+    fn test_week_formatting() {
+        // 2024-05-08 is a Wednesday
+        let today = NaiveDate::from_ymd_opt(2024, 5, 8).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let ticket = |id: i64, due: NaiveDate, estimate: Option<i64>| Ticket {
+            id,
+            name: format!("Ticket {}", id),
+            due_date: Some(due),
+            estimate_minutes: estimate,
+            ..create_test_ticket()
+        };
+        let tickets = vec![
+            ticket(1, monday - Duration::days(3), Some(60)),
+            ticket(2, monday, None),
+            ticket(3, today, Some(90)),
+            ticket(4, today, None),
+            ticket(5, monday + Duration::days(4), Some(240)),
+        ];
+
+        let week = format_week(monday, today, &tickets);
+        let lines: Vec<&str> = week.lines().collect();
+        assert!(lines[0].contains("Week of 2024-05-06"));
+        assert!(lines[1].contains("Overdue (2)"));
+        assert!(week.contains("#1 Ticket 1 [test_project] ~1h 2024-05-03 (overdue by 5 days)"));
+        assert!(week.contains("#2 Ticket 2 [test_project] 2024-05-06 (overdue by 2 days)"));
+        assert!(lines.iter().any(|l| l.starts_with("Mon 05-06") && l.ends_with("nothing due")));
+        assert!(lines.iter().any(|l| l.starts_with("Wed 05-08 (today)") && l.contains("1h 30m  1 without estimate")));
+        assert!(week.contains("   #5 Ticket 5 [test_project] ~4h"));
+        assert!(lines.last().unwrap().ends_with("Estimated: 5h 30m across 3 tickets"));
+    }
+
+    #[test]
+    fn test_calendar_formatting() {
+        // May 2024 starts on a Wednesday
+        let month = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        let due = vec![Ticket { due_date: Some(day(7)), ..create_test_ticket() }];
+        let logged = HashMap::from([(day(7), 1.5), (day(31), 2.0), (day(9), 0.0)]);
+
+        let calendar = format_calendar(month, day(20), &due, &logged);
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert_eq!(lines[0].trim(), "May 2024");
+        assert_eq!(lines[1], "Mon  Tue  Wed  Thu  Fri  Sat  Sun");
+        assert_eq!(lines[2], "            1    2    3    4    5");
+        assert!(lines[3].starts_with("  6    7!+  8    9   10"));
+        assert!(lines[6].ends_with(" 31+"));
+        assert!(calendar.contains("Due in May 2024 (1)"));
+        assert!(calendar.ends_with("   05-07 #1 Test ticket [test_project]"));
+    }
+
+    #[test]
+    fn test_trends_formatting() {
+        let month = |m: u32, opened: i64, closed: i64, hours: f64| MonthTrend {
+            month: NaiveDate::from_ymd_opt(2024, m, 1).unwrap(),
+            opened,
+            closed,
+            hours,
+        };
+        let trends = vec![month(1, 6, 2, 12.0), month(2, 0, 3, 0.0), month(3, 1, 1, 3.5)];
+
+        assert_eq!(trend_bar(6.0, 6.0), "█".repeat(TREND_BAR_WIDTH));
+        assert_eq!(trend_bar(0.1, 6.0), "█");
+        assert_eq!(trend_bar(0.0, 6.0), "");
+
+        let chart = format_trends(&trends);
+        assert!(chart.contains("Jan 2024"));
+        assert!(chart.contains("Backlog grew by 1 tickets over 3 months"));
+
+        let plain = format_trends_plain(&trends);
+        assert!(plain.contains("Feb 2024: 0 opened, 3 closed, 0.0 hours"));
+        assert!(format_trends_plain(&trends[1..]).contains("Backlog shrank by 3 tickets over 2 months"));
+    }
+
     #[test]
     fn test_text_truncation() {
         let long_text = "This is a very long text that should be truncated";
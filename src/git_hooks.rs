@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Marker line identifying hooks written by ltm
+const HOOK_MARKER: &str = "# Installed by ltm";
+
+/// A ticket reference found in a commit message
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitReference {
+    pub ticket_id: i64,
+    /// Raw duration string such as "1h30m", if one was given
+    pub duration: Option<String>,
+    /// Whether the message asks for the ticket to be closed
+    pub closes: bool,
+}
+
+/// Extract ticket references from a commit message.
+///
+/// Recognised forms:
+/// - `ltm #12` mentions the ticket
+/// - `(ltm #12, 1h30m)` mentions the ticket and logs time
+/// - `closes ltm #12` (also close/fixes/resolves) closes the ticket
+pub fn parse_commit_message(message: &str) -> Vec<CommitReference> {
+    let mention = Regex::new(r"(?i)\bltm\s+#(\d+)").unwrap();
+    let timed = Regex::new(r"(?i)\(\s*ltm\s+#(\d+)\s*,\s*([^)]+?)\s*\)").unwrap();
+    let closing = Regex::new(r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?)\s+ltm\s+#(\d+)").unwrap();
+
+    let mut references: Vec<CommitReference> = Vec::new();
+    for caps in mention.captures_iter(message) {
+        let Ok(ticket_id) = caps[1].parse::<i64>() else { continue };
+        if !references.iter().any(|r| r.ticket_id == ticket_id) {
+            references.push(CommitReference { ticket_id, duration: None, closes: false });
+        }
+    }
+
+    for caps in timed.captures_iter(message) {
+        if let Ok(ticket_id) = caps[1].parse::<i64>() {
+            if let Some(existing) = references.iter_mut().find(|r| r.ticket_id == ticket_id) {
+                existing.duration.get_or_insert_with(|| caps[2].to_string());
+            }
+        }
+    }
+
+    for caps in closing.captures_iter(message) {
+        if let Ok(ticket_id) = caps[1].parse::<i64>() {
+            if let Some(existing) = references.iter_mut().find(|r| r.ticket_id == ticket_id) {
+                existing.closes = true;
+            }
+        }
+    }
+
+    references
+}
+
+/// Locate the hooks directory of a repository (honours core.hooksPath and worktrees)
+pub fn hooks_dir(repo: Option<&str>) -> Result<PathBuf> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = repo {
+        cmd.arg("-C").arg(dir);
+    }
+    let output = cmd
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("Not a git repository: {}", repo.unwrap_or("."));
+    }
+
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(match repo {
+        Some(dir) if path.is_relative() => Path::new(dir).join(path),
+        _ => path,
+    })
+}
+
+/// Write the post-commit hook, refusing to clobber foreign hooks unless forced
+pub fn install_post_commit_hook(dir: &Path, ltm_binary: &str, force: bool) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let hook_path = dir.join("post-commit");
+
+    if hook_path.exists() && !force {
+        let existing = fs::read_to_string(&hook_path).unwrap_or_default();
+        if !existing.contains(HOOK_MARKER) {
+            anyhow::bail!(
+                "A post-commit hook already exists at {}. Use --force to replace it.",
+                hook_path.display()
+            );
+        }
+    }
+
+    let script = format!(
+        "#!/bin/sh\n{}: records ticket references from commit messages\n\"{}\" git post-commit || true\n",
+        HOOK_MARKER, ltm_binary
+    );
+    fs::write(&hook_path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(hook_path)
+}
+
+/// Read the abbreviated hash and full message of HEAD
+pub fn read_head_commit() -> Result<(String, String)> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%h%n%B"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("Could not read the latest commit");
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).to_string();
+    let (hash, message) = text.split_once('\n').unwrap_or((text.as_str(), ""));
+    Ok((hash.trim().to_string(), message.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference_with_duration() {
+        let refs = parse_commit_message("Fix login redirect (ltm #12, 1h30m)");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].ticket_id, 12);
+        assert_eq!(refs[0].duration.as_deref(), Some("1h30m"));
+        assert!(!refs[0].closes);
+    }
+
+    #[test]
+    fn test_parse_closing_reference() {
+        let refs = parse_commit_message("Handle expired tokens\n\nCloses ltm #7, refs ltm #8");
+        assert_eq!(refs.len(), 2);
+        assert!(refs.iter().any(|r| r.ticket_id == 7 && r.closes));
+        assert!(refs.iter().any(|r| r.ticket_id == 8 && !r.closes));
+    }
+
+    #[test]
+    fn test_parse_without_references() {
+        assert!(parse_commit_message("Bump dependencies (#12)").is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+
+use crate::db::Database;
+use crate::models::{ActiveTimer, Comment, ProjectSummary, Ticket, TimeLog};
+use crate::validation::{validate_project_name, validate_status};
+
+/// Read-only GraphQL schema over the ticket database
+pub type LtmSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the schema with the database available to resolvers
+pub fn build_schema(db: Database) -> LtmSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription).data(db).finish()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Tickets, optionally filtered by project and status
+    async fn tickets(
+        &self,
+        ctx: &Context<'_>,
+        project: Option<String>,
+        status: Option<String>,
+        #[graphql(desc = "updated, created, status, project, or rank", default_with = "\"updated\".to_string()")]
+        sort: String,
+        limit: Option<usize>,
+    ) -> Result<Vec<TicketNode>> {
+        let db = ctx.data::<Database>()?;
+        let project = project.as_deref().map(validate_project_name).transpose()?;
+        let status = status.as_deref().map(validate_status).transpose()?;
+        let mut tickets = db.list_tickets_filtered(project.as_deref(), status.as_deref(), &sort, false).await?;
+        if let Some(limit) = limit {
+            tickets.truncate(limit);
+        }
+        Ok(tickets.into_iter().map(TicketNode).collect())
+    }
+
+    /// A single ticket by ID
+    async fn ticket(&self, ctx: &Context<'_>, id: i64) -> Result<Option<TicketNode>> {
+        let db = ctx.data::<Database>()?;
+        Ok(db.get_ticket(id).await?.map(TicketNode))
+    }
+
+    /// All project names
+    async fn projects(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        Ok(ctx.data::<Database>()?.list_projects().await?)
+    }
+
+    /// Ticket counts and logged time for a project
+    async fn project_summary(&self, ctx: &Context<'_>, project: String) -> Result<ProjectSummaryNode> {
+        let project = validate_project_name(&project)?;
+        Ok(ProjectSummaryNode(ctx.data::<Database>()?.get_project_summary(&project).await?))
+    }
+
+    /// Running and paused timers
+    async fn timers(&self, ctx: &Context<'_>) -> Result<Vec<TimerNode>> {
+        Ok(ctx.data::<Database>()?.list_timers().await?.into_iter().map(TimerNode).collect())
+    }
+}
+
+pub struct TicketNode(Ticket);
+
+#[Object(name = "Ticket")]
+impl TicketNode {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    /// Stays the same across machines, unlike `id`
+    async fn uuid(&self) -> &str {
+        &self.0.uuid
+    }
+
+    /// Identity from `[user]` in config.toml of whoever created it
+    async fn created_by(&self) -> Option<&str> {
+        self.0.created_by.as_deref()
+    }
+
+    async fn updated_by(&self) -> Option<&str> {
+        self.0.updated_by.as_deref()
+    }
+
+    async fn project(&self) -> &str {
+        &self.0.project
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    async fn status(&self) -> &str {
+        &self.0.status
+    }
+
+    async fn created_at(&self) -> NaiveDateTime {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> NaiveDateTime {
+        self.0.updated_at
+    }
+
+    async fn due_date(&self) -> Option<NaiveDate> {
+        self.0.due_date
+    }
+
+    /// Position in the project's backlog (1 = top)
+    async fn rank(&self) -> i64 {
+        self.0.rank
+    }
+
+    /// low, medium, high, or critical
+    async fn priority(&self) -> Option<&str> {
+        self.0.priority.as_deref()
+    }
+
+    /// Estimated effort in minutes
+    async fn estimate_minutes(&self) -> Option<i64> {
+        self.0.estimate_minutes
+    }
+
+    async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        Ok(ctx.data::<Database>()?.get_ticket_tags(self.0.id).await?)
+    }
+
+    async fn comments(&self, ctx: &Context<'_>) -> Result<Vec<CommentNode>> {
+        let comments = ctx.data::<Database>()?.get_comments(self.0.id).await?;
+        Ok(comments.into_iter().map(CommentNode).collect())
+    }
+
+    async fn time_logs(&self, ctx: &Context<'_>) -> Result<Vec<TimeLogNode>> {
+        let logs = ctx.data::<Database>()?.get_time_logs(self.0.id).await?;
+        Ok(logs.into_iter().map(TimeLogNode).collect())
+    }
+
+    /// Total logged time in hours
+    async fn total_hours(&self, ctx: &Context<'_>) -> Result<f64> {
+        let logs = ctx.data::<Database>()?.get_time_logs(self.0.id).await?;
+        Ok(logs.iter().map(|l| l.hours as f64 + l.minutes as f64 / 60.0).sum())
+    }
+}
+
+pub struct CommentNode(Comment);
+
+#[Object(name = "Comment")]
+impl CommentNode {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    async fn uuid(&self) -> &str {
+        &self.0.uuid
+    }
+
+    /// Identity from `[user]` in config.toml of whoever created it
+    async fn created_by(&self) -> Option<&str> {
+        self.0.created_by.as_deref()
+    }
+
+    async fn updated_by(&self) -> Option<&str> {
+        self.0.updated_by.as_deref()
+    }
+
+    async fn ticket_id(&self) -> i64 {
+        self.0.ticket_id
+    }
+
+    async fn content(&self) -> &str {
+        &self.0.content
+    }
+
+    async fn created_at(&self) -> NaiveDateTime {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> NaiveDateTime {
+        self.0.updated_at
+    }
+
+    async fn parent_comment_id(&self) -> Option<i64> {
+        self.0.parent_comment_id
+    }
+}
+
+pub struct TimeLogNode(TimeLog);
+
+#[Object(name = "TimeLog")]
+impl TimeLogNode {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    async fn uuid(&self) -> &str {
+        &self.0.uuid
+    }
+
+    /// Identity from `[user]` in config.toml of whoever created it
+    async fn created_by(&self) -> Option<&str> {
+        self.0.created_by.as_deref()
+    }
+
+    async fn updated_by(&self) -> Option<&str> {
+        self.0.updated_by.as_deref()
+    }
+
+    async fn ticket_id(&self) -> i64 {
+        self.0.ticket_id
+    }
+
+    async fn hours(&self) -> i32 {
+        self.0.hours
+    }
+
+    async fn minutes(&self) -> i32 {
+        self.0.minutes
+    }
+
+    async fn started_at(&self) -> Option<NaiveDateTime> {
+        self.0.started_at
+    }
+
+    async fn ended_at(&self) -> Option<NaiveDateTime> {
+        self.0.ended_at
+    }
+
+    async fn created_at(&self) -> NaiveDateTime {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> NaiveDateTime {
+        self.0.updated_at
+    }
+
+    async fn note(&self) -> Option<&str> {
+        self.0.note.as_deref()
+    }
+}
+
+pub struct ProjectSummaryNode(ProjectSummary);
+
+#[Object(name = "ProjectSummary")]
+impl ProjectSummaryNode {
+    async fn project(&self) -> &str {
+        &self.0.project
+    }
+
+    async fn total_tickets(&self) -> i64 {
+        self.0.total_tickets
+    }
+
+    async fn open_tickets(&self) -> i64 {
+        self.0.open_tickets
+    }
+
+    async fn closed_tickets(&self) -> i64 {
+        self.0.closed_tickets
+    }
+
+    async fn total_time_hours(&self) -> f64 {
+        self.0.total_time_hours
+    }
+}
+
+pub struct TimerNode(ActiveTimer);
+
+#[Object(name = "Timer")]
+impl TimerNode {
+    async fn ticket(&self, ctx: &Context<'_>) -> Result<Option<TicketNode>> {
+        Ok(ctx.data::<Database>()?.get_ticket(self.0.ticket_id).await?.map(TicketNode))
+    }
+
+    async fn started_at(&self) -> NaiveDateTime {
+        self.0.started_at
+    }
+
+    async fn paused(&self) -> bool {
+        self.0.is_paused()
+    }
+
+    /// Tracked time so far, excluding pauses
+    async fn elapsed_seconds(&self) -> i64 {
+        self.0.elapsed(Utc::now().naive_utc()).num_seconds()
+    }
+}
@@ -0,0 +1,654 @@
+use std::fmt::Display;
+use std::sync::RwLock;
+
+/// Environment variable selecting the message language, e.g. `LTM_LOCALE=de`
+pub const LOCALE_ENV: &str = "LTM_LOCALE";
+
+/// Languages with a message catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    /// Parse a language tag such as `de`, `de-AT` or `de_DE.UTF-8`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['_', '-', '.', '@']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => EN,
+            Locale::De => DE,
+        }
+    }
+}
+
+static CURRENT: RwLock<Locale> = RwLock::new(Locale::En);
+
+/// Set the locale used by `t` and `tf`
+pub fn set_locale(locale: Locale) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+/// The locale used by `t` and `tf`
+pub fn current_locale() -> Locale {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Pick a locale from `LTM_LOCALE`, then the config file, then `LC_ALL`, `LC_MESSAGES` and `LANG`.
+///
+/// Unsupported languages are skipped, so `LANG=fr_FR.UTF-8` falls back to English.
+pub fn detect_locale(configured: Option<&str>) -> Locale {
+    let env = |name: &str| std::env::var(name).ok();
+    [env(LOCALE_ENV), configured.map(str::to_string), env("LC_ALL"), env("LC_MESSAGES"), env("LANG")]
+        .into_iter()
+        .flatten()
+        .find_map(|tag| Locale::from_tag(&tag))
+        .unwrap_or_default()
+}
+
+/// Look up a message, falling back to English and then to the ID itself
+pub fn message(locale: Locale, id: &'static str) -> &'static str {
+    lookup(locale, id).or_else(|| lookup(Locale::En, id)).unwrap_or(id)
+}
+
+/// Look up a message without any fallback
+pub fn lookup(locale: Locale, id: &str) -> Option<&'static str> {
+    locale.catalog().iter().find(|(key, _)| *key == id).map(|(_, text)| *text)
+}
+
+/// Fill `{0}`, `{1}`, ... placeholders in a template, in one pass so placeholders inside
+/// the values are left alone. Placeholders without a value stay as they are.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    fill_with(template, |key| Some(args.get(key.parse::<usize>().ok()?)?.to_string()))
+}
+
+/// Fill `{name}` placeholders from `fields` the way `fill` does numbered ones
+pub fn fill_named(template: &str, fields: &[(&str, &str)]) -> String {
+    fill_with(template, |key| fields.iter().find(|(field, _)| *field == key).map(|(_, value)| value.to_string()))
+}
+
+fn fill_with(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let filled = rest[1..].find('}').and_then(|end| Some((end, value(&rest[1..end + 1])?)));
+        match filled {
+            Some((end, filled)) => {
+                text.push_str(&filled);
+                rest = &rest[end + 2..];
+            }
+            None => {
+                text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Message in the current locale
+pub fn t(id: &'static str) -> &'static str {
+    message(current_locale(), id)
+}
+
+/// Message in the current locale with its placeholders filled in
+pub fn tf(id: &'static str, args: &[&dyn Display]) -> String {
+    fill(t(id), args)
+}
+
+const EN: &[(&str, &str)] = &[
+    // Errors and hints
+    ("error.prefix", "Error: {0}"),
+    ("warning.prefix", "Warning: {0}"),
+    ("hint.example", "Example: {0}"),
+    ("hint.did_you_mean", "Did you mean: {0}"),
+    ("hint.valid_statuses", "Valid statuses: {0}"),
+    ("error.invalid_ticket_id", "Invalid ticket ID '{0}'. Must be a positive number."),
+    ("error.ticket_not_found", "Ticket #{0} not found."),
+    ("error.invalid_project_name", "Invalid project name '{0}'. Only letters, numbers, hyphens, underscores allowed."),
+    ("error.project_name_pattern", "Invalid project name '{0}'. Project names must match {1} (validation.project_name in config.toml)."),
+    ("error.invalid_time", "Invalid time value '{0}'. Hours must be 0-24, minutes must be 0-59."),
+    ("error.invalid_status", "Invalid status '{0}'. Must be one of: {1}."),
+    ("error.invalid_length", "Invalid {0} length. {0} must be between {1} and {2} characters."),
+    ("error.invalid_priority", "Invalid priority '{0}'. Must be one of: {1}."),
+    ("error.invalid_severity", "Invalid severity '{0}'. Must be one of: {1}."),
+    ("error.invalid_component", "Invalid component '{0}'. Use a letter followed by letters, numbers, hyphens, or underscores."),
+    ("error.unknown_component", "Project '{0}' has no component '{1}'."),
+    ("hint.valid_components", "Components: {0}"),
+    ("error.invalid_release", "Invalid release '{0}'. Use up to 30 letters, numbers, dots, hyphens, or underscores, like v1.4."),
+    ("error.unknown_release", "Project '{0}' has no release '{1}'."),
+    ("hint.valid_releases", "Releases: {0}"),
+    ("error.invalid_tag", "Invalid tag '{0}'. Only letters, numbers, hyphens, underscores allowed."),
+    ("error.invalid_date", "Invalid date '{0}'. Use YYYY-MM-DD, today, tomorrow, a weekday, or an offset like +3d."),
+    ("error.invalid_period", "Invalid period '{0}'. Use a number followed by m, h, d, or w."),
+    ("error.invalid_month", "Invalid month '{0}'. Use YYYY-MM."),
+    ("error.invalid_rate", "Invalid rate '{0}'. Use a positive amount per hour, such as 95 or 87.50."),
+    ("error.invalid_score", "Invalid {0} '{1}'. Use {2}."),
+    ("score.range_reach", "a whole number of people or events per quarter, like 500"),
+    ("score.range_impact", "a number from 0.25 (minimal) to 3 (massive)"),
+    ("score.range_confidence", "a percentage from 0 to 100, like 80%"),
+    ("score.range_effort", "a positive number of person-weeks, like 2.5"),
+    ("error.no_focused_ticket", "No ticket ID given and no ticket is focused."),
+    ("error.no_last_ticket", "No ticket has been used yet for @last to refer to."),
+    ("error.invalid_regex", "Invalid regular expression '{0}': {1}"),
+    ("error.invalid_url", "Invalid URL '{0}'. Use a full address such as https://example.com/pr/12."),
+    ("error.invalid_date_range", "Invalid date range: {0} is after {1}."),
+    ("error.invalid_time_span", "Invalid time span: {0} is not before {1}."),
+    ("error.transition_not_allowed", "Cannot change status from '{0}' to '{1}'."),
+    ("hint.allowed_next_status", "Allowed next statuses: {0}. Use --force to override."),
+    ("hint.no_next_status", "The workflow allows no further status changes. Use --force to override."),
+    ("error.status_not_in_project", "Status '{0}' is not used in project '{1}'."),
+    ("error.unknown_setting", "Unknown project setting '{0}'."),
+    ("hint.setting_keys", "Project settings: {0}"),
+    ("error.invalid_setting", "Invalid value '{0}' for project setting '{1}'."),
+    ("error.missing_required_field", "Project '{0}' requires '{1}' on new tickets."),
+    ("error.missing_status_field", "Project '{0}' requires '{1}' before a ticket moves to '{2}'."),
+    ("error.wip_limit_reached", "Project '{0}' allows at most {1} tickets in '{2}'."),
+    ("hint.wip_limit", "Move a ticket out of that status first, or use --force to override."),
+    ("error.invalid_kind", "Invalid ticket type '{0}'. Must be one of: {1}."),
+    ("field.ticket_name", "ticket name"),
+    ("field.description", "description"),
+    ("field.comment", "comment"),
+    ("field.checklist_item", "checklist item"),
+    ("field.timer_note", "timer note"),
+    // Prompts and progress
+    ("confirm.delete", "Are you sure you want to delete {0}?"),
+    ("confirm.update-status", "Are you sure you want to update status of {0}?"),
+    ("confirm.generic", "Are you sure you want to {0} {1}?"),
+    ("confirm.duplicate", "A similar ticket #{0} exists ('{1}'). Create anyway?"),
+    ("confirm.typed_delete", "Type {0} to delete these tickets"),
+    ("delete.bulk_summary", "{0} tickets will be deleted, together with {1}."),
+    ("delete.bulk_summary_alone", "{0} tickets will be deleted; nothing else is attached to them."),
+    ("delete.with", "{0} with {1}"),
+    ("delete.comment", "{0} comment"),
+    ("delete.comments", "{0} comments"),
+    ("delete.time_log", "{0} time log totaling {1}"),
+    ("delete.time_logs", "{0} time logs totaling {1}"),
+    ("delete.link", "{0} link"),
+    ("delete.links", "{0} links"),
+    ("delete.checklist_item", "{0} checklist item"),
+    ("delete.checklist_items", "{0} checklist items"),
+    ("delete.dependency", "{0} dependency"),
+    ("delete.dependencies", "{0} dependencies"),
+    ("delete.synced_issue", "{0} link to a synced issue"),
+    ("delete.synced_issues", "{0} links to synced issues"),
+    ("cleanup.archived", "Archived {0} tickets in {1}"),
+    ("cleanup.deleted", "Deleted {0} tickets in {1}"),
+    ("cleanup.would_archive", "Would archive {0} tickets in {1}"),
+    ("cleanup.would_delete", "Would delete {0} tickets in {1}"),
+    ("warning.similar_ticket", "A similar ticket #{0} exists ('{1}')"),
+    ("feedback.start_tracking", "Starting time tracking for ticket {0}"),
+    // Ticket list and details
+    ("list.empty", "No tickets found"),
+    ("list.header.id", "ID"),
+    ("list.header.project", "Project"),
+    ("list.header.name", "Name"),
+    ("list.header.status", "Status"),
+    ("list.header.updated", "Updated"),
+    ("list.summary", "Summary: {0} tickets ({1} open, {2} closed)"),
+    ("list.updated", "Updated: {0}"),
+    ("list.pinned", "Pinned"),
+    ("list.header.match", "Match"),
+    ("list.match", "Match: {0}%"),
+    ("related.empty", "No similar tickets found"),
+    ("list.comment_matches", "Matching comments ({0}):"),
+    ("list.header.checklist", "Checklist"),
+    ("list.header.type", "Type"),
+    ("list.header.severity", "Severity"),
+    ("list.header.component", "Component"),
+    ("list.checklist", "Checklist: {0}"),
+    // Checklists
+    ("checklist.progress", "{0}/{1} done"),
+    ("checklist.title", "Checklist ({0}):"),
+    ("checklist.empty", "No checklist items"),
+    ("checklist.item_done", "Item {0}, done: {1}"),
+    ("checklist.item_todo", "Item {0}, not done: {1}"),
+    ("details.ticket", "Ticket #{0}"),
+    ("details.project", "Project: {0}"),
+    ("details.status", "Status: {0}"),
+    ("details.created", "Created: {0}"),
+    ("details.due", "Due: {0}"),
+    ("details.priority", "Priority: {0}"),
+    ("details.estimate", "Estimate: {0}"),
+    ("details.type", "Type: {0}"),
+    ("details.severity", "Severity: {0}"),
+    ("details.component", "Component: {0}"),
+    ("details.release", "Release: {0}"),
+    ("details.score", "Scoring: {0}"),
+    ("details.tags", "Tags: {0}"),
+    ("details.archived", "Archived: {0}"),
+    ("details.description", "Description:"),
+    ("details.comments", "Comments ({0}):"),
+    ("details.comment", "Comment {0} of {1}, {2}:"),
+    ("details.reply", "Comment {0} of {1}, reply to comment {2}, {3}:"),
+    ("details.links", "Links ({0}):"),
+    ("details.link", "Link {0}: {1}"),
+    ("due.today", "today"),
+    ("due.tomorrow", "tomorrow"),
+    ("due.overdue_one", "overdue by {0} day"),
+    ("due.overdue_other", "overdue by {0} days"),
+    ("due.in_days", "in {0} days"),
+    // Project summary
+    ("summary.title", "Project Summary for {0}"),
+    ("summary.total", "Total Tickets: {0}"),
+    ("summary.open", "Open Tickets: {0}"),
+    ("summary.closed", "Closed Tickets: {0}"),
+    ("summary.time", "Total Time: {0} hours"),
+    ("summary.kinds", "By type: {0}"),
+    ("summary.components", "By component: {0}"),
+    ("summary.progress", "Progress: [{0}] {1}%"),
+    ("summary.progress_percent", "Progress: {0}%"),
+    // Board
+    ("board.column", "{0}: {1} tickets"),
+    ("board.column_limit", "{0}: {1} of at most {2} tickets"),
+    ("board.over_limit", "over limit"),
+    // Heatmap
+    ("heatmap.months", "Jan Feb Mar Apr May Jun Jul Aug Sep Oct Nov Dec"),
+    ("heatmap.weekdays", "Mon Tue Wed Thu Fri Sat Sun"),
+    ("heatmap.legend", "Less {0} More"),
+    ("heatmap.total_one", "{0} hours over {1} active day"),
+    ("heatmap.total_other", "{0} hours over {1} active days"),
+    ("heatmap.busiest", "busiest week of {0} ({1} hours)"),
+    ("heatmap.week", "Week of {0}: {1} hours"),
+    // Status time report
+    ("events.empty", "No changes recorded"),
+    ("events.id", "ID"),
+    ("events.when", "When"),
+    ("events.change", "Change"),
+    ("events.fields", "Fields"),
+    ("events.command", "Command"),
+    ("events.change_fields", "{0} ({1})"),
+    ("events.plain", "Event {0}, {1}: {2}. Command: {3}"),
+    ("recent.empty", "No recent tickets"),
+    ("recent.ago", "{0} ago"),
+    ("recent.plain", "Ticket #{0}: {1}. Project: {2}. Status: {3}. Used {4}. Command: {5}"),
+    ("status_time.empty", "No status history recorded"),
+    ("status_time.status", "Status"),
+    ("status_time.time", "Time"),
+    ("status_time.visits", "Times entered"),
+    ("status_time.average", "Average"),
+    ("status_time.plain", "{0}: {1} in total, entered {2} times, {3} on average"),
+    ("release.title", "Release {0} of {1}"),
+    ("release.progress", "Progress: [{0}] {1}% ({2} of {3} done)"),
+    ("release.progress_percent", "Progress: {0}% ({1} of {2} done)"),
+    ("release.remaining", "Remaining open tickets ({0}):"),
+    ("release.complete", "Every ticket in this release is done."),
+    ("release.empty", "No tickets are planned for this release yet."),
+    ("score.title_rice", "Backlog by RICE score (reach × impact × confidence ÷ effort)"),
+    ("score.title_ice", "Backlog by ICE score (impact × confidence ÷ effort)"),
+    ("score.empty", "No backlog ticket has every input yet. Set them with: ltm ticket update <id> reach|impact|confidence|effort <value>"),
+    ("score.header.rank", "#"),
+    ("score.header.reach", "Reach"),
+    ("score.header.impact", "Impact"),
+    ("score.header.confidence", "Confidence"),
+    ("score.header.effort", "Effort"),
+    ("score.header.score", "Score"),
+    ("score.plain", "{0}. #{1} {2} ({3}): score {4}, from {5}"),
+    ("score.unscored", "Not scored yet ({0}):"),
+    ("score.missing", "missing {0}"),
+    ("gantt.title", "Schedule of {0} of {1}"),
+    ("gantt.no_due", "no due date"),
+    ("gantt.plain", "#{0} {1}: from {2} to {3}, {4}"),
+    ("gantt.legend", "▼ today  ◆ release due  █ done  ▓ open, red when overdue  ░ no due date"),
+    ("roadmap.title", "Roadmap"),
+    ("roadmap.title_project", "Roadmap of {0}"),
+    ("roadmap.empty", "No releases yet. Add one with: ltm release create <project> <name> --due <date>"),
+    ("roadmap.no_date", "no date"),
+    ("roadmap.due", "due {0}"),
+    ("roadmap.done", "done"),
+    ("roadmap.on_track", "on track"),
+    ("roadmap.at_risk", "at risk"),
+    ("roadmap.overdue", "overdue"),
+    ("roadmap.work", "{0} of work left"),
+    ("roadmap.work_available", "{0} of work left, {1} available"),
+    ("roadmap.unestimated", "{0} open tickets unestimated"),
+    ("roadmap.late", "{0} tickets due late"),
+    ("roadmap.plain", "{0}, {1}: {2}% done ({3} of {4}), {5}"),
+    ("aging.empty", "No open tickets"),
+    ("aging.project", "Project"),
+    ("aging.total", "Total"),
+    ("aging.oldest", "Oldest open tickets:"),
+    ("aging.days", "{0} days old"),
+    ("aging.plain", "{0}: {1} open tickets, {2}. Oldest: {3}"),
+    ("trends.opened", "Opened"),
+    ("trends.closed", "Closed"),
+    ("trends.hours", "Hours"),
+    ("trends.plain", "{0}: {1} opened, {2} closed, {3} hours"),
+    ("trends.grew", "Backlog grew by {0} tickets over {1} months"),
+    ("trends.shrank", "Backlog shrank by {0} tickets over {1} months"),
+    ("trends.steady", "Backlog unchanged over {0} months"),
+    ("time_report.empty", "No time logged in this period"),
+    ("time_report.ticket", "Ticket"),
+    ("time_report.project", "Project"),
+    ("time_report.name", "Name"),
+    ("time_report.entries", "Entries"),
+    ("time_report.hours", "Hours"),
+    ("time_report.billable", "Billable"),
+    ("time_report.amount", "Amount"),
+    ("time_report.total", "Total"),
+    ("time_report.plain", "Ticket {0}, {1} ({2}): {3} hours in {4} entries"),
+    ("time_report.plain_total", "Total: {0} hours across {1} tickets"),
+    ("time_report.plain_billable", "{0} billable"),
+    ("time_report.plain_amount", "amount {0}"),
+    ("time_export.date", "Date"),
+    ("time_export.note", "Note"),
+    ("time_export.plain", "{0}: ticket {1}, {2} ({3}), {4} hours"),
+    ("agenda.title", "Today, {0}"),
+    ("agenda.due", "Due ({0})"),
+    ("agenda.in_progress", "In progress ({0})"),
+    ("agenda.timers", "Timers ({0})"),
+    ("agenda.timer_running", "{0} running"),
+    ("agenda.timer_paused", "{0} paused"),
+    ("agenda.empty", "Nothing due and nothing in progress"),
+    ("agenda.logged", "Logged today: {0}"),
+    ("week.title", "Week of {0}"),
+    ("week.overdue", "Overdue ({0})"),
+    ("week.nothing", "nothing due"),
+    ("week.unestimated", "{0} without estimate"),
+    ("week.total", "Estimated: {0} across {1} tickets"),
+    ("calendar.legend", "! tickets due   + time logged"),
+    ("calendar.due", "Due in {0} ({1})"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("error.prefix", "Fehler: {0}"),
+    ("warning.prefix", "Warnung: {0}"),
+    ("hint.example", "Beispiel: {0}"),
+    ("hint.did_you_mean", "Meinten Sie: {0}"),
+    ("hint.valid_statuses", "Gültige Status: {0}"),
+    ("error.invalid_ticket_id", "Ungültige Ticket-ID '{0}'. Sie muss eine positive Zahl sein."),
+    ("error.ticket_not_found", "Ticket #{0} wurde nicht gefunden."),
+    ("error.invalid_project_name", "Ungültiger Projektname '{0}'. Erlaubt sind nur Buchstaben, Ziffern, Binde- und Unterstriche."),
+    ("error.project_name_pattern", "Ungültiger Projektname '{0}'. Projektnamen müssen auf {1} passen (validation.project_name in config.toml)."),
+    ("error.invalid_time", "Ungültige Zeitangabe '{0}'. Stunden müssen zwischen 0 und 24, Minuten zwischen 0 und 59 liegen."),
+    ("error.invalid_status", "Ungültiger Status '{0}'. Erlaubt sind: {1}."),
+    ("error.invalid_length", "Ungültige Länge für {0}. {0} muss zwischen {1} und {2} Zeichen lang sein."),
+    ("error.invalid_priority", "Ungültige Priorität '{0}'. Erlaubt sind: {1}."),
+    ("error.invalid_severity", "Ungültiger Schweregrad '{0}'. Erlaubt sind: {1}."),
+    ("error.invalid_component", "Ungültige Komponente '{0}'. Erlaubt sind ein Buchstabe, gefolgt von Buchstaben, Ziffern, Binde- oder Unterstrichen."),
+    ("error.unknown_component", "Projekt '{0}' hat keine Komponente '{1}'."),
+    ("hint.valid_components", "Komponenten: {0}"),
+    ("error.invalid_release", "Ungültiges Release '{0}'. Erlaubt sind bis zu 30 Buchstaben, Ziffern, Punkte, Binde- oder Unterstriche, z. B. v1.4."),
+    ("error.unknown_release", "Projekt '{0}' hat kein Release '{1}'."),
+    ("hint.valid_releases", "Releases: {0}"),
+    ("error.invalid_tag", "Ungültiges Tag '{0}'. Erlaubt sind nur Buchstaben, Ziffern, Binde- und Unterstriche."),
+    ("error.invalid_date", "Ungültiges Datum '{0}'. Erlaubt sind JJJJ-MM-TT, today, tomorrow, ein Wochentag oder ein Abstand wie +3d."),
+    ("error.invalid_period", "Ungültiger Zeitraum '{0}'. Erwartet wird eine Zahl gefolgt von m, h, d oder w."),
+    ("error.invalid_month", "Ungültiger Monat '{0}'. Erwartet wird JJJJ-MM."),
+    ("error.invalid_rate", "Ungültiger Stundensatz '{0}'. Erwartet wird ein positiver Betrag pro Stunde, etwa 95 oder 87.50."),
+    ("error.invalid_score", "Ungültiger Wert für {0}: '{1}'. Erwartet wird {2}."),
+    ("score.range_reach", "eine ganze Zahl erreichter Personen oder Ereignisse pro Quartal, etwa 500"),
+    ("score.range_impact", "eine Zahl von 0.25 (minimal) bis 3 (enorm)"),
+    ("score.range_confidence", "ein Prozentsatz von 0 bis 100, etwa 80%"),
+    ("score.range_effort", "eine positive Zahl an Personenwochen, etwa 2.5"),
+    ("error.no_focused_ticket", "Keine Ticket-ID angegeben und kein Ticket im Fokus."),
+    ("error.no_last_ticket", "Es wurde noch kein Ticket verwendet, auf das sich @last beziehen kann."),
+    ("error.invalid_regex", "Ungültiger regulärer Ausdruck '{0}': {1}"),
+    ("error.invalid_url", "Ungültige URL '{0}'. Erwartet wird eine vollständige Adresse wie https://example.com/pr/12."),
+    ("error.invalid_date_range", "Ungültiger Zeitraum: {0} liegt nach {1}."),
+    ("error.invalid_time_span", "Ungültige Zeitspanne: {0} liegt nicht vor {1}."),
+    ("error.transition_not_allowed", "Statuswechsel von '{0}' nach '{1}' ist nicht erlaubt."),
+    ("hint.allowed_next_status", "Erlaubte nächste Status: {0}. Mit --force überschreiben."),
+    ("hint.no_next_status", "Der Workflow erlaubt keine weiteren Statuswechsel. Mit --force überschreiben."),
+    ("error.status_not_in_project", "Status '{0}' wird im Projekt '{1}' nicht verwendet."),
+    ("error.unknown_setting", "Unbekannte Projekteinstellung '{0}'."),
+    ("hint.setting_keys", "Projekteinstellungen: {0}"),
+    ("error.invalid_setting", "Ungültiger Wert '{0}' für die Projekteinstellung '{1}'."),
+    ("error.missing_required_field", "Projekt '{0}' verlangt für neue Tickets: {1}."),
+    ("error.missing_status_field", "Projekt '{0}' verlangt '{1}', bevor ein Ticket in den Status '{2}' wechselt."),
+    ("error.wip_limit_reached", "Projekt '{0}' erlaubt höchstens {1} Tickets im Status '{2}'."),
+    ("hint.wip_limit", "Zuerst ein Ticket aus diesem Status bewegen oder mit --force überschreiben."),
+    ("error.invalid_kind", "Ungültiger Tickettyp '{0}'. Erlaubt sind: {1}."),
+    ("field.ticket_name", "Ticketname"),
+    ("field.description", "Beschreibung"),
+    ("field.comment", "Kommentar"),
+    ("field.checklist_item", "Checklistenpunkt"),
+    ("field.timer_note", "Timer-Notiz"),
+    ("confirm.delete", "Möchten Sie {0} wirklich löschen?"),
+    ("confirm.update-status", "Möchten Sie den Status von {0} wirklich ändern?"),
+    ("confirm.generic", "Möchten Sie wirklich {0}: {1}?"),
+    ("confirm.duplicate", "Ein ähnliches Ticket #{0} existiert bereits ('{1}'). Trotzdem anlegen?"),
+    ("confirm.typed_delete", "Geben Sie {0} ein, um diese Tickets zu löschen"),
+    ("delete.bulk_summary", "{0} Tickets werden gelöscht, zusammen mit {1}."),
+    ("delete.bulk_summary_alone", "{0} Tickets werden gelöscht; sonst hängt nichts daran."),
+    ("delete.with", "{0} mit {1}"),
+    ("delete.comment", "{0} Kommentar"),
+    ("delete.comments", "{0} Kommentaren"),
+    ("delete.time_log", "{0} Zeiteintrag über {1}"),
+    ("delete.time_logs", "{0} Zeiteinträgen über insgesamt {1}"),
+    ("delete.link", "{0} Link"),
+    ("delete.links", "{0} Links"),
+    ("delete.checklist_item", "{0} Checklistenpunkt"),
+    ("delete.checklist_items", "{0} Checklistenpunkten"),
+    ("delete.dependency", "{0} Abhängigkeit"),
+    ("delete.dependencies", "{0} Abhängigkeiten"),
+    ("delete.synced_issue", "{0} Verknüpfung zu einem synchronisierten Issue"),
+    ("delete.synced_issues", "{0} Verknüpfungen zu synchronisierten Issues"),
+    ("cleanup.archived", "{0} Tickets in {1} archiviert"),
+    ("cleanup.deleted", "{0} Tickets in {1} gelöscht"),
+    ("cleanup.would_archive", "Würde {0} Tickets in {1} archivieren"),
+    ("cleanup.would_delete", "Würde {0} Tickets in {1} löschen"),
+    ("warning.similar_ticket", "Ein ähnliches Ticket #{0} existiert bereits ('{1}')"),
+    ("feedback.start_tracking", "Starte Zeiterfassung für Ticket {0}"),
+    ("list.empty", "Keine Tickets gefunden"),
+    ("list.header.id", "ID"),
+    ("list.header.project", "Projekt"),
+    ("list.header.name", "Name"),
+    ("list.header.status", "Status"),
+    ("list.header.updated", "Aktualisiert"),
+    ("list.summary", "Übersicht: {0} Tickets ({1} offen, {2} geschlossen)"),
+    ("list.updated", "Aktualisiert: {0}"),
+    ("list.pinned", "Angeheftet"),
+    ("list.header.match", "Treffer"),
+    ("list.match", "Übereinstimmung: {0}%"),
+    ("related.empty", "Keine ähnlichen Tickets gefunden"),
+    ("list.comment_matches", "Passende Kommentare ({0}):"),
+    ("list.header.checklist", "Checkliste"),
+    ("list.header.type", "Typ"),
+    ("list.header.severity", "Schwere"),
+    ("list.header.component", "Komponente"),
+    ("list.checklist", "Checkliste: {0}"),
+    ("checklist.progress", "{0}/{1} erledigt"),
+    ("checklist.title", "Checkliste ({0}):"),
+    ("checklist.empty", "Keine Checklistenpunkte"),
+    ("checklist.item_done", "Punkt {0}, erledigt: {1}"),
+    ("checklist.item_todo", "Punkt {0}, offen: {1}"),
+    ("details.ticket", "Ticket #{0}"),
+    ("details.project", "Projekt: {0}"),
+    ("details.status", "Status: {0}"),
+    ("details.created", "Erstellt: {0}"),
+    ("details.due", "Fällig: {0}"),
+    ("details.priority", "Priorität: {0}"),
+    ("details.estimate", "Schätzung: {0}"),
+    ("details.type", "Typ: {0}"),
+    ("details.severity", "Schweregrad: {0}"),
+    ("details.component", "Komponente: {0}"),
+    ("details.release", "Release: {0}"),
+    ("details.score", "Bewertung: {0}"),
+    ("details.tags", "Tags: {0}"),
+    ("details.archived", "Archiviert: {0}"),
+    ("details.description", "Beschreibung:"),
+    ("details.comments", "Kommentare ({0}):"),
+    ("details.comment", "Kommentar {0} von {1}, {2}:"),
+    ("details.reply", "Kommentar {0} von {1}, Antwort auf Kommentar {2}, {3}:"),
+    ("details.links", "Links ({0}):"),
+    ("details.link", "Link {0}: {1}"),
+    ("due.today", "heute"),
+    ("due.tomorrow", "morgen"),
+    ("due.overdue_one", "seit {0} Tag überfällig"),
+    ("due.overdue_other", "seit {0} Tagen überfällig"),
+    ("due.in_days", "in {0} Tagen"),
+    ("summary.title", "Projektübersicht für {0}"),
+    ("summary.total", "Tickets gesamt: {0}"),
+    ("summary.open", "Offene Tickets: {0}"),
+    ("summary.closed", "Geschlossene Tickets: {0}"),
+    ("summary.time", "Gesamtzeit: {0} Stunden"),
+    ("summary.kinds", "Nach Typ: {0}"),
+    ("summary.components", "Nach Komponente: {0}"),
+    ("summary.progress", "Fortschritt: [{0}] {1}%"),
+    ("summary.progress_percent", "Fortschritt: {0}%"),
+    ("board.column", "{0}: {1} Tickets"),
+    ("board.column_limit", "{0}: {1} von höchstens {2} Tickets"),
+    ("board.over_limit", "über dem Limit"),
+    ("heatmap.months", "Jan Feb Mär Apr Mai Jun Jul Aug Sep Okt Nov Dez"),
+    ("heatmap.weekdays", "Mo Di Mi Do Fr Sa So"),
+    ("heatmap.legend", "Weniger {0} Mehr"),
+    ("heatmap.total_one", "{0} Stunden an {1} aktiven Tag"),
+    ("heatmap.total_other", "{0} Stunden an {1} aktiven Tagen"),
+    ("heatmap.busiest", "arbeitsreichste Woche ab {0} ({1} Stunden)"),
+    ("heatmap.week", "Woche ab {0}: {1} Stunden"),
+    ("events.empty", "Keine Änderungen aufgezeichnet"),
+    ("events.id", "ID"),
+    ("events.when", "Wann"),
+    ("events.change", "Änderung"),
+    ("events.fields", "Felder"),
+    ("events.command", "Befehl"),
+    ("events.change_fields", "{0} ({1})"),
+    ("events.plain", "Ereignis {0}, {1}: {2}. Befehl: {3}"),
+    ("recent.empty", "Keine zuletzt verwendeten Tickets"),
+    ("recent.ago", "vor {0}"),
+    ("recent.plain", "Ticket #{0}: {1}. Projekt: {2}. Status: {3}. Verwendet {4}. Befehl: {5}"),
+    ("status_time.empty", "Kein Statusverlauf vorhanden"),
+    ("status_time.status", "Status"),
+    ("status_time.time", "Zeit"),
+    ("status_time.visits", "Wie oft erreicht"),
+    ("status_time.average", "Durchschnitt"),
+    ("status_time.plain", "{0}: {1} insgesamt, {2}-mal erreicht, im Schnitt {3}"),
+    ("release.title", "Release {0} von {1}"),
+    ("release.progress", "Fortschritt: [{0}] {1}% ({2} von {3} erledigt)"),
+    ("release.progress_percent", "Fortschritt: {0}% ({1} von {2} erledigt)"),
+    ("release.remaining", "Verbleibende offene Tickets ({0}):"),
+    ("release.complete", "Alle Tickets dieses Releases sind erledigt."),
+    ("release.empty", "Für dieses Release sind noch keine Tickets geplant."),
+    ("score.title_rice", "Backlog nach RICE-Score (Reichweite × Wirkung × Zuversicht ÷ Aufwand)"),
+    ("score.title_ice", "Backlog nach ICE-Score (Wirkung × Zuversicht ÷ Aufwand)"),
+    ("score.empty", "Noch hat kein Backlog-Ticket alle Werte. Setze sie mit: ltm ticket update <id> reach|impact|confidence|effort <wert>"),
+    ("score.header.rank", "#"),
+    ("score.header.reach", "Reichweite"),
+    ("score.header.impact", "Wirkung"),
+    ("score.header.confidence", "Zuversicht"),
+    ("score.header.effort", "Aufwand"),
+    ("score.header.score", "Score"),
+    ("score.plain", "{0}. #{1} {2} ({3}): Score {4}, aus {5}"),
+    ("score.unscored", "Noch ohne Score ({0}):"),
+    ("score.missing", "es fehlt {0}"),
+    ("gantt.title", "Zeitplan von {0} von {1}"),
+    ("gantt.no_due", "ohne Fälligkeit"),
+    ("gantt.plain", "#{0} {1}: von {2} bis {3}, {4}"),
+    ("gantt.legend", "▼ heute  ◆ Release fällig  █ erledigt  ▓ offen, rot wenn überfällig  ░ ohne Fälligkeit"),
+    ("roadmap.title", "Roadmap"),
+    ("roadmap.title_project", "Roadmap von {0}"),
+    ("roadmap.empty", "Noch keine Releases. Lege eines an mit: ltm release create <projekt> <name> --due <datum>"),
+    ("roadmap.no_date", "ohne Datum"),
+    ("roadmap.due", "fällig {0}"),
+    ("roadmap.done", "erledigt"),
+    ("roadmap.on_track", "im Plan"),
+    ("roadmap.at_risk", "gefährdet"),
+    ("roadmap.overdue", "überfällig"),
+    ("roadmap.work", "{0} Arbeit übrig"),
+    ("roadmap.work_available", "{0} Arbeit übrig, {1} verfügbar"),
+    ("roadmap.unestimated", "{0} offene Tickets ohne Schätzung"),
+    ("roadmap.late", "{0} Tickets zu spät fällig"),
+    ("roadmap.plain", "{0}, {1}: {2}% erledigt ({3} von {4}), {5}"),
+    ("aging.empty", "Keine offenen Tickets"),
+    ("aging.project", "Projekt"),
+    ("aging.total", "Gesamt"),
+    ("aging.oldest", "Älteste offene Tickets:"),
+    ("aging.days", "{0} Tage alt"),
+    ("aging.plain", "{0}: {1} offene Tickets, {2}. Älteste: {3}"),
+    ("trends.opened", "Eröffnet"),
+    ("trends.closed", "Geschlossen"),
+    ("trends.hours", "Stunden"),
+    ("trends.plain", "{0}: {1} eröffnet, {2} geschlossen, {3} Stunden"),
+    ("trends.grew", "Rückstand um {0} Tickets in {1} Monaten gewachsen"),
+    ("trends.shrank", "Rückstand um {0} Tickets in {1} Monaten geschrumpft"),
+    ("trends.steady", "Rückstand in {0} Monaten unverändert"),
+    ("time_report.empty", "In diesem Zeitraum wurde keine Zeit erfasst"),
+    ("time_report.ticket", "Ticket"),
+    ("time_report.project", "Projekt"),
+    ("time_report.name", "Name"),
+    ("time_report.entries", "Einträge"),
+    ("time_report.hours", "Stunden"),
+    ("time_report.billable", "Abrechenbar"),
+    ("time_report.amount", "Betrag"),
+    ("time_report.total", "Summe"),
+    ("time_report.plain", "Ticket {0}, {1} ({2}): {3} Stunden in {4} Einträgen"),
+    ("time_report.plain_total", "Summe: {0} Stunden über {1} Tickets"),
+    ("time_report.plain_billable", "{0} abrechenbar"),
+    ("time_report.plain_amount", "Betrag {0}"),
+    ("time_export.date", "Datum"),
+    ("time_export.note", "Notiz"),
+    ("time_export.plain", "{0}: Ticket {1}, {2} ({3}), {4} Stunden"),
+    ("agenda.title", "Heute, {0}"),
+    ("agenda.due", "Fällig ({0})"),
+    ("agenda.in_progress", "In Arbeit ({0})"),
+    ("agenda.timers", "Timer ({0})"),
+    ("agenda.timer_running", "{0}, läuft"),
+    ("agenda.timer_paused", "{0}, pausiert"),
+    ("agenda.empty", "Nichts fällig und nichts in Arbeit"),
+    ("agenda.logged", "Heute erfasst: {0}"),
+    ("week.title", "Woche ab {0}"),
+    ("week.overdue", "Überfällig ({0})"),
+    ("week.nothing", "nichts fällig"),
+    ("week.unestimated", "{0} ohne Schätzung"),
+    ("week.total", "Geschätzt: {0} für {1} Tickets"),
+    ("calendar.legend", "! Tickets fällig   + Zeit erfasst"),
+    ("calendar.due", "Fällig im {0} ({1})"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<usize> {
+        let mut found: Vec<usize> = (0..10).filter(|i| text.contains(&format!("{{{}}}", i))).collect();
+        found.dedup();
+        found
+    }
+
+    #[test]
+    fn test_catalogs_match() {
+        for locale in [Locale::De] {
+            for (id, text) in EN {
+                let translated = lookup(locale, id).unwrap_or_else(|| panic!("{:?} is missing '{}'", locale, id));
+                assert_eq!(placeholders(text), placeholders(translated), "placeholders differ for '{}'", id);
+            }
+            for (id, _) in locale.catalog() {
+                assert!(lookup(Locale::En, id).is_some(), "'{}' has no English message", id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_locale_tags() {
+        assert_eq!(Locale::from_tag("de"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("DE-at"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("en_US"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+        assert_eq!(Locale::from_tag("C"), None);
+    }
+
+    #[test]
+    fn test_message_lookup() {
+        assert_eq!(message(Locale::De, "due.today"), "heute");
+        assert_eq!(message(Locale::En, "due.today"), "today");
+        assert_eq!(message(Locale::De, "no.such.message"), "no.such.message");
+        assert_eq!(fill(message(Locale::De, "due.in_days"), &[&3]), "in 3 Tagen");
+        assert_eq!(fill("{1} {0} {1}", &[&"a", &"b"]), "b a b");
+        assert_eq!(fill("{0} and {1}", &[&"{1}", &"b"]), "{1} and b");
+        assert_eq!(fill("{{0}} {2} {x", &[&"a"]), "{a} {2} {x");
+    }
+}
@@ -1,11 +1,37 @@
 use anyhow::Result;
-use dialoguer::{Confirm, theme::ColorfulTheme};
+use std::io::IsTerminal;
+use dialoguer::{Confirm, Input, Select, theme::{ColorfulTheme, SimpleTheme, Theme}};
 
-/// Prompts the user for confirmation before destructive operations
+use crate::i18n::{current_locale, fill, lookup, tf};
+use crate::models::Ticket;
+use crate::sync::{Conflict, Resolution};
+use crate::output::is_plain;
+
+/// Prompt theme; plain mode avoids the colored symbols
+fn theme() -> Box<dyn Theme> {
+    if is_plain() {
+        Box::new(SimpleTheme)
+    } else {
+        Box::new(ColorfulTheme::default())
+    }
+}
+
+/// Whether stdin is a terminal we can prompt on
+pub fn can_prompt() -> bool {
+    std::io::stdin().is_terminal()
+}
+
+/// Prompts the user for confirmation before destructive operations.
+///
+/// Actions with a `confirm.<action>` catalog message (`delete`, `update-status`) get a
+/// translated prompt; anything else uses the generic wording.
 pub fn confirm_destructive_action(action: &str, target: &str) -> Result<bool> {
-    let prompt = format!("Are you sure you want to {} {}?", action, target);
+    let prompt = match lookup(current_locale(), &format!("confirm.{}", action)) {
+        Some(template) => fill(template, &[&target]),
+        None => tf("confirm.generic", &[&action, &target]),
+    };
     
-    let confirmation = Confirm::with_theme(&ColorfulTheme::default())
+    let confirmation = Confirm::with_theme(theme().as_ref())
         .with_prompt(prompt)
         .default(false)
         .interact()?;
@@ -13,9 +39,20 @@ pub fn confirm_destructive_action(action: &str, target: &str) -> Result<bool> {
     Ok(confirmation)
 }
 
+/// Prompts the user to type `expected` before an operation that is hard to undo; anything
+/// else, including an empty answer, declines
+pub fn confirm_typed(message: &str, expected: &str) -> Result<bool> {
+    let answer: String = Input::with_theme(theme().as_ref())
+        .with_prompt(message)
+        .allow_empty(true)
+        .interact_text()?;
+
+    Ok(answer.trim() == expected)
+}
+
 /// Prompts for confirmation with a custom message
 pub fn confirm_action(message: &str) -> Result<bool> {
-    let confirmation = Confirm::with_theme(&ColorfulTheme::default())
+    let confirmation = Confirm::with_theme(theme().as_ref())
         .with_prompt(message)
         .default(false)
         .interact()?;
@@ -23,9 +60,67 @@ pub fn confirm_action(message: &str) -> Result<bool> {
     Ok(confirmation)
 }
 
+/// Prompts for one key out of `keys`, such as `s`, `t`, or `c` for a `[s]top/[t]rim/[c]ancel`
+/// prompt; Enter or any other key gives `None`
+pub fn choose_key(prompt: &str, keys: &[char]) -> Result<Option<char>> {
+    let term = console::Term::stderr();
+    term.write_str(&format!("{} ", prompt))?;
+    let key = term.read_char()?.to_ascii_lowercase();
+    term.write_line("")?;
+    Ok(Some(key).filter(|key| keys.contains(key)))
+}
+
+/// Asks which side of a sync conflict to keep; `key` is the issue's identifier
+pub fn resolve_conflict(ticket: &Ticket, key: &str, conflict: &Conflict) -> Result<Resolution> {
+    let show = |value: &serde_json::Value| match value {
+        serde_json::Value::String(text) if text.is_empty() => "(empty)".to_string(),
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Null => "(none)".to_string(),
+        other => other.to_string(),
+    };
+    eprintln!("Ticket #{} and {} both changed {}:", ticket.id, key, conflict.field);
+    eprintln!("  local:  {}", show(&conflict.local));
+    eprintln!("  remote: {}", show(&conflict.remote));
+    let (prompt, keys): (&str, &[char]) = if conflict.can_merge() {
+        ("Keep [l]ocal, [r]emote, or [m]erge both?", &['l', 'r', 'm'])
+    } else {
+        ("Keep [l]ocal or [r]emote?", &['l', 'r'])
+    };
+    loop {
+        match choose_key(prompt, keys)? {
+            Some('l') => return Ok(Resolution::Local),
+            Some('r') => return Ok(Resolution::Remote),
+            Some('m') => return Ok(Resolution::Merge),
+            _ => {}
+        }
+    }
+}
+
+/// Prompts to pick one of `items` with the arrow keys, returning its index; Esc or q gives
+/// `None`
+pub fn select(prompt: &str, items: &[String]) -> Result<Option<usize>> {
+    let choice = Select::with_theme(theme().as_ref())
+        .with_prompt(prompt)
+        .items(items)
+        .default(0)
+        .interact_opt()?;
+
+    Ok(choice)
+}
+
+/// Prompts for a line of text; an empty answer gives `None`
+pub fn ask(message: &str) -> Result<Option<String>> {
+    let answer: String = Input::with_theme(theme().as_ref())
+        .with_prompt(message)
+        .allow_empty(true)
+        .interact_text()?;
+
+    Ok(Some(answer.trim().to_string()).filter(|answer| !answer.is_empty()))
+}
+
 /// Prompts for confirmation with default yes
 pub fn confirm_action_default_yes(message: &str) -> Result<bool> {
-    let confirmation = Confirm::with_theme(&ColorfulTheme::default())
+    let confirmation = Confirm::with_theme(theme().as_ref())
         .with_prompt(message)
         .default(true)
         .interact()?;
@@ -0,0 +1,514 @@
+use chrono::{Duration, NaiveDate};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::currency::{format_amount, Currencies};
+use crate::models::{TicketHours, TimeLog};
+
+/// The `[invoice]` table of `~/.ltm/config.toml`:
+///
+/// ```toml
+/// [invoice]
+/// letterhead = ["Ada Lovelace Consulting", "12 Analytical Row", "London"]
+/// rate = 95.0                # per hour, for tickets whose project has no `rate` setting
+/// currency = "EUR"           # for projects without a `currency` setting
+/// due_days = 30              # payment terms
+/// footer = ["IBAN GB00 0000 0000 0000", "Thank you for your business"]
+///
+/// [invoice.clients]          # name = address lines; a client named like a project is its default
+/// webapp = ["ACME Corp", "Accounts Payable", "1 Main Street", "Springfield"]
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct InvoiceConfig {
+    pub letterhead: Vec<String>,
+    pub rate: Option<f64>,
+    pub currency: Option<String>,
+    pub due_days: Option<i64>,
+    pub footer: Vec<String>,
+    pub clients: BTreeMap<String, Vec<String>>,
+}
+
+impl InvoiceConfig {
+    /// The address to bill: a client under `[invoice.clients]`, or `client` itself as the
+    /// only line. Without `client`, the entry named like the project, if any.
+    pub fn client_address(&self, client: Option<&str>, project: Option<&str>) -> Vec<String> {
+        match client {
+            Some(client) => self.clients.get(client).cloned().unwrap_or_else(|| vec![client.to_string()]),
+            None => project.and_then(|project| self.clients.get(project)).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// One time log on an invoice
+#[derive(Debug, Clone, Serialize)]
+pub struct LineItem {
+    pub date: NaiveDate,
+    pub description: String,
+    pub minutes: i64,
+}
+
+/// A ticket's line items, printed under its name with a subtotal
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketItems {
+    pub ticket_id: i64,
+    pub project: String,
+    pub name: String,
+    /// Hourly rate; without one the ticket's lines show hours only
+    pub rate: Option<f64>,
+    pub currency: Option<String>,
+    pub items: Vec<LineItem>,
+}
+
+impl TicketItems {
+    pub fn minutes(&self) -> i64 {
+        self.items.iter().map(|item| item.minutes).sum()
+    }
+
+    /// What `minutes` of work on the ticket cost, rounded to cents
+    pub fn amount(&self, minutes: i64) -> Option<f64> {
+        self.rate.map(|rate| (rate * minutes as f64 / 60.0 * 100.0).round() / 100.0)
+    }
+}
+
+/// Group billable time logs under their tickets, in the order of `rows` and at their rates
+pub fn group_by_ticket(rows: &[TicketHours], logs: &[TimeLog]) -> Vec<TicketItems> {
+    let mut items: HashMap<i64, Vec<LineItem>> = HashMap::new();
+    for log in logs.iter().filter(|log| log.billable) {
+        let description = log.note.as_deref().map(str::trim).filter(|note| !note.is_empty()).unwrap_or("Time logged");
+        items.entry(log.ticket_id).or_default().push(LineItem {
+            date: log.started_at.unwrap_or(log.created_at).date(),
+            description: description.to_string(),
+            minutes: log.hours as i64 * 60 + log.minutes as i64,
+        });
+    }
+    rows.iter()
+        .filter_map(|row| {
+            let items = items.remove(&row.ticket_id)?;
+            Some(TicketItems {
+                ticket_id: row.ticket_id,
+                project: row.project.clone(),
+                name: row.name.clone(),
+                rate: row.rate,
+                currency: row.currency.clone(),
+                items,
+            })
+        })
+        .collect()
+}
+
+/// An invoice for the time logged over a period
+#[derive(Debug, Clone, Serialize)]
+pub struct Invoice {
+    pub number: String,
+    pub date: NaiveDate,
+    pub due: Option<NaiveDate>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub letterhead: Vec<String>,
+    pub client: Vec<String>,
+    pub footer: Vec<String>,
+    pub tickets: Vec<TicketItems>,
+    /// How to write amounts, from `[currencies]`
+    #[serde(skip)]
+    pub currencies: Currencies,
+}
+
+impl Invoice {
+    /// An invoice dated `date`, with the letterhead and terms of `config`
+    pub fn new(config: &InvoiceConfig, number: String, date: NaiveDate, from: NaiveDate, to: NaiveDate) -> Self {
+        Self {
+            number,
+            date,
+            due: config.due_days.map(|days| date + Duration::days(days)),
+            from,
+            to,
+            letterhead: config.letterhead.clone(),
+            client: Vec::new(),
+            footer: config.footer.clone(),
+            tickets: Vec::new(),
+            currencies: Currencies::new(),
+        }
+    }
+
+    pub fn minutes(&self) -> i64 {
+        self.tickets.iter().map(TicketItems::minutes).sum()
+    }
+
+    /// What the tickets with a rate add up to in each currency, with the minutes billed
+    pub fn totals(&self) -> Vec<(Option<String>, i64, f64)> {
+        let mut totals: BTreeMap<Option<String>, (i64, f64)> = BTreeMap::new();
+        for ticket in &self.tickets {
+            if let Some(amount) = ticket.amount(ticket.minutes()) {
+                let total = totals.entry(ticket.currency.clone()).or_default();
+                total.0 += ticket.minutes();
+                total.1 += amount;
+            }
+        }
+        totals.into_iter().map(|(currency, (minutes, amount))| (currency, minutes, (amount * 100.0).round() / 100.0)).collect()
+    }
+
+    /// The amounts due, one per currency, like "EUR 1,234.50, £80.00"
+    pub fn total_text(&self) -> Option<String> {
+        let totals = self.totals();
+        let text: Vec<String> =
+            totals.iter().map(|(currency, _, amount)| self.money(currency.as_deref(), *amount)).collect();
+        (!text.is_empty()).then(|| text.join(", "))
+    }
+
+    /// An amount written as configured for its currency
+    pub fn money(&self, currency: Option<&str>, amount: f64) -> String {
+        format_amount(&self.currencies, currency, amount)
+    }
+
+    /// The invoice as a PDF on A4 pages, using the standard Helvetica fonts
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let totals = self.totals();
+        let mut layout = Layout::new(!totals.is_empty());
+        layout.header(self);
+        for ticket in &self.tickets {
+            let currency = ticket.currency.as_deref();
+            let amount = |minutes: i64| ticket.amount(minutes).map(|amount| self.money(currency, amount));
+            layout.ensure(LINE * 3.0);
+            layout.text(MARGIN, BOLD, 10.0, &format!("#{} {} ({})", ticket.ticket_id, ticket.name, ticket.project));
+            if let Some(rate) = ticket.rate {
+                layout.text_right(PAGE_WIDTH - MARGIN, REGULAR, 9.0, &format!("{} per hour", self.money(currency, rate)));
+            }
+            layout.y -= LINE;
+            for item in &ticket.items {
+                if layout.ensure(LINE) {
+                    layout.text(MARGIN, REGULAR, 8.0, &format!("#{} {} (continued)", ticket.ticket_id, ticket.name));
+                    layout.y -= LINE;
+                }
+                layout.text(MARGIN + 10.0, REGULAR, 10.0, &item.date.format("%Y-%m-%d").to_string());
+                let width = layout.hours_x() - DESCRIPTION_X - 70.0;
+                layout.text(DESCRIPTION_X, REGULAR, 10.0, &fit(&item.description, 10.0, width));
+                layout.amounts(REGULAR, item.minutes, amount(item.minutes), false);
+                layout.y -= LINE;
+            }
+            layout.text(DESCRIPTION_X, BOLD, 10.0, "Subtotal");
+            layout.amounts(BOLD, ticket.minutes(), amount(ticket.minutes()), false);
+            layout.y -= LINE * 1.6;
+        }
+
+        // One total per currency when the tickets bill in several
+        layout.ensure(LINE * (1.0 + totals.len() as f32));
+        layout.rule(1.0);
+        layout.y -= LINE;
+        match totals.as_slice() {
+            [(currency, _, amount)] => {
+                layout.text(DESCRIPTION_X, BOLD, 12.0, "Total");
+                layout.amounts(BOLD, self.minutes(), Some(self.money(currency.as_deref(), *amount)), true);
+            }
+            totals => {
+                layout.text(DESCRIPTION_X, BOLD, 12.0, "Total");
+                layout.amounts(BOLD, self.minutes(), None, true);
+                for (currency, minutes, amount) in totals {
+                    layout.y -= LINE * 1.2;
+                    layout.text(DESCRIPTION_X, BOLD, 12.0, &format!("Total {}", currency.as_deref().unwrap_or_default()));
+                    layout.amounts(BOLD, *minutes, Some(self.money(currency.as_deref(), *amount)), true);
+                }
+            }
+        }
+        layout.y -= LINE * 2.5;
+        for line in &self.footer {
+            layout.ensure(LINE);
+            layout.text(MARGIN, REGULAR, 9.0, line);
+            layout.y -= LINE;
+        }
+        layout.finish(self)
+    }
+}
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const LINE: f32 = 15.0;
+const DESCRIPTION_X: f32 = MARGIN + 80.0;
+const REGULAR: Name<'static> = Name(b"F1");
+const BOLD: Name<'static> = Name(b"F2");
+
+/// Pages of an invoice as they are laid out, top to bottom
+struct Layout {
+    pages: Vec<Content>,
+    y: f32,
+    priced: bool,
+}
+
+impl Layout {
+    fn new(priced: bool) -> Self {
+        Self { pages: vec![Content::new()], y: PAGE_HEIGHT - MARGIN, priced }
+    }
+
+    fn page(&mut self) -> &mut Content {
+        self.pages.last_mut().unwrap()
+    }
+
+    /// Right edge of the hours column
+    fn hours_x(&self) -> f32 {
+        if self.priced { PAGE_WIDTH - MARGIN - 110.0 } else { PAGE_WIDTH - MARGIN }
+    }
+
+    fn text(&mut self, x: f32, font: Name, size: f32, text: &str) {
+        let y = self.y;
+        let text = encode(text);
+        let page = self.page();
+        page.begin_text().set_font(font, size).next_line(x, y).show(Str(&text)).end_text();
+    }
+
+    fn text_right(&mut self, right: f32, font: Name, size: f32, text: &str) {
+        self.text(right - text_width(text, size), font, size, text);
+    }
+
+    fn rule(&mut self, width: f32) {
+        let y = self.y + LINE * 0.7;
+        let page = self.page();
+        page.set_line_width(width).move_to(MARGIN, y).line_to(PAGE_WIDTH - MARGIN, y).stroke();
+    }
+
+    /// The hours, and the amount when there is one, of a row
+    fn amounts(&mut self, font: Name, minutes: i64, amount: Option<String>, large: bool) {
+        let size = if large { 12.0 } else { 10.0 };
+        self.text_right(self.hours_x(), font, size, &format!("{:.2}", minutes as f64 / 60.0));
+        if let Some(amount) = amount.filter(|_| self.priced) {
+            self.text_right(PAGE_WIDTH - MARGIN, font, size, &amount);
+        }
+    }
+
+    fn columns(&mut self) {
+        self.text(MARGIN, BOLD, 9.0, "Date");
+        self.text(DESCRIPTION_X, BOLD, 9.0, "Description");
+        self.text_right(self.hours_x(), BOLD, 9.0, "Hours");
+        if self.priced {
+            self.text_right(PAGE_WIDTH - MARGIN, BOLD, 9.0, "Amount");
+        }
+        self.y -= LINE;
+        self.rule(0.5);
+        self.y -= LINE * 0.4;
+    }
+
+    /// Start a new page, with the column headings again, unless `height` still fits;
+    /// returns whether it did
+    fn ensure(&mut self, height: f32) -> bool {
+        if self.y - height >= MARGIN + LINE * 2.0 {
+            return false;
+        }
+        self.pages.push(Content::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+        self.columns();
+        true
+    }
+
+    /// Letterhead, invoice details, client address, and the column headings
+    fn header(&mut self, invoice: &Invoice) {
+        let top = self.y;
+        for (i, line) in invoice.letterhead.iter().enumerate() {
+            let (font, size) = if i == 0 { (BOLD, 14.0) } else { (REGULAR, 10.0) };
+            self.text(MARGIN, font, size, line);
+            self.y -= if i == 0 { LINE * 1.3 } else { LINE };
+        }
+        let left = self.y;
+
+        self.y = top;
+        let right = PAGE_WIDTH - MARGIN;
+        self.text_right(right, BOLD, 20.0, "INVOICE");
+        self.y -= LINE * 1.8;
+        let mut details = vec![
+            format!("Number: {}", invoice.number),
+            format!("Date: {}", invoice.date.format("%Y-%m-%d")),
+        ];
+        if let Some(due) = invoice.due {
+            details.push(format!("Due: {}", due.format("%Y-%m-%d")));
+        }
+        details.push(format!("Period: {} to {}", invoice.from.format("%Y-%m-%d"), invoice.to.format("%Y-%m-%d")));
+        for line in details {
+            self.text_right(right, REGULAR, 10.0, &line);
+            self.y -= LINE;
+        }
+
+        self.y = self.y.min(left) - LINE;
+        if !invoice.client.is_empty() {
+            self.text(MARGIN, BOLD, 9.0, "Bill to");
+            self.y -= LINE;
+            for line in &invoice.client {
+                self.text(MARGIN, REGULAR, 10.0, line);
+                self.y -= LINE;
+            }
+            self.y -= LINE;
+        }
+        self.y -= LINE;
+        self.columns();
+    }
+
+    /// Write the pages, numbered when there are several, into a PDF file
+    fn finish(mut self, invoice: &Invoice) -> Vec<u8> {
+        let count = self.pages.len();
+        if count > 1 {
+            for (i, page) in self.pages.iter_mut().enumerate() {
+                let text = encode(&format!("Page {} of {}", i + 1, count));
+                let x = PAGE_WIDTH - MARGIN - text_width(&format!("Page {} of {}", i + 1, count), 8.0);
+                page.begin_text().set_font(REGULAR, 8.0).next_line(x, MARGIN / 2.0).show(Str(&text)).end_text();
+            }
+        }
+
+        let mut pdf = Pdf::new();
+        let catalog = Ref::new(1);
+        let tree = Ref::new(2);
+        let regular = Ref::new(3);
+        let bold = Ref::new(4);
+        let info = Ref::new(5);
+        let page_ids: Vec<Ref> = (0..count as i32).map(|i| Ref::new(6 + i * 2)).collect();
+
+        pdf.catalog(catalog).pages(tree);
+        pdf.pages(tree).kids(page_ids.iter().copied()).count(count as i32);
+        for (font, name) in [(regular, Name(b"Helvetica")), (bold, Name(b"Helvetica-Bold"))] {
+            pdf.type1_font(font).base_font(name).encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+        let title = format!("Invoice {}", invoice.number);
+        pdf.document_info(info).title(TextStr(&title)).producer(TextStr("ltm"));
+        for (content, id) in self.pages.into_iter().zip(page_ids) {
+            let content_id = Ref::new(id.get() + 1);
+            let mut page = pdf.page(id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT)).parent(tree).contents(content_id);
+            page.resources().fonts().pair(REGULAR, regular).pair(BOLD, bold);
+            page.finish();
+            pdf.stream(content_id, &content.finish());
+        }
+        pdf.finish()
+    }
+}
+
+/// Helvetica glyph widths of the printable ASCII characters, in thousandths of the font size
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556, 556,
+    556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667, 611, 778, 722, 278,
+    500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469,
+    556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500,
+    278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Roughly how wide `text` is in points; bold text runs a little wider
+fn text_width(text: &str, size: f32) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => HELVETICA_WIDTHS[c as usize - 32] as u32,
+            _ => 556,
+        })
+        .sum();
+    units as f32 * size / 1000.0 * 1.05
+}
+
+/// `text` shortened with an ellipsis to fit `width`
+fn fit(text: &str, size: f32, width: f32) -> String {
+    if text_width(text, size) <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.chars().collect();
+    while !fitted.is_empty() && text_width(&format!("{}...", fitted), size) > width {
+        fitted.pop();
+    }
+    format!("{}...", fitted.trim_end())
+}
+
+/// `text` in the WinAnsi encoding of the standard fonts; other characters become `?`
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            '€' => 0x80,
+            '‚' => 0x82,
+            '„' => 0x84,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '\t' => b' ',
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+
+    fn log(id: i64, ticket_id: i64, minutes: i32, note: Option<&str>, billable: bool) -> TimeLog {
+        let at = NaiveDate::from_ymd_opt(2024, 3, id as u32).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        TimeLog {
+            id,
+            ticket_id,
+            hours: minutes / 60,
+            minutes: minutes % 60,
+            started_at: Some(at),
+            ended_at: None,
+            created_at: at,
+            updated_at: at,
+            note: note.map(str::to_string),
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            billable,
+        }
+    }
+
+    fn row(ticket_id: i64, name: &str, rate: Option<f64>) -> TicketHours {
+        TicketHours {
+            ticket_id,
+            project: "webapp".to_string(),
+            name: name.to_string(),
+            status: "open".to_string(),
+            entries: 0,
+            minutes: 0,
+            hours: 0.0,
+            billable_minutes: 0,
+            rate,
+            amount: None,
+            currency: Some(if ticket_id == 3 { "GBP" } else { "EUR" }.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_invoice() {
+        let logs = [
+            log(1, 7, 90, Some("Login form"), true),
+            log(2, 3, 30, None, true),
+            log(3, 7, 45, Some(" "), true),
+            log(4, 7, 60, Some("Call about the invoice"), false),
+            log(5, 9, 60, None, false),
+        ];
+        let rows = [row(7, "Fix login", Some(1000.0)), row(3, "Docs", Some(500.0)), row(9, "Chat", None)];
+        let tickets = group_by_ticket(&rows, &logs);
+        assert_eq!(tickets.iter().map(|t| (t.ticket_id, t.minutes())).collect::<Vec<_>>(), vec![(7, 135), (3, 30)]);
+        assert_eq!(tickets[0].items[1].description, "Time logged");
+
+        let config = InvoiceConfig {
+            letterhead: vec!["Ada Lovelace Consulting".to_string()],
+            due_days: Some(14),
+            clients: BTreeMap::from([("webapp".to_string(), vec!["ACME Corp".to_string()])]),
+            ..Default::default()
+        };
+        assert_eq!(config.client_address(None, Some("webapp")), vec!["ACME Corp"]);
+        assert_eq!(config.client_address(Some("Globex"), Some("webapp")), vec!["Globex"]);
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let mut invoice = Invoice::new(&config, "2024-03".to_string(), day, day.with_day0(0).unwrap(), day);
+        invoice.tickets = tickets;
+        assert_eq!(invoice.due, NaiveDate::from_ymd_opt(2024, 4, 14));
+        assert_eq!(invoice.totals(), vec![(Some("EUR".to_string()), 135, 2250.0), (Some("GBP".to_string()), 30, 250.0)]);
+        assert_eq!(invoice.total_text().as_deref(), Some("EUR 2,250.00, GBP 250.00"));
+
+        let pdf = invoice.to_pdf();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-"));
+        assert!(text.contains("Helvetica-Bold") && text.contains("/Count 1"));
+        assert_eq!(encode("5 € – café"), b"5 \x80 \x96 caf\xe9");
+        assert_eq!(fit("A rather long description", 10.0, 60.0), "A rather lo...");
+    }
+}
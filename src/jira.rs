@@ -0,0 +1,615 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::db::Database;
+use crate::duration::format_minutes;
+use crate::models::{RemoteLink, RemoteWorklog, Ticket, TimeLog};
+use crate::sync::{self, Change, Fields, RemoteIssue, Resolver};
+
+/// Provider name in `remote_links`, `remote_worklogs`, and `sync_state`
+pub const PROVIDER: &str = "jira";
+
+/// Environment variable with a Jira API token, used when the config file has none
+pub const API_TOKEN_ENV: &str = "JIRA_API_TOKEN";
+
+/// Ticket fields that can be synced, and the Jira fields they map to unless `[jira.fields]`
+/// says otherwise. Status is synced through workflow transitions instead.
+const DEFAULT_FIELDS: &[(&str, &str)] =
+    &[("name", "summary"), ("description", "description"), ("priority", "priority"), ("due_date", "duedate")];
+
+/// The `[jira]` table of `~/.ltm/config.toml`:
+///
+/// ```toml
+/// [jira]
+/// url = "https://example.atlassian.net"
+/// email = "ada@example.com"
+/// api_token = "..."          # or set JIRA_API_TOKEN
+/// issue_type = "Task"        # for issues created from tickets
+///
+/// [jira.projects]            # project key = ltm project
+/// WEB = "webapp"
+///
+/// [jira.statuses]            # Jira status = ltm status, where the category is not enough
+/// "In Review" = "testing"
+///
+/// [jira.fields]              # ltm field = Jira field ID, "" to leave the field alone
+/// due_date = "customfield_10015"
+/// ```
+///
+/// Other statuses map by category: To Do to `open`, In Progress to `in-progress`, and
+/// Done to `closed`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct JiraConfig {
+    pub url: Option<String>,
+    pub email: Option<String>,
+    pub api_token: Option<String>,
+    pub issue_type: Option<String>,
+    pub projects: BTreeMap<String, String>,
+    pub statuses: BTreeMap<String, String>,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl JiraConfig {
+    /// The site URL, account email, and API token, from the config file or `JIRA_API_TOKEN`
+    pub fn credentials(&self) -> Result<(String, String, String)> {
+        let url = self.url.as_deref().map(str::trim).filter(|url| !url.is_empty());
+        let email = self.email.as_deref().map(str::trim).filter(|email| !email.is_empty());
+        let token = self.api_token.clone().or_else(|| std::env::var(API_TOKEN_ENV).ok());
+        let token = token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty());
+        match (url, email, token) {
+            (Some(url), Some(email), Some(token)) => Ok((url.trim_end_matches('/').to_string(), email.to_string(), token)),
+            (None, ..) => anyhow::bail!("No Jira site; set url under [jira] in config.toml"),
+            (_, None, _) => anyhow::bail!("No Jira account; set email under [jira] in config.toml"),
+            _ => anyhow::bail!("No Jira API token; set api_token under [jira] in config.toml or {}", API_TOKEN_ENV),
+        }
+    }
+
+    /// The Jira field a ticket field is synced with, `None` if it is not synced
+    pub fn field(&self, name: &str) -> Option<&str> {
+        let field = match self.fields.get(name) {
+            Some(field) => field.as_str(),
+            None => DEFAULT_FIELDS.iter().find(|(n, _)| *n == name).map(|(_, field)| *field)?,
+        };
+        Some(field).filter(|field| !field.is_empty())
+    }
+
+    /// An issue's synced fields as a ticket would have them
+    pub fn fields(&self, issue: &Issue) -> Fields {
+        let mut fields = Fields::from([("status".to_string(), json!(self.status_for(&issue.status)))]);
+        if let Some(name) = issue.text(self, "name") {
+            fields.insert("name".to_string(), json!(name));
+        }
+        if let Some(description) = issue.text(self, "description") {
+            fields.insert("description".to_string(), json!(description));
+        }
+        if let Some(priority) = issue.priority(self) {
+            fields.insert("priority".to_string(), json!(priority));
+        }
+        if let Some(due_date) = issue.due_date(self) {
+            fields.insert("due_date".to_string(), json!(due_date));
+        }
+        fields
+    }
+
+    /// The status a ticket gets for an issue in `status`
+    pub fn status_for(&self, status: &Status) -> String {
+        if let Some(mapped) = self.statuses.get(&status.name) {
+            return mapped.clone();
+        }
+        match status.category.as_str() {
+            "indeterminate" => "in-progress",
+            "done" => "closed",
+            _ => "open",
+        }
+        .to_string()
+    }
+
+    /// The transition that moves an issue to a ticket's status: one to a Jira status mapped
+    /// to it by name, else the first to a status of the matching category
+    pub fn transition_for<'a>(&self, status: &str, transitions: &'a [Transition]) -> Option<&'a Transition> {
+        let mapped = transitions.iter().find(|t| self.statuses.get(&t.to.name).is_some_and(|mapped| mapped == status));
+        let category = match status {
+            "open" => "new",
+            "closed" | "completed" | "done" | "cancelled" | "wontfix" => "done",
+            _ => "indeterminate",
+        };
+        mapped.or_else(|| transitions.iter().find(|t| t.to.category == category))
+    }
+}
+
+/// A Jira workflow status
+#[derive(Debug, Clone, PartialEq)]
+pub struct Status {
+    pub name: String,
+    /// The status category's key: new, indeterminate, or done
+    pub category: String,
+}
+
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Category {
+            key: String,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Raw {
+            name: String,
+            status_category: Category,
+        }
+        let raw = Raw::deserialize(deserializer)?;
+        Ok(Status { name: raw.name, category: raw.status_category.key })
+    }
+}
+
+/// A workflow transition available to an issue
+#[derive(Debug, Clone, Deserialize)]
+pub struct Transition {
+    pub id: String,
+    pub to: Status,
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub id: String,
+    /// e.g. `WEB-123`
+    pub key: String,
+    pub summary: String,
+    pub status: Status,
+    pub updated_at: NaiveDateTime,
+    /// The issue's fields by ID, as Jira returns them
+    pub fields: Map<String, Value>,
+}
+
+impl Issue {
+    fn from_json(value: Value) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Raw {
+            id: String,
+            key: String,
+            fields: Map<String, Value>,
+        }
+        let raw: Raw = serde_json::from_value(value).context("Unexpected issue from Jira")?;
+        let status = serde_json::from_value(raw.fields.get("status").cloned().unwrap_or_default())
+            .with_context(|| format!("Jira issue {} has no status", raw.key))?;
+        let updated = raw.fields.get("updated").and_then(Value::as_str).unwrap_or_default();
+        let updated_at = parse_time(updated).with_context(|| format!("Jira issue {} has no update time", raw.key))?;
+        let summary = raw.fields.get("summary").and_then(Value::as_str).unwrap_or_default().to_string();
+        Ok(Issue { id: raw.id, key: raw.key, summary, status, updated_at, fields: raw.fields })
+    }
+
+    /// The value of the Jira field a ticket field is synced with, `None` if it is not synced
+    fn field(&self, config: &JiraConfig, name: &str) -> Option<&Value> {
+        config.field(name).map(|field| self.fields.get(field).unwrap_or(&Value::Null))
+    }
+
+    fn text(&self, config: &JiraConfig, name: &str) -> Option<String> {
+        self.field(config, name).map(|value| value.as_str().unwrap_or_default().to_string())
+    }
+
+    fn priority(&self, config: &JiraConfig) -> Option<Option<&'static str>> {
+        self.field(config, "priority").map(|value| value["name"].as_str().and_then(priority_from_jira))
+    }
+
+    fn due_date(&self, config: &JiraConfig) -> Option<Option<NaiveDate>> {
+        self.field(config, "due_date").map(|value| value.as_str().and_then(|date| date.parse().ok()))
+    }
+}
+
+impl RemoteIssue for Issue {
+    fn remote_id(&self) -> &str {
+        &self.id
+    }
+    fn key(&self) -> &str {
+        &self.key
+    }
+    fn title(&self) -> &str {
+        &self.summary
+    }
+    fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
+
+type Action = sync::Action<Issue>;
+
+/// A Jira timestamp such as `2024-05-01T09:00:00.000+0200`, in UTC
+fn parse_time(text: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f%z").ok().map(|at| at.naive_utc())
+}
+
+/// A ticket priority for a Jira priority
+pub fn priority_from_jira(priority: &str) -> Option<&'static str> {
+    match priority {
+        "Highest" | "Blocker" | "Critical" => Some("critical"),
+        "High" | "Major" => Some("high"),
+        "Medium" => Some("medium"),
+        "Low" | "Lowest" | "Minor" | "Trivial" => Some("low"),
+        _ => None,
+    }
+}
+
+/// A Jira priority for a ticket priority
+pub fn priority_to_jira(priority: &str) -> Option<&'static str> {
+    match priority {
+        "critical" => Some("Highest"),
+        "high" => Some("High"),
+        "medium" => Some("Medium"),
+        "low" => Some("Low"),
+        _ => None,
+    }
+}
+
+/// The synced fields of a ticket as Jira writes them. A ticket without a priority leaves
+/// the issue's alone, since Jira projects often require one.
+fn issue_fields(config: &JiraConfig, ticket: &Ticket) -> Map<String, Value> {
+    let mut fields = Map::new();
+    if let Some(field) = config.field("name") {
+        fields.insert(field.to_string(), ticket.name.clone().into());
+    }
+    if let Some(field) = config.field("description") {
+        fields.insert(field.to_string(), ticket.description.clone().into());
+    }
+    if let (Some(field), Some(priority)) = (config.field("priority"), ticket.priority.as_deref().and_then(priority_to_jira)) {
+        fields.insert(field.to_string(), json!({ "name": priority }));
+    }
+    if let Some(field) = config.field("due_date") {
+        fields.insert(field.to_string(), json!(ticket.due_date));
+    }
+    fields
+}
+
+/// A client for Jira Cloud's REST API
+pub struct Client {
+    http: reqwest::Client,
+    url: String,
+    email: String,
+    token: String,
+    /// Fields to fetch with every issue
+    fields: String,
+}
+
+impl Client {
+    pub fn new(config: &JiraConfig) -> Result<Self> {
+        let (url, email, token) = config.credentials()?;
+        let mut fields = vec!["summary", "status", "updated"];
+        for field in DEFAULT_FIELDS.iter().filter_map(|(name, _)| config.field(name)) {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        Ok(Self { http: reqwest::Client::new(), url, email, token, fields: fields.join(",") })
+    }
+
+    async fn send(&self, method: reqwest::Method, path: &str, body: Option<Value>) -> Result<Value> {
+        let mut request = self
+            .http
+            .request(method, format!("{}/rest/api/2/{}", self.url, path))
+            .basic_auth(&self.email, Some(&self.token))
+            .header("Accept", "application/json");
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request.send().await.context("Could not reach Jira")?;
+        let status = response.status();
+        let text = response.text().await.context("Invalid response from Jira")?;
+        let body: Value = if text.trim().is_empty() { Value::Null } else { serde_json::from_str(&text).unwrap_or(Value::Null) };
+        if !status.is_success() {
+            let mut messages: Vec<String> =
+                body["errorMessages"].as_array().into_iter().flatten().filter_map(|m| m.as_str().map(String::from)).collect();
+            if let Some(errors) = body["errors"].as_object() {
+                messages.extend(errors.iter().map(|(field, error)| format!("{}: {}", field, error.as_str().unwrap_or_default())));
+            }
+            if messages.is_empty() {
+                anyhow::bail!("Jira answered with {}", status);
+            }
+            anyhow::bail!("Jira refused the request: {}", messages.join("; "));
+        }
+        Ok(body)
+    }
+
+    /// A project's issues changed after `since`, or all of them. JQL compares times in the
+    /// account's time zone, so the search reaches a day further back and `plan` sorts out
+    /// what actually changed.
+    pub async fn issues(&self, project: &str, since: Option<NaiveDateTime>) -> Result<Vec<Issue>> {
+        let mut jql = format!("project = \"{}\"", project);
+        if let Some(since) = since {
+            jql.push_str(&format!(" AND updated >= \"{}\"", (since - Duration::days(1)).format("%Y/%m/%d %H:%M")));
+        }
+        jql.push_str(" ORDER BY key");
+
+        let mut issues = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut body = json!({ "jql": jql, "fields": self.fields.split(',').collect::<Vec<_>>(), "maxResults": 100 });
+            if let Some(token) = &token {
+                body["nextPageToken"] = token.clone().into();
+            }
+            let page = self.send(reqwest::Method::POST, "search/jql", Some(body)).await?;
+            for issue in page["issues"].as_array().into_iter().flatten() {
+                issues.push(Issue::from_json(issue.clone())?);
+            }
+            match page["nextPageToken"].as_str() {
+                Some(next) if !page["isLast"].as_bool().unwrap_or(true) => token = Some(next.to_string()),
+                _ => return Ok(issues),
+            }
+        }
+    }
+
+    pub async fn issue(&self, key: &str) -> Result<Issue> {
+        Issue::from_json(self.send(reqwest::Method::GET, &format!("issue/{}?fields={}", key, self.fields), None).await?)
+    }
+
+    /// Create an issue, returning its key
+    async fn create_issue(&self, project: &str, issue_type: &str, mut fields: Map<String, Value>) -> Result<String> {
+        fields.insert("project".to_string(), json!({ "key": project }));
+        fields.insert("issuetype".to_string(), json!({ "name": issue_type }));
+        let created = self.send(reqwest::Method::POST, "issue", Some(json!({ "fields": fields }))).await?;
+        created["key"].as_str().map(String::from).context("Jira did not create the issue")
+    }
+
+    async fn update_issue(&self, key: &str, fields: Map<String, Value>) -> Result<()> {
+        self.send(reqwest::Method::PUT, &format!("issue/{}", key), Some(json!({ "fields": fields }))).await?;
+        Ok(())
+    }
+
+    async fn transitions(&self, key: &str) -> Result<Vec<Transition>> {
+        let data = self.send(reqwest::Method::GET, &format!("issue/{}/transitions", key), None).await?;
+        serde_json::from_value(data["transitions"].clone()).context("Unexpected transitions from Jira")
+    }
+
+    async fn transition(&self, key: &str, transition: &Transition) -> Result<()> {
+        let body = json!({ "transition": { "id": transition.id } });
+        self.send(reqwest::Method::POST, &format!("issue/{}/transitions", key), Some(body)).await?;
+        Ok(())
+    }
+
+    /// Send a time log as a work log, or update the one sent before, returning its ID
+    async fn save_worklog(&self, key: &str, log: &TimeLog, remote_id: Option<&str>) -> Result<String> {
+        let mut body = json!({
+            "timeSpentSeconds": (log.hours as i64 * 60 + log.minutes as i64) * 60,
+            "started": log.started_at.unwrap_or(log.created_at).format("%Y-%m-%dT%H:%M:%S%.3f+0000").to_string(),
+        });
+        if let Some(note) = &log.note {
+            body["comment"] = note.clone().into();
+        }
+        let saved = match remote_id {
+            Some(id) => self.send(reqwest::Method::PUT, &format!("issue/{}/worklog/{}", key, id), Some(body)).await?,
+            None => self.send(reqwest::Method::POST, &format!("issue/{}/worklog", key), Some(body)).await?,
+        };
+        saved["id"].as_str().map(String::from).context("Jira did not save the work log")
+    }
+}
+
+/// Sync the configured projects in both directions, settling conflicts with `resolve`,
+/// then send new and edited time logs of linked tickets as work logs. Returns what was
+/// done, or with `dry_run` what would be. Changes made while Jira could not be reached are
+/// newer than the last sync, so the next one sends them.
+pub async fn sync(db: &Database, config: &JiraConfig, dry_run: bool, resolve: Resolver<'_>) -> Result<Vec<Change>> {
+    if config.projects.is_empty() {
+        anyhow::bail!("No Jira projects to sync; map project keys to projects under [jira.projects] in config.toml");
+    }
+    let client = Client::new(config)?;
+    let started = Utc::now().naive_utc();
+    let since = db.last_synced_at(PROVIDER).await?;
+
+    let mut issues = BTreeMap::new();
+    let mut tickets = Vec::new();
+    for (key, project) in &config.projects {
+        issues.insert(key.clone(), client.issues(key, since).await?);
+        tickets.extend(db.list_tickets(Some(project)).await?);
+    }
+    let links = db.list_remote_links(PROVIDER).await?;
+
+    let actions = sync::plan(&config.projects, &issues, &tickets, &links, |issue| config.fields(issue));
+    let mut changes: Vec<Change> = actions.iter().map(Action::change).collect();
+    if dry_run {
+        for (link, log, _) in pending_worklogs(db, &links).await? {
+            changes.push(worklog_change(&link, &log));
+        }
+        return Ok(changes);
+    }
+    for action in &actions {
+        apply(db, config, &client, action, resolve).await?;
+    }
+
+    let links = db.list_remote_links(PROVIDER).await?;
+    for (link, log, sent) in pending_worklogs(db, &links).await? {
+        let key = link.remote_key.as_deref().unwrap_or(&link.remote_id);
+        let remote_id = client.save_worklog(key, &log, sent.as_ref().map(|w| w.remote_id.as_str())).await?;
+        db.save_remote_worklog(&RemoteWorklog {
+            time_log_id: log.id,
+            provider: PROVIDER.to_string(),
+            remote_id,
+            synced_at: log.updated_at,
+        })
+        .await?;
+        changes.push(worklog_change(&link, &log));
+    }
+    db.set_last_synced_at(PROVIDER, started).await?;
+    Ok(changes)
+}
+
+/// Time logs of linked tickets that were never sent or changed since, with the work log
+/// each was sent as
+async fn pending_worklogs(db: &Database, links: &[RemoteLink]) -> Result<Vec<(RemoteLink, TimeLog, Option<RemoteWorklog>)>> {
+    let sent: HashMap<i64, RemoteWorklog> =
+        db.list_remote_worklogs(PROVIDER).await?.into_iter().map(|w| (w.time_log_id, w)).collect();
+    let mut pending = Vec::new();
+    for link in links {
+        let mut logs = db.get_time_logs(link.ticket_id).await?;
+        logs.reverse();
+        for log in logs {
+            let worklog = sent.get(&log.id);
+            // Jira does not take work logs under a minute
+            if log.hours * 60 + log.minutes > 0 && worklog.is_none_or(|w| log.updated_at > w.synced_at) {
+                pending.push((link.clone(), log, worklog.cloned()));
+            }
+        }
+    }
+    Ok(pending)
+}
+
+fn worklog_change(link: &RemoteLink, log: &TimeLog) -> Change {
+    let key = link.remote_key.as_deref().unwrap_or(&link.remote_id);
+    let description = format!("#{} → {} work log {}", link.ticket_id, key, format_minutes((log.hours * 60 + log.minutes) as i64));
+    Change { kind: "worklog", description }
+}
+
+async fn apply(db: &Database, config: &JiraConfig, client: &Client, action: &Action, resolve: Resolver<'_>) -> Result<()> {
+    let (ticket_id, issue) = match action {
+        Action::Import { issue, project } => {
+            let status = config.status_for(&issue.status);
+            let name = issue.text(config, "name").filter(|name| !name.is_empty()).unwrap_or_else(|| issue.summary.clone());
+            let description = issue.text(config, "description").unwrap_or_default();
+            let id = db.add_ticket_with_status(project, &name, &description, &status).await?;
+            let ticket = db.get_ticket(id).await?.context("Imported ticket not found")?;
+            sync::update_ticket(db, &ticket, &pulled_fields(config, issue)).await?;
+            (id, issue.clone())
+        }
+        Action::Pull { ticket, issue, .. } => {
+            sync::update_ticket(db, ticket, &pulled_fields(config, issue)).await?;
+            (ticket.id, issue.clone())
+        }
+        Action::Merge { ticket, issue, link, pull, conflicts } => {
+            let fields = sync::resolve_conflicts(db, PROVIDER, ticket, issue, pull, conflicts, resolve).await?;
+            let ticket = sync::merge_into_ticket(db, ticket, &fields).await?;
+            let key = link.remote_key.as_deref().unwrap_or(&link.remote_id);
+            client.update_issue(key, issue_fields(config, &ticket)).await?;
+            (ticket.id, move_to_status(config, client, key, &ticket.status).await?)
+        }
+        Action::Export { ticket, scope } => {
+            let issue_type = config.issue_type.as_deref().unwrap_or("Task");
+            let key = client.create_issue(scope, issue_type, issue_fields(config, ticket)).await?;
+            (ticket.id, move_to_status(config, client, &key, &ticket.status).await?)
+        }
+        Action::Push { ticket, link } => {
+            let key = link.remote_key.as_deref().unwrap_or(&link.remote_id);
+            client.update_issue(key, issue_fields(config, ticket)).await?;
+            (ticket.id, move_to_status(config, client, key, &ticket.status).await?)
+        }
+    };
+    sync::save_link(db, PROVIDER, ticket_id, &issue, config.fields(&issue)).await
+}
+
+/// An issue's fields to copy to its ticket: an empty summary keeps the ticket's name
+fn pulled_fields(config: &JiraConfig, issue: &Issue) -> Fields {
+    let mut fields = config.fields(issue);
+    if fields.get("name").is_some_and(|name| name.as_str().unwrap_or_default().is_empty()) {
+        fields.remove("name");
+    }
+    fields
+}
+
+/// Transition an issue to a ticket's status if it is elsewhere and the workflow allows it,
+/// returning the issue as it ends up
+async fn move_to_status(config: &JiraConfig, client: &Client, key: &str, status: &str) -> Result<Issue> {
+    let issue = client.issue(key).await?;
+    if config.status_for(&issue.status) == status {
+        return Ok(issue);
+    }
+    match config.transition_for(status, &client.transitions(key).await?) {
+        Some(transition) => {
+            client.transition(key, transition).await?;
+            client.issue(key).await
+        }
+        None => Ok(issue),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(name: &str, category: &str) -> Status {
+        Status { name: name.to_string(), category: category.to_string() }
+    }
+
+    #[test]
+    fn test_status_mapping() {
+        let mut config = JiraConfig::default();
+        config.statuses.insert("In Review".to_string(), "testing".to_string());
+
+        assert_eq!(config.status_for(&status("To Do", "new")), "open");
+        assert_eq!(config.status_for(&status("In Review", "indeterminate")), "testing");
+        assert_eq!(config.status_for(&status("In Progress", "indeterminate")), "in-progress");
+        assert_eq!(config.status_for(&status("Done", "done")), "closed");
+
+        let transitions: Vec<Transition> = ["To Do:new", "In Progress:indeterminate", "In Review:indeterminate", "Done:done"]
+            .iter()
+            .enumerate()
+            .map(|(id, to)| {
+                let (name, category) = to.split_once(':').unwrap();
+                Transition { id: id.to_string(), to: status(name, category) }
+            })
+            .collect();
+        let name = |status: &str| config.transition_for(status, &transitions).map(|t| t.to.name.clone());
+        assert_eq!(name("open").as_deref(), Some("To Do"));
+        assert_eq!(name("testing").as_deref(), Some("In Review"));
+        assert_eq!(name("blocked").as_deref(), Some("In Progress"));
+        assert_eq!(name("wontfix").as_deref(), Some("Done"));
+        assert!(config.transition_for("closed", &transitions[..3]).is_none());
+    }
+
+    #[test]
+    fn test_issue_fields() {
+        let mut config = JiraConfig::default();
+        config.fields.insert("due_date".to_string(), "customfield_10015".to_string());
+        config.fields.insert("description".to_string(), String::new());
+
+        let issue = Issue::from_json(json!({
+            "id": "10001",
+            "key": "WEB-1",
+            "fields": {
+                "summary": "Fix login",
+                "description": "Not synced",
+                "status": { "name": "In Progress", "statusCategory": { "key": "indeterminate" } },
+                "priority": { "name": "Highest" },
+                "customfield_10015": "2024-06-01",
+                "updated": "2024-05-01T11:00:00.000+0200"
+            }
+        }))
+        .unwrap();
+        assert_eq!(issue.updated_at.to_string(), "2024-05-01 09:00:00");
+        assert_eq!(issue.text(&config, "name").as_deref(), Some("Fix login"));
+        assert_eq!(issue.text(&config, "description"), None);
+        assert_eq!(issue.priority(&config), Some(Some("critical")));
+        assert_eq!(issue.due_date(&config), Some(NaiveDate::from_ymd_opt(2024, 6, 1)));
+
+        let ticket = Ticket {
+            id: 1,
+            project: "webapp".to_string(),
+            name: "Fix login".to_string(),
+            description: "Details".to_string(),
+            status: "open".to_string(),
+            created_at: issue.updated_at,
+            updated_at: issue.updated_at,
+            due_date: None,
+            rank: 1,
+            priority: Some("low".to_string()),
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        };
+        assert_eq!(
+            Value::Object(issue_fields(&config, &ticket)),
+            json!({ "summary": "Fix login", "priority": { "name": "Low" }, "customfield_10015": null })
+        );
+    }
+}
@@ -1,9 +1,41 @@
+// The ticket store lives in ltm-core; its modules keep their old paths here
+pub use ltm_core::{backup, db, duration, json_formatting, models, project_settings};
+
+pub mod archive;
 pub mod commands;
-pub mod db;
 pub mod formatting;
-pub mod json_formatting;
-pub mod models;
 pub mod validation;
 pub mod interactive;
 pub mod suggestions;
 pub mod feedback;
+pub mod git_hooks;
+pub mod linear;
+pub mod jira;
+pub mod invoice;
+pub mod email;
+pub mod webhook;
+pub mod currency;
+pub mod sync;
+pub mod completion;
+pub mod server;
+pub mod daemon;
+pub mod remote;
+pub mod graphql;
+pub mod logging;
+pub mod config;
+pub mod i18n;
+pub mod output;
+pub mod workflow;
+pub mod search;
+pub mod quick;
+pub mod log_line;
+pub mod timesheet;
+pub mod time_import;
+pub mod bulk;
+pub mod shortcut;
+pub mod next;
+pub mod status_line;
+pub mod snippet;
+pub mod site;
+pub mod render;
+pub mod results;
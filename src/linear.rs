@@ -0,0 +1,425 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::db::Database;
+use crate::models::Ticket;
+use crate::sync::{self, Change, Fields, RemoteIssue, Resolver};
+
+/// Provider name in `remote_links` and `sync_state`
+pub const PROVIDER: &str = "linear";
+
+/// Environment variable with a Linear API key, used when the config file has none
+pub const API_KEY_ENV: &str = "LINEAR_API_KEY";
+
+const ENDPOINT: &str = "https://api.linear.app/graphql";
+
+/// The `[linear]` table of `~/.ltm/config.toml`:
+///
+/// ```toml
+/// [linear]
+/// api_key = "lin_api_..."    # or set LINEAR_API_KEY
+///
+/// [linear.teams]             # team key = project
+/// ENG = "webapp"
+///
+/// [linear.states]            # workflow state = status, where the state's type is not enough
+/// "In Review" = "testing"
+/// ```
+///
+/// Other states map by type: backlog, triage, and unstarted to `open`, started to
+/// `in-progress`, completed to `closed`, and canceled to `cancelled`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct LinearConfig {
+    pub api_key: Option<String>,
+    pub teams: BTreeMap<String, String>,
+    pub states: BTreeMap<String, String>,
+}
+
+impl LinearConfig {
+    /// The API key from the config file or `LINEAR_API_KEY`
+    pub fn api_key(&self) -> Result<String> {
+        match self.api_key.clone().or_else(|| std::env::var(API_KEY_ENV).ok()) {
+            Some(key) if !key.trim().is_empty() => Ok(key.trim().to_string()),
+            _ => anyhow::bail!(
+                "No Linear API key; set api_key under [linear] in config.toml or {}",
+                API_KEY_ENV
+            ),
+        }
+    }
+
+    /// The status a ticket gets for an issue in `state`
+    pub fn status_for_state(&self, state: &State) -> String {
+        if let Some(status) = self.states.get(&state.name) {
+            return status.clone();
+        }
+        match state.kind.as_str() {
+            "started" => "in-progress",
+            "completed" => "closed",
+            "canceled" => "cancelled",
+            _ => "open",
+        }
+        .to_string()
+    }
+
+    /// An issue's fields as a ticket would have them
+    pub fn fields(&self, issue: &Issue) -> Fields {
+        Fields::from([
+            ("name".to_string(), json!(issue.title)),
+            ("description".to_string(), json!(issue.description.as_deref().unwrap_or_default())),
+            ("status".to_string(), json!(self.status_for_state(&issue.state))),
+            ("priority".to_string(), json!(priority_from_linear(issue.priority))),
+            ("due_date".to_string(), json!(issue.due_date)),
+        ])
+    }
+
+    /// The team state for a ticket's status: one mapped to it by name, else the first state
+    /// of the matching type
+    pub fn state_for_status<'a>(&self, status: &str, states: &'a [State]) -> Option<&'a State> {
+        let mapped = states.iter().find(|s| self.states.get(&s.name).is_some_and(|mapped| mapped == status));
+        let kind = match status {
+            "open" => "unstarted",
+            "closed" | "completed" | "done" => "completed",
+            "cancelled" | "wontfix" => "canceled",
+            _ => "started",
+        };
+        let mut of_kind: Vec<&State> = states.iter().filter(|s| s.kind == kind).collect();
+        of_kind.sort_by(|a, b| a.position.total_cmp(&b.position));
+        mapped.or(of_kind.first().copied())
+    }
+}
+
+/// A workflow state of a Linear team
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct State {
+    pub id: String,
+    pub name: String,
+    /// backlog, triage, unstarted, started, completed, or canceled
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub position: f64,
+}
+
+/// The items of a GraphQL connection
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nodes<T> {
+    pub nodes: Vec<T>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Team {
+    pub id: String,
+    pub key: String,
+    pub states: Nodes<State>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub id: String,
+    /// e.g. `ENG-123`
+    pub identifier: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// 0 none, 1 urgent, 2 high, 3 normal, 4 low
+    pub priority: i64,
+    pub due_date: Option<NaiveDate>,
+    pub updated_at: DateTime<Utc>,
+    pub state: State,
+}
+
+/// A ticket priority for a Linear priority
+pub fn priority_from_linear(priority: i64) -> Option<&'static str> {
+    match priority {
+        1 => Some("critical"),
+        2 => Some("high"),
+        3 => Some("medium"),
+        4 => Some("low"),
+        _ => None,
+    }
+}
+
+/// A Linear priority for a ticket priority
+pub fn priority_to_linear(priority: Option<&str>) -> i64 {
+    match priority {
+        Some("critical") => 1,
+        Some("high") => 2,
+        Some("medium") => 3,
+        Some("low") => 4,
+        _ => 0,
+    }
+}
+
+impl RemoteIssue for Issue {
+    fn remote_id(&self) -> &str {
+        &self.id
+    }
+    fn key(&self) -> &str {
+        &self.identifier
+    }
+    fn title(&self) -> &str {
+        &self.title
+    }
+    fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at.naive_utc()
+    }
+}
+
+type Action = sync::Action<Issue>;
+
+/// Fields of an issue as ltm writes them
+struct IssueInput<'a> {
+    ticket: &'a Ticket,
+    state: Option<&'a State>,
+}
+
+impl IssueInput<'_> {
+    fn to_json(&self) -> Value {
+        let mut input = json!({
+            "title": self.ticket.name,
+            "description": self.ticket.description,
+            "priority": priority_to_linear(self.ticket.priority.as_deref()),
+            "dueDate": self.ticket.due_date,
+        });
+        if let Some(state) = self.state {
+            input["stateId"] = state.id.clone().into();
+        }
+        input
+    }
+}
+
+const ISSUE_FIELDS: &str = "id identifier title description priority dueDate updatedAt state { id name type position }";
+
+/// A client for Linear's GraphQL API
+pub struct Client {
+    http: reqwest::Client,
+    api_key: String,
+}
+
+impl Client {
+    pub fn new(api_key: String) -> Self {
+        Self { http: reqwest::Client::new(), api_key }
+    }
+
+    async fn query<T: DeserializeOwned>(&self, query: &str, variables: Value) -> Result<T> {
+        let response = self
+            .http
+            .post(ENDPOINT)
+            .header("Authorization", &self.api_key)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .context("Could not reach Linear")?;
+        let status = response.status();
+        let body: Value = response.json().await.context("Invalid response from Linear")?;
+        if let Some(errors) = body["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            anyhow::bail!("Linear refused the request: {}", messages.join("; "));
+        }
+        if !status.is_success() {
+            anyhow::bail!("Linear answered with {}", status);
+        }
+        serde_json::from_value(body["data"].clone()).context("Unexpected response from Linear")
+    }
+
+    /// Every team the key can see, with its workflow states
+    pub async fn teams(&self) -> Result<Vec<Team>> {
+        #[derive(Deserialize)]
+        struct Data {
+            teams: Nodes<Team>,
+        }
+        let query = "query { teams(first: 250) { nodes { id key states(first: 100) { nodes { id name type position } } } } }";
+        let data: Data = self.query(query, json!({})).await?;
+        Ok(data.teams.nodes)
+    }
+
+    /// A team's issues changed after `since`, or all of them
+    pub async fn issues(&self, team: &str, since: Option<NaiveDateTime>) -> Result<Vec<Issue>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PageInfo {
+            has_next_page: bool,
+            end_cursor: Option<String>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Page {
+            nodes: Vec<Issue>,
+            page_info: PageInfo,
+        }
+        #[derive(Deserialize)]
+        struct Data {
+            issues: Page,
+        }
+
+        let query = format!(
+            "query Issues($filter: IssueFilter, $after: String) {{ issues(filter: $filter, first: 100, after: $after) {{ nodes {{ {} }} pageInfo {{ hasNextPage endCursor }} }} }}",
+            ISSUE_FIELDS
+        );
+        let mut filter = json!({ "team": { "key": { "eq": team } } });
+        if let Some(since) = since {
+            filter["updatedAt"] = json!({ "gt": since.and_utc() });
+        }
+        let mut issues = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let data: Data = self.query(&query, json!({ "filter": filter, "after": after })).await?;
+            issues.extend(data.issues.nodes);
+            match data.issues.page_info {
+                PageInfo { has_next_page: true, end_cursor: Some(cursor) } => after = Some(cursor),
+                _ => return Ok(issues),
+            }
+        }
+    }
+
+    async fn create_issue(&self, team: &Team, input: IssueInput<'_>) -> Result<Issue> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Data {
+            issue_create: Payload,
+        }
+        let query = format!(
+            "mutation Create($input: IssueCreateInput!) {{ issueCreate(input: $input) {{ issue {{ {} }} }} }}",
+            ISSUE_FIELDS
+        );
+        let mut input = input.to_json();
+        input["teamId"] = team.id.clone().into();
+        let data: Data = self.query(&query, json!({ "input": input })).await?;
+        data.issue_create.issue.context("Linear did not create the issue")
+    }
+
+    async fn update_issue(&self, id: &str, input: IssueInput<'_>) -> Result<Issue> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Data {
+            issue_update: Payload,
+        }
+        let query = format!(
+            "mutation Update($id: String!, $input: IssueUpdateInput!) {{ issueUpdate(id: $id, input: $input) {{ issue {{ {} }} }} }}",
+            ISSUE_FIELDS
+        );
+        let data: Data = self.query(&query, json!({ "id": id, "input": input.to_json() })).await?;
+        data.issue_update.issue.context("Linear did not update the issue")
+    }
+}
+
+#[derive(Deserialize)]
+struct Payload {
+    issue: Option<Issue>,
+}
+
+/// Sync the configured teams with their projects in both directions, settling conflicts
+/// with `resolve`. Returns what was done, or with `dry_run` what would be.
+pub async fn sync(db: &Database, config: &LinearConfig, dry_run: bool, resolve: Resolver<'_>) -> Result<Vec<Change>> {
+    if config.teams.is_empty() {
+        anyhow::bail!("No Linear teams to sync; map team keys to projects under [linear.teams] in config.toml");
+    }
+    let client = Client::new(config.api_key()?);
+    let started = Utc::now().naive_utc();
+    let since = db.last_synced_at(PROVIDER).await?;
+
+    let teams: HashMap<String, Team> = client.teams().await?.into_iter().map(|t| (t.key.clone(), t)).collect();
+    let mut issues = BTreeMap::new();
+    let mut tickets = Vec::new();
+    for (key, project) in &config.teams {
+        if !teams.contains_key(key) {
+            anyhow::bail!("Linear has no team '{}' for this API key", key);
+        }
+        issues.insert(key.clone(), client.issues(key, since).await?);
+        tickets.extend(db.list_tickets(Some(project)).await?);
+    }
+    let links = db.list_remote_links(PROVIDER).await?;
+
+    let actions = sync::plan(&config.teams, &issues, &tickets, &links, |issue| config.fields(issue));
+    if dry_run {
+        return Ok(actions.iter().map(Action::change).collect());
+    }
+    for action in &actions {
+        apply(db, config, &client, &teams, action, resolve).await?;
+    }
+    db.set_last_synced_at(PROVIDER, started).await?;
+    Ok(actions.iter().map(Action::change).collect())
+}
+
+async fn apply(
+    db: &Database,
+    config: &LinearConfig,
+    client: &Client,
+    teams: &HashMap<String, Team>,
+    action: &Action,
+    resolve: Resolver<'_>,
+) -> Result<()> {
+    let (ticket_id, issue) = match action {
+        Action::Import { issue, project } => {
+            let status = config.status_for_state(&issue.state);
+            let description = issue.description.as_deref().unwrap_or_default();
+            let id = db.add_ticket_with_status(project, &issue.title, description, &status).await?;
+            let ticket = db.get_ticket(id).await?.context("Imported ticket not found")?;
+            sync::update_ticket(db, &ticket, &config.fields(issue)).await?;
+            (id, issue.clone())
+        }
+        Action::Pull { ticket, issue, .. } => {
+            sync::update_ticket(db, ticket, &config.fields(issue)).await?;
+            (ticket.id, issue.clone())
+        }
+        Action::Merge { ticket, issue, link, pull, conflicts } => {
+            let fields = sync::resolve_conflicts(db, PROVIDER, ticket, issue, pull, conflicts, resolve).await?;
+            let ticket = &sync::merge_into_ticket(db, ticket, &fields).await?;
+            let team = teams.values().find(|team| config.teams.get(&team.key) == Some(&ticket.project));
+            let state = team.and_then(|team| config.state_for_status(&ticket.status, &team.states.nodes));
+            (ticket.id, client.update_issue(&link.remote_id, IssueInput { ticket, state }).await?)
+        }
+        Action::Export { ticket, scope } => {
+            let team = &teams[scope];
+            let state = config.state_for_status(&ticket.status, &team.states.nodes);
+            (ticket.id, client.create_issue(team, IssueInput { ticket, state }).await?)
+        }
+        Action::Push { ticket, link } => {
+            let team = teams.values().find(|team| config.teams.get(&team.key) == Some(&ticket.project));
+            let state = team.and_then(|team| config.state_for_status(&ticket.status, &team.states.nodes));
+            (ticket.id, client.update_issue(&link.remote_id, IssueInput { ticket, state }).await?)
+        }
+    };
+    sync::save_link(db, PROVIDER, ticket_id, &issue, config.fields(&issue)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(name: &str, kind: &str, position: f64) -> State {
+        State { id: format!("state-{}", name), name: name.to_string(), kind: kind.to_string(), position }
+    }
+
+    #[test]
+    fn test_state_mapping() {
+        let mut config = LinearConfig::default();
+        config.states.insert("In Review".to_string(), "testing".to_string());
+        let states = vec![
+            state("Backlog", "backlog", 0.0),
+            state("Todo", "unstarted", 1.0),
+            state("In Review", "started", 3.0),
+            state("In Progress", "started", 2.0),
+            state("Done", "completed", 4.0),
+            state("Canceled", "canceled", 5.0),
+        ];
+
+        assert_eq!(config.status_for_state(&states[0]), "open");
+        assert_eq!(config.status_for_state(&states[2]), "testing");
+        assert_eq!(config.status_for_state(&states[3]), "in-progress");
+        assert_eq!(config.status_for_state(&states[5]), "cancelled");
+
+        let name = |status: &str| config.state_for_status(status, &states).map(|s| s.name.clone());
+        assert_eq!(name("open").as_deref(), Some("Todo"));
+        assert_eq!(name("testing").as_deref(), Some("In Review"));
+        assert_eq!(name("blocked").as_deref(), Some("In Progress"));
+        assert_eq!(name("wontfix").as_deref(), Some("Canceled"));
+        assert_eq!(priority_from_linear(priority_to_linear(Some("high"))), Some("high"));
+        assert_eq!(priority_to_linear(None), 0);
+    }
+}
@@ -0,0 +1,182 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::validation::validate_ticket_id;
+
+/// Time worked described in a sentence, e.g. `2h on 14 yesterday debugging auth`.
+///
+/// Pieces are pulled out as written; the ticket is validated when the time is logged.
+#[derive(Debug, Default, PartialEq)]
+pub struct LogLine {
+    /// `2h`, `1h 30m`, `1.5h`, `45 min`, or `2h30m`, in minutes
+    pub minutes: Option<i64>,
+    /// `on 14`, `for WEB-12`, or `#14`
+    pub ticket: Option<String>,
+    /// `today`, `yesterday`, `friday` (the last one), `last monday`, `3 days ago`, or a
+    /// YYYY-MM-DD date, optionally after `on`
+    pub date: Option<NaiveDate>,
+    /// The remaining words, without a leading `-`, `:`, `on`, or `for`
+    pub note: String,
+}
+
+/// Split a sentence into the duration, ticket, day, and note of a time log.
+///
+/// The first duration, ticket, and date count; later ones stay in the note, so
+/// `1h on 14 fixing 3m timeouts on 2 hosts` logs an hour on ticket 14.
+pub fn parse_log_line(text: &str, today: NaiveDate) -> LogLine {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut line = LogLine::default();
+    let mut note = Vec::new();
+    let mut in_duration = false;
+    let mut i = 0;
+
+    while i < words.len() {
+        let rest = &words[i..];
+        if let Some((minutes, used)) = duration_at(rest).filter(|_| line.minutes.is_none() || in_duration) {
+            // Too much to add up is left to fail validation, like any other overlong duration
+            line.minutes = Some(line.minutes.unwrap_or(0).saturating_add(minutes));
+            in_duration = true;
+            i += used;
+            continue;
+        }
+        in_duration = false;
+        if let Some((date, used)) = date_at(rest, today).filter(|_| line.date.is_none()) {
+            line.date = Some(date);
+            i += used;
+        } else if let Some((ticket, used)) = ticket_at(rest).filter(|_| line.ticket.is_none()) {
+            line.ticket = Some(ticket);
+            i += used;
+        } else {
+            note.push(words[i]);
+            i += 1;
+        }
+    }
+
+    let connectors = ["-", "–", "—", ":", "on", "for"];
+    let start = note.iter().take_while(|w| connectors.contains(&w.to_lowercase().as_str())).count();
+    line.note = note[start..].join(" ");
+    line
+}
+
+/// Minutes in a single-word duration such as `2h`, `1h30m`, `1.5h`, or `45min`
+pub fn duration_word(word: &str) -> Option<i64> {
+    duration_at(&[word]).map(|(minutes, _)| minutes)
+}
+
+/// A word without trailing punctuation, lowercased
+fn bare(word: &str) -> String {
+    word.trim_end_matches([',', ';', ':', '.']).to_lowercase()
+}
+
+fn unit_minutes(unit: &str) -> Option<f64> {
+    match unit {
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(60.0),
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Minutes in `2h`, `1.5h`, `45min`, `2h30m`, or `2 hours` at the start of `words`, and
+/// how many words that took
+fn duration_at(words: &[&str]) -> Option<(i64, usize)> {
+    let word = bare(words.first()?);
+    if let Some((hours, minutes)) = word.split_once('h') {
+        let minutes = minutes.strip_suffix('m').unwrap_or(minutes);
+        if let (Ok(hours), Ok(minutes)) = (hours.parse::<i64>(), minutes.parse::<i64>()) {
+            return Some((hours.checked_mul(60)?.checked_add(minutes)?, 1));
+        }
+    }
+    let split = word.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(word.len());
+    let amount: f64 = word[..split].parse().ok()?;
+    let (unit, used) = match &word[split..] {
+        "" => (bare(words.get(1)?), 2),
+        unit => (unit.to_string(), 1),
+    };
+    let minutes = (amount * unit_minutes(&unit)?).round() as i64;
+    (minutes > 0).then_some((minutes, used))
+}
+
+/// The day named at the start of `words`, and how many words that took; a weekday such as
+/// `fri` or `friday` is the most recent one, today included unless it says `last`
+fn date_at(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let word = bare(words.first()?);
+    if word == "on" {
+        return date_at(&words[1..], today).map(|(date, used)| (date, used + 1));
+    }
+    let back = |weekday: Weekday, skip_today: bool| {
+        let days = (today.weekday().num_days_from_monday() as i64 - weekday.num_days_from_monday() as i64).rem_euclid(7);
+        today - Duration::days(if days == 0 && skip_today { 7 } else { days })
+    };
+    match word.as_str() {
+        "today" => return Some((today, 1)),
+        "yesterday" => return Some((today - Duration::days(1), 1)),
+        "last" => return bare(words.get(1)?).parse().ok().map(|day| (back(day, true), 2)),
+        _ => {}
+    }
+    if let Ok(day) = word.parse::<Weekday>() {
+        return Some((back(day, false), 1));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&word, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    let days: i64 = word.parse().ok()?;
+    match (bare(words.get(1)?).as_str(), bare(words.get(2)?).as_str()) {
+        ("day" | "days", "ago") => Some((today - Duration::days(days), 3)),
+        _ => None,
+    }
+}
+
+/// The ticket named at the start of `words` as `#14`, `on 14`, `for WEB-12`, or
+/// `ticket 14`, and how many words that took
+fn ticket_at(words: &[&str]) -> Option<(String, usize)> {
+    let word = bare(words.first()?);
+    let (reference, used) = match word.strip_prefix('#') {
+        Some(reference) => (reference.to_string(), 1),
+        None if matches!(word.as_str(), "on" | "for" | "ticket") => {
+            let next = bare(words.get(1)?);
+            (next.strip_prefix('#').unwrap_or(&next).to_string(), 2)
+        }
+        None => return None,
+    };
+    validate_ticket_id(&reference).ok().map(|_| (reference.to_uppercase(), used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_line() {
+        // 2024-05-08 is a Wednesday
+        let today = NaiveDate::from_ymd_opt(2024, 5, 8).unwrap();
+        let day = |d: u32| Some(NaiveDate::from_ymd_opt(2024, 5, d).unwrap());
+        let parse = |text: &str| parse_log_line(text, today);
+
+        assert_eq!(
+            parse("2h on 14 yesterday debugging auth"),
+            LogLine { minutes: Some(120), ticket: Some("14".to_string()), date: day(7), note: "debugging auth".to_string() }
+        );
+        let line = parse("yesterday, 1h 30m for web-12: release notes");
+        assert_eq!((line.minutes, line.ticket.as_deref(), line.date), (Some(90), Some("WEB-12"), day(7)));
+        assert_eq!(line.note, "release notes");
+
+        assert_eq!(parse("1.5h #3").minutes, Some(90));
+        assert_eq!(parse("45 min standup").minutes, Some(45));
+        assert_eq!(parse("2h30m").minutes, Some(150));
+        assert_eq!(parse("2 hours on friday").date, day(3));
+        assert_eq!(parse("2h wednesday").date, day(8));
+        assert_eq!(parse("2h last wednesday").date, day(1));
+        assert_eq!(parse("2h 3 days ago").date, day(5));
+        assert_eq!(parse("2h on 2024-04-30").date, NaiveDate::from_ymd_opt(2024, 4, 30));
+
+        // Only the first of each counts, and words that are none of them make up the note
+        let line = parse("1h on 14 fixing 3m timeouts on 2 hosts");
+        assert_eq!((line.minutes, line.ticket.as_deref()), (Some(60), Some("14")));
+        assert_eq!(line.note, "fixing 3m timeouts on 2 hosts");
+        let line = parse("on auth refactor");
+        assert_eq!((line.minutes, line.ticket, line.date), (None, None, None));
+        assert_eq!(line.note, "auth refactor");
+
+        assert_eq!(parse("153722867280912931h0m").minutes, None);
+        assert_eq!(parse("9223372036854775807m 1m").minutes, Some(i64::MAX));
+    }
+}
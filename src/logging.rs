@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable holding a tracing filter directive, e.g. `LTM_LOG=lticket=debug,sqlx=info`
+pub const LOG_ENV: &str = "LTM_LOG";
+
+/// Default filter for a `-v` count when `LTM_LOG` is not set.
+///
+/// Quiet runs only show warnings (such as slow SQL statements); `-v` adds command and
+/// migration steps, and `-vv` adds every SQL statement with its timing.
+pub fn default_filter(verbosity: u8) -> &'static str {
+    match verbosity {
+        0 => "warn",
+        1 => "warn,ltm=debug,lticket=debug",
+        _ => "warn,ltm=trace,lticket=trace,sqlx::query=debug",
+    }
+}
+
+/// Install the global subscriber, writing to stderr or appending to `log_file`
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    let filter = match std::env::var(LOG_ENV) {
+        Ok(directives) if !directives.trim().is_empty() => {
+            EnvFilter::try_new(&directives).with_context(|| format!("Invalid {} filter '{}'", LOG_ENV, directives))?
+        }
+        _ => EnvFilter::new(default_filter(verbosity)),
+    };
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter).with_target(true);
+    let result = match log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Could not open log file {}", path.display()))?;
+            builder.with_ansi(false).with_writer(Mutex::new(file)).try_init()
+        }
+        None => builder.with_writer(std::io::stderr).try_init(),
+    };
+    result.map_err(|e| anyhow::anyhow!("Could not initialise logging: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filters_parse() {
+        for verbosity in 0..4 {
+            assert!(EnvFilter::try_new(default_filter(verbosity)).is_ok());
+        }
+        assert!(!default_filter(1).contains("sqlx"));
+        assert!(default_filter(2).contains("sqlx::query=debug"));
+    }
+}
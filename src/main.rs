@@ -1,21 +1,89 @@
-mod commands;
-mod db;
-mod formatting;
-mod json_formatting;
-mod models;
-mod validation;
-mod interactive;
-mod suggestions;
-mod feedback;
-
 use anyhow::Result;
-use clap::Parser;
-use commands::{Cli, CommandHandler};
+use lticket::commands::{Cli, CommandHandler};
+use lticket::config::Config;
+use lticket::remote::{self, Route};
+use lticket::{backup, daemon, db, i18n, logging, output};
+use std::time::Instant;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let db = db::Database::new().await?;
-    let mut handler = CommandHandler::new(db);
-    handler.handle_command(cli).await
+    let config = Config::load()?;
+    Cli::check_shortcuts(&config.shortcuts)?;
+    let cli = Cli::parse_with_shortcuts(&config.shortcuts);
+    logging::init(cli.verbose, cli.log_file.as_deref())?;
+
+    let locale = i18n::detect_locale(config.locale.as_deref());
+    i18n::set_locale(locale);
+    config.apply_limits()?;
+    config.apply_validation_rules()?;
+    config.apply_status_aliases()?;
+    config.check_webhooks()?;
+    tracing::debug!(?locale, "selected locale");
+    if !config.emoji.unwrap_or(true) || output::emoji_disabled_by_env() {
+        output::set_emoji(false);
+    }
+    if cli.plain || config.plain {
+        output::set_plain(true);
+        colored::control::set_override(false);
+    }
+
+    let started = Instant::now();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    tracing::debug!(?args, "starting command");
+
+    if let Some(url) = config.remote.url().filter(|_| cli.route() != Route::Local) {
+        tracing::debug!(%url, "running against a shared server");
+        return remote::run(cli, config, url).await;
+    }
+
+    // Status bars poll these; a running `ltm daemon` answers without the database being opened
+    if cli.is_polled() {
+        if let Some(state) = daemon::query().await {
+            return cli.run_polled(&config, &state);
+        }
+    }
+
+    // A server running a client's command stamps the client's identity
+    let user = std::env::var(remote::USER_ENV).ok().or_else(|| config.user.identity());
+    let db = db::Database::open()
+        .await?
+        .with_command(format!("ltm {}", args.join(" ")))
+        .with_user(user);
+    if !cli.is_migrate() {
+        upgrade_schema(&db, &config).await?;
+    }
+    if !cli.is_polled() {
+        backup::daily(&db, &config.backup).await;
+    }
+    let mut handler = CommandHandler::new(db).with_config(config);
+    let result = handler.handle_command(cli).await;
+
+    tracing::debug!(elapsed_ms = started.elapsed().as_millis() as u64, ok = result.is_ok(), "command finished");
+    result
+}
+
+/// Apply pending migrations before running a command, saying so for an existing database.
+/// With `auto_migrate = false` an existing database is left for `ltm migrate`.
+async fn upgrade_schema(db: &db::Database, config: &Config) -> Result<()> {
+    let Some(version) = db.schema_version().await else {
+        db.migrate(&config.backup).await?;
+        return Ok(());
+    };
+    let pending = db.pending_migrations().await;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    if !config.auto_migrate.unwrap_or(true) {
+        anyhow::bail!(
+            "The database (schema version {}) needs {} migrations. Run 'ltm migrate' to apply them.",
+            version,
+            pending.len()
+        );
+    }
+    let applied = db.migrate(&config.backup).await?;
+    eprintln!(
+        "{}",
+        output::with_icon("🔧", format!("Upgraded the database schema ({} migrations applied)", applied.len()))
+    );
+    Ok(())
 }
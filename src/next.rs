@@ -0,0 +1,132 @@
+use chrono::NaiveDate;
+
+use crate::models::Ticket;
+use crate::validation::is_finished_status;
+
+/// A ticket worth working on, with the reasons it scored as it did
+#[derive(Debug)]
+pub struct Recommendation<'a> {
+    pub ticket: &'a Ticket,
+    pub score: i64,
+    /// Short phrases such as "high priority" or "overdue by 2 days"
+    pub reasons: Vec<String>,
+}
+
+/// Rank unfinished, unblocked tickets by what to work on next, best first.
+///
+/// Priority and due date weigh most, work already in progress gets a nudge, and older
+/// tickets slowly climb so nothing waits forever. Ties go to the higher backlog rank.
+pub fn recommend(tickets: &[Ticket], today: NaiveDate) -> Vec<Recommendation<'_>> {
+    let mut recommendations: Vec<Recommendation> = tickets
+        .iter()
+        .filter(|t| !is_finished_status(&t.status) && !t.status.eq_ignore_ascii_case("blocked"))
+        .map(|ticket| score(ticket, today))
+        .collect();
+    recommendations.sort_by_key(|r| (-r.score, r.ticket.rank, r.ticket.id));
+    recommendations
+}
+
+fn score(ticket: &Ticket, today: NaiveDate) -> Recommendation<'_> {
+    let mut score = 0;
+    let mut reasons = Vec::new();
+
+    let priority = ticket.priority.as_deref().unwrap_or("");
+    score += match priority {
+        "critical" => 40,
+        "high" => 30,
+        "medium" => 20,
+        "low" => 5,
+        _ => 15,
+    };
+    if matches!(priority, "critical" | "high") {
+        reasons.push(format!("{} priority", priority));
+    }
+
+    if let Some(due) = ticket.due_date {
+        let days = (due - today).num_days();
+        score += match days {
+            d if d < 0 => 50 + (-d).min(10) * 2,
+            0 => 45,
+            1..=3 => 30,
+            4..=7 => 15,
+            _ => 0,
+        };
+        match days {
+            d if d < 0 => reasons.push(format!("overdue by {} {}", -d, if d == -1 { "day" } else { "days" })),
+            0 => reasons.push("due today".to_string()),
+            1 => reasons.push("due tomorrow".to_string()),
+            2..=7 => reasons.push(format!("due in {} days", days)),
+            _ => {}
+        }
+    }
+
+    if ticket.status.eq_ignore_ascii_case("in-progress") {
+        score += 10;
+        reasons.push("already in progress".to_string());
+    }
+
+    let age = (today - ticket.created_at.date()).num_days().max(0);
+    score += age.min(30) / 3;
+    if age >= 14 {
+        reasons.push(format!("waiting {} days", age));
+    }
+
+    Recommendation { ticket, score, reasons }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn ticket(id: i64, status: &str, priority: Option<&str>, due_in: Option<i64>, today: NaiveDate) -> Ticket {
+        let created = (today - Duration::days(1)).and_hms_opt(9, 0, 0).unwrap();
+        Ticket {
+            id,
+            project: "webapp".to_string(),
+            name: format!("Ticket {}", id),
+            description: String::new(),
+            status: status.to_string(),
+            created_at: created,
+            updated_at: created,
+            due_date: due_in.map(|d| today + Duration::days(d)),
+            rank: id,
+            priority: priority.map(str::to_string),
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        }
+    }
+
+    #[test]
+    fn test_recommend() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let tickets = vec![
+            ticket(1, "open", Some("low"), None, today),
+            ticket(2, "open", Some("high"), None, today),
+            ticket(3, "open", None, Some(-2), today),
+            ticket(4, "blocked", Some("critical"), Some(0), today),
+            ticket(5, "closed", Some("critical"), None, today),
+            ticket(6, "in-progress", Some("medium"), None, today),
+        ];
+
+        let recommendations = recommend(&tickets, today);
+        let ids: Vec<i64> = recommendations.iter().map(|r| r.ticket.id).collect();
+        assert_eq!(ids, vec![3, 2, 6, 1]);
+        assert_eq!(recommendations[0].reasons, vec!["overdue by 2 days"]);
+        assert_eq!(recommendations[1].reasons, vec!["high priority"]);
+        assert_eq!(recommendations[2].reasons, vec!["already in progress"]);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable that turns emoji off when set to any non-empty value
+pub const NO_EMOJI_ENV: &str = "LTM_NO_EMOJI";
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+static EMOJI: AtomicBool = AtomicBool::new(true);
+
+/// ASCII stand-ins used when emoji are off; other icons are dropped
+const ASCII_MARKERS: &[(&str, &str)] = &[
+    ("✅", "[OK]"),
+    ("❌", "[ERROR]"),
+    ("ℹ️", "[INFO]"),
+    ("⚠️", "[WARN]"),
+    ("🤔", "[?]"),
+    ("🎉", "[DONE]"),
+    ("💡", "[HINT]"),
+    ("🔄", "[..]"),
+    ("📍", "[PIN]"),
+];
+
+/// Turn plain output on or off.
+///
+/// Plain output drops emoji, box drawing, and spinners in favour of labelled lines that
+/// read well with a screen reader.
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether plain output is on
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Turn emoji on or off; when off, icons become ASCII markers such as `[OK]`
+pub fn set_emoji(emoji: bool) {
+    EMOJI.store(emoji, Ordering::Relaxed);
+}
+
+/// Whether emoji are on
+pub fn use_emoji() -> bool {
+    EMOJI.load(Ordering::Relaxed)
+}
+
+/// Whether `LTM_NO_EMOJI` asks for emoji to be turned off
+pub fn emoji_disabled_by_env() -> bool {
+    std::env::var(NO_EMOJI_ENV).map(|v| !v.is_empty()).unwrap_or(false)
+}
+
+/// ASCII marker standing in for an emoji, if it has one
+pub fn ascii_marker(icon: &str) -> Option<&'static str> {
+    let icon = icon.trim_end();
+    ASCII_MARKERS.iter().find(|(emoji, _)| *emoji == icon).map(|(_, marker)| *marker)
+}
+
+/// Prefix `text` with an icon, its ASCII marker when emoji are off, or nothing in plain mode
+pub fn with_icon(icon: &str, text: impl Display) -> String {
+    if is_plain() || icon.trim_end().is_empty() {
+        return text.to_string();
+    }
+    if use_emoji() {
+        return format!("{} {}", icon, text);
+    }
+    match ascii_marker(icon) {
+        Some(marker) => format!("{} {}", marker, text),
+        None => text.to_string(),
+    }
+}
+
+/// An icon including its trailing spacing, its ASCII marker when emoji are off, or nothing
+/// in plain mode
+pub fn icon(icon: &'static str) -> String {
+    if is_plain() {
+        String::new()
+    } else if use_emoji() {
+        icon.to_string()
+    } else {
+        ascii_marker(icon).map(|marker| format!("{} ", marker)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_markers() {
+        assert_eq!(ascii_marker("✅"), Some("[OK]"));
+        assert_eq!(ascii_marker("⚠️  "), Some("[WARN]"));
+        assert_eq!(ascii_marker("📋"), None);
+        for (_, marker) in ASCII_MARKERS {
+            assert!(marker.is_ascii());
+        }
+    }
+}
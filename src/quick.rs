@@ -0,0 +1,80 @@
+/// A ticket described in one line, e.g.
+/// `Fix login redirect @webapp !high #auth due:friday est:2h`.
+///
+/// Metadata is pulled out as written; validation happens when the ticket is created.
+#[derive(Debug, Default, PartialEq)]
+pub struct QuickAdd {
+    /// Words that are not metadata, in order
+    pub name: String,
+    /// `@project`
+    pub project: Option<String>,
+    /// `!priority`
+    pub priority: Option<String>,
+    /// `#tag`, each once, in order
+    pub tags: Vec<String>,
+    /// `due:date`
+    pub due: Option<String>,
+    /// `est:duration`
+    pub estimate: Option<String>,
+}
+
+/// Split a quick-add line into a ticket name and its metadata.
+///
+/// A later `@project`, `!priority`, `due:`, or `est:` replaces an earlier one. A leading
+/// backslash keeps a word in the name, so `\#1` becomes `#1`.
+pub fn parse_quick_add(text: &str) -> QuickAdd {
+    let mut quick = QuickAdd::default();
+    let mut name = Vec::new();
+
+    for word in text.split_whitespace() {
+        if let Some(literal) = word.strip_prefix('\\') {
+            name.push(literal);
+        } else if let Some(project) = word.strip_prefix('@').filter(|p| !p.is_empty()) {
+            quick.project = Some(project.to_string());
+        } else if let Some(priority) = word.strip_prefix('!').filter(|p| !p.is_empty()) {
+            quick.priority = Some(priority.to_string());
+        } else if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            if !quick.tags.iter().any(|t| t == tag) {
+                quick.tags.push(tag.to_string());
+            }
+        } else if let Some(due) = word.strip_prefix("due:").filter(|d| !d.is_empty()) {
+            quick.due = Some(due.to_string());
+        } else if let Some(estimate) = word.strip_prefix("est:").filter(|e| !e.is_empty()) {
+            quick.estimate = Some(estimate.to_string());
+        } else {
+            name.push(word);
+        }
+    }
+
+    quick.name = name.join(" ");
+    quick
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quick_add() {
+        let quick = parse_quick_add("Fix login redirect @webapp !high #auth due:friday est:2h");
+        assert_eq!(
+            quick,
+            QuickAdd {
+                name: "Fix login redirect".to_string(),
+                project: Some("webapp".to_string()),
+                priority: Some("high".to_string()),
+                tags: vec!["auth".to_string()],
+                due: Some("friday".to_string()),
+                estimate: Some("2h".to_string()),
+            }
+        );
+
+        // Metadata may appear anywhere; bare markers and escaped words stay in the name
+        let quick = parse_quick_add("#ui Update \\#1 banner @ now #ui #copy");
+        assert_eq!(quick.name, "Update #1 banner @ now");
+        assert_eq!(quick.tags, vec!["ui".to_string(), "copy".to_string()]);
+        assert_eq!(quick.project, None);
+
+        assert_eq!(parse_quick_add("").name, "");
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::commands::{Cli, CommandHandler};
+use crate::config::Config;
+use crate::db::Database;
+use crate::feedback;
+use crate::output;
+
+/// Environment variable with the shared secret of `ltm serve`, used when the config file
+/// has none
+pub const TOKEN_ENV: &str = "LTM_SERVER_TOKEN";
+
+/// Set to use the local database even when `[remote]` names a server; the server sets it
+/// for the commands it runs
+pub const LOCAL_ENV: &str = "LTM_LOCAL";
+
+/// Identity of the client a server runs a command for, stamped instead of the server's
+pub const USER_ENV: &str = "LTM_REMOTE_USER";
+
+/// Response header with the server database's revision, see `RunResponse::revision`
+pub const REVISION_HEADER: &str = "x-ltm-revision";
+
+/// The `[remote]` table of `~/.ltm/config.toml`, which points the CLI at a shared
+/// `ltm serve` instead of the local database:
+///
+/// ```toml
+/// [remote]
+/// url = "http://tickets.lan:7878"
+/// token = "..."          # or set LTM_SERVER_TOKEN
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    pub url: Option<String>,
+    pub token: Option<String>,
+}
+
+impl RemoteConfig {
+    /// The server to use, unless there is none or `LTM_LOCAL` is set
+    pub fn url(&self) -> Option<String> {
+        if std::env::var_os(LOCAL_ENV).is_some() {
+            return None;
+        }
+        let url = self.url.as_deref().map(str::trim).filter(|url| !url.is_empty())?;
+        Some(url.trim_end_matches('/').to_string())
+    }
+
+    pub fn token(&self) -> Option<String> {
+        token_or_env(&self.token)
+    }
+}
+
+/// The `[server]` table: settings for `ltm serve`
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Shared secret clients send to run commands, besides the tokens from
+    /// `ltm serve token create`; without any, the server stays read-only
+    pub token: Option<String>,
+}
+
+impl ServerConfig {
+    pub fn token(&self) -> Option<String> {
+        token_or_env(&self.token)
+    }
+}
+
+fn token_or_env(token: &Option<String>) -> Option<String> {
+    let token = token.clone().or_else(|| std::env::var(TOKEN_ENV).ok())?;
+    Some(token.trim().to_string()).filter(|token| !token.is_empty())
+}
+
+/// Where a command runs in shared-server mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// On the server, which sends back the output
+    Server,
+    /// Against the cached copy of the server's database, refreshed first with `refresh`
+    Cache { refresh: bool },
+    /// On this machine and its own database, like without a server
+    Local,
+}
+
+/// A command line for the server to run, sent to `POST /api/run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRequest {
+    /// Arguments after `ltm`
+    pub args: Vec<String>,
+    /// The client's `[user]` identity
+    pub user: Option<String>,
+    /// Whether the client's terminal shows colors
+    #[serde(default)]
+    pub color: bool,
+    /// Whether the client shows emoji
+    #[serde(default = "default_true")]
+    pub emoji: bool,
+    /// Text for the command's stdin, such as a file the client read for it
+    #[serde(default)]
+    pub stdin: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// What a command run on the server printed, and how it exited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResponse {
+    pub status: i32,
+    pub stdout: String,
+    pub stderr: String,
+    /// Changes whenever the server's database does, so clients know when to refresh
+    /// their cached copy
+    pub revision: String,
+}
+
+/// When and from where the cached copy was taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheInfo {
+    url: String,
+    revision: String,
+    fetched_at: DateTime<Utc>,
+}
+
+fn cache_dir() -> Result<PathBuf> {
+    Ok(dirs::home_dir().context("Could not find home directory")?.join(".ltm"))
+}
+
+/// The cached copy of the server's database, `~/.ltm/remote-cache.db`
+fn cache_path() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("remote-cache.db"))
+}
+
+fn cache_info_path() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("remote-cache.json"))
+}
+
+/// The cached copy's details, if it was taken from `url`
+fn cache_info(url: &str) -> Option<CacheInfo> {
+    let text = std::fs::read_to_string(cache_info_path().ok()?).ok()?;
+    serde_json::from_str::<CacheInfo>(&text).ok().filter(|info| info.url == url)
+}
+
+/// A client for the command API of `ltm serve`
+struct Client {
+    http: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    fn new(url: String, token: Option<String>) -> Result<Self> {
+        let http = reqwest::Client::builder().connect_timeout(Duration::from_secs(5)).build()?;
+        Ok(Self { http, url, token })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.http.request(method, format!("{}/api/{}", self.url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Check a response, turning the server's JSON errors into messages
+    async fn checked(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        match body["message"].as_str() {
+            Some(message) => anyhow::bail!("{} refused the command: {}", self.url, message),
+            None => anyhow::bail!("{} answered with {}", self.url, status),
+        }
+    }
+
+    /// Run a command on the server; `None` when it cannot be reached
+    async fn run(&self, request: &RunRequest) -> Result<Option<RunResponse>> {
+        let response = match self.request(reqwest::Method::POST, "run").json(request).send().await {
+            Ok(response) => response,
+            Err(error) if error.is_connect() || error.is_timeout() => return Ok(None),
+            Err(error) => return Err(error).with_context(|| format!("Could not reach {}", self.url)),
+        };
+        let response = self.checked(response).await?;
+        Ok(Some(response.json().await.with_context(|| format!("Invalid response from {}", self.url))?))
+    }
+
+    /// Download the server's database over the cached copy
+    async fn refresh_cache(&self) -> Result<()> {
+        let response = self.request(reqwest::Method::GET, "snapshot").send().await?;
+        let response = self.checked(response).await?;
+        let revision = response.headers().get(REVISION_HEADER).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+        let bytes = response.bytes().await?;
+
+        let path = cache_path()?;
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        let partial = path.with_extension("db.partial");
+        std::fs::write(&partial, &bytes)?;
+        std::fs::rename(&partial, &path)?;
+        let info = CacheInfo { url: self.url.clone(), revision, fetched_at: Utc::now() };
+        std::fs::write(cache_info_path()?, serde_json::to_string(&info)?)?;
+        tracing::debug!(bytes = bytes.len(), "refreshed the cached copy of the server's database");
+        Ok(())
+    }
+}
+
+/// Run a command in shared-server mode. Most commands run on the server at `url` and
+/// print what it sends back. When the server cannot be reached, they run against the
+/// copy of its database cached after the last command, which cannot be changed.
+pub async fn run(cli: Cli, config: Config, url: String) -> Result<()> {
+    if cli.watches() {
+        anyhow::bail!("--watch needs a local database; it does not work with a shared server ([remote] in config.toml)");
+    }
+    if let Some(reason) = cli.needs_client() {
+        anyhow::bail!(reason);
+    }
+    let client = Client::new(url.clone(), config.remote.token())?;
+    match cli.route() {
+        Route::Local => unreachable!("local commands do not reach the server"),
+        Route::Cache { refresh } => {
+            if refresh {
+                if let Err(error) = client.refresh_cache().await {
+                    feedback::show_warning(&format!("Could not refresh the cached copy of {}: {}", url, error));
+                }
+            }
+            return run_cached(cli, config, &url).await;
+        }
+        Route::Server => {}
+    }
+
+    // Logs are written here, by this process
+    let mut args = Vec::new();
+    let mut given = std::env::args().skip(1);
+    while let Some(arg) = given.next() {
+        if arg == "--log-file" {
+            given.next();
+        } else if !arg.starts_with("--log-file=") {
+            args.push(arg);
+        }
+    }
+    if config.plain && !args.iter().any(|arg| arg == "--plain") {
+        args.push("--plain".to_string());
+    }
+    let stdin = cli.client_file().map(|path| read_client_file(&mut args, path)).transpose()?;
+    let request = RunRequest {
+        args,
+        user: config.user.identity(),
+        color: std::io::stdout().is_terminal() && !output::is_plain(),
+        emoji: output::use_emoji(),
+        stdin,
+    };
+    let Some(response) = client.run(&request).await? else {
+        let when = cache_info(&url).map(|info| info.fetched_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string());
+        let when = when.map(|when| format!(" from {}", when)).unwrap_or_default();
+        feedback::show_warning(&format!("Could not reach {}; showing data cached{} (read-only)", url, when));
+        return run_cached(cli, config, &url).await;
+    };
+
+    print!("{}", response.stdout);
+    eprint!("{}", response.stderr);
+    std::io::stdout().flush()?;
+    if cache_info(&url).map(|info| info.revision) != Some(response.revision) {
+        if let Err(error) = client.refresh_cache().await {
+            tracing::debug!(%error, "could not refresh the cached copy of the server's database");
+        }
+    }
+    if response.status != 0 {
+        std::process::exit(response.status);
+    }
+    Ok(())
+}
+
+/// Read a file the command needs from this machine, and point its argument at stdin, which
+/// the server fills with the text
+fn read_client_file(args: &mut [String], path: &std::path::Path) -> Result<String> {
+    if path.as_os_str() == "-" {
+        return Ok(std::io::read_to_string(std::io::stdin())?);
+    }
+    let text = std::fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+    let path = path.to_string_lossy();
+    let given = args.iter_mut().rev().find(|arg| {
+        arg.as_str() == path || arg.strip_suffix(path.as_ref()).is_some_and(|flag| flag.starts_with("--") && flag.ends_with('='))
+    });
+    if let Some(arg) = given {
+        let flag = arg.len() - path.len();
+        arg.replace_range(flag.., "-");
+    }
+    Ok(text)
+}
+
+/// Run a command against the cached copy of the server's database
+async fn run_cached(cli: Cli, config: Config, url: &str) -> Result<()> {
+    let path = cache_path()?;
+    if cache_info(url).is_none() || !path.exists() {
+        anyhow::bail!("Could not reach {}, and there is no cached copy of its data yet", url);
+    }
+    let db = Database::open_read_only(&path).await?;
+    let mut handler = CommandHandler::new(db).with_config(config);
+    handler.handle_command(cli).await.map_err(|error| {
+        if error.chain().any(|cause| cause.to_string().contains("readonly database")) {
+            anyhow::anyhow!("{} cannot be reached, and its cached data is read-only; try again once it is back", url)
+        } else {
+            error
+        }
+    })
+}
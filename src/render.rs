@@ -0,0 +1,330 @@
+use clap::ValueEnum;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::formatting::csv_field;
+
+/// How command results are written, chosen with `-o`/`--output`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Text for people, following `--plain` and the emoji settings
+    #[default]
+    Human,
+    /// One line of JSON
+    Json,
+    /// Indented JSON
+    JsonPretty,
+    /// A header row and one row per record
+    Csv,
+    /// Block-style YAML
+    Yaml,
+}
+
+/// Set once a command has printed its result, see `Renderer::printed`
+static PRINTED: AtomicBool = AtomicBool::new(false);
+
+/// The result of a command, which can be shown as text or written as data.
+///
+/// Handlers build one of these instead of printing, and the `Renderer` picks the form:
+///
+/// ```text
+/// ltm today           # human text
+/// ltm today -o json   # data(), as JSON
+/// ltm today -o yaml   # data(), as YAML
+/// ltm today -o csv    # csv(), by default the records in data()
+/// ```
+pub trait Render {
+    /// Text for the terminal
+    fn human(&self) -> String;
+    /// The result as data, shared by JSON, YAML, and CSV output
+    fn data(&self) -> Value;
+    /// The result as CSV, for results with a table layout of their own
+    fn csv(&self) -> String {
+        to_csv(&self.data())
+    }
+}
+
+/// Writes command results in the output format the user chose
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer {
+    format: OutputFormat,
+}
+
+impl Renderer {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Whether results are written as data rather than text
+    pub fn is_data(&self) -> bool {
+        self.format != OutputFormat::Human
+    }
+
+    pub fn render(&self, result: &impl Render) -> String {
+        match self.format {
+            OutputFormat::Human => result.human(),
+            OutputFormat::Csv => result.csv(),
+            _ => self.render_data(&result.data()),
+        }
+    }
+
+    /// Write data that has no text form of its own, such as an error response; human
+    /// output gets pretty-printed JSON
+    pub fn render_data(&self, data: &Value) -> String {
+        match self.format {
+            OutputFormat::Human | OutputFormat::JsonPretty => serde_json::to_string_pretty(data).unwrap_or_default(),
+            OutputFormat::Json => data.to_string(),
+            OutputFormat::Csv => to_csv(data),
+            OutputFormat::Yaml => to_yaml(data),
+        }
+    }
+
+    pub fn print(&self, result: &impl Render) {
+        self.print_data_str(&self.render(result));
+    }
+
+    /// Print data with no text form of its own, see `render_data`
+    pub fn print_data(&self, data: &Value) {
+        self.print_data_str(&self.render_data(data));
+    }
+
+    fn print_data_str(&self, text: &str) {
+        PRINTED.store(true, Ordering::Relaxed);
+        println!("{}", text);
+    }
+
+    /// Whether a result has been printed, so commands without one can report their
+    /// messages as data instead
+    pub fn printed() -> bool {
+        PRINTED.load(Ordering::Relaxed)
+    }
+}
+
+/// Write data as CSV with a header row. The rows are the first list in an object (its
+/// `tickets`, say), the items of a list, or else the value itself as one row. Nested
+/// objects become dotted columns such as `elapsed.seconds`; lists of plain values are
+/// joined with `;`, and other lists are written as JSON.
+pub fn to_csv(value: &Value) -> String {
+    let list = match value {
+        Value::Array(items) => Some(("value", items)),
+        Value::Object(map) => map.iter().find_map(|(key, value)| value.as_array().map(|items| (key.as_str(), items))),
+        _ => None,
+    };
+    let rows: Vec<Vec<(String, String)>> = match list {
+        Some((key, items)) => items.iter().map(|item| csv_cells(key, item)).collect(),
+        None => vec![csv_cells("value", value)],
+    };
+
+    let mut columns: Vec<&str> = Vec::new();
+    for (column, _) in rows.iter().flatten() {
+        if !columns.contains(&column.as_str()) {
+            columns.push(column);
+        }
+    }
+    let mut lines = vec![columns.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(",")];
+    for row in &rows {
+        let cells = columns.iter().map(|column| {
+            row.iter().find(|(c, _)| c == column).map(|(_, cell)| csv_field(cell)).unwrap_or_default()
+        });
+        lines.push(cells.collect::<Vec<_>>().join(","));
+    }
+    lines.join("\n")
+}
+
+/// The cells of one CSV row; a plain value becomes a single cell called `name`
+fn csv_cells(name: &str, value: &Value) -> Vec<(String, String)> {
+    let mut cells = Vec::new();
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_cell(key, value, &mut cells);
+            }
+        }
+        other => flatten_cell(name, other, &mut cells),
+    }
+    cells
+}
+
+fn flatten_cell(column: &str, value: &Value, cells: &mut Vec<(String, String)>) {
+    let text = match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_cell(&format!("{}.{}", column, key), value, cells);
+            }
+            return;
+        }
+        Value::Array(items) if items.iter().all(|item| !item.is_object() && !item.is_array()) => {
+            items.iter().map(csv_scalar).collect::<Vec<_>>().join(";")
+        }
+        Value::Array(_) => value.to_string(),
+        other => csv_scalar(other),
+    };
+    cells.push((column.to_string(), text));
+}
+
+fn csv_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Write a JSON value as block-style YAML. Strings that YAML would read as something else
+/// (numbers, booleans, strings with `: `) are double-quoted with JSON escapes.
+pub fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    write_yaml(&mut out, value, 0);
+    out.truncate(out.trim_end().len());
+    out
+}
+
+fn write_yaml(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                out.push_str(&format!("{}{}:", pad, yaml_string(key)));
+                write_nested(out, value, indent + 2);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                if is_block(item) {
+                    // The first line of the nested block goes after the dash
+                    let mut nested = String::new();
+                    write_yaml(&mut nested, item, indent + 2);
+                    out.push_str(&format!("{}- {}", pad, &nested[indent + 2..]));
+                } else {
+                    out.push_str(&format!("{}- {}\n", pad, yaml_scalar(item)));
+                }
+            }
+        }
+        _ => out.push_str(&format!("{}{}\n", pad, yaml_scalar(value))),
+    }
+}
+
+/// The value of a mapping entry: scalars on the key's line, blocks on the lines below
+fn write_nested(out: &mut String, value: &Value, indent: usize) {
+    if is_block(value) {
+        out.push('\n');
+        write_yaml(out, value, indent);
+    } else {
+        out.push_str(&format!(" {}\n", yaml_scalar(value)));
+    }
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn yaml_string(s: &str) -> String {
+    let reserved = matches!(s.to_lowercase().as_str(), "true" | "false" | "null" | "~" | "yes" | "no" | "on" | "off");
+    let needs_quotes = s.is_empty()
+        || reserved
+        || s.parse::<f64>().is_ok()
+        || s.starts_with(|c: char| c.is_whitespace() || "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        || s.ends_with(|c: char| c.is_whitespace() || c == ':')
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.chars().any(char::is_control);
+    if needs_quotes {
+        Value::String(s.to_string()).to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Count(usize);
+
+    impl Render for Count {
+        fn human(&self) -> String {
+            format!("{} tickets", self.0)
+        }
+        fn data(&self) -> Value {
+            json!({"count": self.0})
+        }
+    }
+
+    #[test]
+    fn test_renderer() {
+        assert_eq!(Renderer::default().render(&Count(3)), "3 tickets");
+        assert_eq!(Renderer::new(OutputFormat::Json).render(&Count(3)), r#"{"count":3}"#);
+        assert_eq!(Renderer::new(OutputFormat::Yaml).render(&Count(3)), "count: 3");
+        assert!(!Renderer::default().is_data() && Renderer::new(OutputFormat::Yaml).is_data());
+        assert_eq!(Renderer::new(OutputFormat::JsonPretty).render(&Count(3)), "{\n  \"count\": 3\n}");
+        assert_eq!(Renderer::new(OutputFormat::Csv).render(&Count(3)), "count\n3");
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let value = json!({
+            "version": "1.0",
+            "timers": [
+                {"ticket_id": 1, "name": "Fix login, again", "elapsed": {"seconds": 60, "human": "1m"}, "tags": ["a", "b"]},
+                {"ticket_id": 2, "name": null, "note": "say \"hi\""}
+            ]
+        });
+        let expected = "ticket_id,name,elapsed.seconds,elapsed.human,tags,note\n\
+                        1,\"Fix login, again\",60,1m,a;b,\n\
+                        2,,,,,\"say \"\"hi\"\"\"";
+        assert_eq!(to_csv(&value), expected);
+        assert_eq!(to_csv(&json!({"projects": ["api", "web"]})), "projects\napi\nweb");
+        assert_eq!(to_csv(&json!({"open": 2, "summary": {"closed": 1}})), "open,summary.closed\n2,1");
+    }
+
+    #[test]
+    fn test_yaml_output() {
+        let value = json!({
+            "version": "1.0",
+            "project": null,
+            "tickets": [
+                {"id": 1, "name": "Fix login: again", "tags": ["auth", "web"]},
+                {"id": 2, "name": "true", "tags": []}
+            ],
+            "summary": {"open": 2, "note": "multi\nline"},
+            "empty": {}
+        });
+        let expected = r#"version: "1.0"
+project: null
+tickets:
+  - id: 1
+    name: "Fix login: again"
+    tags:
+      - auth
+      - web
+  - id: 2
+    name: "true"
+    tags: []
+summary:
+  open: 2
+  note: "multi\nline"
+empty: {}"#;
+        assert_eq!(to_yaml(&value), expected);
+        assert_eq!(to_yaml(&json!([[1, 2], "-x"])), "- - 1\n  - 2\n- \"-x\"");
+        assert_eq!(to_yaml(&json!("plain text")), "plain text");
+    }
+}
@@ -0,0 +1,591 @@
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use crate::duration::{format_duration, format_minutes, HumanDuration};
+use crate::feedback::{info_message, success_message, thinking_message};
+use crate::currency::Currencies;
+use crate::formatting::{
+    format_agenda, format_calendar, format_comment_matches, format_component_counts, format_events, format_recent, format_kind_counts, format_ticket_details,
+    format_ticket_list_with_checklists, format_time_export, format_time_export_csv, format_time_report, format_time_report_csv, format_week,
+};
+use crate::json_formatting::{
+    project_summary_response, ticket_details_response, ticket_list_response, time_report_response, EventListResponse,
+};
+use crate::models::{
+    thread_comments, ActiveTimer, ChecklistItem, Comment, Event, ProjectSummary, RecentTicket, Ticket, TicketHours, TicketLock, TicketUrl, TimeLog,
+    TimeLogEntry,
+};
+use crate::output::{icon, with_icon};
+use crate::render::Render;
+
+/// Version stamped on every JSON and YAML result, as in the `--json` responses
+const VERSION: &str = "1.0";
+
+/// `ltm list`: the matching tickets, with comments that matched a text or regex search
+pub struct TicketList {
+    pub tickets: Vec<Ticket>,
+    pub project: Option<String>,
+    /// Checklist progress `(done, total)` by ticket ID
+    pub checklists: HashMap<i64, (i64, i64)>,
+    pub comment_matches: Vec<(Comment, Range<usize>)>,
+}
+
+impl Render for TicketList {
+    fn human(&self) -> String {
+        let mut lines = vec![format_ticket_list_with_checklists(&self.tickets, &self.checklists)];
+        if !self.comment_matches.is_empty() {
+            let matches: Vec<_> = self.comment_matches.iter().map(|(c, range)| (c, range.clone())).collect();
+            lines.push(format!("\n{}", format_comment_matches(&matches)));
+        }
+        lines.push(if self.tickets.is_empty() {
+            info_message("No tickets found")
+        } else {
+            success_message(&format!("Found {} ticket(s)", self.tickets.len()))
+        });
+        lines.join("\n")
+    }
+
+    fn data(&self) -> Value {
+        json!(ticket_list_response(&self.tickets, self.project.as_deref()))
+    }
+}
+
+/// `ltm show`: a ticket with its comments, time logs, links, checklist, and tags
+pub struct TicketDetails {
+    pub ticket: Ticket,
+    pub comments: Vec<Comment>,
+    pub time_logs: Vec<TimeLog>,
+    pub links: Vec<TicketUrl>,
+    pub checklist: Vec<ChecklistItem>,
+    pub tags: Vec<String>,
+    pub lock: Option<TicketLock>,
+}
+
+impl Render for TicketDetails {
+    fn human(&self) -> String {
+        let mut details =
+            format_ticket_details(&self.ticket, &self.comments, &self.time_logs, &self.links, &self.checklist, &self.tags);
+        if let Some(lock) = &self.lock {
+            let by = lock.locked_by.as_deref().map(|user| format!(" by {}", user)).unwrap_or_default();
+            let day = lock.locked_at.and_utc().with_timezone(&Local).format("%Y-%m-%d");
+            details.push_str(&format!("\n{}Locked{} on {}; changes need --unlock\n", icon("🔒 "), by, day));
+        }
+        let done = success_message(&format!("Details for ticket {} ('{}')", self.ticket.id, self.ticket.name));
+        format!("{}\n{}", details, done)
+    }
+
+    fn data(&self) -> Value {
+        let mut data = json!(ticket_details_response(&self.ticket, &self.comments, &self.time_logs, &self.links, &self.tags));
+        data["lock"] = json!(self.lock);
+        data
+    }
+}
+
+/// `ltm project summary`; `suggestion` names similar projects when this one has no tickets
+pub struct ProjectSummaryResult {
+    pub project: String,
+    pub summary: ProjectSummary,
+    pub suggestion: Option<String>,
+}
+
+impl Render for ProjectSummaryResult {
+    fn human(&self) -> String {
+        if self.summary.total_tickets == 0 {
+            let mut lines = vec![info_message(&format!("No tickets found for project '{}'", self.project))];
+            lines.extend(self.suggestion.as_deref().map(thinking_message));
+            return lines.join("\n");
+        }
+        let mut lines = vec![
+            success_message(&with_icon("📊", format!("Project Summary for '{}':", self.project))),
+            format!("   {}Total Tickets: {}", icon("📋 "), self.summary.total_tickets),
+            format!("   {}Open Tickets: {}", icon("🟢 "), self.summary.open_tickets),
+            format!("   {}Closed Tickets: {}", icon("🔴 "), self.summary.closed_tickets),
+            format!("   {}Total Time: {}", icon("⏱️  "), format_minutes((self.summary.total_time_hours * 60.0).round() as i64)),
+        ];
+        if !self.summary.kinds.is_empty() {
+            lines.push(format!("   {}{}", icon("🧩 "), format_kind_counts(&self.summary.kinds)));
+        }
+        if !self.summary.components.is_empty() {
+            lines.push(format!("   {}{}", icon("🧱 "), format_component_counts(&self.summary.components)));
+        }
+        lines.join("\n")
+    }
+
+    fn data(&self) -> Value {
+        json!(project_summary_response(&self.project, &self.summary))
+    }
+}
+
+/// `ltm project list`: project names, sorted
+pub struct ProjectList {
+    pub projects: Vec<String>,
+}
+
+impl Render for ProjectList {
+    fn human(&self) -> String {
+        if self.projects.is_empty() {
+            return info_message("No projects found");
+        }
+        let mut lines = vec![format!("{}Projects:", icon("📁 "))];
+        lines.extend(self.projects.iter().map(|project| format!("  {}{}", icon("• "), project)));
+        lines.push(success_message(&format!("Found {} project(s)", self.projects.len())));
+        lines.join("\n")
+    }
+
+    fn data(&self) -> Value {
+        json!({ "version": VERSION, "projects": self.projects })
+    }
+}
+
+/// `ltm comment list`: a ticket's comments, replies under the comment they answer
+pub struct CommentList {
+    pub ticket_id: i64,
+    pub comments: Vec<Comment>,
+}
+
+impl Render for CommentList {
+    fn human(&self) -> String {
+        if self.comments.is_empty() {
+            return info_message(&format!("No comments found for ticket {}", self.ticket_id));
+        }
+        let mut lines = vec![format!("{}Comments for ticket {}:", icon("💬 "), self.ticket_id)];
+        for (i, (comment, depth)) in thread_comments(&self.comments).into_iter().enumerate() {
+            lines.push(format!(
+                "  {}{}. #{} {} - {}",
+                "    ".repeat(depth),
+                i + 1,
+                comment.id,
+                comment.created_at.format("%Y-%m-%d %H:%M"),
+                comment.content
+            ));
+        }
+        lines.push(success_message(&format!("Found {} comment(s)", self.comments.len())));
+        lines.join("\n")
+    }
+
+    fn data(&self) -> Value {
+        json!({ "version": VERSION, "ticket_id": self.ticket_id, "comments": self.comments })
+    }
+}
+
+/// A running or paused timer with its ticket's name, `None` when the ticket is gone
+fn timer_data(timer: &ActiveTimer, name: Option<&str>, now: NaiveDateTime) -> Value {
+    json!({
+        "ticket_id": timer.ticket_id,
+        "ticket_name": name,
+        "started_at": timer.started_at,
+        "paused": timer.is_paused(),
+        "elapsed": HumanDuration::from(timer.elapsed(now)),
+        "note": timer.note,
+    })
+}
+
+/// `ltm time active`: active timers with their tickets' names
+pub struct TimerList {
+    pub timers: Vec<(ActiveTimer, Option<String>)>,
+    pub now: NaiveDateTime,
+}
+
+impl Render for TimerList {
+    fn human(&self) -> String {
+        if self.timers.is_empty() {
+            return info_message("No active timers");
+        }
+        let mut lines = vec![format!("{}Active Timers:", icon("⏱️  "))];
+        for (timer, name) in &self.timers {
+            // Paused timers stop accumulating at the pause point
+            let elapsed = format_duration(timer.elapsed(self.now));
+            let status = if timer.is_paused() { with_icon("⏸️ ", "PAUSED") } else { with_icon("▶️ ", "RUNNING") };
+            let note = timer.note.as_deref().map(|n| format!(" - {}", n)).unwrap_or_default();
+            let ticket = match name {
+                Some(name) => format!("Ticket {} ('{}')", timer.ticket_id, name),
+                None => format!("Ticket {}", timer.ticket_id),
+            };
+            lines.push(format!("  {}{}: {} - {}{}", icon("• "), ticket, elapsed, status, note));
+        }
+        lines.push(success_message(&format!("{} active timer(s)", self.timers.len())));
+        lines.join("\n")
+    }
+
+    fn data(&self) -> Value {
+        let timers: Vec<Value> =
+            self.timers.iter().map(|(timer, name)| timer_data(timer, name.as_deref(), self.now)).collect();
+        json!({ "version": VERSION, "timers": timers })
+    }
+}
+
+/// `ltm time current`: the timer being tracked, if any, with its ticket's name
+pub struct CurrentTimer {
+    pub timer: Option<(ActiveTimer, String)>,
+    pub now: NaiveDateTime,
+}
+
+impl Render for CurrentTimer {
+    fn human(&self) -> String {
+        let Some((timer, name)) = &self.timer else {
+            return info_message("No timer running");
+        };
+        let state = if timer.is_paused() { "paused" } else { "running" };
+        format!("#{} {}: {} ({})", timer.ticket_id, name, format_duration(timer.elapsed(self.now)), state)
+    }
+
+    /// The timer as in `ltm time active`, or `{}` without one
+    fn data(&self) -> Value {
+        match &self.timer {
+            Some((timer, name)) => timer_data(timer, Some(name), self.now),
+            None => json!({}),
+        }
+    }
+}
+
+/// What a command without a result of its own did, as `(level, message)` pairs from
+/// `feedback::captured_messages`
+pub struct Messages(pub Vec<(&'static str, String)>);
+
+impl Render for Messages {
+    fn human(&self) -> String {
+        self.0.iter().map(|(_, message)| message.as_str()).collect::<Vec<_>>().join("\n")
+    }
+
+    fn data(&self) -> Value {
+        let messages: Vec<Value> =
+            self.0.iter().map(|(level, message)| json!({"level": level, "message": message})).collect();
+        json!({ "version": VERSION, "messages": messages })
+    }
+}
+
+/// A result whose text the command formats itself, such as a report or the board, with
+/// the same facts as data
+pub struct Report {
+    pub text: String,
+    pub data: Value,
+}
+
+impl Report {
+    /// `fields` is a JSON object, written after the version
+    pub fn new(text: impl Into<String>, fields: Value) -> Self {
+        let mut data = json!({ "version": VERSION });
+        if let (Some(data), Value::Object(fields)) = (data.as_object_mut(), fields) {
+            data.extend(fields);
+        }
+        Self { text: text.into(), data }
+    }
+}
+
+impl Render for Report {
+    fn human(&self) -> String {
+        self.text.clone()
+    }
+
+    fn data(&self) -> Value {
+        self.data.clone()
+    }
+}
+
+/// `ltm events`: the newest journal entries, optionally for one ticket
+pub struct EventList {
+    pub events: Vec<Event>,
+    pub ticket_id: Option<i64>,
+}
+
+impl Render for EventList {
+    fn human(&self) -> String {
+        format_events(&self.events)
+    }
+
+    fn data(&self) -> Value {
+        let response =
+            EventListResponse { version: VERSION.to_string(), ticket_id: self.ticket_id, events: self.events.clone() };
+        serde_json::to_value(response).unwrap_or_default()
+    }
+}
+
+/// `ltm recent`: the tickets used last, newest first
+pub struct RecentList {
+    pub tickets: Vec<RecentTicket>,
+}
+
+impl Render for RecentList {
+    fn human(&self) -> String {
+        format_recent(&self.tickets, Utc::now().naive_utc())
+    }
+
+    fn data(&self) -> Value {
+        json!({ "version": VERSION, "tickets": self.tickets })
+    }
+}
+
+/// `ltm time report`: hours per ticket from `from` to `to`, inclusive
+pub struct TimeReport {
+    pub rows: Vec<TicketHours>,
+    pub project: Option<String>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// How to write amounts, from `[currencies]`
+    pub currencies: Currencies,
+}
+
+impl Render for TimeReport {
+    fn human(&self) -> String {
+        let heading = match &self.project {
+            Some(p) => format!("{}Time logged for {} from {} to {}\n", icon("⏱️  "), p, self.from, self.to),
+            None => format!("{}Time logged from {} to {}\n", icon("⏱️  "), self.from, self.to),
+        };
+        format!("{}\n{}", heading, format_time_report(&self.rows, &self.currencies))
+    }
+
+    fn data(&self) -> Value {
+        serde_json::to_value(time_report_response(&self.rows, self.project.as_deref(), self.from, self.to))
+            .unwrap_or_default()
+    }
+
+    /// One row per ticket and a total row, as spreadsheets expect
+    fn csv(&self) -> String {
+        format_time_report_csv(&self.rows).trim_end().to_string()
+    }
+}
+
+/// `ltm time export`: each time log from `from` to `to`, inclusive
+pub struct TimeExport {
+    pub entries: Vec<TimeLogEntry>,
+    pub project: Option<String>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl Render for TimeExport {
+    fn human(&self) -> String {
+        let heading = match &self.project {
+            Some(p) => format!("{}Time logs for {} from {} to {}\n", icon("⏱️  "), p, self.from, self.to),
+            None => format!("{}Time logs from {} to {}\n", icon("⏱️  "), self.from, self.to),
+        };
+        format!("{}\n{}", heading, format_time_export(&self.entries))
+    }
+
+    fn data(&self) -> Value {
+        let entries: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut value = serde_json::to_value(entry).unwrap_or_default();
+                value["hours"] = json!((entry.minutes as f64 / 60.0 * 100.0).round() / 100.0);
+                value
+            })
+            .collect();
+        json!({ "version": VERSION, "project": self.project, "from": self.from, "to": self.to, "time_logs": entries })
+    }
+
+    /// One row per time log, in the columns payroll and accounting tools import
+    fn csv(&self) -> String {
+        format_time_export_csv(&self.entries).trim_end().to_string()
+    }
+}
+
+/// `ltm next`: the recommended ticket and the runners-up, best first
+pub struct NextTickets {
+    /// Tickets with their score and the reasons for it
+    pub recommendations: Vec<(Ticket, i64, Vec<String>)>,
+}
+
+impl Render for NextTickets {
+    fn human(&self) -> String {
+        let Some((best, rest)) = self.recommendations.split_first() else {
+            return info_message("Nothing to work on: no open tickets that are not blocked");
+        };
+        let describe = |(ticket, _, reasons): &(Ticket, i64, Vec<String>)| {
+            let mut line = format!("#{} {} [{}]", ticket.id, ticket.name, ticket.project);
+            if !reasons.is_empty() {
+                line.push_str(&format!(" ({})", reasons.join(", ")));
+            }
+            line
+        };
+        let mut lines = vec![format!("{}Next: {}", icon("🎯 "), describe(best))];
+        if !rest.is_empty() {
+            lines.push("   Also consider:".to_string());
+            lines.extend(rest.iter().map(|r| format!("   - {}", describe(r))));
+        }
+        lines.join("\n")
+    }
+
+    fn data(&self) -> Value {
+        let entry = |(ticket, score, reasons): &(Ticket, i64, Vec<String>)| {
+            json!({ "ticket": ticket, "score": score, "reasons": reasons })
+        };
+        let (best, rest) = match self.recommendations.split_first() {
+            Some((best, rest)) => (entry(best), rest.iter().map(entry).collect()),
+            None => (Value::Null, Vec::new()),
+        };
+        json!({ "version": VERSION, "next": best, "alternatives": rest })
+    }
+}
+
+/// `ltm today`: tickets due, tickets in progress, timers, and the time logged today
+pub struct Agenda {
+    pub date: NaiveDate,
+    pub due: Vec<Ticket>,
+    pub in_progress: Vec<Ticket>,
+    pub timers: Vec<(ActiveTimer, String)>,
+    pub logged_minutes: i64,
+    pub now: NaiveDateTime,
+}
+
+impl Render for Agenda {
+    fn human(&self) -> String {
+        format_agenda(self.date, &self.due, &self.in_progress, &self.timers, self.logged_minutes, self.now)
+    }
+
+    fn data(&self) -> Value {
+        let timers: Vec<Value> =
+            self.timers.iter().map(|(timer, name)| timer_data(timer, Some(name), self.now)).collect();
+        json!({
+            "version": VERSION,
+            "date": self.date,
+            "due": self.due,
+            "in_progress": self.in_progress,
+            "timers": timers,
+            "logged_minutes": self.logged_minutes,
+        })
+    }
+}
+
+/// `ltm week`: unfinished tickets due by the end of the week starting `monday`
+pub struct WeekPlan {
+    pub monday: NaiveDate,
+    pub today: NaiveDate,
+    pub tickets: Vec<Ticket>,
+}
+
+impl Render for WeekPlan {
+    fn human(&self) -> String {
+        format_week(self.monday, self.today, &self.tickets)
+    }
+
+    fn data(&self) -> Value {
+        let overdue: Vec<&Ticket> = self.tickets.iter().filter(|t| t.due_date.is_some_and(|d| d < self.today)).collect();
+        let days: Vec<Value> = (0..7)
+            .map(|i| {
+                let day = self.monday + Duration::days(i);
+                let tickets: Vec<&Ticket> =
+                    self.tickets.iter().filter(|t| t.due_date == Some(day) && day >= self.today).collect();
+                let estimate: i64 = tickets.iter().filter_map(|t| t.estimate_minutes).sum();
+                json!({ "date": day, "estimate_minutes": estimate, "tickets": tickets })
+            })
+            .collect();
+        json!({
+            "version": VERSION,
+            "from": self.monday,
+            "to": self.monday + Duration::days(6),
+            "overdue": overdue,
+            "days": days,
+        })
+    }
+}
+
+/// `ltm calendar`: tickets due in the month starting `month`, and hours logged per day
+pub struct MonthCalendar {
+    pub month: NaiveDate,
+    pub today: NaiveDate,
+    pub due: Vec<Ticket>,
+    pub logged: HashMap<NaiveDate, f64>,
+}
+
+impl Render for MonthCalendar {
+    fn human(&self) -> String {
+        format_calendar(self.month, self.today, &self.due, &self.logged)
+    }
+
+    fn data(&self) -> Value {
+        let logged: BTreeMap<_, _> = self.logged.iter().filter(|(_, hours)| **hours > 0.0).collect();
+        json!({
+            "version": VERSION,
+            "month": self.month.format("%Y-%m").to_string(),
+            "due": self.due,
+            "logged_hours": logged,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{OutputFormat, Renderer};
+
+    fn ticket(id: i64, due: Option<NaiveDate>, estimate: Option<i64>) -> Ticket {
+        let created = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        Ticket {
+            id,
+            project: "webapp".to_string(),
+            name: format!("Ticket {}", id),
+            description: String::new(),
+            status: "open".to_string(),
+            created_at: created,
+            updated_at: created,
+            due_date: due,
+            rank: id,
+            priority: None,
+            estimate_minutes: estimate,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        }
+    }
+
+    #[test]
+    fn test_result_data() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        let week = WeekPlan {
+            monday: day(6),
+            today: day(8),
+            tickets: vec![ticket(1, Some(day(7)), None), ticket(2, Some(day(9)), Some(90)), ticket(3, Some(day(9)), None)],
+        };
+        let data = week.data();
+        assert_eq!(data["overdue"][0]["id"], 1);
+        assert_eq!(data["days"][3]["date"], "2024-05-09");
+        assert_eq!(data["days"][3]["estimate_minutes"], 90);
+        assert_eq!(data["days"][3]["tickets"].as_array().unwrap().len(), 2);
+        assert_eq!(data["to"], "2024-05-12");
+
+        let next = NextTickets { recommendations: vec![(ticket(4, None, None), 20, vec!["high priority".to_string()])] };
+        assert_eq!(next.data()["next"]["reasons"][0], "high priority");
+        assert_eq!(NextTickets { recommendations: Vec::new() }.data()["next"], Value::Null);
+
+        let now = NaiveDate::from_ymd_opt(2024, 5, 8).unwrap().and_hms_opt(10, 0, 0).unwrap();
+        let timer = ActiveTimer {
+            ticket_id: 4,
+            started_at: now - Duration::minutes(65),
+            segment_started_at: now - Duration::minutes(65),
+            paused_at: None,
+            elapsed_seconds: 0,
+            note: None,
+        };
+        let current = CurrentTimer { timer: Some((timer, "Fix login".to_string())), now };
+        assert_eq!(current.data()["elapsed"]["seconds"], 3900);
+        assert_eq!(current.data()["paused"], false);
+        assert_eq!(CurrentTimer { timer: None, now }.data(), json!({}));
+
+        let projects = ProjectList { projects: vec!["api".to_string(), "webapp".to_string()] };
+        assert_eq!(Renderer::new(OutputFormat::Yaml).render(&projects), "version: \"1.0\"\nprojects:\n  - api\n  - webapp");
+        assert_eq!(
+            Renderer::new(OutputFormat::Json).render(&projects),
+            r#"{"version":"1.0","projects":["api","webapp"]}"#
+        );
+
+        let report = Report::new("api = 1", json!({"project": "api", "settings": {"wip_limit": "1"}}));
+        assert_eq!(Renderer::new(OutputFormat::Csv).render(&report), "version,project,settings.wip_limit\n1.0,api,1");
+        let messages = Messages(vec![("success", "Ticket created".to_string())]);
+        assert_eq!(messages.data()["messages"][0], json!({"level": "success", "message": "Ticket created"}));
+    }
+}
@@ -0,0 +1,102 @@
+use regex::Regex;
+use std::ops::Range;
+
+/// What a search looks for: plain text (case-insensitive) or a regular expression
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    Text(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Byte range of the first match in `haystack`
+    pub fn find(&self, haystack: &str) -> Option<Range<usize>> {
+        match self {
+            Matcher::Text(needle) => find_ignore_case(haystack, needle),
+            Matcher::Regex(regex) => regex.find(haystack).map(|m| m.range()),
+        }
+    }
+
+    /// Whether `haystack` contains a match
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.find(haystack).is_some()
+    }
+}
+
+/// Byte range of the first case-insensitive occurrence of `needle` in `haystack`
+pub fn find_ignore_case(haystack: &str, needle: &str) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return Some(0..0);
+    }
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut rest = haystack[start..].char_indices();
+        let mut expected = needle.chars().flat_map(char::to_lowercase);
+        let mut end = start;
+        loop {
+            let Some(want) = expected.next() else {
+                return Some(start..end);
+            };
+            let (offset, c) = rest.next()?;
+            let mut lower = c.to_lowercase();
+            if lower.next() != Some(want) {
+                return None;
+            }
+            // Characters that lowercase to several chars must match all of them
+            for extra in lower {
+                if expected.next() != Some(extra) {
+                    return None;
+                }
+            }
+            end = start + offset + c.len_utf8();
+        }
+    })
+}
+
+/// A single-line excerpt of `text` around `range`, with `...` where it was cut
+pub fn snippet(text: &str, range: Range<usize>, context: usize) -> String {
+    let before: Vec<char> = text[..range.start].chars().collect();
+    let after: Vec<char> = text[range.end..].chars().collect();
+    let head_start = before.len().saturating_sub(context);
+    let tail_end = after.len().min(context);
+
+    let mut excerpt = String::new();
+    if head_start > 0 {
+        excerpt.push_str("...");
+    }
+    excerpt.extend(&before[head_start..]);
+    excerpt.push_str(&text[range]);
+    excerpt.extend(&after[..tail_end]);
+    if tail_end < after.len() {
+        excerpt.push_str("...");
+    }
+    excerpt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(find_ignore_case("Got a Stack Trace here", "stack trace"), Some(6..17));
+        assert_eq!(find_ignore_case("Größe ÄNDERN", "änd"), Some(8..12));
+        assert_eq!(find_ignore_case("nothing", "stack"), None);
+    }
+
+    #[test]
+    fn test_matchers() {
+        let text = Matcher::Text("TIMEOUT".to_string());
+        assert!(text.is_match("gateway timeout"));
+        let regex = Matcher::Regex(Regex::new(r"error (500|502)").unwrap());
+        assert_eq!(regex.find("saw error 502 twice"), Some(4..13));
+        assert!(!regex.is_match("error 404"));
+    }
+
+    #[test]
+    fn test_snippet() {
+        let text = "The job crashed with\na stack trace pointing at the parser module";
+        let range = find_ignore_case(text, "stack trace").unwrap();
+        assert_eq!(snippet(text, range.clone(), 8), "... with a stack trace pointin...");
+        assert_eq!(snippet(text, range, 100), "The job crashed with a stack trace pointing at the parser module");
+    }
+}
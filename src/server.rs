@@ -0,0 +1,394 @@
+use anyhow::Result;
+use async_graphql::http::GraphiQLSource;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Router};
+use chrono::Utc;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::commands::Cli;
+use crate::db::{ChangeWatcher, Database};
+use crate::graphql::{build_schema, LtmSchema};
+use crate::output::NO_EMOJI_ENV;
+use crate::remote::{Route, RunRequest, RunResponse, LOCAL_ENV, REVISION_HEADER, USER_ENV};
+use crate::json_formatting::{
+    project_summary_response, ticket_list_response, ErrorResponse, ProjectSummaryResponse, TicketDetailsResponse,
+    TicketListResponse,
+};
+use crate::validation::{resolve_status_alias, validate_project_name, validate_ticket_id, ValidationError};
+
+/// Single-page UI bundled into the binary for `ltm serve --web`
+const WEB_UI: &str = include_str!("web/index.html");
+
+/// Start of every token from `ltm serve token create`, so one is easy to recognise
+pub const TOKEN_PREFIX: &str = "ltm_";
+
+/// A new secret for `ltm serve token create`: the prefix and 160 random bits in hex
+pub fn new_token() -> Result<String> {
+    let mut bytes = [0u8; 20];
+    getrandom::getrandom(&mut bytes).map_err(|e| anyhow::anyhow!("Could not generate a token: {}", e))?;
+    Ok(bytes.iter().fold(TOKEN_PREFIX.to_string(), |token, byte| token + &format!("{:02x}", byte)))
+}
+
+/// How a token is kept in the database: the SHA-256 of its secret, in hex
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Query parameters accepted by `GET /api/tickets`
+#[derive(Debug, Deserialize)]
+pub struct TicketQuery {
+    pub project: Option<String>,
+    pub status: Option<String>,
+    pub sort: Option<String>,
+}
+
+/// JSON response structure for the project list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectListResponse {
+    pub version: String,
+    pub projects: Vec<String>,
+}
+
+/// A running or paused timer as reported by the API
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimerView {
+    pub ticket_id: i64,
+    pub ticket_name: Option<String>,
+    pub paused: bool,
+    pub elapsed_seconds: i64,
+}
+
+/// JSON response structure for the timer list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimerListResponse {
+    pub version: String,
+    pub timers: Vec<TimerView>,
+}
+
+/// An error rendered with the same shape as `--json` CLI errors
+struct ApiError(StatusCode, ErrorResponse);
+
+impl ApiError {
+    fn new(status: StatusCode, code: &str, message: String) -> Self {
+        Self(
+            status,
+            ErrorResponse {
+                version: "1.0".to_string(),
+                error: true,
+                message,
+                code: code.to_string(),
+                details: serde_json::json!({}),
+            },
+        )
+    }
+}
+
+impl From<ValidationError> for ApiError {
+    fn from(error: ValidationError) -> Self {
+        let status = match error {
+            ValidationError::TicketNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        let body = serde_json::from_str(&crate::json_formatting::format_error_json(&error))
+            .unwrap_or_else(|_| ApiError::new(status, "VALIDATION_ERROR", error.to_string()).1);
+        Self(status, body)
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR", error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(self.1)).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+/// What a request may do, from the token it carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Access {
+    /// A missing or unknown token, while the server has tokens
+    Denied,
+    /// No token, on a server without any: the read-only API is open to anyone
+    Open,
+    /// A read-only token: the API and database snapshots, but no commands
+    Read,
+    /// The `[server]` token or a token from `ltm serve token create` without `--read-only`
+    Full,
+}
+
+/// Checks the tokens requests carry against the `[server]` token and those in the database,
+/// so a revoked token is refused from the next request on
+#[derive(Clone)]
+pub struct Auth {
+    db: Database,
+    /// Hash of the `[server]` token, compared like the stored ones so the comparison takes
+    /// no longer for a closer guess
+    token_hash: Option<String>,
+}
+
+impl Auth {
+    pub fn new(db: Database, token: Option<String>) -> Self {
+        Self { db, token_hash: token.as_deref().map(hash_token) }
+    }
+
+    /// What a request with these headers may do
+    pub async fn access(&self, headers: &HeaderMap) -> Result<Access> {
+        let given = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()).and_then(|v| v.strip_prefix("Bearer "));
+        Ok(match given.map(|given| hash_token(given.trim())) {
+            Some(hash) if self.token_hash.as_ref() == Some(&hash) => Access::Full,
+            Some(hash) => match self.db.api_token_by_hash(&hash).await? {
+                Some(token) if token.read_only => Access::Read,
+                Some(_) => Access::Full,
+                None => Access::Denied,
+            },
+            None if self.token_hash.is_none() && self.db.api_tokens().await?.is_empty() => Access::Open,
+            None => Access::Denied,
+        })
+    }
+
+    /// Refuse a request that may not do what `needs` allows
+    async fn check(&self, headers: &HeaderMap, needs: Access) -> std::result::Result<(), ApiError> {
+        match self.access(headers).await? {
+            access if access >= needs => Ok(()),
+            Access::Read => {
+                let message = "This token is read-only; it can query the API but not run commands".to_string();
+                Err(ApiError::new(StatusCode::FORBIDDEN, "READ_ONLY_TOKEN", message))
+            }
+            Access::Open => {
+                let message = "This server has no tokens; create one on it with 'ltm serve token create <name>'".to_string();
+                Err(ApiError::new(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", message))
+            }
+            _ => Err(unauthorized()),
+        }
+    }
+}
+
+/// Let a request through to the JSON API and GraphQL only with a token, once the server has
+/// any. The web UI page itself loads without one and sends the token from its address.
+async fn require_token(State(auth): State<Auth>, request: Request, next: Next) -> Response {
+    if request.uri().path() == "/" {
+        return next.run(request).await;
+    }
+    match auth.check(request.headers(), Access::Open).await {
+        Ok(()) => next.run(request).await,
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Build the HTTP router; the bundled UI is only mounted when `web` is set
+pub fn router(db: Database, web: bool) -> Router {
+    let mut router = Router::new()
+        .route("/api/tickets", get(list_tickets))
+        .route("/api/tickets/:id", get(show_ticket))
+        .route("/api/projects", get(list_projects))
+        .route("/api/projects/:name", get(project_summary))
+        .route("/api/timers", get(list_timers))
+        .route("/graphql", get(graphiql).post(graphql))
+        .layer(Extension(build_schema(db.clone())));
+    if web {
+        router = router.route("/", get(|| async { Html(WEB_UI) }));
+    }
+    router.with_state(db)
+}
+
+async fn graphql(Extension(schema): Extension<LtmSchema>, Json(request): Json<async_graphql::Request>) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+/// Everything `ltm serve` answers: the API and, with `web`, the UI, behind the server's
+/// tokens, and the routes for clients in shared-server mode
+pub async fn app(db: Database, web: bool, token: Option<String>) -> Result<Router> {
+    let auth = Auth::new(db.clone(), token.clone());
+    let app = router(db.clone(), web).layer(middleware::from_fn_with_state(auth, require_token));
+    Ok(app.merge(shared_router(db, token).await?))
+}
+
+/// Serve the API (and optionally the UI) until the process is stopped. Clients with a
+/// token that is not read-only can also run commands in shared-server mode.
+pub async fn serve(db: Database, addr: SocketAddr, web: bool, token: Option<String>) -> Result<()> {
+    let app = app(db, web, token).await?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// What the command API of a shared server needs
+struct Shared {
+    db: Database,
+    auth: Auth,
+    revision: Mutex<Revision>,
+}
+
+/// Counts the database's changes, by this process or the commands it runs
+struct Revision {
+    watcher: ChangeWatcher,
+    started: i64,
+    count: u64,
+}
+
+impl Shared {
+    /// A name for the database's current state; it changes with every write
+    async fn revision(&self) -> Result<String> {
+        let mut revision = self.revision.lock().await;
+        if revision.watcher.changed().await? {
+            revision.count += 1;
+        }
+        Ok(format!("{}.{}", revision.started, revision.count))
+    }
+}
+
+fn unauthorized() -> ApiError {
+    ApiError::new(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "Missing or wrong server token".to_string())
+}
+
+/// Routes for clients in shared-server mode, which need `Authorization: Bearer TOKEN` with
+/// the `[server]` token or one from `ltm serve token create`: `POST /api/run` runs a command
+/// line, which read-only tokens may not, and `GET /api/snapshot` sends a copy of the database
+pub async fn shared_router(db: Database, token: Option<String>) -> Result<Router> {
+    let revision = Revision { watcher: db.watch_changes().await?, started: Utc::now().timestamp(), count: 0 };
+    let shared = Shared { auth: Auth::new(db.clone(), token), db, revision: Mutex::new(revision) };
+    Ok(Router::new()
+        .route("/api/run", post(run_command))
+        .route("/api/snapshot", get(snapshot))
+        .with_state(Arc::new(shared)))
+}
+
+/// Run a client's command line with this binary against the local database, stamping the
+/// client's identity. Commands about the client's machine are refused; lines that do not
+/// parse run anyway, so clients see ltm's own usage errors and help.
+async fn run_command(
+    State(shared): State<Arc<Shared>>,
+    headers: HeaderMap,
+    Json(request): Json<RunRequest>,
+) -> ApiResult<RunResponse> {
+    shared.auth.check(&headers, Access::Full).await?;
+    if let Ok(cli) = Cli::try_parse_from(std::iter::once("ltm".to_string()).chain(request.args.iter().cloned())) {
+        let reads_file = cli.client_file().is_some_and(|path| path.as_os_str() != "-");
+        if let Some(reason) = cli.needs_client() {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "LOCAL_COMMAND", reason.to_string()));
+        }
+        if cli.route() != Route::Server || cli.watches() || cli.log_file.is_some() || reads_file {
+            let message = "This command runs on the client's machine, not on a shared server".to_string();
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "LOCAL_COMMAND", message));
+        }
+    }
+
+    let mut command = tokio::process::Command::new(std::env::current_exe().map_err(anyhow::Error::from)?);
+    command.args(&request.args).env(LOCAL_ENV, "1");
+    command.stdin(if request.stdin.is_some() { Stdio::piped() } else { Stdio::null() });
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    match &request.user {
+        Some(user) => command.env(USER_ENV, user),
+        None => command.env_remove(USER_ENV),
+    };
+    if request.color {
+        command.env("CLICOLOR_FORCE", "1");
+    }
+    if !request.emoji {
+        command.env(NO_EMOJI_ENV, "1");
+    }
+    let mut child = command.spawn().map_err(anyhow::Error::from)?;
+    if let (Some(mut pipe), Some(text)) = (child.stdin.take(), request.stdin) {
+        // The command may exit without reading everything, closing the pipe early
+        tokio::spawn(async move {
+            let _ = pipe.write_all(text.as_bytes()).await;
+        });
+    }
+    let output = child.wait_with_output().await.map_err(anyhow::Error::from)?;
+    Ok(Json(RunResponse {
+        status: output.status.code().unwrap_or(1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        revision: shared.revision().await?,
+    }))
+}
+
+/// A consistent copy of the database file, for clients to cache
+async fn snapshot(State(shared): State<Arc<Shared>>, headers: HeaderMap) -> std::result::Result<Response, ApiError> {
+    shared.auth.check(&headers, Access::Read).await?;
+    let revision = shared.revision().await?;
+    let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    let path = std::env::temp_dir().join(format!("ltm-snapshot-{}-{}.db", std::process::id(), nanos));
+    shared.db.backup_to(&path).await?;
+    let bytes = tokio::fs::read(&path).await.map_err(anyhow::Error::from);
+    let _ = tokio::fs::remove_file(&path).await;
+    let headers = [(header::CONTENT_TYPE, "application/vnd.sqlite3".to_string()), (header::HeaderName::from_static(REVISION_HEADER), revision)];
+    Ok((headers, bytes?).into_response())
+}
+
+async fn list_tickets(State(db): State<Database>, Query(query): Query<TicketQuery>) -> ApiResult<TicketListResponse> {
+    let project = query.project.as_deref().map(validate_project_name).transpose()?;
+    let sort = query.sort.as_deref().unwrap_or("updated");
+    let status = query.status.as_deref().map(resolve_status_alias);
+    let tickets = db.list_tickets_filtered(project.as_deref(), status.as_deref(), sort, false).await?;
+    Ok(Json(ticket_list_response(&tickets, project.as_deref())))
+}
+
+async fn show_ticket(State(db): State<Database>, Path(id): Path<String>) -> ApiResult<TicketDetailsResponse> {
+    let ticket_id = validate_ticket_id(&id)?;
+    let ticket = db.get_ticket(ticket_id).await?.ok_or(ValidationError::TicketNotFound(ticket_id))?;
+    let comments = db.get_comments(ticket_id).await?;
+    let time_logs = db.get_time_logs(ticket_id).await?;
+    let urls = db.get_ticket_urls(ticket_id).await?;
+    let tags = db.get_ticket_tags(ticket_id).await?;
+    Ok(Json(TicketDetailsResponse {
+        version: "1.0".to_string(),
+        ticket,
+        comments,
+        time_logs,
+        urls,
+        tags,
+    }))
+}
+
+async fn list_projects(State(db): State<Database>) -> ApiResult<ProjectListResponse> {
+    Ok(Json(ProjectListResponse {
+        version: "1.0".to_string(),
+        projects: db.list_projects().await?,
+    }))
+}
+
+async fn project_summary(State(db): State<Database>, Path(name): Path<String>) -> ApiResult<ProjectSummaryResponse> {
+    let project = validate_project_name(&name)?;
+    let summary = db.get_project_summary(&project).await?;
+    Ok(Json(project_summary_response(&project, &summary)))
+}
+
+async fn list_timers(State(db): State<Database>) -> ApiResult<TimerListResponse> {
+    let now = Utc::now().naive_utc();
+    let mut timers = Vec::new();
+    for timer in db.list_timers().await? {
+        let ticket_name = db.get_ticket(timer.ticket_id).await?.map(|t| t.name);
+        timers.push(TimerView {
+            ticket_id: timer.ticket_id,
+            ticket_name,
+            paused: timer.is_paused(),
+            elapsed_seconds: timer.elapsed(now).num_seconds(),
+        });
+    }
+    Ok(Json(TimerListResponse {
+        version: "1.0".to_string(),
+        timers,
+    }))
+}
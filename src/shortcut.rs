@@ -0,0 +1,92 @@
+use clap::{Arg, Command};
+use serde::Deserialize;
+
+/// A status shortcut of your own, like the built-in `ltm block`, from config.toml:
+///
+/// ```toml
+/// [shortcuts.review]
+/// status = "testing"
+/// comment = "Ready for review"
+/// about = "Hand a ticket over for review"
+/// ```
+///
+/// `ltm review 12` then moves ticket 12 to testing and comments "Ready for review".
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Shortcut {
+    /// Status to set; aliases and project statuses are accepted
+    pub status: String,
+    /// Comment added each time the shortcut is used
+    pub comment: Option<String>,
+    /// Help text shown in `ltm --help`
+    pub about: Option<String>,
+}
+
+impl Shortcut {
+    /// The `ltm <name>` subcommand, taking a ticket ID (the focused ticket when left out) and
+    /// `-m` for more comment text
+    pub fn command(&self, name: &str) -> Command {
+        let about = self.about.clone().unwrap_or_else(|| format!("Set ticket status to {}", self.status));
+        Command::new(name.to_string())
+            .about(about)
+            .arg(Arg::new("ticket_id").value_name("TICKET_ID").help("Ticket ID (defaults to the focused ticket)"))
+            .arg(Arg::new("message").short('m').long("message").help("Comment text, added after the configured comment"))
+    }
+
+    /// The comment to add when the shortcut is used with `message`, if any
+    pub fn comment_text(&self, message: Option<&str>) -> Option<String> {
+        match (self.comment.as_deref(), message) {
+            (Some(comment), Some(message)) => Some(format!("{}: {}", comment, message)),
+            (comment, message) => comment.or(message).map(str::to_string),
+        }
+    }
+}
+
+/// Whether `name` can be typed as a subcommand: lowercase letters, digits, and hyphens,
+/// starting with a letter
+pub fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut() {
+        let review = Shortcut { status: "testing".to_string(), comment: Some("Ready for review".to_string()), about: None };
+        assert_eq!(review.comment_text(None).as_deref(), Some("Ready for review"));
+        assert_eq!(review.comment_text(Some("see PR 7")).as_deref(), Some("Ready for review: see PR 7"));
+        let hold = Shortcut { status: "blocked".to_string(), ..Default::default() };
+        assert_eq!(hold.comment_text(None), None);
+        assert_eq!(hold.comment_text(Some("waiting on legal")).as_deref(), Some("waiting on legal"));
+
+        let matches = review.command("review").try_get_matches_from(["review", "12", "-m", "see PR 7"]).unwrap();
+        assert_eq!(matches.get_one::<String>("ticket_id").map(String::as_str), Some("12"));
+        assert!(review.command("review").get_about().unwrap().to_string().contains("testing"));
+
+        assert!(is_valid_name("review") && is_valid_name("qa-2"));
+        assert!(!is_valid_name("Review") && !is_valid_name("-x") && !is_valid_name("my review") && !is_valid_name(""));
+    }
+
+    #[test]
+    fn test_parse_with_shortcuts() {
+        use crate::commands::Cli;
+        use crate::render::OutputFormat;
+        use std::collections::BTreeMap;
+
+        let shortcuts = BTreeMap::from([("review".to_string(), Shortcut { status: "testing".to_string(), ..Default::default() })]);
+        let cli = Cli::try_parse_with_shortcuts(["ltm", "-v", "review", "12", "-o", "json"], &shortcuts).unwrap();
+        assert_eq!((cli.verbose, cli.output), (1, OutputFormat::Json));
+        assert!(Cli::try_parse_with_shortcuts(["ltm", "list"], &shortcuts).is_ok());
+        assert!(Cli::try_parse_with_shortcuts(["ltm", "review", "12", "13"], &shortcuts).is_err());
+        assert!(Cli::try_parse_with_shortcuts(["ltm", "review"], &BTreeMap::new()).is_err());
+
+        assert!(Cli::check_shortcuts(&shortcuts).is_ok());
+        let clash = BTreeMap::from([("list".to_string(), Shortcut { status: "open".to_string(), ..Default::default() })]);
+        assert!(Cli::check_shortcuts(&clash).unwrap_err().to_string().contains("built-in 'ltm list'"));
+        let no_status = BTreeMap::from([("park".to_string(), Shortcut::default())]);
+        assert!(Cli::check_shortcuts(&no_status).is_err());
+    }
+}
@@ -0,0 +1,405 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::duration::format_minutes;
+use crate::models::{Comment, Ticket, TimeLog};
+use crate::validation::is_finished_status;
+
+const STYLE: &str = "body { font: 15px/1.5 system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+a { color: #0b5cad; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav, footer, .meta { color: #666; font-size: 0.9em; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0; }
+th, td { text-align: left; padding: 0.3rem 0.6rem; border-bottom: 1px solid #ddd; vertical-align: top; }
+td.number, th.number { text-align: right; }
+.text { white-space: pre-wrap; }
+.status { display: inline-block; padding: 0 0.5rem; border-radius: 0.6rem; background: #eee; font-size: 0.9em; }
+.finished { color: #888; }
+.comment { border-left: 3px solid #ddd; padding: 0.2rem 0.8rem; margin: 1rem 0; }
+.reply { margin-left: 2rem; }
+";
+
+/// A browsable copy of the tickets, for `ltm export site`: each file's path in the site
+/// and its contents.
+///
+/// ```text
+/// index.html              the projects, with their open tickets and time logged
+/// style.css
+/// projects/<name>.html    a project's tickets
+/// tickets/<id>.html       a ticket with its comments and time logs
+/// ```
+///
+/// Pages link to each other by relative paths, so the site works from any folder or server.
+pub fn build_site(
+    tickets: &[Ticket],
+    comments: &[Comment],
+    time_logs: &[TimeLog],
+    generated_at: DateTime<Utc>,
+) -> Vec<(PathBuf, String)> {
+    let mut comments_by_ticket: HashMap<i64, Vec<&Comment>> = HashMap::new();
+    for comment in comments {
+        comments_by_ticket.entry(comment.ticket_id).or_default().push(comment);
+    }
+    let mut logs_by_ticket: HashMap<i64, Vec<&TimeLog>> = HashMap::new();
+    for log in time_logs {
+        logs_by_ticket.entry(log.ticket_id).or_default().push(log);
+    }
+    let minutes = |id: i64| logs_by_ticket.get(&id).map_or(0, |logs| logs.iter().map(|log| log.duration().num_minutes()).sum());
+
+    let mut projects: BTreeMap<&str, Vec<&Ticket>> = BTreeMap::new();
+    for ticket in tickets {
+        projects.entry(&ticket.project).or_default().push(ticket);
+    }
+    let pages = project_pages(projects.keys().copied());
+    let footer = format!("Exported from ltm on {}", local(generated_at.naive_utc()));
+
+    let mut files = vec![(PathBuf::from("style.css"), STYLE.to_string())];
+    let mut rows = Vec::new();
+    for (project, tickets) in &mut projects {
+        tickets.sort_by_key(|t| (t.archived_at.is_some(), is_finished_status(&t.status), t.rank, t.id));
+        let open = tickets.iter().filter(|t| t.archived_at.is_none() && !is_finished_status(&t.status)).count();
+        let logged: i64 = tickets.iter().map(|t| minutes(t.id)).sum();
+        rows.push(format!(
+            "<tr><td><a href=\"projects/{}\">{}</a></td><td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+            pages[project],
+            escape(project),
+            open,
+            tickets.len(),
+            format_minutes(logged)
+        ));
+
+        let ticket_rows: Vec<String> = tickets
+            .iter()
+            .map(|t| {
+                let archived = if t.archived_at.is_some() { " (archived)" } else { "" };
+                format!(
+                    "<tr{}><td><a href=\"../tickets/{}.html\">#{}</a></td><td><a href=\"../tickets/{}.html\">{}</a>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"number\">{}</td></tr>",
+                    finished_class(t),
+                    t.id,
+                    t.id,
+                    t.id,
+                    escape(&t.name),
+                    archived,
+                    status(&t.status),
+                    escape(t.priority.as_deref().unwrap_or("")),
+                    t.due_date.map(|due| due.to_string()).unwrap_or_default(),
+                    format_minutes(minutes(t.id))
+                )
+            })
+            .collect();
+        let body = format!(
+            "<nav><a href=\"../index.html\">All projects</a></nav>\n<h1>{}</h1>\n<p class=\"meta\">{} open of {} tickets, {} logged</p>\n\
+             <table>\n<tr><th>ID</th><th>Ticket</th><th>Status</th><th>Priority</th><th>Due</th><th class=\"number\">Time</th></tr>\n{}\n</table>",
+            escape(project),
+            open,
+            tickets.len(),
+            format_minutes(logged),
+            ticket_rows.join("\n")
+        );
+        files.push((PathBuf::from("projects").join(&pages[project]), page(project, "../", &body, &footer)));
+
+        for ticket in tickets.iter() {
+            let comments = comments_by_ticket.get(&ticket.id).map(Vec::as_slice).unwrap_or_default();
+            let logs = logs_by_ticket.get(&ticket.id).map(Vec::as_slice).unwrap_or_default();
+            let body = ticket_body(ticket, &pages[project], comments, logs);
+            let title = format!("#{} {}", ticket.id, ticket.name);
+            files.push((PathBuf::from("tickets").join(format!("{}.html", ticket.id)), page(&title, "../", &body, &footer)));
+        }
+    }
+
+    let body = if rows.is_empty() {
+        "<h1>Tickets</h1>\n<p>There are no tickets.</p>".to_string()
+    } else {
+        format!(
+            "<h1>Tickets</h1>\n<table>\n<tr><th>Project</th><th class=\"number\">Open</th><th class=\"number\">Tickets</th><th class=\"number\">Time</th></tr>\n{}\n</table>",
+            rows.join("\n")
+        )
+    };
+    files.push((PathBuf::from("index.html"), page("Tickets", "", &body, &footer)));
+    files
+}
+
+fn ticket_body(ticket: &Ticket, project_page: &str, comments: &[&Comment], logs: &[&TimeLog]) -> String {
+    let mut meta = vec![("Status", status(&ticket.status))];
+    let mut field = |name: &'static str, value: Option<String>| {
+        if let Some(value) = value {
+            meta.push((name, escape(&value)));
+        }
+    };
+    field("Priority", ticket.priority.clone());
+    field("Type", ticket.kind.clone());
+    field("Severity", ticket.severity.clone());
+    field("Component", ticket.component.clone());
+    field("Release", ticket.target_release.clone());
+    field("Due", ticket.due_date.map(|due| due.to_string()));
+    field("Estimate", ticket.estimate_minutes.map(format_minutes));
+    field("Created", Some(by(local(ticket.created_at), ticket.created_by.as_deref())));
+    field("Updated", Some(by(local(ticket.updated_at), ticket.updated_by.as_deref())));
+    field("Archived", ticket.archived_at.map(local));
+
+    let mut html = format!(
+        "<nav><a href=\"../index.html\">All projects</a> / <a href=\"../projects/{}\">{}</a></nav>\n<h1>#{} {}</h1>\n<table class=\"meta\">\n{}\n</table>\n",
+        project_page,
+        escape(&ticket.project),
+        ticket.id,
+        escape(&ticket.name),
+        meta.iter().map(|(name, value)| format!("<tr><th>{}</th><td>{}</td></tr>", name, value)).collect::<Vec<_>>().join("\n")
+    );
+    if !ticket.description.trim().is_empty() {
+        html.push_str(&format!("<div class=\"text\">{}</div>\n", escape(ticket.description.trim())));
+    }
+
+    if !comments.is_empty() {
+        html.push_str(&format!("<h2>Comments ({})</h2>\n", comments.len()));
+        for comment in comments {
+            let class = if comment.parent_comment_id.is_some() { "comment reply" } else { "comment" };
+            let reply = comment.parent_comment_id.map(|parent| format!(", in reply to #{}", parent)).unwrap_or_default();
+            html.push_str(&format!(
+                "<div class=\"{}\" id=\"comment-{}\">\n<p class=\"meta\">#{} {}{}</p>\n<div class=\"text\">{}</div>\n</div>\n",
+                class,
+                comment.id,
+                comment.id,
+                escape(&by(local(comment.created_at), comment.created_by.as_deref())),
+                reply,
+                escape(comment.content.trim())
+            ));
+        }
+    }
+
+    if !logs.is_empty() {
+        let total: i64 = logs.iter().map(|log| log.duration().num_minutes()).sum();
+        html.push_str(&format!("<h2>Time logged ({})</h2>\n<table>\n<tr><th>When</th><th>By</th><th class=\"number\">Time</th><th>Note</th></tr>\n", format_minutes(total)));
+        for log in logs {
+            let billable = if log.billable { "" } else { " (not billable)" };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"number\">{}</td><td>{}{}</td></tr>\n",
+                local(log.started_at.unwrap_or(log.created_at)),
+                escape(log.created_by.as_deref().unwrap_or("")),
+                format_minutes(log.duration().num_minutes()),
+                escape(log.note.as_deref().unwrap_or("")),
+                billable
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html
+}
+
+/// The list of files an export wrote, kept beside them so the next export into the same
+/// directory knows which files are its own
+pub const MANIFEST: &str = ".ltm-site";
+
+/// Write the site's files to `dir`, which must be empty or hold an earlier export, and
+/// remove the earlier export's files that are gone from this one, such as the pages of
+/// deleted tickets
+pub fn write_site(dir: &Path, files: &[(PathBuf, String)]) -> Result<()> {
+    let manifest = dir.join(MANIFEST);
+    let earlier: Vec<PathBuf> = match fs::read_to_string(&manifest) {
+        Ok(text) => text.lines().map(PathBuf::from).collect(),
+        Err(_) if fs::read_dir(dir).map(|mut entries| entries.next().is_none()).unwrap_or(true) => Vec::new(),
+        Err(_) => anyhow::bail!("{} is not empty; export the site to a new directory or to one holding an earlier export", dir.display()),
+    };
+
+    for (path, contents) in files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Could not create {}", parent.display()))?;
+        }
+        fs::write(&path, contents).with_context(|| format!("Could not write {}", path.display()))?;
+    }
+    let list: String = files.iter().map(|(path, _)| format!("{}\n", path.display())).collect();
+    fs::write(&manifest, list).with_context(|| format!("Could not write {}", manifest.display()))?;
+
+    let written: HashSet<&Path> = files.iter().map(|(path, _)| path.as_path()).collect();
+    for path in earlier {
+        // Only paths an export could have written, in case the list was edited
+        let own = path.components().all(|component| matches!(component, Component::Normal(_)));
+        if own && !written.contains(path.as_path()) {
+            let _ = fs::remove_file(dir.join(&path));
+        }
+    }
+    Ok(())
+}
+
+/// A file name for each project's page. Names are kept when they are safe as file names,
+/// and otherwise reduced to the characters that are, with a number when two would clash.
+fn project_pages<'a>(projects: impl Iterator<Item = &'a str>) -> HashMap<&'a str, String> {
+    let mut pages = HashMap::new();
+    let mut taken = HashMap::new();
+    for project in projects {
+        let mut stem: String = project.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+        let count: &mut usize = taken.entry(stem.to_lowercase()).or_default();
+        *count += 1;
+        if *count > 1 {
+            stem = format!("{}-{}", stem, count);
+        }
+        pages.insert(project, format!("{}.html", stem));
+    }
+    pages
+}
+
+fn page(title: &str, root: &str, body: &str, footer: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<link rel=\"stylesheet\" href=\"{}style.css\">\n</head>\n<body>\n{}\n<footer>{}</footer>\n</body>\n</html>\n",
+        escape(title),
+        root,
+        body,
+        escape(footer)
+    )
+}
+
+fn status(status: &str) -> String {
+    format!("<span class=\"status\">{}</span>", escape(status))
+}
+
+fn finished_class(ticket: &Ticket) -> &'static str {
+    if ticket.archived_at.is_some() || is_finished_status(&ticket.status) {
+        " class=\"finished\""
+    } else {
+        ""
+    }
+}
+
+fn by(when: String, who: Option<&str>) -> String {
+    match who {
+        Some(who) => format!("{} by {}", when, who),
+        None => when,
+    }
+}
+
+fn local(at: NaiveDateTime) -> String {
+    at.and_utc().with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// Text made safe to put in HTML, both between tags and in quoted attributes
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn ticket(id: i64, project: &str, name: &str, status: &str) -> Ticket {
+        let at = DateTime::from_timestamp(1715000000, 0).unwrap().naive_utc();
+        Ticket {
+            id,
+            project: project.to_string(),
+            name: name.to_string(),
+            description: "Steps:\n1. <open> the page".to_string(),
+            status: status.to_string(),
+            created_at: at,
+            updated_at: at,
+            due_date: None,
+            rank: id,
+            priority: Some("high".to_string()),
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: Some("Ada".to_string()),
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        }
+    }
+
+    #[test]
+    fn test_build_site() {
+        let at = DateTime::from_timestamp(1715000000, 0).unwrap().naive_utc();
+        let tickets = [ticket(1, "web", "Fix \"login\" & logout", "closed"), ticket(2, "web", "Docs", "open"), ticket(3, "api", "Rate limits", "open")];
+        let comment = |id: i64, parent_comment_id: Option<i64>| Comment {
+            id,
+            ticket_id: 1,
+            content: "<b>not bold</b>".to_string(),
+            created_at: at,
+            updated_at: at,
+            parent_comment_id,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+        };
+        let log = TimeLog {
+            id: 1,
+            ticket_id: 1,
+            hours: 1,
+            minutes: 30,
+            started_at: None,
+            ended_at: None,
+            created_at: at,
+            updated_at: at,
+            note: Some("pairing".to_string()),
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            billable: false,
+        };
+        let files = build_site(&tickets, &[comment(1, None), comment(2, Some(1))], &[log], Utc::now());
+        let paths: Vec<_> = files.iter().map(|(path, _)| path.to_str().unwrap()).collect();
+        assert_eq!(paths, ["style.css", "projects/api.html", "tickets/3.html", "projects/web.html", "tickets/2.html", "tickets/1.html", "index.html"]);
+        let file = |path: &str| &files.iter().find(|(p, _)| p.to_str() == Some(path)).unwrap().1;
+
+        assert!(file("index.html").contains("<a href=\"projects/web.html\">web</a></td><td class=\"number\">1</td><td class=\"number\">2</td><td class=\"number\">1h 30m</td>"));
+        // Open tickets come before finished ones
+        let web = file("projects/web.html");
+        assert!(web.find("#2</a>").unwrap() < web.find("#1</a>").unwrap());
+        assert!(web.contains("<tr class=\"finished\">"));
+
+        let page = file("tickets/1.html");
+        assert!(page.contains("<title>#1 Fix &quot;login&quot; &amp; logout</title>"));
+        assert!(page.contains("<div class=\"text\">Steps:\n1. &lt;open&gt; the page</div>"));
+        assert!(page.contains("<h2>Comments (2)</h2>") && page.contains("&lt;b&gt;not bold&lt;/b&gt;"));
+        assert!(page.contains("<div class=\"comment reply\" id=\"comment-2\">") && page.contains(", in reply to #1</p>"));
+        assert!(page.contains("<h2>Time logged (1h 30m)</h2>") && page.contains("pairing (not billable)"));
+        assert!(!file("tickets/2.html").contains("<h2>"));
+
+        let pages = project_pages(["a.b", "a_b", "A?B", "web"].into_iter());
+        assert_eq!((pages["a.b"].as_str(), pages["a_b"].as_str(), pages["A?B"].as_str()), ("a_b.html", "a_b-2.html", "A_B-3.html"));
+        assert_eq!(pages["web"], "web.html");
+
+        let empty = build_site(&[], &[], &[], Utc::now());
+        assert!(empty.last().unwrap().1.contains("There are no tickets."));
+    }
+
+    #[test]
+    fn test_write_site() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("site");
+        let files = |names: &[&str]| names.iter().map(|name| (PathBuf::from(name), name.to_string())).collect::<Vec<_>>();
+
+        write_site(&site, &files(&["index.html", "tickets/1.html", "tickets/2.html"])).unwrap();
+        assert_eq!(fs::read_to_string(site.join("tickets/2.html")).unwrap(), "tickets/2.html");
+        // A ticket that is gone loses its page, and files the export did not write stay
+        fs::write(site.join("notes.txt"), "mine").unwrap();
+        write_site(&site, &files(&["index.html", "tickets/1.html"])).unwrap();
+        assert!(site.join("tickets/1.html").exists() && !site.join("tickets/2.html").exists());
+        assert!(site.join("notes.txt").exists());
+
+        // Any other directory that is not empty is refused, even with an index.html
+        fs::write(dir.path().join("index.html"), "").unwrap();
+        assert!(write_site(dir.path(), &files(&["index.html"])).is_err());
+    }
+}
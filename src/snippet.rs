@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+
+use crate::i18n::fill_named;
+use crate::models::Ticket;
+
+/// Fill in a comment snippet from the `[snippets]` table.
+///
+/// Placeholders: `{date}`, `{time}`, `{id}`, `{name}`, `{project}`, `{status}`,
+/// `{priority}`, `{due}`, and `{user}`. Fields the ticket does not have become empty,
+/// and unknown placeholders are left as they are.
+pub fn render(template: &str, ticket: &Ticket, user: Option<&str>, now: NaiveDateTime) -> String {
+    let (date, time, id) = (now.format("%Y-%m-%d").to_string(), now.format("%H:%M").to_string(), ticket.id.to_string());
+    let due = ticket.due_date.map(|d| d.to_string()).unwrap_or_default();
+    fill_named(
+        template,
+        &[
+            ("date", &date),
+            ("time", &time),
+            ("id", &id),
+            ("name", &ticket.name),
+            ("project", &ticket.project),
+            ("status", &ticket.status),
+            ("priority", ticket.priority.as_deref().unwrap_or("")),
+            ("due", &due),
+            ("user", user.unwrap_or("")),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_snippet_rendering() {
+        let now = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap().and_hms_opt(9, 30, 0).unwrap();
+        let ticket = Ticket {
+            id: 12,
+            project: "webapp".to_string(),
+            name: "Fix login".to_string(),
+            description: String::new(),
+            status: "open".to_string(),
+            created_at: now,
+            updated_at: now,
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 22),
+            rank: 12,
+            priority: Some("high".to_string()),
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        };
+
+        let text = render("Triaged {date} {time} by {user}: #{id} {name} [{project}]", &ticket, Some("Ada"), now);
+        assert_eq!(text, "Triaged 2024-03-20 09:30 by Ada: #12 Fix login [webapp]");
+        assert_eq!(render("{status}/{priority}/{due}", &ticket, None, now), "open/high/2024-03-22");
+        let ticket = Ticket { priority: None, due_date: None, ..ticket };
+        assert_eq!(render("{priority}|{due}|{user}|{other}", &ticket, None, now), "|||{other}");
+        // Placeholders inside a ticket's fields are left as written
+        let ticket = Ticket { name: "Use {project} var".to_string(), ..ticket };
+        assert_eq!(render("{name} in {project} by {user}", &ticket, Some("{status}"), now), "Use {project} var in webapp by {status}");
+    }
+}
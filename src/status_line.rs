@@ -0,0 +1,94 @@
+use chrono::NaiveDateTime;
+
+use crate::models::{ActiveTimer, OpenCounts};
+
+/// Template used when neither `--format` nor `status_line` in the config file gives one
+pub const DEFAULT_TEMPLATE: &str = "{timer} due:{due} open:{open}";
+
+/// Longest ticket name `{name}` expands to before it is cut short
+const MAX_NAME_WIDTH: usize = 30;
+
+/// The timer shown in the status line: the running one started most recently, or the
+/// most recently paused one when none is running
+pub fn pick_timer(timers: &[ActiveTimer]) -> Option<&ActiveTimer> {
+    timers.iter().max_by_key(|timer| (!timer.is_paused(), timer.segment_started_at))
+}
+
+/// Fill in a status-line template.
+///
+/// Placeholders: `{timer}` (`#12 1:05`, with ` paused` when paused), `{ticket}`, `{name}`,
+/// `{elapsed}`, `{open}`, `{due}`, and `{overdue}`. Timer placeholders are empty without a
+/// timer; runs of spaces left behind are collapsed so the line stays tidy.
+pub fn render(template: &str, timer: Option<(&ActiveTimer, &str)>, counts: &OpenCounts, now: NaiveDateTime) -> String {
+    let (timer_text, ticket, name, elapsed) = match timer {
+        Some((timer, name)) => {
+            let minutes = timer.elapsed(now).num_minutes().max(0);
+            let elapsed = format!("{}:{:02}", minutes / 60, minutes % 60);
+            let paused = if timer.is_paused() { " paused" } else { "" };
+            (
+                format!("#{} {}{}", timer.ticket_id, elapsed, paused),
+                timer.ticket_id.to_string(),
+                shorten(name),
+                elapsed,
+            )
+        }
+        None => Default::default(),
+    };
+
+    let line = template
+        .replace("{timer}", &timer_text)
+        .replace("{ticket}", &ticket)
+        .replace("{name}", &name)
+        .replace("{elapsed}", &elapsed)
+        .replace("{open}", &counts.open.to_string())
+        .replace("{due}", &counts.due_today.to_string())
+        .replace("{overdue}", &counts.overdue.to_string());
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cut a name to `MAX_NAME_WIDTH` characters, ending in `…` when shortened
+fn shorten(name: &str) -> String {
+    if name.chars().count() <= MAX_NAME_WIDTH {
+        name.to_string()
+    } else {
+        let mut short: String = name.chars().take(MAX_NAME_WIDTH - 1).collect();
+        short.push('…');
+        short
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_status_line_rendering() {
+        let at = |h, m| NaiveDate::from_ymd_opt(2024, 3, 20).unwrap().and_hms_opt(h, m, 0).unwrap();
+        let timer = |ticket_id, started, paused_at| ActiveTimer {
+            ticket_id,
+            started_at: started,
+            segment_started_at: started,
+            paused_at,
+            elapsed_seconds: 0,
+            note: None,
+        };
+        let counts = OpenCounts { open: 14, due_today: 2, overdue: 1 };
+
+        let running = timer(12, at(9, 0), None);
+        let line = render(DEFAULT_TEMPLATE, Some((&running, "Fix login")), &counts, at(10, 5));
+        assert_eq!(line, "#12 1:05 due:2 open:14");
+        assert_eq!(render(DEFAULT_TEMPLATE, None, &counts, at(10, 5)), "due:2 open:14");
+        assert_eq!(
+            render("[{ticket}] {name} {elapsed} !{overdue} {unknown}", Some((&running, &"x".repeat(40))), &counts, at(9, 30)),
+            format!("[12] {}… 0:30 !1 {{unknown}}", "x".repeat(29))
+        );
+
+        // A running timer wins over a paused one started later
+        let paused = timer(7, at(9, 30), Some(at(9, 45)));
+        let timers = [running.clone(), paused.clone()];
+        assert_eq!(pick_timer(&timers).unwrap().ticket_id, 12);
+        assert_eq!(render("{timer}", Some((&paused, "")), &counts, at(11, 0)), "#7 0:15 paused");
+        assert!(pick_timer(&[]).is_none());
+    }
+}
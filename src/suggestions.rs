@@ -1,6 +1,16 @@
-use strsim::jaro_winkler;
+use clap::Command;
+use strsim::{jaro_winkler, sorensen_dice};
 use crate::db::Database;
 use anyhow::Result;
+use crate::models::Ticket;
+use crate::output::icon;
+use crate::validation::is_finished_status;
+
+/// Bigram similarity above which a new ticket is treated as a likely duplicate.
+///
+/// Jaro-Winkler favours shared prefixes too much for ticket names ("Fix login page" and
+/// "Fix logout page" score over 0.9), so duplicates use Sørensen-Dice instead.
+const DUPLICATE_THRESHOLD: f64 = 0.75;
 
 /// Suggests close project names based on typos using string similarity
 pub async fn suggest_project_names(db: &Database, input: &str) -> Result<Vec<String>> {
@@ -26,6 +36,45 @@ pub async fn suggest_project_names(db: &Database, input: &str) -> Result<Vec<Str
         .collect())
 }
 
+/// Open tickets whose names are very similar to `name`, most similar first
+pub fn find_similar_tickets<'a>(tickets: &'a [Ticket], name: &str) -> Vec<&'a Ticket> {
+    let name = name.trim().to_lowercase();
+    let mut similar: Vec<(&Ticket, f64)> = tickets
+        .iter()
+        .filter(|t| !is_finished_status(&t.status))
+        .map(|t| (t, sorensen_dice(&name, &t.name.trim().to_lowercase())))
+        .filter(|(_, similarity)| *similarity >= DUPLICATE_THRESHOLD)
+        .collect();
+    similar.sort_by(|a, b| b.1.total_cmp(&a.1));
+    similar.into_iter().map(|(t, _)| t).collect()
+}
+
+/// Minimum score for `ticket similar` to list a ticket
+const RELATED_THRESHOLD: f64 = 0.3;
+
+/// Text similarity of two tickets, weighting names over descriptions
+fn ticket_similarity(a: &Ticket, b: &Ticket) -> f64 {
+    let name = sorensen_dice(&a.name.to_lowercase(), &b.name.to_lowercase());
+    if a.description.trim().is_empty() || b.description.trim().is_empty() {
+        return name;
+    }
+    let description = sorensen_dice(&a.description.to_lowercase(), &b.description.to_lowercase());
+    0.6 * name + 0.4 * description
+}
+
+/// Other tickets ranked by how similar their name and description are to `ticket`,
+/// most similar first, with their scores between 0 and 1
+pub fn rank_related<'a>(ticket: &Ticket, candidates: &'a [Ticket]) -> Vec<(&'a Ticket, f64)> {
+    let mut related: Vec<(&Ticket, f64)> = candidates
+        .iter()
+        .filter(|t| t.id != ticket.id)
+        .map(|t| (t, ticket_similarity(ticket, t)))
+        .filter(|(_, score)| *score >= RELATED_THRESHOLD)
+        .collect();
+    related.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.id.cmp(&b.0.id)));
+    related
+}
+
 /// Suggests status names based on common statuses and typos
 pub fn suggest_status_names(input: &str) -> Vec<String> {
     let common_statuses = vec![
@@ -49,15 +98,124 @@ pub fn suggest_status_names(input: &str) -> Vec<String> {
         .collect()
 }
 
+/// Similarity above which a subcommand is suggested for a mistyped one
+const COMMAND_THRESHOLD: f64 = 0.8;
+
+/// Visible subcommands of `command` close to `input`, most similar first. An alias counts
+/// towards its command.
+fn similar_subcommands<'a>(command: &'a Command, input: &str) -> Vec<&'a Command> {
+    let input = input.to_lowercase();
+    let mut similar: Vec<(&Command, f64)> = command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set())
+        .map(|sub| {
+            let names = std::iter::once(sub.get_name()).chain(sub.get_visible_aliases());
+            (sub, names.map(|name| jaro_winkler(&input, name)).fold(0.0, f64::max))
+        })
+        .filter(|(_, similarity)| *similarity > COMMAND_THRESHOLD)
+        .collect();
+    similar.sort_by(|a, b| b.1.total_cmp(&a.1));
+    similar.into_iter().map(|(sub, _)| sub).collect()
+}
+
+/// Suggests commands for `invalid`, a subcommand in `args` (the words after the binary
+/// name) that `command` does not have. Each suggestion starts where the typo is and
+/// corrects the action after it too, so `tiket crate` gets `ticket create`. Top 3.
+pub fn suggest_subcommands(command: &Command, args: &[String], invalid: &str) -> Vec<String> {
+    let mut current = command;
+    let mut rest = args.iter();
+    for arg in rest.by_ref() {
+        if arg == invalid {
+            break;
+        }
+        if let Some(sub) = current.find_subcommand(arg) {
+            current = sub;
+        }
+    }
+    let next = rest.find(|arg| !arg.starts_with('-'));
+    similar_subcommands(current, invalid)
+        .into_iter()
+        .take(3)
+        .map(|sub| {
+            let action = next.and_then(|next| sub.find_subcommand(next).or_else(|| similar_subcommands(sub, next).first().copied()));
+            match action {
+                Some(action) => format!("{} {}", sub.get_name(), action.get_name()),
+                None => sub.get_name().to_string(),
+            }
+        })
+        .collect()
+}
+
 /// Returns helpful message with suggestions
 pub fn format_suggestions(_input: &str, suggestions: &[String], item_type: &str) -> Option<String> {
     if suggestions.is_empty() {
         None
     } else {
         Some(format!(
-            "🤔 Did you mean one of these {}s?\n  {}",
+            "{}Did you mean one of these {}s?\n  {}",
+            icon("🤔 "),
             item_type,
             suggestions.join(", ")
         ))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn described(id: i64, name: &str, description: &str) -> Ticket {
+        Ticket { id, name: name.to_string(), description: description.to_string(), ..Default::default() }
+    }
+
+    fn ticket(id: i64, name: &str, status: &str) -> Ticket {
+        Ticket { id, name: name.to_string(), status: status.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_suggest_subcommands() {
+        let command = Command::new("ltm")
+            .subcommand(Command::new("ticket").subcommand(Command::new("create")).subcommand(Command::new("list").visible_alias("ls")))
+            .subcommand(Command::new("time").subcommand(Command::new("start")))
+            .subcommand(Command::new("timer"))
+            .subcommand(Command::new("tickle").hide(true));
+        let args = |words: &str| words.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(suggest_subcommands(&command, &args("tiket crate 12"), "tiket"), ["ticket create", "time"]);
+        assert_eq!(suggest_subcommands(&command, &args("-v ticket lst"), "lst"), ["list"]);
+        assert_eq!(suggest_subcommands(&command, &args("tiket ls"), "tiket")[0], "ticket list");
+        assert!(suggest_subcommands(&command, &args("xyzzy"), "xyzzy").is_empty());
+    }
+
+    #[test]
+    fn test_find_similar_tickets() {
+        let tickets = vec![
+            ticket(1, "Fix login bug", "open"),
+            ticket(2, "Add dark mode", "in-progress"),
+            ticket(3, "Fix login bugs on Safari", "closed"),
+            ticket(4, "Write release notes", "open"),
+        ];
+
+        let similar: Vec<i64> = find_similar_tickets(&tickets, "fix the login bug").iter().map(|t| t.id).collect();
+        assert_eq!(similar, vec![1]);
+        assert_eq!(find_similar_tickets(&tickets, "Add dark mode ").len(), 1);
+        assert!(find_similar_tickets(&tickets, "Update dependencies").is_empty());
+        assert!(find_similar_tickets(&tickets, "Fix logout page").is_empty());
+        // Finished tickets are not duplicates
+        assert!(find_similar_tickets(&tickets, "Fix login bugs on Safari").is_empty());
+    }
+
+    #[test]
+    fn test_rank_related() {
+        let ticket = described(1, "Login fails on Safari", "Session cookie is dropped after redirect");
+        let tickets = vec![
+            ticket.clone(),
+            described(2, "Write release notes", "Summarise the changes since 1.2"),
+            described(3, "Safari login loop", "The session cookie is dropped on redirect"),
+            described(4, "Login fails on Firefox", ""),
+        ];
+
+        let ranked: Vec<i64> = rank_related(&ticket, &tickets).iter().map(|(t, _)| t.id).collect();
+        assert_eq!(ranked, vec![3, 4]);
+        assert!(rank_related(&ticket, &tickets).iter().all(|(_, score)| (0.0..=1.0).contains(score)));
+    }
+}
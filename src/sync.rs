@@ -0,0 +1,479 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::db::Database;
+use crate::models::{RemoteLink, Ticket};
+use crate::validation::is_finished_status;
+
+/// An issue in another tracker, as `plan` needs it
+pub trait RemoteIssue: Clone {
+    /// The tracker's ID for the issue
+    fn remote_id(&self) -> &str;
+    /// The identifier people use, e.g. `ENG-123`
+    fn key(&self) -> &str;
+    fn title(&self) -> &str;
+    /// When the issue last changed, in UTC
+    fn updated_at(&self) -> NaiveDateTime;
+}
+
+/// Ticket fields as one side of a sync has them, keyed by field name: `name`,
+/// `description`, `status`, `priority`, and `due_date`. Fields a tracker does not sync are
+/// left out.
+pub type Fields = BTreeMap<String, Value>;
+
+/// Every synced field of a ticket
+pub fn ticket_fields(ticket: &Ticket) -> Fields {
+    Fields::from([
+        ("name".to_string(), json!(ticket.name)),
+        ("description".to_string(), json!(ticket.description)),
+        ("status".to_string(), json!(ticket.status)),
+        ("priority".to_string(), json!(ticket.priority)),
+        ("due_date".to_string(), json!(ticket.due_date)),
+    ])
+}
+
+/// Write the fields that differ from the ticket's
+pub async fn update_ticket(db: &Database, ticket: &Ticket, fields: &Fields) -> Result<()> {
+    let current = ticket_fields(ticket);
+    for (field, value) in fields.iter().filter(|(field, value)| current.get(*field) != Some(value)) {
+        let text = value.as_str();
+        match field.as_str() {
+            "name" => db.update_ticket_name(ticket.id, text.unwrap_or_default()).await?,
+            "description" => db.update_ticket_description(ticket.id, text.unwrap_or_default()).await?,
+            "status" => db.update_ticket_status(ticket.id, text.unwrap_or("open")).await?,
+            "priority" => db.update_ticket_priority(ticket.id, text).await?,
+            "due_date" => db.update_ticket_due_date(ticket.id, text.and_then(|date| date.parse().ok())).await?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// A field changed differently on both sides since ticket and issue last matched
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub field: String,
+    pub local: Value,
+    pub remote: Value,
+    /// Whether the issue changed after the ticket
+    pub remote_newer: bool,
+}
+
+impl Conflict {
+    /// Whether both versions can be kept, one after the other
+    pub fn can_merge(&self) -> bool {
+        self.field == "description"
+    }
+
+    /// The value a resolution gives the field on both sides
+    pub fn value(&self, resolution: Resolution) -> Value {
+        match resolution {
+            Resolution::Local => self.local.clone(),
+            Resolution::Remote => self.remote.clone(),
+            Resolution::Merge => {
+                let parts = [&self.local, &self.remote].map(|v| v.as_str().unwrap_or_default().trim().to_string());
+                json!(parts.iter().filter(|p| !p.is_empty()).cloned().collect::<Vec<_>>().join("\n\n"))
+            }
+        }
+    }
+}
+
+/// How a conflict was settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Keep the ticket's value
+    Local,
+    /// Keep the issue's value
+    Remote,
+    /// Keep both, the ticket's first
+    Merge,
+}
+
+impl Resolution {
+    pub fn name(self) -> &'static str {
+        match self {
+            Resolution::Local => "local",
+            Resolution::Remote => "remote",
+            Resolution::Merge => "merge",
+        }
+    }
+}
+
+/// How `ltm sync --prefer` settles conflicts without asking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Prefer {
+    /// Keep the ticket's value
+    Local,
+    /// Keep the issue's value
+    Remote,
+    /// Keep the value of whichever side changed last
+    Newer,
+}
+
+impl Prefer {
+    pub fn resolve(self, conflict: &Conflict) -> Resolution {
+        match self {
+            Prefer::Local => Resolution::Local,
+            Prefer::Remote => Resolution::Remote,
+            Prefer::Newer if conflict.remote_newer => Resolution::Remote,
+            Prefer::Newer => Resolution::Local,
+        }
+    }
+}
+
+/// Settles a conflict on a ticket and the issue with the given key, say by asking
+pub type Resolver<'a> = &'a (dyn Fn(&Ticket, &str, &Conflict) -> Result<Resolution> + Sync);
+
+/// The fields of a ticket and its issue when they last matched, stored with the link
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Base {
+    pub local: Fields,
+    pub remote: Fields,
+}
+
+/// Compare a ticket and its issue with how they last matched. Returns the issue's fields
+/// to copy to the ticket, which changed on its side only, and the fields that changed
+/// differently on both. `None` for links saved without a base.
+pub fn three_way(ticket: &Ticket, remote: &Fields, remote_newer: bool, link: &RemoteLink) -> Option<(Fields, Vec<Conflict>)> {
+    let base: Base = serde_json::from_str(link.base.as_deref()?).ok()?;
+    let local = ticket_fields(ticket);
+    let mut pull = Fields::new();
+    let mut conflicts = Vec::new();
+    for (field, remote_value) in remote {
+        let local_value = &local[field];
+        let remote_changed = base.remote.get(field) != Some(remote_value);
+        let local_changed = base.local.get(field) != Some(local_value);
+        match (local_changed, remote_changed) {
+            (false, true) => {
+                pull.insert(field.clone(), remote_value.clone());
+            }
+            (true, true) if local_value != remote_value => conflicts.push(Conflict {
+                field: field.clone(),
+                local: local_value.clone(),
+                remote: remote_value.clone(),
+                remote_newer,
+            }),
+            _ => {}
+        }
+    }
+    Some((pull, conflicts))
+}
+
+/// One change a sync makes
+#[derive(Debug, Clone)]
+pub enum Action<I> {
+    /// Create a ticket in `project` for a new issue
+    Import { issue: I, project: String },
+    /// Copy an issue's changes to its ticket
+    Pull { ticket: Ticket, issue: I, link: RemoteLink },
+    /// Create an issue in `scope` (a team or project of the tracker) for a new ticket
+    Export { ticket: Ticket, scope: String },
+    /// Copy a ticket's changes to its issue
+    Push { ticket: Ticket, link: RemoteLink },
+    /// Copy the issue's changes to its ticket field by field, settle `conflicts`, then
+    /// copy the ticket to the issue; for tickets and issues that both changed
+    Merge { ticket: Ticket, issue: I, link: RemoteLink, pull: Fields, conflicts: Vec<Conflict> },
+}
+
+impl<I: RemoteIssue> Action<I> {
+    /// What the action does, for people
+    pub fn describe(&self) -> String {
+        match self {
+            Action::Import { issue, project } => format!("{} → new ticket in {}: {}", issue.key(), project, issue.title()),
+            Action::Pull { ticket, issue, .. } => format!("{} → #{} {}", issue.key(), ticket.id, issue.title()),
+            Action::Export { ticket, scope } => format!("#{} → new issue in {}: {}", ticket.id, scope, ticket.name),
+            Action::Push { ticket, link } => {
+                format!("#{} → {} {}", ticket.id, link.remote_key.as_deref().unwrap_or(&link.remote_id), ticket.name)
+            }
+            Action::Merge { ticket, issue, conflicts, .. } if conflicts.is_empty() => {
+                format!("#{} ⇄ {} {}", ticket.id, issue.key(), ticket.name)
+            }
+            Action::Merge { ticket, issue, conflicts, .. } => {
+                let fields: Vec<&str> = conflicts.iter().map(|c| c.field.as_str()).collect();
+                format!("#{} ⇄ {} {} (conflicts: {})", ticket.id, issue.key(), ticket.name, fields.join(", "))
+            }
+        }
+    }
+
+    /// The action as the command reports it
+    pub fn change(&self) -> Change {
+        let kind = match self {
+            Action::Import { .. } => "import",
+            Action::Pull { .. } => "pull",
+            Action::Export { .. } => "export",
+            Action::Push { .. } => "push",
+            Action::Merge { .. } => "merge",
+        };
+        Change { kind, description: self.describe() }
+    }
+}
+
+/// What a sync did, or would do, as the command reports it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// import, pull, export, push, merge, or worklog
+    pub kind: &'static str,
+    pub description: String,
+}
+
+/// Work out what a sync does. `scopes` maps the tracker's teams or projects to ltm
+/// projects, `issues` are those changed since the last sync keyed by scope, `tickets` are
+/// the tickets of every synced project, and `fields` reads an issue's synced fields.
+///
+/// An issue or ticket that changed on one side only is copied to the other. When both
+/// changed since they last matched, they are merged field by field, and fields changed on
+/// both sides become conflicts; links saved before fields were tracked fall back to the
+/// newer change winning. Unlinked tickets that are already finished are not exported.
+pub fn plan<I: RemoteIssue>(
+    scopes: &BTreeMap<String, String>,
+    issues: &BTreeMap<String, Vec<I>>,
+    tickets: &[Ticket],
+    links: &[RemoteLink],
+    fields: impl Fn(&I) -> Fields,
+) -> Vec<Action<I>> {
+    let by_remote: HashMap<&str, &RemoteLink> = links.iter().map(|l| (l.remote_id.as_str(), l)).collect();
+    let by_ticket: HashMap<i64, &RemoteLink> = links.iter().map(|l| (l.ticket_id, l)).collect();
+    let tickets_by_id: HashMap<i64, &Ticket> = tickets.iter().map(|t| (t.id, t)).collect();
+    let mut actions = Vec::new();
+    let mut pulled = Vec::new();
+
+    for (scope, project) in scopes {
+        for issue in issues.get(scope).into_iter().flatten() {
+            let Some(link) = by_remote.get(issue.remote_id()) else {
+                actions.push(Action::Import { issue: issue.clone(), project: project.clone() });
+                continue;
+            };
+            let Some(ticket) = tickets_by_id.get(&link.ticket_id) else {
+                continue;
+            };
+            let remote_changed = issue.updated_at() > link.remote_updated_at;
+            let local_changed = ticket.updated_at > link.synced_at;
+            let remote_newer = issue.updated_at() > ticket.updated_at;
+            if !remote_changed {
+                continue;
+            }
+            if !local_changed {
+                pulled.push(ticket.id);
+                actions.push(Action::Pull { ticket: (*ticket).clone(), issue: issue.clone(), link: (*link).clone() });
+            } else if let Some((pull, conflicts)) = three_way(ticket, &fields(issue), remote_newer, link) {
+                pulled.push(ticket.id);
+                let (ticket, issue, link) = ((*ticket).clone(), issue.clone(), (*link).clone());
+                actions.push(Action::Merge { ticket, issue, link, pull, conflicts });
+            } else if remote_newer {
+                pulled.push(ticket.id);
+                actions.push(Action::Pull { ticket: (*ticket).clone(), issue: issue.clone(), link: (*link).clone() });
+            }
+        }
+    }
+
+    let projects: HashMap<&str, &str> = scopes.iter().map(|(scope, project)| (project.as_str(), scope.as_str())).collect();
+    for ticket in tickets {
+        let Some(scope) = projects.get(ticket.project.as_str()) else {
+            continue;
+        };
+        match by_ticket.get(&ticket.id) {
+            None if !is_finished_status(&ticket.status) => {
+                actions.push(Action::Export { ticket: ticket.clone(), scope: scope.to_string() })
+            }
+            Some(link) if ticket.updated_at > link.synced_at && !pulled.contains(&ticket.id) => {
+                actions.push(Action::Push { ticket: ticket.clone(), link: (*link).clone() })
+            }
+            _ => {}
+        }
+    }
+    actions
+}
+
+/// Settle a merge's conflicts with `resolve`, recording each resolution in the event
+/// journal, and return the fields to write to the ticket
+pub async fn resolve_conflicts(
+    db: &Database,
+    provider: &str,
+    ticket: &Ticket,
+    issue: &impl RemoteIssue,
+    pull: &Fields,
+    conflicts: &[Conflict],
+    resolve: Resolver<'_>,
+) -> Result<Fields> {
+    let mut fields = pull.clone();
+    for conflict in conflicts {
+        let resolution = resolve(ticket, issue.key(), conflict)?;
+        let value = conflict.value(resolution);
+        let before = json!({
+            "ticket_id": ticket.id,
+            "provider": provider,
+            "remote_key": issue.key(),
+            "field": conflict.field,
+            "local": conflict.local,
+            "remote": conflict.remote,
+        });
+        let after = json!({
+            "ticket_id": ticket.id,
+            "provider": provider,
+            "remote_key": issue.key(),
+            "field": conflict.field,
+            "resolution": resolution.name(),
+            "value": value,
+        });
+        db.record_sync_resolution(ticket.id, &before, &after).await?;
+        fields.insert(conflict.field.clone(), value);
+    }
+    Ok(fields)
+}
+
+/// Write a merge's fields to the ticket, returning the ticket as it is afterwards, ready to
+/// be copied to the issue
+pub async fn merge_into_ticket(db: &Database, ticket: &Ticket, fields: &Fields) -> Result<Ticket> {
+    update_ticket(db, ticket, fields).await?;
+    db.get_ticket(ticket.id).await?.with_context(|| format!("Ticket {} disappeared during the sync", ticket.id))
+}
+
+/// Record that a ticket and an issue with `remote` fields match as they are now
+pub async fn save_link(db: &Database, provider: &str, ticket_id: i64, issue: &impl RemoteIssue, remote: Fields) -> Result<()> {
+    let ticket = db.get_ticket(ticket_id).await?;
+    let base = Base { local: ticket.as_ref().map(ticket_fields).unwrap_or_default(), remote };
+    db.save_remote_link(&RemoteLink {
+        ticket_id,
+        provider: provider.to_string(),
+        remote_id: issue.remote_id().to_string(),
+        remote_key: Some(issue.key().to_string()),
+        synced_at: ticket.map(|t| t.updated_at).unwrap_or_default(),
+        remote_updated_at: issue.updated_at(),
+        base: Some(serde_json::to_string(&base)?),
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+
+    #[derive(Debug, Clone)]
+    struct Issue {
+        id: String,
+        key: String,
+        updated_at: NaiveDateTime,
+        fields: Fields,
+    }
+
+    impl RemoteIssue for Issue {
+        fn remote_id(&self) -> &str {
+            &self.id
+        }
+        fn key(&self) -> &str {
+            &self.key
+        }
+        fn title(&self) -> &str {
+            &self.id
+        }
+        fn updated_at(&self) -> NaiveDateTime {
+            self.updated_at
+        }
+    }
+
+    fn fields(pairs: &[(&str, Value)]) -> Fields {
+        pairs.iter().map(|(field, value)| (field.to_string(), value.clone())).collect()
+    }
+
+    #[test]
+    fn test_sync_plan() {
+        let then = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let ticket = |id: i64, status: &str, updated: NaiveDateTime| Ticket {
+            id,
+            project: "webapp".to_string(),
+            name: format!("Ticket {}", id),
+            description: String::new(),
+            status: status.to_string(),
+            created_at: then,
+            updated_at: updated,
+            due_date: None,
+            rank: id,
+            priority: None,
+            estimate_minutes: None,
+            archived_at: None,
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            rate: None,
+            kind: None,
+            severity: None,
+            component: None,
+            target_release: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            pinned_at: None,
+        };
+        let issue = |id: &str, updated_at| Issue { id: id.to_string(), key: format!("ENG-{}", id), updated_at, fields: Fields::new() };
+        let link = |ticket_id: i64, remote_id: &str| RemoteLink {
+            ticket_id,
+            provider: "test".to_string(),
+            remote_id: remote_id.to_string(),
+            remote_key: None,
+            synced_at: then,
+            remote_updated_at: then,
+            base: None,
+        };
+        let later = |minutes| then + Duration::minutes(minutes);
+
+        let scopes = BTreeMap::from([("ENG".to_string(), "webapp".to_string())]);
+        let tickets = vec![
+            ticket(1, "open", then),            // unchanged, issue changed: pull
+            ticket(2, "open", later(10)),       // changed, issue unchanged: push
+            ticket(3, "open", later(20)),       // both changed, ticket newer: push
+            ticket(4, "open", later(5)),        // both changed, issue newer: pull
+            ticket(5, "open", later(1)),        // unlinked: export
+            ticket(6, "closed", later(1)),      // unlinked but finished: left alone
+            ticket(7, "testing", later(20)),    // both changed, with a base: merge
+        ];
+        let base = Base {
+            local: ticket_fields(&ticket(7, "open", then)),
+            remote: fields(&[("name", json!("Ticket 7")), ("status", json!("open"))]),
+        };
+        let links = vec![
+            link(1, "a"),
+            link(2, "b"),
+            link(3, "c"),
+            link(4, "d"),
+            RemoteLink { base: Some(serde_json::to_string(&base).unwrap()), ..link(7, "f") },
+        ];
+        let merged = Issue { fields: fields(&[("name", json!("Renamed")), ("status", json!("closed"))]), ..issue("f", later(30)) };
+        let issues = BTreeMap::from([(
+            "ENG".to_string(),
+            vec![issue("a", later(3)), issue("c", later(15)), issue("d", later(15)), issue("e", later(2)), merged],
+        )]);
+
+        let actions = plan(&scopes, &issues, &tickets, &links, |issue| issue.fields.clone());
+        let changes: Vec<(&str, String)> = actions.iter().map(|a| a.change()).map(|c| (c.kind, c.description)).collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("pull", "ENG-a → #1 a".to_string()),
+                ("pull", "ENG-d → #4 d".to_string()),
+                ("import", "ENG-e → new ticket in webapp: e".to_string()),
+                ("merge", "#7 ⇄ ENG-f Ticket 7 (conflicts: status)".to_string()),
+                ("push", "#2 → b Ticket 2".to_string()),
+                ("push", "#3 → c Ticket 3".to_string()),
+                ("export", "#5 → new issue in ENG: Ticket 5".to_string()),
+            ]
+        );
+        let Action::Merge { pull, conflicts, .. } = &actions[3] else { panic!("expected a merge") };
+        assert_eq!(pull, &fields(&[("name", json!("Renamed"))]));
+        assert_eq!((conflicts[0].local.clone(), conflicts[0].remote.clone(), conflicts[0].remote_newer), (json!("testing"), json!("closed"), true));
+    }
+
+    #[test]
+    fn test_conflict_resolution() {
+        let conflict =
+            Conflict { field: "description".to_string(), local: json!("Mine"), remote: json!("Theirs "), remote_newer: false };
+        assert!(conflict.can_merge());
+        assert_eq!(conflict.value(Resolution::Local), json!("Mine"));
+        assert_eq!(conflict.value(Resolution::Remote), json!("Theirs "));
+        assert_eq!(conflict.value(Resolution::Merge), json!("Mine\n\nTheirs"));
+        assert!(!Conflict { field: "status".to_string(), ..conflict }.can_merge());
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+
+use crate::log_line::duration_word;
+use crate::validation::{
+    logged_at_local, validate_content_length, validate_date, validate_ticket_id, validate_minutes, validate_timestamp,
+    ContentType,
+};
+
+/// The CSV columns holding each part of a time log, each a header name (in any case) or a
+/// 1-based position
+#[derive(Debug, Clone)]
+pub struct ColumnMap {
+    pub ticket: String,
+    pub date: String,
+    pub duration: String,
+    /// Notes come from a `note` column, if there is one, unless a column is given
+    pub note: Option<String>,
+}
+
+impl Default for ColumnMap {
+    fn default() -> Self {
+        Self { ticket: "ticket".to_string(), date: "date".to_string(), duration: "duration".to_string(), note: None }
+    }
+}
+
+/// A time log read from one row, before its ticket is looked up
+#[derive(Debug, PartialEq)]
+pub struct ImportedLog {
+    /// Line of the file the row starts on
+    pub line: u64,
+    pub ticket_id: i64,
+    /// In UTC; a date without a time is logged at local midday, like `time log --date`
+    pub logged_at: NaiveDateTime,
+    pub minutes: i64,
+    pub note: Option<String>,
+}
+
+/// A row that can't be imported: its line and what is wrong with it
+pub type RowError = (u64, String);
+
+/// Read time logs from CSV with a header row.
+///
+/// Rows that don't parse come back as messages with their line number, so one pass
+/// reports every problem; an unknown column fails the whole file.
+pub fn read_time_csv(text: &str, columns: &ColumnMap) -> Result<(Vec<ImportedLog>, Vec<RowError>)> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let ticket = column_index(&headers, &columns.ticket)?;
+    let date = column_index(&headers, &columns.date)?;
+    let duration = column_index(&headers, &columns.duration)?;
+    let note = match &columns.note {
+        Some(note) => Some(column_index(&headers, note)?),
+        None => column_index(&headers, "note").ok(),
+    };
+
+    let mut logs = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        // The reader's own line count starts a record that follows a blank line on the blank
+        // line, so count the lines up to its first character instead
+        let start = record.position().map_or(0, |position| position.byte() as usize);
+        let start = text.len() - text[start..].trim_start_matches(['\r', '\n']).len();
+        let line = text[..start].matches('\n').count() as u64 + 1;
+        let cell = |index: usize| record.get(index).unwrap_or("");
+        match read_row(line, cell(ticket), cell(date), cell(duration), note.map_or("", cell)) {
+            Ok(log) => logs.push(log),
+            Err(e) => errors.push((line, e.to_string())),
+        }
+    }
+    Ok((logs, errors))
+}
+
+fn column_index(headers: &csv::StringRecord, column: &str) -> Result<usize> {
+    if let Ok(position) = column.parse::<usize>() {
+        if position == 0 || position > headers.len() {
+            anyhow::bail!("Column {} is out of range; the file has {} columns", position, headers.len());
+        }
+        return Ok(position - 1);
+    }
+    headers.iter().position(|header| header.eq_ignore_ascii_case(column)).ok_or_else(|| {
+        anyhow::anyhow!("No '{}' column; the file has {}", column, headers.iter().collect::<Vec<_>>().join(", "))
+    })
+}
+
+fn read_row(line: u64, ticket: &str, date: &str, duration: &str, note: &str) -> Result<ImportedLog> {
+    let ticket_id = validate_ticket_id(ticket)?;
+    let logged_at = match validate_date(date) {
+        Ok(day) => logged_at_local(day, None)?,
+        Err(_) => validate_timestamp(date)?,
+    };
+    if logged_at > Utc::now().naive_utc() {
+        anyhow::bail!("{} is in the future", date);
+    }
+    let Some(minutes) = cell_minutes(duration) else {
+        anyhow::bail!("'{}' is not a duration like 2h30m, 1:30, or 1.5 (hours)", duration);
+    };
+    validate_minutes(minutes)?;
+    let note = match note {
+        "" => None,
+        note => Some(validate_content_length(note, ContentType::TimerNote)?),
+    };
+    Ok(ImportedLog { line, ticket_id, logged_at, minutes, note })
+}
+
+/// Minutes in a duration as other trackers export it: `2h30m`, `1h 30m`, `45 min`,
+/// `1:30` or `1:30:00`, or decimal hours such as `1.5`
+fn cell_minutes(value: &str) -> Option<i64> {
+    if let Ok(hours) = value.parse::<f64>() {
+        return Some((hours * 60.0).round()).filter(|minutes| minutes.is_finite() && *minutes > 0.0).map(|m| m as i64);
+    }
+    if let Some((hours, rest)) = value.split_once(':') {
+        let (minutes, seconds) = rest.split_once(':').unwrap_or((rest, "0"));
+        let (hours, minutes, seconds) = (hours.parse::<i64>().ok()?, minutes.parse::<i64>().ok()?, seconds.parse::<i64>().ok()?);
+        let total = hours.checked_mul(60)?.checked_add(minutes)?.checked_add(seconds.checked_add(30)? / 60)?;
+        return Some(total).filter(|&minutes| minutes > 0);
+    }
+    duration_word(&value.replace(' ', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_read_time_csv() {
+        let day = |d: u32| logged_at_local(NaiveDate::from_ymd_opt(2024, 5, d).unwrap(), None).unwrap();
+        let csv = "Ticket,Date,Duration,Note\n\
+                   14,2024-05-06,2h30m,\"debugging auth, again\"\n\
+                   WEB-7,2024-05-07,1:30:00,\n\
+                   \n\
+                   7,2024-05-08,1.25,\"release\n notes\"\n\
+                   x,2024-05-08,1h,\n\
+                   7,someday,1h,\n\
+                   7,2024-05-09,soon,\n\
+                   7,2024-05-09,257698037765:00,\n\
+                   7,2024-05-09,153722867280912931:00,\n";
+        let (logs, errors) = read_time_csv(csv, &ColumnMap::default()).unwrap();
+        assert_eq!(
+            logs[0],
+            ImportedLog { line: 2, ticket_id: 14, logged_at: day(6), minutes: 150, note: Some("debugging auth, again".to_string()) }
+        );
+        assert_eq!((logs[1].ticket_id, logs[1].minutes, logs[1].note.as_deref()), (7, 90, None));
+        assert_eq!((logs[2].line, logs[2].minutes), (5, 75));
+        assert_eq!(logs.len(), 3);
+        assert_eq!(errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(), [7, 8, 9, 10, 11]);
+        assert!(errors[2].1.contains("'soon' is not a duration"));
+        // Durations too long to store are refused, not cut down
+        assert!(errors[3].1.starts_with("Invalid time value"));
+        assert!(errors[4].1.contains("is not a duration"));
+
+        // Columns by name or position, with a timestamp for the date
+        let toggl = "Description,Project,Start,Hours\nStandup,3,2024-05-06 09:15,45 min\n";
+        let columns = ColumnMap { ticket: "2".to_string(), date: "start".to_string(), duration: "hours".to_string(), note: Some("Description".to_string()) };
+        let (logs, errors) = read_time_csv(toggl, &columns).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!((logs[0].ticket_id, logs[0].minutes, logs[0].note.as_deref()), (3, 45, Some("Standup")));
+        assert_eq!(logs[0].logged_at, validate_timestamp("2024-05-06 09:15").unwrap());
+
+        let missing = read_time_csv(toggl, &ColumnMap::default()).unwrap_err().to_string();
+        assert_eq!(missing, "No 'ticket' column; the file has Description, Project, Start, Hours");
+        assert!(read_time_csv(toggl, &ColumnMap { ticket: "5".to_string(), ..columns }).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::log_line::duration_word;
+use crate::models::{TimeLog, TimeLogChange};
+use crate::validation::{validate_content_length, validate_ticket_id, validate_time, ContentType};
+
+/// One line of an edited timesheet: a time log, or a new one when `id` is `None`
+#[derive(Debug, PartialEq)]
+pub struct TimesheetRow {
+    pub id: Option<i64>,
+    pub ticket_id: i64,
+    pub minutes: i64,
+    pub note: Option<String>,
+}
+
+/// A day's time logs as text to edit, one per line as `ID TICKET DURATION NOTE`, after a
+/// commented header naming the tickets
+pub fn format_timesheet(day: NaiveDate, logs: &[TimeLog], ticket_names: &BTreeMap<i64, String>) -> String {
+    let mut text = format!(
+        "# Time logs for {}. Change, add, or remove lines, then save and quit.\n\
+         # Columns: log ID (\"new\" for a new log), ticket, duration (2h, 1h30m, 45m), note.\n\
+         # Removing a line deletes its time log; lines starting with # are ignored.\n",
+        day
+    );
+    if !ticket_names.is_empty() {
+        text.push_str("#\n");
+        for (id, name) in ticket_names {
+            text.push_str(&format!("#   {:<6} {}\n", id, name));
+        }
+    }
+    text.push('\n');
+    for log in logs {
+        let line = format!(
+            "{:<6} {:<6} {:<7} {}",
+            log.id,
+            log.ticket_id,
+            compact_minutes(log.hours as i64 * 60 + log.minutes as i64),
+            log.note.as_deref().unwrap_or("")
+        );
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// A length as one word that `duration_word` reads back: `2h`, `1h05m`, or `45m`
+fn compact_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{:02}m", hours, minutes),
+    }
+}
+
+/// Read the lines of an edited timesheet, with the line number in any error
+pub fn parse_timesheet(text: &str) -> Result<Vec<TimesheetRow>> {
+    let mut rows = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row = parse_row(line).map_err(|e| anyhow::anyhow!("Line {}: {}", number + 1, e))?;
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn parse_row(line: &str) -> Result<TimesheetRow> {
+    let mut rest = line;
+    let mut next = || {
+        let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        rest = tail.trim_start();
+        word
+    };
+    let (id, ticket, duration) = (next(), next(), next());
+    if duration.is_empty() {
+        anyhow::bail!("expected a log ID, ticket, and duration in '{}'", line);
+    }
+
+    let id = match id.to_lowercase().as_str() {
+        "new" | "-" => None,
+        _ => Some(id.parse::<i64>().ok().filter(|&id| id > 0).ok_or_else(|| {
+            anyhow::anyhow!("'{}' is not a time log ID; write \"new\" to add a time log", id)
+        })?),
+    };
+    let ticket_id = validate_ticket_id(ticket)?;
+    let Some(minutes) = duration_word(duration) else {
+        anyhow::bail!("'{}' is not a duration like 2h, 1h30m, or 45m; remove the line to delete the log", duration);
+    };
+    validate_time((minutes / 60) as i32, (minutes % 60) as i32)?;
+    let note = match rest {
+        "" => None,
+        note => Some(validate_content_length(note, ContentType::TimerNote)?),
+    };
+    Ok(TimesheetRow { id, ticket_id, minutes, note })
+}
+
+/// The changes that turn `logs` into `rows`: removed lines are deleted, changed ones
+/// updated (keeping a start time but moving the end to match a new length), and new ones
+/// added, dated `logged_at`
+pub fn plan_changes(logs: &[TimeLog], rows: &[TimesheetRow], logged_at: NaiveDateTime) -> Result<Vec<TimeLogChange>> {
+    let mut seen = HashSet::new();
+    let mut changes = Vec::new();
+    for row in rows {
+        let (hours, minutes) = ((row.minutes / 60) as i32, (row.minutes % 60) as i32);
+        let Some(id) = row.id else {
+            changes.push(TimeLogChange::Add { ticket_id: row.ticket_id, hours, minutes, logged_at, note: row.note.clone() });
+            continue;
+        };
+        let Some(log) = logs.iter().find(|log| log.id == id) else {
+            anyhow::bail!("Time log #{} is not one of this day's logs; write \"new\" to add a time log", id);
+        };
+        if !seen.insert(id) {
+            anyhow::bail!("Time log #{} is listed more than once", id);
+        }
+        let length = Duration::minutes(row.minutes);
+        if log.ticket_id == row.ticket_id && log.duration() == length && log.note == row.note {
+            continue;
+        }
+        let (started_at, ended_at, _) = log.respan(None, None, Some(length));
+        changes.push(TimeLogChange::Update { id, ticket_id: row.ticket_id, hours, minutes, started_at, ended_at, note: row.note.clone() });
+    }
+    for log in logs.iter().filter(|log| !seen.contains(&log.id)) {
+        changes.push(TimeLogChange::Delete { id: log.id });
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(id: i64, ticket_id: i64, minutes: i32, note: Option<&str>) -> TimeLog {
+        let at = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        TimeLog {
+            id,
+            ticket_id,
+            hours: minutes / 60,
+            minutes: minutes % 60,
+            started_at: Some(at),
+            ended_at: Some(at + Duration::minutes(minutes as i64)),
+            created_at: at,
+            updated_at: at,
+            note: note.map(str::to_string),
+            uuid: String::new(),
+            created_by: None,
+            updated_by: None,
+            billable: true,
+        }
+    }
+
+    #[test]
+    fn test_timesheet() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let logs = vec![log(12, 14, 120, Some("debugging auth")), log(13, 7, 45, None), log(15, 7, 65, None)];
+        let names = BTreeMap::from([(7, "Release notes".to_string()), (14, "Fix auth".to_string())]);
+        let text = format_timesheet(day, &logs, &names);
+        assert!(text.contains("#   14     Fix auth\n"));
+        assert!(text.contains("\n12     14     2h      debugging auth\n13     7      45m\n15     7      1h05m\n"));
+
+        // Unedited, the sheet reads back to no changes
+        let rows = parse_timesheet(&text).unwrap();
+        assert_eq!(rows[0], TimesheetRow { id: Some(12), ticket_id: 14, minutes: 120, note: Some("debugging auth".to_string()) });
+        let noon = day.and_hms_opt(12, 0, 0).unwrap();
+        assert!(plan_changes(&logs, &rows, noon).unwrap().is_empty());
+
+        let edited = "12 14 2h30m debugging auth, again\n# 13 is gone\n15 7 1h05m\nnew 3 15m standup\n";
+        let changes = plan_changes(&logs, &parse_timesheet(edited).unwrap(), noon).unwrap();
+        let start = logs[0].started_at.unwrap();
+        assert_eq!(
+            changes,
+            [
+                TimeLogChange::Update {
+                    id: 12,
+                    ticket_id: 14,
+                    hours: 2,
+                    minutes: 30,
+                    started_at: Some(start),
+                    ended_at: Some(start + Duration::minutes(150)),
+                    note: Some("debugging auth, again".to_string()),
+                },
+                TimeLogChange::Add { ticket_id: 3, hours: 0, minutes: 15, logged_at: noon, note: Some("standup".to_string()) },
+                TimeLogChange::Delete { id: 13 },
+            ]
+        );
+
+        let error = |text: &str| parse_timesheet(text).and_then(|rows| plan_changes(&logs, &rows, noon)).unwrap_err().to_string();
+        assert!(error("# header\n12 14\n").starts_with("Line 2:"));
+        assert!(error("12 14 0m\n").contains("not a duration"));
+        assert!(error("x 14 1h\n").contains("not a time log ID"));
+        assert!(error("99 14 1h\n").contains("#99 is not one of this day's logs"));
+        assert!(error("12 14 1h\n12 14 2h\n").contains("more than once"));
+    }
+}
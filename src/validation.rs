@@ -1,282 +1,252 @@
-use regex::Regex;
-use strsim::levenshtein;
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum ValidationError {
-    #[error("Invalid ticket ID '{0}'. Must be a positive number.")]
-    InvalidTicketId(String),
-
-    #[error("Ticket #{0} not found.")]
-    TicketNotFound(i64),
-
-    #[error("Invalid project name '{0}'. Only letters, numbers, hyphens, underscores allowed.")]
-    InvalidProjectName(String),
-
-    #[error("Invalid time value. Hours must be 0-24, minutes must be 0-59.")]
-    InvalidTime(String),
-
-    #[error("Invalid status '{0}'. Must be one of: open, in-progress, testing, blocked, closed, cancelled.")]
-    InvalidStatus(String),
-
-    #[error("Invalid {field_type} length. {field_type} must be between {min} and {max} characters.")]
-    InvalidContentLength {
-        field_type: String,
-        min: usize,
-        max: usize,
-    },
-}
-
-#[derive(Debug, Clone)]
-pub enum ContentType {
-    TicketName,
-    Description,
-    Comment,
-}
-
-impl ContentType {
-    pub fn limits(&self) -> (usize, usize) {
-        match self {
-            ContentType::TicketName => (1, 100),
-            ContentType::Description => (1, 2000),
-            ContentType::Comment => (1, 1000),
-        }
-    }
-
-    pub fn name(&self) -> &'static str {
-        match self {
-            ContentType::TicketName => "ticket name",
-            ContentType::Description => "description",
-            ContentType::Comment => "comment",
-        }
-    }
-}
+pub use ltm_core::validation::*;
 
-/// Validate ticket IDs: positive integers that exist in database
-pub fn validate_ticket_id(id: &str) -> Result<i64, ValidationError> {
-    let parsed_id = id
-        .parse::<i64>()
-        .map_err(|_| ValidationError::InvalidTicketId(id.to_string()))?;
+use strsim::levenshtein;
 
-    if parsed_id <= 0 {
-        return Err(ValidationError::InvalidTicketId(id.to_string()));
+use crate::i18n::{t, tf};
+use crate::output::with_icon;
+
+/// Translated name of a content field such as "ticket name"
+fn field_name(field_type: &str) -> String {
+    match field_type {
+        "ticket name" => t("field.ticket_name").to_string(),
+        "description" => t("field.description").to_string(),
+        "comment" => t("field.comment").to_string(),
+        "checklist item" => t("field.checklist_item").to_string(),
+        "timer note" => t("field.timer_note").to_string(),
+        other => other.to_string(),
     }
-
-    Ok(parsed_id)
 }
 
-/// Validate project names: alphanumeric, hyphens, underscores, 1-50 chars
-pub fn validate_project_name(name: &str) -> Result<String, ValidationError> {
-    if name.is_empty() || name.len() > 50 {
-        return Err(ValidationError::InvalidProjectName(name.to_string()));
-    }
-
-    let regex = Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap();
-    if !regex.is_match(name) {
-        return Err(ValidationError::InvalidProjectName(name.to_string()));
+/// A valid value for a scoring field, for hints
+fn score_example(field: &str) -> &'static str {
+    match field {
+        "reach" => "500",
+        "impact" => "2",
+        "confidence" => "80%",
+        _ => "2.5",
     }
-
-    Ok(name.to_string())
 }
 
-/// Validate time values: non-negative, reasonable limits
-pub fn validate_time(hours: i32, minutes: i32) -> Result<(i32, i32), ValidationError> {
-    if hours < 0 || hours > 24 || minutes < 0 || minutes > 59 {
-        return Err(ValidationError::InvalidTime(format!(
-            "{}h {}m",
-            hours, minutes
-        )));
+/// Example command setting a valid value for a project setting
+fn setting_example(key: &str) -> &'static str {
+    match key {
+        "statuses" => "ltm project set ideas statuses backlog,open,closed",
+        "default_status" => "ltm project set ideas default_status backlog",
+        "default_priority" => "ltm project set webapp default_priority medium",
+        "time_rounding" => "ltm project set client-a time_rounding 15",
+        "id_prefix" => "ltm project set webapp id_prefix WEB",
+        "currency" => "ltm project set webapp currency EUR",
+        "wip_limits" => "ltm project set webapp wip_limits in-progress:3,review:2",
+        "wip_policy" => "ltm project set webapp wip_policy block",
+        "kinds" => "ltm project set webapp kinds bug,feature,spike",
+        "required_for" => "ltm project set webapp required_for in-progress:estimate,closed:comment",
+        _ => "ltm project set webapp required_fields due,estimate",
     }
-
-    Ok((hours, minutes))
 }
 
-/// Validate status: must be one of the predefined values
-pub fn validate_status(status: &str) -> Result<String, ValidationError> {
-    let valid_statuses = [
-        "open",
-        "in-progress",
-        "testing",
-        "blocked",
-        "closed",
-        "cancelled",
-        "wontfix",
-    ];
-
-    let lowercase_status = status.to_lowercase();
-    if valid_statuses.contains(&lowercase_status.as_str()) {
-        return Ok(lowercase_status);
+/// Example command creating a ticket with a required field
+fn required_field_example(field: &str) -> &'static str {
+    match field {
+        "due" => "ltm ticket create webapp \"Fix login\" \"Details\" --due friday",
+        "priority" => "ltm quick \"Fix login @webapp !high\"",
+        "estimate" => "ltm quick \"Fix login @webapp est:2h\"",
+        "tags" => "ltm quick \"Fix login @webapp #auth\"",
+        _ => "ltm ticket create webapp \"Fix login\" \"Details\"",
     }
-
-    // Try to provide helpful suggestions using fuzzy matching
-    let mut suggestions = valid_statuses
-        .iter()
-        .map(|&s| (s, levenshtein(status, s)))
-        .collect::<Vec<_>>();
-    suggestions.sort_by_key(|&(_, dist)| dist);
-
-    Err(ValidationError::InvalidStatus(status.to_string()))
 }
 
-/// Validate content length for different field types
-pub fn validate_content_length(
-    content: &str,
-    field_type: ContentType,
-) -> Result<String, ValidationError> {
-    let (min, max) = field_type.limits();
-    let len = content.len();
-
-    if len < min || len > max {
-        return Err(ValidationError::InvalidContentLength {
-            field_type: field_type.name().to_string(),
-            min,
-            max,
-        });
+/// Example command giving an existing ticket a field that a status requires
+fn status_field_example(field: &str, status: &str) -> String {
+    match field {
+        "due" => "ltm update due 12 friday".to_string(),
+        "priority" => "ltm ticket update 12 priority high".to_string(),
+        "estimate" => "ltm ticket update 12 estimate 2h".to_string(),
+        "tags" => "ltm ticket update 12 tags auth".to_string(),
+        "comment" if status == "blocked" => "ltm block 12 \"Waiting on the API key\"".to_string(),
+        "comment" if is_finished_status(status) => "ltm close 12 -m \"Fixed in 1.4\"".to_string(),
+        "comment" => format!("ltm update status 12 {} -m \"Why it moved\"", status),
+        _ => "ltm ticket update 12 description \"Steps to reproduce\"".to_string(),
     }
-
-    Ok(content.to_string())
 }
 
 /// Format validation error with helpful examples
 pub fn format_validation_error(error: &ValidationError) -> String {
-    match error {
-        ValidationError::InvalidTicketId(id) => {
-            format!(
-                "❌ Error: Invalid ticket ID '{}'. Must be a positive number.\n💡 Example: ltm show 1",
-                id
-            )
-        }
-        ValidationError::TicketNotFound(id) => {
-            format!("❌ Error: Ticket #{} not found.", id)
-        }
-        ValidationError::InvalidProjectName(name) => {
-            format!(
-                "❌ Error: Invalid project name '{}'. Only letters, numbers, hyphens, underscores allowed.\n💡 Example: ltm add my-project \"test\" \"description\"",
-                name
-            )
-        }
-        ValidationError::InvalidTime(time) => {
-            format!(
-                "❌ Error: Invalid time value '{}'. Hours must be 0-24, minutes must be 0-59.\n💡 Example: ltm log 1 --hours 2 --minutes 30",
-                time
-            )
-        }
+    let (message, hint) = match error {
+        ValidationError::InvalidTicketId(id) => (
+            tf("error.invalid_ticket_id", &[id]),
+            Some(tf("hint.example", &[&"ltm show 1"])),
+        ),
+        ValidationError::TicketNotFound(id) => (tf("error.ticket_not_found", &[id]), None),
+        ValidationError::InvalidProjectName(name) => match project_name_pattern() {
+            Some(pattern) => (tf("error.project_name_pattern", &[name, &pattern]), None),
+            None => (
+                tf("error.invalid_project_name", &[name]),
+                Some(tf("hint.example", &[&"ltm add my-project \"test\" \"description\""])),
+            ),
+        },
+        ValidationError::InvalidTime(time) => (
+            tf("error.invalid_time", &[time]),
+            Some(tf("hint.example", &[&"ltm log 1 --hours 2 --minutes 30"])),
+        ),
         ValidationError::InvalidStatus(status) => {
-            let valid_statuses = ["open", "in-progress", "testing", "blocked", "closed", "cancelled"];
+            let valid_statuses = valid_statuses();
             
             // Find closest match for suggestion
             let mut suggestions = valid_statuses
                 .iter()
-                .map(|&s| (s, levenshtein(status, s)))
+                .map(|s| (s, levenshtein(status, s)))
                 .collect::<Vec<_>>();
             suggestions.sort_by_key(|&(_, dist)| dist);
             
             let suggestion = if suggestions[0].1 <= 3 {
-                format!("\n💡 Did you mean: ltm status 1 {}", suggestions[0].0)
+                tf("hint.did_you_mean", &[&format!("ltm status 1 {}", suggestions[0].0)])
             } else {
-                format!("\n💡 Valid statuses: {}", valid_statuses.join(", "))
+                tf("hint.valid_statuses", &[&valid_statuses.join(", ")])
             };
 
-            format!(
-                "❌ Error: Invalid status '{}'. Must be one of: {}.{}",
-                status,
-                valid_statuses.join(", "),
-                suggestion
-            )
+            (tf("error.invalid_status", &[status, &valid_statuses.join(", ")]), Some(suggestion))
         }
         ValidationError::InvalidContentLength { field_type, min, max } => {
-            format!(
-                "❌ Error: Invalid {} length. {} must be between {} and {} characters.",
-                field_type, field_type, min, max
+            (tf("error.invalid_length", &[&field_name(field_type), min, max]), None)
+        }
+        ValidationError::InvalidPriority(priority) => (
+            tf("error.invalid_priority", &[priority, &PRIORITIES.join(", ")]),
+            Some(tf("hint.example", &[&"ltm quick \"Fix login @webapp !high\""])),
+        ),
+        ValidationError::InvalidSeverity(severity) => (
+            tf("error.invalid_severity", &[severity, &SEVERITIES.join(", ")]),
+            Some(tf("hint.example", &[&"ltm ticket create webapp \"Fix login\" \"Details\" --severity S2"])),
+        ),
+        ValidationError::InvalidScore { field, value } => {
+            let range = match field.as_str() {
+                "reach" => t("score.range_reach"),
+                "impact" => t("score.range_impact"),
+                "confidence" => t("score.range_confidence"),
+                _ => t("score.range_effort"),
+            };
+            (
+                tf("error.invalid_score", &[field, value, &range]),
+                Some(tf("hint.example", &[&format!("ltm ticket update 12 {} {}", field, score_example(field))])),
             )
         }
+        ValidationError::InvalidComponent(component) => (
+            tf("error.invalid_component", &[component]),
+            Some(tf("hint.example", &[&"ltm component add webapp frontend"])),
+        ),
+        ValidationError::UnknownComponent { component, project, allowed } if allowed.is_empty() => (
+            tf("error.unknown_component", &[project, component]),
+            Some(tf("hint.example", &[&format!("ltm component add {} {}", project, component)])),
+        ),
+        ValidationError::UnknownComponent { component, project, allowed } => (
+            tf("error.unknown_component", &[project, component]),
+            Some(tf("hint.valid_components", &[&allowed.join(", ")])),
+        ),
+        ValidationError::InvalidRelease(release) => (
+            tf("error.invalid_release", &[release]),
+            Some(tf("hint.example", &[&"ltm release create webapp v1.4"])),
+        ),
+        ValidationError::UnknownRelease { release, project, allowed } if allowed.is_empty() => (
+            tf("error.unknown_release", &[project, release]),
+            Some(tf("hint.example", &[&format!("ltm release create {} {}", project, release)])),
+        ),
+        ValidationError::UnknownRelease { release, project, allowed } => (
+            tf("error.unknown_release", &[project, release]),
+            Some(tf("hint.valid_releases", &[&allowed.join(", ")])),
+        ),
+        ValidationError::InvalidTag(tag) => (
+            tf("error.invalid_tag", &[tag]),
+            Some(tf("hint.example", &[&"ltm quick \"Fix login @webapp #auth\""])),
+        ),
+        ValidationError::InvalidDate(date) => (
+            tf("error.invalid_date", &[date]),
+            Some(tf("hint.example", &[&"ltm update due 1 friday"])),
+        ),
+        ValidationError::InvalidPeriod(period) => (
+            tf("error.invalid_period", &[period]),
+            Some(tf("hint.example", &[&"ltm remind --within 3d"])),
+        ),
+        ValidationError::InvalidMonth(month) => (
+            tf("error.invalid_month", &[month]),
+            Some(tf("hint.example", &[&"ltm calendar 2024-05"])),
+        ),
+        ValidationError::InvalidRate(rate) => (
+            tf("error.invalid_rate", &[rate]),
+            Some(tf("hint.example", &[&"ltm project set webapp rate 95"])),
+        ),
+        ValidationError::InvalidUrl(url) => (
+            tf("error.invalid_url", &[url]),
+            Some(tf("hint.example", &[&"ltm ticket link-url 12 https://github.com/acme/web/pull/7 PR"])),
+        ),
+        ValidationError::InvalidDateRange { from, to } => (
+            tf("error.invalid_date_range", &[from, to]),
+            Some(tf("hint.example", &[&"ltm time report --from 2024-03-01 --to 2024-03-31"])),
+        ),
+        ValidationError::InvalidTimeSpan { start, end } => (
+            tf("error.invalid_time_span", &[start, end]),
+            Some(tf("hint.example", &[&"ltm time update 7 --start 09:00 --end 11:30"])),
+        ),
+        ValidationError::InvalidRegex { pattern, reason } => (
+            tf("error.invalid_regex", &[pattern, reason]),
+            Some(tf("hint.example", &[&"ltm list --regex \"error (500|502)\""])),
+        ),
+        ValidationError::NoFocusedTicket => (
+            t("error.no_focused_ticket").to_string(),
+            Some(tf("hint.example", &[&"ltm focus 1"])),
+        ),
+        ValidationError::NoLastTicket => (
+            t("error.no_last_ticket").to_string(),
+            Some(tf("hint.example", &[&"ltm show 1"])),
+        ),
+        ValidationError::TransitionNotAllowed { from, to, allowed } => {
+            let hint = if allowed.is_empty() {
+                t("hint.no_next_status").to_string()
+            } else {
+                tf("hint.allowed_next_status", &[&allowed.join(", ")])
+            };
+            (tf("error.transition_not_allowed", &[from, to]), Some(hint))
+        }
+        ValidationError::StatusNotInProject { status, project, allowed } => (
+            tf("error.status_not_in_project", &[status, project]),
+            Some(tf("hint.valid_statuses", &[&allowed.join(", ")])),
+        ),
+        ValidationError::UnknownSetting(key) => (
+            tf("error.unknown_setting", &[key]),
+            Some(tf("hint.setting_keys", &[&crate::project_settings::SETTING_KEYS.join(", ")])),
+        ),
+        ValidationError::InvalidSetting { key, value } => (
+            tf("error.invalid_setting", &[value, key]),
+            Some(tf("hint.example", &[&setting_example(key)])),
+        ),
+        ValidationError::MissingRequiredField { field, project } => (
+            tf("error.missing_required_field", &[project, field]),
+            Some(tf("hint.example", &[&required_field_example(field)])),
+        ),
+        ValidationError::MissingStatusField { field, project, status } => (
+            tf("error.missing_status_field", &[project, field, status]),
+            Some(tf("hint.example", &[&status_field_example(field, status)])),
+        ),
+        ValidationError::WipLimitReached { status, project, limit } => (
+            tf("error.wip_limit_reached", &[project, limit, status]),
+            Some(t("hint.wip_limit").to_string()),
+        ),
+        ValidationError::InvalidKind { kind, allowed } => (
+            tf("error.invalid_kind", &[kind, &allowed.join(", ")]),
+            Some(tf("hint.example", &[&"ltm ticket create webapp \"Fix login\" \"Details\" --type bug"])),
+        ),
+    };
+
+    let mut formatted = with_icon("❌", tf("error.prefix", &[&message]));
+    if let Some(hint) = hint {
+        formatted.push('\n');
+        formatted.push_str(&with_icon("💡", hint));
     }
+    formatted
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_ticket_id_validation() {
-        assert!(validate_ticket_id("1").is_ok());
-        assert!(validate_ticket_id("123").is_ok());
-        assert_eq!(validate_ticket_id("1").unwrap(), 1);
-        
-        assert!(validate_ticket_id("abc").is_err());
-        assert!(validate_ticket_id("0").is_err());
-        assert!(validate_ticket_id("-1").is_err());
-        assert!(validate_ticket_id("").is_err());
-    }
-
-    #[test]
-    fn test_project_name_validation() {
-        // Valid names
-        assert!(validate_project_name("valid-project").is_ok());
-        assert!(validate_project_name("my_project").is_ok());
-        assert!(validate_project_name("project123").is_ok());
-        assert!(validate_project_name("a").is_ok());
-        
-        // Invalid names
-        assert!(validate_project_name("invalid project!").is_err());
-        assert!(validate_project_name("project with spaces").is_err());
-        assert!(validate_project_name("project@home").is_err());
-        assert!(validate_project_name("").is_err());
-        assert!(validate_project_name(&"a".repeat(51)).is_err());
-    }
-
-    #[test]
-    fn test_time_validation() {
-        // Valid times
-        assert!(validate_time(0, 0).is_ok());
-        assert!(validate_time(24, 59).is_ok());
-        assert!(validate_time(8, 30).is_ok());
-        
-        // Invalid times
-        assert!(validate_time(-1, 0).is_err());
-        assert!(validate_time(25, 0).is_err());
-        assert!(validate_time(0, -1).is_err());
-        assert!(validate_time(0, 60).is_err());
-    }
-
-    #[test]
-    fn test_status_validation() {
-        // Valid statuses
-        assert!(validate_status("open").is_ok());
-        assert!(validate_status("in-progress").is_ok());
-        assert!(validate_status("testing").is_ok());
-        assert!(validate_status("blocked").is_ok());
-        assert!(validate_status("closed").is_ok());
-        assert!(validate_status("cancelled").is_ok());
-        assert!(validate_status("wontfix").is_ok());
-        
-        // Case insensitive
-        assert!(validate_status("OPEN").is_ok());
-        assert!(validate_status("In-Progress").is_ok());
-        
-        // Invalid statuses
-        assert!(validate_status("invalid").is_err());
-        assert!(validate_status("").is_err());
-    }
-
-    #[test]
-    fn test_content_length_validation() {
-        // Valid content
-        assert!(validate_content_length("Valid name", ContentType::TicketName).is_ok());
-        assert!(validate_content_length("Valid description", ContentType::Description).is_ok());
-        assert!(validate_content_length("Valid comment", ContentType::Comment).is_ok());
-        
-        // Invalid content - too short
-        assert!(validate_content_length("", ContentType::TicketName).is_err());
-        assert!(validate_content_length("", ContentType::Description).is_err());
-        assert!(validate_content_length("", ContentType::Comment).is_err());
-        
-        // Invalid content - too long
-        assert!(validate_content_length(&"a".repeat(101), ContentType::TicketName).is_err());
-        assert!(validate_content_length(&"a".repeat(2001), ContentType::Description).is_err());
-        assert!(validate_content_length(&"a".repeat(1001), ContentType::Comment).is_err());
-    }
-
     #[test]
     fn test_error_formatting() {
         let error = ValidationError::InvalidTicketId("abc".to_string());
@@ -301,4 +271,4 @@ mod tests {
         let formatted = format_validation_error(&error);
         assert!(formatted.contains("💡 Valid statuses"));
     }
-}
\ No newline at end of file
+}
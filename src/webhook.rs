@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use chrono::Duration;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::i18n::fill_named;
+use crate::validation::validate_period;
+
+/// Events a webhook can be sent for: a ticket moving to a finished status, a ticket moving
+/// to `blocked`, and a timer running longer than `timer_after`
+pub const EVENTS: &[&str] = &["closed", "blocked", "timer"];
+
+/// A Slack or Discord incoming webhook from config.toml, notified when things happen:
+///
+/// ```toml
+/// [webhooks.team]
+/// url = "https://hooks.slack.com/services/..."
+/// events = ["closed", "blocked"]    # all of them when left out
+/// timer_after = "4h"                # defaults to timer_warning
+///
+/// [webhooks.team.templates]         # replace the default message for an event
+/// closed = ":tada: {user} closed #{ticket} {name}"
+/// ```
+///
+/// Templates can use `{ticket}`, `{name}`, `{project}`, `{status}`, `{previous}`, and
+/// `{user}`; `blocked` adds `{reason}` and `timer` adds `{elapsed}`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Webhook {
+    pub url: String,
+    pub events: Vec<String>,
+    pub templates: BTreeMap<String, String>,
+    pub timer_after: Option<String>,
+}
+
+impl Webhook {
+    /// Fail on a missing URL, an unknown event, or a bad `timer_after`
+    pub fn check(&self, name: &str) -> Result<()> {
+        if self.url.trim().is_empty() {
+            anyhow::bail!("webhooks.{} in config.toml has no url", name);
+        }
+        for event in self.events.iter().chain(self.templates.keys()) {
+            if !EVENTS.contains(&event.as_str()) {
+                anyhow::bail!("Unknown event '{}' in webhooks.{} in config.toml. Events: {}", event, name, EVENTS.join(", "));
+            }
+        }
+        self.timer_after().with_context(|| format!("Invalid timer_after in webhooks.{} in config.toml", name))?;
+        Ok(())
+    }
+
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event)
+    }
+
+    /// How long a timer runs before a `timer` event, when set
+    pub fn timer_after(&self) -> Result<Option<Duration>> {
+        Ok(self.timer_after.as_deref().map(validate_period).transpose()?)
+    }
+
+    /// The message for `event`: its template with each `{field}` filled in
+    pub fn message(&self, event: &str, fields: &[(&str, &str)]) -> String {
+        let template = self.templates.get(event).map(String::as_str).unwrap_or_else(|| default_template(event));
+        fill_named(template, fields)
+    }
+
+    /// Post the message for `event`. Errors leave out the URL, which holds the webhook's secret.
+    pub async fn post(&self, event: &str, fields: &[(&str, &str)]) -> Result<()> {
+        let http = reqwest::Client::builder().timeout(std::time::Duration::from_secs(10)).build()?;
+        let body = self.payload(&self.message(event, fields));
+        let response = http.post(&self.url).json(&body).send().await.map_err(|e| e.without_url())?;
+        response.error_for_status().map_err(|e| e.without_url())?;
+        Ok(())
+    }
+
+    /// The JSON body to post: Discord reads `content`, Slack and most others `text`
+    pub fn payload(&self, message: &str) -> Value {
+        if self.url.contains("discord.com/") || self.url.contains("discordapp.com/") {
+            json!({ "content": message })
+        } else {
+            json!({ "text": message })
+        }
+    }
+}
+
+fn default_template(event: &str) -> &'static str {
+    match event {
+        "closed" => "#{ticket} {name} ({project}) was moved to {status} by {user}",
+        "blocked" => "#{ticket} {name} ({project}) is blocked: {reason}",
+        _ => "The timer on #{ticket} {name} ({project}) has been running for {elapsed}",
+    }
+}
+
+/// Post `event` to every webhook that wants it. A webhook that can't be reached doesn't
+/// fail the command; the failures are returned as messages to show instead.
+pub async fn notify(webhooks: &BTreeMap<String, Webhook>, event: &str, fields: &[(&str, &str)]) -> Vec<String> {
+    let mut failures = Vec::new();
+    for (name, webhook) in webhooks.iter().filter(|(_, webhook)| webhook.wants(event)) {
+        if let Err(e) = webhook.post(event, fields).await {
+            tracing::debug!(webhook = %name, error = %e, "webhook failed");
+            failures.push(format!("Webhook '{}' failed: {}", name, e));
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook() {
+        let fields = [("ticket", "12"), ("name", "Fix login"), ("project", "web"), ("status", "closed"), ("user", "Ada")];
+        let slack = Webhook { url: "https://hooks.slack.com/services/T0/B0/x".to_string(), ..Default::default() };
+        assert_eq!(slack.message("closed", &fields), "#12 Fix login (web) was moved to closed by Ada");
+        // Placeholders inside a value are the value's own text
+        let braces = [("ticket", "13"), ("name", "Use {project} var"), ("project", "web"), ("status", "closed"), ("user", "{name}")];
+        assert_eq!(slack.message("closed", &braces), "#13 Use {project} var (web) was moved to closed by {name}");
+        assert_eq!(slack.payload("hi"), json!({ "text": "hi" }));
+        assert!(slack.wants("timer") && slack.check("team").is_ok());
+
+        let discord = Webhook {
+            url: "https://discord.com/api/webhooks/1/x".to_string(),
+            events: vec!["closed".to_string()],
+            templates: BTreeMap::from([("closed".to_string(), ":tada: {user} closed #{ticket} {unknown}".to_string())]),
+            timer_after: None,
+        };
+        assert_eq!(discord.message("closed", &fields), ":tada: Ada closed #12 {unknown}");
+        assert_eq!(discord.payload("hi"), json!({ "content": "hi" }));
+        assert!(discord.wants("closed") && !discord.wants("blocked"));
+
+        let bad = |webhook: Webhook| webhook.check("team").unwrap_err().to_string();
+        assert!(bad(Webhook { events: vec!["opened".to_string()], ..slack.clone() }).contains("Unknown event 'opened'"));
+        assert!(bad(Webhook { timer_after: Some("soon".to_string()), ..slack.clone() }).contains("timer_after"));
+        assert!(bad(Webhook::default()).contains("no url"));
+    }
+}
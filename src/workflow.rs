@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::validation::ValidationError;
+
+/// Allowed status transitions for a project, keyed by the current status.
+///
+/// Configured per project in `~/.ltm/config.toml`:
+///
+/// ```toml
+/// [workflows.webapp]
+/// open = ["in-progress", "cancelled"]
+/// in-progress = ["testing", "blocked"]
+/// testing = ["closed", "in-progress"]
+/// blocked = ["in-progress"]
+/// ```
+///
+/// Statuses without an entry (including ones outside the workflow) may move anywhere.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Workflow {
+    transitions: BTreeMap<String, Vec<String>>,
+}
+
+impl Workflow {
+    /// Build a workflow from `(status, next statuses)` pairs
+    pub fn new<'a>(transitions: impl IntoIterator<Item = (&'a str, &'a [&'a str])>) -> Self {
+        Self {
+            transitions: transitions
+                .into_iter()
+                .map(|(from, to)| (from.to_lowercase(), to.iter().map(|s| s.to_lowercase()).collect()))
+                .collect(),
+        }
+    }
+
+    /// Statuses reachable from `from`, or `None` when the workflow does not restrict it
+    pub fn allowed_next(&self, from: &str) -> Option<&[String]> {
+        self.transitions.get(&from.to_lowercase()).map(Vec::as_slice)
+    }
+
+    /// Check a transition, returning the allowed next statuses when it is refused
+    pub fn check(&self, from: &str, to: &str) -> Result<(), ValidationError> {
+        let Some(allowed) = self.allowed_next(from) else {
+            return Ok(());
+        };
+        if from.eq_ignore_ascii_case(to) || allowed.iter().any(|s| s.eq_ignore_ascii_case(to)) {
+            return Ok(());
+        }
+        Err(ValidationError::TransitionNotAllowed {
+            from: from.to_lowercase(),
+            to: to.to_lowercase(),
+            allowed: allowed.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workflow_transitions() {
+        let workflow = Workflow::new([
+            ("open", &["in-progress"][..]),
+            ("in-progress", &["testing", "blocked"][..]),
+            ("closed", &[][..]),
+        ]);
+
+        assert!(workflow.check("open", "in-progress").is_ok());
+        assert!(workflow.check("Open", "IN-PROGRESS").is_ok());
+        assert!(workflow.check("open", "open").is_ok());
+        // Statuses without an entry are unrestricted
+        assert!(workflow.check("testing", "open").is_ok());
+
+        match workflow.check("open", "closed") {
+            Err(ValidationError::TransitionNotAllowed { from, to, allowed }) => {
+                assert_eq!((from.as_str(), to.as_str()), ("open", "closed"));
+                assert_eq!(allowed, vec!["in-progress".to_string()]);
+            }
+            other => panic!("expected a refused transition, got {:?}", other),
+        }
+        assert!(workflow.check("closed", "open").is_err());
+    }
+}
@@ -13,7 +13,7 @@ async fn create_test_database() -> Result<Database> {
     
     let pool = SqlitePool::connect_with(options).await?;
     
-    sqlx::migrate!("./migrations")
+    sqlx::migrate!("./ltm-core/migrations")
         .run(&pool)
         .await
         .context("Failed to run migrations")?;
@@ -123,6 +123,38 @@ async fn test_time_logging() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_time_log_respan() -> Result<()> {
+    use chrono::Duration;
+
+    let database = create_test_database().await?;
+    let ticket_id = database.add_ticket("web", "Timed", "desc").await?;
+    let start = Utc::now() - Duration::hours(3);
+    database.add_time_log(ticket_id, 3, 0, Some(start), Some(start + Duration::hours(3))).await?;
+    database.add_time_log(ticket_id, 0, 45, None, None).await?;
+    let logs = database.get_time_logs(ticket_id).await?;
+    let (manual, timed) = (&logs[0], &logs[1]);
+    let (start, end) = (timed.started_at.unwrap(), timed.ended_at.unwrap());
+
+    // Moving the end of a timed log keeps the start and recomputes the length
+    let earlier = end - Duration::minutes(50);
+    assert_eq!(timed.respan(None, Some(earlier), None), (Some(start), Some(earlier), Duration::minutes(130)));
+    assert_eq!(timed.respan(Some(start), None, Some(Duration::hours(1))), (Some(start), Some(start + Duration::hours(1)), Duration::hours(1)));
+    // A new length alone moves the end
+    assert_eq!(timed.respan(None, None, Some(Duration::hours(2))).1, Some(start + Duration::hours(2)));
+    // Logs without a span keep their length when given one end
+    assert_eq!(manual.respan(None, Some(end), None), (Some(end - Duration::minutes(45)), Some(end), Duration::minutes(45)));
+    assert_eq!(manual.respan(None, None, Some(Duration::hours(1))), (None, None, Duration::hours(1)));
+
+    database.update_time_log_span(timed.id, 2, 10, Some(start), Some(earlier)).await?;
+    let updated = database.get_time_log(timed.id).await?.context("time log")?;
+    assert_eq!((updated.hours, updated.minutes, updated.ended_at), (2, 10, Some(earlier)));
+    assert!(updated.updated_at > timed.updated_at);
+    assert!(database.get_time_log(999).await?.is_none());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_project_summary() -> Result<()> {
     let database = create_test_database().await?;
@@ -253,4 +285,1233 @@ async fn test_concurrent_operations() -> Result<()> {
     assert_eq!(all_tickets.len(), 10);
     
     Ok(())
-} 
\ No newline at end of file
+} 
+
+#[tokio::test]
+async fn test_due_dates() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let soon = database.add_ticket("test_project", "due soon", "").await?;
+    let later = database.add_ticket("test_project", "due later", "").await?;
+    let done = database.add_ticket("test_project", "already done", "").await?;
+    database.add_ticket("test_project", "no due date", "").await?;
+
+    let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+    database.update_ticket_due_date(soon, Some(today)).await?;
+    database.update_ticket_due_date(later, Some(today + chrono::Duration::days(10))).await?;
+    database.update_ticket_due_date(done, Some(today)).await?;
+    database.update_ticket_status(done, "closed").await?;
+
+    let due = database.list_tickets_due_by(today + chrono::Duration::days(2)).await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].id, soon);
+    assert_eq!(due[0].due_date, Some(today));
+
+    database.update_ticket_due_date(soon, None).await?;
+    assert!(database.get_ticket(soon).await?.unwrap().due_date.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_persistent_timers() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let ticket_id = database.add_ticket("test_project", "timed ticket", "").await?;
+    let start = Utc::now().naive_utc() - chrono::Duration::hours(2);
+    database.start_timer(ticket_id, start).await?;
+
+    // Pause after 30 minutes, resume an hour later
+    database.pause_timer(ticket_id, start + chrono::Duration::minutes(30)).await?;
+    let paused = database.get_timer(ticket_id).await?.unwrap();
+    assert!(paused.is_paused());
+    assert_eq!(paused.elapsed(start + chrono::Duration::hours(5)).num_minutes(), 30);
+
+    database.resume_timer(ticket_id, start + chrono::Duration::minutes(90)).await?;
+    let resumed = database.get_timer(ticket_id).await?.unwrap();
+    assert!(!resumed.is_paused());
+    assert_eq!(resumed.elapsed(start + chrono::Duration::minutes(120)).num_minutes(), 60);
+
+    assert_eq!(database.list_timers().await?.len(), 1);
+    assert!(database.delete_timer(ticket_id).await?);
+    assert!(database.get_timer(ticket_id).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_daily_hours() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let web = database.add_ticket("web", "web ticket", "").await?;
+    let api = database.add_ticket("api", "api ticket", "").await?;
+
+    let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+    let start = day.and_hms_opt(9, 0, 0).unwrap().and_utc();
+    database.add_time_log(web, 1, 30, Some(start), None).await?;
+    database.add_time_log(web, 2, 0, Some(start + chrono::Duration::days(1)), None).await?;
+    database.add_time_log(api, 0, 45, Some(start), None).await?;
+
+    let all = database.daily_hours(day, day + chrono::Duration::days(6), None).await?;
+    assert_eq!(all, vec![(day, 2.25), (day + chrono::Duration::days(1), 2.0)]);
+
+    let web_only = database.daily_hours(day, day, Some("web")).await?;
+    assert_eq!(web_only, vec![(day, 1.5)]);
+
+    // Backdated logs count towards the day they were logged for
+    let earlier = day - chrono::Duration::days(2);
+    database.add_time_log_at(api, 3, 0, earlier.and_hms_opt(12, 0, 0).unwrap()).await?;
+    assert_eq!(database.daily_hours(earlier, earlier, None).await?, vec![(earlier, 3.0)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backlog_ranking() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let first = database.add_ticket("web", "first", "").await?;
+    let second = database.add_ticket("web", "second", "").await?;
+    let third = database.add_ticket("web", "third", "").await?;
+    let other = database.add_ticket("api", "other project", "").await?;
+    assert_eq!(database.get_ticket(third).await?.unwrap().rank, 3);
+    assert_eq!(database.get_ticket(other).await?.unwrap().rank, 1);
+
+    let order = |tickets: Vec<lticket::models::Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<_>>();
+
+    assert_eq!(database.move_ticket_to_position(third, 1).await?, 1);
+    assert_eq!(order(database.list_tickets_filtered(Some("web"), None, "rank", false).await?), vec![third, first, second]);
+
+    // Positions past the end are clamped
+    assert_eq!(database.move_ticket_to_position(third, 10).await?, 3);
+    assert_eq!(order(database.list_tickets_filtered(Some("web"), None, "rank", false).await?), vec![first, second, third]);
+
+    // Moving to another project puts the ticket at the bottom of its backlog
+    database.move_ticket_project(first, "api").await?;
+    assert_eq!(database.get_ticket(first).await?.unwrap().rank, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_status_history() -> Result<()> {
+    use chrono::Duration;
+    use lticket::models::time_in_status;
+
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let id = database.add_ticket("history", "Ticket", "").await?;
+    database.update_ticket_status(id, "in-progress").await?;
+    database.update_ticket_status(id, "in-progress").await?;
+    database.update_ticket_status(id, "closed").await?;
+    let other = database.add_ticket("history", "Other", "").await?;
+
+    let changes = database.get_status_changes(id).await?;
+    let steps: Vec<(Option<&str>, &str)> =
+        changes.iter().map(|c| (c.from_status.as_deref(), c.to_status.as_str())).collect();
+    assert_eq!(steps, vec![(None, "open"), (Some("open"), "in-progress"), (Some("in-progress"), "closed")]);
+
+    let project_changes = database.get_project_status_changes("history").await?;
+    assert_eq!(project_changes.len(), 4);
+    assert_eq!(project_changes.last().unwrap().ticket_id, other);
+
+    // The open ticket's clock keeps running; the closed ticket's has stopped
+    let now = Utc::now().naive_utc() + Duration::hours(2);
+    let rows = time_in_status(&project_changes, now);
+    let open = rows.iter().find(|r| r.status == "open").unwrap();
+    assert_eq!(open.visits, 2);
+    assert!(open.total >= Duration::minutes(119));
+    let closed = rows.iter().find(|r| r.status == "closed").unwrap();
+    assert_eq!((closed.visits, closed.total), (1, Duration::zero()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ticket_aging() -> Result<()> {
+    use chrono::Duration;
+    use lticket::models::ticket_aging;
+
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let fresh = database.add_ticket("web", "Fresh", "").await?;
+    let stale = database.add_ticket("web", "Stale", "").await?;
+    let ancient = database.add_ticket("web", "Ancient", "").await?;
+    let done = database.add_ticket("web", "Done", "").await?;
+    database.update_ticket_status(done, "closed").await?;
+    database.add_ticket("api", "Endpoint", "").await?;
+
+    // Age the tickets by moving their creation back
+    let now = Utc::now().naive_utc();
+    let mut tickets = database.list_tickets(None).await?;
+    for ticket in &mut tickets {
+        let days = [(stale, 45), (ancient, 200), (done, 300)].iter().find(|(id, _)| *id == ticket.id).map_or(1, |(_, d)| *d);
+        ticket.created_at = now - Duration::days(days);
+    }
+
+    let aging = ticket_aging(&tickets, now, 2);
+    assert_eq!(aging.iter().map(|p| (p.project.as_str(), p.buckets)).collect::<Vec<_>>(), vec![("api", [1, 0, 0, 0]), ("web", [1, 0, 1, 1])]);
+    let oldest: Vec<(i64, i64)> = aging[1].oldest.iter().map(|t| (t.id, t.age_days)).collect();
+    assert_eq!(oldest, vec![(ancient, 200), (stale, 45)]);
+    assert_eq!(aging[1].total(), 3);
+    assert!(!aging[1].oldest.iter().any(|t| t.id == fresh));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_comments() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let web = database.add_ticket("web", "Crash on save", "desc").await?;
+    let api = database.add_ticket("api", "Slow endpoint", "desc").await?;
+    database.add_comment(api, "Profiled the query").await?;
+    database.add_comment(web, "Stack trace attached").await?;
+    database.add_comment(web, "Fixed in the editor").await?;
+
+    let all: Vec<i64> = database.list_comments(None).await?.iter().map(|c| c.ticket_id).collect();
+    assert_eq!(all, vec![web, web, api]);
+
+    let web_comments = database.list_comments(Some("web")).await?;
+    assert_eq!(web_comments.len(), 2);
+    assert_eq!(web_comments[0].content, "Stack trace attached");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ticket_urls() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let id = database.add_ticket("web", "Crash on save", "desc").await?;
+    database.add_ticket_url(id, "https://github.com/acme/web/pull/7", Some("PR")).await?;
+    database.add_ticket_url(id, "https://docs.example.com/save", None).await?;
+
+    let urls = database.get_ticket_urls(id).await?;
+    assert_eq!(urls.len(), 2);
+    assert_eq!(urls[0].label.as_deref(), Some("PR"));
+    assert_eq!(urls[1].url, "https://docs.example.com/save");
+
+    // Links go with the ticket
+    database.delete_ticket(id).await?;
+    assert!(database.get_ticket_urls(id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_checklists() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let id = database.add_ticket("web", "Release 2.0", "desc").await?;
+    let other = database.add_ticket("web", "Other", "desc").await?;
+    assert_eq!(database.add_checklist_item(id, "Tag the release").await?, 1);
+    assert_eq!(database.add_checklist_item(id, "Publish notes").await?, 2);
+    assert_eq!(database.add_checklist_item(other, "Only step").await?, 1);
+
+    assert_eq!(database.toggle_checklist_item(id, 2).await?, Some(true));
+    assert_eq!(database.toggle_checklist_item(id, 3).await?, None);
+
+    let items = database.get_checklist(id).await?;
+    let states: Vec<(i64, bool)> = items.iter().map(|i| (i.position, i.done)).collect();
+    assert_eq!(states, vec![(1, false), (2, true)]);
+
+    let progress = database.checklist_progress().await?;
+    assert_eq!(progress[&id], (1, 2));
+    assert_eq!(progress[&other], (0, 1));
+
+    assert_eq!(database.toggle_checklist_item(id, 2).await?, Some(false));
+    assert_eq!(database.checklist_progress().await?[&id], (0, 2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ticket_metadata() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let id = database.add_ticket("webapp", "Fix login redirect", "").await?;
+    database.update_ticket_priority(id, Some("high")).await?;
+    database.update_ticket_estimate(id, Some(120)).await?;
+    database.add_ticket_tags(id, &["auth".to_string(), "ui".to_string()]).await?;
+    database.add_ticket_tags(id, &["auth".to_string()]).await?;
+
+    let ticket = database.get_ticket(id).await?.unwrap();
+    assert_eq!(ticket.priority.as_deref(), Some("high"));
+    assert_eq!(ticket.estimate_minutes, Some(120));
+    assert_eq!(database.get_ticket_tags(id).await?, vec!["auth".to_string(), "ui".to_string()]);
+
+    database.delete_ticket(id).await?;
+    assert!(database.get_ticket_tags(id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_open_counts() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+    let due_today = database.add_ticket("web", "Due today", "desc").await?;
+    let overdue = database.add_ticket("web", "Overdue", "desc").await?;
+    let closed = database.add_ticket("web", "Closed and overdue", "desc").await?;
+    database.add_ticket("ops", "No due date", "desc").await?;
+    database.update_ticket_due_date(due_today, Some(today)).await?;
+    database.update_ticket_due_date(overdue, today.pred_opt()).await?;
+    database.update_ticket_due_date(closed, today.pred_opt()).await?;
+    database.update_ticket_status(closed, "closed").await?;
+
+    let counts = database.open_counts(today).await?;
+    assert_eq!((counts.open, counts.due_today, counts.overdue), (3, 1, 1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_comment_replies() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let id = database.add_ticket("web", "Flaky login", "desc").await?;
+    database.add_comment(id, "Seeing this on staging").await?;
+    let parent = database.get_comments(id).await?.remove(0);
+    assert_eq!(parent.parent_comment_id, None);
+
+    let reply_id = database.add_comment_reply(&parent, "Same here").await?;
+    let reply = database.get_comment(reply_id).await?.unwrap();
+    assert_eq!((reply.ticket_id, reply.parent_comment_id), (id, Some(parent.id)));
+
+    // Deleting a comment removes its replies
+    database.delete_comment(parent.id).await?;
+    assert!(database.get_comments(id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_timer_notes() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let ticket_id = database.add_ticket("web", "Checkout flow", "desc").await?;
+    let start = Utc::now().naive_utc() - chrono::Duration::minutes(40);
+    database.start_timer_with_note(ticket_id, start, Some("pairing with Sam")).await?;
+
+    // Pausing and resuming keeps the note
+    database.pause_timer(ticket_id, start + chrono::Duration::minutes(10)).await?;
+    database.resume_timer(ticket_id, start + chrono::Duration::minutes(20)).await?;
+    let timer = database.get_timer(ticket_id).await?.unwrap();
+    assert_eq!(timer.note.as_deref(), Some("pairing with Sam"));
+
+    let end = Utc::now();
+    database
+        .add_time_log_with_note(ticket_id, 0, 30, Some(start.and_utc()), Some(end), timer.note.as_deref())
+        .await?;
+    database.add_time_log(ticket_id, 1, 0, None, None).await?;
+
+    let notes: Vec<Option<String>> = database.get_time_logs(ticket_id).await?.into_iter().map(|l| l.note).collect();
+    assert!(notes.contains(&Some("pairing with Sam".to_string())) && notes.contains(&None));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hours_by_ticket() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let login = database.add_ticket("web", "Login", "desc").await?;
+    let signup = database.add_ticket("web", "Signup", "desc").await?;
+    let api = database.add_ticket("api", "Rate limit", "desc").await?;
+
+    let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+    let at = |offset: i64| (day + chrono::Duration::days(offset)).and_hms_opt(12, 0, 0).unwrap();
+    database.add_time_log_at(login, 1, 30, at(0)).await?;
+    database.add_time_log_at(login, 0, 45, at(3)).await?;
+    database.add_time_log_at(signup, 3, 0, at(1)).await?;
+    database.add_time_log_at(api, 2, 0, at(1)).await?;
+    // Outside the range
+    database.add_time_log_at(login, 5, 0, at(10)).await?;
+
+    let web = database.hours_by_ticket(day, day + chrono::Duration::days(6), Some("web")).await?;
+    let summary: Vec<(i64, i64, i64)> = web.iter().map(|r| (r.ticket_id, r.entries, r.minutes)).collect();
+    assert_eq!(summary, vec![(signup, 1, 180), (login, 2, 135)]);
+    assert_eq!(web[1].hours, 2.25);
+
+    let all = database.hours_by_ticket(day, day, None).await?;
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].ticket_id, login);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_billable_hours() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let login = database.add_ticket("web", "Login", "desc").await?;
+    let audit = database.add_ticket("web", "Audit", "desc").await?;
+    let docs = database.add_ticket("docs", "Guide", "desc").await?;
+    database.set_project_setting("web", "rate", "80").await?;
+    database.update_ticket_rate(audit, Some(120.0)).await?;
+
+    let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+    let at = day.and_hms_opt(12, 0, 0).unwrap();
+    database.add_time_log_at(login, 1, 30, at).await?;
+    let meeting = database.add_time_log_at(login, 1, 0, at).await?;
+    database.update_time_log_billable(meeting, false).await?;
+    database.add_time_log_at(audit, 0, 30, at).await?;
+    database.add_time_log_at(docs, 2, 0, at).await?;
+    assert!(!database.get_time_log(meeting).await?.unwrap().billable);
+
+    let rows = database.hours_by_ticket(day, day, None).await?;
+    let billing: Vec<_> = rows.iter().map(|r| (r.ticket_id, r.minutes, r.billable_minutes, r.rate, r.amount)).collect();
+    assert_eq!(
+        billing,
+        vec![(docs, 120, 120, None, None), (login, 150, 90, Some(80.0), Some(120.0)), (audit, 30, 30, Some(120.0), Some(60.0))]
+    );
+    assert_eq!(rows[0].clone().with_default_rate(Some(50.0)).amount, Some(100.0));
+    assert_eq!(database.get_ticket(audit).await?.unwrap().rate, Some(120.0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_billing_locks() -> Result<()> {
+    let database = create_test_database().await?.with_user(Some("ada".to_string()));
+    database.init_db().await?;
+
+    let may = chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+    assert!(database.lock_billing_month(may).await?);
+    assert!(!database.lock_billing_month(may).await?);
+    assert_eq!(database.locked_month(chrono::NaiveDate::from_ymd_opt(2024, 5, 31).unwrap()).await?.as_deref(), Some("2024-05"));
+    assert_eq!(database.locked_month(chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()).await?, None);
+
+    let locks = database.billing_locks().await?;
+    assert_eq!((locks[0].month.as_str(), locks[0].locked_by.as_deref()), ("2024-05", Some("ada")));
+    assert!(database.unlock_billing_month(may).await?);
+    assert!(!database.unlock_billing_month(may).await?);
+    assert!(database.billing_locks().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pinned_tickets() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let first = database.add_ticket("web", "First", "desc").await?;
+    let second = database.add_ticket("web", "Second", "desc").await?;
+    let third = database.add_ticket("web", "Third", "desc").await?;
+    assert!(database.pin_ticket(third).await?);
+    assert!(!database.pin_ticket(third).await?);
+    assert!(database.pin_ticket(first).await?);
+
+    // Pinned tickets come first, each group in the order asked for
+    let ids = |tickets: Vec<lticket::models::Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<_>>();
+    assert_eq!(ids(database.list_tickets_filtered(Some("web"), None, "rank", false).await?), [first, third, second]);
+    assert!(database.get_ticket(first).await?.unwrap().pinned_at.is_some());
+
+    assert!(database.unpin_ticket(first).await?);
+    assert!(!database.unpin_ticket(first).await?);
+    assert_eq!(ids(database.list_tickets_filtered(Some("web"), None, "rank", false).await?), [third, first, second]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_starred_tickets() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+    let ada = database.clone().with_user(Some("ada".to_string()));
+    let bob = database.with_user(Some("bob".to_string()));
+
+    let web = ada.add_ticket("web", "Fix login", "desc").await?;
+    let api = ada.add_ticket("api", "Rate limits", "desc").await?;
+    assert!(ada.star_ticket(api).await?);
+    assert!(!ada.star_ticket(api).await?);
+    assert!(ada.star_ticket(web).await?);
+    assert!(bob.star_ticket(web).await?);
+
+    // Each user has their own stars, in the order they were starred
+    let ids = |tickets: Vec<lticket::models::Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<_>>();
+    assert_eq!(ids(ada.starred_tickets().await?), [api, web]);
+    assert_eq!(ids(bob.starred_tickets().await?), [web]);
+
+    assert!(ada.unstar_ticket(api).await?);
+    assert!(!ada.unstar_ticket(api).await?);
+    ada.delete_ticket(web).await?;
+    assert!(ada.starred_tickets().await?.is_empty());
+    assert!(bob.starred_tickets().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recent_tickets() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+    let ada = database.clone().with_user(Some("ada".to_string())).with_command("ltm show 1");
+    let bob = database.with_user(Some("bob".to_string()));
+
+    let login = ada.add_ticket("web", "Fix login", "desc").await?;
+    let limits = ada.add_ticket("api", "Rate limits", "desc").await?;
+    ada.record_recent(&[login]).await?;
+    bob.record_recent(&[limits]).await?;
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    ada.record_recent(&[limits]).await?;
+
+    let recent = ada.recent_tickets(10).await?;
+    assert_eq!(recent.iter().map(|r| r.ticket_id).collect::<Vec<_>>(), [limits, login]);
+    assert_eq!((recent[1].name.as_str(), recent[1].command.as_deref()), ("Fix login", Some("ltm show 1")));
+    assert_eq!(ada.recent_tickets(1).await?.len(), 1);
+
+    // Using a ticket again moves it up instead of adding a row; deleted tickets drop out
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    ada.record_recent(&[login, 999]).await?;
+    assert_eq!(ada.recent_tickets(10).await?.iter().map(|r| r.ticket_id).collect::<Vec<_>>(), [login, limits]);
+    ada.delete_ticket(limits).await?;
+    assert_eq!(ada.recent_tickets(10).await?.len(), 1);
+    assert!(bob.recent_tickets(10).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_daemon_prompt_state() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+    let today = chrono::Local::now().date_naive();
+    let login = database.add_ticket("web", "Fix login", "desc").await?;
+    database.add_ticket("web", "Docs", "desc").await?;
+
+    let state = lticket::daemon::PromptState::read(&database, today).await?;
+    assert!(state.timer.is_none());
+    assert_eq!(state.counts.open, 2);
+
+    database.start_timer(login, chrono::Utc::now().naive_utc()).await?;
+    let state = lticket::daemon::PromptState::read(&database, today).await?;
+    // The daemon sends the state as JSON, and the client renders it as if it had read it
+    let sent: lticket::daemon::PromptState = serde_json::from_str(&serde_json::to_string(&state)?)?;
+    let (timer, name) = sent.timer.as_ref().unwrap();
+    assert_eq!((timer.ticket_id, name.as_str()), (login, "Fix login"));
+    assert_eq!(sent.counts, state.counts);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ticket_locks() -> Result<()> {
+    let database = create_test_database().await?.with_user(Some("ada".to_string()));
+    database.init_db().await?;
+
+    let postmortem = database.add_ticket("ops", "Outage postmortem", "desc").await?;
+    let other = database.add_ticket("ops", "Follow-up", "desc").await?;
+    assert!(database.lock_ticket(postmortem).await?);
+    assert!(!database.lock_ticket(postmortem).await?);
+    let lock = database.ticket_lock(postmortem).await?.unwrap();
+    assert_eq!((lock.ticket_id, lock.locked_by.as_deref()), (postmortem, Some("ada")));
+    assert_eq!(database.ticket_lock(other).await?, None);
+    assert_eq!(database.locked_tickets(&[postmortem, other]).await?, [postmortem]);
+
+    assert!(database.unlock_ticket(postmortem).await?);
+    assert!(!database.unlock_ticket(postmortem).await?);
+    assert!(database.locked_tickets(&[postmortem, other]).await?.is_empty());
+
+    // Deleting a ticket drops its lock
+    database.lock_ticket(other).await?;
+    database.delete_ticket(other).await?;
+    assert_eq!(database.ticket_lock(other).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ticket_kinds() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let login = database.add_ticket("web", "Fix login", "desc").await?;
+    let dark = database.add_ticket("web", "Add dark mode", "desc").await?;
+    let typo = database.add_ticket("web", "Fix typo", "desc").await?;
+    database.add_ticket("web", "Untyped", "desc").await?;
+    database.update_ticket_kind(login, Some("bug")).await?;
+    database.update_ticket_kind(dark, Some("feature")).await?;
+    database.update_ticket_kind(typo, Some("bug")).await?;
+    assert_eq!(database.get_ticket(login).await?.unwrap().kind.as_deref(), Some("bug"));
+
+    let summary = database.get_project_summary("web").await?;
+    assert_eq!(summary.kinds.into_iter().collect::<Vec<_>>(), [("bug".to_string(), 2), ("feature".to_string(), 1)]);
+
+    database.update_ticket_kind(dark, None).await?;
+    assert_eq!(database.get_ticket(dark).await?.unwrap().kind, None);
+    assert!(database.get_project_summary("empty").await?.kinds.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_components() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    assert!(database.add_component("web", "frontend").await?);
+    assert!(database.add_component("web", "api").await?);
+    assert!(!database.add_component("web", "api").await?);
+    assert!(database.add_component("mobile", "api").await?);
+    let names: Vec<String> = database.components("web").await?.into_iter().map(|c| c.name).collect();
+    assert_eq!(names, ["api", "frontend"]);
+
+    let login = database.add_ticket("web", "Fix login", "desc").await?;
+    let button = database.add_ticket("web", "Fix button", "desc").await?;
+    database.update_ticket_component(login, Some("api")).await?;
+    database.update_ticket_component(button, Some("frontend")).await?;
+    assert_eq!(database.get_ticket(login).await?.unwrap().component.as_deref(), Some("api"));
+    let summary = database.get_project_summary("web").await?;
+    assert_eq!(summary.components.into_iter().collect::<Vec<_>>(), [("api".to_string(), 1), ("frontend".to_string(), 1)]);
+
+    assert!(database.remove_component("web", "api").await?);
+    assert!(!database.remove_component("web", "api").await?);
+    assert_eq!(database.components("web").await?.len(), 1);
+    assert_eq!(database.components("mobile").await?.len(), 1);
+
+    // Components go with their project, and a moved ticket keeps only one its new project has
+    database.rename_project("web", "site").await?;
+    assert!(database.components("web").await?.is_empty());
+    assert_eq!(database.components("site").await?.len(), 1);
+    assert_eq!(database.get_ticket(button).await?.unwrap().component.as_deref(), Some("frontend"));
+    database.update_ticket_component(login, Some("frontend")).await?;
+    database.move_ticket_project(button, "mobile").await?;
+    assert_eq!(database.get_ticket(button).await?.unwrap().component, None);
+    database.add_component("mobile", "frontend").await?;
+    database.move_ticket_project(login, "mobile").await?;
+    assert_eq!(database.get_ticket(login).await?.unwrap().component.as_deref(), Some("frontend"));
+
+    database.forget_project("mobile").await?;
+    assert!(database.components("mobile").await?.is_empty());
+    assert_eq!(database.components("site").await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_releases() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    assert!(database.create_release("web", "v1.4", None).await?);
+    assert!(!database.create_release("web", "v1.4", None).await?);
+    assert!(database.create_release("web", "v1.5", None).await?);
+    let names: Vec<String> = database.releases(Some("web")).await?.into_iter().map(|r| r.name).collect();
+    assert_eq!(names, ["v1.4", "v1.5"]);
+    assert!(database.releases(Some("mobile")).await?.is_empty());
+
+    let login = database.add_ticket("web", "Fix login", "desc").await?;
+    let button = database.add_ticket("web", "Fix button", "desc").await?;
+    database.add_ticket("web", "Later", "desc").await?;
+    database.update_ticket_release(login, Some("v1.4")).await?;
+    database.update_ticket_release(button, Some("v1.4")).await?;
+    let planned: Vec<i64> = database.release_tickets("web", "v1.4").await?.iter().map(|t| t.id).collect();
+    assert_eq!(planned, [login, button]);
+
+    database.update_ticket_release(button, None).await?;
+    assert_eq!(database.release_tickets("web", "v1.4").await?.len(), 1);
+    assert!(database.release_tickets("mobile", "v1.4").await?.is_empty());
+
+    // Releases go with their project, and a moved ticket keeps only one its new project has
+    database.rename_project("web", "site").await?;
+    assert!(database.releases(Some("web")).await?.is_empty());
+    assert_eq!(database.release_tickets("site", "v1.4").await?.len(), 1);
+    database.move_ticket_project(login, "mobile").await?;
+    assert_eq!(database.get_ticket(login).await?.unwrap().target_release, None);
+    database.update_ticket_release(button, Some("v1.5")).await?;
+    database.create_release("mobile", "v1.5", None).await?;
+    database.move_ticket_project(button, "mobile").await?;
+    assert_eq!(database.get_ticket(button).await?.unwrap().target_release.as_deref(), Some("v1.5"));
+
+    database.forget_project("site").await?;
+    assert!(database.releases(Some("site")).await?.is_empty());
+    assert_eq!(database.releases(Some("mobile")).await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_roadmap() -> Result<()> {
+    use lticket::models::{roadmap_item, ReleaseRisk};
+
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+    database.create_release("web", "v1.4", Some(day(10))).await?;
+    database.create_release("web", "v2.0", None).await?;
+    database.create_release("api", "v3", Some(day(3))).await?;
+    database.create_release("api", "v4", None).await?;
+    database.update_release_due_date("api", "v4", Some(day(31))).await?;
+    let releases = database.releases(None).await?;
+    let order: Vec<(&str, &str)> = releases.iter().map(|r| (r.project.as_str(), r.name.as_str())).collect();
+    assert_eq!(order, [("api", "v3"), ("web", "v1.4"), ("api", "v4"), ("web", "v2.0")]);
+
+    let login = database.add_ticket("web", "Fix login", "desc").await?;
+    let button = database.add_ticket("web", "Fix button", "desc").await?;
+    let docs = database.add_ticket("web", "Write docs", "desc").await?;
+    for id in [login, button, docs] {
+        database.update_ticket_release(id, Some("v1.4")).await?;
+    }
+    database.update_ticket_status(login, "closed").await?;
+    database.update_ticket_estimate(button, Some(20 * 60)).await?;
+
+    // Monday to Friday at 6 hours a day leaves 30 hours for 20 hours of estimated work
+    let today = day(6);
+    let v14 = releases.iter().find(|r| r.name == "v1.4").unwrap();
+    let item = roadmap_item(v14, &database.release_tickets("web", "v1.4").await?, today, 6.0);
+    assert_eq!((item.done, item.total, item.percent()), (1, 3, 33));
+    assert_eq!((item.remaining_minutes, item.unestimated, item.available_minutes), (1200, 1, Some(1800)));
+    assert_eq!(item.risk, ReleaseRisk::OnTrack);
+
+    database.update_ticket_estimate(docs, Some(15 * 60)).await?;
+    let item = roadmap_item(v14, &database.release_tickets("web", "v1.4").await?, today, 6.0);
+    assert_eq!(item.risk, ReleaseRisk::AtRisk);
+
+    database.update_ticket_estimate(docs, None).await?;
+    database.update_ticket_due_date(docs, Some(day(20))).await?;
+    let item = roadmap_item(v14, &database.release_tickets("web", "v1.4").await?, today, 6.0);
+    assert_eq!((item.late_tickets, item.risk), (1, ReleaseRisk::AtRisk));
+
+    assert_eq!(roadmap_item(v14, &database.release_tickets("web", "v1.4").await?, day(13), 6.0).risk, ReleaseRisk::Overdue);
+    database.update_ticket_status(button, "closed").await?;
+    database.update_ticket_status(docs, "done").await?;
+    assert_eq!(roadmap_item(v14, &database.release_tickets("web", "v1.4").await?, day(13), 6.0).risk, ReleaseRisk::Done);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ticket_scores() -> Result<()> {
+    use lticket::models::{missing_score_fields, ticket_score, ScoringMethod};
+
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let id = database.add_ticket("web", "Onboarding", "desc").await?;
+    database.update_ticket_score(id, "impact", Some(2.0)).await?;
+    database.update_ticket_score(id, "confidence", Some(80.0)).await?;
+    database.update_ticket_score(id, "effort", Some(4.0)).await?;
+    assert!(database.update_ticket_score(id, "name", Some(1.0)).await.is_err());
+
+    let ticket = database.get_ticket(id).await?.context("ticket")?;
+    assert_eq!((ticket.impact, ticket.confidence, ticket.effort), (Some(2.0), Some(80), Some(4.0)));
+    assert_eq!(ticket_score(&ticket, ScoringMethod::Rice), None);
+    assert_eq!(missing_score_fields(&ticket, ScoringMethod::Rice), ["reach"]);
+    assert_eq!(ticket_score(&ticket, ScoringMethod::Ice), Some(0.4));
+    assert!(missing_score_fields(&ticket, ScoringMethod::Ice).is_empty());
+
+    database.update_ticket_score(id, "reach", Some(500.0)).await?;
+    let ticket = database.get_ticket(id).await?.context("ticket")?;
+    assert_eq!(ticket.reach, Some(500));
+    assert_eq!(ticket_score(&ticket, ScoringMethod::Rice), Some(200.0));
+
+    database.update_ticket_score(id, "effort", None).await?;
+    let ticket = database.get_ticket(id).await?.context("ticket")?;
+    assert_eq!(missing_score_fields(&ticket, ScoringMethod::Rice), ["effort"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dependencies() -> Result<()> {
+    use lticket::models::transitive_blockers;
+
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let schema = database.add_ticket("web", "Schema", "desc").await?;
+    let migrate = database.add_ticket("web", "Migrate", "desc").await?;
+    let launch = database.add_ticket("web", "Launch", "desc").await?;
+    let docs = database.add_ticket("docs", "Write guide", "desc").await?;
+    assert!(database.add_dependency(migrate, schema).await?);
+    assert!(!database.add_dependency(migrate, schema).await?);
+    assert!(database.add_dependency(launch, migrate).await?);
+    assert!(database.add_dependency(launch, docs).await?);
+
+    let dependencies = database.dependencies().await?;
+    assert_eq!(transitive_blockers(&dependencies, &[launch]).into_iter().collect::<Vec<_>>(), [schema, migrate, docs]);
+    assert!(transitive_blockers(&dependencies, &[schema]).is_empty());
+
+    assert!(database.remove_dependency(launch, docs).await?);
+    assert!(!database.remove_dependency(launch, docs).await?);
+    database.delete_ticket(migrate).await?;
+    assert!(database.dependencies().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_database_backup() -> Result<()> {
+    // In-memory databases cannot be snapshotted to a file, so use one on disk
+    let dir = tempfile::tempdir()?;
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", dir.path().join("tickets.db").display()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    let database = Database::from_pool(pool);
+    database.add_ticket("web", "Keep me", "desc").await?;
+
+    let backups = dir.path().join("backups");
+    let path = lticket::backup::create_in(&database, &backups, "test", 5).await?;
+    assert!(path.file_name().unwrap().to_string_lossy().ends_with("-test.db"));
+
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.display()))?;
+    let copy = Database::from_pool(SqlitePool::connect_with(options).await?);
+    let tickets = copy.list_tickets(None).await?;
+    assert_eq!(tickets.len(), 1);
+    assert_eq!(tickets[0].name, "Keep me");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_monthly_trends() -> Result<()> {
+    let database = create_test_database().await?;
+    database.init_db().await?;
+
+    let first = database.add_ticket("web", "First", "desc").await?;
+    let second = database.add_ticket("web", "Second", "desc").await?;
+    database.add_ticket("api", "Third", "desc").await?;
+    database.update_ticket_status(first, "closed").await?;
+    // Re-closing an already finished ticket is not a second close
+    database.update_ticket_status(first, "cancelled").await?;
+    database.add_time_log(second, 1, 30, None, None).await?;
+
+    let months = lticket::models::month_starts(Utc::now().date_naive(), 2);
+    let trends = database.monthly_trends(&months, None).await?;
+    assert_eq!(trends.len(), 2);
+    assert_eq!((trends[0].opened, trends[0].closed, trends[0].hours), (0, 0, 0.0));
+    assert_eq!((trends[1].opened, trends[1].closed, trends[1].hours), (3, 1, 1.5));
+
+    let api = database.monthly_trends(&months, Some("api")).await?;
+    assert_eq!((api[1].opened, api[1].closed, api[1].hours), (1, 0, 0.0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_change_watcher() -> Result<()> {
+    // data_version only moves for commits on other connections to the same file
+    let dir = tempfile::tempdir()?;
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", dir.path().join("tickets.db").display()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    let database = Database::from_pool(pool);
+
+    let mut watcher = database.watch_changes().await?;
+    assert!(!watcher.changed().await?);
+    database.list_tickets(None).await?;
+    assert!(!watcher.changed().await?);
+
+    database.add_ticket("web", "New", "desc").await?;
+    assert!(watcher.changed().await?);
+    assert!(!watcher.changed().await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_event_journal() -> Result<()> {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?.foreign_keys(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    let database = Database::from_pool(pool.clone()).with_command("ltm test");
+
+    let id = database.add_ticket("web", "First", "desc").await?;
+    let other = database.add_ticket("web", "Second", "desc").await?;
+    database.update_ticket_name(id, "Renamed").await?;
+    // Writing the same value again changes nothing and is not recorded
+    database.update_ticket_name(id, "Renamed").await?;
+    database.add_comment(id, "A note").await?;
+    database.delete_ticket(other).await?;
+
+    let events = database.list_events(Some(id), 20).await?;
+    let actions: Vec<(&str, &str)> = events.iter().map(|e| (e.entity.as_str(), e.action.as_str())).collect();
+    assert_eq!(actions, vec![("comment", "create"), ("ticket", "update"), ("ticket", "create")]);
+    assert_eq!(events[1].changed_fields(), vec!["name".to_string()]);
+    assert!(events.iter().all(|e| e.command.as_deref() == Some("ltm test")));
+
+    let all = database.list_events(None, 20).await?;
+    assert_eq!(all.len(), 5);
+    assert_eq!((all[0].action.as_str(), all[0].entity_id), ("delete", other));
+    assert!(all[0].after.is_none() && all[0].before.is_some());
+    assert_eq!(database.list_events(None, 2).await?.len(), 2);
+
+    // The journal is append-only
+    assert!(sqlx::query("DELETE FROM events").execute(&pool).await.is_err());
+    assert!(sqlx::query("UPDATE events SET action = 'x'").execute(&pool).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bulk_delete() -> Result<()> {
+    let database = create_test_database().await?;
+    let first = database.add_ticket("old", "First", "desc").await?;
+    let second = database.add_ticket("old", "Second", "desc").await?;
+    let kept = database.add_ticket("web", "Kept", "desc").await?;
+    database.add_comment(first, "One").await?;
+    database.add_comment(second, "Two").await?;
+    database.add_comment(kept, "Three").await?;
+    database.add_time_log(first, 1, 0, None, None).await?;
+    database.add_time_log(first, 0, 30, None, None).await?;
+    database.add_ticket_url(second, "https://example.com/pr/1", None).await?;
+    database.add_checklist_item(second, "Step").await?;
+    database.add_dependency(kept, first).await?;
+
+    let impact = database.delete_impact(&[first, second]).await?;
+    assert_eq!((impact.comments, impact.time_logs, impact.minutes), (2, 2, 90));
+    assert_eq!((impact.links, impact.checklist_items, impact.dependencies, impact.synced_issues), (1, 1, 1, 0));
+    assert_eq!(database.delete_impact(&[]).await?, lticket::models::DeleteImpact::default());
+
+    // Comments and time logs do not block the delete
+    assert_eq!(database.delete_tickets(&[first, second]).await?, 2);
+    let remaining = database.list_tickets(None).await?;
+    assert_eq!(remaining.iter().map(|t| t.id).collect::<Vec<_>>(), vec![kept]);
+    let left = database.delete_impact(&[first, second, kept]).await?;
+    assert_eq!((left.comments, left.time_logs, left.dependencies), (1, 0, 0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cleanup_archive() -> Result<()> {
+    let database = create_test_database().await?;
+    let closed = database.add_ticket("old", "Closed", "desc").await?;
+    let cancelled = database.add_ticket("old", "Cancelled", "desc").await?;
+    let open = database.add_ticket("old", "Open", "desc").await?;
+    let other = database.add_ticket("web", "Other", "desc").await?;
+    for id in [closed, other] {
+        database.update_ticket_status(id, "closed").await?;
+    }
+    database.update_ticket_status(cancelled, "cancelled").await?;
+
+    let ids = |tickets: Vec<lticket::models::Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<_>>();
+    let future = Utc::now().naive_utc() + chrono::Duration::days(1);
+    assert!(database.list_cleanup_candidates(Utc::now().naive_utc() - chrono::Duration::days(90), None).await?.is_empty());
+    assert_eq!(ids(database.list_cleanup_candidates(future, None).await?), vec![closed, cancelled, other]);
+    let candidates = ids(database.list_cleanup_candidates(future, Some("old")).await?);
+    assert_eq!(candidates, vec![closed, cancelled]);
+
+    assert_eq!(database.archive_tickets(&candidates).await?, 2);
+    assert_eq!(ids(database.list_tickets_filtered(Some("old"), None, "rank", false).await?), vec![open]);
+    assert_eq!(ids(database.list_tickets_filtered(Some("old"), None, "rank", true).await?), vec![closed, cancelled]);
+    assert!(database.get_ticket(closed).await?.unwrap().archived_at.is_some());
+    // Archived tickets are not offered for cleanup again
+    assert_eq!(ids(database.list_cleanup_candidates(future, None).await?), vec![other]);
+
+    assert!(database.unarchive_ticket(closed).await?);
+    assert!(!database.unarchive_ticket(closed).await?);
+    assert_eq!(ids(database.list_tickets_filtered(Some("old"), None, "rank", false).await?), vec![closed, open]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_tickets_dated() -> Result<()> {
+    use lticket::db::DateBounds;
+
+    let database = create_test_database().await?;
+    let older = database.add_ticket("web", "Older", "desc").await?;
+    let mid = Utc::now().naive_utc();
+    let newer = database.add_ticket("web", "Newer", "desc").await?;
+
+    let ids = |tickets: Vec<lticket::models::Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<_>>();
+    let list = |dates: DateBounds| {
+        let database = database.clone();
+        async move { database.list_tickets_dated(Some("web"), None, "rank", false, &dates).await }
+    };
+    assert_eq!(ids(list(DateBounds::default()).await?), vec![older, newer]);
+    assert_eq!(ids(list(DateBounds { created_before: Some(mid), ..Default::default() }).await?), vec![older]);
+    assert_eq!(ids(list(DateBounds { created_after: Some(mid), ..Default::default() }).await?), vec![newer]);
+
+    database.update_ticket_status(older, "in-progress").await?;
+    assert_eq!(ids(list(DateBounds { updated_after: Some(mid), ..Default::default() }).await?), vec![older, newer]);
+    let bounds = DateBounds { created_before: Some(mid), updated_after: Some(mid), ..Default::default() };
+    assert_eq!(ids(list(bounds).await?), vec![older]);
+    assert!(list(DateBounds { updated_before: Some(mid), ..Default::default() }).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remote_links() -> Result<()> {
+    use lticket::models::{RemoteLink, RemoteWorklog};
+
+    let database = create_test_database().await?;
+    let ticket = database.add_ticket("web", "Linked", "desc").await?;
+    assert!(database.last_synced_at("linear").await?.is_none());
+
+    let now = Utc::now().naive_utc();
+    let link = RemoteLink {
+        ticket_id: ticket,
+        provider: "linear".to_string(),
+        remote_id: "issue-1".to_string(),
+        remote_key: Some("ENG-1".to_string()),
+        synced_at: now,
+        remote_updated_at: now,
+        base: None,
+    };
+    database.save_remote_link(&link).await?;
+    database.save_remote_link(&RemoteLink { remote_key: Some("ENG-2".to_string()), ..link.clone() }).await?;
+    let links = database.list_remote_links("linear").await?;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].remote_key.as_deref(), Some("ENG-2"));
+    assert!(database.list_remote_links("jira").await?.is_empty());
+
+    database.set_last_synced_at("linear", now).await?;
+    assert_eq!(database.last_synced_at("linear").await?, Some(now));
+
+    database.add_time_log(ticket, 1, 30, None, None).await?;
+    let log = database.get_time_logs(ticket).await?[0].id;
+    let worklog = RemoteWorklog { time_log_id: log, provider: "jira".to_string(), remote_id: "10001".to_string(), synced_at: now };
+    database.save_remote_worklog(&worklog).await?;
+    database.save_remote_worklog(&worklog).await?;
+    assert_eq!(database.list_remote_worklogs("jira").await?, vec![worklog]);
+    assert!(database.list_remote_worklogs("linear").await?.is_empty());
+
+    let before = serde_json::json!({"ticket_id": ticket, "field": "status", "local": "testing", "remote": "closed"});
+    let after = serde_json::json!({"ticket_id": ticket, "field": "status", "resolution": "remote", "value": "closed"});
+    database.record_sync_resolution(ticket, &before, &after).await?;
+    let events = database.list_events(Some(ticket), 1).await?;
+    assert_eq!((events[0].entity.as_str(), events[0].action.as_str()), ("sync_conflict", "resolve"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_promote_comment() -> Result<()> {
+    let database = create_test_database().await?;
+    let original = database.add_ticket("web", "Original", "desc").await?;
+    database.add_comment(original, "\nSupport dark mode\nUsers keep asking for it.").await?;
+    let comment = database.get_comments(original).await?.remove(0);
+    assert_eq!(comment.title(100), "Support dark mode");
+    assert_eq!(comment.title(9), "Support…");
+    assert_eq!(comment.title(10), "Support d…");
+
+    let promoted = database.promote_comment(&comment, "design", &comment.title(100), "open").await?;
+    let ticket = database.get_ticket(promoted).await?.unwrap();
+    assert_eq!((ticket.project.as_str(), ticket.name.as_str(), ticket.status.as_str()), ("design", "Support dark mode", "open"));
+    assert_eq!(ticket.description, comment.content);
+
+    let back = database.get_comments(promoted).await?;
+    assert_eq!(back.len(), 1);
+    assert_eq!(back[0].content, format!("Promoted from comment #{} on ticket #{}", comment.id, original));
+    let replies = database.get_comments(original).await?;
+    let reply = replies.iter().find(|c| c.parent_comment_id == Some(comment.id)).unwrap();
+    assert_eq!(reply.content, format!("Promoted to ticket #{}", promoted));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rename_project() -> Result<()> {
+    let database = create_test_database().await?;
+    let first = database.add_ticket("web", "First", "desc").await?;
+    let archived = database.add_ticket("web", "Archived", "desc").await?;
+    database.add_ticket("api", "Other", "desc").await?;
+    database.archive_tickets(&[archived]).await?;
+    let updated_at = database.get_ticket(first).await?.unwrap().updated_at;
+
+    assert_eq!(database.rename_project("web", "frontend").await?, 2);
+    assert_eq!(database.list_projects().await?, vec!["api".to_string(), "frontend".to_string()]);
+    assert_eq!(database.list_tickets(Some("frontend")).await?.len(), 2);
+    assert_eq!(database.get_ticket(first).await?.unwrap().updated_at, updated_at);
+    assert_eq!(database.rename_project("missing", "other").await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_project_settings() -> Result<()> {
+    let database = create_test_database().await?;
+    database.add_ticket("web", "First", "desc").await?;
+    database.set_project_setting("web", "id_prefix", "WEB").await?;
+    database.set_project_setting("web", "time_rounding", "15").await?;
+    database.set_project_setting("web", "time_rounding", "30").await?;
+
+    let settings = database.get_project_settings("web").await?;
+    assert_eq!(settings.id_prefix.as_deref(), Some("WEB"));
+    assert_eq!(settings.time_rounding, Some(30));
+    assert_eq!(database.get_project_settings("api").await?, Default::default());
+
+    database.rename_project("web", "frontend").await?;
+    assert_eq!(database.get_project_settings("web").await?, Default::default());
+    assert_eq!(database.get_project_settings("frontend").await?.time_rounding, Some(30));
+
+    assert_eq!(database.unset_project_settings("frontend", Some("id_prefix")).await?, 1);
+    assert_eq!(database.unset_project_settings("frontend", None).await?, 1);
+    assert_eq!(database.get_project_settings("frontend").await?, Default::default());
+
+    let id = database.add_ticket_with_status("ideas", "Idea", "desc", "backlog").await?;
+    let open = database.add_ticket("ideas", "Open", "desc").await?;
+    let closed = database.add_ticket_with_status("ideas", "Closed", "desc", "closed").await?;
+    let testing = database.add_ticket_with_status("ideas", "Testing", "desc", "testing").await?;
+    let ids = |tickets: Vec<lticket::models::Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<_>>();
+    let sorted = ids(database.list_tickets_filtered(Some("ideas"), None, "status", false).await?);
+    assert_eq!(sorted, vec![open, testing, closed, id]);
+    database.set_project_setting("ideas", "statuses", "backlog,open,testing,closed").await?;
+    let sorted = ids(database.list_tickets_filtered(Some("ideas"), None, "status", false).await?);
+    assert_eq!(sorted, vec![id, open, testing, closed]);
+    assert_eq!(database.get_ticket(id).await?.unwrap().status, "backlog");
+    let changes = database.get_status_changes(id).await?;
+    assert_eq!((changes[0].from_status.as_deref(), changes[0].to_status.as_str()), (None, "backlog"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_uuids() -> Result<()> {
+    let database = create_test_database().await?;
+    let ticket_id = database.add_ticket("web", "First", "desc").await?;
+    let copy_id = database.copy_ticket(ticket_id, None).await?;
+    database.add_comment(ticket_id, "note").await?;
+    database.add_time_log(ticket_id, 1, 0, None, None).await?;
+
+    let ticket = database.get_ticket(ticket_id).await?.unwrap();
+    let copy = database.get_ticket(copy_id).await?.unwrap();
+    let comment = &database.get_comments(ticket_id).await?[0];
+    let log = &database.get_time_logs(ticket_id).await?[0];
+    let uuids = [&ticket.uuid, &copy.uuid, &comment.uuid, &log.uuid];
+    for uuid in uuids {
+        let groups: Vec<usize> = uuid.split('-').map(str::len).collect();
+        assert_eq!(groups, vec![8, 4, 4, 4, 12], "{}", uuid);
+        assert!(uuid[14..15] == *"4" && "89ab".contains(&uuid[19..20]), "{}", uuid);
+    }
+    assert_ne!(ticket.uuid, copy.uuid);
+    assert_eq!(database.get_comment(comment.id).await?.unwrap().uuid, comment.uuid);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_user_stamps() -> Result<()> {
+    let database = create_test_database().await?;
+    let pool = database.get_pool().clone();
+    let ada = Database::from_pool(pool.clone()).with_user(Some("Ada <ada@example.com>".to_string()));
+    let grace = Database::from_pool(pool).with_user(Some("Grace".to_string()));
+
+    let id = ada.add_ticket("web", "First", "desc").await?;
+    ada.add_comment(id, "note").await?;
+    grace.update_ticket_name(id, "Renamed").await?;
+    // A change that does nothing leaves the stamp alone
+    ada.update_ticket_name(id, "Renamed").await?;
+
+    let ticket = database.get_ticket(id).await?.unwrap();
+    assert_eq!(ticket.created_by.as_deref(), Some("Ada <ada@example.com>"));
+    assert_eq!(ticket.updated_by.as_deref(), Some("Grace"));
+    let comment = &database.get_comments(id).await?[0];
+    assert_eq!((comment.created_by.as_deref(), comment.updated_by.as_deref()), (Some("Ada <ada@example.com>"), Some("Ada <ada@example.com>")));
+
+    database.add_time_log(id, 1, 0, None, None).await?;
+    assert_eq!(database.get_time_logs(id).await?[0].created_by, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_from() -> Result<()> {
+    // An old database that only has the first migration's tables
+    let path = std::env::temp_dir().join(format!("ltm-import-test-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.display()))?.create_if_missing(true);
+    let old = SqlitePool::connect_with(options).await?;
+    sqlx::Executor::execute(&old, include_str!("../ltm-core/migrations/20240320000000_initial.sql")).await?;
+    sqlx::query("INSERT INTO tickets (project, name, description, status, created_at, updated_at) \
+         VALUES ('web', 'Old', 'desc', 'open', datetime('now'), datetime('now'))")
+        .execute(&old)
+        .await?;
+    sqlx::query("INSERT INTO comments (ticket_id, content, created_at) VALUES (1, 'kept', datetime('now'))").execute(&old).await?;
+    old.close().await;
+
+    let database = create_test_database().await?;
+    assert!(database.pending_migrations().await.is_empty());
+    assert_eq!(database.import_from(&path).await?, 1);
+    let ticket = database.get_ticket(1).await?.unwrap();
+    assert_eq!((ticket.name.as_str(), ticket.uuid.len()), ("Old", 36));
+    assert_eq!(database.get_comments(1).await?[0].content, "kept");
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restore_from() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("ltm-restore-test-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.display()))?.create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    let archived = Database::from_pool(pool.clone());
+    let id = archived.add_ticket("web", "Archived", "desc").await?;
+    archived.add_comment(id, "kept").await?;
+    pool.close().await;
+
+    let database = create_test_database().await?;
+    database.add_ticket("web", "Replaced", "desc").await?;
+    database.add_ticket("web", "Also replaced", "desc").await?;
+    assert_eq!(database.restore_from(&path).await?, 1);
+
+    let tickets = database.list_tickets(None).await?;
+    assert_eq!(tickets.len(), 1);
+    assert_eq!(tickets[0].name, "Archived");
+    assert_eq!(database.get_comments(tickets[0].id).await?[0].content, "kept");
+    assert_eq!(database.list_events(None, 100).await?.len(), 2);
+    // The journal is append-only again afterwards
+    assert!(sqlx::query("DELETE FROM events").execute(database.get_pool()).await.is_err());
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_changes_since() -> Result<()> {
+    let database = create_test_database().await?;
+    let old = database.add_ticket("web", "Old", "desc").await?;
+    database.add_comment(old, "first").await?;
+    database.add_comment(old, "second").await?;
+    database.add_ticket("api", "Other project", "desc").await?;
+    let cutoff = Utc::now().naive_utc();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+    let new = database.add_ticket("web", "New", "desc").await?;
+    let edited = database.get_comments(old).await?.into_iter().find(|c| c.content == "first").unwrap();
+    database.update_comment(edited.id, "first, edited").await?;
+    database.add_time_log(old, 0, 30, None, None).await?;
+
+    let tickets = database.tickets_changed_since(Some(cutoff), None).await?;
+    assert_eq!(tickets.iter().map(|t| t.id).collect::<Vec<_>>(), vec![new]);
+    let comments = database.comments_changed_since(Some(cutoff), None).await?;
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].content, "first, edited");
+    assert!(comments[0].updated_at > comments[0].created_at);
+    assert_eq!(database.time_logs_changed_since(Some(cutoff), Some("web")).await?.len(), 1);
+    assert!(database.time_logs_changed_since(Some(cutoff), Some("api")).await?.is_empty());
+
+    assert_eq!(database.tickets_changed_since(None, None).await?.len(), 3);
+    assert_eq!(database.tickets_changed_since(None, Some("web")).await?.len(), 2);
+    assert_eq!(database.comments_changed_since(None, None).await?.len(), 2);
+
+    Ok(())
+}
@@ -1,7 +1,5 @@
 use lticket::db::Database;
 use lticket::json_formatting::{TicketListResponse, TicketDetailsResponse, ProjectSummaryResponse};
-use serde_json;
-use sqlx;
 use std::str::FromStr;
 
 // Create a test database in memory for better isolation
@@ -12,7 +10,7 @@ async fn create_test_database() -> Database {
         .create_if_missing(true)
         .foreign_keys(true);
     let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
-    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await.unwrap();
 
     Database::from_pool(pool)
 }
@@ -83,7 +81,7 @@ async fn test_show_command_json() {
     let comments = db.get_comments(ticket_id).await.unwrap();
     let time_logs = vec![]; // Empty for now since get_time_logs is not implemented
 
-    let json_output = lticket::json_formatting::format_ticket_details_json(&ticket, &comments, &time_logs);
+    let json_output = lticket::json_formatting::format_ticket_details_json(&ticket, &comments, &time_logs, &[], &[]);
 
     let parsed: TicketDetailsResponse = serde_json::from_str(&json_output).unwrap();
     assert_eq!(parsed.ticket.id, ticket_id);
@@ -174,7 +172,7 @@ async fn test_json_structure_validation() {
 
     let ticket = db.get_ticket(ticket_id).await.unwrap().unwrap();
     let comments = db.get_comments(ticket_id).await.unwrap();
-    let show_json = lticket::json_formatting::format_ticket_details_json(&ticket, &comments, &vec![]);
+    let show_json = lticket::json_formatting::format_ticket_details_json(&ticket, &comments, &[], &[], &[]);
     assert!(serde_json::from_str::<serde_json::Value>(&show_json).is_ok());
 
     let summary = db.get_project_summary("test").await.unwrap();
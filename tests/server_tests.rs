@@ -0,0 +1,242 @@
+use anyhow::Result;
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use lticket::db::Database;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::str::FromStr;
+use tower::ServiceExt;
+
+async fn create_test_database() -> Result<Database> {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?
+        .create_if_missing(true)
+        .foreign_keys(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    Ok(Database::from_pool(pool))
+}
+
+async fn get(db: &Database, web: bool, uri: &str) -> Result<(StatusCode, String)> {
+    let response = lticket::server::router(db.clone(), web)
+        .oneshot(Request::builder().uri(uri).body(Body::empty())?)
+        .await?;
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, String::from_utf8(body.to_vec())?))
+}
+
+#[tokio::test]
+async fn test_ticket_endpoints() -> Result<()> {
+    let db = create_test_database().await?;
+    let id = db.add_ticket("web", "Fix login", "Users are logged out").await?;
+    db.add_ticket("api", "Rate limits", "").await?;
+    db.add_comment(id, "Reproduced locally").await?;
+    db.add_ticket_url(id, "https://github.com/acme/web/pull/7", Some("PR")).await?;
+
+    let (status, body) = get(&db, false, "/api/tickets?project=web").await?;
+    assert_eq!(status, StatusCode::OK);
+    let list: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(list["summary"]["total_tickets"], 1);
+    assert_eq!(list["tickets"][0]["name"], "Fix login");
+
+    let (status, body) = get(&db, false, &format!("/api/tickets/{}", id)).await?;
+    assert_eq!(status, StatusCode::OK);
+    let details: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(details["comments"][0]["content"], "Reproduced locally");
+    assert_eq!(details["urls"][0]["label"], "PR");
+
+    let (status, body) = get(&db, false, "/api/tickets/999").await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains("TICKET_NOT_FOUND"));
+
+    let (status, _) = get(&db, false, "/api/tickets/abc").await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = get(&db, false, "/api/projects").await?;
+    let projects: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(projects["projects"], serde_json::json!(["api", "web"]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_timers_endpoint() -> Result<()> {
+    let db = create_test_database().await?;
+    let id = db.add_ticket("web", "Fix login", "").await?;
+    db.start_timer(id, chrono::Utc::now().naive_utc() - chrono::Duration::minutes(5)).await?;
+
+    let (_, body) = get(&db, false, "/api/timers").await?;
+    let timers: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(timers["timers"][0]["ticket_name"], "Fix login");
+    assert!(timers["timers"][0]["elapsed_seconds"].as_i64().unwrap() >= 300);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_web_ui_only_with_flag() -> Result<()> {
+    let db = create_test_database().await?;
+
+    let (status, _) = get(&db, false, "/").await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = get(&db, true, "/").await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<title>ltm</title>"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_graphql_nested_query() -> Result<()> {
+    let db = create_test_database().await?;
+    let id = db.add_ticket("web", "Fix login", "").await?;
+    db.add_ticket("api", "Rate limits", "").await?;
+    db.add_comment(id, "Reproduced locally").await?;
+    db.add_time_log(id, 1, 30, None, None).await?;
+
+    let query = serde_json::json!({
+        "query": r#"{ tickets(project: "web") { id name comments { content } timeLogs { hours minutes } totalHours } }"#
+    });
+    let response = lticket::server::router(db.clone(), false)
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/graphql")
+                .header("content-type", "application/json")
+                .body(Body::from(query.to_string()))?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+    let tickets = &body["data"]["tickets"];
+    assert_eq!(tickets.as_array().unwrap().len(), 1);
+    assert_eq!(tickets[0]["comments"][0]["content"], "Reproduced locally");
+    assert_eq!(tickets[0]["timeLogs"][0]["minutes"], 30);
+    assert_eq!(tickets[0]["totalHours"], 1.5);
+
+    // Invalid filters come back as GraphQL errors
+    let query = serde_json::json!({ "query": r#"{ tickets(status: "bogus") { id } }"# });
+    let response = lticket::server::router(db, false)
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/graphql")
+                .header("content-type", "application/json")
+                .body(Body::from(query.to_string()))?,
+        )
+        .await?;
+    let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+    assert!(body["errors"][0]["message"].as_str().unwrap().contains("bogus"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shared_routes() -> Result<()> {
+    // Snapshots copy the database file, so this one needs a file
+    let dir = tempfile::tempdir()?;
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", dir.path().join("tickets.db").display()))?.create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    let db = Database::from_pool(pool);
+    db.add_ticket("web", "Fix login", "").await?;
+    let router = lticket::server::shared_router(db, Some("s3cret".to_string())).await?;
+    let run = |token: &str, args: &[&str]| {
+        let body = serde_json::json!({ "args": args, "user": null });
+        Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::from(body.to_string()))
+    };
+
+    let response = router.clone().oneshot(run("wrong", &["list"])?).await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Commands about the client's machine are not run on the server
+    let response = router.clone().oneshot(run("s3cret", &["config", "user.name", "Eve"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = router.clone().oneshot(run("s3cret", &["board", "--watch", "2"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    // Clients send the files they read as stdin; the server reads none of its own
+    let response = router.clone().oneshot(run("s3cret", &["ticket", "create-bulk", "--project", "web", "--from-file", "/etc/passwd"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = router.clone().oneshot(run("s3cret", &["time", "import", "/etc/passwd", "--dry-run"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = router.clone().oneshot(run("s3cret", &["time", "edit"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = router.clone().oneshot(run("s3cret", &["ticket", "create", "web", "No description"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = router.clone().oneshot(run("s3cret", &["open-url", "1"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = router.clone().oneshot(run("s3cret", &["project", "delete", "web", "--export-first", "/tmp/web.json", "--force"])?).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder().uri("/api/snapshot").header("authorization", "Bearer s3cret").body(Body::empty())?;
+    let response = router.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("x-ltm-revision"));
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    assert!(body.starts_with(b"SQLite format 3"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_tokens() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", dir.path().join("tickets.db").display()))?.create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::migrate!("./ltm-core/migrations").run(&pool).await?;
+    let db = Database::from_pool(pool);
+    let app = lticket::server::app(db.clone(), true, None).await?;
+    let get = |uri: &str, token: Option<&str>| {
+        let request = Request::builder().uri(uri);
+        let request = match token {
+            Some(token) => request.header("authorization", format!("Bearer {}", token)),
+            None => request,
+        };
+        request.body(Body::empty()).unwrap()
+    };
+    let run = |token: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::from(r#"{"args": ["list"], "user": null}"#))
+            .unwrap()
+    };
+
+    // Without tokens the API is open, but commands and snapshots are not
+    assert_eq!(app.clone().oneshot(get("/api/projects", None)).await?.status(), StatusCode::OK);
+    assert_eq!(app.clone().oneshot(get("/api/snapshot", None)).await?.status(), StatusCode::UNAUTHORIZED);
+
+    let full = lticket::server::new_token()?;
+    let read = lticket::server::new_token()?;
+    assert!(full.starts_with("ltm_") && full.len() == 44 && full != read);
+    assert!(db.create_api_token("laptop", &lticket::server::hash_token(&full), false).await?);
+    assert!(db.create_api_token("dashboard", &lticket::server::hash_token(&read), true).await?);
+    assert!(!db.create_api_token("laptop", "other", false).await?);
+
+    assert_eq!(app.clone().oneshot(get("/api/projects", None)).await?.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(app.clone().oneshot(get("/graphql", Some("ltm_wrong"))).await?.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(app.clone().oneshot(get("/", None)).await?.status(), StatusCode::OK);
+    assert_eq!(app.clone().oneshot(get("/api/projects", Some(&read))).await?.status(), StatusCode::OK);
+    assert_eq!(app.clone().oneshot(get("/api/snapshot", Some(&read))).await?.status(), StatusCode::OK);
+
+    let response = app.clone().oneshot(run(&read)).await?;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+    assert_eq!(body["code"], "READ_ONLY_TOKEN");
+
+    // Revoking takes effect without restarting the server
+    assert_eq!(app.clone().oneshot(get("/api/tickets", Some(&full))).await?.status(), StatusCode::OK);
+    assert!(db.revoke_api_token("laptop").await?);
+    assert_eq!(app.clone().oneshot(get("/api/tickets", Some(&full))).await?.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(db.api_tokens().await?.iter().map(|t| (t.name.as_str(), t.read_only)).collect::<Vec<_>>(), [("dashboard", true)]);
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ mod validation_integration_tests {
     use clap::Parser;
     use lticket::{
         commands::{Cli, CommandHandler},
+        config::Config,
         db::Database,
     };
     use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
@@ -15,7 +16,7 @@ mod validation_integration_tests {
             .foreign_keys(true);
         let pool = SqlitePool::connect_with(options).await?;
 
-        sqlx::migrate!("./migrations")
+        sqlx::migrate!("./ltm-core/migrations")
             .run(&pool)
             .await?;
 
@@ -29,18 +30,18 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // Test invalid ticket ID validation
-        let cli = Cli::try_parse_from(&["ltm", "show", "abc"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "show", "abc"]).unwrap();
         let result = handler.handle_command(cli).await;
         // Should succeed because we handle ValidationError gracefully
         assert!(result.is_ok());
 
         // Test invalid project name validation  
-        let cli = Cli::try_parse_from(&["ltm", "add", "bad name!", "test", "description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "bad name!", "test", "description"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test invalid status validation using new command structure
-        let cli = Cli::try_parse_from(&["ltm", "update", "status", "1", "invalid_status", "--force"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "update", "status", "1", "invalid_status", "--force"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
@@ -54,17 +55,17 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // Test adding a valid ticket
-        let cli = Cli::try_parse_from(&["ltm", "add", "test-project", "test-ticket", "test description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", "test-ticket", "test description"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test showing the created ticket
-        let cli = Cli::try_parse_from(&["ltm", "show", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "show", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test updating status with valid status using new command structure
-        let cli = Cli::try_parse_from(&["ltm", "update", "status", "1", "in-progress", "--force"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "update", "status", "1", "in-progress", "--force"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
@@ -78,12 +79,12 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // Test operations on non-existent ticket
-        let cli = Cli::try_parse_from(&["ltm", "show", "999"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "show", "999"]).unwrap();
         let result = handler.handle_command(cli).await;
         // Should succeed because we handle ValidationError gracefully
         assert!(result.is_ok());
 
-        let cli = Cli::try_parse_from(&["ltm", "comment", "add", "999", "test comment"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "comment", "add", "999", "test comment"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
@@ -97,13 +98,13 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // Test empty ticket name
-        let cli = Cli::try_parse_from(&["ltm", "add", "test-project", "", "description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", "", "description"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test very long ticket name (over 100 chars)
         let long_name = "a".repeat(101);
-        let cli = Cli::try_parse_from(&["ltm", "add", "test-project", &long_name, "description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", &long_name, "description"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
@@ -117,20 +118,20 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // First create a ticket
-        let cli = Cli::try_parse_from(&["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
         handler.handle_command(cli).await?;
 
         // Test valid time values with positional arguments
-        let cli = Cli::try_parse_from(&["ltm", "log", "1", "8", "30"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "log", "1", "8", "30"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test start/end time tracking
-        let cli = Cli::try_parse_from(&["ltm", "log", "1", "--start"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "log", "1", "--start"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
-        let cli = Cli::try_parse_from(&["ltm", "log", "1", "--end"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "log", "1", "--end"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
@@ -144,88 +145,88 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // First create a ticket
-        let cli = Cli::try_parse_from(&["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
         handler.handle_command(cli).await?;
 
         // Test time start command
-        let cli = Cli::try_parse_from(&["ltm", "time", "start", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "start", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time pause command
-        let cli = Cli::try_parse_from(&["ltm", "time", "pause", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "pause", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time resume command
-        let cli = Cli::try_parse_from(&["ltm", "time", "resume", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "resume", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time pause again
-        let cli = Cli::try_parse_from(&["ltm", "time", "pause", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "pause", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time stop command (should work even when paused)
-        let cli = Cli::try_parse_from(&["ltm", "time", "stop", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "stop", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time start again
-        let cli = Cli::try_parse_from(&["ltm", "time", "start", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "start", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time cancel command
-        let cli = Cli::try_parse_from(&["ltm", "time", "cancel", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "cancel", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test active timers command
-        let cli = Cli::try_parse_from(&["ltm", "active"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "active"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test time log with duration string
-        let cli = Cli::try_parse_from(&["ltm", "time", "log", "1", "2h30m"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "log", "1", "2h30m"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test edge cases
 
         // Test pausing a non-existent timer
-        let cli = Cli::try_parse_from(&["ltm", "time", "pause", "999"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "pause", "999"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok()); // Should show warning but not error
 
         // Test resuming a non-existent timer
-        let cli = Cli::try_parse_from(&["ltm", "time", "resume", "999"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "resume", "999"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok()); // Should show warning but not error
 
         // Start a timer for edge case tests
-        let cli = Cli::try_parse_from(&["ltm", "time", "start", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "start", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test resuming a timer that's not paused
-        let cli = Cli::try_parse_from(&["ltm", "time", "resume", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "resume", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok()); // Should show warning but not error
 
         // Pause the timer
-        let cli = Cli::try_parse_from(&["ltm", "time", "pause", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "pause", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test pausing a timer that's already paused
-        let cli = Cli::try_parse_from(&["ltm", "time", "pause", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "pause", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok()); // Should show warning but not error
 
         // Clean up
-        let cli = Cli::try_parse_from(&["ltm", "time", "cancel", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "time", "cancel", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
@@ -239,34 +240,151 @@ mod validation_integration_tests {
         let mut handler = CommandHandler::new(database);
 
         // First create a ticket
-        let cli = Cli::try_parse_from(&["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
         handler.handle_command(cli).await?;
 
         // Test open command
-        let cli = Cli::try_parse_from(&["ltm", "open", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "open", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test complete command
-        let cli = Cli::try_parse_from(&["ltm", "complete", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "complete", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test block command
-        let cli = Cli::try_parse_from(&["ltm", "block", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "block", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test block command with reason
-        let cli = Cli::try_parse_from(&["ltm", "block", "1", "Waiting for API"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "block", "1", "Waiting for API"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         // Test start command (sets status to in-progress and starts timer)
-        let cli = Cli::try_parse_from(&["ltm", "start", "1"]).unwrap();
+        let cli = Cli::try_parse_from(["ltm", "start", "1"]).unwrap();
         let result = handler.handle_command(cli).await;
         assert!(result.is_ok());
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_focus_mode() -> Result<()> {
+        let database = create_test_database().await?;
+        database.init_db().await?;
+        let mut handler = CommandHandler::new(database.clone());
+
+        // Without a focused ticket or one used before, ID-less commands report an error
+        let cli = Cli::try_parse_from(["ltm", "comment", "add", "orphan note"]).unwrap();
+        handler.handle_command(cli).await?;
+
+        let cli = Cli::try_parse_from(["ltm", "add", "test-project", "test-ticket", "description"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert!(database.get_comments(1).await?.is_empty());
+
+        // Without a focused ticket, they use the last ticket used, as does @last
+        let cli = Cli::try_parse_from(["ltm", "comment", "add", "last note"]).unwrap();
+        handler.handle_command(cli).await?;
+        let cli = Cli::try_parse_from(["ltm", "comment", "add", "@last", "another note"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_comments(1).await?.len(), 2);
+
+        let cli = Cli::try_parse_from(["ltm", "focus", "1"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_state("focus_ticket").await?.as_deref(), Some("1"));
+
+        let cli = Cli::try_parse_from(["ltm", "comment", "add", "focused note"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert!(database.get_comments(1).await?.iter().any(|c| c.content == "focused note"));
+
+        let cli = Cli::try_parse_from(["ltm", "start"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert!(database.get_timer(1).await?.is_some());
+
+        let cli = Cli::try_parse_from(["ltm", "current"]).unwrap();
+        handler.handle_command(cli).await?;
+
+        let cli = Cli::try_parse_from(["ltm", "time", "stop"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert!(database.get_timer(1).await?.is_none());
+        assert_eq!(database.get_time_logs(1).await?.len(), 1);
+
+        let cli = Cli::try_parse_from(["ltm", "complete"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_ticket(1).await?.unwrap().status, "completed");
+
+        let cli = Cli::try_parse_from(["ltm", "focus", "--clear"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert!(database.get_state("focus_ticket").await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_workflow_rules() -> Result<()> {
+        let database = create_test_database().await?;
+        database.init_db().await?;
+        let config = Config::parse(
+            "[workflows.webapp]\nopen = [\"in-progress\"]\nin-progress = [\"testing\"]\ntesting = [\"closed\", \"in-progress\"]\n",
+        )?;
+        let mut handler = CommandHandler::new(database.clone()).with_config(config);
+
+        for project in ["webapp", "other"] {
+            let cli = Cli::try_parse_from(["ltm", "add", project, "test-ticket", "description"]).unwrap();
+            handler.handle_command(cli).await?;
+        }
+
+        // Skipping ahead is refused before any prompt and leaves the status alone
+        let cli = Cli::try_parse_from(["ltm", "close", "1"]).unwrap();
+        handler.handle_command(cli).await?;
+        let cli = Cli::try_parse_from(["ltm", "complete", "1"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_ticket(1).await?.unwrap().status, "open");
+
+        let cli = Cli::try_parse_from(["ltm", "start", "1"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_ticket(1).await?.unwrap().status, "in-progress");
+
+        // --force overrides the workflow
+        let cli = Cli::try_parse_from(["ltm", "update", "status", "1", "closed", "--force"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_ticket(1).await?.unwrap().status, "closed");
+
+        // Projects without a workflow are unrestricted
+        let cli = Cli::try_parse_from(["ltm", "complete", "2"]).unwrap();
+        handler.handle_command(cli).await?;
+        assert_eq!(database.get_ticket(2).await?.unwrap().status, "completed");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_required_for_on_create() -> Result<()> {
+        let database = create_test_database().await?;
+        database.init_db().await?;
+        let mut handler = CommandHandler::new(database.clone());
+
+        let cli = Cli::try_parse_from(["ltm", "project", "set", "web", "required_for", "in-progress:estimate,closed:comment"]).unwrap();
+        handler.handle_command(cli).await?;
+
+        // Creating a ticket straight into a status needs the same fields as moving it there
+        for status in ["in-progress", "closed"] {
+            let cli = Cli::try_parse_from(["ltm", "ticket", "create", "web", status, "description", "--status", status]).unwrap();
+            handler.handle_command(cli).await?;
+        }
+        assert!(database.list_tickets(Some("web")).await?.is_empty());
+
+        let cli = Cli::try_parse_from(["ltm", "ticket", "create", "web", "open", "description"]).unwrap();
+        handler.handle_command(cli).await?;
+        let cli = Cli::try_parse_from(["ltm", "ticket", "create", "web", "forced", "description", "--status", "closed", "--force"]).unwrap();
+        handler.handle_command(cli).await?;
+        let statuses: Vec<String> = database.list_tickets(Some("web")).await?.into_iter().map(|t| t.status).collect();
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses.contains(&"open".to_string()) && statuses.contains(&"closed".to_string()));
+
+        Ok(())
+    }
 }